@@ -1,6 +1,6 @@
 use criterion::{criterion_group, criterion_main, Criterion};
 use std::path::Path;
-use yuanjing_core::{fingerprint, mmr_store::EvidenceStore, evidence::Evidence};
+use yuanjing_core::{fingerprint, mmr_store::EvidenceStore, evidence::{Confidence, Evidence}};
 use std::sync::Once;
 
 static INIT: Once = Once::new();
@@ -41,26 +41,67 @@ fn bench_mmr_append(c: &mut Criterion) {
     let mock_pool_hash = "mock_pool_hash_abc123";
     let _ = store.register_model(mock_pool_hash, "Bench Model");
 
-    let evidence = Evidence {
+    let mut evidence = Evidence {
         image_phash: "mock_phash".to_string(),
         image_sha256: "mock_sha256".to_string(),
         verdict: true,
-        confidence: "0.99".to_string(),
+        confidence: Confidence::BasisPoints(9900),
         activated_prompts: vec![1, 2, 3],
         prompt_pool_hash: mock_pool_hash.to_string(),
         external_knowledge_hash: "mock_ext".to_string(),
         timestamp: 1234567890,
+        claimed_at: None,
+        consensus: None,
+        calibration: None,
+        provenance: None,
+        client_context: None,
     };
 
     c.bench_function("mmr_append_entry", |b| {
         b.iter(|| {
-            // We append the same evidence repeatedly. Sled handles this fine.
-            store.append(&evidence).unwrap();
+            // Identical evidence is rejected as a replay, so bump the timestamp each iteration.
+            evidence.timestamp += 1;
+            store.append(&evidence, None).unwrap();
         })
     });
     
     // Cleanup? Sled typically keeps locks. We might just let it be.
 }
 
-criterion_group!(benches, bench_fingerprint, bench_mmr_append);
+fn bench_mmr_append_batch(c: &mut Criterion) {
+    setup_env();
+    let mut store = EvidenceStore::new("data/temp_bench/bench_mmr_batch_db");
+
+    let mock_pool_hash = "mock_pool_hash_abc123";
+    let _ = store.register_model(mock_pool_hash, "Bench Model");
+
+    let mut timestamp = 1234567890;
+    c.bench_function("mmr_append_batch_100", |b| {
+        b.iter(|| {
+            let batch: Vec<Evidence> = (0..100)
+                .map(|_| {
+                    timestamp += 1;
+                    Evidence {
+                        image_phash: "mock_phash".to_string(),
+                        image_sha256: "mock_sha256".to_string(),
+                        verdict: true,
+                        confidence: Confidence::BasisPoints(9900),
+                        activated_prompts: vec![1, 2, 3],
+                        prompt_pool_hash: mock_pool_hash.to_string(),
+                        external_knowledge_hash: "mock_ext".to_string(),
+                        timestamp,
+                        claimed_at: None,
+                        consensus: None,
+                        calibration: None,
+                        provenance: None,
+                        client_context: None,
+                    }
+                })
+                .collect();
+            store.append_batch(&batch).unwrap();
+        })
+    });
+}
+
+criterion_group!(benches, bench_fingerprint, bench_mmr_append, bench_mmr_append_batch);
 criterion_main!(benches);
@@ -0,0 +1,165 @@
+"""
+跨语言测试向量核对 (test-vectors/v2/golden.json)
+
+逐条重算并比对：BCS 规范字节 (本 SDK 的 Evidence.to_bcs)、证据 ID 与叶子哈希、证据 / 回执 / 背书签名，
+以及按 YuanjingMmrVerifier 合约同一算法校验的 MMR 包含性证明 (回执时刻的根与最终根各一次)。
+Protobuf 日志的规范字节由服务端给出，只核对哈希、签名与证明。
+回执签名覆盖 `receipt_domain || 回执规范字节`。
+
+用法: python check_vectors.py [../test-vectors/v2/golden.json]
+"""
+import json
+import sys
+
+import blake3
+import nacl.exceptions
+import nacl.signing
+
+from evidence_schema import Evidence
+
+DEFAULT_PATH = "../test-vectors/v2/golden.json"
+
+
+def h(value: str) -> bytes:
+    return bytes.fromhex(value)
+
+
+def merge(left: bytes, right: bytes) -> bytes:
+    return blake3.blake3(left + right).digest()
+
+
+def pos_height(pos: int) -> int:
+    p = pos + 1
+    # 不是全 1 时跳到左侧同高度的位置
+    while p & (p + 1) != 0:
+        p -= (1 << (p.bit_length() - 1)) - 1
+    return p.bit_length() - 1
+
+
+def get_peaks(mmr_size: int) -> list:
+    height, pos = 1, 0
+    while (1 << (height + 1)) - 2 < mmr_size:
+        pos = (1 << (height + 1)) - 2
+        height += 1
+    height -= 1
+    peaks = [pos]
+    while height > 0:
+        pos += (2 << height) - 1
+        while pos > mmr_size - 1:
+            if height == 0:
+                return peaks
+            pos -= 2 << (height - 1)
+            height -= 1
+        peaks.append(pos)
+    return peaks
+
+
+def verify_inclusion(mmr_size: int, pos: int, leaf: bytes, proof: list, root: bytes) -> bool:
+    """与合约 YuanjingMmr.verifyInclusion 逐步一致"""
+    if pos >= mmr_size or pos_height(pos) != 0:
+        return False
+    if mmr_size == 1:
+        return not proof and leaf == root
+    peaks = get_peaks(mmr_size)
+    hashes, cursor, i = [], 0, 0
+    while peaks[i] < pos:
+        if cursor >= len(proof):
+            return False
+        hashes.append(proof[cursor])
+        cursor += 1
+        i += 1
+    # 含叶子的峰由证明路径算出
+    p, height, item = pos, 0, leaf
+    while p != peaks[i]:
+        if cursor >= len(proof):
+            return False
+        sibling = proof[cursor]
+        cursor += 1
+        if pos_height(p + 1) > height:
+            p += 1
+            item = merge(sibling, item)
+        else:
+            p += 2 << height
+            item = merge(item, sibling)
+        if p > peaks[i]:
+            return False
+        height += 1
+    hashes.append(item)
+    i += 1
+    while i < len(peaks) and cursor < len(proof):
+        hashes.append(proof[cursor])
+        cursor += 1
+        i += 1
+    if cursor < len(proof):
+        hashes.append(proof[cursor])
+        cursor += 1
+    if cursor != len(proof):
+        return False
+    # 峰从右向左合并：hash(右, 左)
+    while len(hashes) > 1:
+        right, left = hashes.pop(), hashes.pop()
+        hashes.append(merge(right, left))
+    return hashes[0] == root
+
+
+def verify_signature(public_key: str, message: bytes, signature: str) -> bool:
+    try:
+        nacl.signing.VerifyKey(h(public_key)).verify(message, h(signature))
+        return True
+    except nacl.exceptions.BadSignatureError:
+        return False
+
+
+def check_entry(log: dict, entry: dict, receipt_domain: bytes) -> list:
+    failures = []
+    canonical = h(entry["canonical_bytes"])
+    if log["encoding"] == "bcs":
+        evidence = Evidence(**entry["evidence"])
+        if evidence.to_bcs() != canonical:
+            failures.append("BCS canonical bytes")
+    if blake3.blake3(canonical).hexdigest() != entry["evidence_id"]:
+        failures.append("evidence_id")
+    salt = h(entry["salt"]) if entry["salt"] else b""
+    leaf = blake3.blake3(salt + canonical).digest()
+    if leaf.hex() != entry["leaf_hash"] or entry["receipt"]["evidence_hash"] != entry["leaf_hash"]:
+        failures.append("leaf_hash")
+    if not verify_signature(entry["signer_public_key"], canonical, entry["evidence_signature"]):
+        failures.append("evidence signature")
+    if not verify_signature(entry["signer_public_key"], receipt_domain + h(entry["receipt_canonical_bytes"]), entry["receipt_signature"]):
+        failures.append("receipt signature")
+    endorsement = entry["endorsement"]
+    if endorsement is not None and (
+        endorsement["endorsement"]["public_key"] != entry["signer_public_key"]
+        or not verify_signature(log["public_key"], h(endorsement["canonical_bytes"]), endorsement["signature"])
+    ):
+        failures.append("key endorsement")
+    receipt = entry["receipt"]
+    if not verify_inclusion(receipt["tree_size"], receipt["leaf_pos"], leaf, [h(i) for i in entry["receipt_proof"]], h(receipt["root"])):
+        failures.append("inclusion proof at receipt time")
+    proof = entry["proof"]
+    if not verify_inclusion(log["tree_size"], proof["pos"], leaf, [h(i) for i in proof["items"]], h(log["root"])):
+        failures.append("inclusion proof against final root")
+    return failures
+
+
+def main():
+    path = sys.argv[1] if len(sys.argv) > 1 else DEFAULT_PATH
+    with open(path) as f:
+        vectors = json.load(f)
+    print(f"🔍 测试向量 v{vectors['version']}: {path}")
+    receipt_domain = vectors["receipt_domain"].encode()
+    failed = 0
+    for log in vectors["logs"]:
+        print(f"--- 日志 {log['tenant_id']} ({log['encoding']}), tree_size={log['tree_size']} ---")
+        for entry in log["entries"]:
+            failures = check_entry(log, entry, receipt_domain)
+            failed += bool(failures)
+            print(f"{'❌' if failures else '✅'} {entry['name']}" + (f": {', '.join(failures)}" if failures else ""))
+    if failed:
+        print(f"❌ {failed} 条向量不一致")
+        sys.exit(1)
+    print("✅ 全部向量一致")
+
+
+if __name__ == "__main__":
+    main()
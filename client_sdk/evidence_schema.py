@@ -1,10 +1,13 @@
 from dataclasses import dataclass
-from typing import List
+from typing import List, Optional, Union
 import struct
 
 # BCS (Binary Canonical Serialization) 简易实现
 # 对应 Rust 的 bcs crate logic
 
+def serialize_u16(value: int) -> bytes:
+    return struct.pack('<H', value)
+
 def serialize_u32(value: int) -> bytes:
     return struct.pack('<I', value)
 
@@ -36,16 +39,60 @@ def serialize_vector_u32(values: List[int]) -> bytes:
         out += serialize_u32(v)
     return out
 
+# 共识规则在 Rust 枚举中的序号 (BCS 以 uleb128 编码变体序号)
+CONSENSUS_RULES = ['majority', 'unanimous', 'confidence_weighted']
+
+def serialize_consensus(consensus: dict) -> bytes:
+    out = serialize_uleb128(CONSENSUS_RULES.index(consensus['rule']))
+    out += serialize_uleb128(len(consensus['models']))
+    for model in consensus['models']:
+        out += serialize_string(model['prompt_pool_hash'])
+        out += serialize_bool(model['verdict'])
+        out += serialize_u16(model['confidence'])
+    return out
+
+# 提交来源记录方式在 Rust 枚举中的序号
+PROVENANCE_MODES = ['plain', 'hashed', 'pseudonymized']
+
+def serialize_option_string(value: Optional[str]) -> bytes:
+    return b'\x00' if value is None else b'\x01' + serialize_string(value)
+
+def serialize_provenance(provenance: dict) -> bytes:
+    out = serialize_uleb128(PROVENANCE_MODES.index(provenance['mode']))
+    out += serialize_option_string(provenance.get('ip'))
+    out += serialize_option_string(provenance.get('client_cert_subject'))
+    out += serialize_option_string(provenance.get('user_agent'))
+    return out
+
+def serialize_client_context(context: dict) -> bytes:
+    # BCS 的 map 按键序列化后的字节 (含长度前缀) 排序，与 Python 的字符串排序不同
+    entries = sorted((serialize_string(key), serialize_string(value)) for key, value in context.items())
+    out = serialize_uleb128(len(entries))
+    for key, value in entries:
+        out += key + value
+    return out
+
 @dataclass
 class Evidence:
     image_phash: str
     image_sha256: str
     verdict: bool
-    confidence: str
+    # 万分比整数 (0-10000)；引入定点置信度之前的历史证据为原样的字符串 (如 "0.99")
+    confidence: Union[int, str]
     activated_prompts: List[int]
     prompt_pool_hash: str
     external_knowledge_hash: str
     timestamp: int
+    # 提交方声明的采集 / 提交时间；未声明时不写入 (沿用旧布局，哈希不变)
+    claimed_at: Optional[int] = None
+    # 多模型共识 (即 evidence_dump 中的 consensus 对象)；单模型证据为 None，不写入
+    consensus: Optional[dict] = None
+    # 模型校准 (即 evidence_dump 中的 calibration 对象)；未提供时为 None，不写入
+    calibration: Optional[dict] = None
+    # 提交来源 (即 evidence_dump 中的 provenance 对象)；未开启采集时为 None，不写入
+    provenance: Optional[dict] = None
+    # 提交方上下文 (即 evidence_dump 中的 client_context 对象)；未提供时为 None，不写入
+    client_context: Optional[dict] = None
 
     def to_bcs(self) -> bytes:
         """
@@ -55,9 +102,29 @@ class Evidence:
         buffer += serialize_string(self.image_phash)
         buffer += serialize_string(self.image_sha256)
         buffer += serialize_bool(self.verdict)
-        buffer += serialize_string(self.confidence)
+        if isinstance(self.confidence, str):
+            buffer += serialize_string(self.confidence)
+        else:
+            buffer += serialize_u16(self.confidence)
         buffer += serialize_vector_u32(self.activated_prompts)
         buffer += serialize_string(self.prompt_pool_hash)
         buffer += serialize_string(self.external_knowledge_hash)
         buffer += serialize_i64(self.timestamp)
+        # 可选字段只写到最后一个出现的为止，之前未出现的写 0x00 (与 Rust 端的历史布局一致)
+        optional = [self.claimed_at, self.consensus, self.calibration, self.provenance, self.client_context]
+        if any(value is not None for value in optional):
+            buffer += b'\x00' if self.claimed_at is None else b'\x01' + serialize_i64(self.claimed_at)
+        if any(value is not None for value in optional[1:]):
+            buffer += b'\x00' if self.consensus is None else b'\x01' + serialize_consensus(self.consensus)
+        if any(value is not None for value in optional[2:]):
+            if self.calibration is None:
+                buffer += b'\x00'
+            else:
+                buffer += b'\x01' + serialize_string(self.calibration['version'])
+                buffer += serialize_u32(self.calibration['temperature_milli'])
+                buffer += serialize_u16(self.calibration['threshold_bps'])
+        if any(value is not None for value in optional[3:]):
+            buffer += b'\x00' if self.provenance is None else b'\x01' + serialize_provenance(self.provenance)
+        if self.client_context is not None:
+            buffer += b'\x01' + serialize_client_context(self.client_context)
         return bytes(buffer)
@@ -34,7 +34,7 @@ def register_model(pool_hash: str):
     """
     print(f"\n📝 正在注册模型: {pool_hash}...")
     try:
-        resp = requests.post(f"{API_URL}/model/register", json={
+        resp = requests.post(f"{API_URL}/v1/model/register", json={
             "hash": pool_hash,
             "description": "SAPT-v2.0-Mock (Copilot Generated)"
         })
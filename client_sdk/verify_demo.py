@@ -22,7 +22,12 @@ def verify_receipt(receipt: dict, server_pubkey_hex: str):
         activated_prompts=ev_data['activated_prompts'],
         prompt_pool_hash=ev_data['prompt_pool_hash'],
         external_knowledge_hash=ev_data['external_knowledge_hash'],
-        timestamp=ev_data['timestamp']
+        timestamp=ev_data['timestamp'],
+        claimed_at=ev_data.get('claimed_at'),
+        consensus=ev_data.get('consensus'),
+        calibration=ev_data.get('calibration'),
+        provenance=ev_data.get('provenance'),
+        client_context=ev_data.get('client_context')
     )
 
     # 2. 本地执行 BCS 序列化
@@ -73,7 +78,7 @@ def main():
     print(f"🚀 发送存证请求: {payload}")
     
     try:
-        resp = requests.post(f"{API_URL}/prove", json=payload)
+        resp = requests.post(f"{API_URL}/v1/prove", json=payload)
         
         if resp.status_code != 200:
             print(f"❌ 请求失败: {resp.text}")
@@ -0,0 +1,249 @@
+// SPDX-License-Identifier: MIT
+pragma solidity ^0.8.20;
+
+/// @title 原镜 MMR 包含性证明的链上校验
+/// @notice 与 yuanjing-core 的 `proof::verify_proof` 逐步对应：
+///         父节点 = BLAKE3(左 || 右)，峰从右向左合并 (hash(右, 左))，位置为 MMR 节点位置 (从 0 开始)。
+///         证明路径即 `/audit/{pos}` 的 `proof_hex`，`/audit/{pos}/solidity` 直接给出调用数据。
+library YuanjingMmr {
+    uint256 private constant MASK = 0xffffffff;
+    /// CHUNK_START | CHUNK_END | ROOT：64 字节的输入恰好是单块单 chunk
+    uint256 private constant FLAGS = 11;
+    /// `peakRoot` 失败时返回的游标
+    uint256 private constant FAILED = type(uint256).max;
+
+    /// @notice 叶子 `leaf` 位于大小为 `mmrSize`、根为 `root` 的树中的 `pos` 位置
+    function verifyInclusion(uint64 mmrSize, uint64 pos, bytes32 leaf, bytes32[] memory proof, bytes32 root)
+        internal
+        pure
+        returns (bool)
+    {
+        if (pos >= mmrSize || posHeight(pos) != 0) {
+            return false;
+        }
+        if (mmrSize == 1) {
+            return proof.length == 0 && leaf == root;
+        }
+        (bool ok, bytes32[] memory hashes, uint256 count) = peakHashes(mmrSize, pos, leaf, proof);
+        if (!ok) {
+            return false;
+        }
+        // 峰从右向左合并：hash(右, 左)
+        while (count > 1) {
+            bytes32 right = hashes[--count];
+            bytes32 left = hashes[--count];
+            hashes[count++] = merge(right, left);
+        }
+        return hashes[0] == root;
+    }
+
+    /// 各峰的哈希：含叶子的峰由证明路径算出，其余峰 (及右侧已合并的峰) 直接取自证明
+    function peakHashes(uint64 mmrSize, uint64 pos, bytes32 leaf, bytes32[] memory proof)
+        private
+        pure
+        returns (bool, bytes32[] memory hashes, uint256 count)
+    {
+        (uint256[64] memory peaks, uint256 peakCount) = getPeaks(mmrSize);
+        hashes = new bytes32[](peakCount + 1);
+        uint256 cursor = 0;
+        uint256 i = 0;
+        for (; peaks[i] < pos; i++) {
+            if (cursor >= proof.length) {
+                return (false, hashes, 0);
+            }
+            hashes[count++] = proof[cursor++];
+        }
+        bytes32 peakHash;
+        (peakHash, cursor) = peakRoot(pos, leaf, peaks[i], proof, cursor);
+        if (cursor == FAILED) {
+            return (false, hashes, 0);
+        }
+        hashes[count++] = peakHash;
+        for (i++; i < peakCount && cursor < proof.length; i++) {
+            hashes[count++] = proof[cursor++];
+        }
+        if (cursor < proof.length) {
+            hashes[count++] = proof[cursor++];
+        }
+        return (cursor == proof.length, hashes, count);
+    }
+
+    /// @notice 父节点哈希：BLAKE3(left || right)
+    function merge(bytes32 left, bytes32 right) internal pure returns (bytes32) {
+        uint256[16] memory m;
+        uint256 l = uint256(left);
+        uint256 r = uint256(right);
+        for (uint256 i = 0; i < 8; i++) {
+            m[i] = bswap32((l >> (224 - 32 * i)) & MASK);
+            m[i + 8] = bswap32((r >> (224 - 32 * i)) & MASK);
+        }
+        uint256[16] memory v = [
+            uint256(0x6A09E667), 0xBB67AE85, 0x3C6EF372, 0xA54FF53A, 0x510E527F, 0x9B05688C, 0x1F83D9AB, 0x5BE0CD19,
+            0x6A09E667, 0xBB67AE85, 0x3C6EF372, 0xA54FF53A, 0, 0, 64, FLAGS
+        ];
+        for (uint256 round = 0; round < 7; round++) {
+            g(v, 0, 4, 8, 12, m[0], m[1]);
+            g(v, 1, 5, 9, 13, m[2], m[3]);
+            g(v, 2, 6, 10, 14, m[4], m[5]);
+            g(v, 3, 7, 11, 15, m[6], m[7]);
+            g(v, 0, 5, 10, 15, m[8], m[9]);
+            g(v, 1, 6, 11, 12, m[10], m[11]);
+            g(v, 2, 7, 8, 13, m[12], m[13]);
+            g(v, 3, 4, 9, 14, m[14], m[15]);
+            m = [m[2], m[6], m[3], m[10], m[7], m[0], m[4], m[13], m[1], m[11], m[12], m[5], m[9], m[14], m[15], m[8]];
+        }
+        uint256 out = 0;
+        for (uint256 i = 0; i < 8; i++) {
+            out |= bswap32(v[i] ^ v[i + 8]) << (224 - 32 * i);
+        }
+        return bytes32(out);
+    }
+
+    function g(uint256[16] memory v, uint256 a, uint256 b, uint256 c, uint256 d, uint256 x, uint256 y) private pure {
+        unchecked {
+            v[a] = (v[a] + v[b] + x) & MASK;
+            v[d] = rotr(v[d] ^ v[a], 16);
+            v[c] = (v[c] + v[d]) & MASK;
+            v[b] = rotr(v[b] ^ v[c], 12);
+            v[a] = (v[a] + v[b] + y) & MASK;
+            v[d] = rotr(v[d] ^ v[a], 8);
+            v[c] = (v[c] + v[d]) & MASK;
+            v[b] = rotr(v[b] ^ v[c], 7);
+        }
+    }
+
+    function rotr(uint256 x, uint256 n) private pure returns (uint256) {
+        return ((x >> n) | (x << (32 - n))) & MASK;
+    }
+
+    /// BLAKE3 的字为小端序
+    function bswap32(uint256 x) private pure returns (uint256) {
+        return ((x & 0xff) << 24) | ((x & 0xff00) << 8) | ((x >> 8) & 0xff00) | (x >> 24);
+    }
+
+    /// 从叶子沿证明路径算到所在的峰，返回峰哈希与新的证明游标 (失败时为 `FAILED`)
+    function peakRoot(uint64 pos, bytes32 leaf, uint256 peak, bytes32[] memory proof, uint256 cursor)
+        private
+        pure
+        returns (bytes32, uint256)
+    {
+        uint256 p = pos;
+        uint256 height = 0;
+        bytes32 item = leaf;
+        while (p != peak) {
+            if (cursor >= proof.length) {
+                return (0, FAILED);
+            }
+            bytes32 sibling = proof[cursor++];
+            if (posHeight(p + 1) > height) {
+                // 右孩子
+                p += 1;
+                item = merge(sibling, item);
+            } else {
+                p += 2 << height;
+                item = merge(item, sibling);
+            }
+            if (p > peak) {
+                return (0, FAILED);
+            }
+            height++;
+        }
+        return (item, cursor);
+    }
+
+    function posHeight(uint256 pos) private pure returns (uint256) {
+        uint256 p = pos + 1;
+        // 不是全 1 时跳到左侧同高度的位置
+        while (p & (p + 1) != 0) {
+            p -= (1 << (bitLength(p) - 1)) - 1;
+        }
+        return bitLength(p) - 1;
+    }
+
+    function bitLength(uint256 x) private pure returns (uint256 n) {
+        while (x != 0) {
+            x >>= 1;
+            n++;
+        }
+    }
+
+    function getPeaks(uint256 mmrSize) private pure returns (uint256[64] memory peaks, uint256 count) {
+        uint256 height = 1;
+        uint256 pos = 0;
+        while ((1 << (height + 1)) - 2 < mmrSize) {
+            pos = (1 << (height + 1)) - 2;
+            height++;
+        }
+        height--;
+        peaks[count++] = pos;
+        while (height > 0) {
+            pos += (2 << height) - 1;
+            while (pos > mmrSize - 1) {
+                if (height == 0) {
+                    return (peaks, count);
+                }
+                pos -= 2 << (height - 1);
+                height--;
+            }
+            peaks[count++] = pos;
+        }
+    }
+}
+
+/// @title 锚定根登记与证据包含性校验
+/// @notice 日志运营方 (owner) 把签名树头中的 (mmrSize, root) 锚定上链，每个树大小只能锚定一次；
+///         合约与争议处理合约据此校验某条证据的叶子哈希确实在已锚定的根之下。一个部署对应一个租户的日志。
+contract YuanjingMmrVerifier {
+    address public owner;
+    /// 树大小 => 锚定的根 (未锚定为 0)
+    mapping(uint64 => bytes32) public anchoredRoots;
+
+    event RootAnchored(uint64 indexed mmrSize, bytes32 root);
+    event OwnershipTransferred(address indexed previousOwner, address indexed newOwner);
+
+    constructor() {
+        owner = msg.sender;
+    }
+
+    modifier onlyOwner() {
+        require(msg.sender == owner, "YuanjingMmrVerifier: caller is not the owner");
+        _;
+    }
+
+    function transferOwnership(address newOwner) external onlyOwner {
+        require(newOwner != address(0), "YuanjingMmrVerifier: zero address");
+        emit OwnershipTransferred(owner, newOwner);
+        owner = newOwner;
+    }
+
+    function anchorRoot(uint64 mmrSize, bytes32 root) external onlyOwner {
+        require(root != bytes32(0), "YuanjingMmrVerifier: empty root");
+        require(anchoredRoots[mmrSize] == bytes32(0), "YuanjingMmrVerifier: root already anchored");
+        anchoredRoots[mmrSize] = root;
+        emit RootAnchored(mmrSize, root);
+    }
+
+    /// @notice 对照已锚定的根校验；该树大小未锚定时返回 false
+    function verifyAnchored(uint64 mmrSize, uint64 pos, bytes32 leaf, bytes32[] calldata proof)
+        external
+        view
+        returns (bool)
+    {
+        bytes32 root = anchoredRoots[mmrSize];
+        return root != bytes32(0) && YuanjingMmr.verifyInclusion(mmrSize, pos, leaf, proof, root);
+    }
+
+    /// @notice 对照调用方给出的根校验 (根的可信性由调用方负责)
+    function verifyInclusion(uint64 mmrSize, uint64 pos, bytes32 leaf, bytes32[] calldata proof, bytes32 root)
+        external
+        pure
+        returns (bool)
+    {
+        return YuanjingMmr.verifyInclusion(mmrSize, pos, leaf, proof, root);
+    }
+
+    /// @notice BLAKE3(left || right)，供核对实现
+    function merge(bytes32 left, bytes32 right) external pure returns (bytes32) {
+        return YuanjingMmr.merge(left, right);
+    }
+}
@@ -0,0 +1,127 @@
+// SPDX-License-Identifier: MIT
+pragma solidity ^0.8.20;
+
+import {Test} from "forge-std/Test.sol";
+import {YuanjingMmrVerifier} from "../YuanjingMmrVerifier.sol";
+
+/// @notice 用 `test-vectors/v2/golden.json` 核对链上校验与服务端 `proof::verify_proof` 的结论一致：
+///         向量中的每条证明 (最终树与回执当时的树) 都必须通过，任何一处被改动都必须被拒绝。
+contract YuanjingMmrVerifierTest is Test {
+    YuanjingMmrVerifier private verifier;
+    string private vectors;
+
+    function setUp() public {
+        verifier = new YuanjingMmrVerifier();
+        vectors = vm.readFile(string.concat(vm.projectRoot(), "/../test-vectors/v2/golden.json"));
+    }
+
+    /// 服务端生成的调用数据原样提交，选择器必须与合约一致
+    function test_acceptsGoldenCalldata() public view {
+        uint256 checked;
+        for (uint256 l = 0; vm.keyExistsJson(vectors, logKey(l)); l++) {
+            for (uint256 e = 0; vm.keyExistsJson(vectors, entryKey(l, e)); e++) {
+                bytes memory data = vm.parseJsonBytes(vectors, string.concat(entryKey(l, e), ".proof.solidity_calldata"));
+                assertEq(bytes4(data), YuanjingMmrVerifier.verifyInclusion.selector, "selector");
+                (bool ok, bytes memory result) = address(verifier).staticcall(data);
+                assertTrue(ok, "call reverted");
+                assertTrue(abi.decode(result, (bool)), entryKey(l, e));
+                checked++;
+            }
+        }
+        assertGt(checked, 0, "no vectors loaded");
+    }
+
+    /// 每个叶子在最终树中、以及在签发回执时那棵树中的证明
+    function test_acceptsGoldenProofs() public view {
+        for (uint256 l = 0; vm.keyExistsJson(vectors, logKey(l)); l++) {
+            uint64 treeSize = uint64(vm.parseJsonUint(vectors, string.concat(logKey(l), ".tree_size")));
+            bytes32 root = hash(string.concat(logKey(l), ".root"));
+            for (uint256 e = 0; vm.keyExistsJson(vectors, entryKey(l, e)); e++) {
+                string memory key = entryKey(l, e);
+                uint64 pos = uint64(vm.parseJsonUint(vectors, string.concat(key, ".proof.pos")));
+                bytes32 leaf = hash(string.concat(key, ".leaf_hash"));
+                assertTrue(verifier.verifyInclusion(treeSize, pos, leaf, hashes(string.concat(key, ".proof.items")), root), key);
+
+                uint64 receiptSize = uint64(vm.parseJsonUint(vectors, string.concat(key, ".receipt.tree_size")));
+                bytes32 receiptRoot = hash(string.concat(key, ".receipt.root"));
+                bytes32[] memory receiptProof = hashes(string.concat(key, ".receipt_proof"));
+                assertTrue(verifier.verifyInclusion(receiptSize, pos, leaf, receiptProof, receiptRoot), key);
+            }
+        }
+    }
+
+    /// 改动叶子、位置、树大小、根或证明路径中的任何一项都必须被拒绝
+    function test_rejectsTamperedProofs() public view {
+        for (uint256 l = 0; vm.keyExistsJson(vectors, logKey(l)); l++) {
+            uint64 treeSize = uint64(vm.parseJsonUint(vectors, string.concat(logKey(l), ".tree_size")));
+            bytes32 root = hash(string.concat(logKey(l), ".root"));
+            for (uint256 e = 0; vm.keyExistsJson(vectors, entryKey(l, e)); e++) {
+                string memory key = entryKey(l, e);
+                uint64 pos = uint64(vm.parseJsonUint(vectors, string.concat(key, ".proof.pos")));
+                bytes32 leaf = hash(string.concat(key, ".leaf_hash"));
+                bytes32[] memory proof = hashes(string.concat(key, ".proof.items"));
+
+                assertFalse(verifier.verifyInclusion(treeSize, pos, leaf ^ bytes32(uint256(1)), proof, root), key);
+                assertFalse(verifier.verifyInclusion(treeSize, pos, leaf, proof, root ^ bytes32(uint256(1))), key);
+                assertFalse(verifier.verifyInclusion(treeSize, pos + 1, leaf, proof, root), key);
+                assertFalse(verifier.verifyInclusion(treeSize + 1, pos, leaf, proof, root), key);
+                assertFalse(verifier.verifyInclusion(treeSize, pos, leaf, truncated(proof), root), key);
+                for (uint256 i = 0; i < proof.length; i++) {
+                    bytes32 original = proof[i];
+                    proof[i] = original ^ bytes32(uint256(1));
+                    assertFalse(verifier.verifyInclusion(treeSize, pos, leaf, proof, root), key);
+                    proof[i] = original;
+                }
+            }
+        }
+    }
+
+    /// 锚定的根：只认锚定过的树大小
+    function test_verifiesAgainstAnchoredRoots() public {
+        uint64 treeSize = uint64(vm.parseJsonUint(vectors, ".logs[0].tree_size"));
+        bytes32 root = hash(".logs[0].root");
+        string memory key = entryKey(0, 0);
+        uint64 pos = uint64(vm.parseJsonUint(vectors, string.concat(key, ".proof.pos")));
+        bytes32 leaf = hash(string.concat(key, ".leaf_hash"));
+        bytes32[] memory proof = hashes(string.concat(key, ".proof.items"));
+
+        assertFalse(verifier.verifyAnchored(treeSize, pos, leaf, proof));
+        verifier.anchorRoot(treeSize, root);
+        assertTrue(verifier.verifyAnchored(treeSize, pos, leaf, proof));
+        assertFalse(verifier.verifyAnchored(treeSize, pos, leaf ^ bytes32(uint256(1)), proof));
+
+        vm.expectRevert("YuanjingMmrVerifier: root already anchored");
+        verifier.anchorRoot(treeSize, root);
+    }
+
+    function logKey(uint256 l) private pure returns (string memory) {
+        return string.concat(".logs[", vm.toString(l), "]");
+    }
+
+    function entryKey(uint256 l, uint256 e) private pure returns (string memory) {
+        return string.concat(logKey(l), ".entries[", vm.toString(e), "]");
+    }
+
+    /// 向量中的哈希是不带 0x 的 Hex
+    function hash(string memory key) private view returns (bytes32) {
+        return vm.parseBytes32(string.concat("0x", vm.parseJsonString(vectors, key)));
+    }
+
+    function hashes(string memory key) private view returns (bytes32[] memory items) {
+        string[] memory values = vm.parseJsonStringArray(vectors, key);
+        items = new bytes32[](values.length);
+        for (uint256 i = 0; i < values.length; i++) {
+            items[i] = vm.parseBytes32(string.concat("0x", values[i]));
+        }
+    }
+
+    function truncated(bytes32[] memory proof) private pure returns (bytes32[] memory items) {
+        if (proof.length == 0) {
+            return new bytes32[](1);
+        }
+        items = new bytes32[](proof.length - 1);
+        for (uint256 i = 0; i < items.length; i++) {
+            items[i] = proof[i];
+        }
+    }
+}
@@ -0,0 +1,14 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use yuanjing_core::bundle::EvidenceBundle;
+use yuanjing_core::envelope::SealedBundle;
+
+// 证据包与加密证据包 (C FFI 的 `yj_verify_bundle`、`open-bundle`)：JSON 与其中的 Hex、签名、证明都来自第三方
+fuzz_target!(|data: &[u8]| {
+    EvidenceBundle::verify_json(data, None);
+    if let Ok(sealed) = SealedBundle::from_json(data) {
+        sealed.verify_public(None);
+        let _ = sealed.open(&[7; 32]);
+    }
+});
@@ -0,0 +1,21 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use yuanjing_core::evidence::{CanonicalEncoding, Evidence};
+
+// 证据规范字节 (首字节为编码标签)：各代 BCS 布局与 Protobuf 都须安全解析；
+// BCS 是规范编码，解析成功的证据须重新编码回同一串字节 (同一证据不存在两种签名原文)
+fuzz_target!(|data: &[u8]| {
+    let Some((tag, bytes)) = data.split_first() else {
+        return;
+    };
+    let Ok(encoding) = CanonicalEncoding::from_tag(*tag) else {
+        return;
+    };
+    if let Ok(evidence) = Evidence::from_canonical_bytes(encoding, bytes) {
+        let encoded = evidence.canonical_bytes_as(encoding).expect("decoded evidence must re-encode");
+        if encoding == CanonicalEncoding::Bcs {
+            assert_eq!(encoded, bytes);
+        }
+    }
+});
@@ -0,0 +1,19 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use yuanjing_core::proof::{CompactProof, InclusionProof};
+
+// 紧凑二进制证明 (`Accept: application/vnd.yuanjing.proof`、`verify-proof --proof`)：
+// 解析成功的证明须原样编码回同一串字节，校验任意叶子与根都不得 panic
+fuzz_target!(|data: &[u8]| {
+    let Ok(proof) = CompactProof::decode(data) else {
+        return;
+    };
+    assert_eq!(proof.encode(), data);
+    let leaves = vec![[0u8; 32]; proof.positions.len()];
+    let root = proof.items.first().copied().unwrap_or_default();
+    proof.verify(&leaves, root);
+    if let Ok(single) = InclusionProof::try_from(proof) {
+        single.verify([0; 32], root);
+    }
+});
@@ -0,0 +1,10 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use yuanjing_core::fingerprint::{fingerprints_of, ImageLimits};
+
+// 图片摄入：上限取得很小，头部声明超大尺寸的图片须在解码前被拒绝，而不是撑爆 libFuzzer 的内存上限
+fuzz_target!(|data: &[u8]| {
+    let limits = ImageLimits { max_bytes: 1 << 20, max_pixels: 1 << 20 };
+    let _ = fingerprints_of(data, &limits);
+});
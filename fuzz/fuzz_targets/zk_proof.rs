@@ -0,0 +1,11 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use yuanjing_core::zk::ZkProof;
+
+// 零知识披露证明 (`POST /zk/verify` 的请求体)：陈述与各轮重复都来自提交方
+fuzz_target!(|data: &[u8]| {
+    if let Ok(proof) = ZkProof::decode(data) {
+        let _ = proof.verify();
+    }
+});
@@ -0,0 +1,61 @@
+"""
+由跨语言测试向量生成 cargo-fuzz 的初始语料 (fuzz/corpus/<target>/)
+
+    python fuzz/seed_corpus.py [test-vectors/v2/golden.json]
+
+紧凑证明取各条目与日志的合并证明，规范字节前置编码标签 (0 = BCS，1 = Protobuf)，
+证据包由条目的证据、签名、回执与回执时刻的证明拼成，图片取 data/samples/。
+"""
+import json
+import os
+import shutil
+import sys
+
+ROOT = os.path.dirname(os.path.dirname(os.path.abspath(__file__)))
+CORPUS = os.path.join(ROOT, "fuzz", "corpus")
+ENCODING_TAGS = {"bcs": 0, "protobuf": 1}
+
+
+def write(target: str, name: str, data: bytes):
+    directory = os.path.join(CORPUS, target)
+    os.makedirs(directory, exist_ok=True)
+    with open(os.path.join(directory, name), "wb") as f:
+        f.write(data)
+
+
+def bundle_of(log: dict, entry: dict) -> dict:
+    endorsement = entry["endorsement"]
+    return {
+        "format": 1,
+        "evidence": entry["evidence"],
+        "evidence_signature": entry["evidence_signature"],
+        "receipt": entry["receipt"],
+        "receipt_signature": entry["receipt_signature"],
+        "public_key": log["public_key"],
+        "endorsement": {"endorsement": endorsement["endorsement"], "signature": endorsement["signature"]} if endorsement else None,
+        "proof": entry["receipt_proof"],
+        "salt": entry["salt"],
+    }
+
+
+def main():
+    path = sys.argv[1] if len(sys.argv) > 1 else os.path.join(ROOT, "test-vectors", "v1", "golden.json")
+    with open(path) as f:
+        vectors = json.load(f)
+    for log in vectors["logs"]:
+        tag = ENCODING_TAGS[log["encoding"]]
+        for entry in log["entries"]:
+            name = f"{log['tenant_id']}-{entry['name']}"
+            write("compact_proof", name, bytes.fromhex(entry["proof"]["compact"]))
+            write("canonical", name, bytes([tag]) + bytes.fromhex(entry["canonical_bytes"]))
+            write("bundle", name + ".json", json.dumps(bundle_of(log, entry)).encode())
+        write("compact_proof", f"{log['tenant_id']}-batch", bytes.fromhex(log["batch_proof"]["compact"]))
+    samples = os.path.join(ROOT, "data", "samples")
+    for sample in sorted(os.listdir(samples)) if os.path.isdir(samples) else []:
+        os.makedirs(os.path.join(CORPUS, "image"), exist_ok=True)
+        shutil.copy(os.path.join(samples, sample), os.path.join(CORPUS, "image", sample))
+    print(f"✅ 初始语料已写入: {CORPUS}")
+
+
+if __name__ == "__main__":
+    main()
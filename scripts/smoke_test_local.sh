@@ -19,7 +19,7 @@ echo "[1] Backend: $BACKEND_URL"
 echo "[2] prompt_pool_hash: $PROMPT_POOL_HASH"
 
 echo "[3] Check backend reachable"
-curl -fsS "$BACKEND_URL/audit/0" >/dev/null
+curl -fsS "$BACKEND_URL/v1/audit/0" >/dev/null
 
 echo "[4] Ensure JPEG test image exists"
 mkdir -p "$(dirname "$IMAGE_REL")"
@@ -35,13 +35,13 @@ fi
 IMAGE_ABS="$(python3 -c 'import os,sys; print(os.path.abspath(sys.argv[1]))' "$IMAGE_REL")"
 echo "  image_path: $IMAGE_ABS"
 
-echo "[5] POST /model/register"
-curl -fsS -X POST "$BACKEND_URL/model/register" \
+echo "[5] POST /v1/model/register"
+curl -fsS -X POST "$BACKEND_URL/v1/model/register" \
   -H "Content-Type: application/json" \
   -d "{\"hash\":\"$PROMPT_POOL_HASH\",\"description\":\"local smoke test\"}" >/dev/null
 
-echo "[6] POST /prove"
-PROVE_JSON="$(curl -fsS -X POST "$BACKEND_URL/prove" \
+echo "[6] POST /v1/prove"
+PROVE_JSON="$(curl -fsS -X POST "$BACKEND_URL/v1/prove" \
   -H "Content-Type: application/json" \
   -d "{
     \"image_path\":\"$IMAGE_ABS\",
@@ -56,8 +56,8 @@ echo "[7] Extract leaf_pos"
 LEAF_POS="$(python3 -c 'import json,sys; print(json.loads(sys.stdin.read())["leaf_pos"])' <<<"$PROVE_JSON")"
 echo "  leaf_pos=$LEAF_POS"
 
-echo "[8] GET /audit/{leaf_pos}"
-AUDIT_JSON="$(curl -fsS "$BACKEND_URL/audit/$LEAF_POS")"
+echo "[8] GET /v1/audit/{leaf_pos}"
+AUDIT_JSON="$(curl -fsS "$BACKEND_URL/v1/audit/$LEAF_POS")"
 echo "  /audit => $AUDIT_JSON"
 
 echo "OK: smoke test passed"
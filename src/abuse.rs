@@ -0,0 +1,229 @@
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap, VecDeque};
+use std::sync::Mutex;
+
+use crate::evidence::{Confidence, CONFIDENCE_SCALE};
+
+/// 提交方触发规则后的处理
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AbuseAction {
+    /// 只记录，照常存证
+    #[default]
+    Flag,
+    /// 记录，并在 `throttle_secs` 内拒绝该提交方的存证请求 (`429`)
+    Throttle,
+}
+
+impl AbuseAction {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Flag => "flag",
+            Self::Throttle => "throttle",
+        }
+    }
+}
+
+impl std::str::FromStr for AbuseAction {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> anyhow::Result<Self> {
+        match s.to_ascii_lowercase().as_str() {
+            "flag" => Ok(Self::Flag),
+            "throttle" => Ok(Self::Throttle),
+            other => Err(anyhow::anyhow!("Unknown abuse action '{}', expected 'flag' or 'throttle'", other)),
+        }
+    }
+}
+
+/// 可疑提交模式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AbuseRule {
+    /// 时间窗内反复提交同一张图片 (按 SHA-256)
+    IdenticalImages,
+    /// 时间窗内大量低置信度判定
+    LowConfidenceBurst,
+}
+
+impl AbuseRule {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::IdenticalImages => "identical_images",
+            Self::LowConfidenceBurst => "low_confidence_burst",
+        }
+    }
+}
+
+/// 检测规则 (见 `[abuse]` 配置)；次数上限为 0 的规则关闭
+#[derive(Debug, Clone)]
+pub struct AbuseRules {
+    pub window_secs: i64,
+    /// 时间窗内同一张图片最多提交的次数
+    pub max_identical_images: u32,
+    /// 置信度低于该值 (0.0 ~ 1.0) 的判定视为低置信度
+    pub low_confidence_below: f64,
+    /// 时间窗内最多的低置信度判定数
+    pub max_low_confidence: u32,
+    pub action: AbuseAction,
+    pub throttle_secs: i64,
+}
+
+/// 某个提交方触发某条规则的记录 (同一提交方同一规则合并为一条)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AbuseFinding {
+    pub tenant_id: String,
+    /// 提交方 (租户 API Key 指纹)
+    pub principal: String,
+    pub rule: AbuseRule,
+    pub action: AbuseAction,
+    /// 最近一次触发时时间窗内的次数
+    pub count: u32,
+    pub detail: String,
+    /// 首次 / 最近一次触发的时间 (Unix 秒)
+    pub first_seen: i64,
+    pub last_seen: i64,
+    /// 触发过的次数
+    pub occurrences: u64,
+    /// 限流截止时间 (Unix 秒)；`flag` 为 null
+    pub throttled_until: Option<i64>,
+}
+
+/// 时间窗内的一次提交
+struct Submission {
+    at: i64,
+    image_sha256: String,
+    low_confidence: bool,
+}
+
+/// 一个提交方的近期提交与限流状态
+#[derive(Default)]
+struct History {
+    submissions: VecDeque<Submission>,
+    throttled_until: Option<i64>,
+}
+
+#[derive(Default)]
+struct AbuseState {
+    /// 键为 (租户, 提交方)
+    histories: HashMap<(String, String), History>,
+    findings: BTreeMap<(String, String, AbuseRule), AbuseFinding>,
+}
+
+/// 模块：提交滥用检测 (Submission Abuse Detection)
+///
+/// **为什么需要**: 有效的 API Key 不等于可信的提交方。同一张图片被反复提交 (刷量、试探判定边界)，
+/// 或短时间内涌入大量低置信度判定 (批量灌入未经审阅的结果)，都会稀释日志作为证据的分量，
+/// 而单看每一次请求都合法。这里按提交方 (API Key 指纹) 记下最近一个时间窗内的提交，
+/// 超过规则上限时记下一条发现供管理员查看 (`GET /admin/abuse`)，按配置只标记或在一段时间内拒绝其存证。
+///
+/// 状态只在本节点内存中，重启后清零；限流同时记入运维日志。多节点部署时各节点分别计数。
+pub struct AbuseMonitor {
+    rules: AbuseRules,
+    low_confidence_bps: u16,
+    state: Mutex<AbuseState>,
+}
+
+impl AbuseMonitor {
+    pub fn new(rules: AbuseRules) -> Self {
+        let low_confidence_bps = (rules.low_confidence_below * CONFIDENCE_SCALE as f64).round() as u16;
+        Self { rules, low_confidence_bps, state: Mutex::new(AbuseState::default()) }
+    }
+
+    /// 是否有任何规则开启
+    pub fn enabled(&self) -> bool {
+        self.rules.max_identical_images > 0 || self.rules.max_low_confidence > 0
+    }
+
+    /// 提交方在 `now` 时是否仍被限流，是则返回截止时间
+    pub fn throttled_until(&self, tenant_id: &str, principal: &str, now: i64) -> Option<i64> {
+        let state = self.state.lock().unwrap();
+        state
+            .histories
+            .get(&(tenant_id.to_string(), principal.to_string()))
+            .and_then(|history| history.throttled_until)
+            .filter(|until| *until > now)
+    }
+
+    /// 记下一次提交并按规则检查，返回本次触发的发现 (`throttle` 时提交方随即进入限流)
+    pub fn observe(&self, tenant_id: &str, principal: &str, image_sha256: &str, confidence: &Confidence, now: i64) -> Vec<AbuseFinding> {
+        if !self.enabled() {
+            return Vec::new();
+        }
+        let mut state = self.state.lock().unwrap();
+        let history = state.histories.entry((tenant_id.to_string(), principal.to_string())).or_default();
+        while history.submissions.front().is_some_and(|submission| submission.at <= now - self.rules.window_secs) {
+            history.submissions.pop_front();
+        }
+        // 历史证据的置信度无法解析时不算低置信度
+        let low_confidence = confidence.basis_points().is_some_and(|bps| bps < self.low_confidence_bps);
+        history.submissions.push_back(Submission { at: now, image_sha256: image_sha256.to_string(), low_confidence });
+
+        let mut triggered = Vec::new();
+        let identical = history.submissions.iter().filter(|submission| submission.image_sha256 == image_sha256).count() as u32;
+        if self.rules.max_identical_images > 0 && identical > self.rules.max_identical_images {
+            triggered.push((
+                AbuseRule::IdenticalImages,
+                identical,
+                format!("image {} submitted {} times within {} s", image_sha256, identical, self.rules.window_secs),
+            ));
+        }
+        let low = history.submissions.iter().filter(|submission| submission.low_confidence).count() as u32;
+        if self.rules.max_low_confidence > 0 && low > self.rules.max_low_confidence {
+            triggered.push((
+                AbuseRule::LowConfidenceBurst,
+                low,
+                format!("{} verdicts below confidence {} within {} s", low, self.rules.low_confidence_below, self.rules.window_secs),
+            ));
+        }
+        if triggered.is_empty() {
+            return Vec::new();
+        }
+
+        let throttled_until = (self.rules.action == AbuseAction::Throttle).then_some(now + self.rules.throttle_secs);
+        if throttled_until.is_some() {
+            history.throttled_until = throttled_until;
+        }
+        triggered
+            .into_iter()
+            .map(|(rule, count, detail)| {
+                let finding = state
+                    .findings
+                    .entry((tenant_id.to_string(), principal.to_string(), rule))
+                    .or_insert_with(|| AbuseFinding {
+                        tenant_id: tenant_id.to_string(),
+                        principal: principal.to_string(),
+                        rule,
+                        action: self.rules.action,
+                        count,
+                        detail: String::new(),
+                        first_seen: now,
+                        last_seen: now,
+                        occurrences: 0,
+                        throttled_until: None,
+                    });
+                finding.count = count;
+                finding.detail = detail;
+                finding.last_seen = now;
+                finding.occurrences += 1;
+                finding.throttled_until = throttled_until;
+                finding.clone()
+            })
+            .collect()
+    }
+
+    /// 本租户的全部发现 (按提交方、规则排序)
+    pub fn findings(&self, tenant_id: &str) -> Vec<AbuseFinding> {
+        let state = self.state.lock().unwrap();
+        state.findings.values().filter(|finding| finding.tenant_id == tenant_id).cloned().collect()
+    }
+
+    /// 解除提交方的限流并清除其发现与计数，返回清除的发现数
+    pub fn release(&self, tenant_id: &str, principal: &str) -> usize {
+        let mut state = self.state.lock().unwrap();
+        state.histories.remove(&(tenant_id.to_string(), principal.to_string()));
+        let before = state.findings.len();
+        state.findings.retain(|(tenant, submitter, _), _| tenant != tenant_id || submitter != principal);
+        before - state.findings.len()
+    }
+}
@@ -0,0 +1,129 @@
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+
+use crate::codec::StorageCodec;
+use crate::mmr_store::SledStore;
+
+/// 一条访问记录
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AccessLogEntry {
+    /// 记录序号 (单调递增)
+    pub id: u64,
+    /// 请求完成时间 (Unix 秒)
+    pub at: i64,
+    /// 解析到的租户；API Key 无效时为 null
+    pub tenant: Option<String>,
+    /// 请求主体 (API Key 指纹，未携带时为 `anonymous`)
+    pub principal: String,
+    /// 携带有效 `X-Admin-Key` 时的管理员主体
+    pub admin: Option<String>,
+    pub method: String,
+    /// 路由模板 (如 `/evidence/{pos}`)；未匹配任何路由时为请求路径
+    pub route: String,
+    /// HTTP 状态码
+    pub status: u16,
+    /// 处理耗时 (毫秒)
+    pub latency_ms: u64,
+    /// 涉及的证据位置：路径中的 `{pos}`，或 `/prove` 新追加的叶子位置
+    pub pos: Option<u64>,
+}
+
+/// 响应扩展：处理函数据此告知访问日志本次涉及的证据位置 (路径中没有 `{pos}` 时)
+#[derive(Debug, Clone, Copy)]
+pub struct AccessedPos(pub u64);
+
+/// 查询条件
+#[derive(Debug, Clone, Default)]
+pub struct AccessLogFilter {
+    /// 起始时间 (含，Unix 秒)
+    pub since: Option<i64>,
+    /// 截止时间 (不含，Unix 秒)
+    pub until: Option<i64>,
+    pub tenant: Option<String>,
+    /// 匹配 `principal` 或 `admin`
+    pub principal: Option<String>,
+    pub route: Option<String>,
+    pub pos: Option<u64>,
+}
+
+impl AccessLogFilter {
+    fn matches(&self, entry: &AccessLogEntry) -> bool {
+        self.tenant.as_ref().is_none_or(|tenant| entry.tenant.as_ref() == Some(tenant))
+            && self.principal.as_ref().is_none_or(|principal| {
+                entry.principal == *principal || entry.admin.as_ref() == Some(principal)
+            })
+            && self.route.as_ref().is_none_or(|route| entry.route == *route)
+            && self.pos.is_none_or(|pos| entry.pos == Some(pos))
+    }
+}
+
+/// 模块：访问日志 (Access Log)
+///
+/// **为什么需要**: 合规要求能回答“谁在什么时候查看或提交了哪条证据”。签名日志只覆盖签发，
+/// 而审计、导出证据包、生成报告等读操作不留任何记录。
+///
+/// 每个 API 请求完成后由中间件写入一条 (主体、路由、结果、耗时、涉及的证据位置)，
+/// 存在节点级的 `access_log` 树中 (不分租户，不复制，只增不删)，管理员按条件查询。
+/// 键为 完成时间 + 序号，按时间范围查询时直接做区间扫描。
+///
+/// 写入不单独 fsync，随 sled 后台刷盘落盘 (`FLUSH_EVERY_MS`)，崩溃时可能丢失最近一个刷盘周期内的记录。
+#[derive(Clone)]
+pub struct AccessLog {
+    entries: sled::Tree,
+    next_id: Arc<AtomicU64>,
+}
+
+impl AccessLog {
+    pub fn open(store: &SledStore) -> anyhow::Result<Self> {
+        let entries = store.tree("access_log")?;
+        let next_id = match entries.last()? {
+            Some((_, value)) => StorageCodec::decode::<AccessLogEntry>(&value)?.id + 1,
+            None => 0,
+        };
+        Ok(Self { entries, next_id: Arc::new(AtomicU64::new(next_id)) })
+    }
+
+    /// 追加一条记录 (`id` 由此处分配)，返回写入的记录
+    pub fn append(&self, mut entry: AccessLogEntry) -> anyhow::Result<AccessLogEntry> {
+        entry.id = self.next_id.fetch_add(1, Ordering::Relaxed);
+        self.entries.insert(key(entry.at, entry.id), StorageCodec::encode(&entry)?)?;
+        Ok(entry)
+    }
+
+    /// 按条件查询最近的 `limit` 条 (新的在前)
+    pub fn query(&self, filter: &AccessLogFilter, limit: usize) -> anyhow::Result<Vec<AccessLogEntry>> {
+        let start = key(filter.since.unwrap_or(0), 0);
+        let range = match filter.until {
+            Some(until) => self.entries.range(start..key(until, 0)),
+            None => self.entries.range(start..),
+        };
+        let mut entries = Vec::new();
+        for item in range.rev() {
+            let entry: AccessLogEntry = StorageCodec::decode(&item?.1)?;
+            if filter.matches(&entry) {
+                entries.push(entry);
+                if entries.len() >= limit {
+                    break;
+                }
+            }
+        }
+        Ok(entries)
+    }
+}
+
+fn key(at: i64, id: u64) -> [u8; 16] {
+    let mut key = [0u8; 16];
+    key[..8].copy_from_slice(&(at.max(0) as u64).to_be_bytes());
+    key[8..].copy_from_slice(&id.to_be_bytes());
+    key
+}
+
+/// 路由模板中 `{pos}` 段对应的证据位置
+pub fn path_pos(route: &str, path: &str) -> Option<u64> {
+    route
+        .split('/')
+        .zip(path.split('/'))
+        .find(|(template, _)| *template == "{pos}")
+        .and_then(|(_, value)| value.parse().ok())
+}
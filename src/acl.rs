@@ -0,0 +1,140 @@
+use serde::{Deserialize, Serialize};
+
+use crate::codec::StorageCodec;
+use crate::mmr_store::SledStore;
+
+/// 单条证据的访问控制列表
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EvidenceAcl {
+    pub pos: u64,
+    /// 获准读取原文的审计方名称 (见 `[[api.auditors]]`)，按字典序
+    pub auditors: Vec<String>,
+    /// 最近一次设置的时间 (Unix 秒)
+    pub updated_at: i64,
+    /// 设置者 (租户 API Key 指纹)
+    pub principal: String,
+}
+
+/// 模块：证据访问控制列表 (Evidence ACL)
+///
+/// **为什么需要**: 原文只应给提交它的租户和经指定的审计方 (法院、鉴定复核机构) 看，
+/// 而审计方不该因此拿到租户的 API Key、看到租户的全部证据。租户按位置授权审计方，
+/// 审计方凭自己的 Key 只能读取被授权的那几条原文。
+///
+/// 列表只约束原文 (证据记录、证据包、凭证、报告)，包含性证明、叶子哈希与回执校验照常公开。
+/// 当前状态存 `evidence_acl` 树 (按位置)，授权列表为空即删除该条。
+#[derive(Clone)]
+pub struct EvidenceAcls {
+    acls: sled::Tree,
+}
+
+impl EvidenceAcls {
+    pub fn open(store: &SledStore) -> anyhow::Result<Self> {
+        Ok(Self { acls: store.tree("evidence_acl")? })
+    }
+
+    /// 指定位置的访问控制列表；从未授权过返回 None
+    pub fn get(&self, pos: u64) -> anyhow::Result<Option<EvidenceAcl>> {
+        self.acls.get(pos.to_be_bytes())?.map(|v| StorageCodec::decode(&v)).transpose()
+    }
+
+    /// 审计方是否获准读取指定位置的原文
+    pub fn allows(&self, pos: u64, auditor: &str) -> anyhow::Result<bool> {
+        Ok(self.get(pos)?.is_some_and(|acl| acl.auditors.iter().any(|name| name == auditor)))
+    }
+
+    /// 整体替换授权列表；空列表撤销全部授权
+    pub fn set(&self, pos: u64, mut auditors: Vec<String>, principal: &str, at: i64) -> anyhow::Result<EvidenceAcl> {
+        auditors.sort();
+        auditors.dedup();
+        let acl = EvidenceAcl { pos, auditors, updated_at: at, principal: principal.to_string() };
+        if acl.auditors.is_empty() {
+            self.acls.remove(pos.to_be_bytes())?;
+        } else {
+            self.acls.insert(pos.to_be_bytes(), StorageCodec::encode(&acl)?)?;
+        }
+        self.acls.flush()?;
+        Ok(acl)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 测试用的临时库目录，丢弃时删除
+    struct TempDb(std::path::PathBuf);
+
+    impl TempDb {
+        fn new(name: &str) -> Self {
+            let path = std::env::temp_dir().join(format!("yuanjing-acl-{}-{}", name, std::process::id()));
+            let _ = std::fs::remove_dir_all(&path);
+            Self(path)
+        }
+
+        fn open(&self) -> SledStore {
+            SledStore::new(self.0.to_str().unwrap()).unwrap()
+        }
+    }
+
+    impl Drop for TempDb {
+        fn drop(&mut self) {
+            let _ = std::fs::remove_dir_all(&self.0);
+        }
+    }
+
+    fn auditors(names: &[&str]) -> Vec<String> {
+        names.iter().map(|name| name.to_string()).collect()
+    }
+
+    #[test]
+    fn grants_are_scoped_to_the_position_and_the_auditor() {
+        let db = TempDb::new("scope");
+        let acls = EvidenceAcls::open(&db.open()).unwrap();
+        acls.set(3, auditors(&["court"]), "tenant-key", 100).unwrap();
+
+        assert!(acls.allows(3, "court").unwrap());
+        assert!(!acls.allows(3, "reviewer").unwrap());
+        assert!(!acls.allows(4, "court").unwrap());
+        assert!(acls.get(4).unwrap().is_none());
+    }
+
+    #[test]
+    fn set_replaces_the_list_and_an_empty_list_revokes() {
+        let db = TempDb::new("replace");
+        let acls = EvidenceAcls::open(&db.open()).unwrap();
+        acls.set(3, auditors(&["reviewer", "court", "reviewer"]), "tenant-key", 100).unwrap();
+        assert_eq!(acls.get(3).unwrap().unwrap().auditors, auditors(&["court", "reviewer"]));
+
+        let acl = acls.set(3, auditors(&["reviewer"]), "tenant-key", 200).unwrap();
+        assert_eq!(acl.updated_at, 200);
+        assert!(!acls.allows(3, "court").unwrap());
+        assert!(acls.allows(3, "reviewer").unwrap());
+
+        acls.set(3, Vec::new(), "tenant-key", 300).unwrap();
+        assert!(acls.get(3).unwrap().is_none());
+        assert!(!acls.allows(3, "reviewer").unwrap());
+    }
+
+    #[test]
+    fn tenants_do_not_see_each_others_grants() {
+        let db = TempDb::new("tenants");
+        let store = db.open();
+        let default = EvidenceAcls::open(&store).unwrap();
+        let other = EvidenceAcls::open(&store.for_tenant("other").unwrap()).unwrap();
+        default.set(3, auditors(&["court"]), "default-key", 100).unwrap();
+
+        assert!(default.allows(3, "court").unwrap());
+        assert!(!other.allows(3, "court").unwrap());
+    }
+
+    #[test]
+    fn grants_survive_reopening_the_store() {
+        let db = TempDb::new("reopen");
+        EvidenceAcls::open(&db.open()).unwrap().set(7, auditors(&["court"]), "tenant-key", 100).unwrap();
+
+        let acl = EvidenceAcls::open(&db.open()).unwrap().get(7).unwrap().unwrap();
+        assert_eq!(acl.auditors, auditors(&["court"]));
+        assert_eq!(acl.principal, "tenant-key");
+    }
+}
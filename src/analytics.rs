@@ -0,0 +1,212 @@
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+
+use crate::evidence::Evidence;
+use crate::mmr_store::StoreSnapshot;
+use crate::position::{leaf_count, leaf_index_to_pos};
+
+/// 共同激活默认返回的组合数
+pub const DEFAULT_TOP_PAIRS: usize = 50;
+
+/// 共同激活最多返回的组合数
+pub const MAX_TOP_PAIRS: usize = 1000;
+
+/// 趋势的时间粒度 (按 Unix 纪元对齐，周从 1970-01-01 所在的周四算起)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TrendInterval {
+    Hour,
+    #[default]
+    Day,
+    Week,
+}
+
+impl TrendInterval {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Hour => "hour",
+            Self::Day => "day",
+            Self::Week => "week",
+        }
+    }
+
+    pub fn seconds(self) -> i64 {
+        match self {
+            Self::Hour => 3600,
+            Self::Day => 86_400,
+            Self::Week => 7 * 86_400,
+        }
+    }
+}
+
+/// 统计范围 (时间为回执时间，Unix 秒，`since` 含、`until` 不含)
+#[derive(Debug, Clone, Default)]
+pub struct ActivationFilter {
+    pub since: Option<i64>,
+    pub until: Option<i64>,
+    /// 只统计该模型版本 (Prompt 池哈希) 的证据
+    pub model: Option<String>,
+    pub interval: TrendInterval,
+    /// 共同激活返回的组合数
+    pub top: usize,
+}
+
+/// 单个 Prompt 的激活次数，按判定拆分
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PromptActivation {
+    pub index: u32,
+    /// 专家名称 (按 `model` 过滤且登记过说明时才有)
+    pub name: Option<String>,
+    /// 激活了该 Prompt 的证据数
+    pub evidence: u64,
+    /// 其中 `verdict = true` 的
+    pub verdict_true: u64,
+    /// 其中 `verdict = false` 的
+    pub verdict_false: u64,
+}
+
+/// 两个 Prompt 在同一条证据中被同时激活的次数
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CoActivation {
+    /// 两个索引，小的在前
+    pub prompts: [u32; 2],
+    pub evidence: u64,
+}
+
+/// 一个时间段内的激活次数
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ActivationTrend {
+    /// 时间段起点 (Unix 秒)
+    pub start: i64,
+    /// 该时间段内统计到的证据数
+    pub evidence: u64,
+    /// 各 Prompt 的激活次数 (键为索引)
+    pub prompts: BTreeMap<u32, u64>,
+}
+
+/// 激活模式统计
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ActivationAnalytics {
+    pub tenant_id: String,
+    /// 统计时刻 (Unix 秒)
+    pub generated_at: i64,
+    pub model: Option<String>,
+    pub since: Option<i64>,
+    pub until: Option<i64>,
+    pub interval: TrendInterval,
+    /// 统计到的证据数 (不含原文已清理或擦除的)
+    pub evidence: u64,
+    pub verdict_true: u64,
+    pub verdict_false: u64,
+    /// 范围内原文已清理或擦除、无法读出激活路径的证据数 (按模型过滤时无法判断归属，不计入)
+    pub unavailable: u64,
+    /// 各 Prompt 的激活次数，按索引升序
+    pub prompts: Vec<PromptActivation>,
+    /// 最常见的共同激活组合，按次数降序
+    pub co_activations: Vec<CoActivation>,
+    /// 按时间段的激活次数，旧的在前；没有证据的时间段不列出
+    pub trend: Vec<ActivationTrend>,
+}
+
+/// 模块：激活模式统计 (Activation Analytics)
+///
+/// **为什么需要**: `activated_prompts` 记录了每次判定触发了哪些专家，是研究造假手法的一手材料：
+/// 哪些专家总是一起被触发、哪些专家主要出现在“伪造”判定里、某类手法最近是否变多。
+/// 此前只能把证据逐条导出再自行汇总；这里在一个只读快照上直接聚合，只返回计数，不返回任何一条证据的原文。
+///
+/// 与运行统计一样逐条读取回执与原文，耗时与数据量成正比。时间按回执时间计，同一条证据中重复的索引只计一次；
+/// 检查点、保管与复核记录叶子没有回执，不计入。
+pub fn collect(snapshot: &StoreSnapshot, tenant_id: &str, filter: &ActivationFilter, now: i64) -> anyhow::Result<ActivationAnalytics> {
+    let names: BTreeMap<u32, String> = match &filter.model {
+        Some(model) => snapshot
+            .prompt_registry()?
+            .get(model)?
+            .map(|set| set.prompts.into_iter().map(|prompt| (prompt.index, prompt.name)).collect())
+            .unwrap_or_default(),
+        None => BTreeMap::new(),
+    };
+
+    let mut analytics = ActivationAnalytics {
+        tenant_id: tenant_id.to_string(),
+        generated_at: now,
+        model: filter.model.clone(),
+        since: filter.since,
+        until: filter.until,
+        interval: filter.interval,
+        evidence: 0,
+        verdict_true: 0,
+        verdict_false: 0,
+        unavailable: 0,
+        prompts: Vec::new(),
+        co_activations: Vec::new(),
+        trend: Vec::new(),
+    };
+    let mut prompts: BTreeMap<u32, PromptActivation> = BTreeMap::new();
+    let mut pairs: BTreeMap<[u32; 2], u64> = BTreeMap::new();
+    let mut trend: BTreeMap<i64, ActivationTrend> = BTreeMap::new();
+    let interval = filter.interval.seconds();
+
+    for leaf in 0..leaf_count(snapshot.mmr_size()) {
+        let pos = leaf_index_to_pos(leaf);
+        let Some(attestation) = snapshot.get_attestation(pos)? else {
+            continue;
+        };
+        let timestamp = attestation.receipt.timestamp;
+        if filter.since.is_some_and(|since| timestamp < since) || filter.until.is_some_and(|until| timestamp >= until) {
+            continue;
+        }
+        let Some(bytes) = snapshot.evidence_bytes(pos)? else {
+            if filter.model.is_none() {
+                analytics.unavailable += 1;
+            }
+            continue;
+        };
+        let evidence = Evidence::from_canonical_bytes(attestation.receipt.encoding, &bytes)?;
+        if filter.model.as_ref().is_some_and(|model| *model != evidence.prompt_pool_hash) {
+            continue;
+        }
+
+        analytics.evidence += 1;
+        if evidence.verdict {
+            analytics.verdict_true += 1;
+        } else {
+            analytics.verdict_false += 1;
+        }
+        let activated: BTreeSet<u32> = evidence.activated_prompts.iter().copied().collect();
+        let start = timestamp - timestamp.rem_euclid(interval);
+        let bucket = trend.entry(start).or_insert_with(|| ActivationTrend { start, evidence: 0, prompts: BTreeMap::new() });
+        bucket.evidence += 1;
+        for (i, &index) in activated.iter().enumerate() {
+            let prompt = prompts.entry(index).or_insert_with(|| PromptActivation {
+                index,
+                name: names.get(&index).cloned(),
+                evidence: 0,
+                verdict_true: 0,
+                verdict_false: 0,
+            });
+            prompt.evidence += 1;
+            if evidence.verdict {
+                prompt.verdict_true += 1;
+            } else {
+                prompt.verdict_false += 1;
+            }
+            *bucket.prompts.entry(index).or_default() += 1;
+            for &other in activated.iter().skip(i + 1) {
+                *pairs.entry([index, other]).or_default() += 1;
+            }
+        }
+    }
+
+    let mut co_activations: Vec<CoActivation> = pairs
+        .into_iter()
+        .map(|(prompts, evidence)| CoActivation { prompts, evidence })
+        .collect();
+    // 次数相同的按索引排，结果稳定
+    co_activations.sort_by(|a, b| b.evidence.cmp(&a.evidence).then(a.prompts.cmp(&b.prompts)));
+    co_activations.truncate(filter.top);
+
+    analytics.prompts = prompts.into_values().collect();
+    analytics.co_activations = co_activations;
+    analytics.trend = trend.into_values().collect();
+    Ok(analytics)
+}
@@ -0,0 +1,346 @@
+use log::{info, warn};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+use std::sync::Arc;
+use std::time::Duration;
+
+use crate::codec::StorageCodec;
+use crate::evidence::Evidence;
+use crate::mmr_store::{SledStore, StoreSnapshot};
+use crate::position::{leaf_count, leaf_index_to_pos};
+use crate::prompts::PromptSet;
+use crate::tenant::TenantContext;
+
+/// 扫描进度在 `anomaly_scan` 树中的键
+const CURSOR_KEY: &[u8] = b"cursor";
+
+/// 计算激活个数偏离度时标准差的下限，避免同一模型此前个数全都相同时任何偏差都被判为无穷大
+const MIN_STD_DEV: f64 = 0.5;
+
+/// 异常的类别
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AnomalyKind {
+    /// 激活了该模型此前极少 (或从未) 激活的 Prompt
+    RarePrompt,
+    /// 激活了该模型 Prompt 说明对照表中没有的索引 (登记过对照表时才检查)
+    UnregisteredPrompt,
+    /// 激活的 Prompt 个数明显偏离该模型此前的平均值
+    ActivationCount,
+}
+
+/// 一条异常理由
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AnomalyReason {
+    pub kind: AnomalyKind,
+    /// 所涉 Prompt 索引 (`activation_count` 为 null)
+    pub prompt: Option<u32>,
+    /// 给复核人看的说明 (如此前的激活频率)
+    pub detail: String,
+}
+
+/// 复核状态
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum AnomalyStatus {
+    /// 待复核
+    #[default]
+    Open,
+    /// 复核后认为正常
+    Dismissed,
+    /// 复核后确认异常 (如 Prompt 池被篡改或对抗样本)
+    Confirmed,
+}
+
+impl AnomalyStatus {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Open => "open",
+            Self::Dismissed => "dismissed",
+            Self::Confirmed => "confirmed",
+        }
+    }
+}
+
+/// 被标记的证据
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AnomalyFlag {
+    pub pos: u64,
+    /// 证据声明的模型版本 (Prompt 池哈希)
+    pub prompt_pool_hash: String,
+    pub reasons: Vec<AnomalyReason>,
+    /// 参与比较的同模型在先证据数
+    pub baseline_samples: u64,
+    /// 标记时间 (Unix 秒)
+    pub flagged_at: i64,
+    pub status: AnomalyStatus,
+    /// 复核者 (管理员 API Key 指纹)
+    pub resolved_by: Option<String>,
+    /// 复核时间 (Unix 秒)
+    pub resolved_at: Option<i64>,
+    /// 复核说明
+    pub note: String,
+}
+
+/// 一轮分析的结果
+pub struct AnomalyScan {
+    pub flags: Vec<AnomalyFlag>,
+    /// 本轮分析到的叶子数 (下一轮从这里继续)
+    pub scanned_leaves: u64,
+}
+
+/// 同一模型在先证据的激活统计
+#[derive(Default)]
+struct Baseline {
+    samples: u64,
+    prompts: BTreeMap<u32, u64>,
+    count_sum: f64,
+    count_sum_sq: f64,
+}
+
+impl Baseline {
+    fn observe(&mut self, activated: &BTreeSet<u32>) {
+        self.samples += 1;
+        for &index in activated {
+            *self.prompts.entry(index).or_default() += 1;
+        }
+        let count = activated.len() as f64;
+        self.count_sum += count;
+        self.count_sum_sq += count * count;
+    }
+}
+
+/// 模块：激活路径异常检测 (Activation Anomaly Detection)
+///
+/// **为什么需要**: 同一模型版本 (Prompt 池哈希) 的激活路径有稳定的分布：哪些专家常被触发、一次触发几个。
+/// 某条证据触发了该模型几乎从不触发的专家、触发了对照表里没有的索引，或触发个数远离常态，
+/// 往往意味着 Prompt 池被调包 (声明的哈希与实际运行的模型不符) 或输入是对抗样本，值得人工复核。
+///
+/// 后台按叶子顺序扫描，每条证据只与同模型的在先证据比较 (在先证据少于 `min_samples` 条时只检查未登记的索引)，
+/// 结果与扫描进度经写线程存入 `anomaly_flags` / `anomaly_scan` 树，每条证据只分析一次。
+/// 标记只是提示，不进入证据的签名内容；复核结论 (正常或确认异常) 由管理员在复核队列中给出。
+#[derive(Clone)]
+pub struct AnomalyDetector {
+    pub check_interval: Duration,
+    /// 同模型在先证据达到该数量后才按频率与个数判断
+    pub min_samples: u64,
+    /// 在先证据中激活频率低于该值的 Prompt 视为罕见
+    pub rare_frequency: f64,
+    /// 激活个数偏离平均值超过该倍数的标准差视为异常
+    pub max_z_score: f64,
+}
+
+impl AnomalyDetector {
+    /// 从 `from_leaf` 起分析快照中的证据 (更早的叶子只用来建立基线)
+    pub fn analyze(&self, snapshot: &StoreSnapshot, from_leaf: u64, now: i64) -> anyhow::Result<AnomalyScan> {
+        let registry = snapshot.prompt_registry()?;
+        let mut baselines: BTreeMap<String, Baseline> = BTreeMap::new();
+        let mut prompt_sets: BTreeMap<String, Option<PromptSet>> = BTreeMap::new();
+        let mut flags = Vec::new();
+        let scanned_leaves = leaf_count(snapshot.mmr_size());
+
+        for leaf in 0..scanned_leaves {
+            let pos = leaf_index_to_pos(leaf);
+            // 检查点、保管与复核记录叶子没有回执；原文已清理或擦除的证据无从分析，也不计入基线
+            let Some(attestation) = snapshot.get_attestation(pos)? else {
+                continue;
+            };
+            let Some(bytes) = snapshot.evidence_bytes(pos)? else {
+                continue;
+            };
+            let evidence = Evidence::from_canonical_bytes(attestation.receipt.encoding, &bytes)?;
+            let activated: BTreeSet<u32> = evidence.activated_prompts.iter().copied().collect();
+            let baseline = baselines.entry(evidence.prompt_pool_hash.clone()).or_default();
+
+            if leaf >= from_leaf {
+                if !prompt_sets.contains_key(&evidence.prompt_pool_hash) {
+                    prompt_sets.insert(evidence.prompt_pool_hash.clone(), registry.get(&evidence.prompt_pool_hash)?);
+                }
+                let prompt_set = prompt_sets[&evidence.prompt_pool_hash].as_ref();
+                let reasons = self.reasons(baseline, &activated, prompt_set);
+                if !reasons.is_empty() {
+                    flags.push(AnomalyFlag {
+                        pos,
+                        prompt_pool_hash: evidence.prompt_pool_hash.clone(),
+                        reasons,
+                        baseline_samples: baseline.samples,
+                        flagged_at: now,
+                        status: AnomalyStatus::Open,
+                        resolved_by: None,
+                        resolved_at: None,
+                        note: String::new(),
+                    });
+                }
+            }
+            baseline.observe(&activated);
+        }
+        Ok(AnomalyScan { flags, scanned_leaves })
+    }
+
+    /// 与同模型基线比较，返回全部异常理由
+    fn reasons(&self, baseline: &Baseline, activated: &BTreeSet<u32>, prompt_set: Option<&PromptSet>) -> Vec<AnomalyReason> {
+        let mut reasons = Vec::new();
+        if let Some(prompt_set) = prompt_set {
+            let known = prompt_set.by_index();
+            for &index in activated.iter().filter(|index| !known.contains_key(index)) {
+                reasons.push(AnomalyReason {
+                    kind: AnomalyKind::UnregisteredPrompt,
+                    prompt: Some(index),
+                    detail: format!("prompt {} is not in the registry of this model", index),
+                });
+            }
+        }
+        if baseline.samples < self.min_samples {
+            return reasons;
+        }
+
+        let samples = baseline.samples as f64;
+        for &index in activated {
+            let seen = baseline.prompts.get(&index).copied().unwrap_or(0);
+            let frequency = seen as f64 / samples;
+            if frequency < self.rare_frequency {
+                reasons.push(AnomalyReason {
+                    kind: AnomalyKind::RarePrompt,
+                    prompt: Some(index),
+                    detail: format!("prompt {} was activated in {} of {} earlier evidence ({:.2}%)", index, seen, baseline.samples, frequency * 100.0),
+                });
+            }
+        }
+        let mean = baseline.count_sum / samples;
+        let std_dev = (baseline.count_sum_sq / samples - mean * mean).max(0.0).sqrt().max(MIN_STD_DEV);
+        let count = activated.len();
+        let z_score = (count as f64 - mean).abs() / std_dev;
+        if z_score > self.max_z_score {
+            reasons.push(AnomalyReason {
+                kind: AnomalyKind::ActivationCount,
+                prompt: None,
+                detail: format!("{} prompts activated, earlier evidence averaged {:.2} (z = {:.1})", count, mean, z_score),
+            });
+        }
+        reasons
+    }
+
+    /// 对所有租户执行一轮分析
+    pub async fn analyze_once(&self, tenants: &[Arc<TenantContext>]) {
+        for tenant in tenants {
+            let snapshot = tenant.reader.snapshot();
+            let detector = self.clone();
+            let now = chrono::Utc::now().timestamp();
+            let scan = tokio::task::spawn_blocking(move || {
+                let from_leaf = snapshot.anomalies()?.cursor()?;
+                if from_leaf >= leaf_count(snapshot.mmr_size()) {
+                    return Ok(None);
+                }
+                detector.analyze(&snapshot, from_leaf, now).map(Some)
+            })
+            .await
+            .map_err(anyhow::Error::from)
+            .and_then(|scan| scan);
+            let scan = match scan {
+                Ok(Some(scan)) => scan,
+                Ok(None) => continue,
+                Err(e) => {
+                    warn!("⚠️  租户 '{}' 激活路径异常检测失败 (下次重试): {}", tenant.id, e);
+                    continue;
+                }
+            };
+            let flagged = scan.flags.len();
+            match tenant.writer.record_anomalies(scan.flags, scan.scanned_leaves).await {
+                Ok(0) => {}
+                Ok(_) => info!("🔎 租户 '{}' 发现 {} 条激活路径异常的证据，已加入复核队列", tenant.id, flagged),
+                Err(e) => warn!("⚠️  租户 '{}' 异常标记写入失败 (下次重试): {}", tenant.id, e),
+            }
+        }
+    }
+
+    /// 启动后台周期分析任务 (启动时先执行一次)
+    pub fn spawn_periodic(self, tenants: Vec<Arc<TenantContext>>) {
+        if self.check_interval.is_zero() {
+            return;
+        }
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(self.check_interval);
+            loop {
+                ticker.tick().await;
+                self.analyze_once(&tenants).await;
+            }
+        });
+    }
+}
+
+/// 异常标记与扫描进度
+///
+/// 标记存 `anomaly_flags` 树 (键为证据位置)，扫描进度 (已分析的叶子数) 存 `anomaly_scan` 树。
+#[derive(Clone)]
+pub struct AnomalyLog {
+    flags: sled::Tree,
+    scan: sled::Tree,
+}
+
+impl AnomalyLog {
+    pub fn open(store: &SledStore) -> anyhow::Result<Self> {
+        Ok(Self { flags: store.tree("anomaly_flags")?, scan: store.tree("anomaly_scan")? })
+    }
+
+    /// 已分析的叶子数
+    pub fn cursor(&self) -> anyhow::Result<u64> {
+        match self.scan.get(CURSOR_KEY)? {
+            Some(v) => Ok(u64::from_be_bytes(v.as_ref().try_into()?)),
+            None => Ok(0),
+        }
+    }
+
+    /// 指定证据的标记；未被标记返回 None
+    pub fn get(&self, pos: u64) -> anyhow::Result<Option<AnomalyFlag>> {
+        self.flags.get(pos.to_be_bytes())?.map(|v| StorageCodec::decode(&v)).transpose()
+    }
+
+    /// 按位置升序列出标记；`status` 为 None 时列出全部
+    pub fn list(&self, status: Option<AnomalyStatus>) -> anyhow::Result<Vec<AnomalyFlag>> {
+        let mut flags = Vec::new();
+        for value in self.flags.iter().values() {
+            let flag: AnomalyFlag = StorageCodec::decode(&value?)?;
+            if status.is_none_or(|status| flag.status == status) {
+                flags.push(flag);
+            }
+        }
+        Ok(flags)
+    }
+
+    /// 写入一轮分析的结果并推进扫描进度；已有的标记 (含复核结论) 不覆盖，返回新增的条数
+    pub fn record(&self, flags: &[AnomalyFlag], scanned_leaves: u64) -> anyhow::Result<usize> {
+        let mut added = 0;
+        for flag in flags {
+            let key = flag.pos.to_be_bytes();
+            if !self.flags.contains_key(key)? {
+                self.flags.insert(key, StorageCodec::encode(flag)?)?;
+                added += 1;
+            }
+        }
+        if scanned_leaves > self.cursor()? {
+            self.scan.insert(CURSOR_KEY, &scanned_leaves.to_be_bytes())?;
+        }
+        self.flags.flush()?;
+        self.scan.flush()?;
+        Ok(added)
+    }
+
+    /// 给出复核结论；只能复核待复核的标记
+    pub fn resolve(&self, pos: u64, status: AnomalyStatus, principal: &str, note: &str, at: i64) -> anyhow::Result<AnomalyFlag> {
+        if status == AnomalyStatus::Open {
+            return Err(anyhow::anyhow!("Invalid anomaly resolution: status must be dismissed or confirmed"));
+        }
+        let mut flag = self.get(pos)?.ok_or_else(|| anyhow::anyhow!("No anomaly flag at pos {}", pos))?;
+        if flag.status != AnomalyStatus::Open {
+            return Err(anyhow::anyhow!("Anomaly at pos {} is already {}", pos, flag.status.as_str()));
+        }
+        flag.status = status;
+        flag.resolved_by = Some(principal.to_string());
+        flag.resolved_at = Some(at);
+        flag.note = note.to_string();
+        self.flags.insert(pos.to_be_bytes(), StorageCodec::encode(&flag)?)?;
+        self.flags.flush()?;
+        Ok(flag)
+    }
+}
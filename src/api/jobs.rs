@@ -0,0 +1,180 @@
+use log::{error, info};
+use opentelemetry::context::FutureExt;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::sync::mpsc;
+
+use super::{prove_pipeline, AppState, JobResponse, JobStage, Problem, ProveReceipt, ProveRequest};
+use crate::{encoding::BinaryEncoding, evidence::Provenance, ingest::Priority, telemetry, tenant::TenantContext};
+
+/// 排队中的后台存证任务
+struct QueuedJob {
+    id: String,
+    tenant: Arc<TenantContext>,
+    principal: String,
+    provenance: Option<Provenance>,
+    request: ProveRequest,
+    encoding: BinaryEncoding,
+}
+
+struct JobEntry {
+    tenant_id: String,
+    priority: Priority,
+    status: JobResponse,
+}
+
+/// 模块：后台存证任务 (Async Jobs)
+///
+/// **为什么需要**: 大视频的指纹计算远超一次 HTTP 请求能等待的时长，网关与客户端往往先超时，
+/// 调用方既拿不到回执，也不知道证据到底入库没有。`POST /prove/async` 只做请求校验便放入任务队列
+/// 并返回任务 ID，由后台 worker 走与 `/prove` 相同的存证管线，`GET /jobs/{id}` 轮询进度与最终回执。
+///
+/// - 任务队列按优先级分通道，每个通道有界 (`job_queue_depth`)，满了返回 429；
+///   `job_workers` 个 worker 总是先取高优先级通道中的任务
+/// - 后台任务进入存证管线时排队等待而不是直接拒绝 (任务本身已在队列中限流)
+/// - 任务状态只保存在内存中：结束超过 `job_retention_secs` 的任务被清除，节点重启后全部丢失，
+///   调用方应设置 `nonce`，任务丢失时可安全地重新提交 (已入库的会被判为重放)
+pub struct JobQueue {
+    jobs: Mutex<HashMap<String, JobEntry>>,
+    /// 各优先级的任务通道 (按 `Priority::ALL` 顺序)
+    senders: [mpsc::Sender<QueuedJob>; 3],
+    receivers: tokio::sync::Mutex<[mpsc::Receiver<QueuedJob>; 3]>,
+    retention_secs: i64,
+}
+
+impl JobQueue {
+    pub fn new(queue_depth: usize, retention: Duration) -> Self {
+        let [(urgent_tx, urgent_rx), (normal_tx, normal_rx), (bulk_tx, bulk_rx)] = Priority::ALL.map(|_| mpsc::channel(queue_depth.max(1)));
+        Self {
+            jobs: Mutex::new(HashMap::new()),
+            senders: [urgent_tx, normal_tx, bulk_tx],
+            receivers: tokio::sync::Mutex::new([urgent_rx, normal_rx, bulk_rx]),
+            retention_secs: retention.as_secs() as i64,
+        }
+    }
+
+    /// 放入任务队列，返回任务状态；队列已满时返回 None
+    pub(super) fn submit(
+        &self,
+        tenant: Arc<TenantContext>,
+        principal: String,
+        provenance: Option<Provenance>,
+        request: ProveRequest,
+        encoding: BinaryEncoding,
+    ) -> Option<JobResponse> {
+        let now = chrono::Utc::now().timestamp();
+        let id = format!("{:032x}", rand::random::<u128>());
+        let status = JobResponse {
+            job_id: id.clone(),
+            status: JobStage::Queued,
+            created_at: now,
+            updated_at: now,
+            leaf_pos: None,
+            receipt: None,
+            error: None,
+        };
+        let mut jobs = self.jobs.lock().unwrap();
+        jobs.retain(|_, entry| !entry.status.status.is_finished() || now - entry.status.updated_at < self.retention_secs);
+        let tenant_id = tenant.id.clone();
+        let priority = request.priority;
+        self.senders[priority.lane()].try_send(QueuedJob { id: id.clone(), tenant, principal, provenance, request, encoding }).ok()?;
+        jobs.insert(id, JobEntry { tenant_id, priority, status: status.clone() });
+        Some(status)
+    }
+
+    /// 查询任务状态；不属于该租户的任务视为不存在
+    pub fn get(&self, tenant_id: &str, id: &str) -> Option<JobResponse> {
+        let jobs = self.jobs.lock().unwrap();
+        jobs.get(id).filter(|entry| entry.tenant_id == tenant_id).map(|entry| entry.status.clone())
+    }
+
+    /// 该优先级尚未结束的任务数 (排队中 + 处理中)
+    pub fn pending(&self, priority: Priority) -> usize {
+        let jobs = self.jobs.lock().unwrap();
+        jobs.values().filter(|entry| entry.priority == priority && !entry.status.status.is_finished()).count()
+    }
+
+    /// 取下一个任务：高优先级通道优先
+    async fn next(&self) -> Option<QueuedJob> {
+        let mut receivers = self.receivers.lock().await;
+        let [urgent, normal, bulk] = &mut *receivers;
+        tokio::select! {
+            biased;
+            Some(job) = urgent.recv() => Some(job),
+            Some(job) = normal.recv() => Some(job),
+            Some(job) = bulk.recv() => Some(job),
+            else => None,
+        }
+    }
+
+    /// 推进任务阶段 (存证管线调用；`id` 为 None 即同步请求，什么也不做)
+    pub(super) fn advance(&self, id: Option<&str>, stage: JobStage) {
+        self.update(id, |status| status.status = stage);
+    }
+
+    /// 证据已入库 (回执尚未返回)
+    pub(super) fn appended(&self, id: Option<&str>, leaf_pos: u64) {
+        self.update(id, |status| {
+            status.status = JobStage::Appended;
+            status.leaf_pos = Some(leaf_pos);
+        });
+    }
+
+    fn finish(&self, id: &str, result: Result<ProveReceipt, Problem>) {
+        self.update(Some(id), |status| match result {
+            Ok(receipt) => {
+                status.status = JobStage::Completed;
+                status.leaf_pos = Some(receipt.leaf_pos);
+                status.receipt = Some(receipt);
+            }
+            Err(problem) => {
+                status.status = JobStage::Failed;
+                status.error = Some(problem.to_details());
+            }
+        });
+    }
+
+    fn update(&self, id: Option<&str>, f: impl FnOnce(&mut JobResponse)) {
+        let Some(id) = id else { return };
+        if let Some(entry) = self.jobs.lock().unwrap().get_mut(id) {
+            f(&mut entry.status);
+            entry.status.updated_at = chrono::Utc::now().timestamp();
+        }
+    }
+}
+
+/// 启动后台存证 worker
+pub fn spawn_job_workers(state: Arc<AppState>, workers: usize) {
+    for _ in 0..workers.max(1) {
+        let state = state.clone();
+        tokio::spawn(async move {
+            loop {
+                let Some(job) = state.jobs.next().await else { break };
+                run_job(&state, job).await;
+            }
+        });
+    }
+}
+
+async fn run_job(state: &Arc<AppState>, job: QueuedJob) {
+    info!("⚙️  [{}] 开始处理后台任务 {}", job.tenant.id, job.id);
+    let request = telemetry::request("POST /prove/async", &job.tenant.id);
+    request.set_attribute("yuanjing.job_id", job.id.clone());
+    let result = prove_pipeline(state.clone(), job.tenant, job.principal, job.provenance, job.request, job.encoding, Some(&job.id))
+        .with_context(request.context().clone())
+        .await;
+    let result = match result {
+        Ok(receipt) => {
+            request.set_attribute("yuanjing.leaf_pos", receipt.leaf_pos as i64);
+            info!("✅ 后台任务 {} 完成: Pos={}", job.id, receipt.leaf_pos);
+            Ok(receipt.0)
+        }
+        Err(problem) => {
+            request.fail(problem.detail());
+            error!("❌ 后台任务 {} 失败: {}", job.id, problem);
+            Err(problem.with_trace_id(request.trace_id()))
+        }
+    };
+    state.jobs.finish(&job.id, result);
+}
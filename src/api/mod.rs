@@ -0,0 +1,3177 @@
+mod jobs;
+mod problem;
+pub mod v1;
+
+use axum::{
+    body::Bytes,
+    extract::{ConnectInfo, DefaultBodyLimit, FromRequest, FromRequestParts, MatchedPath, Path, Query, Request, State},
+    http::{header::{ACCEPT, AUTHORIZATION, CONTENT_DISPOSITION, CONTENT_LENGTH, CONTENT_TYPE, LINK, LOCATION, RETRY_AFTER}, request::Parts, HeaderMap, HeaderName, HeaderValue, Method, StatusCode},
+    middleware,
+    response::{IntoResponse, Json, Response},
+    routing::{get, post},
+    Extension, Router,
+};
+use log::{error, info, warn};
+use opentelemetry::context::FutureExt;
+use schemars::JsonSchema;
+use serde::Serialize;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tower_http::cors::CorsLayer;
+
+use crate::{
+    abuse::AbuseMonitor,
+    access_log::{self, AccessLog, AccessLogEntry, AccessLogFilter, AccessedPos},
+    analytics::{self, ActivationAnalytics, ActivationFilter},
+    anomaly::AnomalyFlag,
+    bundle::{BundleEndorsement, EvidenceBundle, BUNDLE_FORMAT},
+    challenge::{self, LivenessStatement},
+    clock::{ClockGuard, ClockPolicy},
+    custody::{CustodyAction, CustodyDetails, CustodyRecord},
+    did::DidDocument,
+    dsse::{self, DsseEnvelope},
+    enclave::EnclaveAttestation,
+    encoding::BinaryEncoding,
+    envelope::SealedBundle,
+    evidence::{Calibration, CanonicalEncoding, Confidence, Consensus, ConsensusRule, Evidence, ModelVerdict, Provenance},
+    evidence_index::{EvidenceFilter, SortField, SortOrder},
+    failover::WriteFence,
+    fingerprint::{self, ImageLimits},
+    ingest::{IngestGate, Priority},
+    integrity::IntegrityReport,
+    key_fingerprint,
+    keystore::KeySource,
+    legal_hold::{HoldEvent, LegalHold},
+    mmr_store::{ErasureRecord, StoreSnapshot, DEFAULT_TENANT},
+    model_registry::{self, SignedRootMetadata, SignedTargetsMetadata},
+    ops_log::{OpsEvent, OpsLog},
+    prompts::PromptSet,
+    position,
+    proof::{CompactProof, InclusionProof, COMPACT_PROOF_MEDIA_TYPE},
+    provenance::ProvenanceCapture,
+    publication::{self, RootPublisher},
+    qr,
+    receipt::{Receipt, ReceiptPointer},
+    replication::{self, AckTracker, ReplicationBatch, MAX_LONG_POLL_MS, MAX_REPLICATION_BATCH},
+    report::{Report, ReportFormat, ReportSection},
+    review::{Examiners, ReviewAssessment, ReviewRecord},
+    revocation::SignedRevocationList,
+    schema, scitt,
+    search::{self, SearchService},
+    self_audit::{self, SelfAuditReport},
+    signer::EvidenceSigner,
+    solidity::{self, SolidityProof},
+    stats::{self, TenantStats},
+    tags,
+    telemetry,
+    tenant::{principal_of, AdminKeys, Auditors, TenantContext, TenantRegistry},
+    vc,
+    writer::AttestRequest,
+    x509::{CertificateChain, SignerCertificate, TrustStore},
+    zk::{self, ZkProof, ZK_PROOF_MEDIA_TYPE},
+};
+
+pub use jobs::{spawn_job_workers, JobQueue};
+pub use problem::{FieldError, Problem, ProblemDetails, ProblemType, PROBLEM_MEDIA_TYPE};
+pub use v1::*;
+
+// ==========================================
+// 1. 定义应用状态 (Shared State)
+// ==========================================
+// 所有的 HTTP 请求都会共享这个状态。
+// 每个租户有自己的写线程、只读句柄与签名子密钥 (见 tenant.rs)：
+// 写操作统一交给单写者线程 (MMR 是追加写的，必须串行)；
+// 读操作通过 EvidenceReader 无锁读取已提交快照，审计不会阻塞存证。
+pub struct AppState {
+    pub tenants: TenantRegistry,
+    /// 管理员 API Key，未配置时管理接口一律拒绝
+    pub admins: AdminKeys,
+    /// 审计方 API Key，凭访问控制列表读取被授权的证据原文
+    pub auditors: Auditors,
+    /// 已登记公钥的鉴定人 (复核意见须由其签名)
+    pub examiners: Examiners,
+    /// 多模型证据缺省的共识规则 (`[policy] consensus_rule`)
+    pub consensus_rule: ConsensusRule,
+    pub clock: Arc<ClockGuard>,
+    /// 主身份签名的密钥吊销列表，供 `/verify` 判定与 `/revocations` 公开
+    pub revocations: SignedRevocationList,
+    /// 主身份的 X.509 证书链 (可选)，随主身份签发的回执一起返回
+    pub certificate: Option<SignerCertificate>,
+    /// 受信根证书，用于 `/verify` 校验证书链
+    pub trust: TrustStore,
+    /// 鉴定中心的 DID 文档，回执通过其中的验证方法 ID 引用签名公钥
+    pub did: DidDocument,
+    /// 根发布：各租户最近一次签名的树头
+    pub roots: RootPublisher,
+    /// 存证管线限流
+    pub ingest: IngestGate,
+    /// 提交图片的大小与像素数上限
+    pub image_limits: ImageLimits,
+    /// 按提交方的滥用检测 (见 abuse.rs)
+    pub abuse: AbuseMonitor,
+    /// 提交来源采集 (见 provenance.rs)；未开启时为 None
+    pub provenance: Option<ProvenanceCapture>,
+    /// 证据元数据全文检索 (见 search.rs)；未开启时为 None
+    pub search: Option<Arc<SearchService>>,
+    /// 后台存证任务 (`/prove/async`)
+    pub jobs: JobQueue,
+    /// 构建与运行配置，供 `/version` 公开
+    pub version: VersionInfo,
+    /// 主身份的公开信息，供 `/identity` 公开
+    pub identity: IdentityInfo,
+    /// 副本确认 (多数派确认开启时 `/prove` 据此等待)
+    pub acks: Arc<AckTracker>,
+    /// 写入围栏：未持有写租约 (或为只读副本) 时拒绝写接口
+    pub fence: WriteFence,
+    /// 访问日志 (节点级，见 access_log.rs)
+    pub access_log: AccessLog,
+    /// 是否记录访问日志；关闭后仍可查询已有记录
+    pub access_log_enabled: bool,
+    /// 运维审计日志 (`ops_log.path` 配置为空串时为 None)
+    pub ops_log: Option<OpsLog>,
+}
+
+/// 旧路由 (未带版本前缀) 开始弃用的时间 (Unix 秒，2026-10-16，随 `Deprecation` 头返回)
+const LEGACY_DEPRECATED_AT: i64 = 1_792_108_800;
+
+/// 只读副本与未持有写租约的节点上拒绝的写接口 (按去掉版本前缀的路由模板匹配，只拒绝 POST)
+const REPLICA_REJECTED_ROUTES: &[&str] = &[
+    "/prove",
+    "/prove/async",
+    "/model/register",
+    "/model/registry/root",
+    "/model/registry/targets",
+    "/models/{hash}/prompts",
+    "/evidence/{pos}/erase",
+    "/evidence/{pos}/hold",
+    "/evidence/{pos}/hold/release",
+    "/evidence/{pos}/acl",
+    "/evidence/{pos}/tags",
+    "/evidence/{pos}/custody",
+    "/evidence/{pos}/reviews",
+    "/anomalies/{pos}/resolve",
+];
+
+// 租户范围提取器：从 `X-Api-Key` 或 `Authorization: Bearer` 头解析当前租户。
+// 未启用多租户时总是解析为 `default` 租户。
+pub struct TenantScope(pub Arc<TenantContext>);
+
+impl FromRequestParts<Arc<AppState>> for TenantScope {
+    type Rejection = Problem;
+
+    async fn from_request_parts(parts: &mut Parts, state: &Arc<AppState>) -> Result<Self, Self::Rejection> {
+        state
+            .tenants
+            .resolve(api_key_of(parts))
+            .map(TenantScope)
+            .ok_or_else(|| Problem::new(ProblemType::Unauthorized, "Missing or invalid API key"))
+    }
+}
+
+// 原文读取提取器：携带 `X-Auditor-Key` 时为审计方，租户取自 `?tenant=` (缺省 `default`)，
+// 只能读取访问控制列表授权给它的位置；否则同 `TenantScope`，可读取本租户的全部原文。
+pub struct PayloadScope {
+    pub tenant: Arc<TenantContext>,
+    /// 审计方名称；租户本身访问时为 None
+    pub auditor: Option<String>,
+}
+
+impl FromRequestParts<Arc<AppState>> for PayloadScope {
+    type Rejection = Problem;
+
+    async fn from_request_parts(parts: &mut Parts, state: &Arc<AppState>) -> Result<Self, Self::Rejection> {
+        if parts.headers.contains_key("x-auditor-key") {
+            let auditor = auditor_of(parts, state)
+                .ok_or_else(|| Problem::new(ProblemType::Unauthorized, "Invalid auditor key"))?;
+            let Query(query) = Query::<AuditorTenantQuery>::try_from_uri(&parts.uri)
+                .map_err(|e| Problem::bad_request(e.body_text()))?;
+            let tenant_id = query.tenant.as_deref().unwrap_or(DEFAULT_TENANT);
+            let tenant = state.tenants.get(tenant_id)
+                .ok_or_else(|| Problem::not_found(format!("Unknown tenant '{}'", tenant_id)))?;
+            return Ok(PayloadScope { tenant, auditor: Some(auditor.to_string()) });
+        }
+        let TenantScope(tenant) = TenantScope::from_request_parts(parts, state).await?;
+        Ok(PayloadScope { tenant, auditor: None })
+    }
+}
+
+impl PayloadScope {
+    /// 审计方须经 `pos` 处的访问控制列表授权；租户本身总是放行
+    fn authorize(&self, snapshot: &StoreSnapshot, pos: u64) -> Result<(), Problem> {
+        let Some(auditor) = &self.auditor else {
+            return Ok(());
+        };
+        if snapshot.acls().and_then(|acls| acls.allows(pos, auditor)).map_err(Problem::internal)? {
+            Ok(())
+        } else {
+            Err(Problem::new(
+                ProblemType::Forbidden,
+                format!("Auditor '{}' is not granted access to evidence at pos {}; use /audit/{} for its inclusion proof", auditor, pos, pos),
+            ))
+        }
+    }
+}
+
+/// 请求中有效的审计方名称
+fn auditor_of<'a>(parts: &Parts, state: &'a AppState) -> Option<&'a str> {
+    parts
+        .headers
+        .get("x-auditor-key")
+        .and_then(|v| v.to_str().ok())
+        .and_then(|key| state.auditors.resolve(key))
+}
+
+// 请求主体提取器：API Key 指纹 (见 `tenant::principal_of`)，记入签名日志
+pub struct Principal(pub String);
+
+impl<S: Send + Sync> FromRequestParts<S> for Principal {
+    type Rejection = Problem;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        Ok(Principal(principal_of(api_key_of(parts))))
+    }
+}
+
+// 提交来源提取器：按 `[provenance]` 采集请求方 IP、客户端证书主题与 User-Agent，未开启时为 None。
+// 没有连接信息 (如测试中直接调用路由) 时 IP 只能来自 `X-Forwarded-For`。
+pub struct SubmitterProvenance(pub Option<Provenance>);
+
+impl FromRequestParts<Arc<AppState>> for SubmitterProvenance {
+    type Rejection = Problem;
+
+    async fn from_request_parts(parts: &mut Parts, state: &Arc<AppState>) -> Result<Self, Self::Rejection> {
+        let Some(capture) = &state.provenance else {
+            return Ok(SubmitterProvenance(None));
+        };
+        let peer = parts.extensions.get::<ConnectInfo<std::net::SocketAddr>>().map(|ConnectInfo(addr)| addr.ip());
+        capture.capture(&parts.headers, peer).map(|provenance| SubmitterProvenance(Some(provenance))).map_err(Problem::internal)
+    }
+}
+
+// 管理员提取器：校验 `X-Admin-Key`，得到管理员主体标识 (记入保全历史)
+pub struct Admin(pub String);
+
+impl FromRequestParts<Arc<AppState>> for Admin {
+    type Rejection = Problem;
+
+    async fn from_request_parts(parts: &mut Parts, state: &Arc<AppState>) -> Result<Self, Self::Rejection> {
+        if !state.admins.enabled() {
+            return Err(Problem::new(ProblemType::Forbidden, "Admin API is disabled (configure api.admin_keys)"));
+        }
+        parts
+            .headers
+            .get("x-admin-key")
+            .and_then(|v| v.to_str().ok())
+            .filter(|key| state.admins.contains(key))
+            .map(|key| Admin(AdminKeys::principal_of(key)))
+            .ok_or_else(|| Problem::new(ProblemType::Forbidden, "Missing or invalid admin key"))
+    }
+}
+
+// 输出编码提取器：响应中哈希、签名、公钥的编码 (见 encoding.rs)。
+// `?encoding=` 优先，其次为 `Accept` 的 `encoding` 参数 (如 `application/json; encoding=base64`)，缺省为 Hex。
+pub struct OutputEncoding(pub BinaryEncoding);
+
+impl<S: Send + Sync> FromRequestParts<S> for OutputEncoding {
+    type Rejection = Problem;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        let Query(query) = Query::<EncodingQuery>::try_from_uri(&parts.uri)
+            .map_err(|e| Problem::bad_request(e.body_text()))?;
+        match query.encoding.or_else(|| accept_encoding(&parts.headers)) {
+            Some(name) => name.parse().map(OutputEncoding).map_err(|e: anyhow::Error| Problem::bad_request(e.to_string())),
+            None => Ok(OutputEncoding(BinaryEncoding::default())),
+        }
+    }
+}
+
+/// `Accept` 中第一个带 `encoding` 参数的媒体类型的参数值
+fn accept_encoding(headers: &HeaderMap) -> Option<String> {
+    headers
+        .get_all(ACCEPT)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(','))
+        .flat_map(|media| media.split(';').skip(1))
+        .find_map(|param| {
+            let (name, value) = param.split_once('=')?;
+            name.trim().eq_ignore_ascii_case("encoding").then(|| value.trim().trim_matches('"').to_string())
+        })
+}
+
+// 校验后的 JSON 请求体：先按公开的 JSON Schema 校验 (见 schema.rs)，一次报告全部错误，再交给 serde
+pub struct ValidatedJson<T>(pub T);
+
+impl<T, S> FromRequest<S> for ValidatedJson<T>
+where
+    T: JsonSchema + serde::de::DeserializeOwned,
+    S: Send + Sync,
+{
+    type Rejection = Problem;
+
+    async fn from_request(req: Request, state: &S) -> Result<Self, Self::Rejection> {
+        let Json(value) = Json::<serde_json::Value>::from_request(req, state)
+            .await
+            .map_err(|e| Problem::from_status(e.status(), e.body_text()))?;
+        let errors = schema::validate(&schema::schema_for::<T>(), &value);
+        if !errors.is_empty() {
+            let detail = errors.iter().map(ToString::to_string).collect::<Vec<_>>().join("; ");
+            return Err(Problem::new(
+                ProblemType::ValidationFailed,
+                format!("Request body does not match schema {}: {}", T::schema_name(), detail),
+            )
+            .with_errors(errors.into_iter().map(FieldError::from).collect()));
+        }
+        serde_json::from_value(value)
+            .map(ValidatedJson)
+            .map_err(|e| Problem::new(ProblemType::ValidationFailed, format!("Invalid request body: {}", e)))
+    }
+}
+
+fn api_key_of(parts: &Parts) -> Option<&str> {
+    parts
+        .headers
+        .get("x-api-key")
+        .and_then(|v| v.to_str().ok())
+        .or_else(|| {
+            parts
+                .headers
+                .get(AUTHORIZATION)
+                .and_then(|v| v.to_str().ok())
+                .and_then(|v| v.strip_prefix("Bearer "))
+        })
+}
+
+// ==========================================
+// 2. 数据传输对象 (DTOs)
+// ==========================================
+
+// 请求 / 响应类型按 API 版本分模块 (见 v1.rs)，这里只保留不随 API 版本变化的部分
+
+// 响应：版本与持久化配置
+#[derive(Debug, Clone, Serialize)]
+pub struct VersionInfo {
+    pub name: String,
+    pub version: String,
+    /// 落盘记录的存储格式版本
+    pub storage_format: u8,
+    /// 持久化策略: durable | fast
+    pub durability: String,
+    /// sled 后台刷盘间隔 (毫秒)，null 表示关闭
+    pub flush_every_ms: Option<u64>,
+    /// 新租户的规范编码: bcs | protobuf (各回执以自身的 `encoding` 字段为准)
+    pub canonical_encoding: String,
+    /// 新叶子是否加盐
+    pub salted_leaves: bool,
+    /// 证据原文静态加密的主密钥来源: none | env | file | vault
+    pub evidence_encryption: String,
+    /// 只读副本所跟随的主库地址；主库为 null (热备接管写入后仍保留启动时的配置)
+    pub replica_of: Option<String>,
+    /// 本节点当前是否接受写入
+    pub writable: bool,
+    /// 持有的写租约代号 (未启用主备切换或未持有时为 null)
+    pub lease_epoch: Option<u64>,
+    /// 支持的 API 版本 (路径前缀)，如 `["v1"]`
+    pub api_versions: Vec<String>,
+    /// 是否仍提供未带版本前缀的旧路由
+    pub legacy_routes: bool,
+    /// 旧路由的停用时间 (Unix 秒)，未公布时为 null
+    pub legacy_sunset: Option<i64>,
+}
+
+/// 主身份的公开信息 (启动时确定)
+pub struct IdentityInfo {
+    pub public_key: ed25519_dalek::VerifyingKey,
+    pub key_source: KeySource,
+    pub key_created_at: Option<i64>,
+    /// 新租户采用的规范编码
+    pub canonical_encoding: CanonicalEncoding,
+    /// 飞地签名模式下启动时取得的远程证明
+    pub enclave: Option<EnclaveAttestation>,
+}
+
+// ==========================================
+// 3. API 路由构建
+// ==========================================
+pub fn app(state: Arc<AppState>) -> Router {
+    let v1 = v1_routes();
+    let mut router = Router::new()
+        .nest(v1::PREFIX, v1.clone())
+        // 不随 API 版本变化的接口：约定路径 (.well-known)、监控抓取与版本发现
+        .route("/.well-known/did.json", get(get_did_document))
+        .route("/.well-known/yuanjing-root", get(get_published_roots))
+        .route("/metrics", get(metrics))
+        .route("/version", get(get_version));
+    if state.version.legacy_routes {
+        // 旧路由与 /v1 共用处理函数，只在响应上标注弃用
+        router = router.merge(v1.layer(middleware::from_fn_with_state(state.clone(), deprecate_legacy_route)));
+    }
+    router
+        .layer(middleware::from_fn_with_state(state.clone(), reject_writes_on_replica))
+        .layer(middleware::map_response(problem_for_plain_errors))
+        .layer(middleware::map_response_with_state(state.clone(), add_retry_after))
+        .layer(middleware::from_fn_with_state(state.clone(), record_access))
+        .layer(CorsLayer::permissive()) // ⚠️ 开发模式：允许所有跨域
+        .with_state(state)
+}
+
+/// API v1 的路由：挂在 `/v1` 下，开启旧路由时也挂在根路径下
+///
+/// 回执格式等不兼容的改动在新版本 (`/v2`) 中发布，DTO 放在对应的版本模块里，已接入的调用方继续使用 `/v1`。
+fn v1_routes() -> Router<Arc<AppState>> {
+    Router::new()
+        .route("/prove", post(submit_evidence))
+        .route("/prove/async", post(submit_evidence_async))
+        .route("/jobs/{id}", get(get_job))
+        .route("/audit/batch", post(get_batch_audit_proof))
+        .route("/audit/{pos}", get(get_audit_proof))
+        .route("/audit/{pos}/solidity", get(get_solidity_proof))
+        .route("/audit/{pos}/zk", get(get_zk_proof))
+        .route("/evidence", get(list_evidence))
+        .route("/search", get(search_evidence))
+        .route("/evidence/{pos}", get(get_evidence_record))
+        .route("/evidence/{pos}/credential", get(get_evidence_credential))
+        .route("/evidence/{pos}/dsse", get(get_evidence_dsse))
+        .route("/evidence/{pos}/scitt", get(get_evidence_scitt))
+        .route("/evidence/{pos}/bundle", get(get_evidence_bundle))
+        .route("/evidence/{pos}/bundle/sealed", get(get_sealed_bundle))
+        .route("/evidence/{pos}/chain", get(get_supersession_chain))
+        .route("/evidence/by-id/{id}", get(get_evidence_by_id))
+        .route("/evidence/{pos}/qr", get(get_receipt_qr))
+        .route("/report/{pos}", get(get_audit_report))
+        .route("/evidence/{pos}/erase", post(erase_evidence))
+        .route("/erasures", get(list_erasures))
+        .route("/evidence/{pos}/hold", get(get_legal_hold).post(place_legal_hold))
+        .route("/evidence/{pos}/hold/release", post(release_legal_hold))
+        .route("/evidence/{pos}/acl", get(get_evidence_acl).post(set_evidence_acl))
+        .route("/evidence/{pos}/tags", get(get_evidence_tags).post(set_evidence_tags))
+        .route("/evidence/{pos}/custody", get(get_custody).post(append_custody))
+        .route("/evidence/{pos}/reviews", get(get_reviews).post(submit_review))
+        .route("/legal-holds", get(list_legal_holds))
+        .route("/anomalies", get(list_anomalies))
+        .route("/anomalies/{pos}/resolve", post(resolve_anomaly))
+        .route("/admin/self-audit", post(run_self_audit))
+        .route("/admin/integrity", get(check_integrity))
+        .route("/admin/stats", get(get_stats))
+        .route("/admin/abuse", get(get_abuse_findings))
+        .route("/admin/abuse/release", post(release_submitter))
+        .route("/analytics/activations", get(get_activation_analytics))
+        .route("/admin/access-log", get(get_access_log))
+        .route("/admin/ops-log", get(get_ops_log))
+        .route("/replication/leaves", get(get_replication_leaves))
+        .route("/verify", post(verify_receipt))
+        .route("/verify-signatures", post(verify_signatures))
+        .route("/zk/verify", post(verify_zk_proof).layer(DefaultBodyLimit::max(zk::MAX_ZK_PROOF_BYTES)))
+        .route("/challenge", post(answer_challenge))
+        .route("/keys", get(get_keys))
+        .route("/identity", get(get_identity))
+        .route("/identicon/{key}", get(get_identicon))
+        .route("/revocations", get(get_revocations))
+        .route("/roots/feed", get(get_root_feed))
+        .route("/roots/notarizations", get(get_notarizations))
+        .route("/roots/rekor", get(get_rekor_entries))
+        .route("/sth/at", get(get_sth_at))
+        .route("/signing-log", get(get_signing_log))
+        .route("/model/register", post(register_model))
+        .route("/model/registry", get(get_model_registry))
+        .route("/model/registry/root", post(rotate_registry_root))
+        .route("/model/registry/targets", post(update_registry_targets))
+        .route("/models/{hash}/prompts", get(get_prompts).post(set_prompts))
+        .route("/schemas", get(list_schemas))
+        .route("/schemas/{name}", get(get_schema))
+        .route("/contracts/YuanjingMmrVerifier.sol", get(get_verifier_contract))
+}
+
+// ==========================================
+// 4. 处理函数 (Handlers)
+// ==========================================
+
+/// 接口：注册新的 AI 模型
+async fn register_model(
+    TenantScope(tenant): TenantScope,
+    ValidatedJson(req): ValidatedJson<ModelRegisterRequest>,
+) -> Result<Json<ModelRegisterResponse>, Problem> {
+    info!("🆕 [{}] 注册模型: {} ({})", tenant.id, req.hash, req.description);
+    let registry = tenant.reader.snapshot().model_registry().map_err(Problem::internal)?;
+    if registry.is_enabled() {
+        return Err(Problem::conflict("The model registry is in signed mode; publish signed targets metadata instead"));
+    }
+
+    // 白名单写入同样经由单写者线程，保证所有写操作有唯一顺序
+    tenant.writer.register_model(req.hash, req.description)
+        .await
+        .map_err(Problem::internal)?;
+
+    Ok(Json(ModelRegisterResponse {
+        status: "Registered".to_string(),
+    }))
+}
+
+/// 接口：签名的模型注册表 (公开)
+///
+/// 返回根元数据链与全部被接受过的目标元数据，验证方从自己事先信任的根出发逐级核对，
+/// 不必信任本服务。未启用签名模式时 `enabled` 为 false，两个列表为空。
+async fn get_model_registry(
+    State(state): State<Arc<AppState>>,
+    Query(query): Query<ModelRegistryQuery>,
+) -> Result<Json<ModelRegistryResponse>, Problem> {
+    let tenant_id = query.tenant.unwrap_or_else(|| DEFAULT_TENANT.to_string());
+    let tenant = state.tenants.get(&tenant_id)
+        .ok_or_else(|| Problem::not_found(format!("Unknown tenant: {}", tenant_id)))?;
+    let registry = tenant.reader.snapshot().model_registry().map_err(Problem::internal)?;
+    Ok(Json(ModelRegistryResponse {
+        tenant_id: tenant.id.clone(),
+        enabled: registry.is_enabled(),
+        roots: registry.roots().map_err(Problem::internal)?,
+        targets: registry.targets_history().map_err(Problem::internal)?,
+    }))
+}
+
+/// 接口：轮换模型注册表的根元数据 (租户)
+///
+/// 可信与否只看签名：须由当前根与新根的根角色各自达到门限签名，API Key 只决定提交到哪个租户。
+async fn rotate_registry_root(
+    TenantScope(tenant): TenantScope,
+    ValidatedJson(root): ValidatedJson<SignedRootMetadata>,
+) -> Result<Json<RegistryUpdateResponse>, Problem> {
+    let registry = tenant.reader.snapshot().model_registry().map_err(Problem::internal)?;
+    let current = registry.root()
+        .map_err(Problem::internal)?
+        .ok_or_else(|| Problem::conflict("The signed model registry is not enabled"))?;
+    model_registry::check_root_rotation(&current.signed, &root, chrono::Utc::now().timestamp())
+        .map_err(|e| Problem::bad_request(e.to_string()))?;
+
+    info!("🛡️  [{}] 模型注册表根元数据轮换: v{} → v{}", tenant.id, current.signed.version, root.signed.version);
+    let (version, expires) = (root.signed.version, root.signed.expires);
+    tenant.writer.rotate_registry_root(root).await.map_err(Problem::internal)?;
+    Ok(Json(RegistryUpdateResponse { role: "root".to_string(), version, expires }))
+}
+
+/// 接口：发布模型注册表的目标元数据 (租户)
+///
+/// 须由当前根的目标角色达到门限签名、版本号大于已接受的版本且未过期；接受后模型白名单整体替换为其中的列表。
+async fn update_registry_targets(
+    TenantScope(tenant): TenantScope,
+    ValidatedJson(targets): ValidatedJson<SignedTargetsMetadata>,
+) -> Result<Json<RegistryUpdateResponse>, Problem> {
+    let registry = tenant.reader.snapshot().model_registry().map_err(Problem::internal)?;
+    let root = registry.root()
+        .map_err(Problem::internal)?
+        .ok_or_else(|| Problem::conflict("The signed model registry is not enabled"))?;
+    let current = registry.targets().map_err(Problem::internal)?;
+    model_registry::check_targets(
+        &root.signed,
+        current.as_ref().map(|accepted| &accepted.targets.signed),
+        &targets,
+        chrono::Utc::now().timestamp(),
+    )
+    .map_err(|e| Problem::bad_request(e.to_string()))?;
+
+    info!("🛡️  [{}] 模型注册表目标元数据 v{}: {} 个模型", tenant.id, targets.signed.version, targets.signed.models.len());
+    let accepted = tenant.writer.update_registry_targets(targets).await.map_err(Problem::internal)?;
+    Ok(Json(RegistryUpdateResponse {
+        role: "targets".to_string(),
+        version: accepted.targets.signed.version,
+        expires: accepted.targets.signed.expires,
+    }))
+}
+
+/// 接口：模型版本的 Prompt 说明对照表 (租户)
+///
+/// 模型未注册返回 `404`；已注册但从未登记说明时返回空列表。
+async fn get_prompts(
+    TenantScope(tenant): TenantScope,
+    Path(hash): Path<String>,
+) -> Result<Json<PromptRegistryResponse>, Problem> {
+    let snapshot = tenant.reader.snapshot();
+    if !snapshot.is_model_registered(&hash) {
+        return Err(Problem::not_found(format!("Unknown model '{}'", hash)));
+    }
+    let set = snapshot.prompt_registry()
+        .and_then(|registry| registry.get(&hash))
+        .map_err(Problem::internal)?;
+    Ok(Json(set.map(PromptRegistryResponse::from).unwrap_or(PromptRegistryResponse {
+        prompt_pool_hash: hash,
+        prompts: Vec::new(),
+        updated_at: None,
+        principal: None,
+    })))
+}
+
+/// 接口：整体替换模型版本的 Prompt 说明对照表 (租户)
+///
+/// 说明不进入证据的签名内容，可随时补充或改正。模型未注册返回 `404`，索引重复或名称为空返回 `400`。
+async fn set_prompts(
+    TenantScope(tenant): TenantScope,
+    Principal(principal): Principal,
+    Path(hash): Path<String>,
+    ValidatedJson(req): ValidatedJson<PromptRegistryRequest>,
+) -> Result<Json<PromptRegistryResponse>, Problem> {
+    if let Some(prompt) = req.prompts.iter().find(|prompt| prompt.name.trim().is_empty()) {
+        return Err(Problem::bad_request(format!("Prompt {} has an empty name", prompt.index)));
+    }
+    info!("🧩 [{}] 登记 Prompt 说明: 模型={}, 条目={}, 主体={}", tenant.id, hash, req.prompts.len(), principal);
+    tenant.writer.set_prompts(hash, req.prompts, principal)
+        .await
+        .map(|set| Json(set.into()))
+        .map_err(|e| {
+            let message = e.to_string();
+            if message.contains("Unknown model") {
+                Problem::not_found(message)
+            } else if message.contains("Duplicate prompt index") {
+                Problem::bad_request(message)
+            } else {
+                Problem::internal(e)
+            }
+        })
+}
+
+/// 接口：提交证据并上链
+async fn submit_evidence(
+    State(state): State<Arc<AppState>>,
+    TenantScope(tenant): TenantScope,
+    Principal(principal): Principal,
+    SubmitterProvenance(provenance): SubmitterProvenance,
+    OutputEncoding(encoding): OutputEncoding,
+    ValidatedJson(req): ValidatedJson<ProveRequest>,
+) -> Result<(Extension<AccessedPos>, Json<ProveReceipt>), Problem> {
+    let request = telemetry::request("POST /prove", &tenant.id);
+    let result = prove_pipeline(state, tenant, principal, provenance, req, encoding, None)
+        .with_context(request.context().clone())
+        .await;
+    match result {
+        Ok(response) => {
+            request.set_attribute("yuanjing.leaf_pos", response.leaf_pos as i64);
+            Ok((Extension(AccessedPos(response.leaf_pos)), response))
+        }
+        Err(problem) => {
+            request.set_attribute("http.response.status_code", problem.status().as_u16() as i64);
+            request.fail(problem.detail());
+            Err(problem.with_trace_id(request.trace_id()))
+        }
+    }
+}
+
+/// 接口：提交后台存证任务，立即返回任务 ID (见 `jobs.rs`)
+async fn submit_evidence_async(
+    State(state): State<Arc<AppState>>,
+    TenantScope(tenant): TenantScope,
+    Principal(principal): Principal,
+    SubmitterProvenance(provenance): SubmitterProvenance,
+    OutputEncoding(encoding): OutputEncoding,
+    ValidatedJson(req): ValidatedJson<ProveRequest>,
+) -> Result<(StatusCode, [(HeaderName, String); 1], Json<AsyncProveResponse>), Problem> {
+    info!("📥 [{}] 收到后台存证请求: 图片={}", tenant.id, req.image_path);
+    let job = state.jobs.submit(tenant, principal, provenance, req, encoding).ok_or_else(|| {
+        Problem::new(ProblemType::RateLimited, "Job queue is full, retry later")
+    })?;
+    let status_url = format!("{}/jobs/{}", v1::PREFIX, job.job_id);
+    Ok((
+        StatusCode::ACCEPTED,
+        [(LOCATION, status_url.clone())],
+        Json(AsyncProveResponse { job_id: job.job_id, status: job.status, status_url }),
+    ))
+}
+
+/// 接口：查询后台存证任务的进度与回执
+async fn get_job(
+    State(state): State<Arc<AppState>>,
+    TenantScope(tenant): TenantScope,
+    Path(id): Path<String>,
+) -> Result<Json<JobResponse>, Problem> {
+    state
+        .jobs
+        .get(&tenant.id, &id)
+        .map(Json)
+        .ok_or_else(|| Problem::not_found(format!("No job '{}' (jobs are kept in memory and expire after completion)", id)))
+}
+
+/// 提交方声明时间可超前服务端时钟的秒数
+const MAX_CLAIMED_AT_SKEW_SECS: i64 = 300;
+
+/// 存证管线：策略校验 → 指纹 → 签名入库 → 回执，各阶段记为追踪 span (见 `telemetry.rs`)
+///
+/// `job` 为后台任务 ID (同步的 `/prove` 为 None)，管线据此推进任务阶段；
+/// `provenance` 为受理请求时采集的提交来源 (后台任务在入队时采集)。
+async fn prove_pipeline(
+    state: Arc<AppState>,
+    tenant: Arc<TenantContext>,
+    principal: String,
+    provenance: Option<Provenance>,
+    req: ProveRequest,
+    encoding: BinaryEncoding,
+    job: Option<&str>,
+) -> Result<Json<ProveReceipt>, Problem> {
+    info!("📥 [{}] 收到存证请求: 图片={}, 判定={}", tenant.id, req.image_path, req.verdict);
+
+    // 0. 时钟校验：时间戳不可信时按策略拒绝或标记
+    let policy = telemetry::stage("policy");
+    let clock_uncertain = !state.clock.is_healthy();
+    if clock_uncertain && state.clock.policy() == ClockPolicy::Refuse {
+        return Err(Problem::new(
+            ProblemType::ClockUntrusted,
+            format!("Server clock failed NTP sanity check (skew {} ms); refusing to sign", state.clock.last_skew_ms()),
+        ));
+    }
+
+    // 1. 校验 confidence 字段
+    if req.confidence.is_nan() || req.confidence.is_infinite() {
+        return Err(Problem::bad_request("Invalid confidence value: must be a finite number, got NaN or Inf"));
+    }
+    // 换算为万分比定点数 (见 evidence::Confidence)，不再以浮点数的字符串形式入库
+    let confidence = Confidence::from_ratio(req.confidence)
+        .map_err(|e| Problem::bad_request(format!("Invalid confidence value: {}", e)))?;
+
+    // 多模型共识：按规则合成判定，顶层判定须与之一致；置信度取合成结果
+    let (confidence, consensus) = match req.models.len() {
+        0 if req.consensus_rule.is_some() => {
+            return Err(Problem::bad_request("consensus_rule requires models"));
+        }
+        0 => (confidence, None),
+        1 => return Err(Problem::bad_request("Consensus needs at least two models; submit a single model without models")),
+        _ => {
+            let models = req
+                .models
+                .iter()
+                .map(|model| {
+                    let confidence = Confidence::from_ratio(model.confidence).map_err(|e| {
+                        Problem::bad_request(format!("Invalid confidence for model '{}': {}", model.prompt_pool_hash, e))
+                    })?;
+                    Ok(ModelVerdict { prompt_pool_hash: model.prompt_pool_hash.clone(), verdict: model.verdict, confidence })
+                })
+                .collect::<Result<Vec<_>, Problem>>()?;
+            let consensus = Consensus { rule: req.consensus_rule.unwrap_or(state.consensus_rule), models };
+            let (verdict, confidence) = consensus.decide().map_err(|e| Problem::bad_request(e.to_string()))?;
+            if verdict != req.verdict {
+                return Err(Problem::bad_request(format!(
+                    "verdict {} disagrees with the {} consensus of the models ({})",
+                    req.verdict,
+                    consensus.rule.as_str(),
+                    verdict
+                )));
+            }
+            (confidence, Some(consensus))
+        }
+    };
+
+    // 模型校准：温度与阈值换算为定点数
+    let calibration = req
+        .calibration
+        .map(|params| {
+            if params.version.trim().is_empty() {
+                return Err(Problem::bad_request("Calibration version must not be empty"));
+            }
+            Calibration::from_ratios(params.version, params.temperature, params.threshold)
+                .map_err(|e| Problem::bad_request(format!("Invalid calibration: {}", e)))
+        })
+        .transpose()?;
+    if let Some(context) = &req.client_context {
+        context.validate().map_err(|e| Problem::bad_request(format!("Invalid client_context: {}", e)))?;
+    }
+    if req.source.len() > search::MAX_SOURCE_BYTES {
+        return Err(Problem::bad_request(format!("source must be at most {} bytes", search::MAX_SOURCE_BYTES)));
+    }
+
+    // 提交方声明的时间只记录、不采信，但不能晚于服务端此刻 (容许少量时钟偏差)
+    if let Some(claimed_at) = req.claimed_at {
+        let latest = chrono::Utc::now().timestamp() + MAX_CLAIMED_AT_SKEW_SECS;
+        if !(0..=latest).contains(&claimed_at) {
+            return Err(Problem::bad_request(format!(
+                "Invalid claimed_at {}: must be a Unix time no later than the server clock (+{} s)",
+                claimed_at, MAX_CLAIMED_AT_SKEW_SECS
+            )));
+        }
+    }
+
+    // 派生密钥：按部门 / 设备选择签名密钥
+    let signing_key = match &req.department {
+        Some(name) => Some(tenant.derived_key(name).ok_or_else(|| {
+            Problem::bad_request(format!("Unknown department: {}", name))
+        })?),
+        None => None,
+    };
+
+    // 已吊销的密钥不再签发新的回执
+    let signing_public_key = signing_key.as_ref()
+        .map(|key| key.signer.public_key())
+        .unwrap_or_else(|| tenant.signer.public_key());
+    if state.revocations.list.is_revoked(&signing_public_key, chrono::Utc::now().timestamp()) {
+        return Err(Problem::new(ProblemType::KeyRevoked, "Signing key has been revoked"));
+    }
+
+    // 因可疑提交模式被限流的提交方直接拒绝 (见 abuse.rs)
+    if let Some(until) = state.abuse.throttled_until(&tenant.id, &principal, chrono::Utc::now().timestamp()) {
+        return Err(Problem::new(ProblemType::Throttled, format!("Submitter is throttled until {} for suspicious submission patterns", until)));
+    }
+    drop(policy);
+
+    // 2. 进入有界管线 (按优先级分通道)：满载时直接拒绝，避免排队任务耗尽内存 (后台任务已在任务队列中限流，排队等待)
+    let priority = req.priority;
+    let _ticket = match job {
+        Some(_) => state.ingest.admit(priority).await.map_err(Problem::internal)?,
+        None => state.ingest.try_admit(priority).ok_or_else(|| {
+            Problem::new(
+                ProblemType::RateLimited,
+                format!("Ingestion pipeline is saturated for {} priority, retry later", priority.as_str()),
+            )
+        })?,
+    };
+
+    // 3. 提取指纹 (CPU 密集型操作，在并发上限内放到阻塞线程池执行)
+    state.jobs.advance(job, JobStage::Fingerprinting);
+    let img_path_str = req.image_path.clone(); // Clone for closure
+    let image_limits = state.image_limits;
+    let (sha, phash) = telemetry::traced("fingerprint", state.ingest.run_blocking(priority, move || {
+        let _compute = telemetry::stage("fingerprint.compute");
+        let path = std::path::Path::new(&img_path_str);
+        if !path.exists() {
+            return Err(anyhow::anyhow!("图片不存在: {}", img_path_str));
+        }
+        fingerprint::generate_fingerprints_with(path, &image_limits)
+    }))
+    .await
+    .map_err(Problem::internal)?;
+
+    // 提交模式检查：规则为 `throttle` 时触发规则的这一次也不再入库
+    let findings = state.abuse.observe(&tenant.id, &principal, &sha, &confidence, chrono::Utc::now().timestamp());
+    for finding in findings.iter().filter(|finding| finding.occurrences == 1 || finding.throttled_until.is_some()) {
+        info!("🚩 [{}] 可疑提交: 主体={}, 规则={}, {}", tenant.id, principal, finding.rule.as_str(), finding.detail);
+    }
+    if let Some(finding) = findings.iter().find(|finding| finding.throttled_until.is_some()) {
+        let until = finding.throttled_until.unwrap_or_default();
+        if let Some(ops_log) = &state.ops_log {
+            ops_log.note(OpsEvent::Throttled {
+                tenant_id: tenant.id.clone(),
+                principal: principal.clone(),
+                rule: finding.rule.as_str().to_string(),
+                until,
+            });
+        }
+        return Err(Problem::new(ProblemType::Throttled, format!("Submitter is throttled until {}: {}", until, finding.detail)));
+    }
+
+    // 4. 构造 Evidence (模拟 AI 结合 Rust 提取的特征)
+    state.jobs.advance(job, JobStage::Inference);
+    let evidence = Evidence {
+        image_phash: phash,
+        image_sha256: sha,
+        verdict: req.verdict,
+        confidence,
+        activated_prompts: vec![1, 2, 99], // Mock
+        prompt_pool_hash: req.prompt_pool_hash,
+        external_knowledge_hash: "mock_wiki_hash_xyz789".to_string(),
+        timestamp: chrono::Utc::now().timestamp(),
+        claimed_at: req.claimed_at,
+        consensus,
+        calibration,
+        provenance,
+        client_context: req.client_context,
+    };
+
+    // 5. 签名 + 入库 (单一事务：签名只有在入库成功后才会返回)
+    state.jobs.advance(job, JobStage::Signing);
+    let request = AttestRequest {
+        evidence: evidence.clone(),
+        nonce: req.nonce,
+        supersedes: req.supersedes,
+        commitment: req.commitment,
+        clock_uncertain,
+        key: signing_key,
+        principal,
+        priority,
+        source: req.source,
+    };
+    let attested = telemetry::traced("attest", tenant.writer.attest(request))
+        .await
+        .map_err(|e| {
+            let message = e.to_string();
+            let kind = if message.contains("Unauthorized Model") {
+                ProblemType::UnregisteredModel
+            } else if message.contains("no attested evidence") {
+                ProblemType::NotFound
+            } else if message.contains("Replay detected") {
+                ProblemType::Replay
+            } else if message.contains("already superseded") {
+                ProblemType::Conflict
+            } else if message.contains("Writer queue full") || message.contains("Model registry") {
+                ProblemType::Unavailable
+            } else if message.contains("writer lease") {
+                ProblemType::NotWriter
+            } else {
+                ProblemType::Internal
+            };
+            Problem::new(kind, message)
+        })?;
+    let receipt = attested.receipt;
+    state.jobs.appended(job, receipt.leaf_pos);
+    // 来源说明在叶子公开后才写入，再排一次索引，免得后台刷新恰好漏掉
+    if let Some(search) = &state.search {
+        search.touch(&tenant.id, receipt.leaf_pos);
+    }
+    let evidence_id = evidence.evidence_id_as(receipt.encoding).map_err(Problem::internal)?;
+
+    info!("✅ 存证成功: Root={}, Pos={}, Seq={}", receipt.root, receipt.leaf_pos, receipt.seq);
+
+    // 6. 多数派确认 (可选)：足够多的副本落盘该叶子之前不返回回执；超时返回 503，回执不外发
+    let _receipt_stage = telemetry::stage("receipt");
+    let replication = if state.acks.quorum() > 0 {
+        if let Err(confirmed) = telemetry::traced("replication.quorum", state.acks.wait_for(&tenant.id, receipt.tree_size)).await {
+            warn!(
+                "⚠️  [{}] Pos={} 在 {} ms 内只得到 {}/{} 个副本确认，不返回回执",
+                tenant.id,
+                receipt.leaf_pos,
+                state.acks.timeout().as_millis(),
+                confirmed,
+                state.acks.quorum()
+            );
+            return Err(Problem::new(
+                ProblemType::NotReplicated,
+                format!(
+                    "Evidence {} at pos {} was confirmed by {} of {} required replicas within {} ms; the receipt is withheld",
+                    hex::encode(evidence_id),
+                    receipt.leaf_pos,
+                    confirmed,
+                    state.acks.quorum(),
+                    state.acks.timeout().as_millis()
+                ),
+            ));
+        }
+        Some(ReplicationAck { confirmed: state.acks.confirmed(&tenant.id, receipt.tree_size), required: state.acks.quorum() })
+    } else {
+        None
+    };
+
+    Ok(Json(ProveReceipt {
+        root_hash: encoding.from_hex(&receipt.root).map_err(Problem::internal)?,
+        leaf_pos: receipt.leaf_pos,
+        signature: encoding.encode(attested.evidence_signature.to_bytes()),
+        evidence_dump: evidence,
+        receipt,
+        receipt_signature: encoding.encode(attested.receipt_signature.to_bytes()),
+        certificate_chain: certificate_for(&state, &signing_public_key),
+        verification_method: state.did.find(&signing_public_key),
+        salt: attested.outcome.salt.map(|salt| encoding.encode(salt)),
+        binary_encoding: encoding,
+        evidence_id: hex::encode(evidence_id),
+        replication,
+    }))
+}
+
+/// 接口：获取审计证明
+async fn get_audit_proof(
+    PayloadScope { tenant, .. }: PayloadScope,
+    Path(pos): Path<u64>,
+    Query(query): Query<AuditQuery>,
+    OutputEncoding(encoding): OutputEncoding,
+    headers: HeaderMap,
+) -> Result<Response, Problem> {
+    let pos = resolve_position(pos, query.position)?;
+    info!("🔍 [{}] 收到审计请求: Pos={}", tenant.id, pos);
+
+    let snapshot = tenant.reader.snapshot();
+    let (tree_size, signed_tree_head) = resolve_tree_size(&snapshot, query.tree_size, query.root.as_deref())?;
+    if pos >= tree_size {
+        return Err(Problem::not_found(format!("No evidence at pos {} in tree size {}", pos, tree_size)));
+    }
+
+    // 获取 Proof
+    let proof = snapshot.get_proof_at(tree_size, vec![pos])
+        .map_err(|e| Problem::bad_request(format!("Cannot build proof for pos {}: {}", pos, e)))?;
+    let root = snapshot.root_at(tree_size)
+        .map_err(Problem::internal)?;
+
+    if wants_compact_proof(&headers) {
+        return Ok(compact_proof_response(CompactProof {
+            tree_size,
+            positions: vec![pos],
+            items: proof.proof_items().to_vec(),
+        }));
+    }
+
+    // 序列化 Proof 路径
+    let proof_hex: Vec<String> = proof
+        .proof_items()
+        .iter()
+        .map(|hash| encoding.encode(hash))
+        .collect();
+
+    Ok(Json(AuditResponse {
+        proof_valid: true,
+        leaf_pos: pos,
+        leaf_index: position::pos_to_leaf_index(pos),
+        proof_hex,
+        tree_size,
+        root: encoding.encode(root),
+        binary_encoding: encoding,
+        signed_tree_head,
+    })
+    .into_response())
+}
+
+/// 接口：链上校验用的证明
+///
+/// 与 `/audit/{pos}` 同一条证明，附带叶子哈希与 `contracts/YuanjingMmrVerifier.sol` 的 ABI 调用数据，
+/// 链上争议处理合约可直接据此校验证据在已锚定的根之下。
+async fn get_solidity_proof(
+    PayloadScope { tenant, .. }: PayloadScope,
+    Path(pos): Path<u64>,
+    Query(query): Query<AuditQuery>,
+) -> Result<Json<SolidityProofResponse>, Problem> {
+    let pos = resolve_position(pos, query.position)?;
+    let snapshot = tenant.reader.snapshot();
+    let (tree_size, _) = resolve_tree_size(&snapshot, query.tree_size, query.root.as_deref())?;
+    if pos >= tree_size {
+        return Err(Problem::not_found(format!("No evidence at pos {} in tree size {}", pos, tree_size)));
+    }
+    let proof = snapshot.get_proof_at(tree_size, vec![pos])
+        .map_err(|e| Problem::bad_request(format!("Cannot build proof for pos {}: {}", pos, e)))?;
+    let leaf = snapshot.get_leaf(pos)
+        .map_err(Problem::internal)?
+        .ok_or_else(|| Problem::not_found(format!("No evidence at pos {}", pos)))?;
+    let root = snapshot.root_at(tree_size)
+        .map_err(Problem::internal)?;
+
+    let proof = SolidityProof { mmr_size: tree_size, pos, leaf, items: proof.proof_items().to_vec(), root };
+    if !proof.verify() {
+        return Err(Problem::internal(format!("Proof for pos {} does not verify against tree size {}", pos, tree_size)));
+    }
+    Ok(Json(SolidityProofResponse {
+        mmr_size: proof.mmr_size,
+        leaf_pos: proof.pos,
+        leaf_index: position::pos_to_leaf_index(proof.pos),
+        leaf: solidity::to_0x(proof.leaf),
+        proof: proof.items.iter().map(solidity::to_0x).collect(),
+        root: solidity::to_0x(proof.root),
+        inclusion_calldata: solidity::to_0x(proof.inclusion_calldata()),
+        anchored_calldata: solidity::to_0x(proof.anchored_calldata()),
+        anchor_calldata: solidity::to_0x(proof.anchor_calldata()),
+    }))
+}
+
+/// 接口：零知识披露证明 (实验性)
+///
+/// 证明“根为 R 的树中存在 `image_sha256` = X、`verdict` 为某值的叶子”，不透露叶子位置与证据的其余字段，
+/// 供向第三方披露判定结果 (见 `zk.rs`)。生成需要数秒 CPU，证明有数 MB，只支持 BCS 规范编码的租户。
+async fn get_zk_proof(
+    TenantScope(tenant): TenantScope,
+    Path(pos): Path<u64>,
+    Query(query): Query<AuditQuery>,
+) -> Result<Response, Problem> {
+    if tenant.reader.canonical_encoding() != CanonicalEncoding::Bcs {
+        return Err(Problem::bad_request("Zero-knowledge disclosure is only available for BCS tenants"));
+    }
+    let pos = resolve_position(pos, query.position)?;
+    let snapshot = tenant.reader.snapshot();
+    let (tree_size, _) = resolve_tree_size(&snapshot, query.tree_size, query.root.as_deref())?;
+    if pos >= tree_size {
+        return Err(Problem::bad_request(format!("Position {} is not within tree size {}", pos, tree_size)));
+    }
+    if snapshot.get_evidence(pos).map_err(Problem::internal)?.is_none() {
+        return Err(Problem::not_found(format!("No evidence at pos {} (erased, pruned or not an evidence leaf)", pos)));
+    }
+    let bytes = snapshot.evidence_bytes(pos)
+        .map_err(Problem::internal)?
+        .ok_or_else(|| Problem::not_found(format!("No evidence at pos {}", pos)))?;
+    let salt = snapshot.get_salt(pos).map_err(Problem::internal)?;
+    info!("🕶️  [{}] 生成零知识披露证明: Pos={}, 树大小={}", tenant.id, pos, tree_size);
+
+    let proof = tokio::task::spawn_blocking(move || {
+        let (statement, witness) = zk::prepare(tree_size, pos, salt, &bytes, |node| {
+            snapshot.get_leaf(node)?.ok_or_else(|| anyhow::anyhow!("Missing MMR node {}", node))
+        })?;
+        if statement.root != hex::encode(snapshot.root_at(tree_size)?) {
+            return Err(anyhow::anyhow!("Peaks of tree size {} do not match its root", tree_size));
+        }
+        ZkProof::prove(&statement, &witness, rand::random())?.encode()
+    })
+        .await
+        .map_err(Problem::internal)?
+        .map_err(|e| Problem::bad_request(format!("Zero-knowledge proof failed: {}", e)))?;
+
+    let disposition = HeaderValue::from_str(&format!("attachment; filename=\"evidence-{}.zkproof\"", pos))
+        .map_err(Problem::internal)?;
+    Ok(([(CONTENT_TYPE, HeaderValue::from_static(ZK_PROOF_MEDIA_TYPE)), (CONTENT_DISPOSITION, disposition)], proof).into_response())
+}
+
+/// 接口：校验零知识披露证明
+///
+/// 公开接口，请求体为 `/audit/{pos}/zk` 下载的二进制证明。除证明本身外，
+/// 还核对陈述中的根是否确为该租户日志在 `tree_size` 时的根 (`root_matches`)。
+async fn verify_zk_proof(
+    State(state): State<Arc<AppState>>,
+    Query(query): Query<ZkVerifyQuery>,
+    body: Bytes,
+) -> Result<Json<ZkVerifyResponse>, Problem> {
+    let tenant_id = query.tenant.unwrap_or_else(|| DEFAULT_TENANT.to_string());
+    let tenant = state.tenants.get(&tenant_id)
+        .ok_or_else(|| Problem::not_found(format!("Unknown tenant: {}", tenant_id)))?;
+    let proof = ZkProof::decode(&body)
+        .map_err(|e| Problem::bad_request(format!("Invalid zero-knowledge proof: {}", e)))?;
+    info!("🕶️  收到零知识披露证明校验请求: 树大小={}", proof.statement.tree_size);
+
+    let statement = proof.statement.clone();
+    let valid = tokio::task::spawn_blocking(move || proof.verify())
+        .await
+        .map_err(Problem::internal)?
+        .map_err(|e| Problem::bad_request(format!("Invalid zero-knowledge statement: {}", e)))?;
+    let snapshot = tenant.reader.snapshot();
+    let root_matches = statement.tree_size <= snapshot.mmr_size()
+        && snapshot.root_at(statement.tree_size).is_ok_and(|root| hex::encode(root) == statement.root);
+    Ok(Json(ZkVerifyResponse { valid, tenant_id: tenant.id.clone(), root_matches, statement }))
+}
+
+/// 接口：链上校验合约源码
+///
+/// 公开接口，原样返回 `contracts/YuanjingMmrVerifier.sol`，与本服务的证明格式保持同一版本。
+async fn get_verifier_contract() -> Response {
+    ([(CONTENT_TYPE, HeaderValue::from_static("text/plain; charset=utf-8"))], solidity::MMR_VERIFIER_SOURCE).into_response()
+}
+
+/// 把审计接口收到的位置换算为 MMR 位置 (`?position=leaf_index` 时按叶子序号解释)
+fn resolve_position(value: u64, kind: PositionKind) -> Result<u64, Problem> {
+    match kind {
+        PositionKind::MmrPos => Ok(value),
+        PositionKind::LeafIndex if value < MAX_LEAF_INDEX => Ok(position::leaf_index_to_pos(value)),
+        PositionKind::LeafIndex => Err(Problem::bad_request(format!("Leaf index {} is out of range", value))),
+    }
+}
+
+/// 确定审计接口开具证明所用的树：`root` 按根历史 (当前根与根发布存档) 查找，否则取 `tree_size`，缺省为当前树
+///
+/// 同时返回存档中该树大小的签名树头 (有的话)，旧回执可对照签出时的根逐字复核。
+fn resolve_tree_size(snapshot: &StoreSnapshot, tree_size: Option<u64>, root: Option<&str>) -> Result<(u64, Option<publication::SignedTreeHead>), Problem> {
+    let archive = snapshot.tree_heads().map_err(Problem::internal)?;
+    let Some(root) = root else {
+        let tree_size = tree_size.unwrap_or(snapshot.mmr_size());
+        if tree_size == 0 || tree_size > snapshot.mmr_size() {
+            return Err(Problem::bad_request(format!("Invalid tree size {} (current size {})", tree_size, snapshot.mmr_size())));
+        }
+        if !position::is_valid_mmr_size(tree_size) {
+            return Err(Problem::bad_request(format!("Invalid tree size {}: not the size of any tree in this log", tree_size)));
+        }
+        return Ok((tree_size, archive.get(tree_size).map_err(Problem::internal)?));
+    };
+    let root = hex::encode(decode_hash(BinaryEncoding::Hex, root)?);
+    let head = archive.find_root(&root).map_err(Problem::internal)?;
+    let found = match &head {
+        Some(head) => Some(head.tree_head.tree_size),
+        None => {
+            let current = snapshot.mmr_size();
+            (current > 0 && hex::encode(snapshot.root_at(current).map_err(Problem::internal)?) == root).then_some(current)
+        }
+    };
+    match (found, tree_size) {
+        (None, _) => Err(Problem::not_found(format!("Root {} is not in the root history", root))),
+        (Some(found), Some(tree_size)) if found != tree_size => Err(Problem::bad_request(format!(
+            "Root {} belongs to tree size {}, not {}",
+            root, found, tree_size
+        ))),
+        (Some(found), _) => Ok((found, head)),
+    }
+}
+
+/// 可换算为位置的最大叶子序号 (再大换算时溢出)
+const MAX_LEAF_INDEX: u64 = 1 << 62;
+
+/// 单次批量证明的叶子数上限
+const MAX_BATCH_AUDIT: usize = 1000;
+
+/// 接口：批量审计证明
+///
+/// 审计方抽查大量条目时，一条合并的证明路径比逐条调用 `/audit/{pos}` 省得多。
+async fn get_batch_audit_proof(
+    PayloadScope { tenant, .. }: PayloadScope,
+    OutputEncoding(encoding): OutputEncoding,
+    headers: HeaderMap,
+    ValidatedJson(req): ValidatedJson<BatchAuditRequest>,
+) -> Result<Response, Problem> {
+    let mut positions = req.positions;
+    for index in req.leaf_indexes {
+        positions.push(resolve_position(index, PositionKind::LeafIndex)?);
+    }
+    if positions.len() > MAX_BATCH_AUDIT {
+        return Err(Problem::bad_request(format!(
+            "Too many positions: {} (at most {})",
+            positions.len(),
+            MAX_BATCH_AUDIT
+        )));
+    }
+    info!("🔍 [{}] 收到批量审计请求: {} 个位置", tenant.id, positions.len());
+
+    let snapshot = tenant.reader.snapshot();
+    let (tree_size, signed_tree_head) = resolve_tree_size(&snapshot, req.tree_size, req.root.as_deref())?;
+    let batch = snapshot.get_batch_proof(tree_size, &positions)
+        .map_err(|e| Problem::bad_request(format!("Cannot build proof: {}", e)))?;
+    let root = snapshot.root_at(tree_size)
+        .map_err(Problem::internal)?;
+
+    if wants_compact_proof(&headers) {
+        return Ok(compact_proof_response(CompactProof {
+            tree_size,
+            positions: batch.leaves.iter().map(|(pos, _)| *pos).collect(),
+            items: batch.proof.proof_items().to_vec(),
+        }));
+    }
+
+    Ok(Json(BatchAuditResponse {
+        leaves: batch.leaves
+            .into_iter()
+            .map(|(pos, leaf)| AuditLeaf { pos, leaf_index: position::pos_to_leaf_index(pos), leaf_hash: encoding.encode(leaf) })
+            .collect(),
+        proof_hex: batch.proof.proof_items().iter().map(|hash| encoding.encode(hash)).collect(),
+        tree_size,
+        root: encoding.encode(root),
+        binary_encoding: encoding,
+        signed_tree_head,
+    })
+    .into_response())
+}
+
+/// `Accept` 中是否列出了紧凑证明的媒体类型 (忽略参数与顺序)
+fn wants_compact_proof(headers: &HeaderMap) -> bool {
+    headers
+        .get_all(ACCEPT)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(','))
+        .any(|media| media.split(';').next().is_some_and(|media| media.trim().eq_ignore_ascii_case(COMPACT_PROOF_MEDIA_TYPE)))
+}
+
+/// 紧凑证明响应：只含树大小、位置与证明路径；根与叶子哈希须由验证方从回执中取
+fn compact_proof_response(proof: CompactProof) -> Response {
+    ([(CONTENT_TYPE, HeaderValue::from_static(COMPACT_PROOF_MEDIA_TYPE))], proof.encode()).into_response()
+}
+
+/// 接口：验证回执绑定
+///
+/// 同时校验四件事：证据哈希一致、回执签名有效、叶子在回执声明的那棵树中、签名密钥在回执时间点未被吊销。
+/// 公开接口，无需 API Key：按回执中的 `tenant_id` 找到对应租户的公钥与 MMR。
+async fn verify_receipt(
+    State(state): State<Arc<AppState>>,
+    ValidatedJson(req): ValidatedJson<VerifyRequest>,
+) -> Result<Json<VerifyResponse>, Problem> {
+    info!("🧾 收到回执验证请求: Tenant={}, Pos={}", req.receipt.tenant_id, req.receipt.leaf_pos);
+
+    let tenant = state.tenants.get(&req.receipt.tenant_id)
+        .ok_or_else(|| Problem::not_found(format!("Unknown tenant: {}", req.receipt.tenant_id)))?;
+
+    let salt = req.salt.as_deref().map(|salt| decode_hash(req.binary_encoding, salt)).transpose()?;
+    let leaf_hash = req.evidence.salted_leaf_hash_as(req.receipt.encoding, salt.as_ref())
+        .map_err(|e| Problem::bad_request(e.to_string()))?;
+    let evidence_hash_match = hex::encode(leaf_hash) == req.receipt.evidence_hash;
+
+    // 回执由派生密钥签发时，按 key_path 找到对应的子公钥
+    let verifying_key = tenant.verifying_key(&req.receipt.key_path)
+        .ok_or_else(|| Problem::not_found(format!("Unknown signing key: {}", req.receipt.key_path)))?;
+    let receipt_signature = decode_signature(req.binary_encoding, &req.receipt_signature)?;
+    let receipt_signature_valid = EvidenceSigner::verify_receipt(&verifying_key, &req.receipt, &receipt_signature)
+        .map_err(|e| Problem::bad_request(e.to_string()))?;
+
+    let root = decode_hash(BinaryEncoding::Hex, &req.receipt.root)?;
+    let snapshot = tenant.reader.snapshot();
+    let inclusion_valid = snapshot
+        .verify_inclusion(req.receipt.leaf_pos, leaf_hash, req.receipt.tree_size, root)
+        .map_err(Problem::internal)?;
+
+    // 签名的模型注册表：从引导时的受信根逐级核对签名链，回执签发时生效的目标元数据须列出证据用到的每个模型
+    let registry = snapshot.model_registry().map_err(Problem::internal)?;
+    let roots = registry.roots().map_err(Problem::internal)?;
+    let targets = registry.targets_history().map_err(Problem::internal)?;
+    let model_registry_valid = match (roots.first(), targets.first()) {
+        (Some(trusted), Some(first)) if first.accepted_at <= req.receipt.timestamp => {
+            let models = std::iter::once(&req.evidence.prompt_pool_hash)
+                .chain(req.evidence.consensus.iter().flat_map(|consensus| &consensus.models).map(|model| &model.prompt_pool_hash));
+            Some(models.into_iter().all(|model| {
+                model_registry::verify_model_at(trusted, &roots, &targets, model, req.receipt.timestamp).is_ok()
+            }))
+        }
+        _ => None,
+    };
+
+    // 吊销生效之后签发的回执不再可信，之前的照常有效
+    let key_revoked = state.revocations.list.is_revoked(&verifying_key, req.receipt.timestamp);
+
+    // 证书链：在回执签发时刻可追溯到受信根，且证书公钥正是回执的签名密钥
+    let chain = match &req.certificate_chain {
+        Some(certs) => Some(CertificateChain::from_hex(certs).map_err(|e| Problem::bad_request(e.to_string()))?),
+        None => state.certificate.as_ref()
+            .filter(|cert| cert.public_key == verifying_key)
+            .map(|cert| cert.chain.clone()),
+    };
+    let certificate_valid = match chain {
+        Some(chain) if !state.trust.is_empty() => {
+            let payload = req.receipt.signing_bytes().map_err(|e| Problem::bad_request(e.to_string()))?;
+            Some(chain.verify(&state.trust, req.receipt.timestamp, &payload, &receipt_signature).is_ok())
+        }
+        _ => None,
+    };
+
+    Ok(Json(VerifyResponse {
+        valid: evidence_hash_match
+            && receipt_signature_valid
+            && inclusion_valid
+            && !key_revoked
+            && certificate_valid.unwrap_or(true)
+            && model_registry_valid.unwrap_or(true),
+        evidence_hash_match,
+        receipt_signature_valid,
+        inclusion_valid,
+        key_revoked,
+        certificate_valid,
+        model_registry_valid,
+    }))
+}
+
+/// 单次批量验证的签名数上限
+const MAX_BATCH_VERIFY: usize = 10_000;
+
+/// 接口：批量验证证据签名
+///
+/// 公开接口，只校验签名本身 (不查叶子、不判吊销)。审计方一次复核成千上万张回执时，
+/// Ed25519 批量验证比逐条调用 `/verify` 快得多 (见 `EvidenceSigner::verify_batch`)。
+async fn verify_signatures(
+    ValidatedJson(req): ValidatedJson<VerifySignaturesRequest>,
+) -> Result<Json<VerifySignaturesResponse>, Problem> {
+    if req.items.len() > MAX_BATCH_VERIFY {
+        return Err(Problem::bad_request(format!(
+            "Too many signatures: {} (at most {})",
+            req.items.len(),
+            MAX_BATCH_VERIFY
+        )));
+    }
+    info!("🧾 收到批量签名验证请求: {} 条", req.items.len());
+
+    let encoding = req.binary_encoding;
+    let items = req
+        .items
+        .iter()
+        .enumerate()
+        .map(|(index, item)| {
+            let payload = item.evidence.canonical_bytes_as(item.encoding)
+                .map_err(|e| Problem::bad_request(format!("items[{}]: {}", index, e)))?;
+            let signature = decode_signature(encoding, &item.signature)?;
+            let key = encoding.decode(&item.key_id)
+                .ok()
+                .and_then(|v| <[u8; 32]>::try_from(v.as_slice()).ok())
+                .and_then(|bytes| ed25519_dalek::VerifyingKey::from_bytes(&bytes).ok())
+                .ok_or_else(|| Problem::bad_request(format!("items[{}]: invalid {} public key: {}", index, encoding, item.key_id)))?;
+            Ok((payload, signature, key))
+        })
+        .collect::<Result<Vec<_>, Problem>>()?;
+
+    let valid = tokio::task::spawn_blocking(move || EvidenceSigner::verify_batch(&items))
+        .await
+        .map_err(Problem::internal)?;
+    let invalid: Vec<usize> = valid.iter().enumerate().filter(|(_, ok)| !**ok).map(|(index, _)| index).collect();
+    Ok(Json(VerifySignaturesResponse { all_valid: invalid.is_empty(), valid, invalid }))
+}
+
+/// 接口：公钥目录
+///
+/// 公开接口，列出每个租户的根公钥与派生公钥。Ed25519 派生只能硬化，
+/// 验证方无法从根公钥推算子公钥，因此每把子公钥都附带根密钥签发的背书，
+/// 离线验证方只需信任根公钥即可逐一校验。
+async fn get_keys(State(state): State<Arc<AppState>>, OutputEncoding(encoding): OutputEncoding) -> Json<KeysResponse> {
+    let mut tenants: Vec<_> = state.tenants.all().collect();
+    tenants.sort_by(|a, b| a.id.cmp(&b.id));
+
+    let tenants = tenants
+        .into_iter()
+        .map(|tenant| {
+            let mut derived_keys: Vec<_> = tenant
+                .derived_keys
+                .values()
+                .map(|key| DerivedKeyInfo {
+                    name: key.name.clone(),
+                    endorsement: key.endorsement.clone(),
+                    endorsement_signature: encoding.encode(key.endorsement_signature.to_bytes()),
+                })
+                .collect();
+            derived_keys.sort_by(|a, b| a.endorsement.key_path.cmp(&b.endorsement.key_path));
+            TenantKeys {
+                tenant_id: tenant.id.clone(),
+                public_key: encoding.encode(tenant.signer.public_key().to_bytes()),
+                fingerprint: key_fingerprint::key_fingerprint(&tenant.signer.public_key()),
+                derived_keys,
+            }
+        })
+        .collect();
+    Json(KeysResponse { tenants, binary_encoding: encoding })
+}
+
+/// 接口：服务身份
+///
+/// 公开接口，给出主签名身份的公钥、DID 与证书链，以及回执可能采用的规范编码；
+/// 接入方据此固定 (pin) 公钥，不必再从启动日志里抄。各租户的子公钥见 `/keys`。
+async fn get_identity(State(state): State<Arc<AppState>>, OutputEncoding(encoding): OutputEncoding) -> Json<IdentityResponse> {
+    let identity = &state.identity;
+    Json(IdentityResponse {
+        public_key: encoding.encode(identity.public_key.to_bytes()),
+        fingerprint: key_fingerprint::key_fingerprint(&identity.public_key),
+        identicon: format!("{}/identicon/{}", v1::PREFIX, hex::encode(identity.public_key.to_bytes())),
+        key_id: state.did.method_id(&identity.public_key),
+        key_source: identity.key_source.as_str().to_string(),
+        key_created_at: identity.key_created_at,
+        did: state.did.id.clone(),
+        did_document: "/.well-known/did.json".to_string(),
+        certificate_chain: certificate_for(&state, &identity.public_key),
+        canonical_encodings: CanonicalEncoding::ALL.to_vec(),
+        default_canonical_encoding: identity.canonical_encoding,
+        binary_encoding: encoding,
+        enclave: identity.enclave.clone(),
+    })
+}
+
+/// 接口：公钥识别图 (SVG)
+///
+/// 公开接口。`{key}` 为 Hex 或 multibase (`z...`) 编码的 Ed25519 公钥，不要求是本服务的公钥，
+/// 验证方可对回执或证书里的任何公钥生成识别图，与报告上印的图案对照。
+async fn get_identicon(Path(key): Path<String>) -> Result<Response, Problem> {
+    let bytes = if key.len() == 64 {
+        BinaryEncoding::Hex.decode(&key)
+    } else {
+        crate::encoding::decode_multibase(&key)
+    };
+    let public_key = bytes
+        .ok()
+        .and_then(|v| <[u8; 32]>::try_from(v.as_slice()).ok())
+        .and_then(|bytes| ed25519_dalek::VerifyingKey::from_bytes(&bytes).ok())
+        .ok_or_else(|| Problem::bad_request(format!("Invalid public key (expected hex or multibase): {}", key)))?;
+    let svg = key_fingerprint::identicon_svg(&public_key);
+    Ok(([(CONTENT_TYPE, HeaderValue::from_static("image/svg+xml"))], svg).into_response())
+}
+
+/// 接口：密钥吊销列表
+///
+/// 公开接口，原样返回主身份签名的列表，验证方可离线校验签名后缓存使用。
+async fn get_revocations(State(state): State<Arc<AppState>>) -> Json<SignedRevocationList> {
+    Json(state.revocations.clone())
+}
+
+/// 接口：DID 文档
+///
+/// 公开接口。配置 `did_web` 后，本路径即 did:web 的解析地址
+/// (`https://<域名>/.well-known/did.json`)。
+async fn get_did_document(State(state): State<Arc<AppState>>) -> Json<DidDocument> {
+    Json(state.did.clone())
+}
+
+/// 接口：最新签名树头
+///
+/// 公开接口，返回根发布任务最近一次签名的各租户树头 (见 `publication.rs`)，
+/// 供第三方定期抓取并固定；与回执中的根对照即可发现日志分叉。
+async fn get_published_roots(State(state): State<Arc<AppState>>) -> Json<PublishedRootsResponse> {
+    Json(PublishedRootsResponse { tree_heads: state.roots.latest() })
+}
+
+/// 接口：树头订阅源 (Atom / RSS)
+///
+/// 公开接口，输出根发布存档的历史树头 (新的在前)，供监控方用现成的订阅工具抓取与归档。
+async fn get_root_feed(
+    State(state): State<Arc<AppState>>,
+    Query(query): Query<RootFeedQuery>,
+) -> Result<Response, Problem> {
+    let tenant_id = query.tenant.unwrap_or_else(|| DEFAULT_TENANT.to_string());
+    let tenant = state.tenants.get(&tenant_id)
+        .ok_or_else(|| Problem::not_found(format!("Unknown tenant: {}", tenant_id)))?;
+    let limit = query.limit.unwrap_or(50).min(500);
+    let heads = tenant.reader.snapshot().tree_heads()
+        .and_then(|archive| archive.recent(limit))
+        .map_err(Problem::internal)?;
+
+    let self_link = format!("/roots/feed?tenant={}&format={}", tenant.id, query.format.as_str());
+    let feed = publication::render_feed(query.format, &tenant.id, &self_link, &heads)
+        .map_err(Problem::internal)?;
+    Ok(([(CONTENT_TYPE, HeaderValue::from_static(query.format.content_type()))], feed).into_response())
+}
+
+/// 接口：按时间查找签名树头
+///
+/// 公开接口，返回 `ts` 时刻仍为最新的存档树头：日志在那一刻对外声称存在的全部内容就是这棵树。
+/// 审计方回答“截至某日日志声称存在哪些证据”时，用其中的 `tree_size` 调 `/audit/{pos}?tree_size=` 即可对照这个根校验。
+async fn get_sth_at(
+    State(state): State<Arc<AppState>>,
+    Query(query): Query<SthAtQuery>,
+) -> Result<Json<SthAtResponse>, Problem> {
+    let tenant_id = query.tenant.unwrap_or_else(|| DEFAULT_TENANT.to_string());
+    let tenant = state.tenants.get(&tenant_id)
+        .ok_or_else(|| Problem::not_found(format!("Unknown tenant: {}", tenant_id)))?;
+    let (signed_tree_head, superseded_at) = tenant.reader.snapshot().tree_heads()
+        .and_then(|archive| archive.at(query.ts))
+        .map_err(Problem::internal)?
+        .ok_or_else(|| Problem::not_found(format!("No tree head was signed at or before {}", query.ts)))?;
+    Ok(Json(SthAtResponse { tenant_id: tenant.id.clone(), ts: query.ts, signed_tree_head, superseded_at }))
+}
+
+/// 接口：公证回执
+///
+/// 公开接口，列出 TSA 对该租户树头签发的时间戳与外部公证方的副署 (见 `notary.rs`)。
+/// 验证方固定 TSA 受信根或公证方公钥后用 `TimestampReceipt::verify` / `NotaryReceipt::verify` 校验，
+/// 即可证明对应的根在签发时间之前已经存在。
+async fn get_notarizations(
+    State(state): State<Arc<AppState>>,
+    Query(query): Query<NotarizationsQuery>,
+) -> Result<Json<NotarizationsResponse>, Problem> {
+    let tenant_id = query.tenant.unwrap_or_else(|| DEFAULT_TENANT.to_string());
+    let tenant = state.tenants.get(&tenant_id)
+        .ok_or_else(|| Problem::not_found(format!("Unknown tenant: {}", tenant_id)))?;
+    let limit = query.limit.unwrap_or(50).min(500);
+    let snapshot = tenant.reader.snapshot();
+    let receipts = snapshot.notary_receipts()
+        .and_then(|archive| archive.recent(limit))
+        .map_err(Problem::internal)?;
+    let timestamps = snapshot.timestamp_tokens()
+        .and_then(|archive| archive.recent(limit))
+        .map_err(Problem::internal)?;
+    Ok(Json(NotarizationsResponse { tenant_id: tenant.id.clone(), receipts, timestamps }))
+}
+
+/// 接口：Rekor 收录记录
+///
+/// 公开接口，列出该租户树头在 Rekor 透明日志中的条目 (见 `rekor.rs`)。
+/// 验证方凭 `log_index` 在 Rekor 上查到同一条目，即可确认对应的根在 `integrated_time` 之前已经公开。
+async fn get_rekor_entries(
+    State(state): State<Arc<AppState>>,
+    Query(query): Query<RekorEntriesQuery>,
+) -> Result<Json<RekorEntriesResponse>, Problem> {
+    let tenant_id = query.tenant.unwrap_or_else(|| DEFAULT_TENANT.to_string());
+    let tenant = state.tenants.get(&tenant_id)
+        .ok_or_else(|| Problem::not_found(format!("Unknown tenant: {}", tenant_id)))?;
+    let limit = query.limit.unwrap_or(50).min(500);
+    let entries = tenant.reader.snapshot().rekor_entries()
+        .and_then(|archive| archive.recent(limit))
+        .map_err(Problem::internal)?;
+    Ok(Json(RekorEntriesResponse { tenant_id: tenant.id.clone(), entries }))
+}
+
+/// 接口：读取已入库的证据及其回执
+async fn get_evidence_record(
+    State(state): State<Arc<AppState>>,
+    scope: PayloadScope,
+    Path(pos): Path<u64>,
+    OutputEncoding(encoding): OutputEncoding,
+) -> Result<Json<EvidenceRecordResponse>, Problem> {
+    let snapshot = scope.tenant.reader.snapshot();
+    scope.authorize(&snapshot, pos)?;
+    evidence_record(&state, &scope.tenant, &snapshot, pos, encoding).map(Json)
+}
+
+/// 接口：按内容寻址的证据 ID 读取证据及其回执
+///
+/// ID 为 Blake3(证据规范字节) 的 Hex，见 `/prove` 响应的 `evidence_id`；已擦除的证据按 ID 查不到。
+async fn get_evidence_by_id(
+    State(state): State<Arc<AppState>>,
+    scope: PayloadScope,
+    Path(id): Path<String>,
+    OutputEncoding(encoding): OutputEncoding,
+) -> Result<(Extension<AccessedPos>, Json<EvidenceRecordResponse>), Problem> {
+    let evidence_id = decode_hash(BinaryEncoding::Hex, &id)?;
+    let snapshot = scope.tenant.reader.snapshot();
+    let pos = snapshot.pos_of_evidence_id(&evidence_id)
+        .map_err(Problem::internal)?
+        .ok_or_else(|| Problem::not_found(format!("No evidence with id {}", id)))?;
+    scope.authorize(&snapshot, pos)?;
+    let record = evidence_record(&state, &scope.tenant, &snapshot, pos, encoding)?;
+    Ok((Extension(AccessedPos(pos)), Json(record)))
+}
+
+/// 接口：读取证据所在的更正链
+///
+/// 从任意一环都能取到整条链；每一环都带回执，可分别用 `/audit/{pos}` 取包含性证明。
+async fn get_supersession_chain(
+    State(state): State<Arc<AppState>>,
+    scope: PayloadScope,
+    Path(pos): Path<u64>,
+    OutputEncoding(encoding): OutputEncoding,
+) -> Result<Json<SupersessionChainResponse>, Problem> {
+    let snapshot = scope.tenant.reader.snapshot();
+    let entries = snapshot.supersession_chain(pos)
+        .map_err(Problem::internal)?
+        .into_iter()
+        .map(|p| {
+            scope.authorize(&snapshot, p)?;
+            evidence_record(&state, &scope.tenant, &snapshot, p, encoding)
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    let latest_pos = entries.last().map_or(pos, |entry| entry.leaf_pos);
+    Ok(Json(SupersessionChainResponse { latest_pos, entries }))
+}
+
+/// 组装单条证据记录 (`/evidence/{pos}` 与更正链共用)
+fn evidence_record(
+    state: &AppState,
+    tenant: &TenantContext,
+    snapshot: &StoreSnapshot,
+    pos: u64,
+    encoding: BinaryEncoding,
+) -> Result<EvidenceRecordResponse, Problem> {
+    if pos >= snapshot.mmr_size() {
+        return Err(Problem::not_found(format!("No evidence at pos {}", pos)));
+    }
+
+    let evidence = snapshot.get_evidence(pos)
+        .map_err(Problem::internal)?;
+    let erasure = match evidence {
+        Some(_) => None,
+        None => snapshot.erasure(pos).map_err(Problem::internal)?,
+    };
+    let pruned_at = match (&evidence, &erasure) {
+        (None, None) => snapshot.pruned_at(pos).map_err(Problem::internal)?,
+        _ => None,
+    };
+    let blinded = match (&evidence, &erasure, pruned_at) {
+        (None, None, None) => snapshot.is_blinded(pos).map_err(Problem::internal)?,
+        _ => false,
+    };
+    let status = match (&evidence, &erasure, pruned_at) {
+        (Some(_), _, _) => EvidenceStatus::Available,
+        (None, Some(_), _) => EvidenceStatus::Erased,
+        (None, None, Some(_)) => EvidenceStatus::Pruned,
+        (None, None, None) if blinded => EvidenceStatus::Blinded,
+        (None, None, None) => return Err(Problem::not_found(format!("No evidence at pos {}", pos))),
+    };
+    let salt = snapshot.get_salt(pos)
+        .map_err(Problem::internal)?;
+    let evidence_id = snapshot.evidence_id(pos)
+        .map_err(Problem::internal)?;
+    let legal_hold = snapshot.legal_holds()
+        .and_then(|holds| holds.get(pos))
+        .map_err(Problem::internal)?;
+    let anomaly = snapshot.anomalies()
+        .and_then(|anomalies| anomalies.get(pos))
+        .map_err(Problem::internal)?;
+    let leaf_hash = snapshot.get_leaf(pos)
+        .map_err(Problem::internal)?
+        .ok_or_else(|| Problem::not_found(format!("No evidence at pos {}", pos)))?;
+    let attestation = snapshot.get_attestation(pos)
+        .map_err(Problem::internal)?;
+    let superseded_by = snapshot.superseded_by(pos)
+        .map_err(Problem::internal)?;
+    let latest_pos = match superseded_by {
+        Some(_) => snapshot.supersession_chain(pos)
+            .map_err(Problem::internal)?
+            .last()
+            .copied()
+            .unwrap_or(pos),
+        None => pos,
+    };
+
+    let signing_key = attestation.as_ref().and_then(|a| tenant.verifying_key(&a.receipt.key_path));
+    let certificate_chain = signing_key.and_then(|key| certificate_for(state, &key));
+    let verification_method = signing_key.and_then(|key| state.did.find(&key));
+
+    Ok(EvidenceRecordResponse {
+        leaf_pos: pos,
+        status,
+        evidence_id: evidence_id.map(hex::encode),
+        leaf_hash: encoding.encode(leaf_hash),
+        evidence,
+        pruned_at,
+        salt: salt.map(|salt| encoding.encode(salt)),
+        erasure,
+        legal_hold,
+        anomaly,
+        superseded_by,
+        latest_pos,
+        // 擦除时证据签名随之清空
+        evidence_signature: attestation.as_ref()
+            .filter(|a| !a.evidence_signature.is_empty())
+            .map(|a| encoding.encode(&a.evidence_signature)),
+        receipt_signature: attestation.as_ref().map(|a| encoding.encode(&a.receipt_signature)),
+        receipt: attestation.map(|a| a.receipt),
+        certificate_chain,
+        verification_method,
+        binary_encoding: encoding,
+    })
+}
+
+/// 接口：回执二维码
+///
+/// 内容为回执指针 (`yuanjing:receipt?...`)，供印在纸质报告上；扫码后回到日志取完整回执验证。
+/// 只依赖回执，原文被清理或擦除后仍可生成。
+async fn get_receipt_qr(
+    TenantScope(tenant): TenantScope,
+    Path(pos): Path<u64>,
+    Query(query): Query<QrQuery>,
+) -> Result<Response, Problem> {
+    let snapshot = tenant.reader.snapshot();
+    if pos >= snapshot.mmr_size() {
+        return Err(Problem::not_found(format!("No evidence at pos {}", pos)));
+    }
+    let attestation = snapshot.get_attestation(pos)
+        .map_err(Problem::internal)?
+        .ok_or_else(|| Problem::not_found(format!("No receipt at pos {}", pos)))?;
+    let signing_key = tenant.verifying_key(&attestation.receipt.key_path)
+        .ok_or_else(|| Problem::internal(format!("Unknown signing key path '{}'", attestation.receipt.key_path)))?;
+
+    let pointer = ReceiptPointer::new(
+        &attestation.receipt,
+        &hex::encode(signing_key.to_bytes()),
+        &hex::encode(&attestation.receipt_signature),
+    );
+    let image = qr::render(&pointer.to_uri(), query.format)
+        .map_err(Problem::internal)?;
+    Ok(([(CONTENT_TYPE, HeaderValue::from_static(query.format.content_type()))], image).into_response())
+}
+
+/// 接口：鉴定报告 (HTML / PDF)
+///
+/// 汇总图片指纹、鉴定结论、模型哈希、签名信息与包含性证明，并在服务端当场复核：
+/// 叶子哈希 (需原文)、回执签名、回执所签根下的包含性、签名密钥吊销状态。
+/// 原文已清理或擦除时仍可出具，指纹一节注明原因，叶子哈希复核记为无法进行。
+async fn get_audit_report(
+    State(state): State<Arc<AppState>>,
+    scope: PayloadScope,
+    Path(pos): Path<u64>,
+    Query(query): Query<ReportQuery>,
+) -> Result<Response, Problem> {
+    let tenant = scope.tenant.clone();
+    let snapshot = tenant.reader.snapshot();
+    scope.authorize(&snapshot, pos)?;
+    let record = evidence_record(&state, &tenant, &snapshot, pos, BinaryEncoding::Hex)?;
+    let (Some(receipt), Some(receipt_signature)) = (&record.receipt, &record.receipt_signature) else {
+        return Err(Problem::not_found(format!("No receipt at pos {}", pos)));
+    };
+    let signing_key = tenant.verifying_key(&receipt.key_path)
+        .ok_or_else(|| Problem::internal(format!("Unknown signing key path '{}'", receipt.key_path)))?;
+
+    let leaf_hash = decode_hash(BinaryEncoding::Hex, &record.leaf_hash)?;
+    let evidence_hash_match = match &record.evidence {
+        Some(evidence) => {
+            let salt = record.salt.as_deref().map(|salt| decode_hash(BinaryEncoding::Hex, salt)).transpose()?;
+            let recomputed = evidence.salted_leaf_hash_as(receipt.encoding, salt.as_ref())
+                .map_err(Problem::internal)?;
+            Some(hex::encode(recomputed) == receipt.evidence_hash)
+        }
+        None => None,
+    };
+    let receipt_signature_valid = EvidenceSigner::verify_receipt(&signing_key, receipt, &decode_signature(BinaryEncoding::Hex, receipt_signature)?)
+        .map_err(Problem::internal)?;
+    let signed_root = decode_hash(BinaryEncoding::Hex, &receipt.root)?;
+    let inclusion_valid = snapshot.verify_inclusion(pos, leaf_hash, receipt.tree_size, signed_root)
+        .map_err(Problem::internal)?;
+    let proof_items = snapshot.get_proof_at(receipt.tree_size, vec![pos])
+        .map_err(Problem::internal)?
+        .proof_items()
+        .len();
+    let current_root = snapshot.root_at(snapshot.mmr_size())
+        .map_err(Problem::internal)?;
+    let key_revoked = state.revocations.list.is_revoked(&signing_key, receipt.timestamp);
+    let valid = evidence_hash_match.unwrap_or(true) && receipt_signature_valid && inclusion_valid && !key_revoked;
+
+    let check = |ok: bool| if ok { "PASS" } else { "FAIL" };
+    let mut summary = ReportSection::new("Summary")
+        .row("Overall result", match (valid, evidence_hash_match) {
+            (false, _) => "INVALID - at least one check failed",
+            (true, None) => "VALID (receipt and proof only; original evidence no longer stored)",
+            (true, Some(_)) => "VALID",
+        })
+        .row("Evidence status", format!("{:?}", record.status).to_lowercase());
+    if let Some(newer) = record.superseded_by {
+        summary = summary.row("Superseded by", format!("pos {} (latest correction: pos {})", newer, record.latest_pos));
+    }
+    if let Some(previous) = receipt.supersedes {
+        summary = summary.row("Corrects", format!("pos {}", previous));
+    }
+    if let Some(hold) = &record.legal_hold {
+        summary = summary.row("Legal hold", format!("since {} ({})", format_time(hold.placed_at), hold.reason));
+    }
+
+    let prompts = match &record.evidence {
+        Some(evidence) => snapshot.prompt_registry()
+            .and_then(|registry| registry.get(&evidence.prompt_pool_hash))
+            .map_err(Problem::internal)?,
+        None => None,
+    };
+    let (fingerprints, verdict, model) = match &record.evidence {
+        Some(evidence) => (
+            ReportSection::new("Image fingerprints")
+                .row("SHA-256", &evidence.image_sha256)
+                .row("Perceptual hash", &evidence.image_phash)
+                .row("Analysed at", format_time(evidence.timestamp))
+                .row("Claimed by submitter", evidence.claimed_at.map(format_time).unwrap_or_else(|| "-".to_string()))
+                .row("Submitter provenance", evidence.provenance.as_ref().map(ToString::to_string).unwrap_or_else(|| "-".to_string()))
+                .row("Client context", evidence.client_context.as_ref().map(ToString::to_string).unwrap_or_else(|| "-".to_string())),
+            ReportSection::new("Verdict")
+                .row("Verdict", if evidence.verdict { "AUTHENTIC" } else { "NOT AUTHENTIC" })
+                .row("Confidence", evidence.confidence.to_string())
+                .row("Calibration", evidence.calibration.as_ref().map(ToString::to_string).unwrap_or_else(|| "-".to_string()))
+                .row("Activated prompts", activated_prompts(&evidence.activated_prompts, prompts.as_ref()))
+                .row("External knowledge hash", &evidence.external_knowledge_hash),
+            match &evidence.consensus {
+                Some(consensus) => consensus.models.iter().enumerate().fold(
+                    ReportSection::new("Model")
+                        .row("Prompt pool hash", &evidence.prompt_pool_hash)
+                        .row("Consensus rule", consensus.rule.as_str()),
+                    |section, (i, model)| {
+                        section.row(
+                            &format!("Model {}", i + 1),
+                            format!(
+                                "{}: {} ({})",
+                                model.prompt_pool_hash,
+                                if model.verdict { "AUTHENTIC" } else { "NOT AUTHENTIC" },
+                                model.confidence
+                            ),
+                        )
+                    },
+                ),
+                None => ReportSection::new("Model").row("Prompt pool hash", &evidence.prompt_pool_hash),
+            },
+        ),
+        None => {
+            let reason = match (&record.erasure, record.pruned_at) {
+                (Some(erasure), _) => format!("Erased at {}", format_time(erasure.erased_at)),
+                (None, Some(pruned_at)) => format!("Pruned by retention policy at {}", format_time(pruned_at)),
+                (None, None) => "Not available".to_string(),
+            };
+            (
+                ReportSection::new("Image fingerprints").row("Original evidence", reason),
+                ReportSection::new("Verdict").row("Verdict", "Not available (original evidence no longer stored)"),
+                ReportSection::new("Model").row("Prompt pool hash", "Not available"),
+            )
+        }
+    };
+
+    let signature = ReportSection::new("Receipt and signature")
+        .row("Leaf hash", &record.leaf_hash)
+        .row("Sequence number", receipt.seq.to_string())
+        .row("Issued at", format_time(receipt.timestamp))
+        .row("Clock uncertain", if receipt.clock_uncertain { "yes" } else { "no" })
+        .row("Canonical encoding", format!("{:?}", receipt.encoding))
+        .row("Signature algorithm", "Ed25519")
+        .row("Signing key path", if receipt.key_path.is_empty() { "(tenant root key)" } else { receipt.key_path.as_str() })
+        .row("Signing public key", hex::encode(signing_key.to_bytes()))
+        .row("Signing key fingerprint", key_fingerprint::key_fingerprint(&signing_key))
+        .row("Verification method", record.verification_method.clone().unwrap_or_else(|| "-".to_string()))
+        .row("Certificate chain", match &record.certificate_chain {
+            Some(chain) => format!("{} certificate(s) available", chain.len()),
+            None => "none".to_string(),
+        })
+        .row("Receipt signature", receipt_signature)
+        .row("Evidence signature", record.evidence_signature.clone().unwrap_or_else(|| "-".to_string()))
+        .row("Evidence hash check", match evidence_hash_match {
+            Some(matched) => check(matched),
+            None => "NOT CHECKED (original evidence no longer stored)",
+        })
+        .row("Receipt signature check", check(receipt_signature_valid))
+        .row("Signing key revoked", if key_revoked { "YES - receipt issued after revocation" } else { "no" });
+
+    let inclusion = ReportSection::new("Inclusion proof")
+        .row("Leaf position", pos.to_string())
+        .row("Tree size at issuance", receipt.tree_size.to_string())
+        .row("Signed root", &receipt.root)
+        .row("Proof length", format!("{} sibling/peak hashes", proof_items))
+        .row("Inclusion under signed root", check(inclusion_valid))
+        .row("Current tree size", snapshot.mmr_size().to_string())
+        .row("Current root", hex::encode(current_root));
+
+    let instructions = vec![
+        format!(
+            "Obtain the public key of tenant '{}' from GET /keys (or a copy you already trust) and confirm it matches the signing public key above.",
+            tenant.id
+        ),
+        format!(
+            "Recompute the leaf hash: Blake3 over the {:?} canonical encoding of the evidence (prefixed by the salt for salted leaves) must equal the leaf hash.",
+            receipt.encoding
+        ),
+        "Verify the Ed25519 receipt signature over the canonical receipt bytes with the signing public key.".to_string(),
+        format!(
+            "Fetch GET /audit/{}?tree_size={} and check that the proof reproduces the signed root from the leaf hash.",
+            pos, receipt.tree_size
+        ),
+        format!("For fully offline verification, download GET /evidence/{}/bundle and check it with the Yuanjing verifier (WASM or C library).", pos),
+        "Scan the QR code to retrieve this receipt from the log and repeat the checks independently.".to_string(),
+    ];
+
+    let pointer = ReceiptPointer::new(receipt, &hex::encode(signing_key.to_bytes()), receipt_signature);
+    let report = Report {
+        title: "Yuanjing Forensic Audit Report".to_string(),
+        subtitle: format!("Tenant {} / leaf position {} / generated {}", tenant.id, pos, format_time(chrono::Utc::now().timestamp())),
+        sections: vec![summary, fingerprints, verdict, model, signature, inclusion],
+        instructions,
+        qr_payload: Some(pointer.to_uri()),
+    };
+    let body = report.render(query.format)
+        .map_err(Problem::internal)?;
+
+    let disposition = format!(
+        "inline; filename=\"report-{}-{}.{}\"",
+        tenant.id,
+        pos,
+        if query.format == ReportFormat::Pdf { "pdf" } else { "html" }
+    );
+    let disposition = HeaderValue::from_str(&disposition)
+        .map_err(Problem::internal)?;
+    Ok((
+        [(CONTENT_TYPE, HeaderValue::from_static(query.format.content_type())), (CONTENT_DISPOSITION, disposition)],
+        body,
+    )
+        .into_response())
+}
+
+/// Unix 秒格式化为 `RFC 3339 (Unix 秒)`，便于人读也便于与回执字段对照
+fn format_time(timestamp: i64) -> String {
+    match chrono::DateTime::from_timestamp(timestamp, 0) {
+        Some(time) => format!("{} ({})", time.to_rfc3339_opts(chrono::SecondsFormat::Secs, true), timestamp),
+        None => timestamp.to_string(),
+    }
+}
+
+/// 激活的提示词索引，按 Prompt 说明对照表附上名称与说明 (如 `#3 medical-expert: 医学影像篡改`)；未登记的索引只列数字
+fn activated_prompts(indexes: &[u32], prompts: Option<&PromptSet>) -> String {
+    let Some(prompts) = prompts else {
+        return format!("{:?}", indexes);
+    };
+    let by_index = prompts.by_index();
+    indexes
+        .iter()
+        .map(|index| match by_index.get(index) {
+            Some(prompt) if prompt.description.is_empty() => format!("#{} {}", index, prompt.name),
+            Some(prompt) => format!("#{} {}: {}", index, prompt.name, prompt.description),
+            None => format!("#{}", index),
+        })
+        .collect::<Vec<_>>()
+        .join("; ")
+}
+
+/// 读取证据原文；已被保留期清理或擦除时返回 410 (导出凭证 / 证据包都需要原文)
+fn require_evidence(snapshot: &StoreSnapshot, pos: u64) -> Result<Evidence, Problem> {
+    if let Some(evidence) = snapshot.get_evidence(pos).map_err(Problem::internal)? {
+        return Ok(evidence);
+    }
+    if let Some(erasure) = snapshot.erasure(pos).map_err(Problem::internal)? {
+        return Err(Problem::new(ProblemType::EvidenceGone, format!("Evidence at pos {} was erased at {}", pos, erasure.erased_at)));
+    }
+    match snapshot.pruned_at(pos).map_err(Problem::internal)? {
+        Some(pruned_at) => Err(Problem::new(
+            ProblemType::EvidenceGone,
+            format!("Evidence at pos {} was pruned at {}; its receipt and inclusion proof remain available", pos, pruned_at),
+        )),
+        None => Err(Problem::not_found(format!("No evidence at pos {}", pos))),
+    }
+}
+
+/// 接口：把已入库的证据导出为 W3C 可验证凭证
+///
+/// 由当初签发回执的同一把密钥签名，`issuer` 为鉴定中心 DID。
+async fn get_evidence_credential(
+    State(state): State<Arc<AppState>>,
+    scope: PayloadScope,
+    Path(pos): Path<u64>,
+) -> Result<Json<serde_json::Value>, Problem> {
+    let tenant = scope.tenant.clone();
+    let snapshot = tenant.reader.snapshot();
+    scope.authorize(&snapshot, pos)?;
+    if pos >= snapshot.mmr_size() {
+        return Err(Problem::not_found(format!("No evidence at pos {}", pos)));
+    }
+    let evidence = require_evidence(&snapshot, pos)?;
+    let attestation = snapshot.get_attestation(pos)
+        .map_err(Problem::internal)?
+        .ok_or_else(|| Problem::not_found(format!("Evidence at pos {} has no receipt", pos)))?;
+
+    let signer = tenant.signing_key(&attestation.receipt.key_path)
+        .ok_or_else(|| Problem::internal(format!("Unknown signing key: {}", attestation.receipt.key_path)))?;
+    let verification_method = state.did.find(&signer.public_key())
+        .ok_or_else(|| Problem::internal("Signing key is not listed in the DID document".to_string()))?;
+
+    let credential = vc::issue(
+        &evidence,
+        &attestation.receipt,
+        &state.did.id,
+        &verification_method,
+        signer,
+        chrono::Utc::now().timestamp(),
+    )
+    .map_err(Problem::internal)?;
+    Ok(Json(credential))
+}
+
+/// 接口：把已入库的证据导出为 DSSE 信封中的 in-toto 声明
+///
+/// 由当初签发回执的同一把密钥签名，`keyid` 为 DID 文档中的验证方法 ID。
+async fn get_evidence_dsse(
+    State(state): State<Arc<AppState>>,
+    scope: PayloadScope,
+    Path(pos): Path<u64>,
+) -> Result<Json<DsseEnvelope>, Problem> {
+    let tenant = scope.tenant.clone();
+    let snapshot = tenant.reader.snapshot();
+    scope.authorize(&snapshot, pos)?;
+    if pos >= snapshot.mmr_size() {
+        return Err(Problem::not_found(format!("No evidence at pos {}", pos)));
+    }
+    let evidence = require_evidence(&snapshot, pos)?;
+    let attestation = snapshot.get_attestation(pos)
+        .map_err(Problem::internal)?
+        .ok_or_else(|| Problem::not_found(format!("Evidence at pos {} has no receipt", pos)))?;
+
+    let signer = tenant.signing_key(&attestation.receipt.key_path)
+        .ok_or_else(|| Problem::internal(format!("Unknown signing key: {}", attestation.receipt.key_path)))?;
+    let keyid = state.did.find(&signer.public_key())
+        .ok_or_else(|| Problem::internal("Signing key is not listed in the DID document".to_string()))?;
+
+    let envelope = dsse::statement(&evidence, &attestation)
+        .and_then(|statement| dsse::seal(&statement, &keyid, signer))
+        .map_err(Problem::internal)?;
+    Ok(Json(envelope))
+}
+
+/// 接口：把已入库的证据导出为 SCITT 透明声明 (COSE_Sign1)
+///
+/// 签名声明由签发回执的密钥签名，收据由租户根密钥对当前根签名，附当前树上的包含性证明 (见 `scitt.rs`)。
+async fn get_evidence_scitt(
+    State(state): State<Arc<AppState>>,
+    scope: PayloadScope,
+    Path(pos): Path<u64>,
+) -> Result<Response, Problem> {
+    let tenant = scope.tenant.clone();
+    let snapshot = tenant.reader.snapshot();
+    scope.authorize(&snapshot, pos)?;
+    let tree_size = snapshot.mmr_size();
+    if pos >= tree_size {
+        return Err(Problem::not_found(format!("No evidence at pos {}", pos)));
+    }
+    let evidence = require_evidence(&snapshot, pos)?;
+    let attestation = snapshot.get_attestation(pos)
+        .map_err(Problem::internal)?
+        .ok_or_else(|| Problem::not_found(format!("Evidence at pos {} has no receipt", pos)))?;
+
+    let signer = tenant.signing_key(&attestation.receipt.key_path)
+        .ok_or_else(|| Problem::internal(format!("Unknown signing key: {}", attestation.receipt.key_path)))?;
+    let keyid = state.did.find(&signer.public_key())
+        .ok_or_else(|| Problem::internal("Signing key is not listed in the DID document".to_string()))?;
+    let service_keyid = state.did.find(&tenant.signer.public_key())
+        .ok_or_else(|| Problem::internal("Tenant root key is not listed in the DID document".to_string()))?;
+
+    let proof = snapshot.get_proof_at(tree_size, vec![pos]).map_err(Problem::internal)?;
+    let root = snapshot.root_at(tree_size).map_err(Problem::internal)?;
+    let statement = dsse::statement(&evidence, &attestation)
+        .and_then(|statement| scitt::signed_statement(&statement, &state.did.id, &keyid, signer))
+        .and_then(|signed| {
+            let proof = InclusionProof::new(tree_size, pos, proof.proof_items().to_vec());
+            let receipt = scitt::receipt(&proof, root, &state.did.id, &service_keyid, chrono::Utc::now().timestamp(), &tenant.signer)?;
+            scitt::transparent_statement(signed, receipt)
+        })
+        .map_err(Problem::internal)?;
+    Ok(([(CONTENT_TYPE, HeaderValue::from_static(scitt::MEDIA_TYPE))], statement).into_response())
+}
+
+/// 接口：导出离线证据包
+///
+/// 证明路径取自回执当时的那棵树，验证方只需信任租户根公钥即可离线校验 (见 `bundle.rs`)。
+async fn get_evidence_bundle(
+    State(state): State<Arc<AppState>>,
+    scope: PayloadScope,
+    Path(pos): Path<u64>,
+) -> Result<Json<EvidenceBundle>, Problem> {
+    build_bundle(&state, &scope, pos).map(Json)
+}
+
+/// 接口：导出只有指定收件人能解密的证据包
+///
+/// 收件人为 X25519 公钥 (`yuanjing-core recipient-keygen` 生成)，回执与证明路径留在明文中供公开校验 (见 `envelope.rs`)。
+async fn get_sealed_bundle(
+    State(state): State<Arc<AppState>>,
+    scope: PayloadScope,
+    Path(pos): Path<u64>,
+    Query(query): Query<SealedBundleQuery>,
+) -> Result<Json<SealedBundle>, Problem> {
+    let recipients = query.recipients
+        .split(',')
+        .map(str::trim)
+        .filter(|recipient| !recipient.is_empty())
+        .map(|recipient| {
+            hex::decode(recipient).ok()
+                .and_then(|bytes| <[u8; 32]>::try_from(bytes).ok())
+                .ok_or_else(|| Problem::bad_request(format!("Recipient '{}' is not a 32-byte hex X25519 public key", recipient)))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    if recipients.is_empty() {
+        return Err(Problem::bad_request("At least one recipient is required"));
+    }
+    let bundle = build_bundle(&state, &scope, pos)?;
+    let sealed = SealedBundle::seal(&bundle, &recipients).map_err(Problem::internal)?;
+    info!("📦 [{}] 导出加密证据包: Pos={}, 收件人 {} 个", scope.tenant.id, pos, recipients.len());
+    Ok(Json(sealed))
+}
+
+/// 组装某个位置的离线证据包
+fn build_bundle(state: &AppState, scope: &PayloadScope, pos: u64) -> Result<EvidenceBundle, Problem> {
+    let tenant = &scope.tenant;
+    let snapshot = tenant.reader.snapshot();
+    if pos >= snapshot.mmr_size() {
+        return Err(Problem::not_found(format!("No evidence at pos {}", pos)));
+    }
+    scope.authorize(&snapshot, pos)?;
+    let evidence = require_evidence(&snapshot, pos)?;
+    let attestation = snapshot.get_attestation(pos)
+        .map_err(Problem::internal)?
+        .ok_or_else(|| Problem::not_found(format!("Evidence at pos {} has no receipt", pos)))?;
+
+    let key_path = &attestation.receipt.key_path;
+    let endorsement = if key_path.is_empty() {
+        None
+    } else {
+        let derived = tenant.derived_keys.values()
+            .find(|key| key.key_path() == *key_path)
+            .ok_or_else(|| Problem::internal(format!("Unknown signing key: {}", key_path)))?;
+        Some(BundleEndorsement {
+            endorsement: derived.endorsement.clone(),
+            signature: hex::encode(derived.endorsement_signature.to_bytes()),
+        })
+    };
+    let proof = snapshot.get_proof_at(attestation.receipt.tree_size, vec![pos])
+        .map_err(Problem::internal)?;
+    let certificate_chain = tenant.verifying_key(key_path).and_then(|key| certificate_for(state, &key));
+    let salt = snapshot.get_salt(pos)
+        .map_err(Problem::internal)?;
+
+    Ok(EvidenceBundle {
+        format: BUNDLE_FORMAT,
+        evidence,
+        evidence_signature: hex::encode(&attestation.evidence_signature),
+        receipt_signature: hex::encode(&attestation.receipt_signature),
+        receipt: attestation.receipt,
+        public_key: hex::encode(tenant.signer.public_key().to_bytes()),
+        endorsement,
+        proof: proof.proof_items().iter().map(hex::encode).collect(),
+        certificate_chain,
+        salt: salt.map(hex::encode),
+    })
+}
+
+/// 接口：密码学擦除 (GDPR 删除请求)
+///
+/// 销毁加盐叶子的盐值并删除原文，MMR 结构与历史根不变 (见 `EvidenceStore::erase`)。
+/// 无盐叶子返回 `409`，重复擦除返回 `409`。
+async fn erase_evidence(
+    State(state): State<Arc<AppState>>,
+    TenantScope(tenant): TenantScope,
+    Principal(principal): Principal,
+    Path(pos): Path<u64>,
+    ValidatedJson(req): ValidatedJson<ErasureRequest>,
+) -> Result<Json<ErasureRecord>, Problem> {
+    if pos >= tenant.reader.mmr_size() {
+        return Err(Problem::not_found(format!("No evidence at pos {}", pos)));
+    }
+    info!("🧨 [{}] 收到擦除请求: Pos={}, 主体={}, 依据={}", tenant.id, pos, principal, req.reason);
+
+    let record = tenant.writer.erase(pos, principal, req.reason)
+        .await
+        .map_err(|e| {
+            if e.to_string().contains("not salted") || e.to_string().contains("is blinded") || e.to_string().contains("already erased") || e.to_string().contains("legal hold") {
+                Problem::conflict(e.to_string())
+            } else if e.to_string().contains("No sequence number") {
+                Problem::not_found(e.to_string())
+            } else {
+                Problem::internal(e)
+            }
+        })?;
+    if let Some(search) = &state.search {
+        search.touch(&tenant.id, pos);
+    }
+    Ok(Json(record))
+}
+
+/// 接口：擦除记录 (审计留痕)
+async fn list_erasures(TenantScope(tenant): TenantScope) -> Result<Json<ErasuresResponse>, Problem> {
+    let erasures = tenant.reader.snapshot().erasures()
+        .map_err(Problem::internal)?;
+    Ok(Json(ErasuresResponse { erasures }))
+}
+
+/// 接口：设置诉讼保全 (管理员)
+///
+/// 保全中的条目不会被保留期清理，擦除请求返回 `409`，直到解除保全。
+async fn place_legal_hold(
+    TenantScope(tenant): TenantScope,
+    Admin(principal): Admin,
+    Path(pos): Path<u64>,
+    ValidatedJson(req): ValidatedJson<LegalHoldRequest>,
+) -> Result<Json<LegalHold>, Problem> {
+    info!("⚖️  [{}] 设置诉讼保全: Pos={}, 主体={}, 依据={}", tenant.id, pos, principal, req.reason);
+    tenant.writer.place_hold(pos, principal, req.reason)
+        .await
+        .map(Json)
+        .map_err(hold_error)
+}
+
+/// 接口：解除诉讼保全 (管理员)
+async fn release_legal_hold(
+    TenantScope(tenant): TenantScope,
+    Admin(principal): Admin,
+    Path(pos): Path<u64>,
+    ValidatedJson(req): ValidatedJson<LegalHoldRequest>,
+) -> Result<Json<HoldEvent>, Problem> {
+    info!("⚖️  [{}] 解除诉讼保全: Pos={}, 主体={}, 依据={}", tenant.id, pos, principal, req.reason);
+    tenant.writer.release_hold(pos, principal, req.reason)
+        .await
+        .map(Json)
+        .map_err(hold_error)
+}
+
+/// 接口：读取证据的访问控制列表 (租户)
+async fn get_evidence_acl(
+    TenantScope(tenant): TenantScope,
+    Path(pos): Path<u64>,
+) -> Result<Json<EvidenceAclResponse>, Problem> {
+    let snapshot = tenant.reader.snapshot();
+    if pos >= snapshot.mmr_size() {
+        return Err(Problem::not_found(format!("No evidence at pos {}", pos)));
+    }
+    let acl = snapshot.acls()
+        .and_then(|acls| acls.get(pos))
+        .map_err(Problem::internal)?;
+    Ok(Json(acl.map(EvidenceAclResponse::from).unwrap_or(EvidenceAclResponse {
+        pos,
+        auditors: Vec::new(),
+        updated_at: None,
+        principal: None,
+    })))
+}
+
+/// 接口：替换证据的访问控制列表 (租户)
+///
+/// 被列出的审计方凭 `X-Auditor-Key` 可读取该条原文；空列表撤销全部授权。未配置的审计方名称返回 `400`。
+async fn set_evidence_acl(
+    State(state): State<Arc<AppState>>,
+    TenantScope(tenant): TenantScope,
+    Principal(principal): Principal,
+    Path(pos): Path<u64>,
+    ValidatedJson(req): ValidatedJson<EvidenceAclRequest>,
+) -> Result<Json<EvidenceAclResponse>, Problem> {
+    if let Some(unknown) = req.auditors.iter().find(|name| !state.auditors.contains_name(name)) {
+        return Err(Problem::bad_request(format!("Unknown auditor '{}'", unknown)));
+    }
+    info!("🔐 [{}] 设置访问控制列表: Pos={}, 主体={}, 审计方={:?}", tenant.id, pos, principal, req.auditors);
+    tenant.writer.set_acl(pos, req.auditors, principal)
+        .await
+        .map(|acl| Json(acl.into()))
+        .map_err(|e| if e.to_string().contains("No evidence") { Problem::not_found(e.to_string()) } else { Problem::internal(e) })
+}
+
+/// 接口：读取证据的标签 (租户)
+async fn get_evidence_tags(
+    TenantScope(tenant): TenantScope,
+    Path(pos): Path<u64>,
+) -> Result<Json<EvidenceTagsResponse>, Problem> {
+    let snapshot = tenant.reader.snapshot();
+    if pos >= snapshot.mmr_size() {
+        return Err(Problem::not_found(format!("No evidence at pos {}", pos)));
+    }
+    let tags = snapshot.tags()
+        .and_then(|tags| tags.get(pos))
+        .map_err(Problem::internal)?;
+    Ok(Json(tags.map(EvidenceTagsResponse::from).unwrap_or(EvidenceTagsResponse {
+        pos,
+        tags: Vec::new(),
+        updated_at: None,
+        principal: None,
+    })))
+}
+
+/// 接口：替换证据的标签 (租户)
+///
+/// 标签在签名内容之外，修改不影响叶子哈希、回执与证明。只能针对已签名入库的证据，其他位置返回 `404`；
+/// 标签不合规 (字符集、长度、数量) 返回 `400`，空列表删除全部标签。
+async fn set_evidence_tags(
+    State(state): State<Arc<AppState>>,
+    TenantScope(tenant): TenantScope,
+    Principal(principal): Principal,
+    Path(pos): Path<u64>,
+    ValidatedJson(req): ValidatedJson<EvidenceTagsRequest>,
+) -> Result<Json<EvidenceTagsResponse>, Problem> {
+    let tags = tags::normalize_tags(req.tags).map_err(|e| Problem::bad_request(e.to_string()))?;
+    info!("🏷️  [{}] 设置证据标签: Pos={}, 主体={}, 标签={:?}", tenant.id, pos, principal, tags);
+    let set = tenant.writer.set_tags(pos, tags, principal)
+        .await
+        .map_err(|e| if e.to_string().contains("No evidence") { Problem::not_found(e.to_string()) } else { Problem::internal(e) })?;
+    if let Some(search) = &state.search {
+        search.touch(&tenant.id, pos);
+    }
+    Ok(Json(set.into()))
+}
+
+/// 接口：全文检索证据元数据 (租户)
+///
+/// 在来源说明、标签、模型版本与模型说明中查找，按相关度排序 (见 `search.rs`)；结果最多滞后一个刷新间隔。
+/// 未开启检索时返回 `404`，查询语法错误返回 `400`。
+async fn search_evidence(
+    State(state): State<Arc<AppState>>,
+    TenantScope(tenant): TenantScope,
+    Query(query): Query<SearchQuery>,
+) -> Result<Json<SearchResponse>, Problem> {
+    let service = state.search.clone()
+        .ok_or_else(|| Problem::not_found("Full-text search is disabled (configure search.enabled)"))?;
+    if query.q.trim().is_empty() {
+        return Err(Problem::bad_request("q must not be empty"));
+    }
+    let limit = query.limit.unwrap_or(search::DEFAULT_SEARCH_LIMIT).clamp(1, search::MAX_SEARCH_LIMIT);
+    let results = tokio::task::spawn_blocking(move || -> Result<SearchResponse, Problem> {
+        let index = service.index(&tenant.id)
+            .ok_or_else(|| Problem::internal(format!("No search index for tenant '{}'", tenant.id)))?;
+        let parsed = index.parse_query(&query.q).map_err(|e| Problem::bad_request(format!("Invalid query: {}", e)))?;
+        let results = index.search(parsed.as_ref(), limit, query.offset).map_err(Problem::internal)?;
+        Ok(SearchResponse { query: query.q, total: results.total, hits: results.hits })
+    })
+    .await
+    .map_err(Problem::internal)??;
+    Ok(Json(results))
+}
+
+/// 接口：列出本租户的证据 (租户)
+///
+/// 只列出已签名入库的证据 (不含检查点、保管与复核记录叶子)，按位置、回执时间或置信度排序，
+/// 可按判定、模型、置信度区间与标签组合筛选。判定与模型、置信度区间由二级索引解决 (见 `evidence_index.rs`)，
+/// 只按位置列出时沿叶子顺序或标签索引扫描；每页只读本页的条目，原文请按位置读取。
+async fn list_evidence(
+    TenantScope(tenant): TenantScope,
+    Query(query): Query<EvidenceListQuery>,
+) -> Result<Json<EvidenceListResponse>, Problem> {
+    let limit = query.limit.unwrap_or(100).clamp(1, 1000);
+    let sort = query.sort.as_deref()
+        .map(str::parse::<SortOrder>)
+        .transpose()
+        .map_err(|e| Problem::bad_request(e.to_string()))?
+        .unwrap_or_default();
+    let confidence_bps = |ratio: f64, name: &str| {
+        Confidence::from_ratio(ratio)
+            .ok()
+            .and_then(|confidence| confidence.basis_points())
+            .ok_or_else(|| Problem::bad_request(format!("{} must be between 0.0 and 1.0, got {}", name, ratio)))
+    };
+    let filter = EvidenceFilter {
+        verdict: query.verdict,
+        model: query.model,
+        min_confidence: query.min_confidence.map(|ratio| confidence_bps(ratio, "min_confidence")).transpose()?,
+        max_confidence: query.max_confidence.map(|ratio| confidence_bps(ratio, "max_confidence")).transpose()?,
+    };
+    if let (Some(min), Some(max)) = (filter.min_confidence, filter.max_confidence) {
+        if min > max {
+            return Err(Problem::bad_request("min_confidence must not exceed max_confidence"));
+        }
+    }
+    let cursor = match (query.cursor, sort.field) {
+        (Some(_), SortField::Pos) => return Err(Problem::bad_request("cursor applies to sort by timestamp or confidence; use from")),
+        (Some(cursor), field) => Some(
+            hex::decode(&cursor)
+                .ok()
+                .filter(|cursor| cursor.len() == field.suffix_len())
+                .ok_or_else(|| Problem::bad_request(format!("Invalid cursor for sort by {}", field.as_str())))?,
+        ),
+        (None, SortField::Pos) => query.from.map(|from| from.to_be_bytes().to_vec()),
+        (None, _) if query.from.is_some() => return Err(Problem::bad_request("from applies to sort by pos; use cursor")),
+        (None, _) => None,
+    };
+    let snapshot = tenant.reader.snapshot();
+    let tag = query.tag.map(|tag| tag.trim().to_ascii_lowercase());
+    let listing = tokio::task::spawn_blocking(move || -> anyhow::Result<EvidenceListResponse> {
+        let tags = snapshot.tags()?;
+        let index = snapshot.evidence_index()?;
+        let size = snapshot.mmr_size();
+        let (positions, next, next_cursor) = if sort.field == SortField::Pos && (tag.is_some() || !filter.is_scoped()) {
+            // 按位置：沿标签索引或叶子顺序扫描，其余条件逐条核对
+            let candidates: Box<dyn Iterator<Item = u64>> = match (&tag, sort.descending) {
+                (Some(tag), descending) => Box::new(tags.positions(tag, query.from, usize::MAX, descending)?.into_iter()),
+                (None, false) => {
+                    let first = (query.from.unwrap_or(0)..size).find(|pos| position::is_leaf_pos(*pos)).unwrap_or(size);
+                    let first_leaf = position::pos_to_leaf_index(first).unwrap_or(position::leaf_count(size));
+                    Box::new((first_leaf..position::leaf_count(size)).map(position::leaf_index_to_pos))
+                }
+                (None, true) => {
+                    let last = query.from.unwrap_or(u64::MAX).min(size.saturating_sub(1));
+                    let leaves = (0..=last).rev().find(|pos| position::is_leaf_pos(*pos)).and_then(position::pos_to_leaf_index);
+                    Box::new(leaves.into_iter().flat_map(|last_leaf| (0..=last_leaf).rev()).map(position::leaf_index_to_pos))
+                }
+            };
+            let filtered = filter.is_scoped() || filter.has_confidence_range();
+            let mut positions = Vec::new();
+            let mut next = None;
+            for pos in candidates.filter(|pos| *pos < size) {
+                if snapshot.get_attestation(pos)?.is_none() {
+                    continue;
+                }
+                if filtered && !index.get(pos)?.is_some_and(|attrs| filter.matches(&attrs)) {
+                    continue;
+                }
+                if positions.len() == limit {
+                    next = Some(pos);
+                    break;
+                }
+                positions.push(pos);
+            }
+            (positions, next, None)
+        } else {
+            // 判定与模型由索引前缀限定，按置信度排序时置信度区间即键范围；索引随写入即时变化，按快照大小过滤
+            let page = index.scan(sort, &filter, cursor.as_deref(), limit, |pos| {
+                if pos >= size {
+                    return Ok(false);
+                }
+                if let Some(tag) = &tag {
+                    if !tags.get(pos)?.is_some_and(|set| set.tags.contains(tag)) {
+                        return Ok(false);
+                    }
+                }
+                if sort.field != SortField::Confidence && filter.has_confidence_range() {
+                    return Ok(index.get(pos)?.is_some_and(|attrs| filter.matches(&attrs)));
+                }
+                Ok(true)
+            })?;
+            match (page.next, sort.field) {
+                (Some(suffix), SortField::Pos) => {
+                    let next = suffix.try_into().map(u64::from_be_bytes).map_err(|_| anyhow::anyhow!("Corrupted evidence order key"))?;
+                    (page.positions, Some(next), None)
+                }
+                (suffix, _) => (page.positions, None, suffix.map(hex::encode)),
+            }
+        };
+        let mut entries = Vec::with_capacity(positions.len());
+        for pos in positions {
+            let Some(attestation) = snapshot.get_attestation(pos)? else {
+                continue;
+            };
+            // 原文已擦除的证据没有属性，只剩位置、回执时间与标签
+            let attrs = index.get(pos)?;
+            entries.push(EvidenceListEntry {
+                pos,
+                timestamp: attestation.receipt.timestamp,
+                verdict: attrs.as_ref().map(|attrs| attrs.verdict),
+                confidence: attrs.as_ref().and_then(|attrs| attrs.confidence),
+                model: attrs.map(|attrs| attrs.model),
+                tags: tags.get(pos)?.map(|set| set.tags).unwrap_or_default(),
+            });
+        }
+        Ok(EvidenceListResponse { entries, next, next_cursor })
+    })
+    .await
+    .map_err(Problem::internal)?
+    .map_err(Problem::internal)?;
+    Ok(Json(listing))
+}
+
+/// 接口：追加一条保管事件 (租户)
+///
+/// 事件由租户根密钥签名，作为一片叶子追加进 MMR，并链到该证据上一条事件。只能针对已签名入库的证据，
+/// 其他位置 (含检查点与保管记录叶子) 返回 `404`；`transferred` 必须填写 `recipient`，其余动作不得填写。
+async fn append_custody(
+    TenantScope(tenant): TenantScope,
+    Principal(principal): Principal,
+    Path(pos): Path<u64>,
+    ValidatedJson(req): ValidatedJson<CustodyRequest>,
+) -> Result<Json<CustodyEntry>, Problem> {
+    if req.actor.trim().is_empty() {
+        return Err(Problem::bad_request("actor must not be empty"));
+    }
+    match (req.action, req.recipient.as_deref().map(str::trim)) {
+        (CustodyAction::Transferred, None | Some("")) => {
+            return Err(Problem::bad_request("A transferred event needs a recipient"));
+        }
+        (CustodyAction::Transferred, _) | (_, None) => {}
+        (_, Some(_)) => return Err(Problem::bad_request("Only a transferred event takes a recipient")),
+    }
+    info!("🧷 [{}] 记录保管事件: Pos={}, 动作={:?}, 经手人={}, 主体={}", tenant.id, pos, req.action, req.actor, principal);
+    let details = CustodyDetails { action: req.action, actor: req.actor, recipient: req.recipient, note: req.note };
+    let (leaf_pos, record) = tenant.writer
+        .append_custody(pos, details, principal)
+        .await
+        .map_err(hold_error)?;
+    custody_entry(leaf_pos, record).map(Json).map_err(Problem::internal)
+}
+
+/// 接口：某条证据的完整保管链 (租户)
+async fn get_custody(
+    TenantScope(tenant): TenantScope,
+    Path(pos): Path<u64>,
+) -> Result<Json<CustodyResponse>, Problem> {
+    let snapshot = tenant.reader.snapshot();
+    if pos >= snapshot.mmr_size() {
+        return Err(Problem::not_found(format!("No evidence at pos {}", pos)));
+    }
+    let events = snapshot.custody(pos)
+        .and_then(|records| records.into_iter().map(|(leaf_pos, record)| custody_entry(leaf_pos, record)).collect())
+        .map_err(Problem::internal)?;
+    Ok(Json(CustodyResponse { pos, events }))
+}
+
+fn custody_entry(leaf_pos: u64, record: CustodyRecord) -> anyhow::Result<CustodyEntry> {
+    Ok(CustodyEntry {
+        leaf_pos,
+        leaf_hash: hex::encode(record.leaf_hash()?),
+        record,
+    })
+}
+
+/// 接口：提交鉴定人复核 (租户)
+///
+/// 鉴定人事先用自己的密钥签署复核意见，租户代为提交。鉴定人须在 `[[api.examiners]]` 中登记，
+/// 签名按该位置证据的叶子哈希核对，不符返回 `400`；同一份签名重复提交返回 `409`。
+async fn submit_review(
+    State(state): State<Arc<AppState>>,
+    TenantScope(tenant): TenantScope,
+    Principal(principal): Principal,
+    Path(pos): Path<u64>,
+    ValidatedJson(req): ValidatedJson<ReviewRequest>,
+) -> Result<Json<ReviewEntry>, Problem> {
+    let key = state.examiners
+        .key_of(&req.examiner)
+        .ok_or_else(|| Problem::bad_request(format!("Unknown examiner '{}'", req.examiner)))?;
+    let now = chrono::Utc::now().timestamp();
+    if !(0..=now + MAX_CLAIMED_AT_SKEW_SECS).contains(&req.assessed_at) {
+        return Err(Problem::bad_request(format!(
+            "Invalid assessed_at {}: must be a Unix time no later than the server clock (+{} s)",
+            req.assessed_at, MAX_CLAIMED_AT_SKEW_SECS
+        )));
+    }
+    let snapshot = tenant.reader.snapshot();
+    // 只有签名入库的证据可以复核 (检查点、保管与复核记录叶子没有回执)
+    let attested = pos < snapshot.mmr_size() && snapshot.get_attestation(pos).map_err(Problem::internal)?.is_some();
+    let leaf = if attested { snapshot.get_leaf(pos).map_err(Problem::internal)? } else { None }
+        .ok_or_else(|| Problem::not_found(format!("No evidence at pos {}", pos)))?;
+    let record = ReviewRecord {
+        assessment: ReviewAssessment {
+            tenant_id: tenant.id.clone(),
+            pos,
+            evidence_hash: hex::encode(leaf),
+            opinion: req.opinion,
+            notes: req.notes,
+            examiner: req.examiner,
+            assessed_at: req.assessed_at,
+        },
+        examiner_public_key: hex::encode(key.to_bytes()),
+        signature: req.signature,
+        principal,
+        recorded_at: now,
+    };
+    if !record.verify(key).unwrap_or(false) {
+        return Err(Problem::bad_request(format!(
+            "Signature does not verify against the registered key of examiner '{}' for the evidence at pos {}",
+            record.assessment.examiner, pos
+        )));
+    }
+    info!("🧑‍⚖️ [{}] 收录鉴定人复核: Pos={}, 鉴定人={}, 结论={:?}", tenant.id, pos, record.assessment.examiner, record.assessment.opinion);
+    let leaf_pos = tenant.writer.append_review(record.clone()).await.map_err(|e| {
+        if e.to_string().contains("No evidence") {
+            Problem::not_found(e.to_string())
+        } else if e.to_string().contains("already recorded") || e.to_string().contains("does not match") {
+            Problem::conflict(e.to_string())
+        } else {
+            Problem::internal(e)
+        }
+    })?;
+    review_entry(leaf_pos, record).map(Json).map_err(Problem::internal)
+}
+
+/// 接口：某条证据的全部鉴定人复核 (租户)
+async fn get_reviews(
+    TenantScope(tenant): TenantScope,
+    Path(pos): Path<u64>,
+) -> Result<Json<ReviewsResponse>, Problem> {
+    let snapshot = tenant.reader.snapshot();
+    if pos >= snapshot.mmr_size() {
+        return Err(Problem::not_found(format!("No evidence at pos {}", pos)));
+    }
+    let reviews = snapshot.reviews(pos)
+        .and_then(|records| records.into_iter().map(|(leaf_pos, record)| review_entry(leaf_pos, record)).collect())
+        .map_err(Problem::internal)?;
+    Ok(Json(ReviewsResponse { pos, reviews }))
+}
+
+fn review_entry(leaf_pos: u64, record: ReviewRecord) -> anyhow::Result<ReviewEntry> {
+    Ok(ReviewEntry { leaf_pos, leaf_hash: hex::encode(record.leaf_hash()?), record })
+}
+
+fn hold_error(e: anyhow::Error) -> Problem {
+    if e.to_string().contains("No evidence") {
+        Problem::not_found(e.to_string())
+    } else if e.to_string().contains("legal hold") || e.to_string().contains("already erased") {
+        Problem::conflict(e.to_string())
+    } else {
+        Problem::internal(e)
+    }
+}
+
+/// 接口：某条证据的保全状态与历史
+async fn get_legal_hold(
+    TenantScope(tenant): TenantScope,
+    Path(pos): Path<u64>,
+) -> Result<Json<LegalHoldStatusResponse>, Problem> {
+    let holds = tenant.reader.snapshot().legal_holds()
+        .map_err(Problem::internal)?;
+    Ok(Json(LegalHoldStatusResponse {
+        pos,
+        hold: holds.get(pos).map_err(Problem::internal)?,
+        history: holds.history(pos).map_err(Problem::internal)?,
+    }))
+}
+
+/// 接口：本租户全部生效中的保全
+async fn list_legal_holds(TenantScope(tenant): TenantScope) -> Result<Json<LegalHoldsResponse>, Problem> {
+    let holds = tenant.reader.snapshot().legal_holds()
+        .and_then(|holds| holds.active())
+        .map_err(Problem::internal)?;
+    Ok(Json(LegalHoldsResponse { holds }))
+}
+
+/// 接口：激活路径异常的复核队列 (租户)
+///
+/// 后台检测 (见 `anomaly.rs`) 标记的证据，缺省只列出待复核的；标记只在主库上产生，只读副本上为空。
+async fn list_anomalies(
+    TenantScope(tenant): TenantScope,
+    Query(query): Query<AnomalyQuery>,
+) -> Result<Json<AnomaliesResponse>, Problem> {
+    let anomalies = tenant.reader.snapshot().anomalies().map_err(Problem::internal)?;
+    let flags = anomalies.list(Some(query.status))
+        .map_err(Problem::internal)?
+        .into_iter()
+        .filter(|flag| query.model.as_ref().is_none_or(|model| *model == flag.prompt_pool_hash))
+        .collect();
+    let scanned_leaves = anomalies.cursor().map_err(Problem::internal)?;
+    Ok(Json(AnomaliesResponse { scanned_leaves, flags }))
+}
+
+/// 接口：给出异常标记的复核结论 (管理员)
+///
+/// 只能复核待复核的标记：没有标记返回 `404`，已复核过返回 `409`，`status` 为 `open` 返回 `400`。
+async fn resolve_anomaly(
+    TenantScope(tenant): TenantScope,
+    Admin(principal): Admin,
+    Path(pos): Path<u64>,
+    ValidatedJson(req): ValidatedJson<AnomalyResolveRequest>,
+) -> Result<Json<AnomalyFlag>, Problem> {
+    info!("🔎 [{}] 复核异常标记: Pos={}, 结论={}, 主体={}", tenant.id, pos, req.status.as_str(), principal);
+    tenant.writer.resolve_anomaly(pos, req.status, principal, req.note)
+        .await
+        .map(Json)
+        .map_err(|e| {
+            let message = e.to_string();
+            if message.contains("No anomaly flag") {
+                Problem::not_found(message)
+            } else if message.contains("already") {
+                Problem::conflict(message)
+            } else if message.contains("Invalid anomaly resolution") {
+                Problem::bad_request(message)
+            } else {
+                Problem::internal(e)
+            }
+        })
+}
+
+/// 接口：自审重放 (管理员)
+///
+/// 在只读快照上重放全部叶子并比对历史根 (见 `self_audit.rs`)，不阻塞存证；
+/// 耗时与叶子数成正比，放到阻塞线程池执行。
+async fn run_self_audit(
+    TenantScope(tenant): TenantScope,
+    Admin(principal): Admin,
+) -> Result<Json<SelfAuditReport>, Problem> {
+    info!("🔁 [{}] 自审重放: 主体={}", tenant.id, principal);
+    let snapshot = tenant.reader.snapshot();
+    let tenant_id = tenant.id.clone();
+    let report = tokio::task::spawn_blocking(move || self_audit::replay(&snapshot, &tenant_id))
+        .await
+        .map_err(Problem::internal)?
+        .map_err(Problem::internal)?;
+    if let Some(divergence) = &report.first_divergence {
+        error!("🚨 [{}] 自审发现分歧: {:?} @ Pos={}", tenant.id, divergence.kind, divergence.pos);
+    }
+    Ok(Json(report))
+}
+
+/// 接口：完整性检查 (管理员)
+///
+/// 在写线程中执行，检查期间追加排队等待；只输出报告，修复须停机后用 `yuanjing check-integrity --repair`。
+async fn check_integrity(
+    TenantScope(tenant): TenantScope,
+    Admin(principal): Admin,
+) -> Result<Json<IntegrityReport>, Problem> {
+    info!("🩺 [{}] 完整性检查: 主体={}", tenant.id, principal);
+    let report = tenant.writer.check_integrity().await.map_err(|e| {
+        error!("❌ [{}] 完整性检查失败: {}", tenant.id, e);
+        Problem::internal(e)
+    })?;
+    if !report.is_healthy() {
+        error!("🚨 [{}] 完整性检查发现 {} 项问题", tenant.id, report.findings.len());
+    }
+    Ok(Json(report))
+}
+
+/// 接口：运行统计 (管理员)
+///
+/// 本租户的树大小、根、判定分布、追加速率、存储与索引占用、锚定状态 (见 `stats.rs`)；
+/// 逐条读取全部叶子，放到阻塞线程池执行。
+async fn get_stats(
+    State(state): State<Arc<AppState>>,
+    TenantScope(tenant): TenantScope,
+    Admin(_): Admin,
+) -> Result<Json<TenantStats>, Problem> {
+    let snapshot = tenant.reader.snapshot();
+    let tenant_id = tenant.id.clone();
+    let roots = state.roots.clone();
+    let now = chrono::Utc::now().timestamp();
+    let stats = tokio::task::spawn_blocking(move || stats::collect(&snapshot, &tenant_id, &roots, now))
+        .await
+        .map_err(Problem::internal)?
+        .map_err(Problem::internal)?;
+    Ok(Json(stats))
+}
+
+/// 接口：可疑提交方 (管理员)
+///
+/// 本节点内存中的滥用检测发现 (见 `abuse.rs`)，重启后清零；已被限流的提交方 `throttled_until` 晚于当前时间。
+async fn get_abuse_findings(
+    State(state): State<Arc<AppState>>,
+    TenantScope(tenant): TenantScope,
+    Admin(_): Admin,
+) -> Json<AbuseFindingsResponse> {
+    Json(AbuseFindingsResponse { findings: state.abuse.findings(&tenant.id) })
+}
+
+/// 接口：解除提交方的限流并清除其发现与计数 (管理员)
+async fn release_submitter(
+    State(state): State<Arc<AppState>>,
+    TenantScope(tenant): TenantScope,
+    Admin(admin): Admin,
+    ValidatedJson(req): ValidatedJson<AbuseReleaseRequest>,
+) -> Json<AbuseReleaseResponse> {
+    let cleared = state.abuse.release(&tenant.id, &req.principal);
+    info!("🚩 [{}] 解除提交方限流: 提交方={}, 清除发现={}, 主体={}", tenant.id, req.principal, cleared, admin);
+    Json(AbuseReleaseResponse { principal: req.principal, cleared })
+}
+
+/// 接口：激活模式统计 (租户)
+///
+/// 聚合本租户证据的 `activated_prompts`：各 Prompt 按判定的激活次数、最常见的共同激活组合与随时间的变化。
+/// 只返回计数；`since` 不早于 `until` 时返回 `400`。
+async fn get_activation_analytics(
+    TenantScope(tenant): TenantScope,
+    Query(query): Query<ActivationQuery>,
+) -> Result<Json<ActivationAnalytics>, Problem> {
+    if let (Some(since), Some(until)) = (query.since, query.until) {
+        if since >= until {
+            return Err(Problem::bad_request(format!("since ({}) must be earlier than until ({})", since, until)));
+        }
+    }
+    let filter = ActivationFilter {
+        since: query.since,
+        until: query.until,
+        model: query.model,
+        interval: query.interval,
+        top: query.top.unwrap_or(analytics::DEFAULT_TOP_PAIRS).min(analytics::MAX_TOP_PAIRS),
+    };
+    let snapshot = tenant.reader.snapshot();
+    let tenant_id = tenant.id.clone();
+    let now = chrono::Utc::now().timestamp();
+    let analytics = tokio::task::spawn_blocking(move || analytics::collect(&snapshot, &tenant_id, &filter, now))
+        .await
+        .map_err(Problem::internal)?
+        .map_err(Problem::internal)?;
+    Ok(Json(analytics))
+}
+
+/// 复制长轮询检查树大小的间隔
+const LONG_POLL_STEP: Duration = Duration::from_millis(10);
+
+/// 接口：复制拉取 (管理员)
+///
+/// 只读副本按叶子顺序拉取已提交的叶子；`from` 必须是一个合法的树大小且不超过主库当前大小，
+/// 超过说明副本比主库还长 (分叉或指错了主库)，返回 `409`。
+/// 带 `X-Replica-Key` 的请求同时是该副本对 `from` 的确认 (密钥不在 `replication.replicas` 中时返回 `401`)；
+/// `wait_ms` 使追上的副本在此等待新叶子。
+async fn get_replication_leaves(
+    State(state): State<Arc<AppState>>,
+    TenantScope(tenant): TenantScope,
+    Admin(_): Admin,
+    headers: HeaderMap,
+    Query(query): Query<ReplicationQuery>,
+) -> Result<Json<ReplicationBatch>, Problem> {
+    if query.from > tenant.reader.mmr_size() {
+        return Err(Problem::conflict(format!(
+            "Replica size {} is ahead of the primary ({})",
+            query.from,
+            tenant.reader.mmr_size()
+        )));
+    }
+    if !position::is_valid_mmr_size(query.from) {
+        return Err(Problem::bad_request(format!("{} is not a valid MMR size", query.from)));
+    }
+    if let Some(ack_key) = headers.get("x-replica-key").and_then(|value| value.to_str().ok()) {
+        let replica = state.acks.resolve(ack_key).ok_or_else(|| Problem::new(ProblemType::Unauthorized, "Invalid replica ack key"))?;
+        state.acks.record(&tenant.id, replica, query.from);
+    }
+
+    // 长轮询：树还没长大就等，步长很短，新叶子提交后副本几乎立即拿到
+    let deadline = tokio::time::Instant::now() + Duration::from_millis(query.wait_ms.unwrap_or(0).min(MAX_LONG_POLL_MS));
+    while tenant.reader.mmr_size() == query.from && tokio::time::Instant::now() < deadline {
+        tokio::time::sleep(LONG_POLL_STEP).await;
+    }
+
+    let snapshot = tenant.reader.snapshot();
+    let limit = query.limit.unwrap_or(MAX_REPLICATION_BATCH).clamp(1, MAX_REPLICATION_BATCH);
+    let batch = tokio::task::spawn_blocking(move || replication::export(&snapshot, query.from, limit))
+        .await
+        .map_err(Problem::internal)?
+        .map_err(Problem::internal)?;
+    Ok(Json(batch))
+}
+
+/// 接口：挑战应答 (远程审计时证明服务在线、持有私钥并掌握当前账本)
+async fn answer_challenge(
+    TenantScope(tenant): TenantScope,
+    OutputEncoding(encoding): OutputEncoding,
+    ValidatedJson(req): ValidatedJson<ChallengeRequest>,
+) -> Result<Json<ChallengeResponse>, Problem> {
+    challenge::validate_nonce(&req.nonce).map_err(|e| Problem::bad_request(e.to_string()))?;
+
+    let snapshot = tenant.reader.snapshot();
+    let tree_size = snapshot.mmr_size();
+    let root = if tree_size == 0 {
+        [0u8; 32]
+    } else {
+        snapshot.root_at(tree_size).map_err(Problem::internal)?
+    };
+    let statement = LivenessStatement {
+        nonce: req.nonce,
+        tenant_id: tenant.id.clone(),
+        root: hex::encode(root),
+        tree_size,
+        timestamp: chrono::Utc::now().timestamp(),
+    };
+    let signature = statement.sign(&tenant.signer).map_err(Problem::internal)?;
+
+    Ok(Json(ChallengeResponse {
+        statement,
+        signature: encoding.encode(signature.to_bytes()),
+        public_key: encoding.encode(tenant.signer.public_key().to_bytes()),
+        binary_encoding: encoding,
+    }))
+}
+
+/// 接口：签名审计日志 (哈希链) 及其在 MMR 中的检查点
+async fn get_signing_log(
+    TenantScope(tenant): TenantScope,
+    Query(query): Query<SigningLogQuery>,
+) -> Result<Json<SigningLogResponse>, Problem> {
+    let log = tenant.reader.snapshot().signing_log()
+        .map_err(Problem::internal)?;
+    let limit = query.limit.unwrap_or(100).min(1000);
+    Ok(Json(SigningLogResponse {
+        length: log.len().map_err(Problem::internal)?,
+        entries: log.entries(query.from, limit).map_err(Problem::internal)?,
+        checkpoints: log.checkpoints().map_err(Problem::internal)?,
+    }))
+}
+
+/// 接口：运维审计日志 (管理员)
+///
+/// 节点级，按行号分页；读取时重算哈希链，链断裂返回 `500`。检查点条目中的 `pos` 可用 `/audit/{pos}` 取包含性证明。
+async fn get_ops_log(
+    State(state): State<Arc<AppState>>,
+    Admin(_): Admin,
+    Query(query): Query<SigningLogQuery>,
+) -> Result<Json<OpsLogResponse>, Problem> {
+    let ops_log = state.ops_log.as_ref()
+        .ok_or_else(|| Problem::not_found("Ops log is disabled (ops_log.path is empty)"))?;
+    let (length, head) = ops_log.head().map_err(Problem::internal)?;
+    let entries = ops_log.entries(query.from, query.limit.unwrap_or(100).min(1000))
+        .map_err(Problem::internal)?
+        .into_iter()
+        .map(|(entry, hash)| OpsLogItem { entry, hash: hex::encode(hash) })
+        .collect();
+    Ok(Json(OpsLogResponse { length, head: hex::encode(head), entries }))
+}
+
+/// 接口：查询访问日志 (管理员)
+///
+/// 节点级，不限于当前租户；按 `tenant` 过滤。`principal` 同时匹配 API Key 主体与管理员主体。
+async fn get_access_log(
+    State(state): State<Arc<AppState>>,
+    Admin(_): Admin,
+    Query(query): Query<AccessLogQuery>,
+) -> Result<Json<AccessLogResponse>, Problem> {
+    let filter = AccessLogFilter {
+        since: query.since,
+        until: query.until,
+        tenant: query.tenant,
+        principal: query.principal,
+        route: query.route,
+        pos: query.pos,
+    };
+    let limit = query.limit.unwrap_or(100).min(1000);
+    let entries = state.access_log.query(&filter, limit)
+        .map_err(Problem::internal)?;
+    Ok(Json(AccessLogResponse { entries }))
+}
+
+/// 接口：版本与持久化配置
+async fn get_version(State(state): State<Arc<AppState>>) -> Json<VersionInfo> {
+    Json(VersionInfo {
+        writable: state.fence.is_open(),
+        lease_epoch: state.fence.epoch(),
+        ..state.version.clone()
+    })
+}
+
+/// 接口：公开的 JSON Schema 列表
+async fn list_schemas() -> Json<SchemaListResponse> {
+    Json(SchemaListResponse {
+        schemas: v1::PUBLISHED_SCHEMAS.iter().map(|name| name.to_string()).collect(),
+    })
+}
+
+/// 接口：按名称获取 JSON Schema (Draft 2020-12)
+///
+/// 请求体 (`ProveRequest` 等) 在服务端按同一份 Schema 校验；
+/// `Evidence`、`ProveReceipt`、`EvidenceBundle` 与 `SealedBundle` 供接入方校验响应与离线证据包。
+async fn get_schema(Path(name): Path<String>) -> Result<Json<serde_json::Value>, Problem> {
+    let schema = match name.as_str() {
+        "Evidence" => schema::schema_for::<Evidence>(),
+        "Receipt" => schema::schema_for::<Receipt>(),
+        "ProveRequest" => schema::schema_for::<ProveRequest>(),
+        "ProveReceipt" => schema::schema_for::<ProveReceipt>(),
+        "EvidenceBundle" => schema::schema_for::<EvidenceBundle>(),
+        "SealedBundle" => schema::schema_for::<SealedBundle>(),
+        "VerifyRequest" => schema::schema_for::<VerifyRequest>(),
+        "VerifySignaturesRequest" => schema::schema_for::<VerifySignaturesRequest>(),
+        "ChallengeRequest" => schema::schema_for::<ChallengeRequest>(),
+        "BatchAuditRequest" => schema::schema_for::<BatchAuditRequest>(),
+        "ModelRegisterRequest" => schema::schema_for::<ModelRegisterRequest>(),
+        "PromptRegistryRequest" => schema::schema_for::<PromptRegistryRequest>(),
+        "ErasureRequest" => schema::schema_for::<ErasureRequest>(),
+        "LegalHoldRequest" => schema::schema_for::<LegalHoldRequest>(),
+        "AnomalyResolveRequest" => schema::schema_for::<AnomalyResolveRequest>(),
+        "AbuseReleaseRequest" => schema::schema_for::<AbuseReleaseRequest>(),
+        "EvidenceAclRequest" => schema::schema_for::<EvidenceAclRequest>(),
+        "EvidenceTagsRequest" => schema::schema_for::<EvidenceTagsRequest>(),
+        "CustodyRequest" => schema::schema_for::<CustodyRequest>(),
+        "ReviewRequest" => schema::schema_for::<ReviewRequest>(),
+        "ProblemDetails" => schema::schema_for::<ProblemDetails>(),
+        _ => return Err(Problem::not_found(format!("Unknown schema '{}'", name))),
+    };
+    Ok(Json((*schema).clone()))
+}
+
+/// 接口：运行指标 (Prometheus 文本格式)
+///
+/// 公开接口，按租户标签输出证明缓存命中情况与 MMR 大小。
+async fn metrics(State(state): State<Arc<AppState>>) -> String {
+    let mut tenants: Vec<_> = state.tenants.all().collect();
+    tenants.sort_by(|a, b| a.id.cmp(&b.id));
+
+    let mut out = String::new();
+    for cache in ["proof", "evidence", "attestation"] {
+        out.push_str(&format!("# TYPE yuanjing_{}_cache_hits_total counter\n", cache));
+        out.push_str(&format!("# TYPE yuanjing_{}_cache_misses_total counter\n", cache));
+        out.push_str(&format!("# TYPE yuanjing_{}_cache_entries gauge\n", cache));
+    }
+    out.push_str("# TYPE yuanjing_mmr_size gauge\n");
+    // 存证管线按优先级分通道：在管线中 / 等待计算 / 计算中的请求数、入场上限、计算配额与拒绝数
+    type LaneMetric = (&'static str, &'static str, fn(&IngestGate, Priority) -> u64);
+    let ingest_metrics: [LaneMetric; 6] = [
+        ("in_flight", "gauge", |gate, priority| gate.in_flight(priority) as u64),
+        ("queued", "gauge", |gate, priority| gate.queued(priority) as u64),
+        ("running", "gauge", |gate, priority| gate.running(priority) as u64),
+        ("capacity", "gauge", |gate, priority| gate.capacity(priority) as u64),
+        ("quota", "gauge", |gate, priority| gate.quota(priority) as u64),
+        ("rejected_total", "counter", |gate, priority| gate.rejected_total(priority)),
+    ];
+    for (name, kind, value) in ingest_metrics {
+        out.push_str(&format!("# TYPE yuanjing_ingest_{} {}\n", name, kind));
+        for priority in Priority::ALL {
+            out.push_str(&format!("yuanjing_ingest_{}{{priority=\"{}\"}} {}\n", name, priority.as_str(), value(&state.ingest, priority)));
+        }
+    }
+    out.push_str("# TYPE yuanjing_jobs_pending gauge\n");
+    for priority in Priority::ALL {
+        out.push_str(&format!("yuanjing_jobs_pending{{priority=\"{}\"}} {}\n", priority.as_str(), state.jobs.pending(priority)));
+    }
+    out.push_str("# TYPE yuanjing_writer_queued gauge\n");
+    for tenant in tenants {
+        let read_cache = tenant.reader.read_cache();
+        let caches = [
+            ("proof", tenant.reader.proof_cache_stats()),
+            ("evidence", read_cache.evidence.stats()),
+            ("attestation", read_cache.attestations.stats()),
+        ];
+        for (cache, stats) in caches {
+            out.push_str(&format!("yuanjing_{}_cache_hits_total{{tenant=\"{}\"}} {}\n", cache, tenant.id, stats.hits));
+            out.push_str(&format!("yuanjing_{}_cache_misses_total{{tenant=\"{}\"}} {}\n", cache, tenant.id, stats.misses));
+            out.push_str(&format!("yuanjing_{}_cache_entries{{tenant=\"{}\"}} {}\n", cache, tenant.id, stats.entries));
+        }
+        out.push_str(&format!("yuanjing_mmr_size{{tenant=\"{}\"}} {}\n", tenant.id, tenant.reader.mmr_size()));
+        for priority in Priority::ALL {
+            out.push_str(&format!(
+                "yuanjing_writer_queued{{tenant=\"{}\",priority=\"{}\"}} {}\n",
+                tenant.id,
+                priority.as_str(),
+                tenant.writer.queued(priority)
+            ));
+        }
+    }
+    out
+}
+
+// ==========================================
+// 5. 辅助函数 (Helpers)
+// ==========================================
+
+/// 围栏关闭时拒绝写接口：MMR 只能由持有写租约的主库追加，副本或失去租约的旧主库自行追加会分叉
+///
+/// 写线程在追加前还会再查一次围栏，这里只是尽早给出明确的错误。
+async fn reject_writes_on_replica(
+    State(state): State<Arc<AppState>>,
+    matched: Option<MatchedPath>,
+    request: Request,
+    next: middleware::Next,
+) -> Response {
+    let rejected = matches!(&matched, Some(matched) if REPLICA_REJECTED_ROUTES.contains(&v1::unversioned(matched.as_str())));
+    if rejected && request.method() == Method::POST && !state.fence.is_open() {
+        return match &state.version.replica_of {
+            Some(primary) => Problem::new(
+                ProblemType::ReadOnlyReplica,
+                format!("This server is a read-only replica; send writes to the primary at {}", primary),
+            )
+            .into_response(),
+            None => Problem::new(
+                ProblemType::NotWriter,
+                "This server does not hold the writer lease; send writes to the current primary",
+            )
+            .into_response(),
+        };
+    }
+    next.run(request).await
+}
+
+/// 旧路由的响应标注弃用：`Deprecation` (RFC 9745)、指向 `/v1` 同名接口的 `Link`，公布了停用时间时加 `Sunset` (RFC 8594)
+async fn deprecate_legacy_route(State(state): State<Arc<AppState>>, request: Request, next: middleware::Next) -> Response {
+    let successor = format!("<{}{}>; rel=\"successor-version\"", v1::PREFIX, request.uri().path());
+    let mut response = next.run(request).await;
+    let headers = response.headers_mut();
+    headers.insert("deprecation", HeaderValue::from_str(&format!("@{}", LEGACY_DEPRECATED_AT)).expect("valid header"));
+    if let Ok(link) = HeaderValue::from_str(&successor) {
+        headers.append(LINK, link);
+    }
+    if let Some(sunset) = state.version.legacy_sunset.and_then(|at| chrono::DateTime::from_timestamp(at, 0)) {
+        let sunset = sunset.format("%a, %d %b %Y %H:%M:%S GMT").to_string();
+        headers.insert("sunset", HeaderValue::from_str(&sunset).expect("valid header"));
+    }
+    response
+}
+
+/// 请求完成后写入访问日志：主体、路由、状态码、耗时与涉及的证据位置
+///
+/// 位于 `reject_writes_on_replica` 与 `add_retry_after` 之外，被拒绝的请求同样留下记录。
+/// 写入失败只打印告警，不影响已生成的响应。
+async fn record_access(
+    State(state): State<Arc<AppState>>,
+    matched: Option<MatchedPath>,
+    request: Request,
+    next: middleware::Next,
+) -> Response {
+    if !state.access_log_enabled {
+        return next.run(request).await;
+    }
+    let started = Instant::now();
+    let (parts, body) = request.into_parts();
+    let path = parts.uri.path().to_string();
+    let route = match &matched {
+        Some(matched) => matched.as_str().to_string(),
+        None => path.chars().take(256).collect(),
+    };
+    let api_key = api_key_of(&parts);
+    let auditor = auditor_of(&parts, &state).map(Auditors::principal_of);
+    let tenant = match &auditor {
+        Some(_) => Query::<AuditorTenantQuery>::try_from_uri(&parts.uri)
+            .ok()
+            .map(|Query(query)| query.tenant.unwrap_or_else(|| DEFAULT_TENANT.to_string())),
+        None => state.tenants.resolve(api_key).map(|tenant| tenant.id.clone()),
+    };
+    let principal = auditor.unwrap_or_else(|| principal_of(api_key));
+    let admin = parts
+        .headers
+        .get("x-admin-key")
+        .and_then(|v| v.to_str().ok())
+        .filter(|key| state.admins.contains(key))
+        .map(AdminKeys::principal_of);
+    let method = parts.method.to_string();
+
+    let response = next.run(Request::from_parts(parts, body)).await;
+
+    let pos = access_log::path_pos(&route, &path).or_else(|| response.extensions().get::<AccessedPos>().map(|pos| pos.0));
+    let entry = AccessLogEntry {
+        id: 0,
+        at: chrono::Utc::now().timestamp(),
+        tenant,
+        principal,
+        admin,
+        method,
+        route,
+        status: response.status().as_u16(),
+        latency_ms: started.elapsed().as_millis() as u64,
+        pos,
+    };
+    if let Err(e) = state.access_log.append(entry) {
+        warn!("⚠️  访问日志写入失败: {}", e);
+    }
+    response
+}
+
+/// 框架生成的错误 (路径 / 查询参数解析失败、路由未匹配、方法不允许等) 是纯文本，统一改写为 problem+json
+///
+/// 处理函数返回的 [`Problem`] 已是 problem+json，原样放行；原响应头 (如 `Allow`) 保留。
+async fn problem_for_plain_errors(response: Response) -> Response {
+    let status = response.status();
+    let is_problem = response
+        .headers()
+        .get(CONTENT_TYPE)
+        .is_some_and(|value| value.as_bytes().starts_with(PROBLEM_MEDIA_TYPE.as_bytes()));
+    if !(status.is_client_error() || status.is_server_error()) || is_problem {
+        return response;
+    }
+    let (parts, body) = response.into_parts();
+    let text = axum::body::to_bytes(body, 64 * 1024)
+        .await
+        .map(|bytes| String::from_utf8_lossy(&bytes).trim().to_string())
+        .unwrap_or_default();
+    let detail = if text.is_empty() { status.canonical_reason().unwrap_or_default().to_string() } else { text };
+
+    let mut response = Problem::from_status(status, detail).into_response();
+    for (name, value) in &parts.headers {
+        if name != CONTENT_TYPE && name != CONTENT_LENGTH {
+            response.headers_mut().append(name, value.clone());
+        }
+    }
+    response
+}
+
+/// 为 429 / 503 响应补上 `Retry-After`，提示客户端退避重试
+async fn add_retry_after(State(state): State<Arc<AppState>>, mut response: Response) -> Response {
+    let status = response.status();
+    if (status == StatusCode::TOO_MANY_REQUESTS || status == StatusCode::SERVICE_UNAVAILABLE)
+        && !response.headers().contains_key(RETRY_AFTER)
+    {
+        response.headers_mut().insert(RETRY_AFTER, HeaderValue::from(state.ingest.retry_after_secs()));
+    }
+    response
+}
+
+fn decode_hash(encoding: BinaryEncoding, text: &str) -> Result<[u8; 32], Problem> {
+    encoding.decode(text)
+        .ok()
+        .and_then(|v| <[u8; 32]>::try_from(v.as_slice()).ok())
+        .ok_or_else(|| Problem::bad_request(format!("Invalid 32-byte {} hash: {}", encoding, text)))
+}
+
+fn decode_signature(encoding: BinaryEncoding, text: &str) -> Result<ed25519_dalek::Signature, Problem> {
+    encoding.decode(text)
+        .ok()
+        .and_then(|v| ed25519_dalek::Signature::from_slice(&v).ok())
+        .ok_or_else(|| Problem::bad_request(format!("Invalid signature {}: {}", encoding, text)))
+}
+
+/// 签名密钥为主身份时，返回其证书链 (Hex DER)
+fn certificate_for(state: &AppState, public_key: &ed25519_dalek::VerifyingKey) -> Option<Vec<String>> {
+    state.certificate.as_ref()
+        .filter(|cert| cert.public_key == *public_key)
+        .map(|cert| cert.chain.to_hex())
+}
@@ -0,0 +1,260 @@
+use axum::http::{header::CONTENT_TYPE, HeaderValue, StatusCode};
+use axum::response::{IntoResponse, Response};
+use log::error;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+use crate::schema::SchemaError;
+
+/// 错误响应的媒体类型 (RFC 9457，即原 RFC 7807)
+pub const PROBLEM_MEDIA_TYPE: &str = "application/problem+json";
+
+/// 错误类型：`type` 字段为 `urn:yuanjing:problem:<slug>`，调用方据此分支，不要解析 `detail`
+///
+/// 已发布的 slug 不再改名；新的错误情形增加新的类型。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProblemType {
+    /// 参数或编码错误
+    BadRequest,
+    /// 请求体不符合 Schema，`errors` 列出各字段
+    ValidationFailed,
+    /// 缺少或无效的 API Key
+    Unauthorized,
+    /// 缺少或无效的管理员密钥，或管理接口未开启
+    Forbidden,
+    /// 签名密钥已吊销
+    KeyRevoked,
+    /// 模型不在白名单中
+    UnregisteredModel,
+    NotFound,
+    /// 证据原文已被清理或擦除
+    EvidenceGone,
+    /// nonce 重复 (重放)
+    Replay,
+    /// 与现有状态冲突 (已更正、已擦除、保全中等)
+    Conflict,
+    /// 存证管线已满
+    RateLimited,
+    /// 提交方因可疑提交模式被限流 (见 `abuse.rs`)
+    Throttled,
+    /// 暂时不可用 (如写线程队列已满)
+    Unavailable,
+    /// 服务端时钟未通过校验，拒绝签发
+    ClockUntrusted,
+    /// 只读副本不接受写入
+    ReadOnlyReplica,
+    /// 本节点未持有写租约
+    NotWriter,
+    /// 超时前确认的副本不足 `replication.ack_quorum`，回执不予返回
+    NotReplicated,
+    Internal,
+}
+
+impl ProblemType {
+    pub fn slug(self) -> &'static str {
+        match self {
+            Self::BadRequest => "bad-request",
+            Self::ValidationFailed => "validation-failed",
+            Self::Unauthorized => "unauthorized",
+            Self::Forbidden => "forbidden",
+            Self::KeyRevoked => "key-revoked",
+            Self::UnregisteredModel => "unregistered-model",
+            Self::NotFound => "not-found",
+            Self::EvidenceGone => "evidence-gone",
+            Self::Replay => "replay",
+            Self::Conflict => "conflict",
+            Self::RateLimited => "rate-limited",
+            Self::Throttled => "throttled",
+            Self::Unavailable => "unavailable",
+            Self::ClockUntrusted => "clock-untrusted",
+            Self::ReadOnlyReplica => "read-only-replica",
+            Self::NotWriter => "not-writer",
+            Self::NotReplicated => "not-replicated",
+            Self::Internal => "internal",
+        }
+    }
+
+    pub fn title(self) -> &'static str {
+        match self {
+            Self::BadRequest => "Bad request",
+            Self::ValidationFailed => "Request body does not match its schema",
+            Self::Unauthorized => "Missing or invalid API key",
+            Self::Forbidden => "Forbidden",
+            Self::KeyRevoked => "Signing key has been revoked",
+            Self::UnregisteredModel => "Model is not registered",
+            Self::NotFound => "Not found",
+            Self::EvidenceGone => "Evidence content is no longer available",
+            Self::Replay => "Replay detected",
+            Self::Conflict => "Conflicts with the current state",
+            Self::RateLimited => "Too many requests",
+            Self::Throttled => "Submitter is throttled",
+            Self::Unavailable => "Temporarily unavailable",
+            Self::ClockUntrusted => "Server clock is not trusted",
+            Self::ReadOnlyReplica => "Read-only replica",
+            Self::NotWriter => "Not the current writer",
+            Self::NotReplicated => "Not confirmed by a replica quorum",
+            Self::Internal => "Internal error",
+        }
+    }
+
+    pub fn status(self) -> StatusCode {
+        match self {
+            Self::BadRequest | Self::UnregisteredModel => StatusCode::BAD_REQUEST,
+            Self::ValidationFailed => StatusCode::UNPROCESSABLE_ENTITY,
+            Self::Unauthorized => StatusCode::UNAUTHORIZED,
+            Self::Forbidden | Self::KeyRevoked | Self::ReadOnlyReplica => StatusCode::FORBIDDEN,
+            Self::NotFound => StatusCode::NOT_FOUND,
+            Self::EvidenceGone => StatusCode::GONE,
+            Self::Replay | Self::Conflict => StatusCode::CONFLICT,
+            Self::RateLimited | Self::Throttled => StatusCode::TOO_MANY_REQUESTS,
+            Self::Unavailable | Self::ClockUntrusted | Self::NotWriter | Self::NotReplicated => StatusCode::SERVICE_UNAVAILABLE,
+            Self::Internal => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+
+    /// 只知道状态码时 (如框架生成的错误) 的通用类型
+    fn for_status(status: StatusCode) -> Self {
+        match status {
+            StatusCode::UNAUTHORIZED => Self::Unauthorized,
+            StatusCode::FORBIDDEN => Self::Forbidden,
+            StatusCode::NOT_FOUND => Self::NotFound,
+            StatusCode::CONFLICT => Self::Conflict,
+            StatusCode::UNPROCESSABLE_ENTITY => Self::ValidationFailed,
+            StatusCode::TOO_MANY_REQUESTS => Self::RateLimited,
+            StatusCode::SERVICE_UNAVAILABLE => Self::Unavailable,
+            status if status.is_server_error() => Self::Internal,
+            _ => Self::BadRequest,
+        }
+    }
+}
+
+/// 字段级错误 (请求体 Schema 校验)
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct FieldError {
+    /// 出错字段的 JSON Pointer，请求体本身为 `/`
+    pub pointer: String,
+    pub message: String,
+}
+
+impl From<SchemaError> for FieldError {
+    fn from(error: SchemaError) -> Self {
+        Self { pointer: error.pointer, message: error.message }
+    }
+}
+
+/// 错误响应正文 (problem+json)
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ProblemDetails {
+    /// `urn:yuanjing:problem:<slug>`
+    #[serde(rename = "type")]
+    pub problem_type: String,
+    pub title: String,
+    pub status: u16,
+    pub detail: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub trace_id: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub errors: Vec<FieldError>,
+}
+
+/// 模块：错误响应 (Problem Details)
+///
+/// **为什么需要**: 原先的错误是纯文本，调用方只能按状态码或匹配措辞来区分
+/// (同为 409 的重放与已更正、同为 403 的吊销与副本拒写)，措辞一改集成方就坏。
+/// 现在所有错误都以 `application/problem+json` 返回，`type` 是稳定的机器可读类型，
+/// `detail` 仅供人阅读；Schema 校验失败时 `errors` 逐条给出字段位置。
+///
+/// `trace_id` 用于关联服务端记录：请求已开启链路追踪时为其 trace ID，否则为随机生成的 ID
+/// (5xx 错误会连同该 ID 打印到服务端日志)。
+#[derive(Debug, Clone)]
+pub struct Problem {
+    kind: ProblemType,
+    status: StatusCode,
+    detail: String,
+    trace_id: Option<String>,
+    errors: Vec<FieldError>,
+}
+
+impl Problem {
+    pub fn new(kind: ProblemType, detail: impl Into<String>) -> Self {
+        Self { kind, status: kind.status(), detail: detail.into(), trace_id: None, errors: Vec::new() }
+    }
+
+    /// 只有状态码与纯文本说明的错误 (框架的提取器拒绝、路由未匹配等)
+    pub fn from_status(status: StatusCode, detail: impl Into<String>) -> Self {
+        Self { status, ..Self::new(ProblemType::for_status(status), detail) }
+    }
+
+    pub fn bad_request(detail: impl Into<String>) -> Self {
+        Self::new(ProblemType::BadRequest, detail)
+    }
+
+    pub fn not_found(detail: impl Into<String>) -> Self {
+        Self::new(ProblemType::NotFound, detail)
+    }
+
+    pub fn conflict(detail: impl Into<String>) -> Self {
+        Self::new(ProblemType::Conflict, detail)
+    }
+
+    /// 内部错误 (存储、任务等)，常用作 `map_err(Problem::internal)`
+    pub fn internal(error: impl fmt::Display) -> Self {
+        Self::new(ProblemType::Internal, error.to_string())
+    }
+
+    pub fn with_errors(mut self, errors: Vec<FieldError>) -> Self {
+        self.errors = errors;
+        self
+    }
+
+    pub fn with_trace_id(mut self, trace_id: Option<String>) -> Self {
+        self.trace_id = trace_id;
+        self
+    }
+
+    pub fn kind(&self) -> ProblemType {
+        self.kind
+    }
+
+    pub fn status(&self) -> StatusCode {
+        self.status
+    }
+
+    pub fn detail(&self) -> &str {
+        &self.detail
+    }
+
+    /// 响应正文 (也用于后台任务的失败原因)
+    pub fn to_details(&self) -> ProblemDetails {
+        ProblemDetails {
+            problem_type: format!("urn:yuanjing:problem:{}", self.kind.slug()),
+            title: self.kind.title().to_string(),
+            status: self.status.as_u16(),
+            detail: self.detail.clone(),
+            trace_id: self.trace_id.clone(),
+            errors: self.errors.clone(),
+        }
+    }
+}
+
+impl fmt::Display for Problem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} ({}): {}", self.kind.title(), self.status, self.detail)
+    }
+}
+
+impl IntoResponse for Problem {
+    fn into_response(self) -> Response {
+        let trace_id = self.trace_id.clone().unwrap_or_else(|| format!("{:032x}", rand::random::<u128>()));
+        if self.status.is_server_error() {
+            error!("❗ [{}] {}: {}", trace_id, self.kind.title(), self.detail);
+        }
+        let status = self.status;
+        let details = self.with_trace_id(Some(trace_id)).to_details();
+        let body = serde_json::to_vec(&details).unwrap_or_default();
+        let mut response = (status, body).into_response();
+        response.headers_mut().insert(CONTENT_TYPE, HeaderValue::from_static(PROBLEM_MEDIA_TYPE));
+        response
+    }
+}
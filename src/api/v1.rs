@@ -0,0 +1,1060 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use super::ProblemDetails;
+use crate::{
+    abuse::AbuseFinding,
+    access_log::AccessLogEntry,
+    analytics::TrendInterval,
+    anomaly::{AnomalyFlag, AnomalyStatus},
+    acl::EvidenceAcl,
+    challenge::LivenessStatement,
+    custody::{CustodyAction, CustodyRecord},
+    enclave::EnclaveAttestation,
+    encoding::BinaryEncoding,
+    evidence::{CanonicalEncoding, ClientContext, ConsensusRule, Evidence},
+    hdkey::KeyEndorsement,
+    ingest::Priority,
+    legal_hold::{HoldEvent, LegalHold},
+    mmr_store::{CommitmentMode, ErasureRecord},
+    model_registry::{AcceptedTargets, SignedRootMetadata},
+    notary::{NotaryReceipt, TimestampReceipt},
+    rekor::RekorEntry,
+    ops_log::OpsLogEntry,
+    prompts::{PromptInfo, PromptSet},
+    publication::{FeedFormat, SignedTreeHead},
+    qr::QrFormat,
+    receipt::Receipt,
+    review::{ReviewOpinion, ReviewRecord},
+    report::ReportFormat,
+    search::SearchHit,
+    signing_log::{CheckpointLocation, SigningLogEntry},
+    tags::EvidenceTagSet,
+    zk::ZkStatement,
+};
+
+/// API v1 的路径前缀
+pub const PREFIX: &str = "/v1";
+
+/// 去掉路由模板的版本前缀 (未带前缀的旧路由原样返回)
+pub fn unversioned(route: &str) -> &str {
+    route.strip_prefix(PREFIX).unwrap_or(route)
+}
+
+// 请求：提交证据
+#[derive(Serialize, Deserialize, JsonSchema)]
+pub struct ProveRequest {
+    // 实际场景中这里也是 Mock 的，前端发来图片路径
+    pub image_path: String,
+    
+    // 模拟的 AI 参数（如果王嗣萱的模块调用，这里就是真实 AI 结果）
+    pub verdict: bool,
+    pub confidence: f64,
+    pub source: String, // 来源说明
+    /// AI model version hash; must be pre-registered via `/model/register`
+    pub prompt_pool_hash: String,
+    /// 客户端一次性提交标识 (可选)，同一 nonce 重复提交将被视为重放
+    #[serde(default)]
+    pub nonce: Option<String>,
+    /// 使用哪个部门 / 设备的派生密钥签名 (可选)，缺省使用租户根密钥
+    #[serde(default)]
+    pub department: Option<String>,
+    /// 本条所更正 (取代) 的早先叶子位置 (可选)，须为更正链上最新的一条
+    #[serde(default)]
+    pub supersedes: Option<u64>,
+    /// 优先级：`urgent` (有时限的提交)、`normal` (缺省) 或 `bulk` (批量采集)
+    #[serde(default)]
+    pub priority: Priority,
+    /// 叶子承诺方式：`default` (按租户配置) 或 `blinded` (盐值只在本响应中返回，服务端不保存原文与盐值)
+    #[serde(default)]
+    pub commitment: CommitmentMode,
+    /// 提交方声明的采集 / 提交时间 (Unix 秒，可选)，原样记入证据的 `claimed_at` 并随证据签名；
+    /// 服务端的分析时间与签发时间另行记录，不以此为准
+    #[serde(default)]
+    pub claimed_at: Option<i64>,
+    /// 多个检测模型各自的判定 (可选，至少两个)；给出时顶层 `verdict` 须与按共识规则合成的结果一致，
+    /// 证据的置信度取合成结果，各模型的判定一并记入证据的 `consensus`
+    #[serde(default)]
+    pub models: Vec<ModelVote>,
+    /// 共识规则 (可选)，缺省按 `[policy] consensus_rule`；仅与 `models` 一起使用
+    #[serde(default)]
+    pub consensus_rule: Option<ConsensusRule>,
+    /// 模型校准信息 (可选)，记入证据的 `calibration` 并随证据签名
+    #[serde(default)]
+    pub calibration: Option<CalibrationParams>,
+    /// 提交方上下文 (可选)：字符串键值对，如工单号、流水线 ID；按键排序后记入证据的 `client_context` 并随证据签名
+    #[serde(default)]
+    pub client_context: Option<ClientContext>,
+}
+
+// 请求：判定所用的模型校准信息
+#[derive(Serialize, Deserialize, JsonSchema)]
+pub struct CalibrationParams {
+    /// 校准版本 (如温度缩放参数的发布标识)
+    pub version: String,
+    /// 温度缩放系数 (正数，记到千分位)
+    pub temperature: f64,
+    /// 把 SAPT 分数转为判定的决策阈值，范围 `[0.0, 1.0]` (记到万分位)
+    pub threshold: f64,
+}
+
+// 请求：多模型共识中单个模型的判定
+#[derive(Serialize, Deserialize, JsonSchema)]
+pub struct ModelVote {
+    /// 该模型的 Prompt 池哈希，须已登记
+    pub prompt_pool_hash: String,
+    pub verdict: bool,
+    /// `[0, 1]` 的比例，同顶层 `confidence`
+    pub confidence: f64,
+}
+
+// 响应：存证回执
+#[derive(Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ProveReceipt {
+    pub root_hash: String, // 按 binary_encoding 编码
+    pub leaf_pos: u64,
+    pub signature: String, // 按 binary_encoding 编码
+    pub evidence_dump: Evidence, // 返回完整证据包供核对 (签名内容，字段编码固定)
+    pub receipt: Receipt, // 位置绑定：叶子哈希 + 位置 + 根 + 树大小 (签名内容，固定为 Hex)
+    pub receipt_signature: String, // 按 binary_encoding 编码，对 receipt 的签名
+    pub certificate_chain: Option<Vec<String>>, // 签名密钥的 X.509 证书链 (Hex DER)，未配置时为 null
+    pub verification_method: Option<String>, // 签名公钥在 DID 文档中的验证方法 ID
+    /// 叶子盐值 (按 `binary_encoding` 编码)，仅加盐叶子；验证时须一并提供
+    #[serde(default)]
+    pub salt: Option<String>,
+    /// 本响应中哈希、签名与盐值的编码 (见 `?encoding=`)
+    #[serde(default)]
+    pub binary_encoding: BinaryEncoding,
+    /// 内容寻址的证据 ID (Hex，Blake3(证据规范字节))，可用 `/evidence/by-id/{id}` 查找
+    #[serde(default)]
+    pub evidence_id: String,
+    /// 多数派确认的结果；未开启 `replication.ack_quorum` 时为 null (确认不足时不返回回执，见 `not-replicated`)
+    #[serde(default)]
+    pub replication: Option<ReplicationAck>,
+}
+
+/// 回执的多数派确认结果
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ReplicationAck {
+    /// 返回时已确认包含该叶子的副本数，不小于 `required`
+    pub confirmed: usize,
+    /// 需要确认的副本数 (`replication.ack_quorum`)
+    pub required: usize,
+}
+
+// 响应：后台存证任务已受理 (202)
+#[derive(Serialize, Deserialize)]
+pub struct AsyncProveResponse {
+    pub job_id: String,
+    pub status: JobStage,
+    /// 轮询任务状态的地址 (同 `Location` 头)
+    pub status_url: String,
+}
+
+/// 后台存证任务的阶段，按顺序推进；`completed` 与 `failed` 为终态
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum JobStage {
+    Queued,
+    /// 计算图片 / 视频指纹
+    Fingerprinting,
+    /// 组装模型判定结果
+    Inference,
+    /// 签名并入库
+    Signing,
+    /// 已追加到 MMR，等待回执 (如多数派确认)
+    Appended,
+    Completed,
+    Failed,
+}
+
+impl JobStage {
+    pub fn is_finished(self) -> bool {
+        matches!(self, Self::Completed | Self::Failed)
+    }
+}
+
+// 响应：后台存证任务的状态
+#[derive(Clone, Serialize, Deserialize)]
+pub struct JobResponse {
+    pub job_id: String,
+    pub status: JobStage,
+    pub created_at: i64,
+    pub updated_at: i64,
+    /// 已追加的叶子位置 (进入 `appended` 阶段后)；失败时若已入库仍会给出
+    pub leaf_pos: Option<u64>,
+    /// 完成后的存证回执，与 `/prove` 的响应相同
+    pub receipt: Option<ProveReceipt>,
+    /// 失败原因 (problem+json 正文)
+    pub error: Option<ProblemDetails>,
+}
+
+// 请求：验证回执
+#[derive(Deserialize, JsonSchema)]
+pub struct VerifyRequest {
+    pub evidence: Evidence,
+    pub receipt: Receipt,
+    pub receipt_signature: String, // 按 binary_encoding 编码
+    /// 签名密钥的证书链 (Hex DER，可选)；缺省时使用服务端配置的证书链
+    #[serde(default)]
+    pub certificate_chain: Option<Vec<String>>,
+    /// 叶子盐值 (按 `binary_encoding` 编码)，加盐叶子必填
+    #[serde(default)]
+    pub salt: Option<String>,
+    /// `receipt_signature` 与 `salt` 的编码，缺省为 Hex；`/prove` 响应中的同名字段可原样带回
+    #[serde(default)]
+    pub binary_encoding: BinaryEncoding,
+}
+
+// 响应：验证结果
+#[derive(Serialize)]
+pub struct VerifyResponse {
+    pub valid: bool,
+    pub evidence_hash_match: bool,   // evidence 的叶子哈希是否等于 receipt.evidence_hash
+    pub receipt_signature_valid: bool,
+    pub inclusion_valid: bool,       // 叶子是否位于 tree_size 时刻的树中且根一致
+    pub key_revoked: bool,           // 签名密钥在回执时间点是否已被吊销
+    pub certificate_valid: Option<bool>, // 证书链是否可追溯到受信根；无证书链或未配置受信根时为 null
+    pub model_registry_valid: Option<bool>, // 回执签发时模型是否由签名的注册表批准；未启用签名模式或回执早于首个目标元数据时为 null
+}
+
+// 请求：批量验证证据签名
+#[derive(Deserialize, JsonSchema)]
+pub struct VerifySignaturesRequest {
+    pub items: Vec<SignatureCheck>,
+    /// `signature` 与 `key_id` 的编码，缺省为 Hex
+    #[serde(default)]
+    pub binary_encoding: BinaryEncoding,
+}
+
+/// 一条待验证的证据签名 (回执的 `evidence_dump` 与 `signature`)
+#[derive(Deserialize, JsonSchema)]
+pub struct SignatureCheck {
+    pub evidence: Evidence,
+    pub signature: String,
+    /// 签名公钥 (与签名日志的 `key_id` 相同，见 `/keys`)
+    pub key_id: String,
+    /// 签名所基于的规范编码 (回执的 `encoding`)，缺省为 `bcs`
+    #[serde(default)]
+    pub encoding: CanonicalEncoding,
+}
+
+// 响应：批量验证结果
+#[derive(Serialize, Deserialize)]
+pub struct VerifySignaturesResponse {
+    /// 全部签名有效
+    pub all_valid: bool,
+    /// 逐条结果，与请求的 `items` 一一对应
+    pub valid: Vec<bool>,
+    /// 无效签名在 `items` 中的下标
+    pub invalid: Vec<usize>,
+}
+
+// 请求：审计接口中的位置是 MMR 位置还是叶子序号
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PositionKind {
+    /// MMR 位置 (默认，即回执的 `leaf_pos`)
+    #[default]
+    MmrPos,
+    /// 从 0 开始连续编号的叶子序号
+    LeafIndex,
+}
+
+// 请求：在历史树上开具证明 (可选)
+#[derive(Deserialize)]
+pub struct AuditQuery {
+    /// 缺省为当前树；传入回执的 `tree_size` 即可对照回执中签名的根校验
+    pub tree_size: Option<u64>,
+    /// 按历史根 (Hex) 开具证明：在当前根与根发布存档中查找对应的树大小，可与 `tree_size` 同时给出以互相核对
+    pub root: Option<String>,
+    /// 路径中的数字按何种位置解释，缺省为 `mmr_pos`
+    #[serde(default)]
+    pub position: PositionKind,
+}
+
+// 请求：输出编码 (哈希、签名、公钥)
+#[derive(Deserialize)]
+pub struct EncodingQuery {
+    /// `hex` (默认)、`base64` 或 `multibase`
+    pub encoding: Option<String>,
+}
+
+// 请求：回执二维码
+#[derive(Deserialize)]
+pub struct QrQuery {
+    /// `svg` (默认) 或 `png`
+    #[serde(default)]
+    pub format: QrFormat,
+}
+
+// 请求：鉴定报告
+#[derive(Deserialize)]
+pub struct ReportQuery {
+    /// `html` (默认) 或 `pdf`
+    #[serde(default)]
+    pub format: ReportFormat,
+}
+
+// 请求：树头订阅源
+#[derive(Deserialize)]
+pub struct RootFeedQuery {
+    /// 租户 ID，缺省为默认租户
+    pub tenant: Option<String>,
+    /// `atom` (默认) 或 `rss`
+    #[serde(default)]
+    pub format: FeedFormat,
+    /// 条目数，默认 50，最多 500
+    pub limit: Option<usize>,
+}
+
+// 请求：公证回执列表
+#[derive(Deserialize)]
+pub struct NotarizationsQuery {
+    /// 租户 ID，缺省为默认租户
+    pub tenant: Option<String>,
+    /// 条数，默认 50，最多 500
+    pub limit: Option<usize>,
+}
+
+// 请求：Rekor 收录记录列表
+#[derive(Deserialize)]
+pub struct RekorEntriesQuery {
+    /// 租户 ID，缺省为默认租户
+    pub tenant: Option<String>,
+    /// 条数，默认 50，最多 500
+    pub limit: Option<usize>,
+}
+
+// 请求：按时间查找签名树头
+#[derive(Deserialize)]
+pub struct SthAtQuery {
+    /// 租户 ID，缺省为默认租户
+    pub tenant: Option<String>,
+    /// 查询时刻 (Unix 秒)
+    pub ts: i64,
+}
+
+// 请求：复制拉取 (只读副本 → 主库)
+#[derive(Deserialize)]
+pub struct ReplicationQuery {
+    /// 副本当前的 MMR 大小，从它之后的第一片叶子开始导出
+    pub from: u64,
+    /// 叶子数，默认与最大值均为 1000
+    pub limit: Option<usize>,
+    /// 没有新叶子时在主库等待的毫秒数 (长轮询，至多 10000)，默认不等待
+    pub wait_ms: Option<u64>,
+}
+
+// 响应：Merkle Proof
+#[derive(Serialize, Deserialize)]
+pub struct AuditResponse {
+    pub proof_valid: bool, // 仅作为标记，实际验证在客户端
+    pub leaf_pos: u64,
+    /// 叶子序号 (从 0 开始连续编号)
+    #[serde(default)]
+    pub leaf_index: Option<u64>,
+    pub proof_hex: Vec<String>, // proof path 数组，按 binary_encoding 编码 (字段名沿用旧称)
+    /// 证明所在树的大小 (MMR 节点总数)
+    pub tree_size: u64,
+    /// 该树的根 (按 `binary_encoding` 编码)
+    pub root: String,
+    #[serde(default)]
+    pub binary_encoding: BinaryEncoding,
+    /// 根发布存档中该树大小的签名树头；未签出过该大小时为 null (此时以回执中签名的根为准)
+    #[serde(default)]
+    pub signed_tree_head: Option<SignedTreeHead>,
+}
+
+// 响应：链上校验用的证明 (EVM 惯例：`0x` 前缀 Hex，不随 binary_encoding 变化)
+#[derive(Serialize, Deserialize)]
+pub struct SolidityProofResponse {
+    /// 树大小 (MMR 节点总数)，即合约参数 `mmrSize`
+    pub mmr_size: u64,
+    pub leaf_pos: u64,
+    /// 叶子序号 (从 0 开始连续编号)
+    #[serde(default)]
+    pub leaf_index: Option<u64>,
+    /// 叶子哈希 (`leaf`)
+    pub leaf: String,
+    pub proof: Vec<String>,
+    pub root: String,
+    /// `verifyInclusion(mmrSize, pos, leaf, proof, root)` 的调用数据
+    pub inclusion_calldata: String,
+    /// `verifyAnchored(mmrSize, pos, leaf, proof)` 的调用数据
+    pub anchored_calldata: String,
+    /// `anchorRoot(mmrSize, root)` 的调用数据 (合约 owner 锚定该根时使用)
+    pub anchor_calldata: String,
+}
+
+// 请求：审计方读取原文或证明时指定租户 (请求头 `X-Auditor-Key`)
+#[derive(Deserialize)]
+pub struct AuditorTenantQuery {
+    /// 缺省为默认租户
+    pub tenant: Option<String>,
+}
+
+// 请求：导出加密证据包
+#[derive(Deserialize)]
+pub struct SealedBundleQuery {
+    /// 收件人 X25519 公钥 (64 位 Hex)，多个用逗号分隔
+    pub recipients: String,
+}
+
+// 请求：校验零知识披露证明 (请求体为 `application/vnd.yuanjing.zkproof` 二进制证明)
+#[derive(Deserialize)]
+pub struct ZkVerifyQuery {
+    /// 核对根所用的租户，缺省为默认租户
+    pub tenant: Option<String>,
+}
+
+// 响应：零知识披露证明校验结果
+#[derive(Serialize, Deserialize)]
+pub struct ZkVerifyResponse {
+    /// 证明对其陈述成立
+    pub valid: bool,
+    pub tenant_id: String,
+    /// 陈述中的根确为该租户日志在 `tree_size` 时的根
+    pub root_matches: bool,
+    pub statement: ZkStatement,
+}
+
+// 请求：批量审计证明
+#[derive(Serialize, Deserialize, JsonSchema)]
+pub struct BatchAuditRequest {
+    /// 要抽查的叶子位置 (与 `leaf_indexes` 合计至多 1000 个，顺序与重复无关)
+    #[serde(default)]
+    pub positions: Vec<u64>,
+    /// 也可以按叶子序号 (从 0 开始连续编号) 指定，与 `positions` 合并
+    #[serde(default)]
+    pub leaf_indexes: Vec<u64>,
+    /// 缺省为当前树
+    #[serde(default)]
+    pub tree_size: Option<u64>,
+    /// 按历史根 (Hex) 指定树，见 `/audit/{pos}` 的 `root`
+    #[serde(default)]
+    pub root: Option<String>,
+}
+
+// 响应：批量审计证明 (一条合并的证明路径覆盖全部叶子)
+#[derive(Serialize, Deserialize)]
+pub struct BatchAuditResponse {
+    /// 按位置升序、去重后的叶子
+    pub leaves: Vec<AuditLeaf>,
+    pub proof_hex: Vec<String>,
+    pub tree_size: u64,
+    pub root: String,
+    /// `proof_hex`、`root` 与叶子哈希的编码
+    #[serde(default)]
+    pub binary_encoding: BinaryEncoding,
+    /// 根发布存档中该树大小的签名树头，见 `AuditResponse`
+    #[serde(default)]
+    pub signed_tree_head: Option<SignedTreeHead>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct AuditLeaf {
+    pub pos: u64,
+    #[serde(default)]
+    pub leaf_index: Option<u64>,
+    /// 叶子哈希 (按所在响应的 `binary_encoding` 编码)
+    pub leaf_hash: String,
+}
+
+/// 证据原文的保存状态
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EvidenceStatus {
+    Available,
+    /// 原文已被保留期清理；叶子哈希、回执与包含性证明仍在，可凭留存的原文离线验证
+    Pruned,
+    /// 盐值已销毁 (密码学擦除)；叶子哈希与回执仍在，但已无法与任何原文对应
+    Erased,
+    /// 盲化叶子：原文与盐值从未落盘，只在提交方手中；凭二者可离线验证叶子哈希与回执
+    Blinded,
+}
+
+// 响应：已入库的证据 (仅签名入库的条目带有回执)
+#[derive(Serialize, Deserialize)]
+pub struct EvidenceRecordResponse {
+    pub leaf_pos: u64,
+    pub status: EvidenceStatus,
+    /// 证据 ID (Hex)；已擦除、已清理的加盐叶子为 null
+    #[serde(default)]
+    pub evidence_id: Option<String>,
+    /// 叶子哈希 (按 `binary_encoding` 编码)
+    pub leaf_hash: String,
+    /// 证据原文，已清理时为 null
+    pub evidence: Option<Evidence>,
+    /// 清理时间 (Unix 秒)
+    pub pruned_at: Option<i64>,
+    /// 叶子盐值 (按 `binary_encoding` 编码)，仅未擦除的加盐叶子
+    pub salt: Option<String>,
+    /// 擦除记录，仅已擦除的条目
+    pub erasure: Option<ErasureRecord>,
+    /// 生效中的诉讼保全
+    pub legal_hold: Option<LegalHold>,
+    /// 激活路径异常标记 (见 `/anomalies`)，未被标记或在只读副本上为 null
+    #[serde(default)]
+    pub anomaly: Option<AnomalyFlag>,
+    /// 取代本条的更正叶子位置，未被更正时为 null
+    pub superseded_by: Option<u64>,
+    /// 更正链上当前有效的条目位置 (未被更正时即本条)
+    pub latest_pos: u64,
+    /// 证据签名 (按 `binary_encoding` 编码)
+    pub evidence_signature: Option<String>,
+    /// 回执 (签名内容，固定为 Hex)
+    pub receipt: Option<Receipt>,
+    /// 回执签名 (按 `binary_encoding` 编码)
+    pub receipt_signature: Option<String>,
+    pub certificate_chain: Option<Vec<String>>,
+    pub verification_method: Option<String>,
+    #[serde(default)]
+    pub binary_encoding: BinaryEncoding,
+}
+
+// 响应：更正链 (按先后顺序，最后一条为当前有效的结论)
+#[derive(Serialize, Deserialize)]
+pub struct SupersessionChainResponse {
+    pub latest_pos: u64,
+    pub entries: Vec<EvidenceRecordResponse>,
+}
+
+// 响应：最新签名树头 (按租户 ID 排序，空树的租户不在其中)
+#[derive(Serialize, Deserialize)]
+pub struct PublishedRootsResponse {
+    pub tree_heads: Vec<SignedTreeHead>,
+}
+
+// 响应：某一时刻仍为最新的签名树头
+#[derive(Serialize, Deserialize)]
+pub struct SthAtResponse {
+    pub tenant_id: String,
+    /// 查询时刻 (Unix 秒)
+    pub ts: i64,
+    /// 签名时间不晚于 `ts` 的最后一个存档树头
+    pub signed_tree_head: SignedTreeHead,
+    /// 下一个存档树头的签名时间 (即该树头不再是最新的时刻)；之后再无存档为 null
+    pub superseded_at: Option<i64>,
+}
+
+// 响应：外部公证方的副署与 TSA 时间戳 (各自树大小大的在前)
+#[derive(Serialize, Deserialize)]
+pub struct NotarizationsResponse {
+    pub tenant_id: String,
+    /// 副署协议的公证回执
+    pub receipts: Vec<NotaryReceipt>,
+    /// RFC 3161 时间戳回执
+    #[serde(default)]
+    pub timestamps: Vec<TimestampReceipt>,
+}
+
+// 响应：Rekor 对树头的收录记录 (树大小大的在前)
+#[derive(Serialize, Deserialize)]
+pub struct RekorEntriesResponse {
+    pub tenant_id: String,
+    pub entries: Vec<RekorEntry>,
+}
+
+// 响应：租户公钥目录
+#[derive(Serialize, Deserialize)]
+pub struct KeysResponse {
+    pub tenants: Vec<TenantKeys>,
+    /// 公钥与背书签名的编码 (背书内容本身是签名内容，固定为 Hex)
+    #[serde(default)]
+    pub binary_encoding: BinaryEncoding,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct TenantKeys {
+    pub tenant_id: String,
+    /// 租户根公钥 (按 `binary_encoding` 编码)
+    pub public_key: String,
+    /// 根公钥的短指纹 (`XXXX-XXXX-XXXX-XXXX`)，供人工核对
+    pub fingerprint: String,
+    pub derived_keys: Vec<DerivedKeyInfo>,
+}
+
+// 派生公钥及根密钥对它的背书
+#[derive(Serialize, Deserialize)]
+pub struct DerivedKeyInfo {
+    pub name: String,
+    pub endorsement: KeyEndorsement,
+    pub endorsement_signature: String, // 按 binary_encoding 编码，租户根密钥对 endorsement 的签名
+}
+
+// 响应：服务身份 (主签名身份)
+#[derive(Serialize, Deserialize)]
+pub struct IdentityResponse {
+    pub public_key: String, // 主身份公钥，按 binary_encoding 编码
+    /// 主公钥的短指纹 (`XXXX-XXXX-XXXX-XXXX`)，电话或纸面核对时念这一串即可
+    pub fingerprint: String,
+    /// 主公钥识别图 (SVG) 的路径
+    pub identicon: String,
+    /// 主公钥在 DID 文档中的验证方法 ID (`did#z6Mk...`)，与回执的 `verification_method` 对应
+    pub key_id: String,
+    /// 签名 Seed 的来源: file | vault | keychain | tpm | enclave | piv
+    pub key_source: String,
+    /// 身份文件的生成时间 (Unix 秒)；Seed 来自 Vault、钥匙串、飞地或 PIV 令牌时为 null
+    pub key_created_at: Option<i64>,
+    pub did: String,
+    /// DID 文档的路径
+    pub did_document: String,
+    pub certificate_chain: Option<Vec<String>>, // 主身份的 X.509 证书链 (Hex DER)，未配置时为 null
+    /// 支持的规范编码 (回执 `encoding` 的取值)
+    pub canonical_encodings: Vec<CanonicalEncoding>,
+    /// 新租户采用的规范编码
+    pub default_canonical_encoding: CanonicalEncoding,
+    #[serde(default)]
+    pub binary_encoding: BinaryEncoding,
+    /// 飞地签名进程的远程证明 (`key_source = "enclave"` 时)，其余情况为 null
+    #[serde(default)]
+    pub enclave: Option<EnclaveAttestation>,
+}
+
+// 请求：挑战应答
+#[derive(Serialize, Deserialize, JsonSchema)]
+pub struct ChallengeRequest {
+    /// 审计方生成的随机 nonce (16-256 字节)
+    pub nonce: String,
+}
+
+// 响应：对 nonce + 当前根的签名
+#[derive(Serialize, Deserialize)]
+pub struct ChallengeResponse {
+    pub statement: LivenessStatement, // 签名内容，字段编码固定
+    pub signature: String, // 按 binary_encoding 编码，租户根密钥对 statement 的签名
+    pub public_key: String, // 租户根公钥，按 binary_encoding 编码
+    #[serde(default)]
+    pub binary_encoding: BinaryEncoding,
+}
+
+// 请求：分页读取签名日志
+#[derive(Deserialize)]
+pub struct SigningLogQuery {
+    #[serde(default)]
+    pub from: u64,
+    pub limit: Option<usize>,
+}
+
+// 响应：签名审计日志
+#[derive(Serialize)]
+pub struct SigningLogResponse {
+    /// 日志总条数
+    pub length: u64,
+    pub entries: Vec<SigningLogEntry>,
+    /// 已写入 MMR 的检查点 (可用 `/audit/{pos}` 取包含性证明)
+    pub checkpoints: Vec<CheckpointLocation>,
+}
+
+// 响应：运维审计日志的一条 (附本条哈希，下一条的 `prev_hash` 应等于它)
+#[derive(Serialize)]
+pub struct OpsLogItem {
+    #[serde(flatten)]
+    pub entry: OpsLogEntry,
+    pub hash: String,
+}
+
+// 响应：运维审计日志 (请求参数同签名日志)
+#[derive(Serialize)]
+pub struct OpsLogResponse {
+    /// 日志总条数
+    pub length: u64,
+    /// 当前链头 (最后一条的哈希，空日志为全零)
+    pub head: String,
+    pub entries: Vec<OpsLogItem>,
+}
+
+// 请求：查询访问日志 (时间为 Unix 秒，`since` 含、`until` 不含)
+#[derive(Deserialize)]
+pub struct AccessLogQuery {
+    pub since: Option<i64>,
+    pub until: Option<i64>,
+    pub tenant: Option<String>,
+    pub principal: Option<String>,
+    pub route: Option<String>,
+    pub pos: Option<u64>,
+    pub limit: Option<usize>,
+}
+
+// 请求：激活模式统计 (时间为回执时间，Unix 秒，`since` 含、`until` 不含)
+#[derive(Deserialize)]
+pub struct ActivationQuery {
+    pub since: Option<i64>,
+    pub until: Option<i64>,
+    /// 只统计该模型版本 (Prompt 池哈希)，并附上登记过的专家名称
+    pub model: Option<String>,
+    /// 趋势的时间粒度：`hour`、`day` (缺省) 或 `week`
+    #[serde(default)]
+    pub interval: TrendInterval,
+    /// 共同激活返回的组合数 (缺省 50，至多 1000)
+    pub top: Option<usize>,
+}
+
+// 响应：访问日志 (新的在前)
+#[derive(Serialize)]
+pub struct AccessLogResponse {
+    pub entries: Vec<AccessLogEntry>,
+}
+
+// 请求：密码学擦除
+#[derive(Serialize, Deserialize, JsonSchema)]
+pub struct ErasureRequest {
+    /// 擦除依据 (如数据主体的删除请求编号)，记入擦除记录
+    pub reason: String,
+}
+
+// 响应：擦除记录列表
+#[derive(Serialize, Deserialize)]
+pub struct ErasuresResponse {
+    pub erasures: Vec<ErasureRecord>,
+}
+
+// 请求：设置 / 解除诉讼保全
+#[derive(Serialize, Deserialize, JsonSchema)]
+pub struct LegalHoldRequest {
+    /// 依据 (如案号、解除保全的裁定)，记入保全历史
+    pub reason: String,
+}
+
+// 请求：替换证据的访问控制列表 (空列表撤销全部授权)
+#[derive(Serialize, Deserialize, JsonSchema)]
+pub struct EvidenceAclRequest {
+    /// 获准读取原文的审计方名称 (见 `[[api.auditors]]`)
+    pub auditors: Vec<String>,
+}
+
+// 响应：证据的访问控制列表
+#[derive(Serialize, Deserialize)]
+pub struct EvidenceAclResponse {
+    pub pos: u64,
+    /// 获准读取原文的审计方，未授权时为空
+    pub auditors: Vec<String>,
+    /// 最近一次设置的时间 (Unix 秒)，从未设置时为 null
+    pub updated_at: Option<i64>,
+    /// 最近一次设置者 (租户 API Key 指纹)
+    pub principal: Option<String>,
+}
+
+impl From<EvidenceAcl> for EvidenceAclResponse {
+    fn from(acl: EvidenceAcl) -> Self {
+        Self { pos: acl.pos, auditors: acl.auditors, updated_at: Some(acl.updated_at), principal: Some(acl.principal) }
+    }
+}
+
+// 请求：替换证据的标签 (空列表删除全部标签)
+#[derive(Serialize, Deserialize, JsonSchema)]
+pub struct EvidenceTagsRequest {
+    /// 标签 (不区分大小写，按小写保存)，如 `case:2026-017`、`batch/weibo`
+    pub tags: Vec<String>,
+}
+
+// 响应：证据的标签
+#[derive(Serialize, Deserialize)]
+pub struct EvidenceTagsResponse {
+    pub pos: u64,
+    /// 按字典序，未设置时为空
+    pub tags: Vec<String>,
+    /// 最近一次设置的时间 (Unix 秒)，从未设置时为 null
+    pub updated_at: Option<i64>,
+    /// 最近一次设置者 (租户 API Key 指纹)
+    pub principal: Option<String>,
+}
+
+impl From<EvidenceTagSet> for EvidenceTagsResponse {
+    fn from(set: EvidenceTagSet) -> Self {
+        Self { pos: set.pos, tags: set.tags, updated_at: Some(set.updated_at), principal: Some(set.principal) }
+    }
+}
+
+// 请求：列出证据 (排序、筛选与分页)
+#[derive(Deserialize)]
+pub struct EvidenceListQuery {
+    /// 只列出带该标签的证据
+    pub tag: Option<String>,
+    /// 排序，如 `-confidence,-timestamp` (前缀 `-` 为降序)，缺省按位置升序
+    pub sort: Option<String>,
+    /// 只列出该判定的证据
+    pub verdict: Option<bool>,
+    /// 只列出该模型版本 (Prompt 池哈希) 的证据
+    pub model: Option<String>,
+    /// 置信度下限 / 上限 (`[0, 1]` 的比例，含)
+    pub min_confidence: Option<f64>,
+    pub max_confidence: Option<f64>,
+    /// 按位置排序时从该位置 (含) 开始 (降序时为上界)
+    pub from: Option<u64>,
+    /// 按回执时间或置信度排序时，上一页返回的 `next_cursor`
+    pub cursor: Option<String>,
+    pub limit: Option<usize>,
+}
+
+// 响应：证据列表的一条
+#[derive(Serialize, Deserialize)]
+pub struct EvidenceListEntry {
+    pub pos: u64,
+    /// 回执时间 (Unix 秒)
+    pub timestamp: i64,
+    /// 判定、置信度 (万分比) 与模型版本；盲化或索引建立前已清理、擦除的证据为 null
+    pub verdict: Option<bool>,
+    pub confidence: Option<u16>,
+    pub model: Option<String>,
+    pub tags: Vec<String>,
+}
+
+// 响应：证据列表
+#[derive(Serialize, Deserialize)]
+pub struct EvidenceListResponse {
+    pub entries: Vec<EvidenceListEntry>,
+    /// 按位置排序时下一页的 `from`；没有更多时为 null
+    pub next: Option<u64>,
+    /// 按回执时间或置信度排序时下一页的 `cursor`；没有更多时为 null
+    pub next_cursor: Option<String>,
+}
+
+// 请求：全文检索证据元数据
+#[derive(Deserialize)]
+pub struct SearchQuery {
+    /// 查询 (tantivy 查询语法)，如 `微博 截图`、`tags:batch`、`"case:2026-017"`
+    pub q: String,
+    pub limit: Option<usize>,
+    /// 跳过前若干条命中 (翻页)
+    #[serde(default)]
+    pub offset: usize,
+}
+
+// 响应：检索结果
+#[derive(Serialize, Deserialize)]
+pub struct SearchResponse {
+    pub query: String,
+    /// 命中总数
+    pub total: usize,
+    /// 按相关度降序
+    pub hits: Vec<SearchHit>,
+}
+
+// 请求：追加一条保管事件
+#[derive(Serialize, Deserialize, JsonSchema)]
+pub struct CustodyRequest {
+    pub action: CustodyAction,
+    /// 经手人 (如办案人员姓名或工号)
+    pub actor: String,
+    /// 接收方，`transferred` 时必填，其余动作不得填写
+    #[serde(default)]
+    pub recipient: Option<String>,
+    #[serde(default)]
+    pub note: String,
+}
+
+// 响应：一条保管事件及其在 MMR 中的叶子
+#[derive(Serialize, Deserialize)]
+pub struct CustodyEntry {
+    /// 保管记录叶子的位置 (可用 `/audit/{pos}` 取包含性证明)
+    pub leaf_pos: u64,
+    /// 叶子哈希 (Hex)，即下一条事件的 `prev_hash`
+    pub leaf_hash: String,
+    pub record: CustodyRecord,
+}
+
+// 响应：某条证据的完整保管链 (按事件先后)
+#[derive(Serialize, Deserialize)]
+pub struct CustodyResponse {
+    pub pos: u64,
+    pub events: Vec<CustodyEntry>,
+}
+
+// 请求：提交鉴定人签署的复核意见
+#[derive(Serialize, Deserialize, JsonSchema)]
+pub struct ReviewRequest {
+    /// 鉴定人名称 (见 `[[api.examiners]]`)
+    pub examiner: String,
+    pub opinion: ReviewOpinion,
+    #[serde(default)]
+    pub notes: String,
+    /// 鉴定人签署的时间 (Unix 秒)
+    pub assessed_at: i64,
+    /// 鉴定人对复核意见的签名 (Hex，见 `ReviewAssessment`)
+    pub signature: String,
+}
+
+// 响应：一条复核记录及其在 MMR 中的叶子
+#[derive(Serialize, Deserialize)]
+pub struct ReviewEntry {
+    /// 复核记录叶子的位置 (可用 `/audit/{pos}` 取包含性证明)
+    pub leaf_pos: u64,
+    /// 叶子哈希 (Hex)
+    pub leaf_hash: String,
+    pub record: ReviewRecord,
+}
+
+// 响应：某条证据的全部复核记录 (按收录先后)
+#[derive(Serialize, Deserialize)]
+pub struct ReviewsResponse {
+    pub pos: u64,
+    pub reviews: Vec<ReviewEntry>,
+}
+
+// 响应：某条证据的保全状态与完整历史
+#[derive(Serialize, Deserialize)]
+pub struct LegalHoldStatusResponse {
+    pub pos: u64,
+    /// 生效中的保全，未保全时为 null
+    pub hold: Option<LegalHold>,
+    pub history: Vec<HoldEvent>,
+}
+
+// 响应：全部生效中的保全
+#[derive(Serialize, Deserialize)]
+pub struct LegalHoldsResponse {
+    pub holds: Vec<LegalHold>,
+}
+
+// 请求：异常复核队列 (按位置升序)
+#[derive(Deserialize)]
+pub struct AnomalyQuery {
+    /// `open` (缺省)、`dismissed` 或 `confirmed`
+    #[serde(default)]
+    pub status: AnomalyStatus,
+    /// 只列出该模型版本 (Prompt 池哈希) 的标记
+    pub model: Option<String>,
+}
+
+// 响应：异常复核队列
+#[derive(Serialize, Deserialize)]
+pub struct AnomaliesResponse {
+    /// 已分析的叶子数 (更新的证据尚待下一轮分析)
+    pub scanned_leaves: u64,
+    pub flags: Vec<AnomalyFlag>,
+}
+
+// 请求：异常标记的复核结论
+#[derive(Serialize, Deserialize, JsonSchema)]
+pub struct AnomalyResolveRequest {
+    /// `dismissed` (正常) 或 `confirmed` (确认异常)
+    pub status: AnomalyStatus,
+    /// 复核说明
+    #[serde(default)]
+    pub note: String,
+}
+
+// 响应：本租户的可疑提交方 (按提交方、规则排序)
+#[derive(Serialize, Deserialize)]
+pub struct AbuseFindingsResponse {
+    pub findings: Vec<AbuseFinding>,
+}
+
+// 请求：解除提交方的限流
+#[derive(Serialize, Deserialize, JsonSchema)]
+pub struct AbuseReleaseRequest {
+    /// 提交方 (租户 API Key 指纹，见发现中的 `principal`)
+    pub principal: String,
+}
+
+// 响应：解除限流
+#[derive(Serialize, Deserialize)]
+pub struct AbuseReleaseResponse {
+    pub principal: String,
+    /// 清除的发现数 (0 表示该提交方没有发现)
+    pub cleared: usize,
+}
+
+// 请求：注册模型
+#[derive(Serialize, Deserialize, JsonSchema)]
+pub struct ModelRegisterRequest {
+    pub hash: String,
+    pub description: String,
+}
+
+// 响应：公开的 Schema 名称
+#[derive(Serialize, Deserialize)]
+pub struct SchemaListResponse {
+    pub schemas: Vec<String>,
+}
+
+/// `GET /schemas` 列出的名称，与 `get_schema` 的分支一一对应
+pub(super) const PUBLISHED_SCHEMAS: &[&str] = &[
+    "Evidence",
+    "Receipt",
+    "ProveRequest",
+    "ProveReceipt",
+    "EvidenceBundle",
+    "SealedBundle",
+    "VerifyRequest",
+    "VerifySignaturesRequest",
+    "ChallengeRequest",
+    "BatchAuditRequest",
+    "ModelRegisterRequest",
+    "PromptRegistryRequest",
+    "ErasureRequest",
+    "LegalHoldRequest",
+    "AnomalyResolveRequest",
+    "AbuseReleaseRequest",
+    "EvidenceAclRequest",
+    "EvidenceTagsRequest",
+    "CustodyRequest",
+    "ReviewRequest",
+    "ProblemDetails",
+];
+
+// 响应：注册成功
+#[derive(Serialize, Deserialize)]
+pub struct ModelRegisterResponse {
+    pub status: String,
+}
+
+// 请求：读取签名的模型注册表
+#[derive(Deserialize)]
+pub struct ModelRegistryQuery {
+    /// 租户 ID，缺省为默认租户
+    pub tenant: Option<String>,
+}
+
+// 响应：签名的模型注册表 (验证方据此离线核对签名链，见 `model_registry::verify_model_at`)
+#[derive(Serialize, Deserialize)]
+pub struct ModelRegistryResponse {
+    pub tenant_id: String,
+    /// 是否处于签名模式
+    pub enabled: bool,
+    /// 全部根元数据 (按版本升序，第一个为引导时的受信根)
+    pub roots: Vec<SignedRootMetadata>,
+    /// 全部被接受过的目标元数据 (按版本升序，最后一个为当前生效的)
+    pub targets: Vec<AcceptedTargets>,
+}
+
+// 响应：注册表元数据已接受
+#[derive(Serialize, Deserialize)]
+pub struct RegistryUpdateResponse {
+    /// `root` 或 `targets`
+    pub role: String,
+    pub version: u64,
+    pub expires: i64,
+}
+
+// 请求：整体替换模型版本的 Prompt 说明对照表
+#[derive(Serialize, Deserialize, JsonSchema)]
+pub struct PromptRegistryRequest {
+    /// 索引不得重复；空列表删除对照表
+    pub prompts: Vec<PromptInfo>,
+}
+
+// 响应：模型版本的 Prompt 说明对照表
+#[derive(Serialize, Deserialize)]
+pub struct PromptRegistryResponse {
+    pub prompt_pool_hash: String,
+    /// 按索引升序，从未登记时为空
+    pub prompts: Vec<PromptInfo>,
+    /// 最近一次设置的时间 (Unix 秒)，从未设置时为 null
+    pub updated_at: Option<i64>,
+    /// 最近一次设置者 (租户 API Key 指纹)
+    pub principal: Option<String>,
+}
+
+impl From<PromptSet> for PromptRegistryResponse {
+    fn from(set: PromptSet) -> Self {
+        Self {
+            prompt_pool_hash: set.prompt_pool_hash,
+            prompts: set.prompts,
+            updated_at: Some(set.updated_at),
+            principal: Some(set.principal),
+        }
+    }
+}
@@ -0,0 +1,178 @@
+use chacha20poly1305::aead::{Aead, KeyInit, Payload};
+use chacha20poly1305::{Key, XChaCha20Poly1305, XNonce};
+use rand::RngCore;
+use serde::Deserialize;
+use zeroize::Zeroizing;
+
+use crate::config::EncryptionConfig;
+use crate::mmr_store::DEFAULT_TENANT;
+use crate::vault;
+
+/// 证据主密钥的来源
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum EncryptionKeySource {
+    /// 不加密 (默认)
+    #[default]
+    None,
+    /// 环境变量 `EVIDENCE_MASTER_KEY` (64 位 Hex)
+    Env,
+    /// 密钥文件 (`store.encryption.key_file`，64 位 Hex)
+    File,
+    /// HashiCorp Vault KV v2 (`[store.encryption.vault]`)
+    Vault,
+}
+
+impl EncryptionKeySource {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::None => "none",
+            Self::Env => "env",
+            Self::File => "file",
+            Self::Vault => "vault",
+        }
+    }
+}
+
+impl std::str::FromStr for EncryptionKeySource {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> anyhow::Result<Self> {
+        match s.to_ascii_lowercase().as_str() {
+            "none" => Ok(Self::None),
+            "env" => Ok(Self::Env),
+            "file" => Ok(Self::File),
+            "vault" => Ok(Self::Vault),
+            other => Err(anyhow::anyhow!("Unknown evidence key source '{}', expected 'none', 'env', 'file' or 'vault'", other)),
+        }
+    }
+}
+
+/// 密文块的魔数：`YJE` + 格式版本 1
+const MAGIC: &[u8; 4] = b"YJE\x01";
+/// 魔数 (4) + 密钥 ID (8) + XChaCha20 随机数 (24)
+const HEADER_LEN: usize = 4 + 8 + 24;
+/// 租户数据密钥的 Blake3 派生上下文
+const KEY_CONTEXT: &str = "yuanjing-core evidence at-rest v1";
+/// 附加认证数据的域前缀，随后是 MMR 位置 (大端)
+const AAD_DOMAIN: &[u8] = b"yuanjing-core evidence blob v1";
+
+/// 模块：证据原文静态加密 (At-Rest Encryption)
+///
+/// **为什么需要**: 证据原文里有真实案件的取证细节 (图片指纹、来源说明、鉴定结论)，
+/// 数据库文件、备份或磁盘一旦外泄，明文就全部暴露。开启后 `evidence` 树与 WAL 中的原文
+/// 用 XChaCha20-Poly1305 加密落盘，MMR 节点、内容索引、回执与签名等哈希类数据仍是明文，
+/// 包含性证明、审计与复制不受影响。
+///
+/// - 主密钥来自配置 (`EVIDENCE_MASTER_KEY` 或密钥文件) 或 Vault KV，只在内存中；
+///   每个租户的数据密钥为 Blake3-KDF(主密钥 || 租户 ID)，租户之间的密文互不可解
+/// - 密文块 = `YJE\x01` || 密钥 ID (8) || 随机数 (24) || 密文，AAD 绑定 MMR 位置，密文挪到别的位置即解密失败
+/// - 不以魔数开头的块视为开启加密前写入的明文，照常读取；启动时由
+///   [`SledStore::ensure_evidence_encryption`](crate::mmr_store::SledStore::ensure_evidence_encryption) 一次性补加密
+pub struct EvidenceCipher {
+    master: Zeroizing<[u8; 32]>,
+    cipher: XChaCha20Poly1305,
+    key_id: [u8; 8],
+}
+
+impl EvidenceCipher {
+    /// 按主密钥派生默认租户的数据密钥，其他租户见 [`EvidenceCipher::for_tenant`]
+    pub fn new(master: Zeroizing<[u8; 32]>) -> Self {
+        Self::derive(master, DEFAULT_TENANT)
+    }
+
+    /// 按 `store.encryption` 加载主密钥；未开启时返回 None
+    pub fn load(config: &EncryptionConfig) -> anyhow::Result<Option<Self>> {
+        let master = match config.key_source {
+            EncryptionKeySource::None => return Ok(None),
+            EncryptionKeySource::Env => {
+                let value = Zeroizing::new(std::env::var("EVIDENCE_MASTER_KEY")
+                    .map_err(|_| anyhow::anyhow!("store.encryption.key_source is 'env' but EVIDENCE_MASTER_KEY is not set"))?);
+                parse_key(value.trim())?
+            }
+            EncryptionKeySource::File => {
+                let value = Zeroizing::new(std::fs::read_to_string(&config.key_file)
+                    .map_err(|e| anyhow::anyhow!("Cannot read evidence master key '{}': {}", config.key_file, e))?);
+                parse_key(value.trim())?
+            }
+            EncryptionKeySource::Vault => vault::fetch_key(&config.vault, "证据主密钥")?,
+        };
+        Ok(Some(Self::new(master)))
+    }
+
+    /// 同一主密钥下某个租户的数据密钥
+    pub fn for_tenant(&self, tenant_id: &str) -> Self {
+        Self::derive(self.master.clone(), tenant_id)
+    }
+
+    fn derive(master: Zeroizing<[u8; 32]>, tenant_id: &str) -> Self {
+        let mut material = Zeroizing::new(master.to_vec());
+        material.extend_from_slice(tenant_id.as_bytes());
+        let key = Zeroizing::new(blake3::derive_key(KEY_CONTEXT, &material));
+        let mut key_id = [0u8; 8];
+        key_id.copy_from_slice(&blake3::hash(key.as_slice()).as_bytes()[..8]);
+        Self { master, cipher: XChaCha20Poly1305::new(Key::from_slice(key.as_slice())), key_id }
+    }
+
+    /// 数据密钥的 ID (Blake3(数据密钥) 的前 8 字节)，写在每个密文块的头部
+    pub fn key_id(&self) -> [u8; 8] {
+        self.key_id
+    }
+
+    /// 加密某个位置的证据原文
+    pub fn seal(&self, pos: u64, plaintext: &[u8]) -> anyhow::Result<Vec<u8>> {
+        let mut nonce = [0u8; 24];
+        rand::rngs::OsRng.fill_bytes(&mut nonce);
+        let aad = aad(pos);
+        let ciphertext = self
+            .cipher
+            .encrypt(XNonce::from_slice(&nonce), Payload { msg: plaintext, aad: &aad })
+            .map_err(|_| anyhow::anyhow!("Evidence encryption failed at pos {}", pos))?;
+        let mut blob = Vec::with_capacity(HEADER_LEN + ciphertext.len());
+        blob.extend_from_slice(MAGIC);
+        blob.extend_from_slice(&self.key_id);
+        blob.extend_from_slice(&nonce);
+        blob.extend_from_slice(&ciphertext);
+        Ok(blob)
+    }
+
+    /// 解密某个位置的证据原文；明文块原样返回
+    pub fn open(&self, pos: u64, blob: &[u8]) -> anyhow::Result<Vec<u8>> {
+        if !is_sealed(blob) {
+            return Ok(blob.to_vec());
+        }
+        if blob[4..12] != self.key_id {
+            return Err(anyhow::anyhow!(
+                "Evidence at pos {} was encrypted with a different key (id {})",
+                pos,
+                hex::encode(&blob[4..12])
+            ));
+        }
+        let aad = aad(pos);
+        self.cipher
+            .decrypt(XNonce::from_slice(&blob[12..HEADER_LEN]), Payload { msg: &blob[HEADER_LEN..], aad: &aad })
+            .map_err(|_| anyhow::anyhow!("Evidence at pos {} failed authenticated decryption", pos))
+    }
+}
+
+/// 是否为密文块
+pub fn is_sealed(blob: &[u8]) -> bool {
+    blob.len() >= HEADER_LEN && blob.starts_with(MAGIC)
+}
+
+fn aad(pos: u64) -> Vec<u8> {
+    let mut aad = AAD_DOMAIN.to_vec();
+    aad.extend_from_slice(&pos.to_be_bytes());
+    aad
+}
+
+/// 主密钥为 64 位 Hex
+fn parse_key(value: &str) -> anyhow::Result<Zeroizing<[u8; 32]>> {
+    let bytes = Zeroizing::new(hex::decode(value).map_err(|_| anyhow::anyhow!("Evidence master key must be hex"))?);
+    let mut key = Zeroizing::new([0u8; 32]);
+    if bytes.len() != key.len() {
+        return Err(anyhow::anyhow!("Evidence master key must be 32 bytes, got {}", bytes.len()));
+    }
+    key.copy_from_slice(&bytes);
+    Ok(key)
+}
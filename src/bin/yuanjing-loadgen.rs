@@ -0,0 +1,443 @@
+use clap::Parser;
+use image::{Rgb, RgbImage};
+use rand::Rng;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use yuanjing_core::api::{ModelRegisterRequest, ProveReceipt, ProveRequest};
+use yuanjing_core::evidence::ClientContext;
+
+/// 进度输出间隔
+const PROGRESS_INTERVAL: Duration = Duration::from_secs(5);
+
+/// 合成图片中随机色块的数量
+const SYNTHETIC_BLOCKS: u32 = 12;
+
+/// 模块：合成负载压测 (yuanjing-loadgen)
+///
+/// **为什么需要**: 生产硬件的选型 (CPU 核数、磁盘、`durability` 取舍) 要靠实测：
+/// `benches/` 只量单个函数，量不到指纹、签名、单写者线程与 fsync 串起来之后的端到端延迟。
+/// 这里按给定的速率与并发向运行中的服务提交随机但合法的证据 (`/prove`)，并按比例抽查已提交条目的证明 (`/audit/{pos}`)，
+/// 结束后按接口输出吞吐与延迟分位数。
+///
+/// - 限速模式下延迟从“计划发出时间”起算：服务跟不上时排队的时间也计入，避免协同遗漏把 p99 算得好看；
+/// - 服务端按 `image_path` 读取图片，合成图片写在本机目录 (默认临时目录)，因此须与服务在同一台机器或共享目录上运行；
+/// - 图片池越小越容易触发 `[abuse] max_identical_images`，压测时按需调大池子或放宽阈值。
+#[derive(Parser)]
+#[command(name = "yuanjing-loadgen", version, about = "Synthetic load generator for the yuanjing-core API")]
+struct Cli {
+    /// 服务地址
+    #[arg(long, default_value = "http://localhost:3000")]
+    url: String,
+    /// 以 `Authorization: Bearer` 携带的 API Key (决定租户)
+    #[arg(long)]
+    api_key: Option<String>,
+    /// 目标请求速率 (次/秒，所有并发合计)；0 为不限速，各并发连续发送
+    #[arg(long, default_value_t = 50.0)]
+    rps: f64,
+    /// 并发连接数
+    #[arg(long, default_value_t = 8)]
+    concurrency: usize,
+    /// 压测时长 (秒)
+    #[arg(long, default_value_t = 30)]
+    duration: u64,
+    /// 总请求数上限 (先到者为准)
+    #[arg(long)]
+    requests: Option<u64>,
+    /// `/audit/{pos}` 占全部请求的比例 (0 ~ 1)，其余为 `/prove`
+    #[arg(long, default_value_t = 0.5)]
+    audit_ratio: f64,
+    /// 证据使用的模型哈希，启动时自动注册
+    #[arg(long, default_value = "loadgen")]
+    model: String,
+    /// 使用已有图片 (服务端可读的路径，可重复)；给出时不生成合成图片
+    #[arg(long)]
+    image: Vec<String>,
+    /// 合成图片池的大小
+    #[arg(long, default_value_t = 64)]
+    synthetic_images: usize,
+    /// 合成图片的边长 (像素)
+    #[arg(long, default_value_t = 512)]
+    image_size: u32,
+    /// 合成图片的存放目录 (默认为临时目录，结束后删除)
+    #[arg(long)]
+    image_dir: Option<String>,
+    /// 把结果另存为 JSON 报告
+    #[arg(long)]
+    report: Option<String>,
+}
+
+fn main() -> anyhow::Result<()> {
+    let cli = Cli::parse();
+    if cli.concurrency == 0 || cli.rps < 0.0 || !(0.0..=1.0).contains(&cli.audit_ratio) {
+        return Err(anyhow::anyhow!("--concurrency must be at least 1, --rps non-negative and --audit-ratio within 0 ~ 1"));
+    }
+    let base = cli.url.trim_end_matches('/').to_string();
+    let agent = ureq::AgentBuilder::new().timeout(Duration::from_secs(60)).build();
+
+    // 准备图片池与模型
+    let (images, generated) = prepare_images(&cli)?;
+    let request = ModelRegisterRequest { hash: cli.model.clone(), description: "yuanjing-loadgen synthetic model".to_string() };
+    match post_json(&agent, &format!("{}/v1/model/register", base), cli.api_key.as_deref(), &request)? {
+        Ok(_) => println!("🆕 模型已注册: {}", cli.model),
+        Err(e) => println!("⚠️  注册模型失败 (已注册或注册表为签名模式时可忽略): {}", e),
+    }
+
+    println!(
+        "🚀 压测开始: {} | {} 并发 | 目标 {} | /audit 占比 {:.0}% | {} 秒{}",
+        base,
+        cli.concurrency,
+        if cli.rps > 0.0 { format!("{} 次/秒", cli.rps) } else { "不限速".to_string() },
+        cli.audit_ratio * 100.0,
+        cli.duration,
+        cli.requests.map_or(String::new(), |requests| format!(" / 至多 {} 次", requests))
+    );
+    let pacer = Arc::new(Pacer::new(cli.rps, Duration::from_secs(cli.duration), cli.requests));
+    let worker = Arc::new(Worker {
+        base,
+        api_key: cli.api_key.clone(),
+        agent,
+        model: cli.model.clone(),
+        images,
+        audit_ratio: cli.audit_ratio,
+        proven: Mutex::new(Vec::new()),
+        completed: AtomicU64::new(0),
+        run_id: format!("{:08x}", rand::thread_rng().gen::<u32>()),
+    });
+
+    let started = Instant::now();
+    let handles: Vec<_> = (0..cli.concurrency)
+        .map(|_| {
+            let (worker, pacer) = (worker.clone(), pacer.clone());
+            std::thread::spawn(move || worker.run(&pacer))
+        })
+        .collect();
+    let mut progress = started;
+    while !handles.iter().all(|handle| handle.is_finished()) {
+        std::thread::sleep(Duration::from_millis(100));
+        if progress.elapsed() >= PROGRESS_INTERVAL {
+            progress = Instant::now();
+            println!("⏱️  {:>4} 秒: 已完成 {} 次请求", started.elapsed().as_secs(), worker.completed.load(Ordering::Relaxed));
+        }
+    }
+    let mut stats = Stats::default();
+    for handle in handles {
+        stats.merge(handle.join().map_err(|_| anyhow::anyhow!("Load generator worker panicked"))?);
+    }
+    let elapsed = started.elapsed();
+
+    let report = LoadReport {
+        url: worker.base.clone(),
+        target_rps: cli.rps,
+        concurrency: cli.concurrency,
+        elapsed_secs: elapsed.as_secs_f64(),
+        operations: stats.operations.iter().map(|(name, operation)| operation.report(name, elapsed)).collect(),
+    };
+    print_report(&report);
+    if let Some(path) = &cli.report {
+        std::fs::write(path, serde_json::to_vec_pretty(&report)?)?;
+        println!("📝 JSON 报告已写入: '{}'", path);
+    }
+    if let Some(dir) = generated {
+        let _ = std::fs::remove_dir_all(dir);
+    }
+    Ok(())
+}
+
+/// 图片池 (绝对路径)；生成在临时目录时一并返回该目录，结束后删除
+fn prepare_images(cli: &Cli) -> anyhow::Result<(Vec<String>, Option<PathBuf>)> {
+    if !cli.image.is_empty() {
+        let images = cli
+            .image
+            .iter()
+            .map(|path| std::fs::canonicalize(path).map(|path| path.to_string_lossy().into_owned()))
+            .collect::<Result<Vec<_>, _>>()?;
+        return Ok((images, None));
+    }
+    if cli.synthetic_images == 0 || cli.image_size < 8 {
+        return Err(anyhow::anyhow!("--synthetic-images must be at least 1 and --image-size at least 8"));
+    }
+    let (dir, temporary) = match &cli.image_dir {
+        Some(dir) => (PathBuf::from(dir), false),
+        None => (std::env::temp_dir().join(format!("yuanjing-loadgen-{}", std::process::id())), true),
+    };
+    std::fs::create_dir_all(&dir)?;
+    let dir = std::fs::canonicalize(&dir)?;
+    let mut rng = rand::thread_rng();
+    let images = (0..cli.synthetic_images)
+        .map(|index| {
+            let path = dir.join(format!("synthetic-{:05}.jpg", index));
+            synthetic_image(&mut rng, cli.image_size).save(&path)?;
+            Ok(path.to_string_lossy().into_owned())
+        })
+        .collect::<anyhow::Result<Vec<_>>>()?;
+    println!("🖼️  已生成 {} 张合成图片 ({}×{} JPEG): '{}'", images.len(), cli.image_size, cli.image_size, dir.display());
+    Ok((images, temporary.then_some(dir)))
+}
+
+/// 随机底色上叠若干随机色块：感知哈希彼此不同，JPEG 体积与真实照片相近
+fn synthetic_image(rng: &mut impl Rng, size: u32) -> RgbImage {
+    let mut image = RgbImage::from_pixel(size, size, Rgb(rng.gen()));
+    for _ in 0..SYNTHETIC_BLOCKS {
+        let (x, y) = (rng.gen_range(0..size), rng.gen_range(0..size));
+        let (width, height) = (rng.gen_range(1..=size - x), rng.gen_range(1..=size - y));
+        let color: [u8; 3] = rng.gen();
+        for py in y..y + height {
+            for px in x..x + width {
+                let noise = rng.gen_range(0..16u8);
+                image.put_pixel(px, py, Rgb(color.map(|channel| channel.saturating_add(noise))));
+            }
+        }
+    }
+    image
+}
+
+/// 以 JSON 请求体发出 POST；外层错误为序列化失败，内层为请求本身的结果
+fn post_json<T: Serialize>(
+    agent: &ureq::Agent,
+    url: &str,
+    api_key: Option<&str>,
+    body: &T,
+) -> anyhow::Result<Result<ureq::Response, ureq::Error>> {
+    let body = serde_json::to_string(body)?;
+    Ok(authorize(agent.post(url), api_key).set("Content-Type", "application/json").send_string(&body))
+}
+
+fn authorize(request: ureq::Request, api_key: Option<&str>) -> ureq::Request {
+    match api_key {
+        Some(api_key) => request.set("Authorization", &format!("Bearer {}", api_key)),
+        None => request,
+    }
+}
+
+/// 请求节拍：按目标速率分配计划发出时间，到时长或请求数上限即停止
+struct Pacer {
+    next: Mutex<Instant>,
+    interval: Option<Duration>,
+    deadline: Instant,
+    remaining: Option<AtomicU64>,
+}
+
+impl Pacer {
+    fn new(rps: f64, duration: Duration, requests: Option<u64>) -> Self {
+        let now = Instant::now();
+        Self {
+            next: Mutex::new(now),
+            interval: (rps > 0.0).then(|| Duration::from_secs_f64(1.0 / rps)),
+            deadline: now + duration,
+            remaining: requests.map(AtomicU64::new),
+        }
+    }
+
+    /// 下一个请求的计划发出时间；不限速时为当前时刻
+    fn next(&self) -> Option<Instant> {
+        if let Some(remaining) = &self.remaining {
+            remaining.fetch_update(Ordering::Relaxed, Ordering::Relaxed, |left| left.checked_sub(1)).ok()?;
+        }
+        let due = match self.interval {
+            Some(interval) => {
+                let mut next = self.next.lock().unwrap_or_else(|e| e.into_inner());
+                let due = *next;
+                *next += interval;
+                due
+            }
+            None => Instant::now(),
+        };
+        (due < self.deadline).then_some(due)
+    }
+}
+
+struct Worker {
+    base: String,
+    api_key: Option<String>,
+    agent: ureq::Agent,
+    model: String,
+    images: Vec<String>,
+    audit_ratio: f64,
+    /// 已提交成功的叶子位置，供 `/audit` 抽查
+    proven: Mutex<Vec<u64>>,
+    completed: AtomicU64,
+    /// 本次压测的标识，记入证据的 `client_context`
+    run_id: String,
+}
+
+impl Worker {
+    fn run(&self, pacer: &Pacer) -> Stats {
+        let mut stats = Stats::default();
+        let mut rng = rand::thread_rng();
+        while let Some(due) = pacer.next() {
+            if let Some(wait) = due.checked_duration_since(Instant::now()) {
+                std::thread::sleep(wait);
+            }
+            let audit_pos = if rng.gen_bool(self.audit_ratio) {
+                let proven = self.proven.lock().unwrap_or_else(|e| e.into_inner());
+                (!proven.is_empty()).then(|| proven[rng.gen_range(0..proven.len())])
+            } else {
+                None
+            };
+            let (operation, outcome) = match audit_pos {
+                Some(pos) => ("/v1/audit/{pos}", self.audit(pos)),
+                None => ("/v1/prove", self.prove(&mut rng)),
+            };
+            stats.record(operation, due.elapsed(), outcome);
+            self.completed.fetch_add(1, Ordering::Relaxed);
+        }
+        stats
+    }
+
+    /// 提交一条随机但合法的证据
+    fn prove(&self, rng: &mut impl Rng) -> Result<(), String> {
+        let request = ProveRequest {
+            image_path: self.images[rng.gen_range(0..self.images.len())].clone(),
+            verdict: rng.gen_bool(0.5),
+            confidence: f64::from(rng.gen_range(0..=10_000u32)) / 10_000.0,
+            source: "yuanjing-loadgen".to_string(),
+            prompt_pool_hash: self.model.clone(),
+            nonce: Some(format!("{:032x}", rng.gen::<u128>())),
+            department: None,
+            supersedes: None,
+            priority: Default::default(),
+            commitment: Default::default(),
+            claimed_at: Some(chrono::Utc::now().timestamp()),
+            models: Vec::new(),
+            consensus_rule: None,
+            calibration: None,
+            client_context: Some(ClientContext(BTreeMap::from([("loadgen_run".to_string(), self.run_id.clone())]))),
+        };
+        let response = post_json(&self.agent, &format!("{}/v1/prove", self.base), self.api_key.as_deref(), &request)
+            .map_err(|e| e.to_string())?
+            .map_err(describe)?;
+        let receipt: ProveReceipt = serde_json::from_reader(response.into_reader()).map_err(|e| format!("invalid response: {}", e))?;
+        self.proven.lock().unwrap_or_else(|e| e.into_inner()).push(receipt.leaf_pos);
+        Ok(())
+    }
+
+    /// 取一条已提交证据的包含性证明
+    fn audit(&self, pos: u64) -> Result<(), String> {
+        let response = authorize(self.agent.get(&format!("{}/v1/audit/{}", self.base, pos)), self.api_key.as_deref())
+            .call()
+            .map_err(describe)?;
+        response.into_string().map(drop).map_err(|e| format!("invalid response: {}", e))
+    }
+}
+
+/// 失败归类：HTTP 状态码或传输错误
+fn describe(error: ureq::Error) -> String {
+    match error {
+        ureq::Error::Status(status, _) => format!("HTTP {}", status),
+        ureq::Error::Transport(transport) => format!("transport: {}", transport.kind()),
+    }
+}
+
+#[derive(Default)]
+struct Stats {
+    operations: BTreeMap<&'static str, OperationStats>,
+}
+
+impl Stats {
+    fn record(&mut self, operation: &'static str, latency: Duration, outcome: Result<(), String>) {
+        let entry = self.operations.entry(operation).or_default();
+        match outcome {
+            Ok(()) => entry.latencies.push(latency),
+            Err(error) => *entry.errors.entry(error).or_default() += 1,
+        }
+    }
+
+    fn merge(&mut self, other: Stats) {
+        for (operation, stats) in other.operations {
+            let entry = self.operations.entry(operation).or_default();
+            entry.latencies.extend(stats.latencies);
+            for (error, count) in stats.errors {
+                *entry.errors.entry(error).or_default() += count;
+            }
+        }
+    }
+}
+
+#[derive(Default)]
+struct OperationStats {
+    /// 成功请求的延迟
+    latencies: Vec<Duration>,
+    /// 失败请求按原因计数
+    errors: BTreeMap<String, u64>,
+}
+
+impl OperationStats {
+    fn report(&self, name: &str, elapsed: Duration) -> OperationReport {
+        let mut latencies = self.latencies.clone();
+        latencies.sort_unstable();
+        let percentile = |p: f64| {
+            let index = ((latencies.len() as f64 * p).ceil() as usize).clamp(1, latencies.len().max(1)) - 1;
+            latencies.get(index).map_or(0.0, |latency| latency.as_secs_f64() * 1000.0)
+        };
+        let total: Duration = latencies.iter().sum();
+        OperationReport {
+            operation: name.to_string(),
+            succeeded: latencies.len() as u64,
+            failed: self.errors.values().sum(),
+            errors: self.errors.clone(),
+            throughput: latencies.len() as f64 / elapsed.as_secs_f64(),
+            latency_ms: LatencyReport {
+                mean: if latencies.is_empty() { 0.0 } else { total.as_secs_f64() * 1000.0 / latencies.len() as f64 },
+                p50: percentile(0.50),
+                p90: percentile(0.90),
+                p99: percentile(0.99),
+                p999: percentile(0.999),
+                max: latencies.last().map_or(0.0, |latency| latency.as_secs_f64() * 1000.0),
+            },
+        }
+    }
+}
+
+/// 压测报告 (`--report` 写出的 JSON)
+#[derive(Serialize)]
+struct LoadReport {
+    url: String,
+    /// 目标速率 (次/秒)，0 为不限速
+    target_rps: f64,
+    concurrency: usize,
+    elapsed_secs: f64,
+    operations: Vec<OperationReport>,
+}
+
+#[derive(Serialize)]
+struct OperationReport {
+    operation: String,
+    succeeded: u64,
+    failed: u64,
+    errors: BTreeMap<String, u64>,
+    /// 成功请求的吞吐 (次/秒)
+    throughput: f64,
+    latency_ms: LatencyReport,
+}
+
+#[derive(Serialize)]
+struct LatencyReport {
+    mean: f64,
+    p50: f64,
+    p90: f64,
+    p99: f64,
+    p999: f64,
+    max: f64,
+}
+
+fn print_report(report: &LoadReport) {
+    println!("📊 压测结束: 用时 {:.1} 秒", report.elapsed_secs);
+    for operation in &report.operations {
+        println!(
+            "   {:<16} 成功 {:>7} | 失败 {:>5} | {:>8.1} 次/秒",
+            operation.operation, operation.succeeded, operation.failed, operation.throughput
+        );
+        let latency = &operation.latency_ms;
+        println!(
+            "   {:<16} 延迟 (ms) 平均 {:.1} | p50 {:.1} | p90 {:.1} | p99 {:.1} | p99.9 {:.1} | 最大 {:.1}",
+            "", latency.mean, latency.p50, latency.p90, latency.p99, latency.p999, latency.max
+        );
+        for (error, count) in &operation.errors {
+            println!("   {:<16} ⚠️  {} × {}", "", error, count);
+        }
+    }
+}
@@ -0,0 +1,342 @@
+use ed25519_dalek::{Signature, Verifier, VerifyingKey};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::evidence::{leaf_hash_of, Evidence};
+use crate::hdkey::KeyEndorsement;
+use crate::proof;
+use crate::receipt::Receipt;
+use crate::revocation::RevocationList;
+
+/// 当前证据包格式版本
+pub const BUNDLE_FORMAT: u32 = 1;
+
+/// 派生密钥的背书 (回执由部门 / 设备密钥签发时随包附带)
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
+pub struct BundleEndorsement {
+    pub endorsement: KeyEndorsement,
+    /// 租户根密钥对 endorsement 的签名 (Hex)
+    pub signature: String,
+}
+
+/// 模块：证据包 (Evidence Bundle)
+///
+/// **职责**: 把离线验证一条证据所需的全部材料打成一个 JSON 文档：
+/// 证据原文、签名、回执、租户根公钥、(派生密钥时) 背书，以及回执所在那棵树上的包含性证明。
+/// 验证方拿到证据包后无需联网，只需事先信任租户根公钥。
+///
+/// **为什么放在核心库、且不依赖 `server` 特性**: 浏览器 (WASM)、嵌入式设备 (C FFI)
+/// 与服务端必须对同一份证据包得出同一结论，校验逻辑只能有一份。
+///
+/// 证书链原样携带，供具备 X.509 校验能力的验证方使用 (服务端 `/verify`)；本模块不校验证书链。
+///
+/// 吊销列表不随包携带：伪造者手里的被盗密钥同样能造出一份“不带列表”的包，
+/// 列表只能由验证方从可信渠道 (`GET /revocations`) 取得、核对主身份签名后传入 [`EvidenceBundle::verify`]。
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct EvidenceBundle {
+    /// 证据包格式版本，当前为 1
+    pub format: u32,
+    pub evidence: Evidence,
+    /// 证据签名 (Hex)
+    pub evidence_signature: String,
+    pub receipt: Receipt,
+    /// 回执签名 (Hex)
+    pub receipt_signature: String,
+    /// 租户根公钥 (Hex)
+    pub public_key: String,
+    #[serde(default)]
+    pub endorsement: Option<BundleEndorsement>,
+    /// 叶子在 `receipt.tree_size` 大小的树中的证明路径 (Hex)
+    pub proof: Vec<String>,
+    #[serde(default)]
+    pub certificate_chain: Option<Vec<String>>,
+    /// 叶子盐值 (Hex)，仅加盐叶子
+    #[serde(default)]
+    pub salt: Option<String>,
+}
+
+/// 证据包校验结论
+///
+/// 判别值即 C FFI 的返回码，只可追加、不可改动。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BundleStatus {
+    Valid = 0,
+    /// 无法解析，或格式版本不受支持
+    Malformed = 1,
+    /// 叶子哈希 (Blake3(规范字节)，加盐时为 Blake3(salt || 规范字节)) 与回执中的 `evidence_hash` 不符
+    EvidenceHashMismatch = 2,
+    EvidenceSignatureInvalid = 3,
+    ReceiptSignatureInvalid = 4,
+    /// 派生密钥缺少有效背书，或背书与回执的 `key_path` / 租户不符
+    EndorsementInvalid = 5,
+    /// 包含性证明不成立
+    InclusionInvalid = 6,
+    /// 租户根公钥不是调用方信任的那一把
+    UntrustedKey = 7,
+    /// 回执签名密钥在回执时间已被吊销
+    KeyRevoked = 8,
+}
+
+impl EvidenceBundle {
+    /// 从 JSON 字节解析
+    pub fn from_json(bytes: &[u8]) -> anyhow::Result<Self> {
+        let bundle: Self = serde_json::from_slice(bytes)?;
+        if bundle.format != BUNDLE_FORMAT {
+            return Err(anyhow::anyhow!("Unsupported bundle format {}", bundle.format));
+        }
+        Ok(bundle)
+    }
+
+    /// 解析并校验；任何解析错误都归为 `Malformed`
+    pub fn verify_json(bytes: &[u8], trusted_root: Option<&VerifyingKey>, revocations: Option<&RevocationList>) -> BundleStatus {
+        match Self::from_json(bytes) {
+            Ok(bundle) => bundle.verify(trusted_root, revocations),
+            Err(_) => BundleStatus::Malformed,
+        }
+    }
+
+    /// 离线完整校验：证据哈希、证据与回执签名、派生密钥背书、包含性证明、密钥吊销
+    ///
+    /// `trusted_root` 为调用方事先信任的租户根公钥；为 None 时信任包内自带的公钥，
+    /// 此时只能证明“材料自洽”，不能证明“出自谁手”。
+    /// `revocations` 为已核对主身份签名的吊销列表 (见 [`crate::revocation::SignedRevocationList::verify`])；
+    /// 为 None 时不做吊销检查。
+    pub fn verify(&self, trusted_root: Option<&VerifyingKey>, revocations: Option<&RevocationList>) -> BundleStatus {
+        let signing_key = match receipt_signing_key(&self.receipt, &self.public_key, self.endorsement.as_ref(), trusted_root) {
+            Ok(key) => key,
+            Err(status) => return status,
+        };
+
+        let Ok(evidence_bytes) = self.evidence.canonical_bytes_as(self.receipt.encoding) else {
+            return BundleStatus::Malformed;
+        };
+        let salt = match self.salt.as_deref().map(decode_hash).transpose() {
+            Ok(salt) => salt,
+            Err(_) => return BundleStatus::Malformed,
+        };
+        let leaf = leaf_hash_of(&evidence_bytes, salt.as_ref());
+        if hex::encode(leaf) != self.receipt.evidence_hash {
+            return BundleStatus::EvidenceHashMismatch;
+        }
+        if !verify_signature(&signing_key, &evidence_bytes, &self.evidence_signature) {
+            return BundleStatus::EvidenceSignatureInvalid;
+        }
+        verify_receipt(&self.receipt, &self.receipt_signature, &signing_key, &self.proof, revocations)
+    }
+}
+
+/// 回执的签名公钥：先核对包内根公钥是否受信，`key_path` 为空时即根公钥，
+/// 否则须有根公钥签发的匹配背书
+pub(crate) fn receipt_signing_key(
+    receipt: &Receipt,
+    public_key: &str,
+    endorsement: Option<&BundleEndorsement>,
+    trusted_root: Option<&VerifyingKey>,
+) -> Result<VerifyingKey, BundleStatus> {
+    let root_key = decode_key(public_key).map_err(|_| BundleStatus::Malformed)?;
+    if trusted_root.is_some_and(|trusted| *trusted != root_key) {
+        return Err(BundleStatus::UntrustedKey);
+    }
+    if receipt.key_path.is_empty() {
+        return Ok(root_key);
+    }
+    let Some(endorsed) = endorsement else {
+        return Err(BundleStatus::EndorsementInvalid);
+    };
+    let endorsement = &endorsed.endorsement;
+    if endorsement.key_path != receipt.key_path || endorsement.tenant_id != receipt.tenant_id {
+        return Err(BundleStatus::EndorsementInvalid);
+    }
+    let bytes = endorsement.canonical_bytes().map_err(|_| BundleStatus::Malformed)?;
+    if !verify_signature(&root_key, &bytes, &endorsed.signature) {
+        return Err(BundleStatus::EndorsementInvalid);
+    }
+    decode_key(&endorsement.public_key).map_err(|_| BundleStatus::Malformed)
+}
+
+/// 回执签名、包含性证明 (叶子取回执中的 `evidence_hash`) 与密钥吊销，不涉及证据原文
+///
+/// 吊销按回执时间判定 (见 [`RevocationList`])，放在签名与包含性之后：只有确实由该密钥签发、
+/// 确实在日志中的回执才谈得上“签发时密钥已被吊销”。
+pub(crate) fn verify_receipt(
+    receipt: &Receipt,
+    receipt_signature: &str,
+    signing_key: &VerifyingKey,
+    proof: &[String],
+    revocations: Option<&RevocationList>,
+) -> BundleStatus {
+    let Ok(receipt_bytes) = receipt.signing_bytes() else {
+        return BundleStatus::Malformed;
+    };
+    if !verify_signature(signing_key, &receipt_bytes, receipt_signature) {
+        return BundleStatus::ReceiptSignatureInvalid;
+    }
+    let (Ok(leaf), Ok(root), Ok(items)) = (
+        decode_hash(&receipt.evidence_hash),
+        decode_hash(&receipt.root),
+        proof.iter().map(|item| decode_hash(item)).collect::<anyhow::Result<Vec<_>>>(),
+    ) else {
+        return BundleStatus::Malformed;
+    };
+    if !proof::verify_proof(receipt.tree_size, items, receipt.leaf_pos, leaf, root) {
+        return BundleStatus::InclusionInvalid;
+    }
+    if revocations.is_some_and(|list| list.is_revoked(signing_key, receipt.timestamp)) {
+        return BundleStatus::KeyRevoked;
+    }
+    BundleStatus::Valid
+}
+
+fn verify_signature(public_key: &VerifyingKey, payload: &[u8], signature: &str) -> bool {
+    hex::decode(signature)
+        .ok()
+        .and_then(|bytes| Signature::from_slice(&bytes).ok())
+        .is_some_and(|signature| public_key.verify(payload, &signature).is_ok())
+}
+
+fn decode_hash(value: &str) -> anyhow::Result<[u8; 32]> {
+    hex::decode(value)?
+        .try_into()
+        .map_err(|_| anyhow::anyhow!("Expected a 32-byte hash, got '{}'", value))
+}
+
+fn decode_key(value: &str) -> anyhow::Result<VerifyingKey> {
+    Ok(VerifyingKey::from_bytes(&decode_hash(value)?)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::evidence::{CanonicalEncoding, Confidence};
+    use crate::proof::MergeBlake3;
+    use crate::revocation::Revocation;
+    use ckb_merkle_mountain_range::util::MemMMR;
+    use ed25519_dalek::{Signer, SigningKey};
+
+    const ISSUED_AT: i64 = 1_767_225_700;
+
+    fn evidence() -> Evidence {
+        Evidence {
+            image_phash: "jPDw8PDw8PA=".to_string(),
+            image_sha256: hex::encode([0x5A; 32]),
+            verdict: true,
+            confidence: Confidence::BasisPoints(9100),
+            activated_prompts: vec![1, 4],
+            prompt_pool_hash: "model".to_string(),
+            external_knowledge_hash: hex::encode([0x11; 32]),
+            timestamp: 1_767_225_600,
+            claimed_at: None,
+            consensus: None,
+            calibration: None,
+            provenance: None,
+            client_context: None,
+        }
+    }
+
+    /// 由 `key` 签发的证据包：证据是三叶子树中的第二个叶子
+    fn bundle(key: &SigningKey) -> EvidenceBundle {
+        let evidence = evidence();
+        let evidence_bytes = evidence.canonical_bytes_as(CanonicalEncoding::Bcs).unwrap();
+        let leaf = leaf_hash_of(&evidence_bytes, None);
+
+        let mut mmr = MemMMR::<[u8; 32], MergeBlake3>::default();
+        mmr.push([0x01; 32]).unwrap();
+        let pos = mmr.push(leaf).unwrap();
+        mmr.push([0x03; 32]).unwrap();
+        let proof = mmr.gen_proof(vec![pos]).unwrap();
+
+        let receipt = Receipt {
+            evidence_hash: hex::encode(leaf),
+            leaf_pos: pos,
+            root: hex::encode(mmr.get_root().unwrap()),
+            tree_size: mmr.mmr_size(),
+            timestamp: ISSUED_AT,
+            seq: 1,
+            clock_uncertain: false,
+            tenant_id: "default".to_string(),
+            key_path: String::new(),
+            encoding: CanonicalEncoding::Bcs,
+            supersedes: None,
+        };
+        EvidenceBundle {
+            format: BUNDLE_FORMAT,
+            evidence_signature: hex::encode(key.sign(&evidence_bytes).to_bytes()),
+            receipt_signature: hex::encode(key.sign(&receipt.signing_bytes().unwrap()).to_bytes()),
+            receipt,
+            evidence,
+            public_key: hex::encode(key.verifying_key().to_bytes()),
+            endorsement: None,
+            proof: proof.proof_items().iter().map(hex::encode).collect(),
+            certificate_chain: None,
+            salt: None,
+        }
+    }
+
+    fn revoked(key: &SigningKey, effective_from: i64) -> RevocationList {
+        RevocationList {
+            version: 1,
+            issued_at: effective_from,
+            entries: vec![Revocation {
+                public_key: hex::encode(key.verifying_key().to_bytes()),
+                effective_from,
+                reason: "key compromise".to_string(),
+            }],
+        }
+    }
+
+    #[test]
+    fn accepts_a_consistent_bundle_from_the_trusted_key() {
+        let key = SigningKey::from_bytes(&[7; 32]);
+        let bundle = bundle(&key);
+        assert_eq!(bundle.verify(Some(&key.verifying_key()), None), BundleStatus::Valid);
+        assert_eq!(bundle.verify(None, Some(&RevocationList::default())), BundleStatus::Valid);
+
+        let other = SigningKey::from_bytes(&[8; 32]);
+        assert_eq!(bundle.verify(Some(&other.verifying_key()), None), BundleStatus::UntrustedKey);
+    }
+
+    #[test]
+    fn rejects_receipts_signed_at_or_after_the_revocation_time() {
+        let key = SigningKey::from_bytes(&[7; 32]);
+        let bundle = bundle(&key);
+        let trusted = key.verifying_key();
+
+        assert_eq!(bundle.verify(Some(&trusted), Some(&revoked(&key, ISSUED_AT + 1))), BundleStatus::Valid);
+        assert_eq!(bundle.verify(Some(&trusted), Some(&revoked(&key, ISSUED_AT))), BundleStatus::KeyRevoked);
+        assert_eq!(bundle.verify(Some(&trusted), Some(&revoked(&key, ISSUED_AT - 3600))), BundleStatus::KeyRevoked);
+
+        let json = serde_json::to_vec(&bundle).unwrap();
+        assert_eq!(EvidenceBundle::verify_json(&json, Some(&trusted), Some(&revoked(&key, ISSUED_AT))), BundleStatus::KeyRevoked);
+    }
+
+    #[test]
+    fn revocation_of_another_key_does_not_affect_the_bundle() {
+        let key = SigningKey::from_bytes(&[7; 32]);
+        let other = SigningKey::from_bytes(&[8; 32]);
+        assert_eq!(bundle(&key).verify(None, Some(&revoked(&other, 0))), BundleStatus::Valid);
+    }
+
+    #[test]
+    fn a_forged_receipt_is_reported_as_such_rather_than_revoked() {
+        let key = SigningKey::from_bytes(&[7; 32]);
+        let mut bundle = bundle(&key);
+        bundle.receipt.seq += 1;
+        assert_eq!(bundle.verify(None, Some(&revoked(&key, 0))), BundleStatus::ReceiptSignatureInvalid);
+    }
+
+    #[test]
+    fn detects_tampered_evidence_and_proofs() {
+        let key = SigningKey::from_bytes(&[7; 32]);
+
+        let mut tampered = bundle(&key);
+        tampered.evidence.verdict = false;
+        assert_eq!(tampered.verify(None, None), BundleStatus::EvidenceHashMismatch);
+
+        let mut tampered = bundle(&key);
+        tampered.proof.reverse();
+        assert_eq!(tampered.verify(None, None), BundleStatus::InclusionInvalid);
+
+        assert_eq!(EvidenceBundle::verify_json(b"{}", None, None), BundleStatus::Malformed);
+    }
+}
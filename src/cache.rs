@@ -0,0 +1,125 @@
+use lru::LruCache;
+use std::hash::Hash;
+use std::num::NonZeroUsize;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+
+use crate::evidence::Evidence;
+use crate::mmr_store::StoredAttestation;
+
+/// 证明路径 (兄弟节点与山峰哈希)
+pub type ProofItems = Arc<Vec<[u8; 32]>>;
+
+/// 缓存统计 (Cache Stats)
+#[derive(Debug, Clone, Copy, Default)]
+pub struct CacheStats {
+    pub hits: u64,
+    pub misses: u64,
+    pub entries: u64,
+}
+
+/// 带命中统计的 LRU 缓存
+///
+/// 容量为 0 表示关闭缓存：`get` 总是未命中且不计数，`put` 直接丢弃。
+pub struct MeteredCache<K: Hash + Eq, V: Clone> {
+    entries: Option<Mutex<LruCache<K, V>>>,
+    hits: AtomicU64,
+    misses: AtomicU64,
+}
+
+impl<K: Hash + Eq, V: Clone> MeteredCache<K, V> {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            entries: NonZeroUsize::new(capacity).map(|cap| Mutex::new(LruCache::new(cap))),
+            hits: AtomicU64::new(0),
+            misses: AtomicU64::new(0),
+        }
+    }
+
+    pub fn get(&self, key: &K) -> Option<V> {
+        let entries = self.entries.as_ref()?;
+        let found = entries.lock().expect("cache poisoned").get(key).cloned();
+        match found {
+            Some(_) => self.hits.fetch_add(1, Ordering::Relaxed),
+            None => self.misses.fetch_add(1, Ordering::Relaxed),
+        };
+        found
+    }
+
+    pub fn put(&self, key: K, value: V) {
+        if let Some(entries) = &self.entries {
+            entries.lock().expect("cache poisoned").put(key, value);
+        }
+    }
+
+    /// 失效单个条目
+    pub fn invalidate(&self, key: &K) {
+        if let Some(entries) = &self.entries {
+            entries.lock().expect("cache poisoned").pop(key);
+        }
+    }
+
+    /// 失效全部条目
+    pub fn clear(&self) {
+        if let Some(entries) = &self.entries {
+            entries.lock().expect("cache poisoned").clear();
+        }
+    }
+
+    pub fn stats(&self) -> CacheStats {
+        CacheStats {
+            hits: self.hits.load(Ordering::Relaxed),
+            misses: self.misses.load(Ordering::Relaxed),
+            entries: self
+                .entries
+                .as_ref()
+                .map(|e| e.lock().expect("cache poisoned").len() as u64)
+                .unwrap_or(0),
+        }
+    }
+}
+
+/// 模块：证明缓存 (Proof Cache)
+///
+/// **为什么需要**: 热门位置会被反复审计，每次生成证明都要对 sled 做 O(log n) 次随机读。
+/// 同一个 `(tree_size, pos)` 的证明是永远不变的 (MMR 只追加)，非常适合缓存。
+///
+/// **失效策略**: 读端总是基于“最新已提交大小”取证明，旧 `tree_size` 的条目追加后不会再命中，
+/// 因此写端每次发布新大小时清空缓存，及时释放内存。
+pub type ProofCache = MeteredCache<(u64, u64), ProofItems>;
+
+/// 模块：证据读缓存 (Evidence Read Cache)
+///
+/// **为什么需要**: 审计看板会反复读取最近的若干条证据及其回执 (每条回执就是
+/// 一个签名树头，STH)。证据原文与签名材料一经入库不再变化，按位置缓存即可，
+/// 免去每次从 sled 读取并解码。
+///
+/// **失效钩子**: 正常追加不会修改已有条目，因此写入路径无需失效；
+/// 需要删除或改写已入库数据的功能 (如保留策略、擦除) 必须调用
+/// [`EvidenceReadCache::invalidate`] 或 [`EvidenceReadCache::clear`]。
+pub struct EvidenceReadCache {
+    pub evidence: MeteredCache<u64, Arc<Evidence>>,
+    pub attestations: MeteredCache<u64, Arc<StoredAttestation>>,
+}
+
+impl EvidenceReadCache {
+    /// `capacity` 为每类记录各自的条目上限，0 表示关闭
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            evidence: MeteredCache::new(capacity),
+            attestations: MeteredCache::new(capacity),
+        }
+    }
+
+    /// 失效某个位置的全部缓存记录
+    pub fn invalidate(&self, pos: u64) {
+        self.evidence.invalidate(&pos);
+        self.attestations.invalidate(&pos);
+    }
+
+    /// 失效全部缓存记录
+    pub fn clear(&self) {
+        self.evidence.clear();
+        self.attestations.clear();
+    }
+}
@@ -0,0 +1,57 @@
+use ed25519_dalek::{Signature, VerifyingKey};
+use serde::{Deserialize, Serialize};
+
+use crate::signer::EvidenceSigner;
+
+/// 挑战应答签名的域分隔前缀，避免与证据 / 回执签名混用
+const CHALLENGE_DOMAIN: &[u8] = b"yuanjing-core liveness challenge v1";
+
+/// 挑战 nonce 的长度范围 (字节)
+pub const NONCE_MIN_LEN: usize = 16;
+pub const NONCE_MAX_LEN: usize = 256;
+
+/// 模块：挑战应答 (Challenge-Response Attestation)
+///
+/// **为什么需要**: 回执只能证明“过去某个时刻签过名”。远程审计时，审计方还想确认：
+/// 服务此刻仍在运行、仍持有这把私钥、当前账本就是这棵树。
+/// 审计方发送一个随机 nonce，服务端把它与当前根、树大小、时间一起签名返回；
+/// nonce 由审计方临时生成，应答无法被预先计算或从旧应答重放。
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct LivenessStatement {
+    /// 审计方提供的随机 nonce (原样回显)
+    pub nonce: String,
+    pub tenant_id: String,
+    /// 当前已提交的 MMR 根 (Hex)，空树为全零
+    pub root: String,
+    /// 当前已提交的 MMR 大小
+    pub tree_size: u64,
+    /// 应答时间 (Unix 秒)
+    pub timestamp: i64,
+}
+
+impl LivenessStatement {
+    /// 规范化字节 (域前缀 + BCS)，签名与验签均基于此
+    pub fn canonical_bytes(&self) -> anyhow::Result<Vec<u8>> {
+        let mut bytes = CHALLENGE_DOMAIN.to_vec();
+        bytes.extend_from_slice(&bcs::to_bytes(self)?);
+        Ok(bytes)
+    }
+
+    pub fn sign(&self, signer: &EvidenceSigner) -> anyhow::Result<Signature> {
+        signer.sign_bytes(&self.canonical_bytes()?)
+    }
+
+    /// 审计方校验：签名有效且 nonce 与自己发出的一致
+    pub fn verify(&self, public_key: &VerifyingKey, signature: &Signature, expected_nonce: &str) -> anyhow::Result<bool> {
+        Ok(self.nonce == expected_nonce && EvidenceSigner::verify_bytes(public_key, &self.canonical_bytes()?, signature))
+    }
+}
+
+/// 校验审计方提交的 nonce 长度
+pub fn validate_nonce(nonce: &str) -> anyhow::Result<()> {
+    if (NONCE_MIN_LEN..=NONCE_MAX_LEN).contains(&nonce.len()) {
+        Ok(())
+    } else {
+        Err(anyhow::anyhow!("Challenge nonce must be {}-{} bytes, got {}", NONCE_MIN_LEN, NONCE_MAX_LEN, nonce.len()))
+    }
+}
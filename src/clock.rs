@@ -0,0 +1,200 @@
+use log::{error, info, warn};
+use std::net::UdpSocket;
+use std::sync::atomic::{AtomicBool, AtomicI64, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// NTP 纪元 (1900-01-01) 与 Unix 纪元 (1970-01-01) 之间的秒数
+const NTP_UNIX_OFFSET_SECS: u64 = 2_208_988_800;
+
+/// 时钟偏差超限时的处理策略
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ClockPolicy {
+    /// 拒绝签名
+    Refuse,
+    /// 签名但标记时钟不确定
+    Flag,
+}
+
+impl std::str::FromStr for ClockPolicy {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> anyhow::Result<Self> {
+        match s.to_ascii_lowercase().as_str() {
+            "refuse" => Ok(Self::Refuse),
+            "flag" => Ok(Self::Flag),
+            other => Err(anyhow::anyhow!("Unknown clock policy '{}', expected 'refuse' or 'flag'", other)),
+        }
+    }
+}
+
+/// 模块：时钟校验 (Clock Sanity)
+///
+/// **职责**: 证明“时间戳是可信的”。
+/// 整个账本的时间线都建立在服务器系统时钟之上，一旦时钟被拨乱（人为或故障），
+/// 所有后续证据的时间戳都会失真，且无法事后区分。
+/// 因此服务在启动时以及运行期间定期向配置的 NTP 服务器对时，偏差超过阈值时：
+/// - `refuse` 策略：拒绝签发新证据；
+/// - `flag` 策略：照常签发，但在回执中标记 `clock_uncertain = true`。
+///
+/// 时钟守卫保存最近一次对时结果，供签名路径无锁读取。
+pub struct ClockGuard {
+    servers: Vec<String>,
+    max_skew_ms: i64,
+    policy: ClockPolicy,
+    healthy: AtomicBool,
+    last_skew_ms: AtomicI64,
+}
+
+impl ClockGuard {
+    pub fn new(servers: Vec<String>, max_skew_ms: i64, policy: ClockPolicy) -> Self {
+        Self {
+            servers,
+            max_skew_ms,
+            policy,
+            // 未配置 NTP 服务器时视为关闭校验，默认健康
+            healthy: AtomicBool::new(true),
+            last_skew_ms: AtomicI64::new(0),
+        }
+    }
+
+    pub fn policy(&self) -> ClockPolicy {
+        self.policy
+    }
+
+    /// 最近一次校验是否在阈值内
+    pub fn is_healthy(&self) -> bool {
+        self.healthy.load(Ordering::Relaxed)
+    }
+
+    /// 最近一次测得的偏差 (毫秒，正数表示本机时钟落后)
+    pub fn last_skew_ms(&self) -> i64 {
+        self.last_skew_ms.load(Ordering::Relaxed)
+    }
+
+    /// 执行一次对时 (阻塞调用)
+    ///
+    /// 取所有对时成功的服务器偏差的中位数；全部失败 (不可达或应答无效) 时同样视为时钟不可信。
+    pub fn check_once(&self) {
+        if self.servers.is_empty() {
+            return;
+        }
+
+        let mut offsets: Vec<i64> = self
+            .servers
+            .iter()
+            .filter_map(|server| match query_ntp_offset_ms(server, Duration::from_secs(3)) {
+                Ok(offset) => Some(offset),
+                Err(e) => {
+                    warn!("⚠️  NTP 服务器 {} 对时失败: {}", server, e);
+                    None
+                }
+            })
+            .collect();
+
+        if offsets.is_empty() {
+            warn!("⏰ 所有 NTP 服务器均对时失败，时钟状态标记为不可信");
+            self.healthy.store(false, Ordering::Relaxed);
+            return;
+        }
+
+        offsets.sort_unstable();
+        let skew = offsets[offsets.len() / 2];
+        let healthy = skew.abs() <= self.max_skew_ms;
+        self.last_skew_ms.store(skew, Ordering::Relaxed);
+        self.healthy.store(healthy, Ordering::Relaxed);
+
+        if healthy {
+            info!("⏰ 时钟校验通过: 偏差 {} ms", skew);
+        } else {
+            error!("🚨 时钟偏差 {} ms 超过阈值 {} ms (策略: {:?})", skew, self.max_skew_ms, self.policy);
+        }
+    }
+
+    /// 启动后台周期校验任务
+    pub fn spawn_periodic(self: Arc<Self>, interval: Duration) {
+        if self.servers.is_empty() {
+            return;
+        }
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            ticker.tick().await; // 第一次 tick 立即返回，启动时已经校验过
+            loop {
+                ticker.tick().await;
+                let guard = self.clone();
+                let _ = tokio::task::spawn_blocking(move || guard.check_once()).await;
+            }
+        });
+    }
+}
+
+/// 向单个 NTP 服务器查询本机时钟偏差 (SNTP v4, RFC 4330)
+///
+/// $$ \theta = \frac{(T_2 - T_1) + (T_3 - T_4)}{2} $$
+/// 其中 $T_1$ 为本地发送时间，$T_2$/$T_3$ 为服务器收/发时间，$T_4$ 为本地接收时间。
+///
+/// 应答须回显 $T_1$，且服务器自身已同步 (LI ≠ 3，层级 1..15)，否则按对时失败处理，不计入中位数。
+pub fn query_ntp_offset_ms(server: &str, timeout: Duration) -> anyhow::Result<i64> {
+    let addr = if server.contains(':') { server.to_string() } else { format!("{}:123", server) };
+    let socket = UdpSocket::bind("0.0.0.0:0")?;
+    socket.set_read_timeout(Some(timeout))?;
+    socket.connect(&addr)?;
+
+    let mut packet = [0u8; 48];
+    packet[0] = 0x23; // LI = 0, VN = 4, Mode = 3 (client)
+    let t1 = SystemTime::now();
+    let transmit = to_ntp_timestamp(t1);
+    packet[40..48].copy_from_slice(&transmit);
+    socket.send(&packet)?;
+
+    let mut response = [0u8; 48];
+    let len = socket.recv(&mut response)?;
+    let t4 = SystemTime::now();
+    if len < 48 {
+        return Err(anyhow::anyhow!("Short NTP response ({} bytes)", len));
+    }
+    if response[0] & 0x07 != 4 {
+        return Err(anyhow::anyhow!("Unexpected NTP mode {}", response[0] & 0x07));
+    }
+    // 起始时间戳须原样回显我们发出的发送时间，否则是伪造或迟到的旧应答
+    if response[24..32] != transmit {
+        return Err(anyhow::anyhow!("NTP response does not echo our transmit timestamp (spoofed or stale reply)"));
+    }
+    // LI = 3 与层级 16 表示服务器自身未同步；层级 0 是 Kiss-o'-Death，参考 ID 为拒绝原因
+    if response[0] >> 6 == 3 {
+        return Err(anyhow::anyhow!("NTP server clock is unsynchronized (leap indicator 3)"));
+    }
+    match response[1] {
+        0 => return Err(anyhow::anyhow!("NTP server sent Kiss-o'-Death '{}'", String::from_utf8_lossy(&response[12..16]).trim_end_matches('\0'))),
+        stratum if stratum >= 16 => return Err(anyhow::anyhow!("NTP server is unsynchronized (stratum {})", stratum)),
+        _ => {}
+    }
+
+    let t1 = unix_millis(t1)?;
+    let t4 = unix_millis(t4)?;
+    let t2 = from_ntp_timestamp(&response[32..40]);
+    let t3 = from_ntp_timestamp(&response[40..48]);
+    Ok(((t2 - t1) + (t3 - t4)) / 2)
+}
+
+fn unix_millis(t: SystemTime) -> anyhow::Result<i64> {
+    Ok(t.duration_since(UNIX_EPOCH)?.as_millis() as i64)
+}
+
+fn to_ntp_timestamp(t: SystemTime) -> [u8; 8] {
+    let since_epoch = t.duration_since(UNIX_EPOCH).unwrap_or_default();
+    let secs = (since_epoch.as_secs() + NTP_UNIX_OFFSET_SECS) as u32;
+    let frac = ((since_epoch.subsec_nanos() as u64) << 32) / 1_000_000_000;
+    let mut out = [0u8; 8];
+    out[..4].copy_from_slice(&secs.to_be_bytes());
+    out[4..].copy_from_slice(&(frac as u32).to_be_bytes());
+    out
+}
+
+/// NTP 64 位时间戳 -> Unix 毫秒
+fn from_ntp_timestamp(bytes: &[u8]) -> i64 {
+    let secs = u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]) as i64;
+    let frac = u32::from_be_bytes([bytes[4], bytes[5], bytes[6], bytes[7]]) as i64;
+    (secs - NTP_UNIX_OFFSET_SECS as i64) * 1000 + ((frac * 1000) >> 32)
+}
@@ -0,0 +1,65 @@
+use bincode::Options;
+use serde::{de::DeserializeOwned, Serialize};
+
+/// 存储格式版本 (写在每个租户的 `meta/storage_format` 中)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StorageFormat {
+    /// 旧格式：所有记录都直接使用 BCS
+    LegacyBcs,
+    /// 当前格式：记录使用 bincode (变长整数)
+    Bincode,
+}
+
+impl StorageFormat {
+    pub const CURRENT: StorageFormat = StorageFormat::Bincode;
+
+    pub fn tag(self) -> u8 {
+        match self {
+            Self::LegacyBcs => 0,
+            Self::Bincode => 1,
+        }
+    }
+
+    pub fn from_tag(tag: u8) -> anyhow::Result<Self> {
+        match tag {
+            0 => Ok(Self::LegacyBcs),
+            1 => Ok(Self::Bincode),
+            other => Err(anyhow::anyhow!("Unknown storage format tag {}", other)),
+        }
+    }
+}
+
+/// 模块：存储编码 (Storage Codec)
+///
+/// **职责**: 把“哈希/签名用的规范编码”与“落盘用的存储编码”分开。
+/// - 规范编码 (BCS 或 Protobuf，见 `CanonicalEncoding`) 决定叶子哈希与签名，字节必须跨语言、跨版本稳定，
+///   见 `Evidence::canonical_bytes` / `Receipt::canonical_bytes`。
+/// - 存储编码只服务于 sled 中的记录 (WAL、签名材料、签名根)，追求体积与编解码速度，
+///   可以随版本演进，旧数据由 [`crate::mmr_store::SledStore::migrate_storage_format`] 转换。
+///
+/// 证据原文仍按规范字节原样保存：它本身就是紧凑二进制，且直接哈希即可复核叶子。
+pub struct StorageCodec;
+
+impl StorageCodec {
+    fn options() -> impl Options {
+        bincode::DefaultOptions::new().with_varint_encoding().reject_trailing_bytes()
+    }
+
+    /// 以当前存储格式编码
+    pub fn encode<T: Serialize>(value: &T) -> anyhow::Result<Vec<u8>> {
+        Ok(Self::options().serialize(value)?)
+    }
+
+    /// 以当前存储格式解码
+    pub fn decode<T: DeserializeOwned>(bytes: &[u8]) -> anyhow::Result<T> {
+        Ok(Self::options().deserialize(bytes)?)
+    }
+
+    /// 按指定格式解码 (迁移旧数据时使用)
+    pub fn decode_as<T: DeserializeOwned>(format: StorageFormat, bytes: &[u8]) -> anyhow::Result<T> {
+        match format {
+            StorageFormat::LegacyBcs => Ok(bcs::from_bytes(bytes)?),
+            StorageFormat::Bincode => Self::decode(bytes),
+        }
+    }
+}
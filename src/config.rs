@@ -1,23 +1,1605 @@
+use ed25519_dalek::VerifyingKey;
+use serde::Deserialize;
 use std::env;
+use std::fmt::Display;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
 
-#[derive(Debug, Clone)]
-pub struct Config {
+use crate::abuse::AbuseAction;
+use crate::clock::ClockPolicy;
+use crate::enclave::AttestationPlatform;
+use crate::evidence::{CanonicalEncoding, ConsensusRule, ProvenanceMode};
+use crate::fingerprint::{self, ImageLimits};
+use crate::hdkey::DerivationPath;
+use crate::at_rest::EncryptionKeySource;
+use crate::keystore::{KeyFilePolicy, KeySource};
+use crate::log_sink::{self, LogFormat};
+use crate::mmr_store::{Durability, DEFAULT_FLUSH_EVERY_MS, DEFAULT_TENANT};
+use crate::notary::NotaryKind;
+use crate::ops_log::OPS_LOG_FILE;
+use crate::piv::TouchPolicy;
+use crate::replication::MAX_LONG_POLL_MS;
+use crate::tenant::validate_tenant_id;
+use crate::tpm;
+
+/// 未指定 `CONFIG_FILE` 时尝试加载的默认配置文件
+pub const DEFAULT_CONFIG_FILE: &str = "yuanjing.toml";
+
+/// 租户配置：租户 ID 与其 API Key
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct TenantConfig {
+    pub id: String,
+    pub api_key: String,
+}
+
+/// 审计方配置：名称与其 API Key (请求头 `X-Auditor-Key`)
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct AuditorConfig {
+    pub name: String,
+    pub api_key: String,
+}
+
+/// 副本配置 (主库一侧)：副本 ID 与其确认密钥 (请求头 `X-Replica-Key`)
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ReplicaConfig {
+    pub id: String,
+    pub ack_key: String,
+}
+
+/// 鉴定人配置：名称与其 Ed25519 公钥 (Hex)，用于核对复核意见的签名
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ExaminerConfig {
+    pub name: String,
+    pub public_key: String,
+}
+
+/// `[api]`：HTTP 服务、租户与限流
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ApiConfig {
     pub host: String,
     pub port: u16,
-    pub db_path: String,
+    /// 租户列表，为空则以单租户 `default` 模式运行且不鉴权
+    pub tenants: Vec<TenantConfig>,
+    /// 同时计算指纹的请求数上限
+    pub ingest_concurrency: usize,
+    /// 每个优先级等待计算的排队请求数上限，超出后返回 429
+    pub ingest_queue_depth: usize,
+    /// 各优先级可同时占用的计算名额
+    pub ingest_quotas: IngestQuotas,
+    /// 提交图片的文件大小上限 (字节)
+    pub image_max_bytes: u64,
+    /// 提交图片的像素数上限 (宽 × 高)，只读头部判断，超出的不解码直接拒绝
+    pub image_max_pixels: u64,
+    /// 每个租户写线程的排队上限，超出后返回 503
+    pub writer_queue_depth: usize,
+    /// 429 / 503 响应中建议的重试间隔 (秒)
+    pub retry_after_secs: u64,
+    /// 处理后台存证任务 (`/prove/async`) 的 worker 数
+    pub job_workers: usize,
+    /// 排队中的后台任务数上限，超出后返回 429
+    pub job_queue_depth: usize,
+    /// 后台任务结束后保留状态的时长 (秒)，过期后 `/jobs/{id}` 返回 404
+    pub job_retention_secs: u64,
+    /// 管理员 API Key (请求头 `X-Admin-Key`)，为空则关闭管理接口 (诉讼保全等)
+    pub admin_keys: Vec<String>,
+    /// 审计方：租户按条授权后，可凭自己的 Key 读取这些证据的原文 (见 `acl.rs`)
+    pub auditors: Vec<AuditorConfig>,
+    /// 鉴定人：只收录这些公钥签署的复核意见 (见 `review.rs`)
+    pub examiners: Vec<ExaminerConfig>,
+    /// 记录每个 API 请求的访问日志 (管理员经 `/admin/access-log` 查询)
+    pub access_log: bool,
+    /// 是否保留未带版本前缀的旧路由 (响应带 `Deprecation` 头，指向 `/v1` 下的同名接口)
+    pub legacy_routes: bool,
+    /// 旧路由的停用时间 (RFC 3339)，配置后随 `Sunset` 头告知调用方；为空则不公布
+    pub legacy_sunset: String,
+}
+
+impl Default for ApiConfig {
+    fn default() -> Self {
+        Self {
+            host: "0.0.0.0".to_string(),
+            port: 3000,
+            tenants: Vec::new(),
+            ingest_concurrency: std::thread::available_parallelism().map(|n| n.get()).unwrap_or(4),
+            ingest_queue_depth: 64,
+            ingest_quotas: IngestQuotas::default(),
+            image_max_bytes: fingerprint::DEFAULT_MAX_IMAGE_BYTES,
+            image_max_pixels: fingerprint::DEFAULT_MAX_IMAGE_PIXELS,
+            writer_queue_depth: 1024,
+            retry_after_secs: 1,
+            job_workers: 2,
+            job_queue_depth: 256,
+            job_retention_secs: 3600,
+            admin_keys: Vec::new(),
+            auditors: Vec::new(),
+            examiners: Vec::new(),
+            access_log: true,
+            legacy_routes: true,
+            legacy_sunset: String::new(),
+        }
+    }
+}
+
+/// `[api.ingest_quotas]`：各优先级可同时占用的计算名额 (不超过 `ingest_concurrency`)
+///
+/// 省略时 `urgent` 与 `normal` 可占满全部名额，`bulk` 最多占一半 (至少 1 个)。
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct IngestQuotas {
+    pub urgent: Option<usize>,
+    pub normal: Option<usize>,
+    pub bulk: Option<usize>,
+}
+
+impl ApiConfig {
+    /// 各优先级的计算配额 (按 `Priority::ALL` 顺序)，省略的取默认值
+    pub fn ingest_quotas(&self) -> [usize; 3] {
+        let concurrency = self.ingest_concurrency.max(1);
+        let quotas = &self.ingest_quotas;
+        [
+            quotas.urgent.unwrap_or(concurrency),
+            quotas.normal.unwrap_or(concurrency),
+            quotas.bulk.unwrap_or(concurrency.div_ceil(2)),
+        ]
+    }
+
+    /// 图片摄入上限
+    pub fn image_limits(&self) -> ImageLimits {
+        ImageLimits { max_bytes: self.image_max_bytes, max_pixels: self.image_max_pixels }
+    }
+
+    /// 旧路由的停用时间 (Unix 秒)，未配置或格式错误时为 None (格式由 `validate` 检查)
+    pub fn legacy_sunset_at(&self) -> Option<i64> {
+        chrono::DateTime::parse_from_rfc3339(&self.legacy_sunset).ok().map(|at| at.timestamp())
+    }
+}
+
+/// 派生签名密钥配置：部门 / 设备名称与 SLIP-0010 派生路径
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct DepartmentKeyConfig {
+    pub name: String,
+    /// 硬化派生路径，如 `m/0'/1'`
+    pub path: String,
+}
+
+/// `[signer.vault]`：从 Vault KV v2 读取签名 Seed
+///
+/// Token 不写进配置文件：优先读取 `VAULT_TOKEN` 环境变量，其次读取 `token_file`。
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct VaultConfig {
+    /// Vault 地址，如 `https://vault.example.org:8200`
+    pub addr: String,
+    /// KV v2 引擎的挂载点
+    pub mount: String,
+    /// 秘密路径 (挂载点之下)
+    pub path: String,
+    /// 保存 Seed 的字段名，取值为 64 位 Hex 或 24 词助记词
+    pub field: String,
+    /// Vault Enterprise 命名空间，空串表示不使用
+    pub namespace: String,
+    /// 校验 Vault 服务端证书的 CA (PEM)，空串则使用公共根证书
+    pub ca_cert: String,
+    /// Token 文件 (如 Vault Agent 的 sink)
+    pub token_file: String,
+}
+
+impl Default for VaultConfig {
+    fn default() -> Self {
+        Self {
+            addr: String::new(),
+            mount: "secret".to_string(),
+            path: "yuanjing/signer".to_string(),
+            field: "seed".to_string(),
+            namespace: String::new(),
+            ca_cert: String::new(),
+            token_file: String::new(),
+        }
+    }
+}
+
+/// `[signer]`：签名身份
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct SignerConfig {
+    /// 签名 Seed 的来源: file | vault | keychain | tpm | enclave | piv
+    pub key_source: KeySource,
     pub key_path: String,
+    /// 只读密钥模式：身份文件不存在时报错而不是自动生成 (生产环境建议开启)
+    pub read_only: bool,
+    /// 允许加载其他用户可访问的身份文件 (默认拒绝)
+    pub allow_insecure_permissions: bool,
+    /// 每个租户都会从自己的根密钥派生这些子密钥
+    pub departments: Vec<DepartmentKeyConfig>,
+    /// 由主身份签名的密钥吊销列表 (JSON)，文件不存在视为空列表
+    pub revocation_list: String,
+    /// 主身份的 X.509 证书链 (PEM，签名者证书在前)，空串表示不启用
+    pub certificate_chain: String,
+    /// 受信根证书 (PEM 文件列表)，用于校验证书链
+    pub trust_anchors: Vec<String>,
+    /// did:web 的域名 (`host[:port][/path]`)，空串则以主公钥的 did:key 作为标识
+    pub did_web: String,
+    /// 轮换前使用过的旧公钥 (Hex)，仍列入 DID 文档供验证历史回执
+    pub previous_keys: Vec<String>,
+    pub vault: VaultConfig,
+    pub keychain: KeychainConfig,
+    pub tpm: TpmConfig,
+    pub enclave: EnclaveConfig,
+    pub piv: SignerPivConfig,
 }
 
-impl Config {
-    pub fn from_env() -> Self {
+impl Default for SignerConfig {
+    fn default() -> Self {
+        Self {
+            key_source: KeySource::File,
+            key_path: "yuanjing.key".to_string(),
+            read_only: false,
+            allow_insecure_permissions: false,
+            departments: Vec::new(),
+            revocation_list: "revocations.json".to_string(),
+            certificate_chain: String::new(),
+            trust_anchors: Vec::new(),
+            did_web: String::new(),
+            previous_keys: Vec::new(),
+            vault: VaultConfig::default(),
+            keychain: KeychainConfig::default(),
+            tpm: TpmConfig::default(),
+            enclave: EnclaveConfig::default(),
+            piv: SignerPivConfig::default(),
+        }
+    }
+}
+
+/// `[signer.keychain]`：系统钥匙串中的签名 Seed (见 `keychain.rs`)
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct KeychainConfig {
+    /// 钥匙串条目的服务名
+    pub service: String,
+    /// 钥匙串条目的账户名
+    pub account: String,
+}
+
+impl Default for KeychainConfig {
+    fn default() -> Self {
+        Self {
+            service: "yuanjing".to_string(),
+            account: "signer".to_string(),
+        }
+    }
+}
+
+/// `[signer.tpm]`：封存在主机 TPM 中的签名 Seed (见 `tpm.rs`)
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct TpmConfig {
+    /// 封存文件 (`keygen` 写出的 JSON)
+    pub sealed_path: String,
+    /// 封存策略绑定的 PCR (如 `sha256:0,2,4,7`)，`keygen` 封存时使用；解封以文件中记录的为准
+    pub pcrs: String,
+    /// tpm2-tools 的 TCTI (如 `device:/dev/tpmrm0`)，空串取工具默认值
+    pub tcti: String,
+}
+
+impl Default for TpmConfig {
+    fn default() -> Self {
+        Self {
+            sealed_path: "yuanjing.sealed.json".to_string(),
+            pcrs: "sha256:0,2,4,7".to_string(),
+            tcti: String::new(),
+        }
+    }
+}
+
+/// `[signer.enclave]`：飞地签名进程 (见 `enclave.rs`)
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct EnclaveConfig {
+    /// 飞地签名进程监听的 Unix 套接字；服务 (`key_source = "enclave"`) 与 `enclave-signer` 两侧共用
+    pub socket: String,
+    /// 飞地一侧：远程证明的平台 none | sgx | tsm
+    pub platform: AttestationPlatform,
+    /// 服务一侧：启动时拿不到远程证明即拒绝启动 (关闭后 `/identity` 的 `enclave` 为 null)
+    pub require_attestation: bool,
+}
+
+impl Default for EnclaveConfig {
+    fn default() -> Self {
+        Self {
+            socket: "/run/yuanjing/enclave.sock".to_string(),
+            platform: AttestationPlatform::None,
+            require_attestation: true,
+        }
+    }
+}
+
+/// `[signer.piv]`：签名身份所在的 PIV 硬件令牌 (`key_source = "piv"`，见 `piv.rs`)
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct SignerPivConfig {
+    /// `yubico-piv-tool` 可执行文件
+    pub command: String,
+    /// 读卡器名称 (子串匹配)，空串表示第一个读卡器
+    pub reader: String,
+    /// 主身份 (默认租户) 密钥所在的槽位 (Hex)
+    pub slot: String,
+    /// 各槽位至少应有的触摸策略: always | cached | never
+    pub touch_policy: TouchPolicy,
+    /// 证明 CA 证书 (PEM)，空串表示不校验
+    pub attestation_ca: String,
+    /// 其他租户的根密钥所在槽位 (租户 ID → Hex 槽位)；令牌里的私钥派生不出子密钥，每个租户须单独占一个槽位
+    pub tenant_slots: std::collections::BTreeMap<String, String>,
+}
+
+impl SignerPivConfig {
+    /// 某个槽位的令牌配置
+    pub fn slot(&self, slot: &str) -> PivConfig {
+        PivConfig {
+            command: self.command.clone(),
+            reader: self.reader.clone(),
+            slot: slot.to_string(),
+            touch_policy: self.touch_policy,
+            attestation_ca: self.attestation_ca.clone(),
+        }
+    }
+}
+
+impl Default for SignerPivConfig {
+    fn default() -> Self {
+        let token = PivConfig::default();
+        Self {
+            command: token.command,
+            reader: token.reader,
+            slot: token.slot,
+            touch_policy: token.touch_policy,
+            attestation_ca: token.attestation_ca,
+            tenant_slots: std::collections::BTreeMap::new(),
+        }
+    }
+}
+
+/// `[store]`：存储、缓存与持久化
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct StoreConfig {
+    pub db_path: String,
+    /// 证明缓存容量 (条目数)，0 表示关闭
+    pub proof_cache_capacity: usize,
+    /// 证据读缓存容量 (条目数)，0 表示关闭
+    pub evidence_cache_capacity: usize,
+    /// 山峰缓存自检间隔 (条)，0 表示关闭
+    pub peak_check_interval: u64,
+    /// 签名日志检查点间隔 (次签名)，0 表示不写检查点
+    pub signing_checkpoint_interval: u64,
+    /// 持久化策略: durable | fast
+    pub durability: Durability,
+    /// sled 后台刷盘间隔 (毫秒)，0 表示关闭后台刷盘
+    pub flush_every_ms: u64,
+    /// 新租户的规范编码: bcs | protobuf
+    pub canonical_encoding: CanonicalEncoding,
+    /// 新叶子是否加盐 (叶子哈希为 Blake3(salt || 规范字节)，销毁盐值即可密码学擦除)
+    pub salted_leaves: bool,
+    /// 证据原文保留年限，0 表示永久保留 (到期只删原文，叶子哈希与证明保留)
+    pub retention_years: u32,
+    /// 保留期清理间隔 (秒)
+    pub retention_check_interval_secs: u64,
+    /// 启动时执行完整性检查，发现 error 级问题时拒绝启动
+    pub integrity_check: bool,
+    pub encryption: EncryptionConfig,
+}
+
+/// `[store.encryption]`：证据原文静态加密 (见 `at_rest.rs`)
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct EncryptionConfig {
+    /// 主密钥的来源: none | env | file | vault
+    pub key_source: EncryptionKeySource,
+    /// `key_source = "file"` 时的密钥文件 (64 位 Hex)
+    pub key_file: String,
+    /// `key_source = "vault"` 时读取主密钥的位置，字段取值为 64 位 Hex
+    pub vault: VaultConfig,
+}
+
+impl Default for EncryptionConfig {
+    fn default() -> Self {
+        Self {
+            key_source: EncryptionKeySource::None,
+            key_file: String::new(),
+            vault: VaultConfig { path: "yuanjing/evidence".to_string(), field: "key".to_string(), ..VaultConfig::default() },
+        }
+    }
+}
+
+impl Default for StoreConfig {
+    fn default() -> Self {
         Self {
-            host: env::var("HOST").unwrap_or_else(|_| "0.0.0.0".to_string()),
-            port: env::var("PORT")
-                .unwrap_or_else(|_| "3000".to_string())
-                .parse()
-                .expect("PORT must be a number"),
-            db_path: env::var("DB_PATH").unwrap_or_else(|_| "data/db/mmr_db".to_string()),
-            key_path: env::var("KEY_PATH").unwrap_or_else(|_| "yuanjing.key".to_string()),
+            db_path: "data/db/mmr_db".to_string(),
+            proof_cache_capacity: 1024,
+            evidence_cache_capacity: 4096,
+            peak_check_interval: 1000,
+            signing_checkpoint_interval: 100,
+            durability: Durability::Durable,
+            flush_every_ms: DEFAULT_FLUSH_EVERY_MS,
+            canonical_encoding: CanonicalEncoding::Bcs,
+            salted_leaves: false,
+            retention_years: 0,
+            retention_check_interval_secs: 86400,
+            integrity_check: false,
+            encryption: EncryptionConfig::default(),
+        }
+    }
+}
+
+impl SignerConfig {
+    pub fn key_file_policy(&self) -> KeyFilePolicy {
+        KeyFilePolicy {
+            read_only: self.read_only,
+            allow_insecure_permissions: self.allow_insecure_permissions,
         }
     }
 }
+
+impl StoreConfig {
+    /// sled 后台刷盘间隔，None 表示关闭
+    pub fn flush_interval(&self) -> Option<u64> {
+        Some(self.flush_every_ms).filter(|ms| *ms > 0)
+    }
+}
+
+/// `[policy]`：签发策略 (时钟校验、多模型共识)
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct PolicyConfig {
+    /// NTP 服务器列表，为空则关闭时钟校验
+    pub ntp_servers: Vec<String>,
+    /// 允许的最大时钟偏差 (毫秒)
+    pub clock_max_skew_ms: i64,
+    /// 周期校验间隔 (秒)
+    pub clock_check_interval_secs: u64,
+    /// 偏差超限时的策略: refuse | flag
+    pub clock_policy: ClockPolicy,
+    /// 多模型证据缺省的共识规则: majority | unanimous | confidence_weighted (请求可逐条指定)
+    pub consensus_rule: ConsensusRule,
+}
+
+impl Default for PolicyConfig {
+    fn default() -> Self {
+        Self {
+            ntp_servers: Vec::new(),
+            clock_max_skew_ms: 2000,
+            clock_check_interval_secs: 300,
+            clock_policy: ClockPolicy::Refuse,
+            consensus_rule: ConsensusRule::Majority,
+        }
+    }
+}
+
+/// `[anchoring]`：外部锚定 (根发布等)
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct AnchoringConfig {
+    /// 签名树头的发布间隔 (秒)，经 `/.well-known/yuanjing-root` 公开
+    pub root_publish_interval_secs: u64,
+    /// DNS TXT 记录名后缀 (租户 `t` 写入 `t.<dns_name>`)，为空则不写 DNS
+    pub dns_name: String,
+    /// 接受动态更新的权威服务器，为空时由 nsupdate 自行查找
+    pub dns_server: String,
+    /// TSIG 密钥文件，为空时不签名
+    pub dns_key_file: String,
+    pub dns_ttl: u32,
+    /// nsupdate 可执行文件
+    pub nsupdate: String,
+    /// 向外部公证方提交树头的间隔 (秒)
+    pub notary_interval_secs: u64,
+    /// 允许公证时间早于树头时间的最大偏差 (秒)，超出视为公证方时钟异常
+    pub notary_max_skew_secs: i64,
+    /// 外部公证方，为空则不做交叉公证
+    pub notaries: Vec<NotaryConfig>,
+    /// Rekor 透明日志地址 (如 `https://rekor.sigstore.dev`)，为空则不写 Rekor
+    pub rekor_url: String,
+    /// 向 Rekor 提交树头的间隔 (秒)
+    pub rekor_interval_secs: u64,
+}
+
+/// `[[anchoring.notaries]]`：外部公证方
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct NotaryConfig {
+    /// 本地名称，用于日志与公证回执
+    pub name: String,
+    /// 公证接口地址 (`POST`)
+    pub url: String,
+    /// `rfc3161` (时间戳机构，默认) 或 `countersign` (本服务自定的副署协议)
+    #[serde(default)]
+    pub protocol: NotaryKind,
+    /// `rfc3161`：TSA 证书的受信根 (PEM 文件)
+    #[serde(default)]
+    pub trust_anchors: Vec<String>,
+    /// `rfc3161`：要求的时间戳策略 (OID)，留空由 TSA 选择
+    #[serde(default)]
+    pub policy: String,
+    /// `countersign`：公证方公钥 (Hex)，经带外渠道核对后固定
+    #[serde(default)]
+    pub public_key: String,
+    /// 以 `Authorization: Bearer` 发送，留空则不发送
+    #[serde(default)]
+    pub api_key: String,
+}
+
+impl Default for AnchoringConfig {
+    fn default() -> Self {
+        Self {
+            root_publish_interval_secs: 60,
+            dns_name: String::new(),
+            dns_server: String::new(),
+            dns_key_file: String::new(),
+            dns_ttl: 300,
+            nsupdate: "nsupdate".to_string(),
+            notary_interval_secs: 3600,
+            notary_max_skew_secs: 300,
+            notaries: Vec::new(),
+            rekor_url: String::new(),
+            rekor_interval_secs: 3600,
+        }
+    }
+}
+
+/// `[replication]`：只读副本 (`yuanjing serve --replica`)
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ReplicationConfig {
+    /// 以只读副本身份运行：从主库复制叶子，拒绝写接口
+    pub replica: bool,
+    /// 主库地址，如 `http://primary:3000`
+    pub primary_url: String,
+    /// 主库的管理员 API Key (复制接口需要)
+    pub admin_key: String,
+    /// 两轮同步之间的间隔 (毫秒)
+    pub sync_interval_ms: u64,
+    /// 每次拉取的叶子数 (至多 1000)
+    pub batch_size: usize,
+    /// 副本追上后在主库长轮询的时长 (毫秒，至多 10000)，0 表示按 `sync_interval_ms` 轮询
+    pub long_poll_ms: u64,
+    /// 其他副本的地址，热备接管写入前从它们补齐叶子
+    pub peers: Vec<String>,
+    /// 副本：向主库确认复制进度的密钥 (`X-Replica-Key`)，对应主库 `replicas` 中本节点的 `ack_key`；空串表示不参与确认
+    pub ack_key: String,
+    /// 主库：可以确认复制进度的副本，只有持对应密钥的确认计入多数派
+    pub replicas: Vec<ReplicaConfig>,
+    /// 主库：`/prove` 返回回执前需确认的副本数，0 表示不等待 (异步复制)；超时返回 503，不返回回执
+    pub ack_quorum: usize,
+    /// 主库：等待副本确认的超时 (毫秒)
+    pub ack_timeout_ms: u64,
+}
+
+impl Default for ReplicationConfig {
+    fn default() -> Self {
+        Self {
+            replica: false,
+            primary_url: String::new(),
+            admin_key: String::new(),
+            sync_interval_ms: 1000,
+            batch_size: 500,
+            long_poll_ms: 0,
+            peers: Vec::new(),
+            ack_key: String::new(),
+            replicas: Vec::new(),
+            ack_quorum: 0,
+            ack_timeout_ms: 5000,
+        }
+    }
+}
+
+/// `[failover]`：主备切换的写租约 (见 `failover.rs`)
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct FailoverConfig {
+    /// 主备共享的租约目录 (如 NFS 挂载点)，空串表示不启用
+    pub lease_dir: String,
+    /// 本节点 ID (主备各不相同)，写入租约文件标明持有者
+    pub node_id: String,
+    /// 租期 (毫秒)
+    pub lease_ttl_ms: u64,
+    /// 续约 / 检查接管的间隔 (毫秒)
+    pub renew_interval_ms: u64,
+}
+
+impl Default for FailoverConfig {
+    fn default() -> Self {
+        Self {
+            lease_dir: String::new(),
+            node_id: String::new(),
+            lease_ttl_ms: 15000,
+            renew_interval_ms: 3000,
+        }
+    }
+}
+
+impl FailoverConfig {
+    pub fn enabled(&self) -> bool {
+        !self.lease_dir.is_empty()
+    }
+}
+
+/// `[telemetry]`：链路追踪导出 (见 `telemetry.rs`)
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct TelemetryConfig {
+    /// OTLP/HTTP 端点，如 `http://collector:4318`，空串表示不导出
+    pub otlp_endpoint: String,
+    pub service_name: String,
+    /// 采样比例 (0.0 ~ 1.0)
+    pub sample_ratio: f64,
+}
+
+impl Default for TelemetryConfig {
+    fn default() -> Self {
+        Self {
+            otlp_endpoint: String::new(),
+            service_name: "yuanjing".to_string(),
+            sample_ratio: 1.0,
+        }
+    }
+}
+
+/// `[ops_log]`：运维审计日志 (见 `ops_log.rs`)
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct OpsLogConfig {
+    /// 日志文件 (JSON Lines，只追加)；省略时为 `<store.db_path>/ops.log`，空串表示关闭
+    ///
+    /// 检查点叶子在该库的 MMR 中，日志须与库成对存放，默认放进库目录，换库时不会误用别的库的日志。
+    pub path: Option<String>,
+    /// 把链头写进各租户 MMR 的间隔 (秒)，0 表示不写检查点
+    pub checkpoint_interval_secs: u64,
+}
+
+impl Default for OpsLogConfig {
+    fn default() -> Self {
+        Self {
+            path: None,
+            checkpoint_interval_secs: 3600,
+        }
+    }
+}
+
+/// `[abuse]`：提交滥用检测 (见 `abuse.rs`)
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct AbuseConfig {
+    /// 统计时间窗 (秒)
+    pub window_secs: u64,
+    /// 时间窗内同一张图片最多提交的次数，0 表示不检查
+    pub max_identical_images: u32,
+    /// 置信度低于该值 (0.0 ~ 1.0) 的判定视为低置信度
+    pub low_confidence_below: f64,
+    /// 时间窗内最多的低置信度判定数，0 表示不检查
+    pub max_low_confidence: u32,
+    /// 触发后的处理: flag | throttle
+    pub action: AbuseAction,
+    /// `throttle` 时拒绝该提交方存证的时长 (秒)
+    pub throttle_secs: u64,
+}
+
+impl Default for AbuseConfig {
+    fn default() -> Self {
+        Self {
+            window_secs: 600,
+            max_identical_images: 20,
+            low_confidence_below: 0.6,
+            max_low_confidence: 100,
+            action: AbuseAction::Flag,
+            throttle_secs: 900,
+        }
+    }
+}
+
+/// `[provenance]`：提交来源采集 (见 `provenance.rs`)
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ProvenanceConfig {
+    /// 把请求方 IP、客户端证书主题与 User-Agent 写进证据 (默认关闭)
+    pub enabled: bool,
+    /// 记录方式: plain | hashed | pseudonymized (默认)
+    pub mode: ProvenanceMode,
+    /// `mode = "pseudonymized"` 时的密钥文件 (64 位 Hex)，不存在时自动生成
+    pub key_file: String,
+    /// IP 取 `X-Forwarded-For` 的最后一个地址 (仅在可信反向代理之后开启)
+    pub trust_forwarded_for: bool,
+    /// 终止 TLS 的代理写入客户端证书主题的请求头 (如 `x-client-cert-subject`)，留空表示不采集
+    pub client_cert_header: String,
+}
+
+impl Default for ProvenanceConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            mode: ProvenanceMode::Pseudonymized,
+            key_file: "yuanjing.provenance.key".to_string(),
+            trust_forwarded_for: false,
+            client_cert_header: String::new(),
+        }
+    }
+}
+
+/// `[anomaly]`：激活路径异常检测 (见 `anomaly.rs`)
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct AnomalyConfig {
+    /// 后台分析间隔 (秒)，0 表示关闭
+    pub interval_secs: u64,
+    /// 同模型在先证据达到该数量后才按频率与个数判断
+    pub min_samples: u64,
+    /// 在先证据中激活频率低于该值的 Prompt 视为罕见 (0.0 ~ 1.0)
+    pub rare_frequency: f64,
+    /// 激活个数偏离平均值超过该倍数的标准差视为异常
+    pub max_z_score: f64,
+}
+
+impl Default for AnomalyConfig {
+    fn default() -> Self {
+        Self {
+            interval_secs: 3600,
+            min_samples: 50,
+            rare_frequency: 0.01,
+            max_z_score: 4.0,
+        }
+    }
+}
+
+/// `[search]`：证据元数据全文检索 (见 `search.rs`)
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct SearchConfig {
+    pub enabled: bool,
+    /// 索引目录，每个租户一个子目录；删掉后重启即从存储重建
+    pub index_dir: String,
+    /// 后台把新证据与改动写进索引的间隔 (毫秒)，也是检索结果的最大延迟
+    pub refresh_interval_ms: u64,
+}
+
+impl Default for SearchConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            index_dir: "data/search".to_string(),
+            refresh_interval_ms: 1000,
+        }
+    }
+}
+
+/// `[registry]`：签名的模型注册表 (见 `model_registry.rs`)
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct RegistryConfig {
+    /// 受信根元数据 (签名的 JSON) 文件，每个租户启动时以它引导；空串表示不启用签名模式
+    pub trusted_root: String,
+}
+
+/// `[piv]`：鉴定人的 PIV 硬件令牌 (见 `piv.rs`)，`review-sign --piv` 使用
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct PivConfig {
+    /// `yubico-piv-tool` 可执行文件
+    pub command: String,
+    /// 读卡器名称 (子串匹配)，空串表示第一个读卡器
+    pub reader: String,
+    /// 鉴定人密钥所在的槽位 (Hex，如 `9c`)
+    pub slot: String,
+    /// 槽位至少应有的触摸策略: always | cached | never
+    pub touch_policy: TouchPolicy,
+    /// 证明 CA 证书 (PEM，可含多张)，令牌的 `f9` 证明证书须由其中之一签发；空串表示不校验
+    pub attestation_ca: String,
+}
+
+impl Default for PivConfig {
+    fn default() -> Self {
+        Self {
+            command: "yubico-piv-tool".to_string(),
+            reader: String::new(),
+            slot: "9c".to_string(),
+            touch_policy: TouchPolicy::Always,
+            attestation_ca: String::new(),
+        }
+    }
+}
+
+/// `[logging]`：日志输出 (见 `log_sink.rs`)
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct LoggingConfig {
+    /// 最低记录级别：`error`、`warn`、`info` (默认)、`debug`、`trace` 或 `off`
+    pub level: log::LevelFilter,
+    /// `text` (原样) 或 `json` (每行一个 JSON 对象)，同时作用于标准输出与日志文件
+    pub format: LogFormat,
+    /// 是否写标准输出 (只用文件或 syslog 时可关闭)
+    pub stdout: bool,
+    /// 日志文件路径，空串表示不写文件
+    pub file: String,
+    /// 单个日志文件的大小上限 (字节)，超过即轮转，0 表示不轮转
+    pub file_max_bytes: u64,
+    /// 保留的历史文件数 (`<file>.1` ~ `<file>.N`)
+    pub file_keep: u32,
+    /// 是否发往本机 syslog
+    pub syslog: bool,
+    /// syslog 的 Unix 域套接字
+    pub syslog_socket: String,
+    /// syslog 设施：`user`、`daemon`、`auth`、`local0` ~ `local7`
+    pub syslog_facility: String,
+    /// syslog 标识，也是 JSON 行中的 `service`
+    pub syslog_ident: String,
+}
+
+impl Default for LoggingConfig {
+    fn default() -> Self {
+        Self {
+            level: log::LevelFilter::Info,
+            format: LogFormat::Text,
+            stdout: true,
+            file: String::new(),
+            file_max_bytes: 10 * 1024 * 1024,
+            file_keep: 5,
+            syslog: false,
+            syslog_socket: "/dev/log".to_string(),
+            syslog_facility: "daemon".to_string(),
+            syslog_ident: "yuanjing".to_string(),
+        }
+    }
+}
+
+impl LoggingConfig {
+    /// 是否偏离默认的输出 (文本格式只写标准输出)；偏离时 `serve` 启动时列出各输出
+    pub fn customized(&self) -> bool {
+        self.format != LogFormat::Text || !self.stdout || !self.file.is_empty() || self.syslog
+    }
+}
+
+/// 模块：配置 (Configuration)
+///
+/// **分层加载**: 内置默认值 → 配置文件 (TOML) → 环境变量覆盖，后者优先。
+/// - 配置文件路径由 `CONFIG_FILE` 指定；未指定时若当前目录存在 `yuanjing.toml` 则加载它。
+/// - 环境变量沿用原有名称 (`PORT`、`DB_PATH`、`TENANTS`…)，便于容器部署。
+///
+/// **校验**: 加载时把所有问题一次性列出，而不是在第一个非法值处 panic。
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    pub api: ApiConfig,
+    pub signer: SignerConfig,
+    pub store: StoreConfig,
+    pub policy: PolicyConfig,
+    pub anchoring: AnchoringConfig,
+    pub replication: ReplicationConfig,
+    pub failover: FailoverConfig,
+    pub telemetry: TelemetryConfig,
+    pub ops_log: OpsLogConfig,
+    pub abuse: AbuseConfig,
+    pub anomaly: AnomalyConfig,
+    pub provenance: ProvenanceConfig,
+    pub search: SearchConfig,
+    pub registry: RegistryConfig,
+    pub piv: PivConfig,
+    pub logging: LoggingConfig,
+}
+
+impl Config {
+    /// 按 默认值 → 配置文件 → 环境变量 的顺序加载并校验
+    pub fn load() -> anyhow::Result<Self> {
+        let mut config = match env::var("CONFIG_FILE") {
+            Ok(path) => Self::from_file(&path)?,
+            Err(_) if Path::new(DEFAULT_CONFIG_FILE).exists() => Self::from_file(DEFAULT_CONFIG_FILE)?,
+            Err(_) => Self::default(),
+        };
+        config.apply_env()?;
+        config.validate()?;
+        Ok(config)
+    }
+
+    /// 读取 TOML 配置文件 (未出现的字段取默认值)
+    pub fn from_file(path: &str) -> anyhow::Result<Self> {
+        let text = std::fs::read_to_string(path)
+            .map_err(|e| anyhow::anyhow!("Cannot read config file '{}': {}", path, e))?;
+        toml::from_str(&text).map_err(|e| anyhow::anyhow!("Invalid config file '{}': {}", path, e))
+    }
+
+    /// 用环境变量覆盖配置
+    pub fn apply_env(&mut self) -> anyhow::Result<()> {
+        override_from_env("HOST", &mut self.api.host)?;
+        override_from_env("PORT", &mut self.api.port)?;
+        if let Ok(value) = env::var("TENANTS") {
+            self.api.tenants = parse_tenants(&value)?;
+        }
+        override_from_env("INGEST_CONCURRENCY", &mut self.api.ingest_concurrency)?;
+        override_from_env("INGEST_QUEUE_DEPTH", &mut self.api.ingest_queue_depth)?;
+        override_from_env("IMAGE_MAX_BYTES", &mut self.api.image_max_bytes)?;
+        override_from_env("IMAGE_MAX_PIXELS", &mut self.api.image_max_pixels)?;
+        for (name, quota) in [
+            ("INGEST_QUOTA_URGENT", &mut self.api.ingest_quotas.urgent),
+            ("INGEST_QUOTA_NORMAL", &mut self.api.ingest_quotas.normal),
+            ("INGEST_QUOTA_BULK", &mut self.api.ingest_quotas.bulk),
+        ] {
+            if env::var(name).is_ok() {
+                let mut value = 0;
+                override_from_env(name, &mut value)?;
+                *quota = Some(value);
+            }
+        }
+        override_from_env("WRITER_QUEUE_DEPTH", &mut self.api.writer_queue_depth)?;
+        override_from_env("RETRY_AFTER_SECS", &mut self.api.retry_after_secs)?;
+        override_from_env("JOB_WORKERS", &mut self.api.job_workers)?;
+        override_from_env("JOB_QUEUE_DEPTH", &mut self.api.job_queue_depth)?;
+        override_from_env("JOB_RETENTION_SECS", &mut self.api.job_retention_secs)?;
+        if let Ok(value) = env::var("ADMIN_API_KEYS") {
+            self.api.admin_keys = split_list(&value);
+        }
+        override_from_env("ACCESS_LOG", &mut self.api.access_log)?;
+        override_from_env("LEGACY_ROUTES", &mut self.api.legacy_routes)?;
+        override_from_env("LEGACY_SUNSET", &mut self.api.legacy_sunset)?;
+
+        override_from_env("KEY_SOURCE", &mut self.signer.key_source)?;
+        override_from_env("KEY_PATH", &mut self.signer.key_path)?;
+        override_from_env("KEY_READ_ONLY", &mut self.signer.read_only)?;
+        override_from_env("KEY_ALLOW_INSECURE_PERMISSIONS", &mut self.signer.allow_insecure_permissions)?;
+        override_from_env("REVOCATION_LIST", &mut self.signer.revocation_list)?;
+        override_from_env("CERTIFICATE_CHAIN", &mut self.signer.certificate_chain)?;
+        if let Ok(value) = env::var("TRUST_ANCHORS") {
+            self.signer.trust_anchors = split_list(&value);
+        }
+        override_from_env("DID_WEB", &mut self.signer.did_web)?;
+        override_from_env("VAULT_ADDR", &mut self.signer.vault.addr)?;
+        override_from_env("VAULT_NAMESPACE", &mut self.signer.vault.namespace)?;
+        override_from_env("VAULT_CACERT", &mut self.signer.vault.ca_cert)?;
+        override_from_env("KEYCHAIN_SERVICE", &mut self.signer.keychain.service)?;
+        override_from_env("KEYCHAIN_ACCOUNT", &mut self.signer.keychain.account)?;
+        override_from_env("TPM_SEALED_PATH", &mut self.signer.tpm.sealed_path)?;
+        override_from_env("TPM_PCRS", &mut self.signer.tpm.pcrs)?;
+        override_from_env("TPM_TCTI", &mut self.signer.tpm.tcti)?;
+        override_from_env("ENCLAVE_SOCKET", &mut self.signer.enclave.socket)?;
+        override_from_env("SIGNER_PIV_READER", &mut self.signer.piv.reader)?;
+        override_from_env("SIGNER_PIV_SLOT", &mut self.signer.piv.slot)?;
+        override_from_env("SIGNER_PIV_TOUCH_POLICY", &mut self.signer.piv.touch_policy)?;
+        override_from_env("ENCLAVE_PLATFORM", &mut self.signer.enclave.platform)?;
+        override_from_env("ENCLAVE_REQUIRE_ATTESTATION", &mut self.signer.enclave.require_attestation)?;
+        if let Ok(value) = env::var("PREVIOUS_KEYS") {
+            self.signer.previous_keys = split_list(&value);
+        }
+        if let Ok(value) = env::var("DEPARTMENTS") {
+            self.signer.departments = parse_departments(&value)?;
+        }
+
+        override_from_env("DB_PATH", &mut self.store.db_path)?;
+        override_from_env("PROOF_CACHE_CAPACITY", &mut self.store.proof_cache_capacity)?;
+        override_from_env("EVIDENCE_CACHE_CAPACITY", &mut self.store.evidence_cache_capacity)?;
+        override_from_env("PEAK_CHECK_INTERVAL", &mut self.store.peak_check_interval)?;
+        override_from_env("SIGNING_CHECKPOINT_INTERVAL", &mut self.store.signing_checkpoint_interval)?;
+        override_from_env("DURABILITY", &mut self.store.durability)?;
+        override_from_env("FLUSH_EVERY_MS", &mut self.store.flush_every_ms)?;
+        override_from_env("CANONICAL_ENCODING", &mut self.store.canonical_encoding)?;
+        override_from_env("SALTED_LEAVES", &mut self.store.salted_leaves)?;
+        override_from_env("RETENTION_YEARS", &mut self.store.retention_years)?;
+        override_from_env("RETENTION_CHECK_INTERVAL_SECS", &mut self.store.retention_check_interval_secs)?;
+        override_from_env("INTEGRITY_CHECK", &mut self.store.integrity_check)?;
+        override_from_env("EVIDENCE_KEY_SOURCE", &mut self.store.encryption.key_source)?;
+        override_from_env("EVIDENCE_KEY_FILE", &mut self.store.encryption.key_file)?;
+
+        if let Ok(value) = env::var("NTP_SERVERS") {
+            self.policy.ntp_servers = split_list(&value);
+        }
+        override_from_env("CLOCK_MAX_SKEW_MS", &mut self.policy.clock_max_skew_ms)?;
+        override_from_env("CLOCK_CHECK_INTERVAL_SECS", &mut self.policy.clock_check_interval_secs)?;
+        override_from_env("CLOCK_POLICY", &mut self.policy.clock_policy)?;
+        override_from_env("CONSENSUS_RULE", &mut self.policy.consensus_rule)?;
+
+        override_from_env("ROOT_PUBLISH_INTERVAL_SECS", &mut self.anchoring.root_publish_interval_secs)?;
+        override_from_env("ROOT_DNS_NAME", &mut self.anchoring.dns_name)?;
+        override_from_env("ROOT_DNS_SERVER", &mut self.anchoring.dns_server)?;
+        override_from_env("ROOT_DNS_KEY_FILE", &mut self.anchoring.dns_key_file)?;
+        override_from_env("ROOT_DNS_TTL", &mut self.anchoring.dns_ttl)?;
+        override_from_env("NSUPDATE", &mut self.anchoring.nsupdate)?;
+        override_from_env("NOTARY_INTERVAL_SECS", &mut self.anchoring.notary_interval_secs)?;
+        override_from_env("NOTARY_MAX_SKEW_SECS", &mut self.anchoring.notary_max_skew_secs)?;
+        override_from_env("REKOR_URL", &mut self.anchoring.rekor_url)?;
+        override_from_env("REKOR_INTERVAL_SECS", &mut self.anchoring.rekor_interval_secs)?;
+
+        override_from_env("REPLICA", &mut self.replication.replica)?;
+        override_from_env("REPLICA_PRIMARY_URL", &mut self.replication.primary_url)?;
+        override_from_env("REPLICA_ADMIN_KEY", &mut self.replication.admin_key)?;
+        override_from_env("REPLICA_SYNC_INTERVAL_MS", &mut self.replication.sync_interval_ms)?;
+        override_from_env("REPLICA_BATCH_SIZE", &mut self.replication.batch_size)?;
+        override_from_env("REPLICA_LONG_POLL_MS", &mut self.replication.long_poll_ms)?;
+        if let Ok(value) = env::var("REPLICA_PEERS") {
+            self.replication.peers = split_list(&value);
+        }
+        override_from_env("REPLICA_ACK_KEY", &mut self.replication.ack_key)?;
+        if let Ok(value) = env::var("REPLICATION_REPLICAS") {
+            self.replication.replicas = parse_replicas(&value)?;
+        }
+        override_from_env("REPLICATION_ACK_QUORUM", &mut self.replication.ack_quorum)?;
+        override_from_env("REPLICATION_ACK_TIMEOUT_MS", &mut self.replication.ack_timeout_ms)?;
+        override_from_env("LEASE_DIR", &mut self.failover.lease_dir)?;
+        override_from_env("NODE_ID", &mut self.failover.node_id)?;
+        override_from_env("LEASE_TTL_MS", &mut self.failover.lease_ttl_ms)?;
+        override_from_env("LEASE_RENEW_INTERVAL_MS", &mut self.failover.renew_interval_ms)?;
+        // 沿用 OpenTelemetry 的标准环境变量名
+        override_from_env("OTEL_EXPORTER_OTLP_ENDPOINT", &mut self.telemetry.otlp_endpoint)?;
+        override_from_env("OTEL_SERVICE_NAME", &mut self.telemetry.service_name)?;
+        override_from_env("TRACE_SAMPLE_RATIO", &mut self.telemetry.sample_ratio)?;
+
+        if let Ok(value) = env::var("OPS_LOG_PATH") {
+            self.ops_log.path = Some(value.trim().to_string());
+        }
+        override_from_env("OPS_LOG_CHECKPOINT_INTERVAL_SECS", &mut self.ops_log.checkpoint_interval_secs)?;
+
+        override_from_env("ABUSE_WINDOW_SECS", &mut self.abuse.window_secs)?;
+        override_from_env("ABUSE_MAX_IDENTICAL_IMAGES", &mut self.abuse.max_identical_images)?;
+        override_from_env("ABUSE_LOW_CONFIDENCE_BELOW", &mut self.abuse.low_confidence_below)?;
+        override_from_env("ABUSE_MAX_LOW_CONFIDENCE", &mut self.abuse.max_low_confidence)?;
+        override_from_env("ABUSE_ACTION", &mut self.abuse.action)?;
+        override_from_env("ABUSE_THROTTLE_SECS", &mut self.abuse.throttle_secs)?;
+
+        override_from_env("ANOMALY_INTERVAL_SECS", &mut self.anomaly.interval_secs)?;
+        override_from_env("ANOMALY_MIN_SAMPLES", &mut self.anomaly.min_samples)?;
+        override_from_env("ANOMALY_RARE_FREQUENCY", &mut self.anomaly.rare_frequency)?;
+        override_from_env("ANOMALY_MAX_Z_SCORE", &mut self.anomaly.max_z_score)?;
+
+        override_from_env("PROVENANCE_ENABLED", &mut self.provenance.enabled)?;
+        override_from_env("PROVENANCE_MODE", &mut self.provenance.mode)?;
+        override_from_env("PROVENANCE_KEY_FILE", &mut self.provenance.key_file)?;
+        override_from_env("PROVENANCE_TRUST_FORWARDED_FOR", &mut self.provenance.trust_forwarded_for)?;
+        override_from_env("PROVENANCE_CLIENT_CERT_HEADER", &mut self.provenance.client_cert_header)?;
+
+        override_from_env("SEARCH_ENABLED", &mut self.search.enabled)?;
+        override_from_env("SEARCH_INDEX_DIR", &mut self.search.index_dir)?;
+        override_from_env("SEARCH_REFRESH_INTERVAL_MS", &mut self.search.refresh_interval_ms)?;
+
+        override_from_env("MODEL_REGISTRY_ROOT", &mut self.registry.trusted_root)?;
+        override_from_env("PIV_READER", &mut self.piv.reader)?;
+        override_from_env("PIV_SLOT", &mut self.piv.slot)?;
+        override_from_env("PIV_TOUCH_POLICY", &mut self.piv.touch_policy)?;
+
+        override_from_env("LOG_LEVEL", &mut self.logging.level)?;
+        override_from_env("LOG_FORMAT", &mut self.logging.format)?;
+        override_from_env("LOG_STDOUT", &mut self.logging.stdout)?;
+        override_from_env("LOG_FILE", &mut self.logging.file)?;
+        override_from_env("LOG_FILE_MAX_BYTES", &mut self.logging.file_max_bytes)?;
+        override_from_env("LOG_FILE_KEEP", &mut self.logging.file_keep)?;
+        override_from_env("LOG_SYSLOG", &mut self.logging.syslog)?;
+        override_from_env("LOG_SYSLOG_SOCKET", &mut self.logging.syslog_socket)?;
+        override_from_env("LOG_SYSLOG_FACILITY", &mut self.logging.syslog_facility)?;
+        override_from_env("LOG_SYSLOG_IDENT", &mut self.logging.syslog_ident)?;
+        Ok(())
+    }
+
+    /// 运维日志文件：未配置时取库目录下的 `ops.log`，配置为空串时为 None (关闭)
+    pub fn ops_log_path(&self) -> Option<PathBuf> {
+        self.ops_log_path_for(&self.store.db_path)
+    }
+
+    /// 库目录为 `db_path` 时的运维日志文件 (快照恢复到别的目录时用)
+    pub fn ops_log_path_for(&self, db_path: &str) -> Option<PathBuf> {
+        match self.ops_log.path.as_deref() {
+            None => Some(Path::new(db_path).join(OPS_LOG_FILE)),
+            Some("") => None,
+            Some(path) => Some(PathBuf::from(path)),
+        }
+    }
+
+    /// 生效配置的指纹：Blake3(完整配置) 的前 16 字节 (Hex)，记入运维日志以发现配置变更
+    ///
+    /// 配置中的 API Key 等秘密只参与哈希，不会出现在日志里。
+    pub fn fingerprint(&self) -> String {
+        hex::encode(&blake3::hash(format!("{:?}", self).as_bytes()).as_bytes()[..16])
+    }
+
+    /// 校验配置，汇总所有错误
+    pub fn validate(&self) -> anyhow::Result<()> {
+        let mut errors = Vec::new();
+
+        if self.api.host.trim().is_empty() {
+            errors.push("api.host must not be empty".to_string());
+        }
+        if self.api.port == 0 {
+            errors.push("api.port must be between 1 and 65535".to_string());
+        }
+        if self.api.ingest_concurrency == 0 {
+            errors.push("api.ingest_concurrency must be at least 1".to_string());
+        }
+        if self.api.writer_queue_depth == 0 {
+            errors.push("api.writer_queue_depth must be at least 1".to_string());
+        }
+        if self.api.image_max_bytes == 0 || self.api.image_max_pixels == 0 {
+            errors.push("api.image_max_bytes and api.image_max_pixels must be at least 1".to_string());
+        }
+        for (lane, quota) in [("urgent", self.api.ingest_quotas.urgent), ("normal", self.api.ingest_quotas.normal), ("bulk", self.api.ingest_quotas.bulk)] {
+            match quota {
+                Some(0) => errors.push(format!("api.ingest_quotas.{} must be at least 1", lane)),
+                Some(quota) if quota > self.api.ingest_concurrency => errors.push(format!(
+                    "api.ingest_quotas.{} ({}) must not exceed api.ingest_concurrency ({})",
+                    lane, quota, self.api.ingest_concurrency
+                )),
+                _ => {}
+            }
+        }
+        if self.api.job_workers == 0 {
+            errors.push("api.job_workers must be at least 1".to_string());
+        }
+        if self.api.job_queue_depth == 0 {
+            errors.push("api.job_queue_depth must be at least 1".to_string());
+        }
+        if !self.api.legacy_sunset.is_empty() && self.api.legacy_sunset_at().is_none() {
+            errors.push(format!("api.legacy_sunset must be an RFC 3339 timestamp, got '{}'", self.api.legacy_sunset));
+        }
+        let mut seen_ids = std::collections::HashSet::new();
+        let mut seen_keys = std::collections::HashSet::new();
+        for tenant in &self.api.tenants {
+            if let Err(e) = validate_tenant_id(&tenant.id) {
+                errors.push(format!("api.tenants: {}", e));
+            }
+            if tenant.api_key.is_empty() {
+                errors.push(format!("api.tenants: tenant '{}' has an empty api_key", tenant.id));
+            }
+            if !seen_ids.insert(tenant.id.as_str()) {
+                errors.push(format!("api.tenants: duplicate tenant id '{}'", tenant.id));
+            }
+            if !tenant.api_key.is_empty() && !seen_keys.insert(tenant.api_key.as_str()) {
+                errors.push(format!("api.tenants: tenant '{}' reuses another tenant's api_key", tenant.id));
+            }
+        }
+        for admin_key in &self.api.admin_keys {
+            if admin_key.is_empty() {
+                errors.push("api.admin_keys: entries must not be empty".to_string());
+            } else if seen_keys.contains(admin_key.as_str()) {
+                errors.push("api.admin_keys: an admin key must not also be a tenant api_key".to_string());
+            }
+        }
+        let mut seen_auditors = std::collections::HashSet::new();
+        for auditor in &self.api.auditors {
+            if validate_tenant_id(&auditor.name).is_err() {
+                errors.push(format!("api.auditors: invalid auditor name '{}': use 1-64 chars of [a-z0-9_-]", auditor.name));
+            }
+            if !seen_auditors.insert(auditor.name.as_str()) {
+                errors.push(format!("api.auditors: duplicate auditor name '{}'", auditor.name));
+            }
+            if auditor.api_key.is_empty() {
+                errors.push(format!("api.auditors: auditor '{}' has an empty api_key", auditor.name));
+            } else if seen_keys.contains(auditor.api_key.as_str()) || self.api.admin_keys.contains(&auditor.api_key) {
+                errors.push(format!("api.auditors: auditor '{}' reuses a tenant or admin key", auditor.name));
+            } else if !seen_keys.insert(auditor.api_key.as_str()) {
+                errors.push(format!("api.auditors: auditor '{}' reuses another auditor's api_key", auditor.name));
+            }
+        }
+        let mut seen_examiners = std::collections::HashSet::new();
+        for examiner in &self.api.examiners {
+            if validate_tenant_id(&examiner.name).is_err() {
+                errors.push(format!("api.examiners: invalid examiner name '{}': use 1-64 chars of [a-z0-9_-]", examiner.name));
+            }
+            if !seen_examiners.insert(examiner.name.as_str()) {
+                errors.push(format!("api.examiners: duplicate examiner name '{}'", examiner.name));
+            }
+            if let Err(e) = parse_public_key(&examiner.public_key) {
+                errors.push(format!("api.examiners: '{}' public_key: {}", examiner.name, e));
+            }
+        }
+
+        match self.signer.key_source {
+            KeySource::File => {
+                if self.signer.key_path.trim().is_empty() {
+                    errors.push("signer.key_path must not be empty".to_string());
+                }
+            }
+            KeySource::Vault => {
+                let vault = &self.signer.vault;
+                if !vault.addr.starts_with("https://") && !vault.addr.starts_with("http://") {
+                    errors.push(format!("signer.vault.addr must be an http(s) URL, got '{}'", vault.addr));
+                }
+                for (name, value) in [("mount", &vault.mount), ("path", &vault.path), ("field", &vault.field)] {
+                    if value.trim().is_empty() {
+                        errors.push(format!("signer.vault.{} must not be empty", name));
+                    }
+                }
+            }
+            KeySource::Keychain => {
+                let keychain = &self.signer.keychain;
+                for (name, value) in [("service", &keychain.service), ("account", &keychain.account)] {
+                    if value.trim().is_empty() {
+                        errors.push(format!("signer.keychain.{} must not be empty", name));
+                    }
+                }
+            }
+            KeySource::Tpm => {
+                if self.signer.tpm.sealed_path.trim().is_empty() {
+                    errors.push("signer.tpm.sealed_path must not be empty".to_string());
+                }
+                if let Err(e) = tpm::validate_pcrs(&self.signer.tpm.pcrs) {
+                    errors.push(format!("signer.tpm.pcrs: {}", e));
+                }
+            }
+            KeySource::Enclave => {
+                if self.signer.enclave.socket.trim().is_empty() {
+                    errors.push("signer.enclave.socket must not be empty".to_string());
+                }
+            }
+            KeySource::Piv => {
+                let piv = &self.signer.piv;
+                if piv.command.trim().is_empty() {
+                    errors.push("signer.piv.command must not be empty".to_string());
+                }
+                let mut seen_slots = std::collections::HashSet::new();
+                let slots = piv.tenant_slots.iter().map(|(id, slot)| (id.as_str(), slot));
+                for (owner, slot) in std::iter::once((DEFAULT_TENANT, &piv.slot)).chain(slots) {
+                    if !is_piv_key_slot(slot) {
+                        errors.push(format!("signer.piv: slot for tenant '{}' must be a PIV key slot (9a, 9c, 9d, 9e or 82 ~ 95), got '{}'", owner, slot));
+                    } else if !seen_slots.insert(slot.to_ascii_lowercase()) {
+                        errors.push(format!("signer.piv: slot '{}' is assigned to more than one tenant", slot));
+                    }
+                }
+                if piv.tenant_slots.contains_key(DEFAULT_TENANT) {
+                    errors.push(format!("signer.piv.tenant_slots: tenant '{}' uses signer.piv.slot", DEFAULT_TENANT));
+                }
+                for tenant in self.api.tenants.iter().filter(|tenant| tenant.id != DEFAULT_TENANT) {
+                    if !piv.tenant_slots.contains_key(&tenant.id) {
+                        errors.push(format!(
+                            "signer.piv.tenant_slots: tenant '{}' has no slot; keys on a PIV token cannot derive tenant keys",
+                            tenant.id
+                        ));
+                    }
+                }
+                if !self.signer.departments.is_empty() {
+                    errors.push("signer.departments cannot be derived from keys on a PIV token; remove them or use another key_source".to_string());
+                }
+            }
+        }
+        match self.store.encryption.key_source {
+            EncryptionKeySource::File if self.store.encryption.key_file.trim().is_empty() => {
+                errors.push("store.encryption.key_file must not be empty when key_source is 'file'".to_string());
+            }
+            EncryptionKeySource::Vault => {
+                let vault = &self.store.encryption.vault;
+                if !vault.addr.starts_with("https://") && !vault.addr.starts_with("http://") {
+                    errors.push(format!("store.encryption.vault.addr must be an http(s) URL, got '{}'", vault.addr));
+                }
+                for (name, value) in [("mount", &vault.mount), ("path", &vault.path), ("field", &vault.field)] {
+                    if value.trim().is_empty() {
+                        errors.push(format!("store.encryption.vault.{} must not be empty", name));
+                    }
+                }
+            }
+            _ => {}
+        }
+        if self.signer.revocation_list.trim().is_empty() {
+            errors.push("signer.revocation_list must not be empty".to_string());
+        }
+        for key in &self.signer.previous_keys {
+            if let Err(e) = parse_public_key(key) {
+                errors.push(format!("signer.previous_keys: {}", e));
+            }
+        }
+        let mut seen_names = std::collections::HashSet::new();
+        let mut seen_paths = std::collections::HashSet::new();
+        for department in &self.signer.departments {
+            if department.name.trim().is_empty() {
+                errors.push("signer.departments: name must not be empty".to_string());
+            }
+            if !seen_names.insert(department.name.as_str()) {
+                errors.push(format!("signer.departments: duplicate name '{}'", department.name));
+            }
+            match DerivationPath::parse(&department.path) {
+                Ok(path) if path.is_root() => {
+                    errors.push(format!("signer.departments: '{}' must use a child path, not 'm'", department.name));
+                }
+                Ok(path) => {
+                    if !seen_paths.insert(path.to_string()) {
+                        errors.push(format!("signer.departments: '{}' reuses path {}", department.name, path));
+                    }
+                }
+                Err(e) => errors.push(format!("signer.departments: {}", e)),
+            }
+        }
+        if self.store.db_path.trim().is_empty() {
+            errors.push("store.db_path must not be empty".to_string());
+        }
+        if self.store.retention_years > 0 && self.store.retention_check_interval_secs == 0 {
+            errors.push("store.retention_check_interval_secs must be at least 1 when retention_years is set".to_string());
+        }
+
+        if self.policy.clock_max_skew_ms <= 0 {
+            errors.push(format!("policy.clock_max_skew_ms must be positive, got {}", self.policy.clock_max_skew_ms));
+        }
+        if !self.policy.ntp_servers.is_empty() && self.policy.clock_check_interval_secs == 0 {
+            errors.push("policy.clock_check_interval_secs must be at least 1 when ntp_servers is set".to_string());
+        }
+
+        if self.anchoring.root_publish_interval_secs == 0 {
+            errors.push("anchoring.root_publish_interval_secs must be at least 1".to_string());
+        }
+        if !self.anchoring.dns_name.is_empty() && self.anchoring.nsupdate.trim().is_empty() {
+            errors.push("anchoring.nsupdate must not be empty when dns_name is set".to_string());
+        }
+        if !self.anchoring.notaries.is_empty() && self.anchoring.notary_interval_secs == 0 {
+            errors.push("anchoring.notary_interval_secs must be at least 1 when notaries are configured".to_string());
+        }
+        if self.anchoring.notary_max_skew_secs < 0 {
+            errors.push(format!("anchoring.notary_max_skew_secs must not be negative, got {}", self.anchoring.notary_max_skew_secs));
+        }
+        let mut seen_notaries = std::collections::HashSet::new();
+        for notary in &self.anchoring.notaries {
+            if notary.name.trim().is_empty() || notary.name.contains('\0') {
+                errors.push("anchoring.notaries: name must not be empty or contain NUL".to_string());
+            }
+            if !seen_notaries.insert(notary.name.as_str()) {
+                errors.push(format!("anchoring.notaries: duplicate name '{}'", notary.name));
+            }
+            if !notary.url.starts_with("https://") && !notary.url.starts_with("http://") {
+                errors.push(format!("anchoring.notaries: '{}' url must be an http(s) URL, got '{}'", notary.name, notary.url));
+            }
+            match notary.protocol {
+                NotaryKind::Rfc3161 => {
+                    if notary.trust_anchors.is_empty() {
+                        errors.push(format!("anchoring.notaries: '{}' needs trust_anchors (PEM roots of the TSA certificate)", notary.name));
+                    }
+                    if !notary.public_key.is_empty() {
+                        errors.push(format!(
+                            "anchoring.notaries: '{}' public_key only applies to protocol = \"countersign\"; RFC 3161 TSAs are trusted via trust_anchors",
+                            notary.name
+                        ));
+                    }
+                    let valid_policy = notary.policy.split('.').count() >= 2 && notary.policy.split('.').all(|arc| arc.parse::<u64>().is_ok());
+                    if !notary.policy.is_empty() && !valid_policy {
+                        errors.push(format!("anchoring.notaries: '{}' policy must be a dotted OID, got '{}'", notary.name, notary.policy));
+                    }
+                }
+                NotaryKind::Countersign => {
+                    if let Err(e) = parse_public_key(&notary.public_key) {
+                        errors.push(format!("anchoring.notaries: '{}' public_key: {}", notary.name, e));
+                    }
+                    if !notary.trust_anchors.is_empty() || !notary.policy.is_empty() {
+                        errors.push(format!("anchoring.notaries: '{}' trust_anchors and policy only apply to protocol = \"rfc3161\"", notary.name));
+                    }
+                }
+            }
+        }
+        if !self.anchoring.rekor_url.is_empty() {
+            if !self.anchoring.rekor_url.starts_with("https://") && !self.anchoring.rekor_url.starts_with("http://") {
+                errors.push(format!("anchoring.rekor_url must be an http(s) URL, got '{}'", self.anchoring.rekor_url));
+            }
+            if self.anchoring.rekor_interval_secs == 0 {
+                errors.push("anchoring.rekor_interval_secs must be at least 1 when rekor_url is set".to_string());
+            }
+        }
+
+        if self.replication.replica {
+            if self.replication.primary_url.trim().is_empty() {
+                errors.push("replication.primary_url must be set for a replica".to_string());
+            }
+            if self.replication.admin_key.is_empty() {
+                errors.push("replication.admin_key must be set for a replica".to_string());
+            }
+            if self.replication.sync_interval_ms == 0 {
+                errors.push("replication.sync_interval_ms must be at least 1".to_string());
+            }
+            if !(1..=1000).contains(&self.replication.batch_size) {
+                errors.push(format!("replication.batch_size must be between 1 and 1000, got {}", self.replication.batch_size));
+            }
+            if self.replication.long_poll_ms > MAX_LONG_POLL_MS {
+                errors.push(format!(
+                    "replication.long_poll_ms must be at most {}, got {}",
+                    MAX_LONG_POLL_MS, self.replication.long_poll_ms
+                ));
+            }
+        }
+        if self.replication.peers.iter().any(|peer| peer.trim().is_empty()) {
+            errors.push("replication.peers must not contain empty addresses".to_string());
+        }
+        if self.replication.ack_quorum > 0 && self.replication.ack_timeout_ms == 0 {
+            errors.push("replication.ack_timeout_ms must be at least 1 when ack_quorum is set".to_string());
+        }
+        if self.replication.ack_quorum > self.replication.replicas.len() {
+            errors.push(format!(
+                "replication.ack_quorum ({}) exceeds the {} replicas listed in replication.replicas; only listed replicas can acknowledge",
+                self.replication.ack_quorum,
+                self.replication.replicas.len()
+            ));
+        }
+        let mut seen_replicas = std::collections::HashSet::new();
+        let mut seen_ack_keys = std::collections::HashSet::new();
+        for replica in &self.replication.replicas {
+            if !is_node_id(&replica.id) {
+                errors.push(format!("replication.replicas: id must contain only letters, digits, '-', '_' or '.', got '{}'", replica.id));
+            }
+            if !seen_replicas.insert(replica.id.as_str()) {
+                errors.push(format!("replication.replicas: duplicate id '{}'", replica.id));
+            }
+            if replica.ack_key.is_empty() {
+                errors.push(format!("replication.replicas: replica '{}' has an empty ack_key", replica.id));
+            } else if self.api.admin_keys.contains(&replica.ack_key) || replica.ack_key == self.replication.admin_key {
+                errors.push(format!("replication.replicas: replica '{}' reuses an admin key as its ack_key", replica.id));
+            } else if !seen_ack_keys.insert(replica.ack_key.as_str()) {
+                errors.push(format!("replication.replicas: replica '{}' reuses another replica's ack_key", replica.id));
+            }
+        }
+        if !self.replication.ack_key.is_empty() && self.replication.ack_key == self.replication.admin_key {
+            errors.push("replication.ack_key must differ from replication.admin_key".to_string());
+        }
+
+        let node_id = &self.failover.node_id;
+        if (self.failover.enabled() || !node_id.is_empty()) && !is_node_id(node_id) {
+            errors.push(format!(
+                "failover.node_id must be non-empty and contain only letters, digits, '-', '_' or '.', got '{}'",
+                node_id
+            ));
+        }
+        if self.failover.enabled() {
+            // 续约至少要在围栏 (租期减去时钟偏差上限) 到期前尝试两次
+            let fence_window = self.failover.lease_ttl_ms as i64 - self.policy.clock_max_skew_ms;
+            if self.failover.renew_interval_ms == 0 || (self.failover.renew_interval_ms as i64) * 2 >= fence_window {
+                errors.push(format!(
+                    "failover.renew_interval_ms ({}) must be positive and less than half of lease_ttl_ms minus policy.clock_max_skew_ms ({})",
+                    self.failover.renew_interval_ms, fence_window
+                ));
+            }
+        }
+
+        if !(0.0..=1.0).contains(&self.telemetry.sample_ratio) {
+            errors.push(format!("telemetry.sample_ratio must be between 0.0 and 1.0, got {}", self.telemetry.sample_ratio));
+        }
+        if !self.telemetry.otlp_endpoint.is_empty() && self.telemetry.service_name.trim().is_empty() {
+            errors.push("telemetry.service_name must not be empty when otlp_endpoint is set".to_string());
+        }
+
+        if self.abuse.window_secs == 0 {
+            errors.push("abuse.window_secs must be at least 1".to_string());
+        }
+        if !(0.0..=1.0).contains(&self.abuse.low_confidence_below) {
+            errors.push(format!("abuse.low_confidence_below must be between 0.0 and 1.0, got {}", self.abuse.low_confidence_below));
+        }
+        if self.abuse.action == AbuseAction::Throttle && self.abuse.throttle_secs == 0 {
+            errors.push("abuse.throttle_secs must be at least 1 when action is throttle".to_string());
+        }
+        if !(0.0..=1.0).contains(&self.anomaly.rare_frequency) {
+            errors.push(format!("anomaly.rare_frequency must be between 0.0 and 1.0, got {}", self.anomaly.rare_frequency));
+        }
+        if self.anomaly.max_z_score.is_nan() || self.anomaly.max_z_score <= 0.0 {
+            errors.push(format!("anomaly.max_z_score must be positive, got {}", self.anomaly.max_z_score));
+        }
+        if self.provenance.mode == ProvenanceMode::Pseudonymized && self.provenance.key_file.trim().is_empty() {
+            errors.push("provenance.key_file must not be empty when mode is 'pseudonymized'".to_string());
+        }
+        let cert_header = self.provenance.client_cert_header.trim();
+        if !cert_header.is_empty() && axum::http::HeaderName::try_from(cert_header).is_err() {
+            errors.push(format!("provenance.client_cert_header '{}' is not a valid header name", cert_header));
+        }
+        if self.search.enabled {
+            if self.search.index_dir.trim().is_empty() {
+                errors.push("search.index_dir must not be empty when search is enabled".to_string());
+            }
+            if self.search.refresh_interval_ms == 0 {
+                errors.push("search.refresh_interval_ms must be at least 1".to_string());
+            }
+        }
+        if !is_piv_key_slot(&self.piv.slot) {
+            errors.push(format!("piv.slot must be a PIV key slot (9a, 9c, 9d, 9e or 82 ~ 95), got '{}'", self.piv.slot));
+        }
+        if self.piv.command.trim().is_empty() {
+            errors.push("piv.command must not be empty".to_string());
+        }
+
+        if !self.logging.stdout && self.logging.file.is_empty() && !self.logging.syslog {
+            errors.push("logging.stdout = false needs logging.file or logging.syslog, otherwise nothing is logged".to_string());
+        }
+        if self.logging.syslog {
+            if log_sink::facility_code(&self.logging.syslog_facility).is_none() {
+                errors.push(format!(
+                    "logging.syslog_facility must be 'user', 'daemon', 'auth' or 'local0' ~ 'local7', got '{}'",
+                    self.logging.syslog_facility
+                ));
+            }
+            if self.logging.syslog_socket.is_empty() {
+                errors.push("logging.syslog_socket must not be empty when syslog is enabled".to_string());
+            }
+        }
+        if self.logging.syslog_ident.trim().is_empty() {
+            errors.push("logging.syslog_ident must not be empty".to_string());
+        }
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(anyhow::anyhow!("Invalid configuration:\n  - {}", errors.join("\n  - ")))
+        }
+    }
+}
+
+/// 若环境变量存在，则解析并覆盖 `target`；解析失败时报告变量名与原值
+fn override_from_env<T>(name: &str, target: &mut T) -> anyhow::Result<()>
+where
+    T: FromStr,
+    T::Err: Display,
+{
+    if let Ok(value) = env::var(name) {
+        *target = value
+            .trim()
+            .parse()
+            .map_err(|e| anyhow::anyhow!("Invalid value for {}='{}': {}", name, value, e))?;
+    }
+    Ok(())
+}
+
+/// 解析 Hex 编码的 Ed25519 公钥
+pub fn parse_public_key(value: &str) -> anyhow::Result<VerifyingKey> {
+    let bytes: [u8; 32] = hex::decode(value.trim())
+        .ok()
+        .and_then(|v| v.as_slice().try_into().ok())
+        .ok_or_else(|| anyhow::anyhow!("'{}' is not a 32-byte hex public key", value))?;
+    VerifyingKey::from_bytes(&bytes).map_err(|e| anyhow::anyhow!("'{}' is not a valid Ed25519 public key: {}", value, e))
+}
+
+/// 解析逗号分隔的列表，忽略空项
+fn split_list(value: &str) -> Vec<String> {
+    value
+        .split(',')
+        .map(|s| s.trim().to_string())
+        .filter(|s| !s.is_empty())
+        .collect()
+}
+
+/// 是否为可存放签名密钥的 PIV 槽位 (9a、9c、9d、9e 与退役槽位 82 ~ 95)
+fn is_piv_key_slot(slot: &str) -> bool {
+    let slot = u8::from_str_radix(slot, 16).ok().filter(|_| slot.len() == 2);
+    slot.is_some_and(|slot| matches!(slot, 0x9a | 0x9c | 0x9d | 0x9e | 0x82..=0x95))
+}
+
+/// 解析 `DEPARTMENTS=forensics:m/0'/1',field:m/0'/2'`
+fn parse_departments(value: &str) -> anyhow::Result<Vec<DepartmentKeyConfig>> {
+    value
+        .split(',')
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .map(|entry| {
+            let (name, path) = entry
+                .split_once(':')
+                .ok_or_else(|| anyhow::anyhow!("Invalid DEPARTMENTS entry '{}': expected 'name:path'", entry))?;
+            Ok(DepartmentKeyConfig { name: name.trim().to_string(), path: path.trim().to_string() })
+        })
+        .collect()
+}
+
+/// 节点 / 副本 ID：非空，只含字母、数字、`-`、`_` 与 `.`
+fn is_node_id(id: &str) -> bool {
+    !id.is_empty() && id.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'))
+}
+
+/// 解析 `REPLICATION_REPLICAS=id:ack_key,id2:ack_key2`
+fn parse_replicas(value: &str) -> anyhow::Result<Vec<ReplicaConfig>> {
+    value
+        .split(',')
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .map(|entry| {
+            let (id, ack_key) = entry
+                .split_once(':')
+                .ok_or_else(|| anyhow::anyhow!("Invalid REPLICATION_REPLICAS entry '{}': expected 'replica_id:ack_key'", entry))?;
+            Ok(ReplicaConfig { id: id.trim().to_string(), ack_key: ack_key.trim().to_string() })
+        })
+        .collect()
+}
+
+/// 解析 `TENANTS=id:api_key,id2:api_key2`
+fn parse_tenants(value: &str) -> anyhow::Result<Vec<TenantConfig>> {
+    value
+        .split(',')
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .map(|entry| {
+            let (id, api_key) = entry
+                .split_once(':')
+                .ok_or_else(|| anyhow::anyhow!("Invalid TENANTS entry '{}': expected 'tenant_id:api_key'", entry))?;
+            Ok(TenantConfig { id: id.trim().to_string(), api_key: api_key.trim().to_string() })
+        })
+        .collect()
+}
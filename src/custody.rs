@@ -0,0 +1,162 @@
+use ed25519_dalek::{Signature, VerifyingKey};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::mmr_store::SledStore;
+use crate::signer::EvidenceSigner;
+
+/// 保管事件签名的域分隔前缀，避免与证据 / 回执 / 树头签名混用
+const EVENT_DOMAIN: &[u8] = b"yuanjing-core custody event v1";
+
+/// 保管记录叶子的域分隔前缀，使其原文不可能被当作证据解码
+const LEAF_DOMAIN: &[u8] = b"yuanjing-core custody record v1";
+
+/// 保管动作
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum CustodyAction {
+    /// 查阅
+    Accessed,
+    /// 导出副本
+    Exported,
+    /// 移交给另一保管人
+    Transferred,
+    /// 当庭出示
+    PresentedInCourt,
+}
+
+/// 提交方填写的事件内容 (其余字段由服务端补上)
+#[derive(Debug, Clone)]
+pub struct CustodyDetails {
+    pub action: CustodyAction,
+    pub actor: String,
+    pub recipient: Option<String>,
+    pub note: String,
+}
+
+/// 保管事件：谁在何时对哪条证据做了什么
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct CustodyEvent {
+    pub tenant_id: String,
+    /// 所涉证据的位置
+    pub pos: u64,
+    /// 该证据的第几条保管事件 (从 0 开始)
+    pub index: u64,
+    pub action: CustodyAction,
+    /// 经手人 (由提交方填写，如办案人员姓名或工号)
+    pub actor: String,
+    /// 接收方 (仅 `transferred`)
+    pub recipient: Option<String>,
+    pub note: String,
+    /// 提交者 (租户 API Key 指纹)
+    pub principal: String,
+    /// 服务端记录时间 (Unix 秒)
+    pub timestamp: i64,
+    /// 同一证据上一条保管事件的叶子哈希 (Hex)；第一条为全零
+    pub prev_hash: String,
+}
+
+impl CustodyEvent {
+    /// 规范化字节 (域前缀 + BCS)，签名与验签均基于此
+    pub fn canonical_bytes(&self) -> anyhow::Result<Vec<u8>> {
+        let mut bytes = EVENT_DOMAIN.to_vec();
+        bytes.extend_from_slice(&bcs::to_bytes(self)?);
+        Ok(bytes)
+    }
+}
+
+/// 由租户根密钥签名的保管事件，整体作为一片叶子追加进 MMR
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct CustodyRecord {
+    pub event: CustodyEvent,
+    /// 签名 (Hex)
+    pub signature: String,
+    /// 租户根公钥 (Hex)
+    pub public_key: String,
+}
+
+impl CustodyRecord {
+    pub fn sign(event: CustodyEvent, signer: &EvidenceSigner) -> anyhow::Result<Self> {
+        let signature = signer.sign_bytes(&event.canonical_bytes()?)?;
+        Ok(Self {
+            event,
+            signature: hex::encode(signature.to_bytes()),
+            public_key: hex::encode(signer.public_key().to_bytes()),
+        })
+    }
+
+    /// 校验签名；`trusted_key` 为事先固定的租户根公钥，与记录自带的公钥不符时直接判为无效
+    pub fn verify(&self, trusted_key: &VerifyingKey) -> anyhow::Result<bool> {
+        if hex::encode(trusted_key.to_bytes()) != self.public_key {
+            return Ok(false);
+        }
+        let signature = Signature::from_slice(&hex::decode(&self.signature)?)?;
+        Ok(EvidenceSigner::verify_bytes(trusted_key, &self.event.canonical_bytes()?, &signature))
+    }
+
+    /// 叶子原文 (域前缀 + BCS)，叶子哈希为其 Blake3
+    pub fn canonical_bytes(&self) -> anyhow::Result<Vec<u8>> {
+        let mut bytes = LEAF_DOMAIN.to_vec();
+        bytes.extend_from_slice(&bcs::to_bytes(self)?);
+        Ok(bytes)
+    }
+
+    /// 叶子哈希 (即下一条事件的 `prev_hash`)
+    pub fn leaf_hash(&self) -> anyhow::Result<[u8; 32]> {
+        Ok(*blake3::hash(&self.canonical_bytes()?).as_bytes())
+    }
+
+    /// 从叶子原文还原；不是保管记录时返回 None
+    pub fn from_bytes(bytes: &[u8]) -> anyhow::Result<Option<Self>> {
+        bytes
+            .strip_prefix(LEAF_DOMAIN)
+            .map(|payload| Ok(bcs::from_bytes(payload)?))
+            .transpose()
+    }
+}
+
+/// 模块：保管链 (Chain of Custody)
+///
+/// **为什么需要**: 证据在法庭上站得住，不只要证明“入库后没被改过”，还要交代入库之后经过了谁的手：
+/// 谁查阅过、导出过副本、移交给了谁、何时当庭出示。这份经手记录本身也必须不可抵赖、不可事后补写。
+///
+/// 每条保管事件由租户根密钥签名，并作为一片叶子追加进同一棵 MMR (与签名日志、运维日志的检查点一样不是证据)，
+/// 因此享有与证据相同的包含性证明与树头锚定；同一证据的事件以 `prev_hash` 串成链，删改或漏掉其中一条都能被发现。
+/// `custody` 树是按证据查找事件的索引 (键为 证据位置 + 事件序号，值为事件叶子的位置)，副本在应用复制来的叶子时同步建立。
+#[derive(Clone)]
+pub struct CustodyLog {
+    index: sled::Tree,
+}
+
+impl CustodyLog {
+    pub fn open(store: &SledStore) -> anyhow::Result<Self> {
+        Ok(Self { index: store.tree("custody")? })
+    }
+
+    /// 指定证据的保管事件叶子位置 (按事件先后)
+    pub fn leaves(&self, pos: u64) -> anyhow::Result<Vec<u64>> {
+        self.index
+            .scan_prefix(pos.to_be_bytes())
+            .values()
+            .map(|v| Ok(u64::from_be_bytes(v?.as_ref().try_into()?)))
+            .collect()
+    }
+
+    /// 指定证据最近一条保管事件的叶子位置
+    pub fn last(&self, pos: u64) -> anyhow::Result<Option<u64>> {
+        self.index
+            .scan_prefix(pos.to_be_bytes())
+            .values()
+            .next_back()
+            .map(|v| Ok(u64::from_be_bytes(v?.as_ref().try_into()?)))
+            .transpose()
+    }
+
+    /// 记下事件叶子的位置 (重复记录同一事件是幂等的)
+    pub fn record(&self, event: &CustodyEvent, leaf_pos: u64) -> anyhow::Result<()> {
+        let key = [event.pos.to_be_bytes(), event.index.to_be_bytes()].concat();
+        self.index.insert(key, &leaf_pos.to_be_bytes())?;
+        self.index.flush()?;
+        Ok(())
+    }
+}
@@ -0,0 +1,96 @@
+use ed25519_dalek::VerifyingKey;
+use serde::Serialize;
+
+/// multicodec 前缀 ed25519-pub (0xed, varint 编码)
+const ED25519_MULTICODEC: [u8; 2] = [0xed, 0x01];
+
+/// 公钥的 multibase 表示 (base58btc，`z` 前缀)，即 did:key 的方法专有标识
+pub fn multibase(public_key: &VerifyingKey) -> String {
+    let mut bytes = ED25519_MULTICODEC.to_vec();
+    bytes.extend_from_slice(public_key.as_bytes());
+    format!("z{}", bs58::encode(bytes).into_string())
+}
+
+/// did:key 标识，完全由公钥推出，无需任何托管
+pub fn did_key(public_key: &VerifyingKey) -> String {
+    format!("did:key:{}", multibase(public_key))
+}
+
+/// did:web 标识：`host[:port][/path]` 中端口冒号转义为 `%3A`，路径分隔符改为 `:`
+pub fn did_web(domain: &str) -> String {
+    format!("did:web:{}", domain.trim_matches('/').replace(':', "%3A").replace('/', ":"))
+}
+
+/// 验证方法 (Verification Method)
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VerificationMethod {
+    pub id: String,
+    #[serde(rename = "type")]
+    pub kind: String,
+    pub controller: String,
+    pub public_key_multibase: String,
+}
+
+/// 模块：DID 文档 (DID Document)
+///
+/// **为什么需要**: 回执里的裸 Hex 公钥无法说明“这是谁的钥匙”。
+/// DID 文档把鉴定中心的标识符 (did:web 或 did:key) 与它的全部公钥绑定在一起，
+/// 验证方解析 DID 即可得到公钥，而不必从 API 文档里抄写。
+///
+/// - `verificationMethod` 列出当前与历史公钥 (租户根公钥、派生公钥、轮换前的旧公钥、已吊销公钥)，
+///   旧回执因此仍能解析到签名公钥；
+/// - `assertionMethod` 只列出当前可用于签发的公钥。
+///
+/// 每个验证方法的片段 (`#z6Mk...`) 就是该公钥的 multibase，与 did:key 的约定一致。
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DidDocument {
+    #[serde(rename = "@context")]
+    pub context: Vec<String>,
+    pub id: String,
+    pub verification_method: Vec<VerificationMethod>,
+    pub assertion_method: Vec<String>,
+}
+
+impl DidDocument {
+    pub fn new(did: &str) -> Self {
+        Self {
+            context: vec![
+                "https://www.w3.org/ns/did/v1".to_string(),
+                "https://w3id.org/security/suites/ed25519-2020/v1".to_string(),
+            ],
+            id: did.to_string(),
+            verification_method: Vec::new(),
+            assertion_method: Vec::new(),
+        }
+    }
+
+    /// 添加公钥；`current` 为 false 表示仅供验证历史签名。重复的公钥会被忽略。
+    pub fn add_key(&mut self, public_key: &VerifyingKey, current: bool) {
+        let id = self.method_id(public_key);
+        if self.verification_method.iter().any(|method| method.id == id) {
+            return;
+        }
+        self.verification_method.push(VerificationMethod {
+            id: id.clone(),
+            kind: "Ed25519VerificationKey2020".to_string(),
+            controller: self.id.clone(),
+            public_key_multibase: multibase(public_key),
+        });
+        if current {
+            self.assertion_method.push(id);
+        }
+    }
+
+    /// 某把公钥对应的验证方法 ID (`did#z6Mk...`)
+    pub fn method_id(&self, public_key: &VerifyingKey) -> String {
+        format!("{}#{}", self.id, multibase(public_key))
+    }
+
+    /// 公钥在文档中时返回其验证方法 ID
+    pub fn find(&self, public_key: &VerifyingKey) -> Option<String> {
+        let id = self.method_id(public_key);
+        self.verification_method.iter().any(|method| method.id == id).then_some(id)
+    }
+}
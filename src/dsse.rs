@@ -0,0 +1,129 @@
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use chrono::{DateTime, SecondsFormat};
+use ed25519_dalek::{Signature, VerifyingKey};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+use crate::evidence::Evidence;
+use crate::mmr_store::StoredAttestation;
+use crate::signer::EvidenceSigner;
+
+/// in-toto 声明的载荷类型
+pub const PAYLOAD_TYPE: &str = "application/vnd.in-toto+json";
+
+/// in-toto 声明 (Statement v1) 的类型
+pub const STATEMENT_TYPE: &str = "https://in-toto.io/Statement/v1";
+
+/// 本服务鉴定结论的谓词类型
+pub const PREDICATE_TYPE: &str = "urn:yuanjing:predicate:forensic-verdict:v1";
+
+/// DSSE 信封中的一个签名
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct DsseSignature {
+    /// 签名密钥的标识 (DID 文档中的验证方法 ID)
+    pub keyid: String,
+    /// Ed25519 签名 (Base64)，签名对象为 `PAE(payloadType, payload)`
+    pub sig: String,
+}
+
+/// DSSE 信封 (Dead Simple Signing Envelope v1)
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct DsseEnvelope {
+    /// in-toto 声明的 JSON (JCS 规范化) 字节 (Base64)
+    pub payload: String,
+    pub payload_type: String,
+    pub signatures: Vec<DsseSignature>,
+}
+
+/// DSSE 的预认证编码：`"DSSEv1" SP LEN(type) SP type SP LEN(body) SP body`
+pub fn pae(payload_type: &str, payload: &[u8]) -> Vec<u8> {
+    let mut bytes = format!("DSSEv1 {} {} {} ", payload_type.len(), payload_type, payload.len()).into_bytes();
+    bytes.extend_from_slice(payload);
+    bytes
+}
+
+/// 由证据与回执构造 in-toto 声明
+///
+/// `subject` 为被鉴定的图片 (按 SHA-256)；谓词是 AI 判定、模型与该条证据在日志中的位置，
+/// 附完整回执及其签名，消费方可据此调用 `/audit/{pos}` 取包含证明。
+pub fn statement(evidence: &Evidence, attestation: &StoredAttestation) -> anyhow::Result<Value> {
+    let receipt = &attestation.receipt;
+    Ok(json!({
+        "_type": STATEMENT_TYPE,
+        "subject": [{
+            "name": format!("urn:sha256:{}", evidence.image_sha256),
+            "digest": { "sha256": evidence.image_sha256 },
+        }],
+        "predicateType": PREDICATE_TYPE,
+        "predicate": {
+            "verdict": evidence.verdict,
+            "confidence": evidence.confidence,
+            "imagePhash": evidence.image_phash,
+            "model": {
+                "promptPoolHash": evidence.prompt_pool_hash,
+                "activatedPrompts": evidence.activated_prompts,
+                "externalKnowledgeHash": evidence.external_knowledge_hash,
+            },
+            "evidenceTimestamp": rfc3339(evidence.timestamp)?,
+            "proof": {
+                "tenantId": receipt.tenant_id,
+                "evidenceHash": receipt.evidence_hash,
+                "leafPos": receipt.leaf_pos,
+                "root": receipt.root,
+                "treeSize": receipt.tree_size,
+                "seq": receipt.seq,
+                "auditPath": format!("/audit/{}", receipt.leaf_pos),
+                "receipt": receipt,
+                "receiptSignature": hex::encode(&attestation.receipt_signature),
+                "evidenceSignature": hex::encode(&attestation.evidence_signature),
+            },
+        },
+    }))
+}
+
+/// 模块：in-toto / DSSE 证明信封 (DSSE Attestation Envelope)
+///
+/// **为什么需要**: 供应链工具 (cosign、in-toto 验证器、SLSA 策略引擎) 已经普遍能解析 DSSE 信封里的 in-toto 声明，
+/// 却不认识我们的回执格式。把一条证据的鉴定结论写成以图片摘要为 `subject` 的 in-toto 声明，
+/// 用当初签发回执的同一把密钥按 DSSE 签名，这些工具不必改动就能校验与按策略消费。
+///
+/// 载荷为声明的 JCS (RFC 8785) 字节，同一条证据每次导出的信封相同。
+pub fn seal(statement: &Value, keyid: &str, signer: &EvidenceSigner) -> anyhow::Result<DsseEnvelope> {
+    let payload = serde_jcs::to_vec(statement)?;
+    let signature = signer.sign_bytes(&pae(PAYLOAD_TYPE, &payload))?;
+    Ok(DsseEnvelope {
+        payload: BASE64.encode(&payload),
+        payload_type: PAYLOAD_TYPE.to_string(),
+        signatures: vec![DsseSignature { keyid: keyid.to_string(), sig: BASE64.encode(signature.to_bytes()) }],
+    })
+}
+
+/// 校验信封中由 `public_key` 签出的签名 (任意一个有效即可)，返回其中的 in-toto 声明
+pub fn verify(envelope: &DsseEnvelope, public_key: &VerifyingKey) -> anyhow::Result<Value> {
+    if envelope.payload_type != PAYLOAD_TYPE {
+        return Err(anyhow::anyhow!("Unsupported payload type '{}'", envelope.payload_type));
+    }
+    let payload = BASE64.decode(&envelope.payload)?;
+    let message = pae(&envelope.payload_type, &payload);
+    let signed = envelope.signatures.iter().any(|signature| {
+        BASE64.decode(&signature.sig)
+            .ok()
+            .and_then(|bytes| Signature::from_slice(&bytes).ok())
+            .is_some_and(|signature| EvidenceSigner::verify_bytes(public_key, &message, &signature))
+    });
+    if !signed {
+        return Err(anyhow::anyhow!("No valid signature from the given key"));
+    }
+    let statement: Value = serde_json::from_slice(&payload)?;
+    if statement.get("_type").and_then(Value::as_str) != Some(STATEMENT_TYPE) {
+        return Err(anyhow::anyhow!("Payload is not an in-toto v1 statement"));
+    }
+    Ok(statement)
+}
+
+fn rfc3339(timestamp: i64) -> anyhow::Result<String> {
+    DateTime::from_timestamp(timestamp, 0)
+        .map(|time| time.to_rfc3339_opts(SecondsFormat::Secs, true))
+        .ok_or_else(|| anyhow::anyhow!("Timestamp {} out of range", timestamp))
+}
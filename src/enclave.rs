@@ -0,0 +1,372 @@
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use ed25519_dalek::{Signature, VerifyingKey};
+#[cfg(unix)]
+use log::{info, warn};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha512};
+#[cfg(unix)]
+use std::io::{BufRead, BufReader, Write};
+#[cfg(unix)]
+use std::os::unix::fs::PermissionsExt;
+#[cfg(unix)]
+use std::os::unix::net::{UnixListener, UnixStream};
+#[cfg(unix)]
+use std::path::Path;
+use std::path::PathBuf;
+use std::sync::Arc;
+#[cfg(unix)]
+use std::time::Duration;
+
+use crate::config::EnclaveConfig;
+#[cfg(unix)]
+use crate::hdkey::DerivationPath;
+use crate::signer::EvidenceSigner;
+
+/// 报告数据的域分隔前缀
+const REPORT_DATA_DOMAIN: &[u8] = b"yuanjing-core enclave identity v1";
+
+/// 单次请求的超时 (生成远程证明可能要访问平台服务，放宽到 30 秒)
+#[cfg(unix)]
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Gramine (SGX) 的证明伪文件
+#[cfg(unix)]
+const GRAMINE_ATTESTATION_TYPE: &str = "/dev/attestation/attestation_type";
+#[cfg(unix)]
+const GRAMINE_USER_REPORT_DATA: &str = "/dev/attestation/user_report_data";
+#[cfg(unix)]
+const GRAMINE_QUOTE: &str = "/dev/attestation/quote";
+
+/// Linux configfs-tsm 报告目录 (SEV-SNP / TDX 客户机)
+#[cfg(unix)]
+const TSM_REPORT_DIR: &str = "/sys/kernel/config/tsm/report";
+
+/// 飞地所在的可信执行环境，决定远程证明怎么生成
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AttestationPlatform {
+    /// 不生成远程证明 (开发调试)
+    #[default]
+    None,
+    /// Intel SGX，经 Gramine 的 `/dev/attestation` 取 DCAP 引用
+    Sgx,
+    /// Linux configfs-tsm (`/sys/kernel/config/tsm/report`)：AMD SEV-SNP 报告或 Intel TDX 引用
+    Tsm,
+}
+
+impl AttestationPlatform {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::None => "none",
+            Self::Sgx => "sgx",
+            Self::Tsm => "tsm",
+        }
+    }
+}
+
+impl std::str::FromStr for AttestationPlatform {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> anyhow::Result<Self> {
+        match s.to_ascii_lowercase().as_str() {
+            "none" => Ok(Self::None),
+            "sgx" => Ok(Self::Sgx),
+            "tsm" => Ok(Self::Tsm),
+            other => Err(anyhow::anyhow!("Unknown attestation platform '{}', expected 'none', 'sgx' or 'tsm'", other)),
+        }
+    }
+}
+
+/// 从飞地主密钥出发的一步派生
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", content = "value", rename_all = "snake_case")]
+pub enum DerivationStep {
+    /// 租户子密钥 (`EvidenceSigner::derive_tenant`)
+    Tenant(String),
+    /// SLIP-0010 路径 (`EvidenceSigner::derive_path`)
+    Path(String),
+}
+
+/// 宿主发给飞地的请求 (一行一个 JSON)
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(tag = "op", rename_all = "snake_case")]
+pub enum EnclaveRequest {
+    PublicKey { derivation: Vec<DerivationStep> },
+    /// `payload` 为待签的规范化字节 (Base64)
+    Sign { derivation: Vec<DerivationStep>, payload: String },
+    Attest { issued_at: i64 },
+}
+
+/// 飞地的应答 (一行一个 JSON)
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(tag = "result", rename_all = "snake_case")]
+pub enum EnclaveResponse {
+    PublicKey { public_key: String },
+    Signature { signature: String },
+    Attestation(EnclaveAttestation),
+    Error { message: String },
+}
+
+/// 飞地的远程证明：平台对“主公钥出自这个飞地”的签名报告
+///
+/// 报告数据为 `SHA-512("yuanjing-core enclave identity v1" || 主公钥 || issued_at 大端 8 字节)`，
+/// 验证方用平台工具 (Intel DCAP QVL、AMD `snpguest` 等) 校验引用本身与度量值，再用 [`EnclaveAttestation::check_binding`] 核对公钥绑定。
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct EnclaveAttestation {
+    /// `sgx` | `tsm`
+    pub platform: AttestationPlatform,
+    /// 证明的提供方：Gramine 的证明类型 (如 `dcap`) 或 configfs-tsm 的 provider (如 `sev_guest`、`tdx_guest`)
+    pub provider: String,
+    /// 飞地主公钥 (Hex)
+    pub public_key: String,
+    /// 生成时间 (Unix 秒)
+    pub issued_at: i64,
+    /// 报告数据 (Hex，64 字节)
+    pub report_data: String,
+    /// 平台引用 / 报告原文 (Base64)
+    pub quote: String,
+}
+
+impl EnclaveAttestation {
+    /// 报告数据是否按约定绑定了 `public_key`，且原样出现在引用中 (不校验引用的平台签名)
+    pub fn check_binding(&self, public_key: &VerifyingKey) -> bool {
+        let expected = report_data(public_key, self.issued_at);
+        let quote = BASE64.decode(&self.quote).unwrap_or_default();
+        self.public_key == hex::encode(public_key.to_bytes())
+            && self.report_data == hex::encode(expected)
+            && quote.windows(expected.len()).any(|window| window == expected)
+    }
+}
+
+/// 证明中的报告数据
+pub fn report_data(public_key: &VerifyingKey, issued_at: i64) -> [u8; 64] {
+    let mut hasher = Sha512::new();
+    hasher.update(REPORT_DATA_DOMAIN);
+    hasher.update(public_key.to_bytes());
+    hasher.update(issued_at.to_be_bytes());
+    hasher.finalize().into()
+}
+
+/// 宿主一侧：连接飞地签名进程的 Unix 套接字 (每个请求一个连接)
+pub struct EnclaveClient {
+    socket: PathBuf,
+}
+
+impl EnclaveClient {
+    pub fn new(socket: &str) -> Self {
+        Self { socket: PathBuf::from(socket) }
+    }
+
+    #[cfg(unix)]
+    fn call(&self, request: &EnclaveRequest) -> anyhow::Result<EnclaveResponse> {
+        let mut stream = UnixStream::connect(&self.socket)
+            .map_err(|e| anyhow::anyhow!("Cannot reach enclave signer at '{}': {}", self.socket.display(), e))?;
+        stream.set_read_timeout(Some(REQUEST_TIMEOUT))?;
+        let mut line = serde_json::to_vec(request)?;
+        line.push(b'\n');
+        stream.write_all(&line)?;
+        let mut response = String::new();
+        BufReader::new(stream).read_line(&mut response)?;
+        match serde_json::from_str(&response)
+            .map_err(|e| anyhow::anyhow!("Malformed response from enclave signer: {}", e))?
+        {
+            EnclaveResponse::Error { message } => Err(anyhow::anyhow!("Enclave signer: {}", message)),
+            response => Ok(response),
+        }
+    }
+
+    #[cfg(not(unix))]
+    fn call(&self, _request: &EnclaveRequest) -> anyhow::Result<EnclaveResponse> {
+        Err(anyhow::anyhow!("Cannot reach enclave signer at '{}': Unix sockets need a Unix platform", self.socket.display()))
+    }
+
+    /// 请飞地为主公钥生成远程证明
+    pub fn attest(&self, issued_at: i64) -> anyhow::Result<EnclaveAttestation> {
+        match self.call(&EnclaveRequest::Attest { issued_at })? {
+            EnclaveResponse::Attestation(attestation) => Ok(attestation),
+            other => Err(unexpected(&other)),
+        }
+    }
+}
+
+/// 飞地中的一把密钥：宿主只持有派生路径与公钥
+pub struct EnclaveKey {
+    client: Arc<EnclaveClient>,
+    derivation: Vec<DerivationStep>,
+    public_key: VerifyingKey,
+}
+
+impl EnclaveKey {
+    /// 飞地主密钥
+    pub fn open(client: Arc<EnclaveClient>) -> anyhow::Result<Self> {
+        Self::fetch(client, Vec::new())
+    }
+
+    /// 向下派生一步，公钥由飞地算出
+    pub fn derive(&self, step: DerivationStep) -> anyhow::Result<Self> {
+        let mut derivation = self.derivation.clone();
+        derivation.push(step);
+        Self::fetch(self.client.clone(), derivation)
+    }
+
+    fn fetch(client: Arc<EnclaveClient>, derivation: Vec<DerivationStep>) -> anyhow::Result<Self> {
+        let public_key = match client.call(&EnclaveRequest::PublicKey { derivation: derivation.clone() })? {
+            EnclaveResponse::PublicKey { public_key } => crate::config::parse_public_key(&public_key)?,
+            other => return Err(unexpected(&other)),
+        };
+        Ok(Self { client, derivation, public_key })
+    }
+
+    pub fn public_key(&self) -> VerifyingKey {
+        self.public_key
+    }
+
+    /// 把规范化字节送进飞地签名；返回的签名须能以该密钥的公钥验签
+    pub fn sign(&self, payload: &[u8]) -> anyhow::Result<Signature> {
+        let request = EnclaveRequest::Sign { derivation: self.derivation.clone(), payload: BASE64.encode(payload) };
+        let signature = match self.client.call(&request)? {
+            EnclaveResponse::Signature { signature } => Signature::from_slice(&hex::decode(signature)?)?,
+            other => return Err(unexpected(&other)),
+        };
+        if !EvidenceSigner::verify_bytes(&self.public_key, payload, &signature) {
+            return Err(anyhow::anyhow!("Enclave signer returned a signature that does not verify"));
+        }
+        Ok(signature)
+    }
+}
+
+fn unexpected(response: &EnclaveResponse) -> anyhow::Error {
+    anyhow::anyhow!("Unexpected response from enclave signer: {:?}", response)
+}
+
+/// 模块：飞地签名进程 (TEE Enclave Signer)
+///
+/// **为什么需要**: 主机被攻破时，进程内存里的私钥 (即便 `mlock` 过) 可以被 dump 出来，之后攻击者能离线伪造任意回执。
+/// 把签名放进可信执行环境 (Intel SGX 飞地，或 AMD SEV-SNP / Intel TDX 机密虚拟机)：
+/// 服务进程把规范化字节经 Unix 套接字送进去，只拿回签名，私钥和 Seed 不出飞地；
+/// 飞地启动时生成平台签名的远程证明，把主公钥绑定到飞地的度量值，经 `/identity` 公布。
+///
+/// - 飞地里运行的是同一个二进制的 `yuanjing enclave-signer`，Seed 按它自己的 `signer.key_source` 加载 (通常为平台封存的文件)；
+/// - 租户与部门密钥在飞地内按原有规则派生，宿主只记派生路径，公钥与回执和本地密钥模式完全一致；
+/// - 宿主对每个返回的签名用已知公钥验签，飞地进程被替换时立即报错，而不是把错误的签名写进日志。
+#[cfg(unix)]
+pub fn serve(config: &EnclaveConfig, master: EvidenceSigner) -> anyhow::Result<()> {
+    let path = Path::new(&config.socket);
+    if path.exists() {
+        std::fs::remove_file(path)?;
+    }
+    let listener = UnixListener::bind(path).map_err(|e| anyhow::anyhow!("Cannot bind '{}': {}", config.socket, e))?;
+    std::fs::set_permissions(path, std::fs::Permissions::from_mode(0o600))?;
+    info!(
+        "🧱 飞地签名进程已就绪: '{}' (主公钥 {}, 远程证明 {})",
+        config.socket,
+        hex::encode(master.public_key().to_bytes()),
+        config.platform.as_str()
+    );
+
+    let master = Arc::new(master);
+    for stream in listener.incoming() {
+        let stream = match stream {
+            Ok(stream) => stream,
+            Err(e) => {
+                warn!("⚠️  飞地签名进程接受连接失败: {}", e);
+                continue;
+            }
+        };
+        let master = master.clone();
+        let platform = config.platform;
+        std::thread::spawn(move || {
+            if let Err(e) = handle(stream, &master, platform) {
+                warn!("⚠️  飞地签名连接异常结束: {}", e);
+            }
+        });
+    }
+    Ok(())
+}
+
+#[cfg(not(unix))]
+pub fn serve(config: &EnclaveConfig, _master: EvidenceSigner) -> anyhow::Result<()> {
+    Err(anyhow::anyhow!("The enclave signer listens on a Unix socket ('{}') and needs a Unix platform", config.socket))
+}
+
+#[cfg(unix)]
+fn handle(stream: UnixStream, master: &EvidenceSigner, platform: AttestationPlatform) -> anyhow::Result<()> {
+    let mut writer = stream.try_clone()?;
+    for line in BufReader::new(stream).lines() {
+        let response = match serde_json::from_str::<EnclaveRequest>(&line?) {
+            Ok(request) => respond(request, master, platform).unwrap_or_else(|e| EnclaveResponse::Error { message: e.to_string() }),
+            Err(e) => EnclaveResponse::Error { message: format!("Malformed request: {}", e) },
+        };
+        let mut bytes = serde_json::to_vec(&response)?;
+        bytes.push(b'\n');
+        writer.write_all(&bytes)?;
+    }
+    Ok(())
+}
+
+#[cfg(unix)]
+fn respond(request: EnclaveRequest, master: &EvidenceSigner, platform: AttestationPlatform) -> anyhow::Result<EnclaveResponse> {
+    match request {
+        EnclaveRequest::PublicKey { derivation } => {
+            let public_key = derive(master, &derivation)?.map_or_else(|| master.public_key(), |signer| signer.public_key());
+            Ok(EnclaveResponse::PublicKey { public_key: hex::encode(public_key.to_bytes()) })
+        }
+        EnclaveRequest::Sign { derivation, payload } => {
+            let payload = BASE64.decode(payload)?;
+            let signature = match derive(master, &derivation)? {
+                Some(signer) => signer.sign_bytes(&payload)?,
+                None => master.sign_bytes(&payload)?,
+            };
+            Ok(EnclaveResponse::Signature { signature: hex::encode(signature.to_bytes()) })
+        }
+        EnclaveRequest::Attest { issued_at } => Ok(EnclaveResponse::Attestation(attest(platform, &master.public_key(), issued_at)?)),
+    }
+}
+
+/// 按派生路径从主密钥派生；路径为空时返回 None (即主密钥本身)
+#[cfg(unix)]
+fn derive(master: &EvidenceSigner, derivation: &[DerivationStep]) -> anyhow::Result<Option<EvidenceSigner>> {
+    let mut current: Option<EvidenceSigner> = None;
+    for step in derivation {
+        let parent = current.as_ref().unwrap_or(master);
+        current = Some(match step {
+            DerivationStep::Tenant(tenant_id) => parent.derive_tenant(tenant_id)?,
+            DerivationStep::Path(path) => parent.derive_path(&DerivationPath::parse(path)?)?,
+        });
+    }
+    Ok(current)
+}
+
+/// 在飞地内生成远程证明
+#[cfg(unix)]
+fn attest(platform: AttestationPlatform, public_key: &VerifyingKey, issued_at: i64) -> anyhow::Result<EnclaveAttestation> {
+    let data = report_data(public_key, issued_at);
+    let (provider, quote) = match platform {
+        AttestationPlatform::None => return Err(anyhow::anyhow!("Remote attestation is disabled (signer.enclave.platform = \"none\")")),
+        AttestationPlatform::Sgx => {
+            let provider = std::fs::read_to_string(GRAMINE_ATTESTATION_TYPE)
+                .map_err(|e| anyhow::anyhow!("Not running under Gramine SGX ({}): {}", GRAMINE_ATTESTATION_TYPE, e))?;
+            std::fs::write(GRAMINE_USER_REPORT_DATA, data)?;
+            (provider.trim().to_string(), std::fs::read(GRAMINE_QUOTE)?)
+        }
+        AttestationPlatform::Tsm => {
+            let nanos = std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH)?.subsec_nanos();
+            let dir = Path::new(TSM_REPORT_DIR).join(format!("yuanjing-{}-{}", std::process::id(), nanos));
+            std::fs::create_dir(&dir).map_err(|e| anyhow::anyhow!("Cannot create configfs-tsm report '{}': {}", dir.display(), e))?;
+            let report = (|| -> anyhow::Result<(String, Vec<u8>)> {
+                std::fs::write(dir.join("inblob"), data)?;
+                Ok((std::fs::read_to_string(dir.join("provider"))?.trim().to_string(), std::fs::read(dir.join("outblob"))?))
+            })();
+            let _ = std::fs::remove_dir(&dir);
+            report?
+        }
+    };
+    Ok(EnclaveAttestation {
+        platform,
+        provider,
+        public_key: hex::encode(public_key.to_bytes()),
+        issued_at,
+        report_data: hex::encode(data),
+        quote: BASE64.encode(quote),
+    })
+}
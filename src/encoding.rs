@@ -0,0 +1,109 @@
+use base64::alphabet;
+use base64::engine::general_purpose::{GeneralPurpose, GeneralPurposeConfig, STANDARD};
+use base64::engine::DecodePaddingMode;
+use base64::Engine;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+
+/// 解码时填充可有可无 (各语言的 base64 库对填充的处理不一)
+const BASE64_LENIENT: GeneralPurpose = GeneralPurpose::new(
+    &alphabet::STANDARD,
+    GeneralPurposeConfig::new().with_decode_padding_mode(DecodePaddingMode::Indifferent),
+);
+const BASE64_URL_LENIENT: GeneralPurpose = GeneralPurpose::new(
+    &alphabet::URL_SAFE,
+    GeneralPurposeConfig::new().with_decode_padding_mode(DecodePaddingMode::Indifferent),
+);
+
+/// 模块：二进制字段编码 (Binary Encoding)
+///
+/// **为什么需要**: 哈希、签名、公钥在响应中原本一律是 Hex，而感知哈希 (pHash) 却是 base64，
+/// 调用方只能逐个字段去猜。浏览器端与移动端又常常希望用更短的 base64，DID / VC 生态则习惯 multibase。
+/// 这里统一三种编码的编解码，接口按 `?encoding=` 或 `Accept` 参数选择输出编码，
+/// 响应中的 `binary_encoding` 标明本次使用的编码。
+///
+/// - `hex`：小写十六进制 (默认，与存储及签名内容一致)
+/// - `base64`：标准字母表，带填充
+/// - `multibase`：base58btc (`z` 前缀)，与 did:key、VC `proofValue` 相同；
+///   解码时另接受 `f` (base16)、`m` / `M` (base64)、`u` / `U` (base64url) 前缀
+///
+/// 签名内容中的字段 (回执、证据、树头等) 按签名时的原样输出，不随所选编码变化，否则签名无法核对。
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum BinaryEncoding {
+    #[default]
+    Hex,
+    Base64,
+    Multibase,
+}
+
+impl BinaryEncoding {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Hex => "hex",
+            Self::Base64 => "base64",
+            Self::Multibase => "multibase",
+        }
+    }
+
+    pub fn encode(self, bytes: impl AsRef<[u8]>) -> String {
+        let bytes = bytes.as_ref();
+        match self {
+            Self::Hex => hex::encode(bytes),
+            Self::Base64 => STANDARD.encode(bytes),
+            Self::Multibase => format!("z{}", bs58::encode(bytes).into_string()),
+        }
+    }
+
+    pub fn decode(self, text: &str) -> anyhow::Result<Vec<u8>> {
+        match self {
+            Self::Hex => hex::decode(text).map_err(|e| anyhow::anyhow!("Invalid hex: {}", e)),
+            Self::Base64 => BASE64_LENIENT.decode(text).map_err(|e| anyhow::anyhow!("Invalid base64: {}", e)),
+            Self::Multibase => decode_multibase(text),
+        }
+    }
+
+    /// 把 Hex 字符串 (存储与签名内容中的形式) 改写为本编码
+    pub fn from_hex(self, hex_str: &str) -> anyhow::Result<String> {
+        match self {
+            Self::Hex => Ok(hex_str.to_string()),
+            _ => Ok(self.encode(Self::Hex.decode(hex_str)?)),
+        }
+    }
+}
+
+impl fmt::Display for BinaryEncoding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for BinaryEncoding {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "hex" | "base16" => Ok(Self::Hex),
+            "base64" => Ok(Self::Base64),
+            "multibase" => Ok(Self::Multibase),
+            other => Err(anyhow::anyhow!("Unknown encoding '{}' (expected hex, base64 or multibase)", other)),
+        }
+    }
+}
+
+/// 按 multibase 前缀解码
+pub fn decode_multibase(text: &str) -> anyhow::Result<Vec<u8>> {
+    let mut chars = text.chars();
+    let prefix = chars.next().ok_or_else(|| anyhow::anyhow!("Empty multibase string"))?;
+    let body = chars.as_str();
+    let decoded = match prefix {
+        'z' => bs58::decode(body).into_vec().map_err(|e| e.to_string()),
+        'f' | 'F' => hex::decode(body).map_err(|e| e.to_string()),
+        'm' | 'M' => BASE64_LENIENT.decode(body).map_err(|e| e.to_string()),
+        'u' | 'U' => BASE64_URL_LENIENT.decode(body).map_err(|e| e.to_string()),
+        other => return Err(anyhow::anyhow!("Unsupported multibase prefix '{}'", other)),
+    };
+    decoded.map_err(|e| anyhow::anyhow!("Invalid multibase ({}): {}", prefix, e))
+}
@@ -0,0 +1,199 @@
+use chacha20poly1305::aead::{Aead, KeyInit, Payload};
+use chacha20poly1305::{Key, XChaCha20Poly1305, XNonce};
+use ed25519_dalek::VerifyingKey;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use x25519_dalek::{PublicKey, SharedSecret, StaticSecret};
+use zeroize::Zeroizing;
+
+use crate::bundle::{receipt_signing_key, verify_receipt, BundleEndorsement, BundleStatus, EvidenceBundle};
+use crate::receipt::Receipt;
+use crate::revocation::RevocationList;
+
+/// 当前加密证据包格式版本
+pub const SEALED_BUNDLE_FORMAT: u32 = 1;
+
+/// 包装数据密钥时的 Blake3 派生上下文
+const WRAP_CONTEXT: &str = "yuanjing-core bundle key wrap v1";
+/// 证据包密文的附加认证数据前缀，随后是回执的规范字节
+const AAD_DOMAIN: &[u8] = b"yuanjing-core sealed bundle v1";
+/// 每把包装密钥只用一次 (每个收件人一把临时密钥)，随机数固定为零
+const WRAP_NONCE: [u8; 24] = [0; 24];
+
+/// 为某个收件人包装的数据密钥
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
+pub struct WrappedKey {
+    /// 收件人的 X25519 公钥 (Hex)
+    pub recipient: String,
+    /// 本次包装的临时 X25519 公钥 (Hex)
+    pub ephemeral_public_key: String,
+    /// XChaCha20-Poly1305 加密的 32 字节数据密钥 (Hex)
+    pub wrapped_key: String,
+}
+
+/// 模块：信封加密的证据包 (Sealed Evidence Bundle)
+///
+/// **为什么需要**: 证据包要交给特定的法院或代理律师，途中经过邮件、网盘、案件管理系统，
+/// 不能让经手的每一方都看到取证细节。每个证据包生成一把随机数据密钥加密完整的 [`EvidenceBundle`]，
+/// 数据密钥再按收件人的 X25519 公钥分别包装 (临时密钥 ECDH + Blake3-KDF)，只有持有对应私钥的收件人能解开。
+///
+/// 回执、回执签名、租户根公钥、背书与包含性证明留在明文中：任何人都能用 [`SealedBundle::verify_public`]
+/// 确认“日志中确有这样一条经签名的证据”，收件人解密后再用 [`EvidenceBundle::verify`] 做完整校验。
+/// 密文以回执的规范字节为附加认证数据，挪到另一张回执下即解密失败。
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct SealedBundle {
+    /// 格式版本，当前为 1
+    pub format: u32,
+    pub receipt: Receipt,
+    /// 回执签名 (Hex)
+    pub receipt_signature: String,
+    /// 租户根公钥 (Hex)
+    pub public_key: String,
+    #[serde(default)]
+    pub endorsement: Option<BundleEndorsement>,
+    /// 叶子在 `receipt.tree_size` 大小的树中的证明路径 (Hex)
+    pub proof: Vec<String>,
+    /// XChaCha20 随机数 (Hex)
+    pub nonce: String,
+    /// 加密的证据包 JSON (Hex)
+    pub ciphertext: String,
+    pub recipients: Vec<WrappedKey>,
+}
+
+impl SealedBundle {
+    /// 为给定的收件人 (X25519 公钥) 加密证据包
+    #[cfg(feature = "server")]
+    pub fn seal(bundle: &EvidenceBundle, recipients: &[[u8; 32]]) -> anyhow::Result<Self> {
+        use rand::RngCore;
+
+        if recipients.is_empty() {
+            return Err(anyhow::anyhow!("A sealed bundle needs at least one recipient"));
+        }
+        let mut data_key = Zeroizing::new([0u8; 32]);
+        rand::rngs::OsRng.fill_bytes(data_key.as_mut_slice());
+        let mut nonce = [0u8; 24];
+        rand::rngs::OsRng.fill_bytes(&mut nonce);
+
+        let plaintext = Zeroizing::new(serde_json::to_vec(bundle)?);
+        let aad = aad(&bundle.receipt)?;
+        let ciphertext = XChaCha20Poly1305::new(Key::from_slice(data_key.as_slice()))
+            .encrypt(XNonce::from_slice(&nonce), Payload { msg: &plaintext, aad: &aad })
+            .map_err(|_| anyhow::anyhow!("Bundle encryption failed"))?;
+
+        let recipients = recipients
+            .iter()
+            .map(|recipient| {
+                let ephemeral = StaticSecret::random_from_rng(rand::rngs::OsRng);
+                let ephemeral_public = PublicKey::from(&ephemeral);
+                let recipient = PublicKey::from(*recipient);
+                let kek = wrapping_key(&ephemeral.diffie_hellman(&recipient), &ephemeral_public, &recipient)?;
+                let wrapped = XChaCha20Poly1305::new(Key::from_slice(kek.as_slice()))
+                    .encrypt(XNonce::from_slice(&WRAP_NONCE), data_key.as_slice())
+                    .map_err(|_| anyhow::anyhow!("Key wrapping failed"))?;
+                Ok(WrappedKey {
+                    recipient: hex::encode(recipient.as_bytes()),
+                    ephemeral_public_key: hex::encode(ephemeral_public.as_bytes()),
+                    wrapped_key: hex::encode(wrapped),
+                })
+            })
+            .collect::<anyhow::Result<Vec<_>>>()?;
+
+        Ok(Self {
+            format: SEALED_BUNDLE_FORMAT,
+            receipt: bundle.receipt.clone(),
+            receipt_signature: bundle.receipt_signature.clone(),
+            public_key: bundle.public_key.clone(),
+            endorsement: bundle.endorsement.clone(),
+            proof: bundle.proof.clone(),
+            nonce: hex::encode(nonce),
+            ciphertext: hex::encode(ciphertext),
+            recipients,
+        })
+    }
+
+    /// 从 JSON 字节解析
+    pub fn from_json(bytes: &[u8]) -> anyhow::Result<Self> {
+        let sealed: Self = serde_json::from_slice(bytes)?;
+        if sealed.format != SEALED_BUNDLE_FORMAT {
+            return Err(anyhow::anyhow!("Unsupported sealed bundle format {}", sealed.format));
+        }
+        Ok(sealed)
+    }
+
+    /// 不解密的公开校验：回执签名、派生密钥背书、回执中的叶子哈希在回执那棵树中的包含性、密钥吊销
+    ///
+    /// 证明的是“日志里确有一条经签名的证据”，不涉及证据内容；不会返回与原文相关的状态。
+    pub fn verify_public(&self, trusted_root: Option<&VerifyingKey>, revocations: Option<&RevocationList>) -> BundleStatus {
+        match receipt_signing_key(&self.receipt, &self.public_key, self.endorsement.as_ref(), trusted_root) {
+            Ok(signing_key) => verify_receipt(&self.receipt, &self.receipt_signature, &signing_key, &self.proof, revocations),
+            Err(status) => status,
+        }
+    }
+
+    /// 收件人用自己的 X25519 私钥解密出完整证据包 (解密后仍须用 [`EvidenceBundle::verify`] 校验)
+    pub fn open(&self, secret: &[u8; 32]) -> anyhow::Result<EvidenceBundle> {
+        let secret = StaticSecret::from(*secret);
+        let own_public = PublicKey::from(&secret);
+        let public = hex::encode(own_public.as_bytes());
+        let wrapped = self
+            .recipients
+            .iter()
+            .find(|wrapped| wrapped.recipient == public)
+            .ok_or_else(|| anyhow::anyhow!("This bundle is not sealed for recipient {}", public))?;
+
+        let ephemeral_public = PublicKey::from(decode_32(&wrapped.ephemeral_public_key)?);
+        let kek = wrapping_key(&secret.diffie_hellman(&ephemeral_public), &ephemeral_public, &own_public)?;
+        let data_key = Zeroizing::new(
+            XChaCha20Poly1305::new(Key::from_slice(kek.as_slice()))
+                .decrypt(XNonce::from_slice(&WRAP_NONCE), hex::decode(&wrapped.wrapped_key)?.as_slice())
+                .map_err(|_| anyhow::anyhow!("Cannot unwrap the data key for recipient {}", public))?,
+        );
+        if data_key.len() != 32 {
+            return Err(anyhow::anyhow!("Unwrapped data key has {} bytes", data_key.len()));
+        }
+
+        let nonce = hex::decode(&self.nonce)?;
+        if nonce.len() != 24 {
+            return Err(anyhow::anyhow!("Bundle nonce must be 24 bytes, got {}", nonce.len()));
+        }
+        let aad = aad(&self.receipt)?;
+        let plaintext = Zeroizing::new(
+            XChaCha20Poly1305::new(Key::from_slice(&data_key))
+                .decrypt(XNonce::from_slice(&nonce), Payload { msg: &hex::decode(&self.ciphertext)?, aad: &aad })
+                .map_err(|_| anyhow::anyhow!("Bundle ciphertext failed authenticated decryption"))?,
+        );
+        let bundle = EvidenceBundle::from_json(&plaintext)?;
+        if bundle.receipt != self.receipt {
+            return Err(anyhow::anyhow!("Decrypted bundle does not match the public receipt"));
+        }
+        Ok(bundle)
+    }
+}
+
+/// 由 X25519 私钥得出收件人公钥 (收件人生成密钥对后把公钥交给导出方)
+pub fn recipient_public_key(secret: &[u8; 32]) -> [u8; 32] {
+    PublicKey::from(&StaticSecret::from(*secret)).to_bytes()
+}
+
+/// 包装密钥 = Blake3-KDF(共享秘密 || 临时公钥 || 收件人公钥)；拒绝小阶点得出的全零共享秘密
+fn wrapping_key(shared: &SharedSecret, ephemeral: &PublicKey, recipient: &PublicKey) -> anyhow::Result<Zeroizing<[u8; 32]>> {
+    if !shared.was_contributory() {
+        return Err(anyhow::anyhow!("Key agreement with a low-order point"));
+    }
+    let mut material = Zeroizing::new(shared.as_bytes().to_vec());
+    material.extend_from_slice(ephemeral.as_bytes());
+    material.extend_from_slice(recipient.as_bytes());
+    Ok(Zeroizing::new(blake3::derive_key(WRAP_CONTEXT, &material)))
+}
+
+fn aad(receipt: &Receipt) -> anyhow::Result<Vec<u8>> {
+    let mut aad = AAD_DOMAIN.to_vec();
+    aad.extend_from_slice(&receipt.canonical_bytes()?);
+    Ok(aad)
+}
+
+fn decode_32(value: &str) -> anyhow::Result<[u8; 32]> {
+    hex::decode(value)?
+        .try_into()
+        .map_err(|_| anyhow::anyhow!("Expected a 32-byte key, got '{}'", value))
+}
@@ -1,7 +1,13 @@
-use serde::{Deserialize, Serialize}; // 引入序列化库，让结构体能转成JSON/二进制传输
+use prost::Message;
+use schemars::{json_schema, JsonSchema, Schema, SchemaGenerator};
+use serde::de::{self, Visitor};
+use serde::{Deserialize, Deserializer, Serialize, Serializer}; // 引入序列化库，让结构体能转成JSON/二进制传输
+use std::borrow::Cow;
+use std::collections::BTreeMap;
+use std::fmt;
 
 // Derive 宏：自动为结构体生成 Debug打印、序列化、反序列化、克隆(Clone) 的能力
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, JsonSchema)]
 pub struct Evidence {
     // === 第一层：物理指纹 (Identity) ===
     
@@ -9,12 +15,13 @@ pub struct Evidence {
     // 作用：解决“内容同一性”。
     // 细节：比如图片经过微信压缩、裁剪，SHA256 会全变，但 pHash 依然相似。
     // 类型：String (存储为 Base64 编码，因为这种格式短小且适合数据库存储)
+    //       属于签名内容，不随接口所选的输出编码 (`?encoding=`) 改变
     pub image_phash: String, 
     
     // 密码学哈希 (SHA256)
     // 作用：解决“原始完整性”。
     // 细节：哪怕图片元数据里改了一个字节，这个值都会雪崩式变化。这是用来防止“调包”的。
-    // 类型：String (存储为 64字符的 Hex 字符串)
+    // 类型：String (存储为 64字符的 Hex 字符串，同样不随输出编码改变)
     pub image_sha256: String,
 
     // === 第二层：推理指纹 (Reasoning) ===
@@ -26,8 +33,8 @@ pub struct Evidence {
     
     // 置信度
     // 作用：AI 有多大把握。
-    // 类型：String (存储为字符串以确保序列化确定性，例如 "0.99")
-    pub confidence: String,
+    // 类型：Confidence (万分比定点数，规范字节中为 u16，例如 9900；历史证据保留当年的字符串，见 Confidence)
+    pub confidence: Confidence,
     
     // 激活的提示词索引 (SAPT - 稀疏激活)
     // 作用：这是“白盒审计”的关键！
@@ -53,6 +60,799 @@ pub struct Evidence {
     
     // 时间戳
     // 作用：数字确权的核心，证明“在该时间点，该状态已存在”。
-    // 类型：i64 (Unix 时间戳，秒级或毫秒级)
+    // 类型：i64 (Unix 时间戳，秒级或毫秒级)；由服务端在分析时填写
     pub timestamp: i64,
-}
\ No newline at end of file
+
+    // 提交方声明的时间
+    // 作用：提交方自己声称的采集 / 提交时刻，与服务端的 `timestamp`、回执签发时间分开记录。
+    //       两者都在签名内容中，时间线有争议时能分清哪个时间是谁声称的。
+    // 类型：Option<i64> (Unix 秒)；未声明时不进入规范字节，字节与哈希和引入本字段之前一致
+    #[serde(default)]
+    pub claimed_at: Option<i64>,
+
+    // 多模型共识
+    // 作用：多个检测模型评估同一张图时，逐一记下每个模型的判定、置信度与 Prompt 池哈希，以及合成规则。
+    //       顶层的 verdict / confidence 是按规则合成的结果；模型之间的分歧保留在这里，不被合成结果抹平。
+    // 类型：Option<Consensus>；单模型证据为 None，不进入规范字节
+    #[serde(default)]
+    pub consensus: Option<Consensus>,
+
+    // 模型校准
+    // 作用：记下校准版本、温度缩放系数与把 SAPT 分数转为判定的决策阈值。
+    //       同样是 0.51 的“伪造”判定，阈值 0.5 与阈值 0.3 下的含义截然不同；多年后复查时据此还原判定当时的语境。
+    // 类型：Option<Calibration>；未提供时不进入规范字节
+    #[serde(default)]
+    pub calibration: Option<Calibration>,
+
+    // 提交来源
+    // 作用：按 `[provenance]` 配置由服务端记下请求方 IP、客户端证书主题与 User-Agent (可哈希或假名化)。
+    //       提交从哪里来本身就可能是取证线索；写进签名内容后，事后无法再改称来自别处。
+    // 类型：Option<Provenance>；未开启采集时不进入规范字节
+    #[serde(default)]
+    pub provenance: Option<Provenance>,
+
+    // 提交方上下文
+    // 作用：接入方自己的工单号、流水线 ID 等键值对，按键排序后原样写进签名内容，
+    //       日后凭自己系统里的编号就能找到并证明对应的那条证据。
+    // 类型：Option<ClientContext>；未提供时不进入规范字节
+    #[serde(default)]
+    pub client_context: Option<ClientContext>,
+}
+impl Evidence {
+    /// 规范化字节 (Canonical Bytes)
+    ///
+    /// 签名与 MMR 叶子哈希共用同一份 BCS 字节流，保证“签的”和“存的”是同一个东西。
+    /// 先写八个基础字段，可选字段 (`claimed_at`、`consensus`、`calibration`、`provenance`、`client_context`，
+    /// 按引入先后排列) 只写到最后一个为 Some 的为止，其前为 None 的各写一个 0x00。
+    /// 新增可选字段时接在末尾，不带该字段的证据字节与引入前一致，历史证据的哈希与签名因此不变。
+    pub fn canonical_bytes(&self) -> anyhow::Result<Vec<u8>> {
+        let mut bytes = bcs::to_bytes(&(
+            &self.image_phash,
+            &self.image_sha256,
+            self.verdict,
+            &self.confidence,
+            &self.activated_prompts,
+            &self.prompt_pool_hash,
+            &self.external_knowledge_hash,
+            self.timestamp,
+        ))?;
+        let tail = self.optional_fields();
+        if tail > 0 {
+            bytes.extend(bcs::to_bytes(&self.claimed_at)?);
+        }
+        if tail > 1 {
+            bytes.extend(bcs::to_bytes(&self.consensus)?);
+        }
+        if tail > 2 {
+            bytes.extend(bcs::to_bytes(&self.calibration)?);
+        }
+        if tail > 3 {
+            bytes.extend(bcs::to_bytes(&self.provenance)?);
+        }
+        if tail > 4 {
+            bytes.extend(bcs::to_bytes(&self.client_context)?);
+        }
+        Ok(bytes)
+    }
+
+    /// 规范字节中写出的可选字段数：到最后一个为 Some 的字段为止
+    fn optional_fields(&self) -> usize {
+        [
+            self.claimed_at.is_some(),
+            self.consensus.is_some(),
+            self.calibration.is_some(),
+            self.provenance.is_some(),
+            self.client_context.is_some(),
+        ]
+        .iter()
+        .rposition(|present| *present)
+        .map_or(0, |last| last + 1)
+    }
+
+    /// 叶子哈希 (Leaf Hash)
+    ///
+    /// MMR 中每个叶子节点的值：$ H_{leaf} = \text{Blake3}(\text{BCS}(Evidence)) $
+    pub fn leaf_hash(&self) -> anyhow::Result<[u8; 32]> {
+        Ok(*blake3::hash(&self.canonical_bytes()?).as_bytes())
+    }
+
+    /// 按指定规范编码输出字节 (回执的 `encoding` 字段标明签发时使用的编码)
+    pub fn canonical_bytes_as(&self, encoding: CanonicalEncoding) -> anyhow::Result<Vec<u8>> {
+        match encoding {
+            CanonicalEncoding::Bcs => self.canonical_bytes(),
+            CanonicalEncoding::Protobuf => Ok(crate::proto::Evidence::from(self).encode_to_vec()),
+        }
+    }
+
+    /// 按指定规范编码计算叶子哈希：Blake3(canonical_bytes_as(encoding))
+    pub fn leaf_hash_as(&self, encoding: CanonicalEncoding) -> anyhow::Result<[u8; 32]> {
+        Ok(*blake3::hash(&self.canonical_bytes_as(encoding)?).as_bytes())
+    }
+
+    /// 按指定规范编码计算叶子哈希，`salt` 不为空时为加盐叶子 (见 [`leaf_hash_of`])
+    pub fn salted_leaf_hash_as(&self, encoding: CanonicalEncoding, salt: Option<&[u8; 32]>) -> anyhow::Result<[u8; 32]> {
+        Ok(leaf_hash_of(&self.canonical_bytes_as(encoding)?, salt))
+    }
+
+    /// 内容寻址的证据 ID (见 [`evidence_id_of`])
+    pub fn evidence_id_as(&self, encoding: CanonicalEncoding) -> anyhow::Result<[u8; 32]> {
+        Ok(evidence_id_of(&self.canonical_bytes_as(encoding)?))
+    }
+
+    /// 从规范字节还原证据
+    ///
+    /// BCS 字节按与 [`Evidence::canonical_bytes`] 相同的规则解析：从五个可选字段起依次减少，
+    /// 取第一个恰好用完全部字节、且最后一个可选字段为 Some 的布局 (BCS 不允许多余或缺少的字节，所以至多一个成立)。
+    /// 都不成立时按引入定点置信度之前的布局 (置信度为字符串、没有可选字段) 解析；两者不会混淆：
+    /// 旧布局中置信度字符串的首字符 (数字或 `-`) 落在 u16 的高字节上，换算后必然超过 10000。
+    pub fn from_canonical_bytes(encoding: CanonicalEncoding, bytes: &[u8]) -> anyhow::Result<Self> {
+        match encoding {
+            CanonicalEncoding::Bcs => {
+                for tail in (1..=OPTIONAL_FIELDS).rev() {
+                    if let Ok(evidence) = bcs::from_bytes_seed(CanonicalLayout { tail, legacy_confidence: false }, bytes) {
+                        if evidence.optional_fields() == tail {
+                            return Ok(evidence);
+                        }
+                    }
+                }
+                match bcs::from_bytes_seed(CanonicalLayout { tail: 0, legacy_confidence: false }, bytes) {
+                    Ok(evidence) => Ok(evidence),
+                    Err(_) => Ok(bcs::from_bytes_seed(CanonicalLayout { tail: 0, legacy_confidence: true }, bytes)?),
+                }
+            }
+            CanonicalEncoding::Protobuf => Ok(crate::proto::Evidence::decode(bytes)?.into()),
+        }
+    }
+}
+
+/// 规范字节中可选字段的个数
+const OPTIONAL_FIELDS: usize = 5;
+
+/// 规范字节的一种布局：八个基础字段加前 `tail` 个可选字段
+///
+/// `legacy_confidence` 为引入定点置信度之前的布局，置信度按字符串解析为 [`Confidence::Legacy`]，
+/// 重新编码得到的字节与当年一致。
+struct CanonicalLayout {
+    tail: usize,
+    legacy_confidence: bool,
+}
+
+impl<'de> de::DeserializeSeed<'de> for CanonicalLayout {
+    type Value = Evidence;
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<Evidence, D::Error> {
+        deserializer.deserialize_tuple(8 + self.tail, self)
+    }
+}
+
+impl<'de> Visitor<'de> for CanonicalLayout {
+    type Value = Evidence;
+
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "canonical evidence with {} optional fields", self.tail)
+    }
+
+    fn visit_seq<A: de::SeqAccess<'de>>(self, mut seq: A) -> Result<Evidence, A::Error> {
+        fn next<'de, T: Deserialize<'de>, A: de::SeqAccess<'de>>(seq: &mut A) -> Result<T, A::Error> {
+            seq.next_element()?.ok_or_else(|| de::Error::custom("truncated canonical evidence"))
+        }
+
+        let image_phash = next(&mut seq)?;
+        let image_sha256 = next(&mut seq)?;
+        let verdict = next(&mut seq)?;
+        let confidence = if self.legacy_confidence { Confidence::Legacy(next(&mut seq)?) } else { next(&mut seq)? };
+        let mut evidence = Evidence {
+            image_phash,
+            image_sha256,
+            verdict,
+            confidence,
+            activated_prompts: next(&mut seq)?,
+            prompt_pool_hash: next(&mut seq)?,
+            external_knowledge_hash: next(&mut seq)?,
+            timestamp: next(&mut seq)?,
+            claimed_at: None,
+            consensus: None,
+            calibration: None,
+            provenance: None,
+            client_context: None,
+        };
+        if self.tail > 0 {
+            evidence.claimed_at = next(&mut seq)?;
+        }
+        if self.tail > 1 {
+            evidence.consensus = next(&mut seq)?;
+        }
+        if self.tail > 2 {
+            evidence.calibration = next(&mut seq)?;
+        }
+        if self.tail > 3 {
+            evidence.provenance = next(&mut seq)?;
+        }
+        if self.tail > 4 {
+            evidence.client_context = next(&mut seq)?;
+        }
+        Ok(evidence)
+    }
+}
+
+/// 多模型共识中单个模型的判定
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct ModelVerdict {
+    /// 该模型的 Prompt 池哈希 (须已登记)
+    pub prompt_pool_hash: String,
+    pub verdict: bool,
+    pub confidence: Confidence,
+}
+
+/// 由各模型判定合成顶层判定的规则
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ConsensusRule {
+    /// 过半数模型判为真才为真 (平票为假)
+    #[default]
+    Majority,
+    /// 全部模型判为真才为真
+    Unanimous,
+    /// 按置信度加权：判为真的置信度之和大于判为假的置信度之和才为真
+    ConfidenceWeighted,
+}
+
+impl ConsensusRule {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Majority => "majority",
+            Self::Unanimous => "unanimous",
+            Self::ConfidenceWeighted => "confidence_weighted",
+        }
+    }
+}
+
+impl std::str::FromStr for ConsensusRule {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> anyhow::Result<Self> {
+        match s.to_ascii_lowercase().as_str() {
+            "majority" => Ok(Self::Majority),
+            "unanimous" => Ok(Self::Unanimous),
+            "confidence_weighted" => Ok(Self::ConfidenceWeighted),
+            other => Err(anyhow::anyhow!(
+                "Unknown consensus rule '{}', expected 'majority', 'unanimous' or 'confidence_weighted'",
+                other
+            )),
+        }
+    }
+}
+
+/// 多模型共识 (Multi-Model Consensus)
+///
+/// **为什么需要**: 同一张图往往要过好几个检测模型。只存一个合成后的判定，模型之间的分歧 (三个说真、一个说假)
+/// 就从证据里消失了，事后无从追问“哪个模型不同意、有多大把握”。这里把每个模型的判定原样写进签名内容，
+/// 并记下合成所用的规则，任何人都能按规则从各模型判定重算出顶层的 `verdict` / `confidence`。
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct Consensus {
+    pub rule: ConsensusRule,
+    /// 各模型的判定 (按提交顺序)
+    pub models: Vec<ModelVerdict>,
+}
+
+impl Consensus {
+    /// 按规则合成顶层判定与置信度；置信度取与合成判定一致的各模型置信度的平均值 (万分比，四舍五入)
+    ///
+    /// 没有模型、或有模型的置信度不是万分比 (历史字符串) 时报错。
+    pub fn decide(&self) -> anyhow::Result<(bool, Confidence)> {
+        let votes = self
+            .models
+            .iter()
+            .map(|model| {
+                model.confidence.basis_points().map(|bps| (model.verdict, bps as i64)).ok_or_else(|| {
+                    anyhow::anyhow!("Model '{}' has no numeric confidence", model.prompt_pool_hash)
+                })
+            })
+            .collect::<anyhow::Result<Vec<_>>>()?;
+        if votes.is_empty() {
+            return Err(anyhow::anyhow!("Consensus needs at least one model verdict"));
+        }
+        let verdict = match self.rule {
+            ConsensusRule::Majority => votes.iter().filter(|(verdict, _)| *verdict).count() * 2 > votes.len(),
+            ConsensusRule::Unanimous => votes.iter().all(|(verdict, _)| *verdict),
+            ConsensusRule::ConfidenceWeighted => {
+                votes.iter().map(|(verdict, bps)| if *verdict { *bps } else { -bps }).sum::<i64>() > 0
+            }
+        };
+        let agreeing: Vec<i64> = votes.iter().filter(|(v, _)| *v == verdict).map(|(_, bps)| *bps).collect();
+        // 只有加权规则下全体判为真、置信度却都为 0 时没有同意者，此时置信度为 0
+        let count = agreeing.len().max(1) as i64;
+        let mean = (agreeing.iter().sum::<i64>() + count / 2) / count;
+        Ok((verdict, Confidence::from_basis_points(mean as u16)?))
+    }
+}
+
+/// 温度缩放系数的定点倍数 (千分比，1000 即 1.0)
+pub const TEMPERATURE_SCALE: u32 = 1_000;
+
+/// 模型校准 (Model Calibration)
+///
+/// 判定当时模型输出是如何被校准、又是按什么阈值判为真假的。与置信度一样以定点整数入库，
+/// 规范字节中不出现浮点数。
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct Calibration {
+    /// 校准版本 (如温度缩放参数的发布标识)
+    pub version: String,
+    /// 温度缩放系数 (千分比，1000 即 1.0)
+    pub temperature_milli: u32,
+    /// 把 SAPT 分数转为判定的决策阈值 (万分比，0–10000)：分数不低于阈值判为真
+    pub threshold_bps: u16,
+}
+
+impl Calibration {
+    /// 由浮点数换算：温度四舍五入到千分位后须为正，阈值须在 `[0, 1]` 内 (四舍五入到万分位)
+    pub fn from_ratios(version: String, temperature: f64, threshold: f64) -> anyhow::Result<Self> {
+        let temperature_milli = (temperature * TEMPERATURE_SCALE as f64).round();
+        if !temperature_milli.is_finite() || !(1.0..=u32::MAX as f64).contains(&temperature_milli) {
+            return Err(anyhow::anyhow!("Temperature {} must be a positive number of at least 0.001", temperature));
+        }
+        let threshold_bps = Confidence::from_ratio(threshold)
+            .ok()
+            .and_then(|threshold| threshold.basis_points())
+            .ok_or_else(|| anyhow::anyhow!("Decision threshold {} is out of range [0.0, 1.0]", threshold))?;
+        Ok(Self { version, temperature_milli: temperature_milli as u32, threshold_bps })
+    }
+}
+
+/// 提交来源的哈希域分隔
+const PROVENANCE_DOMAIN: &[u8] = b"yuanjing-core provenance v1";
+
+/// 提交来源各字段的记录方式
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ProvenanceMode {
+    /// 原文
+    Plain,
+    /// Blake3 哈希 (Hex)：知道原值的人可以核对；取值空间小的字段 (IP) 可被穷举还原，等同原文
+    Hashed,
+    /// 以服务端密钥做带密钥的 Blake3 (Hex)：同一来源得到同一假名，没有密钥无法核对或穷举
+    #[default]
+    Pseudonymized,
+}
+
+impl ProvenanceMode {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Plain => "plain",
+            Self::Hashed => "hashed",
+            Self::Pseudonymized => "pseudonymized",
+        }
+    }
+
+    /// 按该方式处理一个字段的值：`field` 为字段名 (如 `ip`)，假名化须提供密钥
+    pub fn apply(self, field: &str, value: &str, key: Option<&[u8; 32]>) -> anyhow::Result<String> {
+        let mut hasher = match (self, key) {
+            (Self::Plain, _) => return Ok(value.to_string()),
+            (Self::Hashed, _) => blake3::Hasher::new(),
+            (Self::Pseudonymized, Some(key)) => blake3::Hasher::new_keyed(key),
+            (Self::Pseudonymized, None) => return Err(anyhow::anyhow!("Pseudonymized provenance requires a key")),
+        };
+        hasher.update(PROVENANCE_DOMAIN);
+        hasher.update(field.as_bytes());
+        hasher.update(&[0]);
+        hasher.update(value.as_bytes());
+        Ok(hasher.finalize().to_hex().to_string())
+    }
+}
+
+impl std::str::FromStr for ProvenanceMode {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> anyhow::Result<Self> {
+        match s.to_ascii_lowercase().as_str() {
+            "plain" => Ok(Self::Plain),
+            "hashed" => Ok(Self::Hashed),
+            "pseudonymized" => Ok(Self::Pseudonymized),
+            other => Err(anyhow::anyhow!(
+                "Unknown provenance mode '{}', expected 'plain', 'hashed' or 'pseudonymized'",
+                other
+            )),
+        }
+    }
+}
+
+/// 提交来源 (Submitter Provenance)
+///
+/// 服务端在受理存证请求时观察到的来源，各字段按 `mode` 记为原文、哈希或假名 (见 [`ProvenanceMode::apply`])。
+/// 请求中没有的字段为 None。
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct Provenance {
+    pub mode: ProvenanceMode,
+    /// 请求方 IP
+    pub ip: Option<String>,
+    /// 客户端证书主题 (由终止 TLS 的代理以请求头转交)
+    pub client_cert_subject: Option<String>,
+    pub user_agent: Option<String>,
+}
+
+impl fmt::Display for Provenance {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let fields: Vec<String> = [("ip", &self.ip), ("client cert", &self.client_cert_subject), ("user agent", &self.user_agent)]
+            .into_iter()
+            .filter_map(|(label, value)| value.as_ref().map(|value| format!("{} {}", label, value)))
+            .collect();
+        if fields.is_empty() {
+            write!(f, "{} (nothing observed)", self.mode.as_str())
+        } else {
+            write!(f, "{}: {}", self.mode.as_str(), fields.join(", "))
+        }
+    }
+}
+
+impl fmt::Display for Calibration {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} (temperature {}.{:03}, threshold {})",
+            self.version,
+            self.temperature_milli / TEMPERATURE_SCALE,
+            self.temperature_milli % TEMPERATURE_SCALE,
+            Confidence::BasisPoints(self.threshold_bps)
+        )
+    }
+}
+
+/// 提交方上下文最多的键值对数
+pub const MAX_CLIENT_CONTEXT_ENTRIES: usize = 16;
+
+/// 提交方上下文键的最大长度 (字节)
+pub const MAX_CLIENT_CONTEXT_KEY_BYTES: usize = 64;
+
+/// 提交方上下文值的最大长度 (字节)
+pub const MAX_CLIENT_CONTEXT_VALUE_BYTES: usize = 256;
+
+/// 提交方上下文所有键与值的总长度上限 (字节)
+pub const MAX_CLIENT_CONTEXT_BYTES: usize = 2048;
+
+/// 提交方上下文 (Client Context)
+///
+/// 接入方随存证请求附带的字符串键值对 (工单号、流水线 ID 等)。只接受字符串，
+/// 规范化只有一步：按键排序 (BTreeMap)，不做大小写或空白的改写，签进去的就是提交的原文。
+/// 键限 ASCII 字母、数字与 `_ - . :`，值不得含控制字符；条数与长度的上限见上方常量。
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct ClientContext(pub BTreeMap<String, String>);
+
+impl ClientContext {
+    /// 校验键的字符集、值的内容，以及条数与长度上限
+    pub fn validate(&self) -> anyhow::Result<()> {
+        if self.0.is_empty() {
+            return Err(anyhow::anyhow!("client_context must not be empty"));
+        }
+        if self.0.len() > MAX_CLIENT_CONTEXT_ENTRIES {
+            return Err(anyhow::anyhow!("client_context has {} entries, at most {} allowed", self.0.len(), MAX_CLIENT_CONTEXT_ENTRIES));
+        }
+        let mut total = 0;
+        for (key, value) in &self.0 {
+            if key.is_empty() || key.len() > MAX_CLIENT_CONTEXT_KEY_BYTES {
+                return Err(anyhow::anyhow!("client_context key '{}' must be 1 to {} bytes", key, MAX_CLIENT_CONTEXT_KEY_BYTES));
+            }
+            if !key.bytes().all(|b| b.is_ascii_alphanumeric() || matches!(b, b'_' | b'-' | b'.' | b':')) {
+                return Err(anyhow::anyhow!("client_context key '{}' may only contain ASCII letters, digits, '_', '-', '.' and ':'", key));
+            }
+            if value.len() > MAX_CLIENT_CONTEXT_VALUE_BYTES {
+                return Err(anyhow::anyhow!("client_context value of '{}' exceeds {} bytes", key, MAX_CLIENT_CONTEXT_VALUE_BYTES));
+            }
+            if value.chars().any(char::is_control) {
+                return Err(anyhow::anyhow!("client_context value of '{}' must not contain control characters", key));
+            }
+            total += key.len() + value.len();
+        }
+        if total > MAX_CLIENT_CONTEXT_BYTES {
+            return Err(anyhow::anyhow!("client_context totals {} bytes, at most {} allowed", total, MAX_CLIENT_CONTEXT_BYTES));
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for ClientContext {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let pairs: Vec<String> = self.0.iter().map(|(key, value)| format!("{}={}", key, value)).collect();
+        write!(f, "{}", pairs.join(", "))
+    }
+}
+
+impl JsonSchema for ClientContext {
+    fn schema_name() -> Cow<'static, str> {
+        "ClientContext".into()
+    }
+
+    fn json_schema(_generator: &mut SchemaGenerator) -> Schema {
+        json_schema!({
+            "description": "提交方上下文：字符串键值对 (如工单号、流水线 ID)，按键排序后记入证据并随证据签名",
+            "type": "object",
+            "minProperties": 1,
+            "maxProperties": MAX_CLIENT_CONTEXT_ENTRIES,
+            "propertyNames": { "type": "string", "minLength": 1, "maxLength": MAX_CLIENT_CONTEXT_KEY_BYTES },
+            "additionalProperties": { "type": "string", "maxLength": MAX_CLIENT_CONTEXT_VALUE_BYTES }
+        })
+    }
+}
+
+/// 置信度满量程 (万分比)
+pub const CONFIDENCE_SCALE: u16 = 10_000;
+
+/// 置信度 (Confidence)
+///
+/// **为什么需要**: 置信度原先在接口里是浮点数、落盘时是 `f64::to_string()` 的字符串。
+/// 其他语言格式化同一个浮点数的结果并不一致 ("0.9" / "0.90" / "0.8999999761581421")，
+/// 非 Rust 验证方重算叶子哈希时因此对不上。新证据一律以万分比 (basis points，0–10000) 存储，
+/// 规范字节中就是一个 u16 (BCS 小端 2 字节，Protobuf 为 `confidence_bps`)。
+///
+/// JSON 中输出为整数万分比 (如 `9900`)。解析时：整数按万分比，带小数点的数按 `[0, 1]` 的比例换算，
+/// 字符串视为历史证据的原文 (`Legacy`)。`Legacy` 只用于还原引入定点表示之前入库的证据，
+/// 必须保留原文才能重算出当年的叶子哈希与签名原文。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Confidence {
+    BasisPoints(u16),
+    Legacy(String),
+}
+
+impl Confidence {
+    pub fn from_basis_points(bps: u16) -> anyhow::Result<Self> {
+        if bps > CONFIDENCE_SCALE {
+            return Err(anyhow::anyhow!("Confidence {} bps is out of range [0, {}]", bps, CONFIDENCE_SCALE));
+        }
+        Ok(Self::BasisPoints(bps))
+    }
+
+    /// 由 `[0, 1]` 的比例换算，四舍五入到万分位
+    pub fn from_ratio(ratio: f64) -> anyhow::Result<Self> {
+        if !ratio.is_finite() || !(0.0..=1.0).contains(&ratio) {
+            return Err(anyhow::anyhow!("Confidence {} is out of range [0.0, 1.0]", ratio));
+        }
+        Ok(Self::BasisPoints((ratio * CONFIDENCE_SCALE as f64).round() as u16))
+    }
+
+    /// 万分比；历史证据的字符串无法解析时为 None
+    pub fn basis_points(&self) -> Option<u16> {
+        match self {
+            Self::BasisPoints(bps) => Some(*bps),
+            Self::Legacy(text) => match Self::from_ratio(text.trim().parse().ok()?) {
+                Ok(Self::BasisPoints(bps)) => Some(bps),
+                _ => None,
+            },
+        }
+    }
+}
+
+impl fmt::Display for Confidence {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::BasisPoints(bps) => write!(f, "{}.{:02}%", bps / 100, bps % 100),
+            Self::Legacy(text) => f.write_str(text),
+        }
+    }
+}
+
+impl Serialize for Confidence {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            Self::BasisPoints(bps) => serializer.serialize_u16(*bps),
+            // 与旧布局逐字节相同：BCS 中就是原来的字符串字段
+            Self::Legacy(text) => serializer.serialize_str(text),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for Confidence {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        if !deserializer.is_human_readable() {
+            // 规范字节只有当前布局走到这里，旧布局见 `Evidence::from_canonical_bytes`
+            let bps = u16::deserialize(deserializer)?;
+            return Self::from_basis_points(bps).map_err(de::Error::custom);
+        }
+        deserializer.deserialize_any(ConfidenceVisitor)
+    }
+}
+
+struct ConfidenceVisitor;
+
+impl Visitor<'_> for ConfidenceVisitor {
+    type Value = Confidence;
+
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("basis points (0-10000), a ratio in [0.0, 1.0], or a legacy confidence string")
+    }
+
+    fn visit_u64<E: de::Error>(self, value: u64) -> Result<Confidence, E> {
+        let bps = u16::try_from(value).map_err(|_| E::custom(format!("Confidence {} bps is out of range", value)))?;
+        Confidence::from_basis_points(bps).map_err(E::custom)
+    }
+
+    fn visit_i64<E: de::Error>(self, value: i64) -> Result<Confidence, E> {
+        let value = u64::try_from(value).map_err(|_| E::custom(format!("Confidence {} bps is out of range", value)))?;
+        self.visit_u64(value)
+    }
+
+    fn visit_f64<E: de::Error>(self, value: f64) -> Result<Confidence, E> {
+        Confidence::from_ratio(value).map_err(E::custom)
+    }
+
+    fn visit_str<E: de::Error>(self, value: &str) -> Result<Confidence, E> {
+        Ok(Confidence::Legacy(value.to_string()))
+    }
+}
+
+impl JsonSchema for Confidence {
+    fn schema_name() -> Cow<'static, str> {
+        "Confidence".into()
+    }
+
+    fn json_schema(_generator: &mut SchemaGenerator) -> Schema {
+        json_schema!({
+            "description": "置信度：整数为万分比 (0-10000)，带小数点的数为 [0, 1] 的比例，字符串为历史证据的原文",
+            "anyOf": [
+                { "type": "integer", "minimum": 0, "maximum": CONFIDENCE_SCALE },
+                { "type": "number", "minimum": 0.0, "maximum": 1.0 },
+                { "type": "string" }
+            ]
+        })
+    }
+}
+
+/// 证据 ID：Blake3(canonical_bytes)，只由证据内容决定，与盐值和入库位置无关
+///
+/// 客户端不必知道叶子位置即可引用证据 (`/evidence/by-id/{id}`)，服务端也以它为去重键：
+/// 同一份证据再次提交会被判定为重放。无盐叶子的 ID 就是叶子哈希。
+pub fn evidence_id_of(canonical_bytes: &[u8]) -> [u8; 32] {
+    *blake3::hash(canonical_bytes).as_bytes()
+}
+
+/// 叶子哈希：无盐时为 Blake3(canonical_bytes)，加盐时为 Blake3(salt || canonical_bytes)
+///
+/// 盐值与证据原文分开保存。销毁盐值后，即使有人持有原文也无法再与该叶子对应 (密码学擦除)，
+/// 而叶子哈希本身、MMR 结构与已签名的根都不受影响。
+pub fn leaf_hash_of(canonical_bytes: &[u8], salt: Option<&[u8; 32]>) -> [u8; 32] {
+    match salt {
+        Some(salt) => {
+            let mut hasher = blake3::Hasher::new();
+            hasher.update(salt);
+            hasher.update(canonical_bytes);
+            *hasher.finalize().as_bytes()
+        }
+        None => *blake3::hash(canonical_bytes).as_bytes(),
+    }
+}
+
+/// 规范编码 (Canonical Encoding)
+///
+/// 叶子哈希与签名所基于的字节格式。默认 BCS；gRPC 客户端与非 Rust 验证方可选 Protobuf
+/// (`proto/yuanjing.proto`，确定性编码见 `proto.rs`)。
+/// 编码在租户创建时确定 (`CANONICAL_ENCODING`)，之后不可更改，并写入每张回执。
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum CanonicalEncoding {
+    #[default]
+    Bcs,
+    Protobuf,
+}
+
+impl CanonicalEncoding {
+    /// 支持的全部规范编码
+    pub const ALL: [CanonicalEncoding; 2] = [Self::Bcs, Self::Protobuf];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Bcs => "bcs",
+            Self::Protobuf => "protobuf",
+        }
+    }
+
+    pub fn tag(self) -> u8 {
+        match self {
+            Self::Bcs => 0,
+            Self::Protobuf => 1,
+        }
+    }
+
+    pub fn from_tag(tag: u8) -> anyhow::Result<Self> {
+        match tag {
+            0 => Ok(Self::Bcs),
+            1 => Ok(Self::Protobuf),
+            other => Err(anyhow::anyhow!("Unknown canonical encoding tag {}", other)),
+        }
+    }
+}
+
+impl std::str::FromStr for CanonicalEncoding {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> anyhow::Result<Self> {
+        match s.to_ascii_lowercase().as_str() {
+            "bcs" => Ok(Self::Bcs),
+            "protobuf" => Ok(Self::Protobuf),
+            other => Err(anyhow::anyhow!("Unknown canonical encoding '{}', expected 'bcs' or 'protobuf'", other)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn base() -> Evidence {
+        Evidence {
+            image_phash: "jPDw8PDw8PA=".to_string(),
+            image_sha256: hex::encode([0x5A; 32]),
+            verdict: true,
+            confidence: Confidence::BasisPoints(9100),
+            activated_prompts: vec![1, 4],
+            prompt_pool_hash: "model".to_string(),
+            external_knowledge_hash: hex::encode([0x11; 32]),
+            timestamp: 1_767_225_600,
+            claimed_at: None,
+            consensus: None,
+            calibration: None,
+            provenance: None,
+            client_context: None,
+        }
+    }
+
+    /// 按可选字段的位掩码填充证据
+    fn with_optional(mask: u8) -> Evidence {
+        let mut evidence = base();
+        if mask & 1 != 0 {
+            evidence.claimed_at = Some(1_767_222_000);
+        }
+        if mask & 2 != 0 {
+            let model = ModelVerdict { prompt_pool_hash: "model".to_string(), verdict: true, confidence: Confidence::BasisPoints(9100) };
+            evidence.consensus = Some(Consensus { rule: ConsensusRule::Unanimous, models: vec![model] });
+        }
+        if mask & 4 != 0 {
+            evidence.calibration = Some(Calibration { version: "t1".to_string(), temperature_milli: 1100, threshold_bps: 5000 });
+        }
+        if mask & 8 != 0 {
+            evidence.provenance = Some(Provenance { mode: ProvenanceMode::Plain, ip: Some("192.0.2.1".to_string()), client_cert_subject: None, user_agent: None });
+        }
+        if mask & 16 != 0 {
+            evidence.client_context = Some(ClientContext(BTreeMap::from([("ticket".to_string(), "T-1".to_string())])));
+        }
+        evidence
+    }
+
+    #[test]
+    fn every_optional_field_combination_round_trips() {
+        let base_len = base().canonical_bytes().unwrap().len();
+        for mask in 0..32u8 {
+            let evidence = with_optional(mask);
+            let bytes = evidence.canonical_bytes().unwrap();
+            let decoded = Evidence::from_canonical_bytes(CanonicalEncoding::Bcs, &bytes).unwrap();
+            assert_eq!(decoded.canonical_bytes().unwrap(), bytes, "mask {:05b}", mask);
+            assert_eq!(serde_json::to_value(&decoded).unwrap(), serde_json::to_value(&evidence).unwrap());
+
+            // 写到最后一个 Some 为止，其前每个 None 占一个 0x00
+            let tail = if mask == 0 { 0 } else { 8 - mask.leading_zeros() as usize };
+            assert_eq!(evidence.optional_fields(), tail);
+            if mask == 0 {
+                assert_eq!(bytes.len(), base_len);
+            }
+        }
+    }
+
+    #[test]
+    fn explicit_trailing_none_is_not_canonical() {
+        for mask in [0u8, 1, 5] {
+            let mut bytes = with_optional(mask).canonical_bytes().unwrap();
+            bytes.push(0x00);
+            assert!(Evidence::from_canonical_bytes(CanonicalEncoding::Bcs, &bytes).is_err(), "mask {:05b}", mask);
+        }
+        let mut truncated = with_optional(3).canonical_bytes().unwrap();
+        truncated.pop();
+        assert!(Evidence::from_canonical_bytes(CanonicalEncoding::Bcs, &truncated).is_err());
+    }
+
+    #[test]
+    fn legacy_string_confidence_round_trips() {
+        let legacy = Evidence { confidence: Confidence::Legacy("0.93".to_string()), ..base() };
+        let bytes = legacy.canonical_bytes().unwrap();
+        let decoded = Evidence::from_canonical_bytes(CanonicalEncoding::Bcs, &bytes).unwrap();
+        assert_eq!(decoded.confidence, Confidence::Legacy("0.93".to_string()));
+        assert_eq!(decoded.canonical_bytes().unwrap(), bytes);
+    }
+}
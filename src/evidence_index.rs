@@ -0,0 +1,358 @@
+use serde::{Deserialize, Serialize};
+use sled::transaction::{ConflictableTransactionError, TransactionalTree};
+
+use crate::codec::StorageCodec;
+use crate::evidence::{CanonicalEncoding, Evidence};
+use crate::mmr_store::SledStore;
+
+/// 排序字段
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum SortField {
+    #[default]
+    Pos,
+    Timestamp,
+    Confidence,
+}
+
+impl SortField {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Pos => "pos",
+            Self::Timestamp => "timestamp",
+            Self::Confidence => "confidence",
+        }
+    }
+
+    /// 索引键里的类别字节
+    fn kind(self) -> u8 {
+        match self {
+            Self::Pos => b'p',
+            Self::Timestamp => b't',
+            Self::Confidence => b'c',
+        }
+    }
+
+    /// 键尾 (排序值 || 位置) 的长度，即翻页游标的长度
+    pub fn suffix_len(self) -> usize {
+        match self {
+            Self::Pos => 8,
+            Self::Timestamp => 16,
+            Self::Confidence => 18,
+        }
+    }
+}
+
+/// 排序：主字段相同时依次按回执时间、位置 (置信度 → 回执时间 → 位置)，整体同一方向
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SortOrder {
+    pub field: SortField,
+    pub descending: bool,
+}
+
+impl std::str::FromStr for SortOrder {
+    type Err = anyhow::Error;
+
+    /// 逗号分隔的字段，前缀 `-` 为降序，如 `-confidence,-timestamp`；
+    /// 只能是 `confidence,timestamp,pos` 从某个字段开始的连续一段，且方向一致
+    fn from_str(s: &str) -> anyhow::Result<Self> {
+        const CHAIN: [SortField; 3] = [SortField::Confidence, SortField::Timestamp, SortField::Pos];
+        let mut fields = Vec::new();
+        let mut directions = Vec::new();
+        for part in s.split(',').map(str::trim) {
+            let (descending, name) = match part.strip_prefix('-') {
+                Some(name) => (true, name),
+                None => (false, part),
+            };
+            let field = CHAIN
+                .into_iter()
+                .find(|field| field.as_str() == name)
+                .ok_or_else(|| anyhow::anyhow!("Unknown sort field '{}', expected 'pos', 'timestamp' or 'confidence'", name))?;
+            fields.push(field);
+            directions.push(descending);
+        }
+        let start = CHAIN.iter().position(|field| *field == fields[0]).unwrap_or_default();
+        if CHAIN.get(start..start + fields.len()) != Some(fields.as_slice()) {
+            return Err(anyhow::anyhow!(
+                "Unsupported sort '{}': ties are broken by confidence, then timestamp, then pos, so later fields must follow that order",
+                s
+            ));
+        }
+        if directions.iter().any(|descending| *descending != directions[0]) {
+            return Err(anyhow::anyhow!("Unsupported sort '{}': all fields must share one direction", s));
+        }
+        Ok(Self { field: fields[0], descending: directions[0] })
+    }
+}
+
+/// 证据的可筛选属性 (入库时由原文得出，保留期清理后仍在，擦除时删除)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EvidenceAttrs {
+    /// 回执时间 (Unix 秒)
+    pub timestamp: i64,
+    pub verdict: bool,
+    /// 置信度 (万分比)；历史证据的字符串无法解析时为 None
+    pub confidence: Option<u16>,
+    /// 模型版本 (Prompt 池哈希)
+    pub model: String,
+}
+
+impl EvidenceAttrs {
+    pub fn of(evidence: &Evidence, timestamp: i64) -> Self {
+        Self {
+            timestamp,
+            verdict: evidence.verdict,
+            confidence: evidence.confidence.basis_points(),
+            model: evidence.prompt_pool_hash.clone(),
+        }
+    }
+
+    /// 由规范字节解析 (签名入库的叶子)
+    pub fn decode(encoding: CanonicalEncoding, bytes: &[u8], timestamp: i64) -> anyhow::Result<Self> {
+        Ok(Self::of(&Evidence::from_canonical_bytes(encoding, bytes)?, timestamp))
+    }
+}
+
+/// 筛选条件；`verdict` 与 `model` 由索引前缀解决，置信度区间在按置信度排序时由键范围解决
+#[derive(Debug, Clone, Default)]
+pub struct EvidenceFilter {
+    pub verdict: Option<bool>,
+    pub model: Option<String>,
+    /// 置信度下限 / 上限 (万分比，含)
+    pub min_confidence: Option<u16>,
+    pub max_confidence: Option<u16>,
+}
+
+impl EvidenceFilter {
+    /// 是否限定了判定或模型
+    pub fn is_scoped(&self) -> bool {
+        self.verdict.is_some() || self.model.is_some()
+    }
+
+    /// 是否限定了置信度区间
+    pub fn has_confidence_range(&self) -> bool {
+        self.min_confidence.is_some() || self.max_confidence.is_some()
+    }
+
+    /// 属性是否满足全部条件 (索引之外的逐条核对)
+    pub fn matches(&self, attrs: &EvidenceAttrs) -> bool {
+        let in_range = match attrs.confidence {
+            Some(bps) => self.min_confidence.is_none_or(|min| bps >= min) && self.max_confidence.is_none_or(|max| bps <= max),
+            None => !self.has_confidence_range(),
+        };
+        in_range && self.verdict.is_none_or(|verdict| verdict == attrs.verdict) && self.model.as_ref().is_none_or(|model| *model == attrs.model)
+    }
+}
+
+/// 一页扫描结果
+#[derive(Debug, Clone, Default)]
+pub struct IndexPage {
+    pub positions: Vec<u64>,
+    /// 下一页的游标 (下一条的键尾)；没有更多时为 None
+    pub next: Option<Vec<u8>>,
+}
+
+/// 索引键的作用域：`标志位 || [模型长度 (u32) || 模型] || [判定]`
+fn scope(model: Option<&str>, verdict: Option<bool>) -> Vec<u8> {
+    let mut key = vec![u8::from(model.is_some()) | (u8::from(verdict.is_some()) << 1)];
+    if let Some(model) = model {
+        key.extend_from_slice(&(model.len() as u32).to_be_bytes());
+        key.extend_from_slice(model.as_bytes());
+    }
+    if let Some(verdict) = verdict {
+        key.push(u8::from(verdict));
+    }
+    key
+}
+
+/// 有符号时间戳按字节序排序的编码
+fn timestamp_bytes(timestamp: i64) -> [u8; 8] {
+    ((timestamp as u64) ^ (1 << 63)).to_be_bytes()
+}
+
+/// 某个作用域下某种排序的键
+fn order_key(scope: &[u8], field: SortField, pos: u64, timestamp: i64, confidence: u16) -> Vec<u8> {
+    let mut key = Vec::with_capacity(scope.len() + 1 + field.suffix_len());
+    key.extend_from_slice(scope);
+    key.push(field.kind());
+    if field == SortField::Confidence {
+        key.extend_from_slice(&confidence.to_be_bytes());
+    }
+    if field != SortField::Pos {
+        key.extend_from_slice(&timestamp_bytes(timestamp));
+    }
+    key.extend_from_slice(&pos.to_be_bytes());
+    key
+}
+
+/// 一条证据的全部索引键
+///
+/// 不限定的作用域按位置排序就是叶子本身的顺序，不另建键；没有原文 (盲化、复制来的已清理叶子) 时只有按回执时间的键。
+fn order_keys(pos: u64, timestamp: i64, attrs: Option<&EvidenceAttrs>) -> Vec<Vec<u8>> {
+    let global = scope(None, None);
+    let mut keys = vec![order_key(&global, SortField::Timestamp, pos, timestamp, 0)];
+    let Some(attrs) = attrs else {
+        return keys;
+    };
+    let scopes = [
+        global,
+        scope(None, Some(attrs.verdict)),
+        scope(Some(&attrs.model), None),
+        scope(Some(&attrs.model), Some(attrs.verdict)),
+    ];
+    for (i, scope) in scopes.iter().enumerate() {
+        if i > 0 {
+            keys.push(order_key(scope, SortField::Pos, pos, timestamp, 0));
+            keys.push(order_key(scope, SortField::Timestamp, pos, timestamp, 0));
+        }
+        if let Some(confidence) = attrs.confidence {
+            keys.push(order_key(scope, SortField::Confidence, pos, timestamp, confidence));
+        }
+    }
+    keys
+}
+
+/// 一条签名入库的叶子的索引 (在事务外解析、编码好)
+pub struct IndexEntry {
+    pos: u64,
+    attrs: Option<Vec<u8>>,
+    keys: Vec<Vec<u8>>,
+}
+
+impl IndexEntry {
+    /// `attrs` 为 None 表示没有原文 (盲化、复制来的已清理叶子)
+    pub fn new(pos: u64, timestamp: i64, attrs: Option<&EvidenceAttrs>) -> anyhow::Result<Self> {
+        Ok(Self { pos, attrs: attrs.map(StorageCodec::encode).transpose()?, keys: order_keys(pos, timestamp, attrs) })
+    }
+
+    /// 在追加事务中写入
+    pub fn insert_in_transaction(
+        &self,
+        attrs_tree: &TransactionalTree,
+        order_tree: &TransactionalTree,
+    ) -> Result<(), ConflictableTransactionError<()>> {
+        if let Some(attrs) = &self.attrs {
+            attrs_tree.insert(&self.pos.to_be_bytes(), attrs.as_slice())?;
+        }
+        for key in &self.keys {
+            order_tree.insert(key.as_slice(), &[] as &[u8])?;
+        }
+        Ok(())
+    }
+}
+
+/// 在擦除事务中删除由原文得出的索引 (按回执时间的键不含原文信息，保留)
+pub fn remove_in_transaction(
+    attrs_tree: &TransactionalTree,
+    order_tree: &TransactionalTree,
+    pos: u64,
+    attrs: Option<&EvidenceAttrs>,
+) -> Result<(), ConflictableTransactionError<()>> {
+    let Some(attrs) = attrs else {
+        return Ok(());
+    };
+    attrs_tree.remove(&pos.to_be_bytes())?;
+    let kept = order_keys(pos, attrs.timestamp, None);
+    for key in order_keys(pos, attrs.timestamp, Some(attrs)).into_iter().filter(|key| !kept.contains(key)) {
+        order_tree.remove(key)?;
+    }
+    Ok(())
+}
+
+/// 模块：证据二级索引 (Evidence Secondary Index)
+///
+/// **为什么需要**: 证据列表要按置信度、回执时间排序，并按判定、模型、置信度区间组合筛选。
+/// 逐条读原文再在内存中排序，耗时与证据总量成正比，翻页时每一页都要重做一遍。
+/// 这里在追加事务中顺带写两棵树：`evidence_attrs` (位置 → 判定、置信度、模型、回执时间)，
+/// `evidence_order` (有序键：`作用域 || 排序类别 || 排序值 || 位置`)。
+/// 判定与模型的每种组合各是一个作用域，每个作用域下各有按位置、回执时间、置信度排序的键，
+/// 一次列表请求只是在一个前缀内按键范围顺序 (或逆序) 扫描，置信度区间在按置信度排序时就是键范围。
+///
+/// 标签等其余条件逐条核对。每条证据约 12 个键，只有键没有值。保留期清理不动索引；擦除时删掉由原文得出的键。
+#[derive(Clone)]
+pub struct EvidenceIndex {
+    attrs: sled::Tree,
+    order: sled::Tree,
+}
+
+impl EvidenceIndex {
+    pub fn open(store: &SledStore) -> anyhow::Result<Self> {
+        Ok(Self { attrs: store.tree("evidence_attrs")?, order: store.tree("evidence_order")? })
+    }
+
+    /// 指定位置的属性；没有原文可解析的叶子为 None
+    pub fn get(&self, pos: u64) -> anyhow::Result<Option<EvidenceAttrs>> {
+        self.attrs.get(pos.to_be_bytes())?.map(|v| StorageCodec::decode(&v)).transpose()
+    }
+
+    /// 为升级前入库的叶子补建索引
+    pub fn insert(&self, entry: &IndexEntry) -> anyhow::Result<()> {
+        if let Some(attrs) = &entry.attrs {
+            self.attrs.insert(entry.pos.to_be_bytes(), attrs.as_slice())?;
+        }
+        for key in &entry.keys {
+            self.order.insert(key.as_slice(), &[] as &[u8])?;
+        }
+        Ok(())
+    }
+
+    /// 按排序与筛选扫描一页
+    ///
+    /// `cursor` 为上一页返回的游标 (含)；`accept` 核对索引之外的条件。
+    /// 不限定判定与模型、按位置排序时没有对应的键，由调用方直接按叶子顺序列出。
+    pub fn scan(
+        &self,
+        sort: SortOrder,
+        filter: &EvidenceFilter,
+        cursor: Option<&[u8]>,
+        limit: usize,
+        mut accept: impl FnMut(u64) -> anyhow::Result<bool>,
+    ) -> anyhow::Result<IndexPage> {
+        if cursor.is_some_and(|cursor| cursor.len() != sort.field.suffix_len()) {
+            return Err(anyhow::anyhow!("Cursor does not match sort '{}'", sort.field.as_str()));
+        }
+        let mut prefix = scope(filter.model.as_deref(), filter.verdict);
+        prefix.push(sort.field.kind());
+
+        let mut lower = prefix.clone();
+        let mut upper = prefix.clone();
+        if sort.field == SortField::Confidence {
+            lower.extend_from_slice(&filter.min_confidence.unwrap_or(0).to_be_bytes());
+            if let Some(max) = filter.max_confidence {
+                upper.extend_from_slice(&max.saturating_add(1).to_be_bytes());
+            }
+        }
+        if upper == prefix {
+            // 前缀以类别字节结尾，类别字节加一即为前缀之后的第一个键
+            *upper.last_mut().expect("non-empty prefix") += 1;
+        }
+        if let Some(cursor) = cursor {
+            let mut at = prefix.clone();
+            at.extend_from_slice(cursor);
+            if sort.descending {
+                at.push(0);
+                upper = upper.min(at);
+            } else {
+                lower = lower.max(at);
+            }
+        }
+
+        let range = self.order.range(lower..upper);
+        let entries: Box<dyn Iterator<Item = sled::Result<(sled::IVec, sled::IVec)>>> =
+            if sort.descending { Box::new(range.rev()) } else { Box::new(range) };
+        let mut page = IndexPage::default();
+        for entry in entries {
+            let (key, _) = entry?;
+            let suffix = &key[prefix.len()..];
+            let pos = u64::from_be_bytes(suffix[suffix.len() - 8..].try_into()?);
+            if !accept(pos)? {
+                continue;
+            }
+            if page.positions.len() == limit {
+                page.next = Some(suffix.to_vec());
+                break;
+            }
+            page.positions.push(pos);
+        }
+        Ok(page)
+    }
+}
@@ -0,0 +1,305 @@
+use log::{error, info, warn};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use crate::replication::PeerCatchUp;
+
+/// 写入围栏 (Write Fence)
+///
+/// 同一进程内所有租户的写线程共享一个围栏：围栏关闭时拒绝追加叶子，打开时拒绝应用复制来的叶子。
+/// 未启用租约的主库永久打开，只读副本永久关闭；启用租约时只在持有租约期间打开，截止时刻由续约推进。
+#[derive(Debug, Clone)]
+pub struct WriteFence {
+    base: Instant,
+    /// 围栏打开的截止时刻 (相对 `base` 的毫秒数)
+    deadline_ms: Arc<AtomicU64>,
+    /// 持有的租约代号，0 表示未持有
+    epoch: Arc<AtomicU64>,
+}
+
+impl WriteFence {
+    pub fn open() -> Self {
+        Self::with_deadline(u64::MAX)
+    }
+
+    pub fn closed() -> Self {
+        Self::with_deadline(0)
+    }
+
+    fn with_deadline(deadline_ms: u64) -> Self {
+        Self {
+            base: Instant::now(),
+            deadline_ms: Arc::new(AtomicU64::new(deadline_ms)),
+            epoch: Arc::new(AtomicU64::new(0)),
+        }
+    }
+
+    pub fn is_open(&self) -> bool {
+        (self.base.elapsed().as_millis() as u64) < self.deadline_ms.load(Ordering::Acquire)
+    }
+
+    /// 当前持有的租约代号 (未启用租约、未持有或已过期时为 None)
+    pub fn epoch(&self) -> Option<u64> {
+        Some(self.epoch.load(Ordering::Acquire)).filter(|epoch| *epoch > 0 && self.is_open())
+    }
+
+    /// 写路径在追加前调用
+    pub fn check(&self) -> anyhow::Result<()> {
+        if self.is_open() {
+            Ok(())
+        } else {
+            Err(anyhow::anyhow!("Write fence is closed: this node does not hold the writer lease"))
+        }
+    }
+
+    fn extend(&self, epoch: u64, until: Instant) {
+        self.epoch.store(epoch, Ordering::Release);
+        let deadline = until.saturating_duration_since(self.base).as_millis() as u64;
+        self.deadline_ms.store(deadline, Ordering::Release);
+    }
+
+    fn close(&self) {
+        self.deadline_ms.store(0, Ordering::Release);
+    }
+}
+
+impl Default for WriteFence {
+    fn default() -> Self {
+        Self::open()
+    }
+}
+
+/// 一代租约 (租约目录下的 `epoch-<代号>` 文件)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LeaseRecord {
+    /// 租约代号，每次易主加一，即围栏令牌
+    pub epoch: u64,
+    /// 持有者的节点 ID
+    pub holder: String,
+    /// 到期时间 (Unix 毫秒，持有者的时钟)
+    pub expires_at_ms: i64,
+}
+
+/// 抢占租约的结果
+#[derive(Debug)]
+pub enum Acquisition {
+    Acquired(LeaseRecord),
+    /// 租约仍由其他节点持有 (或同一代被别人抢先)
+    HeldBy(LeaseRecord),
+}
+
+/// 模块：写租约 (Writer Lease)
+///
+/// **为什么需要**: 热备节点要在主库故障后接管存证，但 MMR 只能有一个写者：
+/// 若旧主库只是卡顿、网络隔离而非宕机，两个节点同时追加就会把同一棵树分叉成两条签名历史。
+///
+/// 租约放在主备都能访问的共享目录 (如 NFS) 中，每一代一个文件 `epoch-<代号>`：
+/// - 抢占：先写临时文件再硬链接为下一代的文件名，硬链接在目标已存在时失败，同一代只会有一个赢家；
+/// - 续约：持有者覆盖写自己那一代的到期时间，之后复查没有更新的一代；
+/// - 接管：最新一代的到期时间加上时钟偏差上限 (`policy.clock_max_skew_ms`) 之后，其他节点才可抢占下一代。
+///
+/// 持有者的写入围栏只开到 "续约开始时刻 + 租期 - 时钟偏差上限"，续约晚到时不再重新打开，
+/// 因此只要节点间时钟偏差不超过上限，接管者打开围栏之前旧持有者的围栏必然已经关闭。
+/// 历代文件保留在目录中，作为易主记录。
+pub struct LeaseDir {
+    dir: PathBuf,
+    node_id: String,
+    ttl: Duration,
+    max_skew: Duration,
+}
+
+impl LeaseDir {
+    pub fn open(dir: &str, node_id: &str, ttl: Duration, max_skew: Duration) -> anyhow::Result<Self> {
+        fs::create_dir_all(dir).map_err(|e| anyhow::anyhow!("Failed to create lease directory '{}': {}", dir, e))?;
+        Ok(Self {
+            dir: PathBuf::from(dir),
+            node_id: node_id.to_string(),
+            ttl,
+            max_skew,
+        })
+    }
+
+    pub fn node_id(&self) -> &str {
+        &self.node_id
+    }
+
+    /// 最新一代租约
+    pub fn current(&self) -> anyhow::Result<Option<LeaseRecord>> {
+        let mut latest = None;
+        for entry in fs::read_dir(&self.dir)? {
+            let name = entry?.file_name();
+            let epoch = name.to_str().and_then(|name| name.strip_prefix("epoch-")).and_then(|epoch| epoch.parse::<u64>().ok());
+            if let Some(epoch) = epoch {
+                latest = latest.max(Some(epoch));
+            }
+        }
+        latest.map(|epoch| self.read(epoch)).transpose()
+    }
+
+    /// 抢占下一代租约；当前一代未过期 (含时钟偏差余量) 时返回其持有者
+    pub fn try_acquire(&self) -> anyhow::Result<Acquisition> {
+        let now = chrono::Utc::now().timestamp_millis();
+        let current = self.current()?;
+        if let Some(current) = current.as_ref().filter(|current| now < current.expires_at_ms + self.max_skew.as_millis() as i64) {
+            return Ok(Acquisition::HeldBy(current.clone()));
+        }
+
+        let record = LeaseRecord {
+            epoch: current.map_or(1, |current| current.epoch + 1),
+            holder: self.node_id.clone(),
+            expires_at_ms: now + self.ttl.as_millis() as i64,
+        };
+        let staged = self.staging_path(record.epoch);
+        fs::write(&staged, serde_json::to_vec(&record)?)?;
+        let linked = fs::hard_link(&staged, self.path(record.epoch));
+        let _ = fs::remove_file(&staged);
+        match linked {
+            Ok(()) => Ok(Acquisition::Acquired(record)),
+            Err(e) if e.kind() == std::io::ErrorKind::AlreadyExists => Ok(Acquisition::HeldBy(self.read(record.epoch)?)),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// 续约第 `epoch` 代；已出现更新的一代时返回 false
+    pub fn renew(&self, epoch: u64) -> anyhow::Result<bool> {
+        if self.current()?.map(|current| current.epoch) != Some(epoch) {
+            return Ok(false);
+        }
+        let record = LeaseRecord {
+            epoch,
+            holder: self.node_id.clone(),
+            expires_at_ms: chrono::Utc::now().timestamp_millis() + self.ttl.as_millis() as i64,
+        };
+        let staged = self.staging_path(epoch);
+        fs::write(&staged, serde_json::to_vec(&record)?)?;
+        fs::rename(&staged, self.path(epoch))?;
+        // 复查：抢占者可能在上面的读取之后创建了下一代
+        Ok(self.current()?.map(|current| current.epoch) == Some(epoch))
+    }
+
+    /// 围栏的开放时长 (自抢占或续约开始时起算)
+    pub fn fence_window(&self) -> Duration {
+        self.ttl.saturating_sub(self.max_skew)
+    }
+
+    fn read(&self, epoch: u64) -> anyhow::Result<LeaseRecord> {
+        let path = self.path(epoch);
+        let bytes = fs::read(&path).map_err(|e| anyhow::anyhow!("Failed to read lease '{}': {}", path.display(), e))?;
+        serde_json::from_slice(&bytes).map_err(|e| anyhow::anyhow!("Invalid lease '{}': {}", path.display(), e))
+    }
+
+    fn path(&self, epoch: u64) -> PathBuf {
+        self.dir.join(format!("epoch-{:020}", epoch))
+    }
+
+    fn staging_path(&self, epoch: u64) -> PathBuf {
+        self.dir.join(format!(".epoch-{}.{}.tmp", epoch, self.node_id))
+    }
+}
+
+/// 主备切换 (Hot-Standby Failover)
+///
+/// 主库启动时通过 [`Failover::acquire`] 取得租约并打开围栏，之后由后台任务周期续约；
+/// 热备节点以只读副本身份复制主库，后台任务在主库租约过期后抢占下一代，打开围栏并接管写入 (复制随之停止)。
+/// 失去租约的节点关闭围栏并保持只读，需由运维确认后以副本身份重启。
+/// 配置了其他副本 (`catch_up`) 时，热备抢到租约后先从它们补齐缺少的叶子，再打开围栏；
+/// 开启多数派确认时，追上的副本不足以覆盖多数派就放弃这一代租约，不接管写入。
+pub struct Failover {
+    pub lease: LeaseDir,
+    pub fence: WriteFence,
+    pub renew_interval: Duration,
+    pub catch_up: Option<PeerCatchUp>,
+}
+
+impl Failover {
+    /// 主库启动时取得租约；上一代由本节点持有时等它过期，由其他节点持有时报错
+    pub async fn acquire(self: &Arc<Self>) -> anyhow::Result<u64> {
+        loop {
+            let started = Instant::now();
+            let this = self.clone();
+            match tokio::task::spawn_blocking(move || this.lease.try_acquire()).await?? {
+                Acquisition::Acquired(record) => {
+                    self.fence.extend(record.epoch, started + self.lease.fence_window());
+                    return Ok(record.epoch);
+                }
+                Acquisition::HeldBy(record) if record.holder == self.lease.node_id() => {
+                    info!("⏳ 上一代写租约 (epoch {}) 仍由本节点持有，等待其过期", record.epoch);
+                    tokio::time::sleep(self.renew_interval).await;
+                }
+                Acquisition::HeldBy(record) => {
+                    return Err(anyhow::anyhow!(
+                        "Writer lease epoch {} is held by '{}'; start this node as a standby (`--replica`) instead",
+                        record.epoch,
+                        record.holder
+                    ));
+                }
+            }
+        }
+    }
+
+    /// 启动后台租约任务：持有租约 (`epoch`) 时续约，热备 (None) 时等待接管
+    pub fn spawn(self: Arc<Self>, mut epoch: Option<u64>) {
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(self.renew_interval);
+            loop {
+                ticker.tick().await;
+                let started = Instant::now();
+                let this = self.clone();
+                match epoch {
+                    Some(held) => {
+                        let renewed = tokio::task::spawn_blocking(move || this.lease.renew(held))
+                            .await
+                            .map_err(anyhow::Error::from)
+                            .and_then(|renewed| renewed);
+                        match renewed {
+                            // 续约晚于围栏截止时刻时不再重新打开：接管者可能已据旧的到期时间抢占
+                            Ok(true) if self.fence.is_open() => self.fence.extend(held, started + self.lease.fence_window()),
+                            Ok(true) => {
+                                error!("🚨 写租约 (epoch {}) 续约过晚，围栏已关闭，本节点停止写入", held);
+                                self.fence.close();
+                                return;
+                            }
+                            Ok(false) => {
+                                error!("🚨 写租约 (epoch {}) 已被其他节点接管，本节点停止写入", held);
+                                self.fence.close();
+                                return;
+                            }
+                            Err(e) if self.fence.is_open() => warn!("⚠️  写租约续约失败 (围栏到期前重试): {}", e),
+                            Err(e) => {
+                                error!("🚨 写租约 (epoch {}) 续约失败且已过期，本节点停止写入: {}", held, e);
+                                self.fence.close();
+                                return;
+                            }
+                        }
+                    }
+                    None => match tokio::task::spawn_blocking(move || this.lease.try_acquire()).await {
+                        Ok(Ok(Acquisition::Acquired(record))) => {
+                            if let Some(catch_up) = &self.catch_up {
+                                // 追上的副本不足以覆盖多数派时不接管：可能缺少已确认的回执，放弃这一代，过期后重新抢占
+                                if let Err(e) = catch_up.run().await {
+                                    error!("🚨 接管前追赶未覆盖多数派，放弃写租约 epoch {}: {}", record.epoch, e);
+                                    continue;
+                                }
+                                // 只能在本代租约的围栏期限内打开；超时则放弃这一代，等它过期后重新抢占
+                                if started.elapsed() >= self.lease.fence_window() {
+                                    warn!("⚠️  接管前追赶超过围栏期限，放弃写租约 epoch {}", record.epoch);
+                                    continue;
+                                }
+                            }
+                            self.fence.extend(record.epoch, started + self.lease.fence_window());
+                            info!("🏁 主库写租约已过期，本节点接管写入 (epoch {})", record.epoch);
+                            epoch = Some(record.epoch);
+                        }
+                        Ok(Ok(Acquisition::HeldBy(_))) => {}
+                        Ok(Err(e)) => warn!("⚠️  读取写租约失败: {}", e),
+                        Err(e) => warn!("⚠️  读取写租约失败: {}", e),
+                    },
+                }
+            }
+        });
+    }
+}
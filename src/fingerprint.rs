@@ -1,26 +1,70 @@
+use img_hash::image::io::Reader; // 只读图片头部 (格式与尺寸) 的读取器
+use img_hash::image::ImageFormat;
 use img_hash::{HasherConfig, HashAlg}; // 引入 pHash 相关的配置器和算法枚举
 use sha2::{Sha256, Digest};            // 引入 SHA2 算法和 Digest 特性(方法集)
 use std::fs;                           // 文件系统操作
+use std::io::Cursor;
 use std::path::Path;                   // 路径处理
 
+/// 默认的图片文件大小上限 (字节)
+pub const DEFAULT_MAX_IMAGE_BYTES: u64 = 50 * 1024 * 1024;
+
+/// 默认的图片像素数上限 (约 4000 万像素，解码为 RGBA 约 160 MiB)
+pub const DEFAULT_MAX_IMAGE_PIXELS: u64 = 40_000_000;
+
+/// 图片摄入上限 (`[api] image_max_bytes` / `image_max_pixels`)
+///
+/// **为什么需要**: 提交的图片来自不受信任的一方。几 KB 的 PNG 可以在头部声明 10 万 × 10 万像素，
+/// 解码器照此分配缓冲区就是几十 GB (解压炸弹)，整个进程被 OOM 杀掉，而不只是这一个请求失败。
+/// 这里先只读头部拿到格式与尺寸，超限直接拒绝，通过了才真正解码。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ImageLimits {
+    pub max_bytes: u64,
+    pub max_pixels: u64,
+}
+
+impl Default for ImageLimits {
+    fn default() -> Self {
+        Self { max_bytes: DEFAULT_MAX_IMAGE_BYTES, max_pixels: DEFAULT_MAX_IMAGE_PIXELS }
+    }
+}
+
 // -> anyhow::Result<(String, String)>
 // 这是一个返回 Result 的函数。
 // 成功时：返回一个元组 (String, String)，分别对应 (SHA256, pHash)。
 // 失败时：利用 anyhow 库抛出错误（比如文件找不到）。
+// 按默认上限 (`ImageLimits::default()`) 处理，服务端按配置调用 `generate_fingerprints_with`。
 pub fn generate_fingerprints(path: &Path) -> anyhow::Result<(String, String)> {
+    generate_fingerprints_with(path, &ImageLimits::default())
+}
+
+/// 读取图片文件并计算指纹；文件超过 `max_bytes` 时不读入内存
+pub fn generate_fingerprints_with(path: &Path, limits: &ImageLimits) -> anyhow::Result<(String, String)> {
+    let size = fs::metadata(path)?.len();
+    if size > limits.max_bytes {
+        return Err(anyhow::anyhow!("Image is {} bytes, exceeding the {} byte limit", size, limits.max_bytes));
+    }
     // 1. fs::read(path)?
     // 作用：把整个文件读入内存，变成 Vec<u8> (字节数组)。
     // 语法细节 `?`: 如果读文件失败（文件不存在/无权限），直接在这里 return Err，不再往下走。
     let bytes = fs::read(path)?;
+    fingerprints_of(&bytes, limits)
+}
+
+/// 由图片字节计算 (SHA256, pHash)；只接受 JPEG / PNG，尺寸超限、格式不符或解码失败时报错，不会 panic
+pub fn fingerprints_of(bytes: &[u8], limits: &ImageLimits) -> anyhow::Result<(String, String)> {
+    if bytes.len() as u64 > limits.max_bytes {
+        return Err(anyhow::anyhow!("Image is {} bytes, exceeding the {} byte limit", bytes.len(), limits.max_bytes));
+    }
 
     // 2. Sha256::new()
     // 作用：创建一个哈希计算器的“状态机”实例。
     let mut hasher = Sha256::new();
     
-    // 3. hasher.update(&bytes)
+    // 3. hasher.update(bytes)
     // 作用：像喂碎纸机一样，把数据喂给哈希器。
-    // 语法细节 `&`: 传入数据的引用，不发生所有权转移（虽然这里bytes之后也没用了）。
-    hasher.update(&bytes);
+    // 语法细节：bytes 本身已是借用的切片 (&[u8])，直接传入，不发生所有权转移。
+    hasher.update(bytes);
     
     // 4. hasher.finalize()
     // 作用：按下“结束”按钮，计算出最终的 32 字节哈希值 (GenericArray)。
@@ -28,25 +72,38 @@ pub fn generate_fingerprints(path: &Path) -> anyhow::Result<(String, String)> {
     // 作用：`{:x}` 是格式化占位符，表示将二进制数据转为 "小写十六进制字符串" (Lower Hex)。
     let sha_hash = format!("{:x}", hasher.finalize());
 
-    // 1. img_hash::image::open(path)?
+    // 1. 先看头部：按内容 (而不是扩展名) 识别格式，只读出宽高，不分配像素缓冲区
+    let format = Reader::new(Cursor::new(bytes)).with_guessed_format()?.format();
+    if !matches!(format, Some(ImageFormat::Jpeg | ImageFormat::Png)) {
+        return Err(anyhow::anyhow!("Unsupported image format {:?} (expected JPEG or PNG)", format));
+    }
+    let (width, height) = Reader::new(Cursor::new(bytes)).with_guessed_format()?.into_dimensions()?;
+    let pixels = u64::from(width) * u64::from(height);
+    if pixels == 0 || pixels > limits.max_pixels {
+        return Err(anyhow::anyhow!("Image is {}x{} pixels, outside the limit of {} pixels", width, height, limits.max_pixels));
+    }
+
+    // 2. decode()
     // 作用：这不是读字节，而是“解码图片”。
     // 它会解析 JPG/PNG 头部，把像素数据解压出来放到内存里的 ImageBuffer 中。
     // 如果文件是不是图片格式，这里会报错。
-    let img = img_hash::image::open(path)?;
+    // 第三方解码器在畸形输入上偶有 panic，这里兜住，只让这一次提交失败。
+    let img = std::panic::catch_unwind(|| Reader::new(Cursor::new(bytes)).with_guessed_format()?.decode())
+        .map_err(|_| anyhow::anyhow!("Image decoder failed on malformed input"))??;
 
-    // 2. HasherConfig::new()...to_hasher()
+    // 3. HasherConfig::new()...to_hasher()
     // 作用：配置我们要用什么样的算法算 pHash。
     let hasher = HasherConfig::new()
         .hash_alg(HashAlg::Gradient) // 选择“梯度算法”。比起均值算法(Mean)，梯度对明暗变化更鲁棒。
         .hash_size(8, 8)             // 输出 8x8 = 64位 的指纹。
         .to_hasher();                // 完成配置，构建 Hasher 对象。
     
-    // 3. hasher.hash_image(&img)
+    // 4. hasher.hash_image(&img)
     // 作用：执行核心算法。
     // 过程：缩小图片 -> 灰度化 -> 计算梯度 -> 生成哈希对象。
     let phash = hasher.hash_image(&img);
 
-    // 4. phash.to_base64()
+    // 5. phash.to_base64()
     // 作用：pHash 结果本质是一串二进制位 (010101...)。
     // 为了存得短一点，常用 Base64 编码转成字符串。
     Ok((sha_hash, phash.to_base64()))
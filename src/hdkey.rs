@@ -0,0 +1,117 @@
+use hmac::{Hmac, Mac};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use sha2::Sha512;
+use zeroize::{Zeroize, Zeroizing};
+
+/// 硬化索引起点 (SLIP-0010 中 Ed25519 只支持硬化派生)
+const HARDENED_OFFSET: u32 = 0x8000_0000;
+
+/// 背书签名的域分隔前缀，避免与证据 / 回执签名混用
+const ENDORSEMENT_DOMAIN: &[u8] = b"yuanjing-core key endorsement v1";
+
+/// 派生路径 (Derivation Path)，形如 `m/0'/3'`
+///
+/// 只接受硬化分量 (`'` 或 `h` 后缀)；`m` 表示租户根密钥本身。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DerivationPath {
+    indexes: Vec<u32>,
+}
+
+impl DerivationPath {
+    pub fn parse(path: &str) -> anyhow::Result<Self> {
+        let mut parts = path.trim().split('/');
+        if parts.next() != Some("m") {
+            return Err(anyhow::anyhow!("Invalid derivation path '{}': must start with 'm'", path));
+        }
+        let indexes = parts
+            .map(|part| {
+                let index = part
+                    .strip_suffix('\'')
+                    .or_else(|| part.strip_suffix('h'))
+                    .ok_or_else(|| anyhow::anyhow!("Invalid derivation path '{}': Ed25519 only supports hardened components like \"0'\"", path))?;
+                let index: u32 = index
+                    .parse()
+                    .map_err(|_| anyhow::anyhow!("Invalid derivation path '{}': bad index '{}'", path, part))?;
+                if index >= HARDENED_OFFSET {
+                    return Err(anyhow::anyhow!("Invalid derivation path '{}': index {} out of range", path, index));
+                }
+                Ok(index)
+            })
+            .collect::<anyhow::Result<Vec<_>>>()?;
+        Ok(Self { indexes })
+    }
+
+    /// 是否为根路径 `m`
+    pub fn is_root(&self) -> bool {
+        self.indexes.is_empty()
+    }
+}
+
+impl std::fmt::Display for DerivationPath {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "m")?;
+        for index in &self.indexes {
+            write!(f, "/{}'", index)?;
+        }
+        Ok(())
+    }
+}
+
+/// 模块：分层密钥派生 (SLIP-0010, Ed25519)
+///
+/// **职责**: 从一份租户根 Seed 派生出按部门 / 设备划分的签名密钥，
+/// 运维只需备份主密钥 (或助记词) 就能恢复全部子密钥。
+///
+/// $$ (k_0, c_0) = \text{HMAC-SHA512}(\texttt{"ed25519 seed"}, seed) $$
+/// $$ (k_{i+1}, c_{i+1}) = \text{HMAC-SHA512}(c_i, \texttt{0x00} \,||\, k_i \,||\, \text{ser}_{32}(i + 2^{31})) $$
+///
+/// **注意**: Ed25519 只能做硬化派生，验证方无法仅凭根公钥算出子公钥。
+/// 因此每把子密钥都由租户根密钥签发一份背书 ([`KeyEndorsement`])，
+/// 验证方用已公布的根公钥校验背书，即可信任其中的子公钥。
+///
+/// 中间的私钥与链码在每一步之后都会被擦除。
+pub fn derive_seed(seed: &[u8], path: &DerivationPath) -> Zeroizing<[u8; 32]> {
+    let (mut key, mut chain_code) = hmac_split(b"ed25519 seed", &[seed]);
+    for index in &path.indexes {
+        let hardened = (index | HARDENED_OFFSET).to_be_bytes();
+        (key, chain_code) = hmac_split(chain_code.as_slice(), &[&[0u8], key.as_slice(), &hardened]);
+    }
+    key
+}
+
+fn hmac_split(key: &[u8], parts: &[&[u8]]) -> (Zeroizing<[u8; 32]>, Zeroizing<[u8; 32]>) {
+    let mut mac = Hmac::<Sha512>::new_from_slice(key).expect("HMAC accepts keys of any length");
+    for part in parts {
+        mac.update(part);
+    }
+    let mut output = mac.finalize().into_bytes();
+    let mut left = Zeroizing::new([0u8; 32]);
+    let mut right = Zeroizing::new([0u8; 32]);
+    left.copy_from_slice(&output[..32]);
+    right.copy_from_slice(&output[32..]);
+    output.as_mut_slice().zeroize();
+    (left, right)
+}
+
+/// 子密钥背书 (Key Endorsement)
+///
+/// 租户根密钥对 `{tenant_id, key_path, public_key}` 的签名声明：
+/// “此公钥是我在该路径上派生的子密钥”。
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
+pub struct KeyEndorsement {
+    pub tenant_id: String,
+    /// 派生路径，与回执中的 `key_path` 一致
+    pub key_path: String,
+    /// 子公钥 (Hex)
+    pub public_key: String,
+}
+
+impl KeyEndorsement {
+    /// 背书的规范化字节 (域前缀 + BCS)，签名与验签均基于此
+    pub fn canonical_bytes(&self) -> anyhow::Result<Vec<u8>> {
+        let mut bytes = ENDORSEMENT_DOMAIN.to_vec();
+        bytes.extend_from_slice(&bcs::to_bytes(self)?);
+        Ok(bytes)
+    }
+}
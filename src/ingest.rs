@@ -0,0 +1,240 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use tokio::sync::{Notify, OwnedSemaphorePermit, Semaphore};
+
+use crate::telemetry::Handoff;
+
+/// 存证请求的优先级 (请求体的 `priority` 字段)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum Priority {
+    /// 有时限的提交 (如法院期限)，计算名额与写队列中都排在最前
+    Urgent,
+    #[default]
+    Normal,
+    /// 批量采集，默认最多占用一半计算名额
+    Bulk,
+}
+
+impl Priority {
+    /// 按调度顺序 (高 → 低)
+    pub const ALL: [Priority; 3] = [Self::Urgent, Self::Normal, Self::Bulk];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Urgent => "urgent",
+            Self::Normal => "normal",
+            Self::Bulk => "bulk",
+        }
+    }
+
+    /// 在 [`Priority::ALL`] 中的下标，数值越小越优先
+    pub fn lane(self) -> usize {
+        self as usize
+    }
+}
+
+/// 入场凭证：持有期间占用一个排队名额，drop 时自动归还
+pub struct IngestTicket {
+    _permit: OwnedSemaphorePermit,
+}
+
+/// 模块：有界入库管线 (Bounded Ingestion)
+///
+/// **职责**: 突发流量下保护服务本身。
+/// 之前每个存证请求都会无条件 `spawn_blocking` 计算指纹，请求一多，
+/// 阻塞线程池与内存都会被排队任务撑爆。现在分两级限流：
+/// - **入场**: 每个优先级同时在管线中的请求 (计算中 + 排队中) 不超过 `配额 + queue_depth`，
+///   满了直接拒绝 (`429 Too Many Requests`)，由客户端按 `Retry-After` 重试；
+/// - **计算**: 同时执行指纹计算的任务不超过 `concurrency`，其余在队列中等待。
+///
+/// **优先级通道**: 批量采集不能挡住有时限的提交。各优先级分别入场、分别计数，
+/// 计算名额空出时总是先给等待中的高优先级请求；每个优先级另有占用上限 (配额)，
+/// 默认批量请求最多占一半名额，大文件批量计算期间仍留有名额给紧急请求。
+///
+/// 写线程的通道同样有界，写队列满时返回 `503 Service Unavailable`。
+pub struct IngestGate {
+    lanes: [Lane; 3],
+    workers: Arc<WorkerPool>,
+    retry_after_secs: u64,
+}
+
+/// 一个优先级的入场名额与计数
+struct Lane {
+    admission: Arc<Semaphore>,
+    capacity: usize,
+    rejected: AtomicU64,
+}
+
+impl IngestGate {
+    /// `quotas` 为各优先级 (按 [`Priority::ALL`] 顺序) 可同时占用的计算名额，不超过 `concurrency`
+    pub fn new(concurrency: usize, quotas: [usize; 3], queue_depth: usize, retry_after_secs: u64) -> Self {
+        let concurrency = concurrency.max(1);
+        let quotas = quotas.map(|quota| quota.clamp(1, concurrency));
+        Self {
+            lanes: quotas.map(|quota| Lane {
+                admission: Arc::new(Semaphore::new(quota + queue_depth)),
+                capacity: quota + queue_depth,
+                rejected: AtomicU64::new(0),
+            }),
+            workers: Arc::new(WorkerPool {
+                state: Mutex::new(PoolState { available: concurrency, ..PoolState::default() }),
+                notify: Notify::new(),
+                quotas,
+            }),
+            retry_after_secs,
+        }
+    }
+
+    /// 尝试进入管线；已满时返回 None 并计数
+    pub fn try_admit(&self, priority: Priority) -> Option<IngestTicket> {
+        let lane = &self.lanes[priority.lane()];
+        match lane.admission.clone().try_acquire_owned() {
+            Ok(permit) => Some(IngestTicket { _permit: permit }),
+            Err(_) => {
+                lane.rejected.fetch_add(1, Ordering::Relaxed);
+                None
+            }
+        }
+    }
+
+    /// 等待进入管线 (后台任务使用：任务已在任务队列中限流，管线满时排队而不是拒绝)
+    pub async fn admit(&self, priority: Priority) -> anyhow::Result<IngestTicket> {
+        let permit = self.lanes[priority.lane()]
+            .admission
+            .clone()
+            .acquire_owned()
+            .await
+            .map_err(|e| anyhow::anyhow!("Ingest gate closed: {}", e))?;
+        Ok(IngestTicket { _permit: permit })
+    }
+
+    /// 在计算并发上限内执行阻塞任务 (如指纹计算)，等待计算名额与线程池调度的时间记为 `ingest.queue`
+    pub async fn run_blocking<T, F>(&self, priority: Priority, f: F) -> anyhow::Result<T>
+    where
+        F: FnOnce() -> anyhow::Result<T> + Send + 'static,
+        T: Send + 'static,
+    {
+        let handoff = Handoff::capture();
+        let _worker = self.workers.acquire(priority.lane()).await;
+        tokio::task::spawn_blocking(move || {
+            let _trace = handoff.resume("ingest.queue");
+            f()
+        })
+            .await
+            .map_err(|e| anyhow::anyhow!("Task join error: {}", e))?
+    }
+
+    /// 建议客户端重试的等待秒数
+    pub fn retry_after_secs(&self) -> u64 {
+        self.retry_after_secs
+    }
+
+    /// 该优先级当前在管线中的请求数
+    pub fn in_flight(&self, priority: Priority) -> usize {
+        let lane = &self.lanes[priority.lane()];
+        lane.capacity - lane.admission.available_permits()
+    }
+
+    pub fn capacity(&self, priority: Priority) -> usize {
+        self.lanes[priority.lane()].capacity
+    }
+
+    /// 该优先级可同时占用的计算名额
+    pub fn quota(&self, priority: Priority) -> usize {
+        self.workers.quotas[priority.lane()]
+    }
+
+    /// 该优先级正在等待计算名额的请求数
+    pub fn queued(&self, priority: Priority) -> usize {
+        self.workers.state.lock().unwrap().waiting[priority.lane()]
+    }
+
+    /// 该优先级正在计算的请求数
+    pub fn running(&self, priority: Priority) -> usize {
+        self.workers.state.lock().unwrap().running[priority.lane()]
+    }
+
+    /// 该优先级因管线已满被拒绝的请求总数
+    pub fn rejected_total(&self, priority: Priority) -> u64 {
+        self.lanes[priority.lane()].rejected.load(Ordering::Relaxed)
+    }
+}
+
+/// 计算名额：有空闲时先给等待中的高优先级请求，每个优先级的占用不超过配额
+struct WorkerPool {
+    state: Mutex<PoolState>,
+    notify: Notify,
+    quotas: [usize; 3],
+}
+
+#[derive(Default)]
+struct PoolState {
+    available: usize,
+    running: [usize; 3],
+    waiting: [usize; 3],
+}
+
+impl PoolState {
+    /// 更高优先级中还有能运行 (未达配额) 的等待者时，让它们先走
+    fn can_run(&self, lane: usize, quotas: &[usize; 3]) -> bool {
+        self.available > 0
+            && self.running[lane] < quotas[lane]
+            && (0..lane).all(|higher| self.waiting[higher] == 0 || self.running[higher] >= quotas[higher])
+    }
+}
+
+impl WorkerPool {
+    async fn acquire(self: &Arc<Self>, lane: usize) -> WorkerPermit {
+        self.state.lock().unwrap().waiting[lane] += 1;
+        // 请求被取消 (如客户端断开) 时也要撤销等待计数，否则低优先级会一直让路
+        let waiting = Waiting { pool: self, lane };
+        loop {
+            let notified = self.notify.notified();
+            tokio::pin!(notified);
+            notified.as_mut().enable();
+            {
+                let mut state = self.state.lock().unwrap();
+                if state.can_run(lane, &self.quotas) {
+                    state.available -= 1;
+                    state.running[lane] += 1;
+                    break;
+                }
+            }
+            notified.await;
+        }
+        drop(waiting);
+        WorkerPermit { pool: self.clone(), lane }
+    }
+}
+
+struct Waiting<'a> {
+    pool: &'a WorkerPool,
+    lane: usize,
+}
+
+impl Drop for Waiting<'_> {
+    fn drop(&mut self) {
+        self.pool.state.lock().unwrap().waiting[self.lane] -= 1;
+        self.pool.notify.notify_waiters();
+    }
+}
+
+/// 计算名额，drop 时归还并唤醒等待者
+struct WorkerPermit {
+    pool: Arc<WorkerPool>,
+    lane: usize,
+}
+
+impl Drop for WorkerPermit {
+    fn drop(&mut self) {
+        {
+            let mut state = self.pool.state.lock().unwrap();
+            state.available += 1;
+            state.running[self.lane] -= 1;
+        }
+        self.pool.notify.notify_waiters();
+    }
+}
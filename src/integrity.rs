@@ -0,0 +1,528 @@
+use ckb_merkle_mountain_range::helper::pos_height_in_tree;
+use ckb_merkle_mountain_range::leaf_index_to_pos;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+use crate::codec::StorageCodec;
+use crate::mmr_store::{leaf_count, SledStore, StoreSnapshot, StoredAttestation};
+use crate::position::is_valid_mmr_size;
+
+/// 每项问题在报告中最多列出的样本键数
+const SAMPLE_LIMIT: usize = 10;
+
+/// 问题的严重程度
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    /// 不影响已提交的树与已签发的回执 (无主条目、未完成的清理等)
+    Warning,
+    /// 已提交的数据缺失或与签名根不符，须人工处理
+    Error,
+}
+
+/// 修复建议
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RepairAction {
+    /// 能否由 [`repair`] 自动执行：只删除不属于任何已提交叶子的条目，不改动树与签名材料
+    pub safe: bool,
+    pub description: String,
+}
+
+/// 一项检查发现的问题
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Finding {
+    /// 检查项：`meta` | `nodes` | `seq_index` | `leaf_index` | `nonces` | `evidence` | `salts` |
+    /// `attestations` | `supersessions` | `signed_root` | `tree_head` | `wal`
+    pub check: String,
+    pub severity: Severity,
+    pub message: String,
+    /// 受影响的条目数
+    pub count: u64,
+    /// 部分受影响的键 (位置 / 序列号 / 树大小为十进制，其余为 Hex)
+    pub sample: Vec<String>,
+    pub repair: RepairAction,
+}
+
+/// 完整性检查报告 (Damage Report)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IntegrityReport {
+    pub tenant_id: String,
+    pub mmr_size: u64,
+    pub leaves: u64,
+    /// `mmr_size` 之内实际存在的节点数
+    pub nodes: u64,
+    pub findings: Vec<Finding>,
+}
+
+impl IntegrityReport {
+    /// 没有 `error` 级问题
+    pub fn is_healthy(&self) -> bool {
+        self.findings.iter().all(|f| f.severity != Severity::Error)
+    }
+
+    /// 可自动修复的条目数
+    pub fn safe_repairs(&self) -> u64 {
+        self.findings.iter().filter(|f| f.repair.safe).map(|f| f.count).sum()
+    }
+}
+
+/// 模块：数据库完整性检查 (Integrity Check)
+///
+/// **为什么需要**: 崩溃恢复只核对 WAL 与最近一次签名根，自审重放 (`self_audit.rs`) 只沿着叶子核对哈希与根。
+/// 二级索引 (序列号、内容哈希、nonce)、原文与盐值等 tree 各自独立存放，某一棵损坏或残留无主条目时，
+/// 读写路径要等到真正访问那一条才会出错。
+///
+/// 逐棵 tree 核对：MMR 节点数与 `mmr_size`、索引与原文 / 叶子节点、最近签名根与树头存档与重算的根。
+/// 每项问题附带修复建议；只删除无主条目的修复标记为 `safe`，由 [`repair`] 执行，其余须人工处理。
+pub fn check(store: &SledStore) -> anyhow::Result<IntegrityReport> {
+    Ok(Scan::run(store)?.report)
+}
+
+/// 执行全部 `safe` 修复，返回修复前的报告与删除的条目数
+///
+/// 须在没有写入的情况下执行 (停机，或在写线程中)。
+pub fn repair(store: &SledStore) -> anyhow::Result<(IntegrityReport, u64)> {
+    let scan = Scan::run(store)?;
+    let mut removed = 0;
+    for (name, keys) in &scan.removals {
+        let tree = if *name == "nodes" { store.nodes().clone() } else { store.tree(name)? };
+        let mut batch = sled::Batch::default();
+        for key in keys {
+            batch.remove(key.as_slice());
+        }
+        tree.apply_batch(batch)?;
+        removed += keys.len() as u64;
+    }
+    store.flush()?;
+    Ok((scan.report, removed))
+}
+
+/// 修复方式
+enum Fix {
+    /// 从指定 tree 删除受影响的键
+    Remove(&'static str),
+    /// 只能人工处理
+    Manual(&'static str),
+}
+
+struct Scan<'a> {
+    store: &'a SledStore,
+    mmr_size: u64,
+    report: IntegrityReport,
+    /// 可安全删除的条目：tree 名 -> 键
+    removals: BTreeMap<&'static str, Vec<Vec<u8>>>,
+}
+
+impl<'a> Scan<'a> {
+    fn run(store: &'a SledStore) -> anyhow::Result<Self> {
+        let mmr_size = store.get_meta_size();
+        let mut scan = Self {
+            store,
+            mmr_size,
+            report: IntegrityReport {
+                tenant_id: store.tenant_id().to_string(),
+                mmr_size,
+                leaves: leaf_count(mmr_size),
+                nodes: 0,
+                findings: Vec::new(),
+            },
+            removals: BTreeMap::new(),
+        };
+
+        // 大小本身不合法时其余检查都没有意义
+        if !is_valid_mmr_size(mmr_size) {
+            scan.record(
+                "meta",
+                Severity::Error,
+                format!("Persisted size {} is not a valid MMR size", mmr_size),
+                vec![mmr_size.to_be_bytes().to_vec()],
+                Fix::Manual("Restore the database from a backup"),
+            );
+            return Ok(scan);
+        }
+
+        let wal: Vec<_> = store.wal_pending()?.iter().map(|record| record.seq.to_be_bytes().to_vec()).collect();
+        scan.check_nodes(wal.is_empty())?;
+        let seqs = scan.check_seq_index()?;
+        scan.check_leaf_index(&seqs)?;
+        scan.check_nonces(&seqs)?;
+        let erased = scan.keys_of("erasures")?;
+        scan.check_evidence(&erased)?;
+        scan.check_salts(&erased)?;
+        scan.check_attestations()?;
+        scan.check_supersessions()?;
+        scan.check_roots()?;
+        scan.record(
+            "wal",
+            Severity::Warning,
+            "Pending WAL records".to_string(),
+            wal,
+            Fix::Manual("Start the server once: crash recovery replays or discards them"),
+        );
+        Ok(scan)
+    }
+
+    fn is_leaf(&self, pos: u64) -> bool {
+        pos < self.mmr_size && pos_height_in_tree(pos) == 0
+    }
+
+    /// MMR 节点：`mmr_size` 之内不能缺，之外不应有
+    fn check_nodes(&mut self, wal_empty: bool) -> anyhow::Result<()> {
+        let (mut missing, mut malformed, mut orphans) = (Vec::new(), Vec::new(), Vec::new());
+        let mut expected = 0;
+        for entry in self.store.nodes().iter() {
+            let (key, value) = entry?;
+            let Some(pos) = decode_u64(&key) else {
+                malformed.push(key.to_vec());
+                continue;
+            };
+            if pos >= self.mmr_size {
+                orphans.push(key.to_vec());
+                continue;
+            }
+            missing.extend((expected..pos).map(|p| p.to_be_bytes().to_vec()));
+            expected = pos + 1;
+            self.report.nodes += 1;
+            if value.len() != 32 {
+                malformed.push(key.to_vec());
+            }
+        }
+        missing.extend((expected..self.mmr_size).map(|p| p.to_be_bytes().to_vec()));
+
+        self.record(
+            "nodes",
+            Severity::Error,
+            format!("{} of {} MMR nodes are missing", missing.len(), self.mmr_size),
+            missing,
+            Fix::Manual("Restore the database from a backup; proofs touching these nodes cannot be generated"),
+        );
+        self.record(
+            "nodes",
+            Severity::Error,
+            "MMR nodes with a malformed key or a value that is not 32 bytes".to_string(),
+            malformed,
+            Fix::Manual("Restore the database from a backup"),
+        );
+        let fix = match wal_empty {
+            true => Fix::Remove("nodes"),
+            false => Fix::Manual("Leave them: crash recovery rewrites these positions when it replays the pending WAL"),
+        };
+        self.record(
+            "nodes",
+            Severity::Warning,
+            format!("MMR nodes beyond the committed size {} (left by an interrupted append)", self.mmr_size),
+            orphans,
+            fix,
+        );
+        Ok(())
+    }
+
+    /// 序列号索引：每条指向一个已提交的叶子，返回有效的 序列号 -> 位置
+    fn check_seq_index(&mut self) -> anyhow::Result<HashMap<u64, u64>> {
+        let next_seq = self.store.get_meta_next_seq().unwrap_or_else(|| leaf_count(self.mmr_size));
+        let (mut dangling, mut duplicates) = (Vec::new(), Vec::new());
+        let mut seqs = HashMap::new();
+        let mut positions = HashSet::new();
+        for entry in self.store.tree("seq_index")?.iter() {
+            let (key, value) = entry?;
+            match (decode_u64(&key), decode_u64(&value)) {
+                (Some(seq), Some(pos)) if seq < next_seq && self.is_leaf(pos) => {
+                    if positions.insert(pos) {
+                        seqs.insert(seq, pos);
+                    } else {
+                        duplicates.push(key.to_vec());
+                    }
+                }
+                _ => dangling.push(key.to_vec()),
+            }
+        }
+        let unindexed: Vec<_> = (0..self.report.leaves)
+            .map(leaf_index_to_pos)
+            .filter(|pos| !positions.contains(pos))
+            .map(|pos| pos.to_be_bytes().to_vec())
+            .collect();
+
+        self.record(
+            "seq_index",
+            Severity::Warning,
+            "Sequence numbers pointing past the committed tree".to_string(),
+            dangling,
+            Fix::Remove("seq_index"),
+        );
+        self.record(
+            "seq_index",
+            Severity::Error,
+            "Sequence numbers pointing at a leaf that already has one".to_string(),
+            duplicates,
+            Fix::Manual("Compare with the receipts' seq and remove the wrong entry by hand"),
+        );
+        self.record(
+            "seq_index",
+            Severity::Warning,
+            "Leaves without a sequence number (expected only for leaves written before sequence numbers existed)".to_string(),
+            unindexed,
+            Fix::Manual("Erasure cannot locate these leaves; restore seq_index from a backup if they were indexed before"),
+        );
+        Ok(seqs)
+    }
+
+    /// 内容索引：序列号须有效，键须等于对应原文的 Blake3
+    fn check_leaf_index(&mut self, seqs: &HashMap<u64, u64>) -> anyhow::Result<()> {
+        let (mut dangling, mut mismatched) = (Vec::new(), Vec::new());
+        for entry in self.store.tree("leaf_index")?.iter() {
+            let (key, value) = entry?;
+            let Some(pos) = decode_u64(&value).and_then(|seq| seqs.get(&seq)) else {
+                dangling.push(key.to_vec());
+                continue;
+            };
+            if let Some(bytes) = self.store.get_evidence_bytes(*pos)? {
+                if blake3::hash(&bytes).as_bytes() != key.as_ref() {
+                    mismatched.push(key.to_vec());
+                }
+            }
+        }
+        self.record(
+            "leaf_index",
+            Severity::Warning,
+            "Content hashes pointing at an unknown sequence number".to_string(),
+            dangling,
+            Fix::Remove("leaf_index"),
+        );
+        self.record(
+            "leaf_index",
+            Severity::Error,
+            "Content hashes that do not match the stored evidence".to_string(),
+            mismatched,
+            Fix::Manual("Run `yuanjing self-audit` to tell whether the index or the evidence blob is damaged"),
+        );
+        Ok(())
+    }
+
+    fn check_nonces(&mut self, seqs: &HashMap<u64, u64>) -> anyhow::Result<()> {
+        let mut dangling = Vec::new();
+        for entry in self.store.tree("nonces")?.iter() {
+            let (key, value) = entry?;
+            if !decode_u64(&value).is_some_and(|seq| seqs.contains_key(&seq)) {
+                dangling.push(key.to_vec());
+            }
+        }
+        self.record(
+            "nonces",
+            Severity::Warning,
+            "Nonces pointing at an unknown sequence number".to_string(),
+            dangling,
+            Fix::Remove("nonces"),
+        );
+        Ok(())
+    }
+
+    /// 证据原文：已清理 / 擦除的叶子不应再有原文，盲化叶子本来就没有，其余叶子都应有
+    fn check_evidence(&mut self, erased: &HashSet<u64>) -> anyhow::Result<()> {
+        let pruned = self.keys_of("pruned")?;
+        let blinded = self.keys_of("blinded")?;
+        let (mut orphans, mut leftovers) = (Vec::new(), Vec::new());
+        let mut present = HashSet::new();
+        for key in self.store.tree("evidence")?.iter().keys() {
+            let key = key?;
+            match decode_u64(&key) {
+                Some(pos) if self.is_leaf(pos) => {
+                    if erased.contains(&pos) || pruned.contains(&pos) {
+                        leftovers.push(key.to_vec());
+                    }
+                    present.insert(pos);
+                }
+                _ => orphans.push(key.to_vec()),
+            }
+        }
+        let missing: Vec<_> = (0..self.report.leaves)
+            .map(leaf_index_to_pos)
+            .filter(|pos| !present.contains(pos) && !pruned.contains(pos) && !erased.contains(pos) && !blinded.contains(pos))
+            .map(|pos| pos.to_be_bytes().to_vec())
+            .collect();
+
+        self.record(
+            "evidence",
+            Severity::Warning,
+            "Evidence blobs at positions that are not committed leaves".to_string(),
+            orphans,
+            Fix::Remove("evidence"),
+        );
+        self.record(
+            "evidence",
+            Severity::Warning,
+            "Evidence blobs left behind by an interrupted prune or erasure".to_string(),
+            leftovers,
+            Fix::Remove("evidence"),
+        );
+        self.record(
+            "evidence",
+            Severity::Error,
+            "Leaves whose evidence blob is missing without a prune or erasure record".to_string(),
+            missing,
+            Fix::Manual("Restore the blobs from a backup; existing receipts and proofs still verify"),
+        );
+        Ok(())
+    }
+
+    /// 叶子盐值：擦除后必须已销毁
+    fn check_salts(&mut self, erased: &HashSet<u64>) -> anyhow::Result<()> {
+        let (mut orphans, mut leftovers) = (Vec::new(), Vec::new());
+        for key in self.store.tree("salts")?.iter().keys() {
+            let key = key?;
+            match decode_u64(&key) {
+                Some(pos) if self.is_leaf(pos) => {
+                    if erased.contains(&pos) {
+                        leftovers.push(key.to_vec());
+                    }
+                }
+                _ => orphans.push(key.to_vec()),
+            }
+        }
+        self.record(
+            "salts",
+            Severity::Warning,
+            "Salts at positions that are not committed leaves".to_string(),
+            orphans,
+            Fix::Remove("salts"),
+        );
+        self.record(
+            "salts",
+            Severity::Warning,
+            "Salts that survived an erasure (the erased evidence is still provable)".to_string(),
+            leftovers,
+            Fix::Remove("salts"),
+        );
+        Ok(())
+    }
+
+    /// 签名材料：回执中的 `evidence_hash` 须等于叶子节点
+    fn check_attestations(&mut self) -> anyhow::Result<()> {
+        let snapshot = StoreSnapshot::open_detached(self.store.clone())?;
+        let (mut orphans, mut broken) = (Vec::new(), Vec::new());
+        for entry in self.store.tree("attestations")?.iter() {
+            let (key, value) = entry?;
+            let Some(pos) = decode_u64(&key).filter(|pos| self.is_leaf(*pos)) else {
+                orphans.push(key.to_vec());
+                continue;
+            };
+            let matches = match (StorageCodec::decode::<StoredAttestation>(&value), snapshot.get_leaf(pos)) {
+                (Ok(attestation), Ok(Some(leaf))) => attestation.receipt.evidence_hash == hex::encode(leaf),
+                _ => false,
+            };
+            if !matches {
+                broken.push(key.to_vec());
+            }
+        }
+        self.record(
+            "attestations",
+            Severity::Warning,
+            "Receipts at positions that are not committed leaves".to_string(),
+            orphans,
+            Fix::Remove("attestations"),
+        );
+        self.record(
+            "attestations",
+            Severity::Error,
+            "Receipts that cannot be decoded or do not match their leaf node".to_string(),
+            broken,
+            Fix::Manual("Restore the attestations from a backup; the holder's copy of the receipt is still valid"),
+        );
+        Ok(())
+    }
+
+    fn check_supersessions(&mut self) -> anyhow::Result<()> {
+        let mut dangling = Vec::new();
+        for entry in self.store.tree("supersessions")?.iter() {
+            let (key, value) = entry?;
+            let valid = match (decode_u64(&key), decode_u64(&value)) {
+                (Some(old), Some(new)) => self.is_leaf(old) && self.is_leaf(new),
+                _ => false,
+            };
+            if !valid {
+                dangling.push(key.to_vec());
+            }
+        }
+        self.record(
+            "supersessions",
+            Severity::Warning,
+            "Corrections pointing at positions that are not committed leaves".to_string(),
+            dangling,
+            Fix::Remove("supersessions"),
+        );
+        Ok(())
+    }
+
+    /// 最近签名根与最新存档树头：按其树大小重算根并比对
+    fn check_roots(&mut self) -> anyhow::Result<()> {
+        let snapshot = StoreSnapshot::open_detached(self.store.clone())?;
+        let mut recorded = Vec::new();
+        if let Some(signed) = self.store.get_last_signed_root()? {
+            recorded.push(("signed_root", signed.tree_size, hex::encode(signed.root)));
+        }
+        if let Some(head) = snapshot.tree_heads()?.latest()? {
+            recorded.push(("tree_head", head.tree_head.tree_size, head.tree_head.root));
+        }
+
+        for (check, tree_size, root) in recorded {
+            let key = vec![tree_size.to_be_bytes().to_vec()];
+            if tree_size > self.mmr_size {
+                self.record(
+                    check,
+                    Severity::Error,
+                    format!("Signed at size {} but the committed size is only {}", tree_size, self.mmr_size),
+                    key,
+                    Fix::Manual("Committed leaves were lost; restore the database from a backup"),
+                );
+                continue;
+            }
+            let computed = snapshot.root_at(tree_size).map(hex::encode);
+            if computed.as_ref().ok() != Some(&root) {
+                let computed = computed.unwrap_or_else(|e| e.to_string());
+                self.record(
+                    check,
+                    Severity::Error,
+                    format!("Signed root {} does not match the computed root {}", root, computed),
+                    key,
+                    Fix::Manual("Run `yuanjing self-audit` to locate the first diverging leaf"),
+                );
+            }
+        }
+        Ok(())
+    }
+
+    fn keys_of(&self, name: &str) -> anyhow::Result<HashSet<u64>> {
+        let mut keys = HashSet::new();
+        for key in self.store.tree(name)?.iter().keys() {
+            keys.extend(decode_u64(&key?));
+        }
+        Ok(keys)
+    }
+
+    fn record(&mut self, check: &str, severity: Severity, message: String, keys: Vec<Vec<u8>>, fix: Fix) {
+        if keys.is_empty() {
+            return;
+        }
+        let count = keys.len() as u64;
+        let sample = keys.iter().take(SAMPLE_LIMIT).map(|key| display_key(key)).collect();
+        let repair = match fix {
+            Fix::Remove(tree) => {
+                let description = format!("Delete {} entr{} from `{}`", count, if count == 1 { "y" } else { "ies" }, tree);
+                self.removals.entry(tree).or_default().extend(keys);
+                RepairAction { safe: true, description }
+            }
+            Fix::Manual(hint) => RepairAction { safe: false, description: hint.to_string() },
+        };
+        self.report.findings.push(Finding { check: check.to_string(), severity, message, count, sample, repair });
+    }
+}
+
+fn decode_u64(bytes: &[u8]) -> Option<u64> {
+    Some(u64::from_be_bytes(bytes.try_into().ok()?))
+}
+
+fn display_key(key: &[u8]) -> String {
+    match decode_u64(key) {
+        Some(value) => value.to_string(),
+        None => hex::encode(key),
+    }
+}
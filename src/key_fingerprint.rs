@@ -0,0 +1,97 @@
+use ed25519_dalek::VerifyingKey;
+
+/// RFC 4648 base32 字母表 (不含 0/1/8/9，念出来不易与字母混淆)
+const BASE32_ALPHABET: &[u8; 32] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZ234567";
+
+/// 指纹取摘要的前 10 字节 (80 位)，恰好是 16 个 base32 字符
+const FINGERPRINT_BYTES: usize = 10;
+
+/// 识别图的格子数 (边长) 与每格像素
+const GRID: usize = 5;
+const CELL: usize = 24;
+const MARGIN: usize = 12;
+/// 识别图下方指纹文字的高度
+const CAPTION: usize = 28;
+
+/// 模块：公钥指纹与识别图 (Key Fingerprint & Identicon)
+///
+/// **为什么需要**: 核对身份时，经办人要在电话里或对着打印的报告逐字念 64 位 Hex 公钥，既慢又容易念错。
+/// 指纹是公钥的短摘要，16 个 base32 字符分四组 (如 `MFRG-GZDF-MZTW-Q2LK`)，念一遍即可核对；
+/// 识别图把同一摘要画成对称的色块图案，两份材料放在一起一眼就能看出是否同一把公钥。
+///
+/// 指纹为 `Blake3-KDF("yuanjing-core key fingerprint v1", 公钥)` 的前 80 位：
+/// 伪造一把指纹相同的公钥需要约 2^80 次运算，足以应对人工比对；机器校验仍应比对完整公钥。
+pub fn key_fingerprint(public_key: &VerifyingKey) -> String {
+    let digest = blake3::derive_key("yuanjing-core key fingerprint v1", public_key.as_bytes());
+    let encoded = base32(&digest[..FINGERPRINT_BYTES]);
+    encoded
+        .as_bytes()
+        .chunks(4)
+        .map(|group| std::str::from_utf8(group).unwrap_or_default())
+        .collect::<Vec<_>>()
+        .join("-")
+}
+
+/// 公钥的识别图 (SVG)：5×5 左右对称的色块，下方标注指纹
+///
+/// 图案与颜色取自公钥的另一段摘要 (与指纹相互独立)，格式固定，同一公钥在任何地方渲染都相同。
+pub fn identicon_svg(public_key: &VerifyingKey) -> String {
+    let digest = blake3::derive_key("yuanjing-core key identicon v1", public_key.as_bytes());
+    let hue = u16::from_be_bytes([digest[0], digest[1]]) % 360;
+    let color = format!("hsl({}, 65%, 42%)", hue);
+
+    let size = GRID * CELL + 2 * MARGIN;
+    let mut svg = format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{w}\" height=\"{h}\" viewBox=\"0 0 {w} {h}\">\n\
+         <rect width=\"{w}\" height=\"{h}\" fill=\"#f5f5f5\"/>\n",
+        w = size,
+        h = size + CAPTION
+    );
+    // 只决定左边三列 (15 位)，右边两列镜像
+    let half = GRID.div_ceil(2);
+    for row in 0..GRID {
+        for col in 0..half {
+            let bit = row * half + col;
+            if digest[2 + bit / 8] >> (bit % 8) & 1 == 0 {
+                continue;
+            }
+            let mirror = GRID - 1 - col;
+            let columns = if mirror == col { vec![col] } else { vec![col, mirror] };
+            for x in columns {
+                svg.push_str(&format!(
+                    "<rect x=\"{}\" y=\"{}\" width=\"{c}\" height=\"{c}\" fill=\"{}\"/>\n",
+                    MARGIN + x * CELL,
+                    MARGIN + row * CELL,
+                    color,
+                    c = CELL
+                ));
+            }
+        }
+    }
+    svg.push_str(&format!(
+        "<text x=\"{}\" y=\"{}\" font-family=\"monospace\" font-size=\"13\" text-anchor=\"middle\" fill=\"#222\">{}</text>\n</svg>\n",
+        size / 2,
+        size + CAPTION / 2,
+        key_fingerprint(public_key)
+    ));
+    svg
+}
+
+/// RFC 4648 base32 (无填充)
+fn base32(bytes: &[u8]) -> String {
+    let mut out = String::with_capacity(bytes.len().div_ceil(5) * 8);
+    let mut buffer = 0u32;
+    let mut bits = 0;
+    for byte in bytes {
+        buffer = (buffer << 8) | u32::from(*byte);
+        bits += 8;
+        while bits >= 5 {
+            bits -= 5;
+            out.push(BASE32_ALPHABET[(buffer >> bits) as usize & 0x1f] as char);
+        }
+    }
+    if bits > 0 {
+        out.push(BASE32_ALPHABET[(buffer << (5 - bits)) as usize & 0x1f] as char);
+    }
+    out
+}
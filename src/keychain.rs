@@ -0,0 +1,84 @@
+use log::info;
+use zeroize::Zeroizing;
+
+use crate::config::KeychainConfig;
+use crate::keystore;
+
+/// 模块：系统钥匙串密钥来源 (OS Keychain)
+///
+/// **为什么需要**: 运维人员在自己的工作站上跑 CLI (吊销、生成 DID 文档、离线签名) 时，
+/// 加密身份文件容易被顺手拷进备份、同步盘或工单附件；口令又常常写在同一台机器的脚本里。
+/// 这里把签名 Seed 交给操作系统的钥匙串保管 (macOS Keychain、Windows 凭据管理器、Linux Secret Service)，
+/// 由登录会话解锁，磁盘上不留任何形式的密钥文件。
+///
+/// - 条目以 `service` / `account` 定位，值为 64 位 Hex Seed (也接受 24 词助记词)；
+/// - `yuanjing keygen` 在 `key_source = "keychain"` 时直接写入钥匙串，`--recover` 可从助记词迁入已有身份；
+/// - 需要以 `keychain` 特性编译 (`cargo build --features keychain`)，服务器上通常没有桌面钥匙串，默认不编入。
+pub fn fetch_seed(config: &KeychainConfig) -> anyhow::Result<Zeroizing<[u8; 32]>> {
+    info!("🔐 正在从系统钥匙串读取签名 Seed: {}", describe(config));
+    let value = backend::read(config)?
+        .ok_or_else(|| anyhow::anyhow!("No keychain entry for {} (run `yuanjing keygen` first)", describe(config)))?;
+    keystore::parse_seed(value.trim())
+}
+
+/// 把 Seed 写入钥匙串 (覆盖同名条目)
+pub fn store_seed(config: &KeychainConfig, seed: &[u8; 32]) -> anyhow::Result<()> {
+    backend::write(config, &Zeroizing::new(hex::encode(seed)))
+}
+
+/// 钥匙串中是否已有该条目
+pub fn has_seed(config: &KeychainConfig) -> anyhow::Result<bool> {
+    Ok(backend::read(config)?.is_some())
+}
+
+/// 用于日志与报错的条目名称
+pub fn describe(config: &KeychainConfig) -> String {
+    format!("'{}' / '{}'", config.service, config.account)
+}
+
+#[cfg(feature = "keychain")]
+mod backend {
+    use keyring::{Entry, Error};
+    use zeroize::Zeroizing;
+
+    use super::describe;
+    use crate::config::KeychainConfig;
+
+    pub fn read(config: &KeychainConfig) -> anyhow::Result<Option<Zeroizing<String>>> {
+        match entry(config)?.get_password() {
+            Ok(value) => Ok(Some(Zeroizing::new(value))),
+            Err(Error::NoEntry) => Ok(None),
+            Err(e) => Err(anyhow::anyhow!("Cannot read keychain entry {}: {}", describe(config), e)),
+        }
+    }
+
+    pub fn write(config: &KeychainConfig, value: &str) -> anyhow::Result<()> {
+        entry(config)?
+            .set_password(value)
+            .map_err(|e| anyhow::anyhow!("Cannot write keychain entry {}: {}", describe(config), e))
+    }
+
+    fn entry(config: &KeychainConfig) -> anyhow::Result<Entry> {
+        Entry::new(&config.service, &config.account)
+            .map_err(|e| anyhow::anyhow!("Invalid keychain entry {}: {}", describe(config), e))
+    }
+}
+
+#[cfg(not(feature = "keychain"))]
+mod backend {
+    use zeroize::Zeroizing;
+
+    use crate::config::KeychainConfig;
+
+    pub fn read(_config: &KeychainConfig) -> anyhow::Result<Option<Zeroizing<String>>> {
+        Err(unsupported())
+    }
+
+    pub fn write(_config: &KeychainConfig, _value: &str) -> anyhow::Result<()> {
+        Err(unsupported())
+    }
+
+    fn unsupported() -> anyhow::Error {
+        anyhow::anyhow!("This build has no OS keychain support; rebuild with `--features keychain`")
+    }
+}
@@ -0,0 +1,268 @@
+use argon2::Argon2;
+use chacha20poly1305::aead::{Aead, KeyInit, Payload};
+use chacha20poly1305::{Key, XChaCha20Poly1305, XNonce};
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use zeroize::Zeroizing;
+
+/// 加密身份文件的当前版本
+const KEY_FILE_VERSION: u32 = 1;
+
+/// 模块：加密身份文件 (Encrypted Key File)
+///
+/// **职责**: 让私钥 Seed 永远不以明文形式落盘。
+/// - 口令经 Argon2id 拉伸为 32 字节密钥 (随机盐)；
+/// - Seed 用 XChaCha20-Poly1305 加密，公钥作为附加认证数据 (AAD) 一并绑定，
+///   文件中的公钥被替换时解密会直接失败。
+///
+/// 文件为 JSON，便于人工辨认与跨语言读取。
+/// 解密过程中的口令、派生密钥与明文 Seed 均包在 [`Zeroizing`] 中，用完即擦除。
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct KeyFile {
+    pub version: u32,
+    pub kdf: String,
+    pub cipher: String,
+    /// Argon2 盐 (hex)
+    pub salt: String,
+    /// XChaCha20 随机数 (hex)
+    pub nonce: String,
+    /// 加密后的 Seed (hex，含认证标签)
+    pub ciphertext: String,
+    /// 对应的公钥 (hex)，无需口令即可查看身份
+    pub public_key: String,
+    /// 身份文件的生成时间 (Unix 秒)；早期生成的文件没有该字段
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub created_at: Option<i64>,
+}
+
+impl KeyFile {
+    /// 用口令加密 Seed
+    pub fn seal(seed: &[u8; 32], public_key: &[u8; 32], passphrase: &str) -> anyhow::Result<Self> {
+        let mut salt = [0u8; 16];
+        let mut nonce = [0u8; 24];
+        rand::rngs::OsRng.fill_bytes(&mut salt);
+        rand::rngs::OsRng.fill_bytes(&mut nonce);
+
+        let cipher = XChaCha20Poly1305::new(Key::from_slice(derive_key(passphrase, &salt)?.as_slice()));
+        let ciphertext = cipher
+            .encrypt(XNonce::from_slice(&nonce), Payload { msg: seed, aad: public_key })
+            .map_err(|_| anyhow::anyhow!("Key encryption failed"))?;
+
+        Ok(Self {
+            version: KEY_FILE_VERSION,
+            kdf: "argon2id".to_string(),
+            cipher: "xchacha20poly1305".to_string(),
+            salt: hex::encode(salt),
+            nonce: hex::encode(nonce),
+            ciphertext: hex::encode(ciphertext),
+            public_key: hex::encode(public_key),
+            created_at: Some(chrono::Utc::now().timestamp()),
+        })
+    }
+
+    /// 用口令解密出 Seed
+    pub fn open(&self, passphrase: &str) -> anyhow::Result<Zeroizing<[u8; 32]>> {
+        if self.version != KEY_FILE_VERSION {
+            return Err(anyhow::anyhow!("Unsupported key file version {}", self.version));
+        }
+        let salt = hex::decode(&self.salt)?;
+        let nonce = hex::decode(&self.nonce)?;
+        let ciphertext = hex::decode(&self.ciphertext)?;
+        let public_key = hex::decode(&self.public_key)?;
+        if nonce.len() != 24 {
+            return Err(anyhow::anyhow!("Invalid nonce length in key file"));
+        }
+
+        let cipher = XChaCha20Poly1305::new(Key::from_slice(derive_key(passphrase, &salt)?.as_slice()));
+        let seed = Zeroizing::new(
+            cipher
+                .decrypt(XNonce::from_slice(&nonce), Payload { msg: &ciphertext, aad: &public_key })
+                .map_err(|_| anyhow::anyhow!("Wrong passphrase or corrupted key file"))?,
+        );
+        seed_from_slice(&seed).ok_or_else(|| anyhow::anyhow!("Decrypted seed has invalid length"))
+    }
+
+    pub fn read(path: &Path) -> anyhow::Result<Self> {
+        Ok(serde_json::from_slice(&std::fs::read(path)?)?)
+    }
+
+    pub fn write(&self, path: &Path) -> anyhow::Result<()> {
+        write_secret(path, &serde_json::to_vec_pretty(self)?)
+    }
+}
+
+/// 身份文件的生成时间 (Unix 秒)，用于公开身份信息
+///
+/// 加密身份文件取其中记录的 `created_at`；明文 Seed 与早期的加密文件没有记录，退而取文件的创建 (或修改) 时间。
+pub fn key_created_at(path: &Path) -> Option<i64> {
+    let recorded = std::fs::read(path)
+        .ok()
+        .filter(|bytes| is_encrypted_key_file(bytes))
+        .and_then(|bytes| serde_json::from_slice::<KeyFile>(&bytes).ok())
+        .and_then(|key_file| key_file.created_at);
+    recorded.or_else(|| {
+        let metadata = std::fs::metadata(path).ok()?;
+        let at = metadata.created().or_else(|_| metadata.modified()).ok()?;
+        Some(at.duration_since(std::time::UNIX_EPOCH).ok()?.as_secs() as i64)
+    })
+}
+
+/// 判断文件内容是否为加密身份文件 (否则视为旧版 32 字节明文 Seed)
+pub fn is_encrypted_key_file(bytes: &[u8]) -> bool {
+    bytes.first() == Some(&b'{')
+}
+
+/// 签名 Seed 的来源
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum KeySource {
+    /// 本地身份文件 (`signer.key_path`，默认)
+    File,
+    /// HashiCorp Vault KV v2 (`[signer.vault]`)，Seed 不落盘
+    Vault,
+    /// 系统钥匙串 (`[signer.keychain]`)，运维工作站上的 CLI 使用
+    Keychain,
+    /// 主机 TPM 封存 (`[signer.tpm]`)，只在度量启动未被改动时解封
+    Tpm,
+    /// 飞地签名进程 (`[signer.enclave]`)，私钥不进入本进程
+    Enclave,
+    /// YubiKey PIV 槽位 (`[signer.piv]`)，私钥不出令牌
+    Piv,
+}
+
+impl KeySource {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::File => "file",
+            Self::Vault => "vault",
+            Self::Keychain => "keychain",
+            Self::Tpm => "tpm",
+            Self::Enclave => "enclave",
+            Self::Piv => "piv",
+        }
+    }
+}
+
+impl std::str::FromStr for KeySource {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> anyhow::Result<Self> {
+        match s.to_ascii_lowercase().as_str() {
+            "file" => Ok(Self::File),
+            "vault" => Ok(Self::Vault),
+            "keychain" => Ok(Self::Keychain),
+            "tpm" => Ok(Self::Tpm),
+            "enclave" => Ok(Self::Enclave),
+            "piv" => Ok(Self::Piv),
+            other => Err(anyhow::anyhow!("Unknown key source '{}', expected 'file', 'vault', 'keychain', 'tpm', 'enclave' or 'piv'", other)),
+        }
+    }
+}
+
+/// 身份文件的加载策略
+#[derive(Debug, Clone, Copy, Default)]
+pub struct KeyFilePolicy {
+    /// 只读模式：身份文件不存在时报错，不自动生成
+    pub read_only: bool,
+    /// 允许加载其他用户可访问的身份文件
+    pub allow_insecure_permissions: bool,
+}
+
+/// 以 0600 权限写入密钥文件 (Unix)
+///
+/// 新文件创建时即为 0600，不存在先以默认权限落盘再收紧的窗口；
+/// 覆盖已有文件 (如 `keygen --force`) 时同样收紧为 0600。
+pub fn write_secret(path: &Path, bytes: &[u8]) -> anyhow::Result<()> {
+    use std::io::Write;
+
+    let mut options = std::fs::OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::{OpenOptionsExt, PermissionsExt};
+        options.mode(0o600);
+        let mut file = options.open(path)?;
+        file.set_permissions(std::fs::Permissions::from_mode(0o600))?;
+        file.write_all(bytes)?;
+        file.sync_all()?;
+    }
+    #[cfg(not(unix))]
+    {
+        let mut file = options.open(path)?;
+        file.write_all(bytes)?;
+        file.sync_all()?;
+    }
+    Ok(())
+}
+
+/// 拒绝加载其他用户可访问的身份文件 (Unix)
+///
+/// 同组用户可读仍然允许，便于以服务组方式部署；`policy.allow_insecure_permissions` 可关闭此检查。
+pub fn check_permissions(path: &Path, policy: &KeyFilePolicy) -> anyhow::Result<()> {
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let mode = std::fs::metadata(path)?.permissions().mode() & 0o777;
+        if mode & 0o007 != 0 {
+            if !policy.allow_insecure_permissions {
+                return Err(anyhow::anyhow!(
+                    "身份文件 '{}' 的权限为 {:o}，其他用户可访问；请执行 `chmod 600 {}` (或设置 signer.allow_insecure_permissions)",
+                    path.display(), mode, path.display()
+                ));
+            }
+            log::warn!("⚠️  身份文件 '{}' 的权限为 {:o}，其他用户可访问 (已按配置放行)", path.display(), mode);
+        }
+    }
+    #[cfg(not(unix))]
+    let _ = (path, policy);
+    Ok(())
+}
+
+/// 把 32 字节 Seed 编码为 24 个词的 BIP39 助记词
+pub fn seed_to_mnemonic(seed: &[u8; 32]) -> anyhow::Result<Zeroizing<String>> {
+    Ok(Zeroizing::new(bip39::Mnemonic::from_entropy(seed)?.to_string()))
+}
+
+/// 从 BIP39 助记词还原 32 字节 Seed
+pub fn mnemonic_to_seed(phrase: &str) -> anyhow::Result<Zeroizing<[u8; 32]>> {
+    let mnemonic = bip39::Mnemonic::parse_normalized(phrase.trim())
+        .map_err(|e| anyhow::anyhow!("Invalid mnemonic: {}", e))?;
+    let entropy = Zeroizing::new(mnemonic.to_entropy());
+    seed_from_slice(&entropy).ok_or_else(|| anyhow::anyhow!("Mnemonic must have 24 words (256-bit seed)"))
+}
+
+/// 解析文本形式的 Seed (Vault、系统钥匙串)：64 位 Hex，或 24 词 BIP39 助记词
+pub fn parse_seed(value: &str) -> anyhow::Result<Zeroizing<[u8; 32]>> {
+    if value.contains(char::is_whitespace) {
+        return mnemonic_to_seed(value);
+    }
+    let bytes = Zeroizing::new(hex::decode(value).map_err(|_| anyhow::anyhow!("Seed is neither hex nor a mnemonic"))?);
+    seed_from_slice(&bytes).ok_or_else(|| anyhow::anyhow!("Seed must be 32 bytes, got {}", bytes.len()))
+}
+
+/// 读取口令：优先使用 `KEY_PASSPHRASE` 环境变量，否则在终端交互输入
+pub fn read_passphrase(prompt: &str) -> anyhow::Result<Zeroizing<String>> {
+    if let Ok(passphrase) = std::env::var("KEY_PASSPHRASE") {
+        return Ok(Zeroizing::new(passphrase));
+    }
+    Ok(Zeroizing::new(rpassword::prompt_password(prompt)?))
+}
+
+fn derive_key(passphrase: &str, salt: &[u8]) -> anyhow::Result<Zeroizing<[u8; 32]>> {
+    let mut key = Zeroizing::new([0u8; 32]);
+    Argon2::default()
+        .hash_password_into(passphrase.as_bytes(), salt, key.as_mut_slice())
+        .map_err(|e| anyhow::anyhow!("Key derivation failed: {}", e))?;
+    Ok(key)
+}
+
+/// 把 32 字节切片复制进可擦除的数组，长度不符时返回 `None`
+pub fn seed_from_slice(bytes: &[u8]) -> Option<Zeroizing<[u8; 32]>> {
+    let mut seed = Zeroizing::new([0u8; 32]);
+    if bytes.len() != seed.len() {
+        return None;
+    }
+    seed.copy_from_slice(bytes);
+    Some(seed)
+}
@@ -0,0 +1,116 @@
+use serde::{Deserialize, Serialize};
+use sled::transaction::TransactionError;
+use sled::Transactional;
+
+use crate::codec::StorageCodec;
+use crate::mmr_store::SledStore;
+
+/// 生效中的诉讼保全 (Legal Hold)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LegalHold {
+    pub pos: u64,
+    /// 设置时间 (Unix 秒)
+    pub placed_at: i64,
+    /// 设置者 (管理员 API Key 指纹)
+    pub principal: String,
+    /// 保全依据 (如案号)
+    pub reason: String,
+}
+
+/// 保全操作
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HoldAction {
+    Placed,
+    Released,
+}
+
+/// 保全历史中的一条记录：设置与解除都会留下一条，只增不删
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HoldEvent {
+    pub pos: u64,
+    pub action: HoldAction,
+    /// 操作时间 (Unix 秒)
+    pub at: i64,
+    pub principal: String,
+    pub reason: String,
+}
+
+/// 模块：诉讼保全 (Legal Hold)
+///
+/// **为什么需要**: 进入诉讼的证据在结案前不得删除，哪怕已过保留期、哪怕收到了删除请求。
+/// 保全中的条目会被保留期清理跳过，擦除请求直接拒绝 (见 `EvidenceStore::prune_expired` / `erase`)。
+///
+/// 当前状态存 `legal_holds` 树 (按位置)，每次设置 / 解除都在同一事务中追加到 `legal_hold_history`
+/// (键为 位置 + 该位置的序号)，历史只增不删，可按位置完整查询。
+#[derive(Clone)]
+pub struct LegalHolds {
+    holds: sled::Tree,
+    history: sled::Tree,
+}
+
+impl LegalHolds {
+    pub fn open(store: &SledStore) -> anyhow::Result<Self> {
+        Ok(Self { holds: store.tree("legal_holds")?, history: store.tree("legal_hold_history")? })
+    }
+
+    /// 指定位置当前的保全；未保全返回 None
+    pub fn get(&self, pos: u64) -> anyhow::Result<Option<LegalHold>> {
+        self.holds.get(pos.to_be_bytes())?.map(|v| StorageCodec::decode(&v)).transpose()
+    }
+
+    pub fn is_held(&self, pos: u64) -> anyhow::Result<bool> {
+        Ok(self.holds.contains_key(pos.to_be_bytes())?)
+    }
+
+    /// 全部生效中的保全 (按位置升序)
+    pub fn active(&self) -> anyhow::Result<Vec<LegalHold>> {
+        self.holds.iter().values().map(|v| StorageCodec::decode(&v?)).collect()
+    }
+
+    /// 指定位置的保全历史 (按时间先后)
+    pub fn history(&self, pos: u64) -> anyhow::Result<Vec<HoldEvent>> {
+        self.history.scan_prefix(pos.to_be_bytes()).values().map(|v| StorageCodec::decode(&v?)).collect()
+    }
+
+    /// 设置保全；已在保全中时报错
+    pub fn place(&self, pos: u64, principal: &str, reason: &str, at: i64) -> anyhow::Result<LegalHold> {
+        if self.is_held(pos)? {
+            return Err(anyhow::anyhow!("Evidence at pos {} is already under legal hold", pos));
+        }
+        let hold = LegalHold { pos, placed_at: at, principal: principal.to_string(), reason: reason.to_string() };
+        self.record(pos, Some(&hold), HoldAction::Placed, principal, reason, at)?;
+        Ok(hold)
+    }
+
+    /// 解除保全；未在保全中时报错
+    pub fn release(&self, pos: u64, principal: &str, reason: &str, at: i64) -> anyhow::Result<HoldEvent> {
+        if !self.is_held(pos)? {
+            return Err(anyhow::anyhow!("Evidence at pos {} is not under legal hold", pos));
+        }
+        self.record(pos, None, HoldAction::Released, principal, reason, at)
+    }
+
+    /// 更新当前状态并追加历史 (同一事务)
+    fn record(&self, pos: u64, hold: Option<&LegalHold>, action: HoldAction, principal: &str, reason: &str, at: i64) -> anyhow::Result<HoldEvent> {
+        let event = HoldEvent { pos, action, at, principal: principal.to_string(), reason: reason.to_string() };
+        let index = self.history.scan_prefix(pos.to_be_bytes()).count() as u64;
+        let history_key = [pos.to_be_bytes(), index.to_be_bytes()].concat();
+        let encoded_event = StorageCodec::encode(&event)?;
+        let encoded_hold = hold.map(StorageCodec::encode).transpose()?;
+
+        (&self.holds, &self.history)
+            .transaction(|(holds, history)| {
+                match &encoded_hold {
+                    Some(hold) => holds.insert(&pos.to_be_bytes(), hold.as_slice())?,
+                    None => holds.remove(&pos.to_be_bytes())?,
+                };
+                history.insert(history_key.as_slice(), encoded_event.as_slice())?;
+                Ok(())
+            })
+            .map_err(|e: TransactionError<()>| anyhow::anyhow!("Legal hold transaction failed: {:?}", e))?;
+        self.holds.flush()?;
+        self.history.flush()?;
+        Ok(event)
+    }
+}
@@ -1,6 +1,132 @@
+#[cfg(feature = "server")]
+pub mod abuse;
+#[cfg(feature = "server")]
+pub mod access_log;
+#[cfg(feature = "server")]
+pub mod acl;
+#[cfg(feature = "server")]
+pub mod analytics;
+#[cfg(feature = "server")]
+pub mod anomaly;
+#[cfg(feature = "server")]
 pub mod api;
+#[cfg(feature = "server")]
+pub mod at_rest;
+pub mod bundle;
+#[cfg(feature = "server")]
+pub mod cache;
+#[cfg(feature = "server")]
+pub mod challenge;
+#[cfg(feature = "server")]
+pub mod clock;
+#[cfg(feature = "server")]
+pub mod codec;
+#[cfg(feature = "server")]
 pub mod config;
+#[cfg(feature = "server")]
+pub mod custody;
+#[cfg(feature = "server")]
+pub mod did;
+#[cfg(feature = "server")]
+pub mod dsse;
+#[cfg(feature = "server")]
+pub mod enclave;
+pub mod encoding;
+pub mod envelope;
 pub mod evidence;
+#[cfg(feature = "server")]
+pub mod evidence_index;
+#[cfg(feature = "server")]
+pub mod failover;
+#[cfg(feature = "server")]
 pub mod fingerprint;
+pub mod hdkey;
+#[cfg(feature = "server")]
+pub mod ingest;
+#[cfg(feature = "server")]
+pub mod integrity;
+#[cfg(feature = "server")]
+pub mod keychain;
+#[cfg(feature = "server")]
+pub mod keystore;
+pub mod key_fingerprint;
+#[cfg(feature = "server")]
+pub mod legal_hold;
+#[cfg(feature = "server")]
+pub mod log_sink;
+#[cfg(feature = "server")]
 pub mod mmr_store;
+#[cfg(feature = "server")]
+pub mod model_registry;
+#[cfg(feature = "server")]
+pub mod notary;
+#[cfg(feature = "server")]
+pub mod ops_log;
+#[cfg(feature = "server")]
+pub mod piv;
+pub mod position;
+pub mod proof;
+#[cfg(feature = "server")]
+pub mod prompts;
+pub mod proto;
+#[cfg(feature = "server")]
+pub mod provenance;
+#[cfg(feature = "server")]
+pub mod publication;
+#[cfg(feature = "server")]
+pub mod qr;
+pub mod receipt;
+#[cfg(feature = "server")]
+pub mod rekor;
+#[cfg(feature = "server")]
+pub mod replication;
+#[cfg(feature = "server")]
+pub mod report;
+#[cfg(feature = "server")]
+pub mod retention;
+#[cfg(feature = "server")]
+pub mod review;
+pub mod revocation;
+#[cfg(feature = "server")]
+pub mod schema;
+#[cfg(feature = "server")]
+pub mod scitt;
+#[cfg(feature = "server")]
+pub mod search;
+#[cfg(feature = "server")]
+pub mod self_audit;
+#[cfg(feature = "server")]
 pub mod signer;
+#[cfg(feature = "server")]
+pub mod signing_log;
+#[cfg(feature = "server")]
+pub mod snapshot_archive;
+pub mod solidity;
+#[cfg(feature = "server")]
+pub mod stats;
+#[cfg(feature = "server")]
+pub mod tags;
+#[cfg(feature = "server")]
+pub mod telemetry;
+#[cfg(feature = "server")]
+pub mod tenant;
+#[cfg(feature = "server")]
+pub mod test_vectors;
+#[cfg(feature = "server")]
+pub mod tpm;
+#[cfg(feature = "server")]
+pub mod tsp;
+#[cfg(feature = "server")]
+pub mod vault;
+#[cfg(feature = "server")]
+pub mod vc;
+#[cfg(feature = "server")]
+pub mod writer;
+#[cfg(feature = "server")]
+pub mod x509;
+pub mod zk;
+
+/// 离线校验所需的原语，下游服务无需直接依赖 `ckb_merkle_mountain_range` (版本与本库保持一致)
+pub use ckb_merkle_mountain_range;
+pub use evidence::{evidence_id_of, leaf_hash_of};
+pub use proof::{verify_batch_proof, verify_proof, CompactProof, InclusionProof, MergeBlake3};
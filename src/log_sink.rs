@@ -0,0 +1,291 @@
+use serde::Deserialize;
+
+use crate::config::LoggingConfig;
+
+/// 日志行的输出格式
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LogFormat {
+    /// 原样输出 (默认)；写文件时加上时间与级别前缀
+    #[default]
+    Text,
+    /// 每行一个 JSON 对象：`timestamp`、`level`、`service`、`pid`、`message`
+    Json,
+}
+
+impl LogFormat {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Text => "text",
+            Self::Json => "json",
+        }
+    }
+}
+
+impl std::str::FromStr for LogFormat {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> anyhow::Result<Self> {
+        match s.to_ascii_lowercase().as_str() {
+            "text" => Ok(Self::Text),
+            "json" => Ok(Self::Json),
+            other => Err(anyhow::anyhow!("Unknown log format '{}', expected 'text' or 'json'", other)),
+        }
+    }
+}
+
+/// syslog 设施名对应的编号 (RFC 5424)
+pub fn facility_code(name: &str) -> Option<u8> {
+    Some(match name {
+        "user" => 1,
+        "daemon" => 3,
+        "auth" => 4,
+        "local0" => 16,
+        "local1" => 17,
+        "local2" => 18,
+        "local3" => 19,
+        "local4" => 20,
+        "local5" => 21,
+        "local6" => 22,
+        "local7" => 23,
+        _ => return None,
+    })
+}
+
+/// 写入各输出的级别名 (JSON 的 `level`、文本文件的级别列)
+fn level_name(level: log::Level) -> &'static str {
+    match level {
+        log::Level::Error => "error",
+        log::Level::Warn => "warning",
+        log::Level::Info => "info",
+        log::Level::Debug => "debug",
+        log::Level::Trace => "trace",
+    }
+}
+
+/// syslog 严重程度
+#[cfg(unix)]
+fn severity(level: log::Level) -> u8 {
+    match level {
+        log::Level::Error => 3,
+        log::Level::Warn => 4,
+        log::Level::Info => 6,
+        log::Level::Debug | log::Level::Trace => 7,
+    }
+}
+
+/// 模块：日志输出 (Log Sinks)
+///
+/// **为什么需要**: 取证实验室以 systemd 单元运行服务，不部署 Fluentd / Vector 之类的采集器，
+/// 日志要么直接进本机 syslog (journald / rsyslog)，要么落到按大小轮转的文件里，要么以 JSON 行输出供 journald 结构化检索。
+///
+/// 服务各处用 `log` 宏 (`info!` / `warn!` / `error!`) 记日志，级别在调用点写明；这里是 `log` 的后端，
+/// 把每条记录分发给配置的各个输出 (标准输出、轮转文件、syslog)。本 crate 的记录按 `level` 过滤，
+/// 依赖库 (sled、tantivy 等) 的记录只收警告及以上。
+/// 返回的守卫在丢弃 (drop) 时刷新各输出；它必须活到 `serve` 返回之后，停机前的最后几行不会丢。
+pub struct LogSinks(());
+
+impl LogSinks {
+    /// 按 `[logging]` 安装为进程的全局日志后端；每个进程只能安装一次
+    pub fn install(config: &LoggingConfig) -> anyhow::Result<Self> {
+        let file = if config.file.is_empty() {
+            None
+        } else {
+            Some(RotatingFile::open(&config.file, config.file_max_bytes, config.file_keep)?)
+        };
+        #[cfg(unix)]
+        let syslog = if config.syslog {
+            let facility = facility_code(&config.syslog_facility)
+                .ok_or_else(|| anyhow::anyhow!("Unknown syslog facility '{}'", config.syslog_facility))?;
+            Some(Syslog::connect(&config.syslog_socket, facility, &config.syslog_ident)?)
+        } else {
+            None
+        };
+        #[cfg(not(unix))]
+        if config.syslog {
+            return Err(anyhow::anyhow!("The syslog sink needs a Unix platform"));
+        }
+        let logger = SinkLogger {
+            level: config.level,
+            outputs: std::sync::Mutex::new(Outputs {
+                format: config.format,
+                service: config.syslog_ident.clone(),
+                stdout: config.stdout,
+                file,
+                #[cfg(unix)]
+                syslog,
+            }),
+        };
+        log::set_boxed_logger(Box::new(logger)).map_err(|_| anyhow::anyhow!("A logger is already installed"))?;
+        log::set_max_level(config.level);
+        Ok(Self(()))
+    }
+}
+
+impl Drop for LogSinks {
+    fn drop(&mut self) {
+        log::logger().flush();
+    }
+}
+
+struct SinkLogger {
+    level: log::LevelFilter,
+    outputs: std::sync::Mutex<Outputs>,
+}
+
+impl log::Log for SinkLogger {
+    fn enabled(&self, metadata: &log::Metadata) -> bool {
+        let limit = if metadata.target().starts_with("yuanjing") { self.level } else { self.level.min(log::LevelFilter::Warn) };
+        metadata.level() <= limit
+    }
+
+    fn log(&self, record: &log::Record) {
+        if !self.enabled(record.metadata()) {
+            return;
+        }
+        let message = record.args().to_string();
+        if let Ok(mut outputs) = self.outputs.lock() {
+            outputs.emit(record.level(), record.target(), &message);
+        }
+    }
+
+    fn flush(&self) {
+        use std::io::Write;
+
+        let _ = std::io::stdout().flush();
+    }
+}
+
+struct Outputs {
+    format: LogFormat,
+    service: String,
+    stdout: bool,
+    file: Option<RotatingFile>,
+    #[cfg(unix)]
+    syslog: Option<Syslog>,
+}
+
+impl Outputs {
+    /// 某个输出写失败时不影响其他输出，也不能再记日志报错 (会递归回到这里)
+    fn emit(&mut self, level: log::Level, target: &str, message: &str) {
+        use std::io::Write;
+
+        let timestamp = chrono::Utc::now().to_rfc3339_opts(chrono::SecondsFormat::Millis, true);
+        let json = (self.format == LogFormat::Json).then(|| {
+            serde_json::json!({
+                "timestamp": timestamp,
+                "level": level_name(level),
+                "service": self.service,
+                "pid": std::process::id(),
+                "target": target,
+                "message": message,
+            })
+            .to_string()
+        });
+        if self.stdout {
+            let _ = writeln!(std::io::stdout().lock(), "{}", json.as_deref().unwrap_or(message));
+        }
+        if let Some(file) = &mut self.file {
+            let text = match &json {
+                Some(json) => json.clone(),
+                None => format!("{} {:<7} {}", timestamp, level_name(level), message),
+            };
+            let _ = file.write_line(&text);
+        }
+        #[cfg(unix)]
+        if let Some(syslog) = &self.syslog {
+            syslog.send(level, message);
+        }
+    }
+}
+
+/// 按大小轮转的日志文件：`<path>` 写满后依次改名为 `<path>.1` ~ `<path>.<keep>`，最旧的丢弃
+struct RotatingFile {
+    path: std::path::PathBuf,
+    max_bytes: u64,
+    keep: u32,
+    file: std::fs::File,
+    size: u64,
+}
+
+impl RotatingFile {
+    fn open(path: &str, max_bytes: u64, keep: u32) -> anyhow::Result<Self> {
+        let path = std::path::PathBuf::from(path);
+        if let Some(parent) = path.parent().filter(|parent| !parent.as_os_str().is_empty()) {
+            std::fs::create_dir_all(parent)?;
+        }
+        let file = Self::append(&path)?;
+        let size = file.metadata()?.len();
+        Ok(Self { path, max_bytes, keep, file, size })
+    }
+
+    fn append(path: &std::path::Path) -> anyhow::Result<std::fs::File> {
+        std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .map_err(|e| anyhow::anyhow!("Cannot open log file '{}': {}", path.display(), e))
+    }
+
+    fn write_line(&mut self, line: &str) -> anyhow::Result<()> {
+        use std::io::Write;
+
+        let len = line.len() as u64 + 1;
+        if self.max_bytes > 0 && self.size > 0 && self.size + len > self.max_bytes {
+            self.rotate()?;
+        }
+        writeln!(self.file, "{}", line)?;
+        self.size += len;
+        Ok(())
+    }
+
+    fn rotate(&mut self) -> anyhow::Result<()> {
+        let rotated = |n: u32| {
+            let mut name = self.path.clone().into_os_string();
+            name.push(format!(".{}", n));
+            std::path::PathBuf::from(name)
+        };
+        for n in (1..self.keep).rev() {
+            match std::fs::rename(rotated(n), rotated(n + 1)) {
+                Err(e) if e.kind() != std::io::ErrorKind::NotFound => return Err(e.into()),
+                _ => {}
+            }
+        }
+        if self.keep > 0 {
+            std::fs::rename(&self.path, rotated(1))?;
+        } else {
+            std::fs::remove_file(&self.path)?;
+        }
+        self.file = Self::append(&self.path)?;
+        self.size = 0;
+        Ok(())
+    }
+}
+
+/// 本机 syslog (Unix 域数据报套接字，如 `/dev/log`)，报文为 RFC 3164 格式，时间戳由 syslog 守护进程补上
+#[cfg(unix)]
+struct Syslog {
+    socket: std::os::unix::net::UnixDatagram,
+    path: String,
+    facility: u8,
+    ident: String,
+}
+
+#[cfg(unix)]
+impl Syslog {
+    fn connect(path: &str, facility: u8, ident: &str) -> anyhow::Result<Self> {
+        let socket = std::os::unix::net::UnixDatagram::unbound()?;
+        socket
+            .connect(path)
+            .map_err(|e| anyhow::anyhow!("Cannot connect to syslog socket '{}': {}", path, e))?;
+        Ok(Self { socket, path: path.to_string(), facility, ident: ident.to_string() })
+    }
+
+    /// syslog 守护进程重启后套接字失效，重连一次再发
+    fn send(&self, level: log::Level, line: &str) {
+        let message = format!("<{}>{}[{}]: {}", self.facility * 8 + severity(level), self.ident, std::process::id(), line);
+        if self.socket.send(message.as_bytes()).is_err() {
+            let _ = self.socket.connect(&self.path).and_then(|_| self.socket.send(message.as_bytes()));
+        }
+    }
+}
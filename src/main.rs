@@ -1,55 +1,1319 @@
+use clap::{Args, Parser, Subcommand};
+use log::{error, info, warn};
+use std::path::Path;
+use yuanjing_core::abuse::{AbuseMonitor, AbuseRules};
+use yuanjing_core::access_log::AccessLog;
+use yuanjing_core::anomaly::AnomalyDetector;
 use yuanjing_core::api;
-use yuanjing_core::config::Config;
-use yuanjing_core::mmr_store::EvidenceStore;
+use yuanjing_core::clock::ClockGuard;
+use yuanjing_core::config::{parse_public_key, Config, LoggingConfig};
+use yuanjing_core::failover::{Failover, LeaseDir, WriteFence};
+use yuanjing_core::ingest::IngestGate;
+use yuanjing_core::integrity::{self, IntegrityReport, Severity};
+use yuanjing_core::keychain;
+use yuanjing_core::keystore::{self, KeyFile, KeySource};
+use yuanjing_core::key_fingerprint::key_fingerprint;
+use yuanjing_core::codec::StorageFormat;
+use yuanjing_core::mmr_store::{SledStore, StoreOptions, StoreSnapshot};
+use yuanjing_core::model_registry::{SignedRootMetadata, SignedTargetsMetadata};
+use yuanjing_core::proof::CompactProof;
+use yuanjing_core::provenance::ProvenanceCapture;
+use yuanjing_core::search::SearchService;
+use yuanjing_core::revocation::{Revocation, RevocationList, SignedRevocationList};
 use yuanjing_core::signer::EvidenceSigner;
+use yuanjing_core::did::{self, DidDocument};
+use yuanjing_core::enclave::{self, EnclaveClient, EnclaveKey};
+use yuanjing_core::hdkey::DerivationPath;
+use yuanjing_core::mmr_store::DEFAULT_TENANT;
+use yuanjing_core::notary::{Notarizer, NotaryKind, NotaryProtocol, NotaryTarget};
+use yuanjing_core::rekor::{RekorLog, RekorPublisher};
+use yuanjing_core::publication::{DnsTarget, RootPublisher};
+use yuanjing_core::replication::{AckTracker, PeerCatchUp, ReplicaSync};
+use yuanjing_core::log_sink::LogSinks;
+use yuanjing_core::ops_log::{OpsEvent, OpsLog};
+use yuanjing_core::piv::{self, PivKey, PivSigner};
+use yuanjing_core::retention::RetentionPolicy;
+use yuanjing_core::review::{Examiners, ReviewAssessment, ReviewOpinion};
+use yuanjing_core::self_audit;
+use yuanjing_core::snapshot_archive::{self, SegmentKind, SignedManifest};
+use yuanjing_core::telemetry::Telemetry;
+use yuanjing_core::tpm;
+use yuanjing_core::tenant::{tenant_signer, AdminKeys, Auditors, TenantRegistry};
+use yuanjing_core::at_rest::EvidenceCipher;
+use yuanjing_core::bundle::BundleStatus;
+use yuanjing_core::envelope::{self, SealedBundle};
+use yuanjing_core::test_vectors;
+use yuanjing_core::vault;
+use yuanjing_core::x509::{SignerCertificate, TrustStore};
 use std::sync::Arc;
-use tokio::sync::Mutex;
+use std::time::Duration;
 use tokio::net::TcpListener;
 
+#[derive(Parser)]
+#[command(name = "yuanjing", version, about = "原镜 (Yuanjing) 司法级可信确证服务")]
+struct Cli {
+    #[command(subcommand)]
+    command: Option<Command>,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// 启动 API 服务 (默认)
+    Serve {
+        /// 以只读副本身份运行 (从 `replication.primary_url` 复制，拒绝写接口)
+        #[arg(long)]
+        replica: bool,
+    },
+    /// 生成新的签名身份：写入加密身份文件 (`key_source = "keychain"` 时写入系统钥匙串，`"tpm"` 时封存到 TPM)，并打印助记词备份
+    Keygen {
+        /// 身份文件路径 (默认取配置中的 signer.key_path；指定时总是写文件)
+        #[arg(long)]
+        out: Option<String>,
+        /// 覆盖已存在的身份文件或钥匙串条目
+        #[arg(long)]
+        force: bool,
+        /// 从助记词恢复身份，而不是生成新身份
+        #[arg(long)]
+        recover: bool,
+    },
+    /// 生成 DID 文档 (did.json)，可直接托管到 did:web 域名下
+    Did {
+        /// 输出路径
+        #[arg(long, default_value = "did.json")]
+        out: String,
+    },
+    /// 吊销一把已泄露的密钥：以主身份签名并更新吊销列表
+    Revoke {
+        /// 被吊销的公钥 (Hex)，可以是租户根公钥或派生公钥
+        #[arg(long)]
+        key: String,
+        /// 生效时间 (Unix 秒)，默认为当前时间；此前签发的证据仍然有效
+        #[arg(long)]
+        effective_from: Option<i64>,
+        /// 吊销原因
+        #[arg(long, default_value = "key compromise")]
+        reason: String,
+    },
+    /// 用一把角色密钥给模型注册表元数据 (根或目标，JSON) 追加签名，原地写回
+    RegistrySign {
+        /// 元数据文件：`{"signed": {...}, "signatures": [...]}`，签名列表可为空
+        #[arg(long)]
+        metadata: String,
+        /// 角色密钥的身份文件 (`keygen --out` 生成，不要用服务的主身份)
+        #[arg(long)]
+        key: String,
+    },
+    /// 鉴定人签署复核意见，写出 `POST /v1/evidence/{pos}/reviews` 的请求体
+    ReviewSign(ReviewSignArgs),
+    /// 读取 PIV 令牌槽位的证明：公钥、序列号与触摸策略 (登记鉴定人公钥前先核对)
+    PivInfo,
+    /// 在可信执行环境中运行飞地签名进程：按本进程的 `signer.key_source` 加载主身份，经 `signer.enclave.socket` 代宿主签名
+    EnclaveSigner,
+    /// 离线验证一条证据：重算叶子哈希、验签、校验 MMR 证明，任一项失败即以非零码退出
+    VerifyProof {
+        /// 证据记录 JSON (`GET /evidence/{pos}` 的响应)
+        #[arg(long)]
+        evidence: String,
+        /// 紧凑二进制证明 (`Accept: application/vnd.yuanjing.proof` 下载的 `/audit/{pos}`)
+        #[arg(long)]
+        proof: String,
+        /// 证明所对应的树根 (Hex)
+        #[arg(long)]
+        root: String,
+        /// 回执的签名公钥 (Hex)；派生密钥签发的回执须传派生公钥
+        #[arg(long)]
+        pubkey: String,
+    },
+    /// 生成加密证据包的收件人密钥 (X25519)：私钥写入文件，打印公钥交给导出方
+    RecipientKeygen {
+        /// 私钥文件路径 (64 位 Hex，0600 权限)
+        #[arg(long)]
+        out: String,
+        /// 覆盖已存在的私钥文件
+        #[arg(long)]
+        force: bool,
+    },
+    /// 解密加密证据包 (`GET /evidence/{pos}/bundle/sealed`) 并完整校验，校验通过后写出明文证据包
+    OpenBundle {
+        /// 加密证据包 JSON
+        #[arg(long)]
+        bundle: String,
+        /// 收件人私钥文件 (`recipient-keygen` 生成)
+        #[arg(long)]
+        key: String,
+        /// 明文证据包输出路径
+        #[arg(long)]
+        out: String,
+        /// 事先信任的租户根公钥 (Hex)；缺省时只能证明材料自洽
+        #[arg(long)]
+        pubkey: Option<String>,
+        /// 吊销列表 JSON (`GET /revocations` 的响应)；给出时拒绝签发时密钥已被吊销的回执
+        #[arg(long, requires = "master_key")]
+        revocations: Option<String>,
+        /// 签发吊销列表的主身份公钥 (Hex)
+        #[arg(long)]
+        master_key: Option<String>,
+    },
+    /// 自审重放：由落盘原文重算全部叶子哈希、逐步重建根，并与历史根比对 (须先停止服务)
+    SelfAudit {
+        /// 只审计指定租户 (默认全部)
+        #[arg(long)]
+        tenant: Option<String>,
+    },
+    /// 完整性检查：核对节点数、二级索引与原文、签名根，输出损坏报告与修复建议 (须先停止服务)
+    CheckIntegrity {
+        /// 只检查指定租户 (默认全部)
+        #[arg(long)]
+        tenant: Option<String>,
+        /// 执行标记为 safe 的修复 (只删除无主条目)
+        #[arg(long)]
+        repair: bool,
+    },
+    /// 把整个数据库归档为带签名清单的 `.yjsnap` 快照 (须先停止服务)
+    SnapshotCreate {
+        /// 归档输出路径
+        #[arg(long)]
+        out: String,
+    },
+    /// 完整校验 `.yjsnap` 快照：清单签名与逐段 blake3，任一项不符即以非零码退出
+    SnapshotVerify {
+        /// 归档路径
+        #[arg(long)]
+        archive: String,
+        /// 事先信任的主公钥 (Hex)；缺省时只能证明归档自洽
+        #[arg(long)]
+        pubkey: Option<String>,
+    },
+    /// 校验后把 `.yjsnap` 快照恢复到一个空的数据库目录，并核对各租户重建出的根
+    SnapshotRestore {
+        /// 归档路径
+        #[arg(long)]
+        archive: String,
+        /// 目标数据库目录，须不存在或为空 (默认取配置中的 store.db_path)
+        #[arg(long)]
+        db: Option<String>,
+        /// 事先信任的主公钥 (Hex)；缺省时只能证明归档自洽
+        #[arg(long)]
+        pubkey: Option<String>,
+    },
+    /// 生成跨语言测试向量 (固定证据的规范字节、叶子哈希、签名、回执与证明)，或核对已发布的向量
+    TestVectors {
+        /// 向量文件路径
+        #[arg(long, default_value = test_vectors::TEST_VECTORS_PATH)]
+        out: String,
+        /// 不写文件，只核对现有文件与重新生成的结果是否逐字节一致 (不一致时以非零码退出)
+        #[arg(long)]
+        check: bool,
+    },
+}
+
+#[derive(Args)]
+struct ReviewSignArgs {
+    /// 被复核证据所在的租户
+    #[arg(long, default_value = DEFAULT_TENANT)]
+    tenant: String,
+    /// 被复核证据的位置
+    #[arg(long)]
+    pos: u64,
+    /// 被复核证据的叶子哈希 (Hex，即回执中的 `evidence_hash`)
+    #[arg(long)]
+    evidence_hash: String,
+    /// 复核结论
+    #[arg(long, value_parser = ["agree", "disagree"])]
+    opinion: String,
+    #[arg(long, default_value = "")]
+    notes: String,
+    /// 鉴定人名称 (见 `[[api.examiners]]`)
+    #[arg(long)]
+    examiner: String,
+    /// 鉴定人的身份文件 (`keygen --out` 生成)
+    #[arg(long, required_unless_present = "piv", conflicts_with = "piv")]
+    key: Option<String>,
+    /// 用 PIV 硬件令牌签名 (见 `[piv]`)
+    #[arg(long)]
+    piv: bool,
+    /// 请求体输出路径
+    #[arg(long, default_value = "review.json")]
+    out: String,
+}
+
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
+    let cli = Cli::parse();
+
     // ----------------------------------------------------------------
     // 0. 加载配置
     // ----------------------------------------------------------------
-    let config = Config::from_env();
-    println!("⚙️  配置加载完成: Host={}:{}, DB={}, Key={}", 
-        config.host, config.port, config.db_path, config.key_path);
+    let mut config = Config::load()?;
+
+    let command = cli.command.unwrap_or(Command::Serve { replica: false });
+    // 离线子命令的日志 (加载身份、打开存储等) 只写终端，不进服务的日志文件与 syslog
+    let _console = match command {
+        Command::Serve { .. } => None,
+        _ => Some(LogSinks::install(&LoggingConfig::default())?),
+    };
+
+    match command {
+        Command::Serve { replica } => {
+            if replica {
+                config.replication.replica = true;
+                config.validate()?;
+            }
+            // 日志输出：按 `[logging]` 分发到标准输出 / JSON 行、轮转文件与 syslog
+            let sinks = LogSinks::install(&config.logging)?;
+            let customized = config.logging.customized();
+            if customized {
+                info!(
+                    "🪵 日志输出: 级别={}, 标准输出={} ({}), 文件={}, syslog={}",
+                    config.logging.level,
+                    config.logging.stdout,
+                    config.logging.format.as_str(),
+                    if config.logging.file.is_empty() { "-" } else { &config.logging.file },
+                    if config.logging.syslog { &config.logging.syslog_socket } else { "-" }
+                );
+            }
+            let served = serve(config).await;
+            if let (true, Err(e)) = (customized, &served) {
+                // 启动失败的原因也要进文件与 syslog；main 返回后还会照常打印到标准错误
+                error!("❌ 服务退出: {:#}", e);
+            }
+            drop(sinks);
+            served
+        }
+        Command::Keygen { out, force, recover } => keygen(&config, out.as_deref(), force, recover),
+        Command::Revoke { key, effective_from, reason } => revoke(&config, &key, effective_from, reason),
+        Command::RegistrySign { metadata, key } => registry_sign(&config, &metadata, &key),
+        Command::ReviewSign(args) => review_sign(&config, args),
+        Command::PivInfo => piv_info(&config),
+        Command::EnclaveSigner => enclave_signer(&config),
+        Command::VerifyProof { evidence, proof, root, pubkey } => verify_proof(&evidence, &proof, &root, &pubkey),
+        Command::RecipientKeygen { out, force } => recipient_keygen(&out, force),
+        Command::OpenBundle { bundle, key, out, pubkey, revocations, master_key } => {
+            open_bundle(&bundle, &key, &out, pubkey.as_deref(), revocations.as_deref().zip(master_key.as_deref()))
+        }
+        Command::SelfAudit { tenant } => self_audit(&config, tenant.as_deref()),
+        Command::CheckIntegrity { tenant, repair } => check_integrity(&config, tenant.as_deref(), repair),
+        Command::SnapshotCreate { out } => snapshot_create(&config, &out),
+        Command::SnapshotVerify { archive, pubkey } => snapshot_verify(&archive, pubkey.as_deref()),
+        Command::SnapshotRestore { archive, db, pubkey } => {
+            let db_path = db.as_deref().unwrap_or(&config.store.db_path);
+            snapshot_restore(&archive, db_path, config.ops_log_path_for(db_path).as_deref(), pubkey.as_deref())
+        }
+        Command::TestVectors { out, check } => generate_test_vectors(&out, check),
+        Command::Did { out } => {
+            let master = Arc::new(load_identity(&config, false)?);
+            let revocations = SignedRevocationList::load(Path::new(&config.signer.revocation_list), &master.public_key())?
+                .map(|signed| signed.list)
+                .unwrap_or_default();
+            let document = identity_document(&config, &master, &revocations)?;
+            std::fs::write(&out, serde_json::to_vec_pretty(&document)?)?;
+            println!("🪪 DID 文档已写入: '{}' ({})", out, document.id);
+            Ok(())
+        }
+    }
+}
+
+/// 按 `signer.key_source` 加载主身份
+///
+/// `generate` 为 false 时 (离线子命令) 身份文件缺失直接报错，不会顺手生成一个新身份。
+fn load_identity(config: &Config, generate: bool) -> anyhow::Result<EvidenceSigner> {
+    match config.signer.key_source {
+        KeySource::File => {
+            let mut policy = config.signer.key_file_policy();
+            policy.read_only |= !generate;
+            EvidenceSigner::load_or_generate(&config.signer.key_path, &policy)
+        }
+        KeySource::Vault => {
+            let seed = vault::fetch_seed(&config.signer.vault)?;
+            Ok(EvidenceSigner::from_seed(&seed))
+        }
+        KeySource::Keychain => {
+            let seed = keychain::fetch_seed(&config.signer.keychain)?;
+            Ok(EvidenceSigner::from_seed(&seed))
+        }
+        KeySource::Tpm => {
+            let seed = tpm::unseal(&config.signer.tpm)?;
+            Ok(EvidenceSigner::from_seed(&seed))
+        }
+        KeySource::Enclave => {
+            let client = EnclaveClient::new(&config.signer.enclave.socket);
+            info!("🧱 签名交给飞地签名进程: '{}'", config.signer.enclave.socket);
+            Ok(EvidenceSigner::from_enclave(EnclaveKey::open(Arc::new(client))?))
+        }
+        KeySource::Piv => Ok(EvidenceSigner::from_piv(PivKey::open(&config.signer.piv)?)),
+    }
+}
+
+/// 构建鉴定中心的 DID 文档
+///
+/// 列出主公钥、各租户根公钥与派生公钥 (当前)，以及轮换前的旧公钥与已吊销公钥 (历史)。
+fn identity_document(config: &Config, master: &Arc<EvidenceSigner>, revocations: &RevocationList) -> anyhow::Result<DidDocument> {
+    let id = if config.signer.did_web.is_empty() {
+        did::did_key(&master.public_key())
+    } else {
+        did::did_web(&config.signer.did_web)
+    };
+    let mut document = DidDocument::new(&id);
+    let now = chrono::Utc::now().timestamp();
+
+    let mut current = vec![master.public_key()];
+    for tenant_id in configured_tenants(config) {
+        let signer = tenant_signer(master, tenant_id)?;
+        current.push(signer.public_key());
+        for department in &config.signer.departments {
+            current.push(signer.derive_path(&DerivationPath::parse(&department.path)?)?.public_key());
+        }
+    }
+    for key in current {
+        document.add_key(&key, !revocations.is_revoked(&key, now));
+    }
+
+    for key in &config.signer.previous_keys {
+        document.add_key(&parse_public_key(key)?, false);
+    }
+    for entry in &revocations.entries {
+        document.add_key(&parse_public_key(&entry.public_key)?, false);
+    }
+    Ok(document)
+}
+
+/// 配置中的租户 ID；未配置多租户时只有默认租户
+fn configured_tenants(config: &Config) -> Vec<&str> {
+    if config.api.tenants.is_empty() {
+        vec![DEFAULT_TENANT]
+    } else {
+        config.api.tenants.iter().map(|t| t.id.as_str()).collect()
+    }
+}
+
+/// 离线自审：直接打开数据库逐租户重放，任一租户出现分歧即返回错误 (进程退出码非零)
+///
+/// sled 同一时刻只允许一个进程打开数据库，运行中的服务请改用 `POST /admin/self-audit`。
+fn self_audit(config: &Config, only: Option<&str>) -> anyhow::Result<()> {
+    let tenants = selected_tenants(config, only)?;
+    let base = open_offline(config)?;
+
+    let mut diverged = Vec::new();
+    for tenant_id in tenants {
+        let snapshot = StoreSnapshot::open_detached(base.for_tenant(tenant_id)?)?;
+        let report = self_audit::replay(&snapshot, tenant_id)?;
+        println!(
+            "🔁 租户 '{}': 树大小 {}, 叶子 {} (重算 {}, 原文已删除 {}), 比对历史根 {} 个",
+            tenant_id, report.tree_size, report.leaves, report.rehashed, report.unverifiable, report.roots_checked
+        );
+        match &report.first_divergence {
+            None => println!("   ✅ 与落盘记录一致, 根: {}", report.root.as_deref().unwrap_or("(空树)")),
+            Some(divergence) => {
+                println!(
+                    "   ❌ 第一处分歧: {:?} @ Pos={} (树大小 {}), 来源 {}: 期望 {}, 重放得到 {}",
+                    divergence.kind, divergence.pos, divergence.tree_size, divergence.source, divergence.expected, divergence.actual
+                );
+                diverged.push(tenant_id);
+            }
+        }
+    }
+    if !diverged.is_empty() {
+        return Err(anyhow::anyhow!("Self-audit found divergence in tenant(s): {}", diverged.join(", ")));
+    }
+    Ok(())
+}
+
+/// 离线完整性检查：逐租户输出损坏报告；`repair` 时执行 safe 修复后复查，仍有 error 级问题即返回错误
+fn check_integrity(config: &Config, only: Option<&str>, repair: bool) -> anyhow::Result<()> {
+    let tenants = selected_tenants(config, only)?;
+    let base = open_offline(config)?;
+
+    let mut damaged = Vec::new();
+    for tenant_id in tenants {
+        let store = base.for_tenant(tenant_id)?;
+        let mut report = integrity::check(&store)?;
+        print_integrity(&report);
+        if repair && report.safe_repairs() > 0 {
+            let (_, removed) = integrity::repair(&store)?;
+            println!("   🔧 已删除 {} 条无主条目，复查:", removed);
+            report = integrity::check(&store)?;
+            print_integrity(&report);
+        }
+        if !report.is_healthy() {
+            damaged.push(tenant_id);
+        }
+    }
+    if !damaged.is_empty() {
+        return Err(anyhow::anyhow!("Integrity check found errors in tenant(s): {}", damaged.join(", ")));
+    }
+    Ok(())
+}
+
+fn print_integrity(report: &IntegrityReport) {
+    println!(
+        "🩺 租户 '{}': MMR 大小 {}, 叶子 {}, 节点 {}",
+        report.tenant_id, report.mmr_size, report.leaves, report.nodes
+    );
+    if report.findings.is_empty() {
+        println!("   ✅ 未发现问题");
+    }
+    for finding in &report.findings {
+        let mark = match finding.severity {
+            Severity::Error => "❌",
+            Severity::Warning => "⚠️ ",
+        };
+        println!("   {} [{}] {} ({} 条, 例: {})", mark, finding.check, finding.message, finding.count, finding.sample.join(", "));
+        let repair = if finding.repair.safe { "safe" } else { "manual" };
+        println!("      修复 ({}): {}", repair, finding.repair.description);
+    }
+}
+
+/// 离线命令要处理的租户：`only` 为空时取全部已配置租户
+fn selected_tenants<'a>(config: &'a Config, only: Option<&str>) -> anyhow::Result<Vec<&'a str>> {
+    let tenants: Vec<&str> = configured_tenants(config)
+        .into_iter()
+        .filter(|id| only.is_none_or(|only| only == *id))
+        .collect();
+    if tenants.is_empty() {
+        return Err(anyhow::anyhow!("Unknown tenant: {}", only.unwrap_or_default()));
+    }
+    Ok(tenants)
+}
+
+/// 离线命令直接打开数据库 (sled 同一时刻只允许一个进程打开)
+fn open_offline(config: &Config) -> anyhow::Result<SledStore> {
+    let store = SledStore::new(&config.store.db_path)
+        .map_err(|e| anyhow::anyhow!("Cannot open '{}' (is the server still running?): {}", config.store.db_path, e))?;
+    Ok(store.with_encryption(EvidenceCipher::load(&config.store.encryption)?))
+}
+
+/// 把一把公钥加入吊销列表，版本号加一后用主身份重新签名
+fn revoke(config: &Config, key: &str, effective_from: Option<i64>, reason: String) -> anyhow::Result<()> {
+    let key_bytes = parse_public_key(key)?.to_bytes();
+
+    let signer = load_identity(config, false)?;
+    let path = Path::new(&config.signer.revocation_list);
+    let mut list = SignedRevocationList::load(path, &signer.public_key())?
+        .map(|signed| signed.list)
+        .unwrap_or_default();
+
+    let now = chrono::Utc::now().timestamp();
+    list.entries.push(Revocation {
+        public_key: hex::encode(key_bytes),
+        effective_from: effective_from.unwrap_or(now),
+        reason,
+    });
+    list.version += 1;
+    list.issued_at = now;
+
+    SignedRevocationList::sign(list, &signer)?.write(path)?;
+    println!("⛔ 已吊销公钥 {} , 吊销列表已更新: '{}'", hex::encode(key_bytes), path.display());
+    Ok(())
+}
+
+/// 给模型注册表元数据追加签名 (离线操作，角色私钥不进服务器)
+fn registry_sign(config: &Config, metadata_path: &str, key_path: &str) -> anyhow::Result<()> {
+    let mut policy = config.signer.key_file_policy();
+    policy.read_only = true;
+    let signer = EvidenceSigner::load_or_generate(key_path, &policy)?;
+    let text = std::fs::read_to_string(metadata_path)?;
+    let (kind, version, signatures, signed) = if let Ok(mut root) = serde_json::from_str::<SignedRootMetadata>(&text) {
+        root.sign(&signer)?;
+        ("root", root.signed.version, root.signatures.len(), serde_json::to_vec_pretty(&root)?)
+    } else {
+        let mut targets: SignedTargetsMetadata = serde_json::from_str(&text)
+            .map_err(|e| anyhow::anyhow!("'{}' is neither root nor targets metadata: {}", metadata_path, e))?;
+        targets.sign(&signer)?;
+        ("targets", targets.signed.version, targets.signatures.len(), serde_json::to_vec_pretty(&targets)?)
+    };
+    std::fs::write(metadata_path, signed)?;
+    println!(
+        "🛡️  已以 {} 签名 {} 元数据 v{} (共 {} 个签名): '{}'",
+        hex::encode(signer.public_key().to_bytes()),
+        kind,
+        version,
+        signatures,
+        metadata_path
+    );
+    Ok(())
+}
+
+/// 鉴定人一侧签署复核意见
+///
+/// 签名前先与 `[[api.examiners]]` 中登记的公钥核对，免得让鉴定人按了令牌却签出一份服务端必然拒收的意见。
+fn review_sign(config: &Config, args: ReviewSignArgs) -> anyhow::Result<()> {
+    type SignFn = Box<dyn Fn(&[u8]) -> anyhow::Result<ed25519_dalek::Signature>>;
+    let (public_key, sign): (ed25519_dalek::VerifyingKey, SignFn) = match &args.key {
+        Some(key_path) => {
+            let mut policy = config.signer.key_file_policy();
+            policy.read_only = true;
+            let signer = EvidenceSigner::load_or_generate(key_path, &policy)?;
+            (signer.public_key(), Box::new(move |payload| signer.sign_bytes(payload)))
+        }
+        None => {
+            let token = PivSigner::open(&config.piv)?;
+            (token.public_key(), Box::new(move |payload| token.sign_bytes(payload)))
+        }
+    };
+    match config.api.examiners.iter().find(|examiner| examiner.name == args.examiner) {
+        Some(examiner) if parse_public_key(&examiner.public_key)? != public_key => {
+            return Err(anyhow::anyhow!(
+                "Signing key {} is not the key registered for examiner '{}'",
+                hex::encode(public_key.to_bytes()),
+                args.examiner
+            ));
+        }
+        Some(_) => {}
+        None => println!("⚠️  配置中未登记鉴定人 '{}'，请确认服务端的 [[api.examiners]] 已固定此公钥", args.examiner),
+    }
+
+    let assessment = ReviewAssessment {
+        tenant_id: args.tenant,
+        pos: args.pos,
+        evidence_hash: args.evidence_hash.trim().to_ascii_lowercase(),
+        opinion: if args.opinion == "agree" { ReviewOpinion::Agree } else { ReviewOpinion::Disagree },
+        notes: args.notes,
+        examiner: args.examiner,
+        assessed_at: chrono::Utc::now().timestamp(),
+    };
+    let signature = sign(&assessment.canonical_bytes()?)?;
+    let request = api::ReviewRequest {
+        examiner: assessment.examiner.clone(),
+        opinion: assessment.opinion,
+        notes: assessment.notes.clone(),
+        assessed_at: assessment.assessed_at,
+        signature: hex::encode(signature.to_bytes()),
+    };
+    std::fs::write(&args.out, serde_json::to_vec_pretty(&request)?)?;
+    println!(
+        "🧑‍⚖️ 鉴定人 '{}' 已签署 Pos={} 的复核意见 ({}): '{}'",
+        assessment.examiner,
+        assessment.pos,
+        hex::encode(public_key.to_bytes()),
+        args.out
+    );
+    Ok(())
+}
+
+/// 打印 PIV 槽位的证明内容，并提示是否满足 `[piv].touch_policy`
+fn piv_info(config: &Config) -> anyhow::Result<()> {
+    let attestation = piv::attest(&config.piv)?;
+    println!("🔑 PIV 槽位 {} 的证明:", config.piv.slot);
+    println!("   公钥 (Ed25519): {}", hex::encode(attestation.public_key.to_bytes()));
+    println!("   序列号: {}", attestation.serial.map_or("-".to_string(), |serial| serial.to_string()));
+    println!("   固件: {}", attestation.firmware.as_deref().unwrap_or("-"));
+    println!("   PIN 策略: {}", attestation.pin_policy.map_or("-".to_string(), |policy| policy.to_string()));
+    let satisfied = attestation.touch_policy.satisfies(config.piv.touch_policy);
+    println!(
+        "   触摸策略: {} ({} 配置要求的 '{}')",
+        attestation.touch_policy.as_str(),
+        if satisfied { "✅ 满足" } else { "❌ 不满足" },
+        config.piv.touch_policy.as_str()
+    );
+    if !satisfied {
+        return Err(anyhow::anyhow!("PIV slot {} does not meet the configured touch policy", config.piv.slot));
+    }
+    Ok(())
+}
+
+/// 运行飞地签名进程 (阻塞直到进程被终止)
+fn enclave_signer(config: &Config) -> anyhow::Result<()> {
+    if matches!(config.signer.key_source, KeySource::Enclave | KeySource::Piv) {
+        return Err(anyhow::anyhow!(
+            "The enclave signer loads the seed itself: set signer.key_source to file, vault, keychain or tpm in its configuration"
+        ));
+    }
+    enclave::serve(&config.signer.enclave, load_identity(config, false)?)
+}
+
+/// 离线验证证据记录与紧凑证明
+///
+/// 不读取数据库、不联网，供法庭等场景脚本化核验：逐项打印结果，任一项失败返回错误 (进程退出码非零)。
+fn verify_proof(evidence_path: &str, proof_path: &str, root: &str, pubkey: &str) -> anyhow::Result<()> {
+    let record: api::EvidenceRecordResponse = serde_json::from_slice(&std::fs::read(evidence_path)?)
+        .map_err(|e| anyhow::anyhow!("'{}' is not an evidence record: {}", evidence_path, e))?;
+    let proof = CompactProof::decode(&std::fs::read(proof_path)?)
+        .map_err(|e| anyhow::anyhow!("'{}' is not a compact proof: {}", proof_path, e))?;
+    let root: [u8; 32] = hex::decode(root.trim())
+        .ok()
+        .and_then(|bytes| bytes.try_into().ok())
+        .ok_or_else(|| anyhow::anyhow!("'{}' is not a 32-byte hex root", root))?;
+    let public_key = parse_public_key(pubkey)?;
+
+    let evidence = record.evidence
+        .ok_or_else(|| anyhow::anyhow!("Evidence record at pos {} carries no original evidence ({:?})", record.leaf_pos, record.status))?;
+    let (Some(receipt), Some(receipt_signature)) = (record.receipt, record.receipt_signature) else {
+        return Err(anyhow::anyhow!("Evidence record at pos {} carries no receipt", record.leaf_pos));
+    };
+    let decode_signature = |hex_str: &str| -> anyhow::Result<ed25519_dalek::Signature> {
+        Ok(ed25519_dalek::Signature::from_slice(&hex::decode(hex_str)?)?)
+    };
+
+    let mut checks = Vec::new();
+
+    let salt = record.salt.as_deref()
+        .map(|salt| hex::decode(salt)?.try_into().map_err(|_| anyhow::anyhow!("Salt must be 32 bytes")))
+        .transpose()?;
+    let leaf = evidence.salted_leaf_hash_as(receipt.encoding, salt.as_ref())?;
+    checks.push(("叶子哈希与回执一致", hex::encode(leaf) == receipt.evidence_hash));
+
+    if let Some(signature) = record.evidence_signature.as_deref() {
+        let payload = evidence.canonical_bytes_as(receipt.encoding)?;
+        checks.push(("证据签名有效", EvidenceSigner::verify_bytes(&public_key, &payload, &decode_signature(signature)?)));
+    }
+    checks.push((
+        "回执签名有效",
+        EvidenceSigner::verify_receipt(&public_key, &receipt, &decode_signature(&receipt_signature)?)?,
+    ));
+
+    checks.push(("证明覆盖该叶子", proof.positions == [receipt.leaf_pos]));
+    checks.push(("MMR 证明可推出给定根", proof.verify(&[leaf], root)));
+    if proof.tree_size == receipt.tree_size {
+        checks.push(("给定根即回执所签的根", hex::encode(root) == receipt.root));
+    }
+
+    println!("🔎 离线验证: Tenant={}, Pos={}, 证明树大小={}", receipt.tenant_id, receipt.leaf_pos, proof.tree_size);
+    for (name, passed) in &checks {
+        println!("   {} {}", if *passed { "✅" } else { "❌" }, name);
+    }
+    let failed: Vec<&str> = checks.iter().filter(|(_, passed)| !passed).map(|(name, _)| *name).collect();
+    if !failed.is_empty() {
+        return Err(anyhow::anyhow!("Verification failed: {}", failed.join(", ")));
+    }
+    println!("✅ 验证通过");
+    Ok(())
+}
+
+/// 生成加密证据包的收件人密钥
+fn recipient_keygen(out: &str, force: bool) -> anyhow::Result<()> {
+    let path = Path::new(out);
+    if path.exists() && !force {
+        return Err(anyhow::anyhow!("私钥文件 '{}' 已存在，如需覆盖请加 --force", out));
+    }
+    let secret = EvidenceSigner::generate_seed();
+    keystore::write_secret(path, hex::encode(secret.as_slice()).as_bytes())?;
+    println!("✅ 收件人私钥已写入: '{}' (请妥善保管，丢失后无法解密发给你的证据包)", out);
+    println!("📮 收件人公钥 (X25519，交给导出方): {}", hex::encode(envelope::recipient_public_key(&secret)));
+    Ok(())
+}
+
+/// 离线归档：用主身份签名清单
+fn snapshot_create(config: &Config, out: &str) -> anyhow::Result<()> {
+    let signer = load_identity(config, false)?;
+    let store = open_offline(config)?;
+    let signed = snapshot_archive::create(&store, &signer, config.ops_log_path().as_deref(), Path::new(out))?;
+    println!("📦 快照已写入: '{}'", out);
+    print_snapshot(&signed);
+    Ok(())
+}
+
+fn snapshot_verify(archive: &str, pubkey: Option<&str>) -> anyhow::Result<()> {
+    let trusted = pubkey.map(parse_public_key).transpose()?;
+    let signed = snapshot_archive::verify(Path::new(archive), trusted.as_ref())?;
+    println!("✅ 快照 '{}' 完整{}", archive, if trusted.is_some() { "，由受信任的主公钥签名" } else { " (未指定 --pubkey，只能证明归档自洽)" });
+    print_snapshot(&signed);
+    Ok(())
+}
+
+fn snapshot_restore(archive: &str, db_path: &str, ops_log: Option<&Path>, pubkey: Option<&str>) -> anyhow::Result<()> {
+    let trusted = pubkey.map(parse_public_key).transpose()?;
+    if trusted.is_none() {
+        println!("⚠️  未指定 --pubkey：只校验归档自洽，不校验归档者身份");
+    }
+    let signed = snapshot_archive::restore(Path::new(archive), db_path, ops_log, trusted.as_ref())?;
+    println!("✅ 快照 '{}' 已恢复到 '{}'，各租户的根与清单一致", archive, db_path);
+    if let Some(ops_log) = ops_log.filter(|_| signed.manifest.segments.iter().any(|segment| segment.kind == SegmentKind::OpsLog)) {
+        println!("📒 运维日志已恢复到 '{}'，其中的检查点与恢复出的库一致", ops_log.display());
+    }
+    print_snapshot(&signed);
+    Ok(())
+}
+
+fn print_snapshot(signed: &SignedManifest) {
+    let manifest = &signed.manifest;
+    println!(
+        "   版本 {}, 归档时间 {}, 段 {} 个, 条目 {}, 归档者 {}",
+        manifest.version,
+        manifest.created_at,
+        manifest.segments.len(),
+        manifest.entries(),
+        signed.creator_public_key
+    );
+    for head in &manifest.tenants {
+        println!(
+            "   🌳 租户 '{}': 树大小 {}, 叶子 {}, 根 {}",
+            head.tenant_id,
+            head.mmr_size,
+            head.leaves,
+            head.root.as_deref().unwrap_or("(空树)")
+        );
+    }
+}
+
+/// 生成或核对跨语言测试向量
+fn generate_test_vectors(out: &str, check: bool) -> anyhow::Result<()> {
+    let vectors = test_vectors::generate()?;
+    let json = serde_json::to_string_pretty(&vectors)? + "\n";
+    let entries: usize = vectors.logs.iter().map(|log| log.entries.len()).sum();
+    if !check {
+        if let Some(parent) = Path::new(out).parent().filter(|parent| !parent.as_os_str().is_empty()) {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(out, json)?;
+        println!("✅ 测试向量 v{} 已写入: '{}' ({} 个日志, {} 条证据)", vectors.version, out, vectors.logs.len(), entries);
+        return Ok(());
+    }
+
+    let published = std::fs::read_to_string(out).map_err(|e| anyhow::anyhow!("Cannot read '{}': {}", out, e))?;
+    let expected: test_vectors::TestVectors =
+        serde_json::from_str(&published).map_err(|e| anyhow::anyhow!("'{}' is not a test vector file: {}", out, e))?;
+    let differences = test_vectors::compare(&expected, &vectors)?;
+    for difference in &differences {
+        println!("   ❌ {}", difference);
+    }
+    if !differences.is_empty() || published != json {
+        return Err(anyhow::anyhow!(
+            "'{}' differs from the generated vectors ({} entries changed); the canonical encoding must not change within a version",
+            out,
+            differences.len()
+        ));
+    }
+    println!("✅ 测试向量 v{} 与 '{}' 逐字节一致 ({} 条证据)", vectors.version, out, entries);
+    Ok(())
+}
+
+/// 解密加密证据包：先做不解密的公开校验，再解密并完整校验
+///
+/// `revocations` 为 (吊销列表路径, 主身份公钥)，列表签名不对直接报错，不会退化为不查吊销。
+fn open_bundle(bundle_path: &str, key_path: &str, out: &str, pubkey: Option<&str>, revocations: Option<(&str, &str)>) -> anyhow::Result<()> {
+    let sealed = SealedBundle::from_json(&std::fs::read(bundle_path)?)
+        .map_err(|e| anyhow::anyhow!("'{}' is not a sealed bundle: {}", bundle_path, e))?;
+    let trusted = pubkey.map(parse_public_key).transpose()?;
+    let revocations = revocations
+        .map(|(path, master_key)| {
+            SignedRevocationList::from_json(&std::fs::read(path)?, &parse_public_key(master_key)?)
+                .map_err(|e| anyhow::anyhow!("Cannot use revocation list '{}': {}", path, e))
+        })
+        .transpose()?;
+    let revocations = revocations.as_ref().map(|signed| &signed.list);
+    let secret = zeroize::Zeroizing::new(std::fs::read_to_string(key_path)?);
+    let secret: zeroize::Zeroizing<[u8; 32]> = keystore::seed_from_slice(&zeroize::Zeroizing::new(hex::decode(secret.trim())?))
+        .ok_or_else(|| anyhow::anyhow!("'{}' is not a 32-byte hex recipient key", key_path))?;
+
+    println!("📦 加密证据包: Tenant={}, Pos={}, 收件人 {} 个", sealed.receipt.tenant_id, sealed.receipt.leaf_pos, sealed.recipients.len());
+    let public = sealed.verify_public(trusted.as_ref(), revocations);
+    println!("   {} 回执与包含性证明 (公开校验): {:?}", if public == BundleStatus::Valid { "✅" } else { "❌" }, public);
+    let bundle = sealed.open(&secret)?;
+    let full = bundle.verify(trusted.as_ref(), revocations);
+    println!("   {} 解密后的完整校验: {:?}", if full == BundleStatus::Valid { "✅" } else { "❌" }, full);
+    if public != BundleStatus::Valid || full != BundleStatus::Valid {
+        return Err(anyhow::anyhow!("Sealed bundle verification failed"));
+    }
+    std::fs::write(out, serde_json::to_vec_pretty(&bundle)?)?;
+    println!("✅ 明文证据包已写入: '{}'", out);
+    Ok(())
+}
+
+/// 生成 (或从助记词恢复) 签名身份
+///
+/// 身份文件只保存口令加密后的 Seed；助记词只打印一次，由持有人离线抄写保管。
+fn keygen(config: &Config, out: Option<&str>, force: bool, recover: bool) -> anyhow::Result<()> {
+    let to_keychain = out.is_none() && config.signer.key_source == KeySource::Keychain;
+    let to_tpm = out.is_none() && config.signer.key_source == KeySource::Tpm;
+    let out = out.unwrap_or(if to_tpm { &config.signer.tpm.sealed_path } else { &config.signer.key_path });
+    let path = Path::new(out);
+    if to_keychain {
+        if keychain::has_seed(&config.signer.keychain)? && !force {
+            return Err(anyhow::anyhow!(
+                "钥匙串条目 {} 已存在，如需覆盖请加 --force",
+                keychain::describe(&config.signer.keychain)
+            ));
+        }
+    } else if path.exists() && !force {
+        return Err(anyhow::anyhow!("身份文件 '{}' 已存在，如需覆盖请加 --force", out));
+    }
+
+    let seed = if recover {
+        let mut phrase = zeroize::Zeroizing::new(String::new());
+        println!("📝 请输入 24 个词的助记词 (空格分隔):");
+        std::io::stdin().read_line(&mut phrase)?;
+        keystore::mnemonic_to_seed(&phrase)?
+    } else {
+        EvidenceSigner::generate_seed()
+    };
+    let signer = EvidenceSigner::from_seed(&seed);
+
+    if to_keychain {
+        // 钥匙串由登录会话解锁，不再另设口令
+        keychain::store_seed(&config.signer.keychain, &seed)?;
+        println!("✅ 签名 Seed 已写入系统钥匙串: {}", keychain::describe(&config.signer.keychain));
+    } else if to_tpm {
+        // 只有这颗 TPM、且启动度量与封存时一致才能解封，不再另设口令
+        tpm::seal(&config.signer.tpm, &seed, &signer.public_key().to_bytes())?.write(path)?;
+        println!("✅ 签名 Seed 已封存到 TPM (PCR {}): '{}'", config.signer.tpm.pcrs, out);
+    } else {
+        let passphrase = keystore::read_passphrase("🔐 设置身份文件口令: ")?;
+        if std::env::var("KEY_PASSPHRASE").is_err() && keystore::read_passphrase("🔐 再次输入口令: ")? != passphrase {
+            return Err(anyhow::anyhow!("两次输入的口令不一致"));
+        }
+        if passphrase.is_empty() {
+            return Err(anyhow::anyhow!("口令不能为空"));
+        }
+
+        KeyFile::seal(&seed, &signer.public_key().to_bytes(), &passphrase)?.write(path)?;
+        println!("✅ 加密身份文件已写入: '{}'", out);
+    }
+    println!("🆔 服务身份ID (Public Key): {}", hex::encode(signer.public_key().to_bytes()));
+    println!("🔏 公钥指纹: {}", key_fingerprint(&signer.public_key()));
+
+    if !recover {
+        println!();
+        println!("🧾 助记词备份 (只显示这一次，请离线抄写并妥善保管；任何人凭它都能还原签名身份):");
+        println!();
+        println!("    {}", keystore::seed_to_mnemonic(&seed)?.as_str());
+        println!();
+        println!("   硬件丢失后可用 `yuanjing keygen --recover` 从助记词恢复。");
+    }
+    Ok(())
+}
+
+/// 启动 API 服务
+async fn serve(config: Config) -> anyhow::Result<()> {
+    info!("⚙️  配置加载完成: Host={}:{}, DB={}, Key={}", 
+        config.api.host, config.api.port, config.store.db_path, config.signer.key_path);
 
     // ----------------------------------------------------------------
     // 1. 系统初始化 & 身份加载
     // ----------------------------------------------------------------
-    println!("🛡️ [原镜 Yuanjing] 司法级可信确证服务启动中...");
+    info!("🛡️ [原镜 Yuanjing] 司法级可信确证服务启动中...");
+
+    // 链路追踪 (可选)：存证管线各阶段经 OTLP 导出
+    let telemetry = Telemetry::init(&config.telemetry.otlp_endpoint, &config.telemetry.service_name, config.telemetry.sample_ratio)?;
+    if telemetry.is_some() {
+        info!(
+            "🔭 链路追踪: 导出到 {} (服务名: {}, 采样比例: {})",
+            config.telemetry.otlp_endpoint, config.telemetry.service_name, config.telemetry.sample_ratio
+        );
+    }
+
+    // 运维审计日志 (可选)：密钥加载、配置变更、清理等运维动作串成哈希链
+    let ops_log = match config.ops_log_path() {
+        Some(path) => {
+            let ops_log = OpsLog::open(&path)?;
+            let (entries, head) = ops_log.head()?;
+            info!("📒 运维日志: '{}' ({} 条, 链头 {})", path.display(), entries, hex::encode(&head[..8]));
+            ops_log.note(OpsEvent::Startup { version: env!("CARGO_PKG_VERSION").to_string() });
+            ops_log.note_config(config.fingerprint());
+            Some(ops_log)
+        }
+        None => None,
+    };
     
     // 加载或生成密钥对 (Task C)
-    let signer = EvidenceSigner::load_or_generate(&config.key_path)?;
+    let signer = load_identity(&config, true)?;
     let pub_key_bytes = signer.public_key().to_bytes();
-    println!("🆔 服务身份ID (Public Key): {}", hex::encode(pub_key_bytes));
+    info!("🆔 服务身份ID (Public Key): {}", hex::encode(pub_key_bytes));
+    info!("🔏 公钥指纹: {}", key_fingerprint(&signer.public_key()));
+    if let Some(ops_log) = &ops_log {
+        ops_log.note(OpsEvent::KeyLoaded {
+            purpose: "signer".to_string(),
+            key_id: hex::encode(pub_key_bytes),
+            source: config.signer.key_source.as_str().to_string(),
+        });
+    }
+
+    // 飞地签名模式：取回远程证明，经 `/identity` 公布
+    let enclave_attestation = if config.signer.key_source == KeySource::Enclave {
+        let client = EnclaveClient::new(&config.signer.enclave.socket);
+        match client.attest(chrono::Utc::now().timestamp()) {
+            Ok(attestation) if attestation.check_binding(&signer.public_key()) => {
+                info!("🧱 飞地远程证明: 平台 {} ({}), 引用 {} 字节", attestation.platform.as_str(), attestation.provider, attestation.quote.len() * 3 / 4);
+                Some(attestation)
+            }
+            Ok(_) => return Err(anyhow::anyhow!("Enclave attestation does not bind the enclave's public key")),
+            Err(e) if config.signer.enclave.require_attestation => {
+                return Err(anyhow::anyhow!("{} (set signer.enclave.require_attestation = false to run without it)", e));
+            }
+            Err(e) => {
+                warn!("⚠️  飞地未提供远程证明，/identity 将不含 enclave: {}", e);
+                None
+            }
+        }
+    } else {
+        None
+    };
+
+    // 加载吊销列表 (必须由本服务的主身份签名)
+    let revocations = match SignedRevocationList::load(Path::new(&config.signer.revocation_list), &signer.public_key())? {
+        Some(signed) => {
+            info!("⛔ 吊销列表已加载: 版本 {}, 共 {} 条", signed.list.version, signed.list.entries.len());
+            signed
+        }
+        None => SignedRevocationList::sign(RevocationList::default(), &signer)?,
+    };
+
+    // 加载 X.509 证书链与受信根 (可选)：证书必须与主身份匹配
+    let trust = TrustStore::load_pem(&config.signer.trust_anchors)?;
+    let certificate = if config.signer.certificate_chain.is_empty() {
+        None
+    } else {
+        let certificate = SignerCertificate::load(&config.signer.certificate_chain, &signer, &trust)?;
+        info!("📜 证书链已加载: '{}' (受信根: {} 个)", config.signer.certificate_chain, config.signer.trust_anchors.len());
+        Some(certificate)
+    };
 
     // 初始化 MMR 存储 (Task B)
-    let store = EvidenceStore::new(&config.db_path);
-    println!("📚 证据库 (MMR) 初始化完成 (Headless Mode)");
+    // 每个租户一棵独立的 MMR：读句柄留给 API，存储本体移交该租户的单写者线程
+    let evidence_cipher = EvidenceCipher::load(&config.store.encryption)?;
+    if let Some(cipher) = &evidence_cipher {
+        info!("🔒 证据原文静态加密已开启 (主密钥来源: {})", config.store.encryption.key_source.as_str());
+        if let Some(ops_log) = &ops_log {
+            ops_log.note(OpsEvent::KeyLoaded {
+                purpose: "evidence_master".to_string(),
+                key_id: hex::encode(cipher.key_id()),
+                source: config.store.encryption.key_source.as_str().to_string(),
+            });
+        }
+    }
+    let base_store = SledStore::open_with(&config.store.db_path, config.store.durability, config.store.flush_interval())?
+        .with_encryption(evidence_cipher);
+    match config.store.flush_interval() {
+        Some(ms) => info!("💾 持久化策略: {} (后台刷盘间隔: {} ms)", config.store.durability.as_str(), ms),
+        None => info!("💾 持久化策略: {} (后台刷盘已关闭)", config.store.durability.as_str()),
+    }
+    // 写入围栏：启用主备切换时只在持有写租约期间打开；未启用时主库常开、副本常闭
+    let failover = &config.failover;
+    let fence = if failover.enabled() || config.replication.replica {
+        WriteFence::closed()
+    } else {
+        WriteFence::open()
+    };
+    // 签名的模型注册表 (可选)：各租户以同一个受信根引导
+    let trusted_registry_root = if config.registry.trusted_root.is_empty() {
+        None
+    } else {
+        let root = SignedRootMetadata::load(&config.registry.trusted_root)?;
+        info!("🛡️  模型注册表受信根: v{} (过期时间: {})", root.signed.version, root.signed.expires);
+        Some(root)
+    };
+    let store_options = StoreOptions {
+        proof_cache_capacity: config.store.proof_cache_capacity,
+        evidence_cache_capacity: config.store.evidence_cache_capacity,
+        peak_check_interval: config.store.peak_check_interval,
+        signing_checkpoint_interval: config.store.signing_checkpoint_interval,
+        canonical_encoding: config.store.canonical_encoding,
+        salted_leaves: config.store.salted_leaves,
+        fence: fence.clone(),
+        trusted_registry_root,
+    };
+    let master = Arc::new(signer);
+    let did_document = identity_document(&config, &master, &revocations.list)?;
+    info!("🪪 DID: {}", did_document.id);
+
+    let tenants = TenantRegistry::build(
+        &base_store,
+        &master,
+        &config.api.tenants,
+        &config.signer.departments,
+        &store_options,
+        config.api.writer_queue_depth,
+    )?;
+    info!("📚 证据库 (MMR) 初始化完成 (Headless Mode, 租户鉴权: {})", tenants.auth_required());
+
+    // 运维日志检查点：文件中记录的每个检查点都须与 MMR 中的叶子一致，否则说明日志被改写过
+    if let Some(ops_log) = &ops_log {
+        let verified = ops_log
+            .verify_checkpoints(|tenant_id| Ok(tenants.get(tenant_id).map(|tenant| tenant.reader.snapshot())))
+            .map_err(|e| anyhow::anyhow!("Ops log verification failed ('{}'): {}", ops_log.path().display(), e))?;
+        info!("📒 运维日志校验通过: {} 个检查点与 MMR 一致", verified);
+    }
+
+    // 启动完整性检查 (可选)：error 级问题拒绝启动，warning 只打印
+    if config.store.integrity_check {
+        for tenant in tenants.all() {
+            let report = tenant.writer.check_integrity().await?;
+            print_integrity(&report);
+            if !report.is_healthy() {
+                return Err(anyhow::anyhow!(
+                    "Integrity check failed for tenant '{}'; stop and run `yuanjing check-integrity` for details",
+                    tenant.id
+                ));
+            }
+        }
+    }
+
+    // 复制参数：只读副本用它从主库拉取，热备接管前也用它从其他副本追赶
+    let replication = &config.replication;
+    let replica_sync = ReplicaSync {
+        primary: replication.primary_url.clone(),
+        admin_key: replication.admin_key.clone(),
+        interval: Duration::from_millis(replication.sync_interval_ms),
+        batch_size: replication.batch_size,
+        ack_key: Some(replication.ack_key.clone()).filter(|key| !key.is_empty()),
+        long_poll: Duration::from_millis(replication.long_poll_ms),
+        fence: fence.clone(),
+    };
+    let replica_targets: Vec<_> = tenants
+        .all()
+        .map(|tenant| {
+            let api_key = config.api.tenants.iter().find(|t| t.id == tenant.id).map(|t| t.api_key.clone());
+            (tenant.clone(), api_key)
+        })
+        .collect();
+
+    // 主备切换：主库取得写租约后才接受写入，热备等主库租约过期后接管
+    if failover.enabled() {
+        let lease = Arc::new(Failover {
+            lease: LeaseDir::open(
+                &failover.lease_dir,
+                &failover.node_id,
+                Duration::from_millis(failover.lease_ttl_ms),
+                Duration::from_millis(config.policy.clock_max_skew_ms as u64),
+            )?,
+            fence: fence.clone(),
+            renew_interval: Duration::from_millis(failover.renew_interval_ms),
+            catch_up: (replication.replica && !replication.peers.is_empty()).then(|| PeerCatchUp {
+                peers: replication.peers.clone(),
+                sync: replica_sync.clone(),
+                tenants: replica_targets.clone(),
+                quorum: replication.ack_quorum,
+            }),
+        });
+        if config.replication.replica {
+            info!("🛟 热备模式: 主库写租约过期后接管写入 (租约目录: '{}', 节点: {})", failover.lease_dir, failover.node_id);
+            lease.spawn(None);
+        } else {
+            let epoch = lease.acquire().await?;
+            info!(
+                "🔐 已取得写租约 epoch {} (租约目录: '{}', 节点: {}, 租期: {} ms)",
+                epoch, failover.lease_dir, failover.node_id, failover.lease_ttl_ms
+            );
+            lease.spawn(Some(epoch));
+        }
+    }
+
+    // 启动时钟校验 (NTP)
+    let clock = Arc::new(ClockGuard::new(
+        config.policy.ntp_servers.clone(),
+        config.policy.clock_max_skew_ms,
+        config.policy.clock_policy,
+    ));
+    {
+        let clock = clock.clone();
+        tokio::task::spawn_blocking(move || clock.check_once()).await?;
+    }
+    clock.clone().spawn_periodic(Duration::from_secs(config.policy.clock_check_interval_secs));
+
+    // 启动保留期清理 (只删证据原文，叶子哈希与证明保留)
+    if config.store.retention_years > 0 {
+        info!(
+            "🗑️  证据原文保留 {} 年，每 {} 秒清理一次",
+            config.store.retention_years, config.store.retention_check_interval_secs
+        );
+    }
+    RetentionPolicy {
+        years: config.store.retention_years,
+        check_interval: Duration::from_secs(config.store.retention_check_interval_secs),
+        ops_log: ops_log.clone(),
+    }
+    .spawn_periodic(tenants.all().cloned().collect());
+
+    // 运维日志链头定期写进各租户的 MMR (副本不写，检查点叶子随复制而来)
+    let interval = config.ops_log.checkpoint_interval_secs;
+    if let Some(ops_log) = ops_log.clone().filter(|_| !replication.replica && interval > 0) {
+        info!("📒 运维日志: 每 {} 秒把链头写进各租户的 MMR", interval);
+        ops_log.spawn_periodic(Duration::from_secs(interval), tenants.all().cloned().collect());
+    }
+
+    // 激活路径异常检测 (副本不分析，标记只在主库)
+    if !replication.replica && config.anomaly.interval_secs > 0 {
+        info!("🔎 激活路径异常检测: 每 {} 秒分析一次新证据", config.anomaly.interval_secs);
+        AnomalyDetector {
+            check_interval: Duration::from_secs(config.anomaly.interval_secs),
+            min_samples: config.anomaly.min_samples,
+            rare_frequency: config.anomaly.rare_frequency,
+            max_z_score: config.anomaly.max_z_score,
+        }
+        .spawn_periodic(tenants.all().cloned().collect());
+    }
+
+    // 启动根发布 (签名树头；可选写入 DNS TXT 记录)
+    let anchoring = &config.anchoring;
+    // 副本不写 DNS，免得与主库交替覆盖同一条记录
+    let dns = (!anchoring.dns_name.is_empty() && !replication.replica).then(|| DnsTarget {
+        name: anchoring.dns_name.clone(),
+        server: anchoring.dns_server.clone(),
+        key_file: anchoring.dns_key_file.clone(),
+        ttl: anchoring.dns_ttl,
+        command: anchoring.nsupdate.clone(),
+    });
+    match &dns {
+        Some(dns) => info!("🌐 根发布: 每 {} 秒签名树头，并写入 DNS TXT <tenant>.{}", anchoring.root_publish_interval_secs, dns.name),
+        None => info!("🌐 根发布: 每 {} 秒签名树头 (未配置 DNS)", anchoring.root_publish_interval_secs),
+    }
+    let roots = RootPublisher::new(Duration::from_secs(anchoring.root_publish_interval_secs), dns);
+    roots.clone().spawn_periodic(tenants.all().cloned().collect());
+
+    // 交叉公证：把存档的树头提交给外部公证方副署 (副本不提交，公证回执由主库存档)
+    if !anchoring.notaries.is_empty() && !replication.replica {
+        let notaries = anchoring.notaries
+            .iter()
+            .map(|notary| {
+                let protocol = match notary.protocol {
+                    NotaryKind::Rfc3161 => NotaryProtocol::Rfc3161 {
+                        trust: TrustStore::load_pem(&notary.trust_anchors)?,
+                        policy: Some(notary.policy.clone()).filter(|policy| !policy.is_empty()),
+                    },
+                    NotaryKind::Countersign => NotaryProtocol::Countersign { public_key: parse_public_key(&notary.public_key)? },
+                };
+                Ok(NotaryTarget {
+                    name: notary.name.clone(),
+                    url: notary.url.clone(),
+                    protocol,
+                    api_key: notary.api_key.clone(),
+                    max_skew_secs: anchoring.notary_max_skew_secs,
+                })
+            })
+            .collect::<anyhow::Result<Vec<_>>>()?;
+        let names: Vec<String> = anchoring.notaries
+            .iter()
+            .map(|notary| format!("{} ({})", notary.name, notary.protocol.as_str()))
+            .collect();
+        info!("🤝 交叉公证: 每 {} 秒提交最新树头给 {}", anchoring.notary_interval_secs, names.join(", "));
+        Notarizer::new(Duration::from_secs(anchoring.notary_interval_secs), notaries)
+            .spawn_periodic(tenants.all().cloned().collect());
+    }
+
+    // Rekor 交叉发布：把存档的树头写入公开透明日志 (副本不提交)
+    if !anchoring.rekor_url.is_empty() && !replication.replica {
+        info!("📜 Rekor 交叉发布: 每 {} 秒把最新树头写入 {}", anchoring.rekor_interval_secs, anchoring.rekor_url);
+        RekorPublisher::new(Duration::from_secs(anchoring.rekor_interval_secs), RekorLog { url: anchoring.rekor_url.clone() })
+            .spawn_periodic(tenants.all().cloned().collect());
+    }
+
+    // 只读副本：周期从主库复制叶子
+    if replication.replica {
+        info!("🪞 只读副本模式: 每 {} ms 从主库 {} 复制叶子，写接口已关闭", replication.sync_interval_ms, replication.primary_url);
+        replica_sync.spawn(replica_targets);
+    }
+    if replication.ack_quorum > 0 {
+        info!("🤝 多数派确认: 回执须经 {} 个副本确认后返回，超时返回 503 (超时 {} ms)", replication.ack_quorum, replication.ack_timeout_ms);
+        warn!("⚠️  多数派确认基于写租约 + 拉取式复制，不是 Raft 共识 (openraft 未引入，见 docs/HANDOVER.md)");
+    }
 
     // ----------------------------------------------------------------
     // 2. 状态共享容器
     // ----------------------------------------------------------------
+    let provenance = ProvenanceCapture::load(&config.provenance)?;
+    if let Some(capture) = &provenance {
+        info!("🧭 提交来源采集已开启 (记录方式: {})", capture.mode().as_str());
+    }
+    let search = if config.search.enabled {
+        let search = Arc::new(SearchService::open(
+            &config.search.index_dir,
+            tenants.all().map(|tenant| tenant.id.as_str()),
+            Duration::from_millis(config.search.refresh_interval_ms),
+        )?);
+        info!("🔍 全文检索: 索引目录 {}，每 {} ms 刷新", config.search.index_dir, config.search.refresh_interval_ms);
+        search.clone().spawn_periodic(tenants.all().cloned().collect());
+        Some(search)
+    } else {
+        None
+    };
     let shared_state = Arc::new(api::AppState {
-        store: Arc::new(Mutex::new(store)),
-        signer: Arc::new(signer),
+        tenants,
+        admins: AdminKeys::new(&config.api.admin_keys),
+        auditors: Auditors::new(&config.api.auditors),
+        examiners: Examiners::new(&config.api.examiners)?,
+        consensus_rule: config.policy.consensus_rule,
+        clock,
+        revocations,
+        certificate,
+        trust,
+        did: did_document,
+        roots,
+        ingest: IngestGate::new(config.api.ingest_concurrency, config.api.ingest_quotas(), config.api.ingest_queue_depth, config.api.retry_after_secs),
+        image_limits: config.api.image_limits(),
+        abuse: AbuseMonitor::new(AbuseRules {
+            window_secs: config.abuse.window_secs as i64,
+            max_identical_images: config.abuse.max_identical_images,
+            low_confidence_below: config.abuse.low_confidence_below,
+            max_low_confidence: config.abuse.max_low_confidence,
+            action: config.abuse.action,
+            throttle_secs: config.abuse.throttle_secs as i64,
+        }),
+        provenance,
+        search,
+        jobs: api::JobQueue::new(config.api.job_queue_depth, Duration::from_secs(config.api.job_retention_secs)),
+        acks: Arc::new(AckTracker::new(
+            replication.ack_quorum,
+            Duration::from_millis(replication.ack_timeout_ms),
+            &replication.replicas,
+        )),
+        identity: api::IdentityInfo {
+            public_key: master.public_key(),
+            key_source: config.signer.key_source,
+            key_created_at: match config.signer.key_source {
+                KeySource::File => keystore::key_created_at(Path::new(&config.signer.key_path)),
+                KeySource::Tpm => tpm::created_at(&config.signer.tpm),
+                KeySource::Vault | KeySource::Keychain | KeySource::Enclave | KeySource::Piv => None,
+            },
+            enclave: enclave_attestation,
+            canonical_encoding: config.store.canonical_encoding,
+        },
+        version: api::VersionInfo {
+            name: env!("CARGO_PKG_NAME").to_string(),
+            version: env!("CARGO_PKG_VERSION").to_string(),
+            storage_format: StorageFormat::CURRENT.tag(),
+            durability: config.store.durability.as_str().to_string(),
+            flush_every_ms: config.store.flush_interval(),
+            canonical_encoding: config.store.canonical_encoding.as_str().to_string(),
+            salted_leaves: config.store.salted_leaves,
+            evidence_encryption: config.store.encryption.key_source.as_str().to_string(),
+            replica_of: replication.replica.then(|| replication.primary_url.clone()),
+            // 以下两项在 `/version` 请求时按围栏状态填写
+            writable: false,
+            lease_epoch: None,
+            api_versions: vec!["v1".to_string()],
+            legacy_routes: config.api.legacy_routes,
+            legacy_sunset: config.api.legacy_sunset_at(),
+        },
+        fence,
+        access_log: AccessLog::open(&base_store)?,
+        access_log_enabled: config.api.access_log,
+        ops_log: ops_log.clone(),
     });
 
     // ----------------------------------------------------------------
     // 3. 启动 HTTP 服务 (Task D)
     // ----------------------------------------------------------------
+    api::spawn_job_workers(shared_state.clone(), config.api.job_workers);
     let app = api::app(shared_state);
 
-    let addr = format!("{}:{}", config.host, config.port);
+    let addr = format!("{}:{}", config.api.host, config.api.port);
     let listener = TcpListener::bind(&addr).await?;
     
-    println!("🚀 API 服务已运行在: http://{}", addr);
-    println!("   - POST /prove   : 提交图片指纹进行确证");
-    println!("   - GET  /audit/:pos : 获取特定位置的 Merkle Proof");
+    info!("🚀 API 服务已运行在: http://{}", addr);
+    info!("   - POST /v1/prove   : 提交图片指纹进行确证");
+    info!("   - POST /v1/prove/async : 提交后台存证任务 (GET /v1/jobs/:id 轮询进度)");
+    info!("   - GET  /v1/audit/:pos : 获取特定位置的 Merkle Proof");
+    info!("   - GET  /v1/evidence/:pos : 读取证据原文与回执");
+    info!("   - GET  /v1/evidence/:pos/bundle : 导出离线证据包");
+    info!("   - POST /v1/challenge : 挑战应答 (nonce + 当前根签名)");
+    info!("   - GET  /v1/keys    : 租户公钥与派生公钥目录");
+    info!("   - GET  /v1/identity : 服务身份 (主公钥、DID、证书链)");
+    info!("   - GET  /v1/revocations : 已签名的密钥吊销列表");
+    info!("   - GET  /.well-known/did.json : DID 文档");
+    info!("   - GET  /.well-known/yuanjing-root : 最新签名树头");
+    info!("   - GET  /v1/signing-log : 签名审计日志与 MMR 检查点");
+    info!("   - GET  /metrics : Prometheus 指标");
+    info!("   - GET  /version : 版本与持久化配置");
+    info!("   - GET  /v1/schemas/:name : 请求与证据的 JSON Schema");
+    if config.api.legacy_routes {
+        warn!("   ⚠️  未带 /v1 前缀的旧路由仍可用 (已弃用，响应带 Deprecation 头)");
+    }
 
-    axum::serve(listener, app).await?;
+    // 带上 TCP 对端地址，供提交来源采集 (见 provenance.rs)
+    let served = axum::serve(listener, app.into_make_service_with_connect_info::<std::net::SocketAddr>()).await;
+    if let Some(telemetry) = telemetry {
+        telemetry.shutdown();
+    }
+    served?;
 
     Ok(())
 }
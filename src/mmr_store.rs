@@ -1,70 +1,780 @@
 use ckb_merkle_mountain_range::{MMR, Merge, MMRStore, Result as MMRResult, Error as MMRError};
-use crate::evidence::Evidence;
-use sled::Db;
+use ckb_merkle_mountain_range::helper::{get_peaks, pos_height_in_tree};
+use crate::anomaly::{AnomalyFlag, AnomalyLog, AnomalyStatus};
+use crate::at_rest::{self, EvidenceCipher};
+use crate::cache::{CacheStats, EvidenceReadCache, ProofCache};
+use crate::codec::{StorageCodec, StorageFormat};
+pub use crate::proof::{verify_batch_proof, verify_proof, MergeBlake3};
+pub use crate::position::leaf_count;
+use crate::evidence::{evidence_id_of, leaf_hash_of, CanonicalEncoding, Evidence};
+use crate::failover::WriteFence;
+use crate::integrity::{self, IntegrityReport};
+use crate::acl::{EvidenceAcl, EvidenceAcls};
+use crate::custody::{CustodyDetails, CustodyEvent, CustodyLog, CustodyRecord};
+use crate::legal_hold::{HoldEvent, LegalHold, LegalHolds};
+use crate::model_registry::{AcceptedTargets, ModelRegistry, SignedRootMetadata, SignedTargetsMetadata};
+use crate::notary::{NotaryArchive, TimestampArchive};
+use crate::prompts::{PromptInfo, PromptRegistry, PromptSet};
+use crate::evidence_index::{self, EvidenceAttrs, EvidenceIndex, IndexEntry};
+use crate::tags::{EvidenceTagSet, EvidenceTags};
+use crate::publication::TreeHeadArchive;
+use crate::rekor::RekorArchive;
+use crate::receipt::Receipt;
+use crate::review::{ReviewLog, ReviewRecord};
+use crate::signer::EvidenceSigner;
+use crate::ops_log::OpsCheckpoint;
+use crate::signing_log::{SigningCheckpoint, SigningLog, SigningLogView};
+use crate::telemetry;
+use ed25519_dalek::Signature;
+use log::{error, info, warn};
+use rand::RngCore;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use sled::transaction::TransactionError;
+use sled::{Db, Transactional};
 use std::convert::TryInto;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
 
-/// 合并策略 (Merge Strategy)
-pub struct MergeBlake3;
+/// 默认租户 ID (未启用多租户时所有数据都属于它)
+pub const DEFAULT_TENANT: &str = "default";
 
-impl Merge for MergeBlake3 {
-    type Item = [u8; 32];
+/// 持久化策略 (Durability)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Durability {
+    /// 每次追加在返回回执之前 fsync (默认)
+    Durable,
+    /// 追加后不显式 fsync，依赖 sled 后台定期刷盘 (`flush_every_ms`)，适合批量导入；
+    /// 崩溃时可能丢失最近一个刷盘周期内的追加，但不会出现写了一半的状态
+    Fast,
+}
+
+impl Durability {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Durable => "durable",
+            Self::Fast => "fast",
+        }
+    }
+}
+
+impl std::str::FromStr for Durability {
+    type Err = anyhow::Error;
 
-    fn merge(lhs: &Self::Item, rhs: &Self::Item) -> MMRResult<Self::Item> {
-        let mut hasher = blake3::Hasher::new();
-        hasher.update(lhs);
-        hasher.update(rhs);
-        Ok(*hasher.finalize().as_bytes())
+    fn from_str(s: &str) -> anyhow::Result<Self> {
+        match s.to_ascii_lowercase().as_str() {
+            "durable" => Ok(Self::Durable),
+            "fast" => Ok(Self::Fast),
+            other => Err(anyhow::anyhow!("Unknown durability mode '{}', expected 'durable' or 'fast'", other)),
+        }
     }
 }
 
+/// sled 默认的后台刷盘间隔 (毫秒)
+pub const DEFAULT_FLUSH_EVERY_MS: u64 = 500;
+
 /// 基于 Sled 的持久化存储
+///
+/// **多租户隔离**: 同一个 sled 数据库中，每个租户的所有 tree 都带有独立前缀
+/// (`tenant/{id}/`)，MMR 节点、索引、证据原文、模型白名单互不可见。
+/// 默认租户沿用无前缀的旧 tree 名，已有数据无需迁移。
 #[derive(Clone)]
 pub struct SledStore {
     db: Db,
+    /// 所属租户
+    tenant_id: String,
+    /// tree 名前缀，默认租户为空
+    prefix: String,
+    /// MMR 节点所在的 tree (默认租户为 sled 的默认 tree)
+    nodes: sled::Tree,
+    durability: Durability,
+    /// 该租户的证据原文加密密钥 (见 `at_rest.rs`)；None 表示明文落盘
+    cipher: Option<Arc<EvidenceCipher>>,
 }
 
 impl SledStore {
     pub fn new(path: &str) -> anyhow::Result<Self> {
-        let db = sled::open(path)?;
-        Ok(Self { db })
+        Self::open_with(path, Durability::Durable, Some(DEFAULT_FLUSH_EVERY_MS))
+    }
+
+    /// 按指定持久化策略打开数据库
+    ///
+    /// `flush_every_ms` 为 sled 后台刷盘间隔，None 表示关闭后台刷盘 (只在显式 flush 时落盘)。
+    pub fn open_with(path: &str, durability: Durability, flush_every_ms: Option<u64>) -> anyhow::Result<Self> {
+        let db = sled::Config::new().path(path).flush_every_ms(flush_every_ms).open()?;
+        let nodes = (*db).clone();
+        Ok(Self { db, tenant_id: DEFAULT_TENANT.to_string(), prefix: String::new(), nodes, durability, cipher: None })
+    }
+
+    /// 开启证据原文静态加密；各租户的数据密钥在 [`SledStore::for_tenant`] 时由主密钥派生
+    pub fn with_encryption(mut self, cipher: Option<EvidenceCipher>) -> Self {
+        self.cipher = cipher.map(Arc::new);
+        self
+    }
+
+    /// 打开同一数据库中某个租户的命名空间
+    pub fn for_tenant(&self, tenant_id: &str) -> anyhow::Result<Self> {
+        let cipher = self.cipher.as_ref().map(|cipher| Arc::new(cipher.for_tenant(tenant_id)));
+        if tenant_id == DEFAULT_TENANT {
+            let nodes = (*self.db).clone();
+            return Ok(Self {
+                db: self.db.clone(),
+                tenant_id: tenant_id.to_string(),
+                prefix: String::new(),
+                nodes,
+                durability: self.durability,
+                cipher,
+            });
+        }
+        let prefix = format!("tenant/{}/", tenant_id);
+        let nodes = self.db.open_tree(format!("{}nodes", prefix))?;
+        Ok(Self { db: self.db.clone(), tenant_id: tenant_id.to_string(), prefix, nodes, durability: self.durability, cipher })
+    }
+
+    pub fn tenant_id(&self) -> &str {
+        &self.tenant_id
+    }
+
+    pub fn durability(&self) -> Durability {
+        self.durability
+    }
+
+    pub(crate) fn tree(&self, name: &str) -> sled::Result<sled::Tree> {
+        self.db.open_tree(format!("{}{}", self.prefix, name))
+    }
+
+    /// 底层的 sled 数据库 (所有租户共用，快照归档用)
+    pub(crate) fn db(&self) -> &Db {
+        &self.db
+    }
+
+    /// MMR 节点所在的 tree (完整性检查用)
+    pub(crate) fn nodes(&self) -> &sled::Tree {
+        &self.nodes
     }
 
     pub fn get_meta_size(&self) -> u64 {
-        let meta = self.db.open_tree("meta").expect("open meta tree");
-        match meta.get(b"size") {
+        self.get_meta_u64(b"size").unwrap_or(0)
+    }
+
+    /// 读取下一个待分配的序列号；旧库没有该字段时返回 None
+    pub fn get_meta_next_seq(&self) -> Option<u64> {
+        self.get_meta_u64(b"next_seq")
+    }
+
+    fn get_meta_u64(&self, key: &[u8]) -> Option<u64> {
+        let meta = self.tree("meta").expect("open meta tree");
+        match meta.get(key) {
             Ok(Some(v)) => {
                  let arr: [u8; 8] = v.as_ref().try_into().unwrap_or([0; 8]);
-                 u64::from_be_bytes(arr)
+                 Some(u64::from_be_bytes(arr))
             },
-            _ => 0
+            _ => None
         }
     }
 
     pub fn set_meta_size(&self, size: u64) -> anyhow::Result<()> {
-        let meta = self.db.open_tree("meta")?;
+        let meta = self.tree("meta")?;
         meta.insert(b"size", &size.to_be_bytes())?;
         meta.flush()?;
         Ok(()) 
     }
 
+    /// 写入预写日志 (WAL Intent)
+    ///
+    /// 在任何 MMR 节点落盘之前，先把“这次追加要做什么”完整记下来并 flush。
+    /// 批量追加时每个叶子一条记录，一次 flush。
+    pub fn wal_put(&self, records: &[WalRecord]) -> anyhow::Result<()> {
+        let wal = self.tree("wal")?;
+        let mut batch = sled::Batch::default();
+        for record in records {
+            let encoded = match &self.cipher {
+                Some(_) if !record.evidence_bytes.is_empty() => StorageCodec::encode(&WalRecord {
+                    evidence_bytes: self.seal_evidence(record.pos, &record.evidence_bytes)?,
+                    ..record.clone()
+                })?,
+                _ => StorageCodec::encode(record)?,
+            };
+            batch.insert(&record.seq.to_be_bytes(), encoded);
+        }
+        wal.apply_batch(batch)?;
+        if self.durability == Durability::Durable {
+            wal.flush()?;
+        }
+        Ok(())
+    }
+
+    /// 读取所有未完成的 WAL 记录 (按序列号升序)
+    pub fn wal_pending(&self) -> anyhow::Result<Vec<WalRecord>> {
+        let wal = self.tree("wal")?;
+        wal.iter()
+            .values()
+            .map(|v| {
+                let mut record: WalRecord = StorageCodec::decode(&v?)?;
+                if !record.evidence_bytes.is_empty() {
+                    record.evidence_bytes = self.open_evidence(record.pos, &record.evidence_bytes)?;
+                }
+                Ok(record)
+            })
+            .collect()
+    }
+
+    /// 丢弃无法解码的残缺 WAL 记录 (写到一半时崩溃)，返回丢弃的条数
+    ///
+    /// 追加只在 WAL 完整落盘之后才开始写节点，残缺的记录对应的追加必然没有提交，丢弃即可；
+    /// 能解码但与当前状态对不上的记录由 `recover` 逐条判断。
+    pub fn wal_discard_torn(&self) -> anyhow::Result<u64> {
+        let wal = self.tree("wal")?;
+        let mut discarded = 0;
+        for entry in wal.iter() {
+            let (key, value) = entry?;
+            if StorageCodec::decode::<WalRecord>(&value).is_err() {
+                wal.remove(key)?;
+                discarded += 1;
+            }
+        }
+        if discarded > 0 {
+            wal.flush()?;
+        }
+        Ok(discarded)
+    }
+
+    /// 丢弃一条 WAL 记录
+    pub fn wal_discard(&self, seq: u64) -> anyhow::Result<()> {
+        let wal = self.tree("wal")?;
+        wal.remove(seq.to_be_bytes())?;
+        wal.flush()?;
+        Ok(())
+    }
+
+    /// 原子提交一次追加 (Atomic Append Commit)
+    ///
+    /// MMR 大小、序列号计数器、序列号索引、内容索引、防重放 nonce、证据原文、叶子盐值，
+    /// 签名材料与最近签名根，以及 WAL 记录的删除，在同一个 sled 事务里完成：
+    /// 要么全部生效，要么全部不生效。批量追加的所有叶子同样在一个事务中提交。
+    pub fn commit_append(&self, records: &[WalRecord]) -> anyhow::Result<()> {
+        let Some(last) = records.last() else {
+            return Ok(());
+        };
+        let meta = self.tree("meta")?;
+        let seq_index = self.tree("seq_index")?;
+        let leaf_index = self.tree("leaf_index")?;
+        let nonces = self.tree("nonces")?;
+        let evidence = self.tree("evidence")?;
+        let salts = self.tree("salts")?;
+        let blinded = self.tree("blinded")?;
+        let attestations = self.tree("attestations")?;
+        let supersessions = self.tree("supersessions")?;
+        let evidence_attrs = self.tree("evidence_attrs")?;
+        let evidence_order = self.tree("evidence_order")?;
+        let wal = self.tree("wal")?;
+
+        let attestations_bytes = records
+            .iter()
+            .map(|record| record.attestation.as_ref().map(StorageCodec::encode).transpose())
+            .collect::<Result<Vec<_>, _>>()?;
+        let evidence_blobs = records
+            .iter()
+            .map(|record| self.seal_evidence(record.pos, &record.evidence_bytes))
+            .collect::<anyhow::Result<Vec<_>>>()?;
+        let signed_root = records
+            .iter()
+            .rev()
+            .find_map(|record| record.attestation.as_ref().map(|a| (record, a)))
+            .map(|(record, a)| StorageCodec::encode(&SignedRoot {
+                root: record.new_root,
+                tree_size: record.new_size,
+                signature: a.receipt_signature.clone(),
+            }))
+            .transpose()?;
+        // 检查点、保管与复核记录叶子没有签名材料，不进二级索引
+        let index_entries = records
+            .iter()
+            .map(|record| {
+                let Some(attestation) = &record.attestation else {
+                    return Ok(None);
+                };
+                let receipt = &attestation.receipt;
+                let attrs = (!record.evidence_bytes.is_empty())
+                    .then(|| EvidenceAttrs::decode(receipt.encoding, &record.evidence_bytes, receipt.timestamp))
+                    .transpose()?;
+                IndexEntry::new(record.pos, receipt.timestamp, attrs.as_ref()).map(Some)
+            })
+            .collect::<anyhow::Result<Vec<_>>>()?;
+
+        (&meta, &seq_index, &leaf_index, &nonces, &evidence, &salts, &blinded, &attestations, &supersessions, &evidence_attrs, &evidence_order, &wal)
+            .transaction(|(meta, seq_index, leaf_index, nonces, evidence, salts, blinded, attestations, supersessions, evidence_attrs, evidence_order, wal)| {
+                for (((record, attestation), blob), index_entry) in records.iter().zip(&attestations_bytes).zip(&evidence_blobs).zip(&index_entries) {
+                    seq_index.insert(&record.seq.to_be_bytes(), &record.pos.to_be_bytes())?;
+                    if let Some(nonce) = &record.nonce {
+                        nonces.insert(nonce.as_bytes(), &record.seq.to_be_bytes())?;
+                    }
+                    // 副本复制来的已清理 / 擦除叶子没有原文，加盐叶子此时算不出内容哈希；盲化叶子不建内容索引
+                    if record.blinded {
+                        blinded.insert(&record.pos.to_be_bytes(), &[])?;
+                    } else if record.salt.is_none() || !record.evidence_bytes.is_empty() {
+                        leaf_index.insert(&record.content_hash(), &record.seq.to_be_bytes())?;
+                    }
+                    if !record.evidence_bytes.is_empty() {
+                        evidence.insert(&record.pos.to_be_bytes(), blob.as_slice())?;
+                    }
+                    if let Some(salt) = &record.salt {
+                        salts.insert(&record.pos.to_be_bytes(), salt.as_slice())?;
+                    }
+                    if let Some(attestation) = attestation {
+                        attestations.insert(&record.pos.to_be_bytes(), attestation.as_slice())?;
+                    }
+                    if let Some(superseded) = record.attestation.as_ref().and_then(|a| a.receipt.supersedes) {
+                        supersessions.insert(&superseded.to_be_bytes(), &record.pos.to_be_bytes())?;
+                    }
+                    if let Some(index_entry) = index_entry {
+                        index_entry.insert_in_transaction(evidence_attrs, evidence_order)?;
+                    }
+                    wal.remove(&record.seq.to_be_bytes())?;
+                }
+                meta.insert(b"size", &last.new_size.to_be_bytes())?;
+                meta.insert(b"next_seq", &(last.seq + 1).to_be_bytes())?;
+                if let Some(signed_root) = &signed_root {
+                    meta.insert(b"last_signed_root", signed_root.as_slice())?;
+                }
+                Ok(())
+            })
+            .map_err(|e: TransactionError<()>| anyhow::anyhow!("Append commit transaction failed: {:?}", e))
+    }
+
+    /// 读取指定位置的证据原文 (规范字节)
+    pub fn get_evidence_bytes(&self, pos: u64) -> anyhow::Result<Option<Vec<u8>>> {
+        let evidence = self.tree("evidence")?;
+        evidence.get(pos.to_be_bytes())?.map(|v| self.open_evidence(pos, &v)).transpose()
+    }
+
+    /// 按落盘形式封装证据原文：开启静态加密时为密文块，否则原样
+    fn seal_evidence(&self, pos: u64, bytes: &[u8]) -> anyhow::Result<Vec<u8>> {
+        match &self.cipher {
+            Some(cipher) if !bytes.is_empty() => cipher.seal(pos, bytes),
+            _ => Ok(bytes.to_vec()),
+        }
+    }
+
+    /// 还原落盘的证据原文 (`evidence` 树中的值)；开启加密前写入的明文块原样返回
+    pub fn open_evidence(&self, pos: u64, blob: &[u8]) -> anyhow::Result<Vec<u8>> {
+        match &self.cipher {
+            Some(cipher) => cipher.open(pos, blob),
+            None if at_rest::is_sealed(blob) => Err(anyhow::anyhow!(
+                "Evidence at pos {} of tenant '{}' is encrypted at rest; configure store.encryption",
+                pos,
+                self.tenant_id
+            )),
+            None => Ok(blob.to_vec()),
+        }
+    }
+
+    /// 核对证据加密密钥：库中记录的密钥 ID (`meta/evidence_key_id`) 必须与当前配置一致
+    ///
+    /// 返回 true 表示库已整体加密，false 表示尚未加密 (或未开启加密)。
+    pub fn check_evidence_key(&self) -> anyhow::Result<bool> {
+        let recorded = self.tree("meta")?.get(b"evidence_key_id")?;
+        match (&self.cipher, recorded) {
+            (Some(cipher), Some(id)) if id.as_ref() == cipher.key_id() => Ok(true),
+            (Some(_), Some(id)) => Err(anyhow::anyhow!(
+                "Evidence of tenant '{}' is encrypted with another master key (id {})",
+                self.tenant_id,
+                hex::encode(id)
+            )),
+            (None, Some(_)) => Err(anyhow::anyhow!(
+                "Evidence of tenant '{}' is encrypted at rest; configure store.encryption",
+                self.tenant_id
+            )),
+            (_, None) => Ok(false),
+        }
+    }
+
+    /// 开启静态加密后的一次性补加密：把库中的明文证据块逐个加密，完成后记下密钥 ID
+    ///
+    /// 每个块单独写入 (加密与否都能读)，中途崩溃后重启会接着处理剩下的明文块。返回加密的块数。
+    pub fn ensure_evidence_encryption(&self) -> anyhow::Result<u64> {
+        let Some(cipher) = &self.cipher else {
+            self.check_evidence_key()?;
+            return Ok(0);
+        };
+        if self.check_evidence_key()? {
+            return Ok(0);
+        }
+        let evidence = self.tree("evidence")?;
+        let mut sealed = 0;
+        for entry in evidence.iter() {
+            let (key, blob) = entry?;
+            let pos = u64::from_be_bytes(key.as_ref().try_into()?);
+            // 顺带确认已加密的块都出自当前密钥
+            let plaintext = cipher.open(pos, &blob)?;
+            if !at_rest::is_sealed(&blob) {
+                evidence.insert(key, cipher.seal(pos, &plaintext)?)?;
+                sealed += 1;
+            }
+        }
+        let meta = self.tree("meta")?;
+        meta.insert(b"evidence_key_id", &cipher.key_id())?;
+        self.flush()?;
+        Ok(sealed)
+    }
+
+    /// 为二级索引上线前入库的证据补建索引 (见 `evidence_index.rs`)，返回补建的条数
+    ///
+    /// 此后的追加在同一个事务中建索引；原文已清理或擦除的只补按回执时间的键。
+    pub fn ensure_evidence_index(&self) -> anyhow::Result<u64> {
+        let meta = self.tree("meta")?;
+        if meta.contains_key(b"evidence_index")? {
+            return Ok(0);
+        }
+        let index = EvidenceIndex::open(self)?;
+        let mut built = 0;
+        for entry in self.tree("attestations")?.iter() {
+            let (key, value) = entry?;
+            let pos = u64::from_be_bytes(key.as_ref().try_into()?);
+            let receipt = StorageCodec::decode::<StoredAttestation>(&value)?.receipt;
+            let attrs = self
+                .get_evidence_bytes(pos)?
+                .map(|bytes| EvidenceAttrs::decode(receipt.encoding, &bytes, receipt.timestamp))
+                .transpose()?;
+            index.insert(&IndexEntry::new(pos, receipt.timestamp, attrs.as_ref())?)?;
+            built += 1;
+        }
+        meta.insert(b"evidence_index", &[1])?;
+        self.flush()?;
+        Ok(built)
+    }
+
+    /// 证据原文被保留期清理的时间 (Unix 秒)；未清理返回 None
+    pub fn get_pruned_at(&self, pos: u64) -> anyhow::Result<Option<i64>> {
+        Ok(Self::lookup_u64(&self.tree("pruned")?, &pos.to_be_bytes())?.map(|ts| ts as i64))
+    }
+
+    /// 删除一批证据原文，并在同一个事务中记下清理时间
+    ///
+    /// 只动 `evidence` 与 `pruned` 两棵 tree：MMR 节点、索引、签名材料与签名根原样保留。
+    pub fn prune_evidence(&self, positions: &[u64], pruned_at: i64) -> anyhow::Result<()> {
+        let evidence = self.tree("evidence")?;
+        let pruned = self.tree("pruned")?;
+        (&evidence, &pruned)
+            .transaction(|(evidence, pruned)| {
+                for pos in positions {
+                    evidence.remove(&pos.to_be_bytes())?;
+                    pruned.insert(&pos.to_be_bytes(), &pruned_at.to_be_bytes())?;
+                }
+                Ok(())
+            })
+            .map_err(|e: TransactionError<()>| anyhow::anyhow!("Prune transaction failed: {:?}", e))?;
+        self.flush()
+    }
+
+    /// 读取指定位置的叶子盐值；无盐叶子或盐值已销毁时返回 None
+    pub fn get_salt(&self, pos: u64) -> anyhow::Result<Option<[u8; 32]>> {
+        self.tree("salts")?
+            .get(pos.to_be_bytes())?
+            .map(|v| v.as_ref().try_into().map_err(|_| anyhow::anyhow!("Corrupted salt at pos {}", pos)))
+            .transpose()
+    }
+
+    /// 是否为盲化叶子 (原文与盐值只在提交方手中)
+    pub fn is_blinded(&self, pos: u64) -> anyhow::Result<bool> {
+        Ok(self.tree("blinded")?.contains_key(pos.to_be_bytes())?)
+    }
+
+    /// 取代指定位置的更正叶子位置；尚未被更正时返回 None
+    pub fn superseded_by(&self, pos: u64) -> anyhow::Result<Option<u64>> {
+        Self::lookup_u64(&self.tree("supersessions")?, &pos.to_be_bytes())
+    }
+
+    /// 读取指定位置的擦除记录
+    pub fn get_erasure(&self, pos: u64) -> anyhow::Result<Option<ErasureRecord>> {
+        self.tree("erasures")?
+            .get(pos.to_be_bytes())?
+            .map(|v| StorageCodec::decode(&v))
+            .transpose()
+    }
+
+    /// 全部擦除记录 (按位置升序)
+    pub fn erasures(&self) -> anyhow::Result<Vec<ErasureRecord>> {
+        self.tree("erasures")?
+            .iter()
+            .values()
+            .map(|v| StorageCodec::decode(&v?))
+            .collect()
+    }
+
+    /// 密码学擦除的落盘部分：销毁盐值、删除原文与内容索引、清空证据签名，并写入擦除记录
+    ///
+    /// 证据签名是对原文的签名，留着它仍可把原文与该位置对应起来，因此一并清空；
+    /// 回执只承诺加盐后的叶子哈希，原样保留。
+    pub fn erase_evidence(&self, record: &ErasureRecord, content_key: Option<&[u8]>) -> anyhow::Result<()> {
+        let salts = self.tree("salts")?;
+        let evidence = self.tree("evidence")?;
+        let leaf_index = self.tree("leaf_index")?;
+        let attestations = self.tree("attestations")?;
+        let erasures = self.tree("erasures")?;
+        let sources = self.tree("evidence_sources")?;
+        let evidence_attrs = self.tree("evidence_attrs")?;
+        let evidence_order = self.tree("evidence_order")?;
+        let attrs = EvidenceIndex::open(self)?.get(record.pos)?;
+
+        let key = record.pos.to_be_bytes();
+        let attestation = self
+            .get_attestation(record.pos)?
+            .map(|attestation| StorageCodec::encode(&StoredAttestation { evidence_signature: Vec::new(), ..attestation }))
+            .transpose()?;
+        let encoded = StorageCodec::encode(record)?;
+
+        (&salts, &evidence, &leaf_index, &attestations, &erasures, &sources, &evidence_attrs, &evidence_order)
+            .transaction(|(salts, evidence, leaf_index, attestations, erasures, sources, evidence_attrs, evidence_order)| {
+                salts.remove(&key)?;
+                evidence.remove(&key)?;
+                sources.remove(&key)?;
+                evidence_index::remove_in_transaction(evidence_attrs, evidence_order, record.pos, attrs.as_ref())?;
+                if let Some(content_key) = content_key {
+                    leaf_index.remove(content_key)?;
+                }
+                if let Some(attestation) = &attestation {
+                    attestations.insert(&key, attestation.as_slice())?;
+                }
+                erasures.insert(&key, encoded.as_slice())?;
+                Ok(())
+            })
+            .map_err(|e: TransactionError<()>| anyhow::anyhow!("Erasure transaction failed: {:?}", e))?;
+        self.flush()
+    }
+
+    /// 读取指定位置的签名材料
+    pub fn get_attestation(&self, pos: u64) -> anyhow::Result<Option<StoredAttestation>> {
+        let attestations = self.tree("attestations")?;
+        attestations.get(pos.to_be_bytes())?
+            .map(|v| StorageCodec::decode(&v))
+            .transpose()
+    }
+
+    /// 读取最近一次已签名的根
+    pub fn get_last_signed_root(&self) -> anyhow::Result<Option<SignedRoot>> {
+        let meta = self.tree("meta")?;
+        meta.get(b"last_signed_root")?
+            .map(|v| StorageCodec::decode(&v))
+            .transpose()
+    }
+
+    /// 当前落盘记录使用的存储格式
+    ///
+    /// 没有格式标记但已有数据 (size 或 WAL) 的库，是引入存储编码之前写入的旧库。
+    /// 全新的库返回 None，由调用方直接标记为当前格式。
+    pub fn storage_format(&self) -> anyhow::Result<Option<StorageFormat>> {
+        let meta = self.tree("meta")?;
+        if let Some(tag) = meta.get(b"storage_format")? {
+            let tag = *tag.first().ok_or_else(|| anyhow::anyhow!("Empty storage format tag"))?;
+            return StorageFormat::from_tag(tag).map(Some);
+        }
+        if meta.contains_key(b"size")? || !self.tree("wal")?.is_empty() {
+            return Ok(Some(StorageFormat::LegacyBcs));
+        }
+        Ok(None)
+    }
+
+    /// 确保存储格式为当前版本：新库直接打标记，旧库就地转换
+    pub fn ensure_storage_format(&self) -> anyhow::Result<()> {
+        match self.storage_format()? {
+            Some(format) if format == StorageFormat::CURRENT => Ok(()),
+            Some(format) => {
+                let converted = self.migrate_storage_format(format)?;
+                info!("🔄 租户 '{}' 存储格式已转换: {:?} -> {:?}, 共 {} 条记录", self.tenant_id, format, StorageFormat::CURRENT, converted);
+                Ok(())
+            }
+            None => {
+                let meta = self.tree("meta")?;
+                meta.insert(b"storage_format", &[StorageFormat::CURRENT.tag()])?;
+                meta.flush()?;
+                Ok(())
+            }
+        }
+    }
+
+    /// 本租户的规范编码 (写在 `meta/canonical_encoding` 中)
+    ///
+    /// 没有标记的库是引入规范编码选项之前写入的，一律为 BCS。
+    pub fn canonical_encoding(&self) -> anyhow::Result<CanonicalEncoding> {
+        match self.tree("meta")?.get(b"canonical_encoding")? {
+            Some(tag) => CanonicalEncoding::from_tag(*tag.first().ok_or_else(|| anyhow::anyhow!("Empty canonical encoding tag"))?),
+            None => Ok(CanonicalEncoding::Bcs),
+        }
+    }
+
+    /// 确定本租户的规范编码：空库按配置打标记，已有数据的库必须与配置一致
+    ///
+    /// 规范编码决定叶子哈希，一旦有叶子入库就不能再改，否则新旧叶子无法用同一规则复核。
+    pub fn ensure_canonical_encoding(&self, configured: CanonicalEncoding) -> anyhow::Result<()> {
+        let meta = self.tree("meta")?;
+        let existing = match meta.get(b"canonical_encoding")? {
+            Some(_) => Some(self.canonical_encoding()?),
+            None if self.get_meta_size() > 0 || !self.tree("wal")?.is_empty() => Some(CanonicalEncoding::Bcs),
+            None => None,
+        };
+        match existing {
+            Some(encoding) if encoding != configured => Err(anyhow::anyhow!(
+                "Tenant '{}' already uses canonical encoding '{}', cannot switch to '{}'",
+                self.tenant_id,
+                encoding.as_str(),
+                configured.as_str()
+            )),
+            Some(_) => Ok(()),
+            None => {
+                meta.insert(b"canonical_encoding", &[configured.tag()])?;
+                meta.flush()?;
+                Ok(())
+            }
+        }
+    }
+
+    /// 存储格式转换器 (Storage Migration)
+    ///
+    /// 把签名材料、最近签名根与未完成的 WAL 从旧格式重新编码为当前格式，
+    /// 并在同一个事务中写入新的格式标记：中途崩溃不会留下新旧混杂的记录。
+    /// 证据原文本身就是规范字节，无需转换。返回转换的记录数。
+    pub fn migrate_storage_format(&self, from: StorageFormat) -> anyhow::Result<u64> {
+        let meta = self.tree("meta")?;
+        let attestations = self.tree("attestations")?;
+        let wal = self.tree("wal")?;
+
+        let converted_attestations = attestations
+            .iter()
+            .map(|entry| {
+                let (key, value) = entry?;
+                let attestation = StorageCodec::decode_as::<StoredAttestation>(from, &value)?;
+                Ok((key, StorageCodec::encode(&attestation)?))
+            })
+            .collect::<anyhow::Result<Vec<_>>>()?;
+        let converted_wal = wal
+            .iter()
+            .map(|entry| {
+                let (key, value) = entry?;
+                let record = StorageCodec::decode_as::<WalRecord>(from, &value)?;
+                Ok((key, StorageCodec::encode(&record)?))
+            })
+            .collect::<anyhow::Result<Vec<_>>>()?;
+        let converted_root = meta
+            .get(b"last_signed_root")?
+            .map(|v| StorageCodec::encode(&StorageCodec::decode_as::<SignedRoot>(from, &v)?))
+            .transpose()?;
+
+        (&meta, &attestations, &wal)
+            .transaction(|(meta, attestations, wal)| {
+                for (key, value) in &converted_attestations {
+                    attestations.insert(key, value.as_slice())?;
+                }
+                for (key, value) in &converted_wal {
+                    wal.insert(key, value.as_slice())?;
+                }
+                if let Some(root) = &converted_root {
+                    meta.insert(b"last_signed_root", root.as_slice())?;
+                }
+                meta.insert(b"storage_format", &[StorageFormat::CURRENT.tag()])?;
+                Ok(())
+            })
+            .map_err(|e: TransactionError<()>| anyhow::anyhow!("Storage migration transaction failed: {:?}", e))?;
+        self.flush()?;
+
+        Ok((converted_attestations.len() + converted_wal.len() + converted_root.iter().count()) as u64)
+    }
+
+    /// 按内容哈希 (即证据 ID，Blake3(规范字节)) 查找已分配的序列号 (重放检测、按 ID 查找)
+    ///
+    /// 无盐叶子的内容哈希就是叶子哈希。
+    pub fn seq_of_leaf(&self, content_hash: &[u8; 32]) -> anyhow::Result<Option<u64>> {
+        Self::lookup_u64(&self.tree("leaf_index")?, content_hash)
+    }
+
+    /// 反查某个序列号在内容索引中的键 (擦除时使用，需遍历索引)
+    pub fn content_key_of_seq(&self, seq: u64) -> anyhow::Result<Option<Vec<u8>>> {
+        for entry in self.tree("leaf_index")?.iter() {
+            let (key, value) = entry?;
+            if value.as_ref() == seq.to_be_bytes() {
+                return Ok(Some(key.to_vec()));
+            }
+        }
+        Ok(None)
+    }
+
+    /// 反查某个位置的序列号 (擦除时使用，需遍历索引)
+    pub fn seq_of_pos(&self, pos: u64) -> anyhow::Result<Option<u64>> {
+        for entry in self.tree("seq_index")?.iter() {
+            let (key, value) = entry?;
+            if value.as_ref() == pos.to_be_bytes() {
+                return Ok(Some(u64::from_be_bytes(key.as_ref().try_into()?)));
+            }
+        }
+        Ok(None)
+    }
+
+    /// 按客户端 nonce 查找已分配的序列号 (重放检测)
+    pub fn seq_of_nonce(&self, nonce: &str) -> anyhow::Result<Option<u64>> {
+        Self::lookup_u64(&self.tree("nonces")?, nonce.as_bytes())
+    }
+
+    /// 按序列号查找叶子位置
+    pub fn pos_of_seq(&self, seq: u64) -> anyhow::Result<Option<u64>> {
+        Self::lookup_u64(&self.tree("seq_index")?, &seq.to_be_bytes())
+    }
+
+    fn lookup_u64(tree: &sled::Tree, key: &[u8]) -> anyhow::Result<Option<u64>> {
+        Ok(tree.get(key)?.map(|v| {
+            let arr: [u8; 8] = v.as_ref().try_into().unwrap_or([0; 8]);
+            u64::from_be_bytes(arr)
+        }))
+    }
+
     pub fn flush(&self) -> anyhow::Result<()> {
         self.db.flush()?;
         Ok(())
     }
 
+    /// 追加完成后的落盘：`durable` 模式立即 fsync，`fast` 模式交给后台刷盘
+    pub fn sync_append(&self) -> anyhow::Result<()> {
+        match self.durability {
+            Durability::Durable => self.flush(),
+            Durability::Fast => Ok(()),
+        }
+    }
+
     pub fn register_model(&self, hash: &str, description: &str) -> anyhow::Result<()> {
-        let tree = self.db.open_tree("models_allowlist")?;
+        let tree = self.tree("models_allowlist")?;
         tree.insert(hash, description)?;
         tree.flush()?;
         Ok(())
     }
 
     pub fn is_model_authorized(&self, hash: &str) -> bool {
-        if let Ok(tree) = self.db.open_tree("models_allowlist") {
+        if let Ok(tree) = self.tree("models_allowlist") {
             return tree.contains_key(hash).unwrap_or(false);
         }
         false
     }
+
+    /// 注册模型时登记的说明；未注册为 None
+    pub fn model_description(&self, hash: &str) -> anyhow::Result<Option<String>> {
+        self.tree("models_allowlist")?
+            .get(hash)?
+            .map(|v| String::from_utf8(v.to_vec()).map_err(|e| anyhow::anyhow!("Invalid model description: {}", e)))
+            .transpose()
+    }
+
+    /// 记下提交时的来源说明 (不签名，只用于检索；擦除时一并删除)
+    pub fn set_source(&self, pos: u64, source: &str) -> anyhow::Result<()> {
+        self.tree("evidence_sources")?.insert(pos.to_be_bytes(), source.as_bytes())?;
+        Ok(())
+    }
+
+    /// 提交时的来源说明
+    pub fn get_source(&self, pos: u64) -> anyhow::Result<Option<String>> {
+        self.tree("evidence_sources")?
+            .get(pos.to_be_bytes())?
+            .map(|v| String::from_utf8(v.to_vec()).map_err(|e| anyhow::anyhow!("Invalid source description at pos {}: {}", pos, e)))
+            .transpose()
+    }
 }
 
 // 为引用类型实现 MMRStore
@@ -72,7 +782,7 @@ impl SledStore {
 impl MMRStore<[u8; 32]> for &SledStore {
     fn get_elem(&self, pos: u64) -> MMRResult<Option<[u8; 32]>> {
         let key = pos.to_be_bytes();
-        match self.db.get(key) {
+        match self.nodes.get(key) {
             Ok(Some(v)) => {
                 if v.len() != 32 {
                     return Err(MMRError::StoreError("Invalid data length in DB".to_string()));
@@ -93,69 +803,1666 @@ impl MMRStore<[u8; 32]> for &SledStore {
             batch.insert(&current_pos.to_be_bytes(), &elem);
             current_pos += 1;
         }
-        self.db.apply_batch(batch).map_err(|e| MMRError::StoreError(e.to_string()))
+        self.nodes.apply_batch(batch).map_err(|e| MMRError::StoreError(e.to_string()))
+    }
+}
+
+/// 预写日志记录 (Write-Ahead Log Record)
+///
+/// 一次追加的完整意图。崩溃重启后凭它即可把追加“重做”一遍 (roll forward)，
+/// 或者在与当前状态不符时安全丢弃。
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WalRecord {
+    pub seq: u64,
+    pub pos: u64,
+    pub leaf_hash: [u8; 32],
+    /// 追加前的 MMR 大小，只有与当前大小一致时才允许重做
+    pub prev_size: u64,
+    pub new_size: u64,
+    /// 预期的追加后根，重做后必须一致
+    pub new_root: [u8; 32],
+    pub nonce: Option<String>,
+    /// 证据原文 (规范字节)
+    pub evidence_bytes: Vec<u8>,
+    /// 签名材料；与证据原文同批落盘，保证“已签名”与“已入库”同生共死
+    pub attestation: Option<StoredAttestation>,
+    /// 叶子盐值；加盐叶子的叶子哈希为 Blake3(salt || evidence_bytes)
+    pub salt: Option<[u8; 32]>,
+    /// 盲化叶子 (见 [`CommitmentMode::Blinded`])：原文与盐值都不落盘，`evidence_bytes` 为空、`salt` 为 None
+    pub blinded: bool,
+}
+
+/// 持久化的签名材料 (Stored Attestation)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StoredAttestation {
+    /// 对证据规范字节的签名
+    pub evidence_signature: Vec<u8>,
+    pub receipt: Receipt,
+    /// 对回执规范字节的签名
+    pub receipt_signature: Vec<u8>,
+}
+
+impl WalRecord {
+    /// 内容哈希 Blake3(evidence_bytes)，重放检测索引的键 (无盐叶子即叶子哈希)
+    fn content_hash(&self) -> [u8; 32] {
+        match self.salt {
+            Some(_) => evidence_id_of(&self.evidence_bytes),
+            None => self.leaf_hash,
+        }
+    }
+}
+
+/// 擦除记录 (Erasure Record)
+///
+/// 密码学擦除的审计留痕：谁、何时、为何擦除了哪个位置。记录本身不含任何证据内容。
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ErasureRecord {
+    pub pos: u64,
+    pub seq: u64,
+    /// 擦除时间 (Unix 秒)
+    pub erased_at: i64,
+    /// 发起擦除的主体 (API Key 指纹，见 `tenant::principal_of`)
+    pub principal: String,
+    pub reason: String,
+}
+
+/// 批量证明：一条合并的证明路径及其覆盖的叶子 (按位置升序)
+pub struct BatchProof {
+    pub proof: ckb_merkle_mountain_range::MerkleProof<[u8; 32], MergeBlake3>,
+    /// (位置, 叶子哈希)
+    pub leaves: Vec<(u64, [u8; 32])>,
+}
+
+/// 签发方 (Issuer)：签发一条回执所用的密钥及其上下文
+pub struct Issuer<'a> {
+    pub signer: &'a EvidenceSigner,
+    /// `signer` 的派生路径，会写入回执；使用租户根密钥时为空串
+    pub key_path: &'a str,
+    /// 签发时服务器时钟是否未通过校验
+    pub clock_uncertain: bool,
+    /// 发起请求的主体，签名之前连同证据哈希与签名公钥记入签名日志
+    pub principal: &'a str,
+}
+
+/// 一次完整存证的结果 (Attested Entry)
+#[derive(Debug, Clone)]
+pub struct AttestedEntry {
+    pub outcome: AppendOutcome,
+    pub evidence_signature: Signature,
+    pub receipt: Receipt,
+    pub receipt_signature: Signature,
+}
+
+/// 已签名的根 (Signed Root)
+///
+/// 随每次签名存证一起原子写入，作为崩溃恢复后的校验基准。
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SignedRoot {
+    pub root: [u8; 32],
+    pub tree_size: u64,
+    pub signature: Vec<u8>,
+}
+
+/// 一次追加的结果 (Append Outcome)
+///
+/// 回执签名需要的全部信息：叶子哈希、位置、追加后的根与树大小。
+#[derive(Debug, Clone, Copy)]
+pub struct AppendOutcome {
+    pub leaf_hash: [u8; 32],
+    pub pos: u64,
+    pub root: [u8; 32],
+    pub mmr_size: u64,
+    /// 服务端分配的单调序列号 (从 0 开始，与叶子序号一致)
+    pub seq: u64,
+    /// 叶子盐值 (仅加盐叶子)
+    pub salt: Option<[u8; 32]>,
+}
+
+/// 批量追加结果
+#[derive(Debug, Clone)]
+pub struct BatchOutcome {
+    /// 按输入顺序排列的叶子哈希
+    pub leaf_hashes: Vec<[u8; 32]>,
+    /// 按输入顺序排列的叶子位置
+    pub positions: Vec<u64>,
+    /// 整批追加完成后的根
+    pub root: [u8; 32],
+    pub mmr_size: u64,
+    /// 第一条证据的序列号，其余依次递增
+    pub first_seq: u64,
+    /// 按输入顺序排列的叶子盐值 (仅加盐叶子)
+    pub salts: Vec<Option<[u8; 32]>>,
+}
+
+/// 叶子原文是否为日志叶子 (签名日志或运维日志的检查点、保管记录、复核记录)，而不是证据
+fn is_log_leaf(bytes: &[u8]) -> anyhow::Result<bool> {
+    Ok(SigningCheckpoint::from_bytes(bytes)?.is_some()
+        || OpsCheckpoint::from_bytes(bytes)?.is_some()
+        || CustodyRecord::from_bytes(bytes)?.is_some()
+        || ReviewRecord::from_bytes(bytes)?.is_some())
+}
+
+
+/// 山峰缓存 (Peak Cache)
+///
+/// MMR 的根只取决于各山峰的哈希。写端在内存中维护当前山峰 (高度, 哈希)，
+/// 每次 push 只需合并同高度的山峰 (均摊 O(1))，新根在内存中打包得到，
+/// 读取当前根为 O(1)，无需再从 sled 逐个读取山峰。
+#[derive(Debug, Clone, Default)]
+pub struct PeakCache {
+    /// 从左到右 (从高到低) 排列的山峰
+    peaks: Vec<(u32, [u8; 32])>,
+    root: Option<[u8; 32]>,
+}
+
+impl PeakCache {
+    /// 从存储中加载指定大小时的山峰
+    pub fn load(store: &SledStore, mmr_size: u64) -> anyhow::Result<Self> {
+        if mmr_size == 0 {
+            return Ok(Self::default());
+        }
+        let peaks = get_peaks(mmr_size)
+            .into_iter()
+            .map(|pos| {
+                let hash = store.get_elem(pos)
+                    .map_err(|e| anyhow::anyhow!("MMR get_elem error: {}", e))?
+                    .ok_or_else(|| anyhow::anyhow!("Missing peak at pos {}", pos))?;
+                Ok((pos_height_in_tree(pos), hash))
+            })
+            .collect::<anyhow::Result<Vec<_>>>()?;
+        let mut this = Self { peaks, root: None };
+        this.root = this.bag()?;
+        Ok(this)
+    }
+
+    /// 追加一个叶子，返回新根
+    ///
+    /// 与 `MMR::push` 相同：新叶子成为高度 0 的山峰，
+    /// 只要最右两座山峰高度相同就合并为 `merge(左, 右)`。
+    pub fn push(&mut self, leaf: [u8; 32]) -> anyhow::Result<[u8; 32]> {
+        let mut height = 0;
+        let mut hash = leaf;
+        while let Some(&(left_height, left)) = self.peaks.last() {
+            if left_height != height {
+                break;
+            }
+            self.peaks.pop();
+            hash = MergeBlake3::merge(&left, &hash).map_err(|e| anyhow::anyhow!("MMR merge error: {}", e))?;
+            height += 1;
+        }
+        self.peaks.push((height, hash));
+        let root = self.bag()?.expect("peaks are non-empty after push");
+        self.root = Some(root);
+        Ok(root)
+    }
+
+    /// 当前根 (空树为 None)
+    pub fn root(&self) -> Option<[u8; 32]> {
+        self.root
+    }
+
+    /// 从右向左打包山峰，与 `MMR::get_root` 的 bag_rhs_peaks 一致
+    fn bag(&self) -> anyhow::Result<Option<[u8; 32]>> {
+        let mut iter = self.peaks.iter().rev().map(|(_, hash)| *hash);
+        let Some(mut acc) = iter.next() else {
+            return Ok(None);
+        };
+        for left in iter {
+            acc = MergeBlake3::merge_peaks(&acc, &left).map_err(|e| anyhow::anyhow!("MMR merge error: {}", e))?;
+        }
+        Ok(Some(acc))
+    }
+}
+
+/// 存储调优参数 (Store Options)
+#[derive(Debug, Clone)]
+pub struct StoreOptions {
+    /// 证明缓存容量 (条目数)，0 表示关闭
+    pub proof_cache_capacity: usize,
+    /// 证据读缓存容量 (证据原文与签名材料各自的条目数)，0 表示关闭
+    pub evidence_cache_capacity: usize,
+    /// 每追加多少条证据，用库函数重算一次根与山峰缓存比对，0 表示关闭
+    pub peak_check_interval: u64,
+    /// 每签名多少次，把签名日志链头作为检查点写进 MMR，0 表示关闭
+    pub signing_checkpoint_interval: u64,
+    /// 新租户使用的规范编码 (已有数据的租户必须与之一致)
+    pub canonical_encoding: CanonicalEncoding,
+    /// 新叶子是否加盐 (可随时切换，每个叶子以自己是否有盐值为准)
+    pub salted_leaves: bool,
+    /// 写入围栏 (主备切换，见 `failover.rs`)：关闭时拒绝追加，打开时拒绝应用复制来的叶子
+    pub fence: WriteFence,
+    /// 签名模型注册表的受信根 (见 `model_registry.rs`)，None 表示不启用签名模式
+    pub trusted_registry_root: Option<SignedRootMetadata>,
+}
+
+/// 叶子承诺方式 (存证请求的 `commitment` 字段)
+///
+/// - `default`: 按租户配置 (`SALTED_LEAVES`)，无盐或加盐，盐值由服务端保存并随 `/evidence`、证据包返回；
+/// - `blinded`: 盲化叶子。叶子为 Blake3(盐值 || 规范字节)，盐值只在本次存证响应中返回，
+///   服务端不保存盐值、原文与内容索引，只留叶子哈希与回执。日志对审计方、副本只暴露一个随机的承诺，
+///   提交方日后凭原文与盐值自行决定向谁证明包含性。
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum CommitmentMode {
+    #[default]
+    Default,
+    Blinded,
+}
+
+impl Default for StoreOptions {
+    fn default() -> Self {
+        Self {
+            proof_cache_capacity: 1024,
+            evidence_cache_capacity: 4096,
+            peak_check_interval: 1000,
+            signing_checkpoint_interval: 100,
+            canonical_encoding: CanonicalEncoding::Bcs,
+            salted_leaves: false,
+            fence: WriteFence::open(),
+            trusted_registry_root: None,
+        }
     }
 }
 
 /// 证据仓库 (Evidence Store)
+///
+/// 写端：只允许一个持有者 (见 `writer` 模块的单写者 Actor)。
+/// 读端请通过 [`EvidenceStore::reader`] 获取无锁只读句柄。
 pub struct EvidenceStore {
     store: SledStore,
     mmr_size: u64,
+    next_seq: u64,
+    /// 已提交 (对读端可见) 的 MMR 大小
+    committed_size: Arc<AtomicU64>,
+    /// 与读端共享的证明缓存
+    proof_cache: Arc<ProofCache>,
+    /// 与读端共享的证据读缓存
+    read_cache: Arc<EvidenceReadCache>,
+    /// 当前山峰与根
+    peaks: PeakCache,
+    peak_check_interval: u64,
+    /// 签名审计日志
+    signing_log: SigningLog,
+    signing_checkpoint_interval: u64,
+    /// 叶子哈希与签名所用的规范编码
+    canonical_encoding: CanonicalEncoding,
+    /// 新叶子是否加盐
+    salted_leaves: bool,
+    /// 诉讼保全 (保全中的条目不清理、不擦除)
+    legal_holds: LegalHolds,
+    /// 证据访问控制列表 (审计方读取原文的授权)
+    acls: EvidenceAcls,
+    /// 证据标签 (签名内容之外，可随时修改)
+    tags: EvidenceTags,
+    /// 保管事件索引 (按证据查找其保管记录叶子)
+    custody: CustodyLog,
+    /// 复核记录索引 (按证据查找其复核记录叶子)
+    reviews: ReviewLog,
+    /// Prompt 说明对照表 (按模型版本)
+    prompts: PromptRegistry,
+    /// 签名的模型注册表 (引导受信根后，白名单只随签名的目标元数据变更)
+    registry: ModelRegistry,
+    /// 激活路径异常标记 (后台检测写入，管理员复核)
+    anomalies: AnomalyLog,
+    /// 写入围栏
+    fence: WriteFence,
+}
+
+/// 已在内存中完成、尚未写盘的一次追加
+struct StagedAppend<'a> {
+    mmr: MMR<[u8; 32], MergeBlake3, &'a SledStore>,
+    pos: u64,
+    new_size: u64,
+    root: [u8; 32],
+    peaks: PeakCache,
 }
 
 impl EvidenceStore {
     /// 初始化仓库 (加载 DB)
     pub fn new(db_path: &str) -> Self {
         let store = SledStore::new(db_path).expect("Failed to open Sled DB");
+        Self::open(store, &StoreOptions::default())
+    }
+
+    /// 基于已打开的存储 (可能是某个租户的命名空间) 初始化仓库
+    ///
+    /// 打开数据库后会先执行崩溃恢复：重放未完成的 WAL，并用最近一次签名的根复核状态。
+    pub fn open(store: SledStore, options: &StoreOptions) -> Self {
+        store.ensure_storage_format().expect("Storage format migration failed");
+        store.ensure_canonical_encoding(options.canonical_encoding).expect("Canonical encoding mismatch");
+        match store.ensure_evidence_encryption().expect("Evidence encryption check failed") {
+            0 => {}
+            sealed => info!("🔒 租户 '{}' 已补加密 {} 条证据原文", store.tenant_id(), sealed),
+        }
+        match store.ensure_evidence_index().expect("Evidence index backfill failed") {
+            0 => {}
+            built => info!("🗂️  租户 '{}' 已为 {} 条证据补建排序与筛选索引", store.tenant_id(), built),
+        }
         let mmr_size = store.get_meta_size();
-        
-        println!("📚 MMR Store Loaded. Size: {}", mmr_size);
+        // 旧库没有序列号计数器时，从已有叶子数量继续编号，保证 seq 与叶子序号一致
+        let next_seq = store.get_meta_next_seq().unwrap_or_else(|| leaf_count(mmr_size));
+        let signing_log = SigningLog::open(&store).expect("Failed to open signing log");
+        let legal_holds = LegalHolds::open(&store).expect("Failed to open legal holds");
+        let acls = EvidenceAcls::open(&store).expect("Failed to open evidence ACLs");
+        let tags = EvidenceTags::open(&store).expect("Failed to open evidence tags");
+        let custody = CustodyLog::open(&store).expect("Failed to open custody log");
+        let reviews = ReviewLog::open(&store).expect("Failed to open examiner reviews");
+        let prompts = PromptRegistry::open(&store).expect("Failed to open prompt registry");
+        let registry = ModelRegistry::open(&store).expect("Failed to open model registry");
+        if let Some(trusted) = &options.trusted_registry_root {
+            if registry.bootstrap(trusted).expect("Model registry bootstrap failed") {
+                info!("🛡️  租户 '{}' 的模型注册表已以受信根 v{} 引导", store.tenant_id(), trusted.signed.version);
+            }
+        }
+        let anomalies = AnomalyLog::open(&store).expect("Failed to open anomaly flags");
 
-        Self {
+        let mut this = Self {
             store,
             mmr_size,
+            next_seq,
+            committed_size: Arc::new(AtomicU64::new(mmr_size)),
+            proof_cache: Arc::new(ProofCache::new(options.proof_cache_capacity)),
+            read_cache: Arc::new(EvidenceReadCache::new(options.evidence_cache_capacity)),
+            peaks: PeakCache::default(),
+            peak_check_interval: options.peak_check_interval,
+            signing_log,
+            signing_checkpoint_interval: options.signing_checkpoint_interval,
+            canonical_encoding: options.canonical_encoding,
+            salted_leaves: options.salted_leaves,
+            legal_holds,
+            acls,
+            tags,
+            custody,
+            reviews,
+            prompts,
+            registry,
+            anomalies,
+            fence: options.fence.clone(),
+        };
+        this.recover().expect("Crash recovery failed");
+        let (entries, checkpoints) = this.signing_log
+            .verify(&this.store, this.mmr_size)
+            .expect("Signing log verification failed");
+        if entries > 0 {
+            info!("🧾 签名日志校验通过: {} 条记录, {} 个检查点", entries, checkpoints);
+        }
+        this.peaks = PeakCache::load(&this.store, this.mmr_size).expect("Failed to load MMR peaks");
+        this.publish();
+
+        info!("📚 MMR Store Loaded. Size: {}, Next Seq: {}", this.mmr_size, this.next_seq);
+        this
+    }
+
+    /// 崩溃恢复 (Crash Recovery)
+    ///
+    /// 1. 丢弃无法解码的残缺 WAL 记录；
+    /// 2. 对每条未完成的 WAL：若其 `prev_size` 与当前大小一致，则重新 push 叶子、
+    ///    核对根与记录一致后提交 (roll forward)；否则说明已过期，直接丢弃。
+    /// 3. 恢复完成后，重新计算最近一次签名时的根，与落盘的签名根比对。
+    fn recover(&mut self) -> anyhow::Result<()> {
+        match self.store.wal_discard_torn()? {
+            0 => {}
+            torn => info!("🧹 丢弃 {} 条残缺的 WAL 记录", torn),
+        }
+        for record in self.store.wal_pending()? {
+            if record.prev_size != self.mmr_size || record.seq != self.next_seq {
+                info!("🧹 丢弃过期 WAL 记录: Seq={}", record.seq);
+                self.store.wal_discard(record.seq)?;
+                continue;
+            }
+
+            let mut mmr = MMR::<[u8; 32], MergeBlake3, _>::new(self.mmr_size, &self.store);
+            let pos = mmr.push(record.leaf_hash).map_err(|e| anyhow::anyhow!("MMR append error: {}", e))?;
+            let root = mmr.get_root().map_err(|e| anyhow::anyhow!("MMR get_root error: {}", e))?;
+            if pos != record.pos || mmr.mmr_size() != record.new_size || root != record.new_root {
+                info!("🧹 WAL 记录与重做结果不一致，丢弃: Seq={}", record.seq);
+                self.store.wal_discard(record.seq)?;
+                continue;
+            }
+            mmr.commit().map_err(|e| anyhow::anyhow!("MMR commit error: {}", e))?;
+            self.store.commit_append(std::slice::from_ref(&record))?;
+            self.store.flush()?;
+
+            info!("♻️  WAL 重做完成: Seq={}, Pos={}", record.seq, record.pos);
+            self.mmr_size = record.new_size;
+            self.next_seq = record.seq + 1;
+        }
+
+        if let Some(signed) = self.store.get_last_signed_root()? {
+            let snapshot = StoreSnapshot {
+                store: self.store.clone(),
+                mmr_size: self.mmr_size,
+                proof_cache: self.proof_cache.clone(),
+                read_cache: self.read_cache.clone(),
+                canonical_encoding: self.canonical_encoding,
+            };
+            let root = snapshot.root_at(signed.tree_size)?;
+            if root != signed.root {
+                return Err(anyhow::anyhow!(
+                    "Recovered state diverges from last signed root at size {}: expected {}, got {}",
+                    signed.tree_size,
+                    hex::encode(signed.root),
+                    hex::encode(root)
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    /// 核心功能：签名并入库 (Attest)
+    ///
+    /// 规范化字节、证据签名、回执签名、证据原文落盘、MMR 追加视为一个事务：
+    /// 1. 在内存中完成 MMR push，得到位置与新根 (尚未写盘)；
+    /// 2. 基于同一份规范化字节签名证据，并签发回执；
+    /// 3. 将以上全部材料写入 WAL，再落盘 MMR 节点与元数据。
+    ///
+    /// 任何一步失败都会回滚 (丢弃 WAL，内存中的 size 不前进)，签名不会离开本函数，
+    /// 因此不存在“已签名但未入库”的证据。
+    ///
+    /// `supersedes` 为本条所更正的早先位置，写入回执 (见 [`EvidenceStore::check_supersedes`])。
+    /// `commitment` 为盲化时，盐值只随返回值交给调用方，原文与盐值都不落盘 (见 [`CommitmentMode`])。
+    pub fn attest(
+        &mut self,
+        evidence: &Evidence,
+        nonce: Option<&str>,
+        supersedes: Option<u64>,
+        commitment: CommitmentMode,
+        issuer: &Issuer<'_>,
+    ) -> anyhow::Result<AttestedEntry> {
+        let precheck = telemetry::stage("store.precheck");
+        let (evidence_bytes, content_hash, seq) = self.precheck(evidence, nonce)?;
+        if let Some(superseded) = supersedes {
+            self.check_supersedes(superseded)?;
+        }
+        let blinded = commitment == CommitmentMode::Blinded;
+        let (leaf_hash, salt) = self.salt_leaf(&evidence_bytes, content_hash, blinded);
+        self.check_peaks(seq)?;
+        self.fence.check()?;
+        drop(precheck);
+
+        // 签名日志先于回执落盘 (durable 模式下 fsync)，随后在内存中追加并签名
+        let sign = telemetry::stage("store.sign");
+        let Issuer { signer, key_path, clock_uncertain, principal } = *issuer;
+        let timestamp = chrono::Utc::now().timestamp();
+        self.signing_log.append(
+            leaf_hash,
+            &signer.public_key(),
+            key_path,
+            timestamp,
+            principal,
+            self.store.durability() == Durability::Durable,
+        )?;
+        let StagedAppend { mmr, pos, new_size, root, peaks } = self.stage(leaf_hash)?;
+
+        let evidence_signature = signer.sign_bytes(&evidence_bytes)?;
+        let receipt = Receipt {
+            evidence_hash: hex::encode(leaf_hash),
+            leaf_pos: pos,
+            root: hex::encode(root),
+            tree_size: new_size,
+            timestamp,
+            seq,
+            clock_uncertain,
+            tenant_id: self.store.tenant_id().to_string(),
+            key_path: key_path.to_string(),
+            encoding: self.canonical_encoding,
+            supersedes,
+        };
+        let receipt_signature = signer.sign_receipt(&receipt)?;
+        drop(sign);
+
+        let append = telemetry::stage("store.append");
+        append.set_attribute("yuanjing.leaf_pos", pos as i64);
+        let record = WalRecord {
+            seq,
+            pos,
+            leaf_hash,
+            prev_size: self.mmr_size,
+            new_size,
+            new_root: root,
+            nonce: nonce.map(str::to_string),
+            evidence_bytes: if blinded { Vec::new() } else { evidence_bytes },
+            attestation: Some(StoredAttestation {
+                evidence_signature: evidence_signature.to_bytes().to_vec(),
+                receipt: receipt.clone(),
+                receipt_signature: receipt_signature.to_bytes().to_vec(),
+            }),
+            salt: salt.filter(|_| !blinded),
+            blinded,
+        };
+        Self::persist(&self.store, mmr, std::slice::from_ref(&record))?;
+        self.mmr_size = new_size;
+        self.next_seq = seq + 1;
+        self.peaks = peaks;
+        self.publish();
+
+        // 按持久化策略落盘 (durable 模式下返回前 fsync)
+        self.store.sync_append()?;
+        drop(append);
+
+        Ok(AttestedEntry {
+            outcome: AppendOutcome { leaf_hash, pos, root, mmr_size: new_size, seq, salt },
+            evidence_signature,
+            receipt,
+            receipt_signature,
+        })
+    }
+
+    /// 更正链校验：只能更正已签发回执的叶子，且必须是链上最新的一条
+    ///
+    /// 每条叶子至多被取代一次，更正链因此是一条单链，链尾即当前有效的结论。
+    pub fn check_supersedes(&self, pos: u64) -> anyhow::Result<()> {
+        if pos >= self.mmr_size || pos_height_in_tree(pos) != 0 || self.store.get_attestation(pos)?.is_none() {
+            return Err(anyhow::anyhow!("Cannot supersede pos {}: no attested evidence there", pos));
+        }
+        if let Some(newer) = self.store.superseded_by(pos)? {
+            return Err(anyhow::anyhow!("Cannot supersede pos {}: already superseded by pos {}", pos, newer));
+        }
+        Ok(())
+    }
+
+    /// 证据入库 (不签名)
+    ///
+    /// `nonce` 为客户端提供的一次性提交标识 (可选)。
+    /// 同一份证据 (叶子哈希相同) 或同一个 nonce 再次提交会被判定为重放并拒绝。
+    /// 对外签发回执的路径请使用 [`EvidenceStore::attest`]。
+    pub fn append(&mut self, evidence: &Evidence, nonce: Option<&str>) -> anyhow::Result<AppendOutcome> {
+        let (evidence_bytes, content_hash, seq) = self.precheck(evidence, nonce)?;
+        let (leaf_hash, salt) = self.salt_leaf(&evidence_bytes, content_hash, false);
+        self.append_leaf(evidence_bytes, leaf_hash, salt, seq, nonce)
+    }
+
+    /// 无盐模式下叶子哈希即内容哈希；加盐模式 (或盲化叶子) 下生成随机盐值，叶子哈希为 Blake3(salt || 规范字节)
+    fn salt_leaf(&self, evidence_bytes: &[u8], content_hash: [u8; 32], blinded: bool) -> ([u8; 32], Option<[u8; 32]>) {
+        if !self.salted_leaves && !blinded {
+            return (content_hash, None);
+        }
+        let mut salt = [0u8; 32];
+        rand::rngs::OsRng.fill_bytes(&mut salt);
+        (leaf_hash_of(evidence_bytes, Some(&salt)), Some(salt))
+    }
+
+    /// 密码学擦除 (Cryptographic Erasure)
+    ///
+    /// 只适用于加盐叶子：销毁盐值并删除原文、内容索引与证据签名，写入擦除记录。
+    /// 此后即使有人持有原文，也无法算出该叶子的哈希，叶子与个人数据之间的关联随之断开；
+    /// 叶子哈希、MMR 节点、回执与签名根全部保留，树的结构和历史根不受影响。
+    /// 无盐叶子的哈希由原文直接算出，删除原文并不能断开关联，因此拒绝擦除。
+    pub fn erase(&mut self, pos: u64, principal: &str, reason: &str) -> anyhow::Result<ErasureRecord> {
+        if self.store.get_erasure(pos)?.is_some() {
+            return Err(anyhow::anyhow!("Evidence at pos {} is already erased", pos));
+        }
+        if self.legal_holds.is_held(pos)? {
+            return Err(anyhow::anyhow!("Evidence at pos {} is under legal hold and cannot be erased", pos));
+        }
+        if self.store.is_blinded(pos)? {
+            return Err(anyhow::anyhow!("Leaf at pos {} is blinded: the log holds neither its evidence nor its salt", pos));
+        }
+        if self.store.get_salt(pos)?.is_none() {
+            return Err(anyhow::anyhow!("Leaf at pos {} is not salted and cannot be cryptographically erased", pos));
+        }
+        let seq = self.store.seq_of_pos(pos)?
+            .ok_or_else(|| anyhow::anyhow!("No sequence number recorded for pos {}", pos))?;
+        let content_key = self.store.content_key_of_seq(seq)?;
+
+        let record = ErasureRecord {
+            pos,
+            seq,
+            erased_at: chrono::Utc::now().timestamp(),
+            principal: principal.to_string(),
+            reason: reason.to_string(),
+        };
+        self.store.erase_evidence(&record, content_key.as_deref())?;
+        self.read_cache.invalidate(pos);
+        Ok(record)
+    }
+
+    /// 保留期清理 (Retention Pruning)
+    ///
+    /// 删除签发时间早于 `cutoff` (Unix 秒) 的证据原文，保留“可证明”所需的一切：
+    /// MMR 节点 (叶子哈希就是其中的叶子节点)、签名材料 (回执与签名) 与签名根都不动，
+    /// 历史回执的包含性证明照常成立，读取时返回“已清理但可证明”。
+    /// 未签名入库的条目以证据自身的 `timestamp` 为准；日志叶子 (检查点、保管与复核记录) 与诉讼保全中的条目不清理。
+    /// 返回本次清理的条数。
+    pub fn prune_expired(&mut self, cutoff: i64) -> anyhow::Result<u64> {
+        let mut expired = Vec::new();
+        for entry in self.store.tree("evidence")?.iter() {
+            let (key, blob) = entry?;
+            let pos = u64::from_be_bytes(key.as_ref().try_into()?);
+            let bytes = self.store.open_evidence(pos, &blob)?;
+            if is_log_leaf(&bytes)? || self.legal_holds.is_held(pos)? {
+                continue;
+            }
+            let issued_at = match self.store.get_attestation(pos)? {
+                Some(attestation) => attestation.receipt.timestamp,
+                None => Evidence::from_canonical_bytes(self.canonical_encoding, &bytes)?.timestamp,
+            };
+            if issued_at < cutoff {
+                expired.push(pos);
+            }
+        }
+        if expired.is_empty() {
+            return Ok(0);
+        }
+
+        self.store.prune_evidence(&expired, chrono::Utc::now().timestamp())?;
+        for pos in &expired {
+            self.read_cache.invalidate(*pos);
+        }
+        Ok(expired.len() as u64)
+    }
+
+    /// 完整性检查 (见 `integrity.rs`)；在写线程中执行，检查期间不会有追加
+    pub fn check_integrity(&self) -> anyhow::Result<IntegrityReport> {
+        integrity::check(&self.store)
+    }
+
+    /// 设置诉讼保全：只能针对已入库、未擦除的叶子
+    pub fn place_hold(&mut self, pos: u64, principal: &str, reason: &str) -> anyhow::Result<LegalHold> {
+        if pos >= self.mmr_size || pos_height_in_tree(pos) != 0 {
+            return Err(anyhow::anyhow!("No evidence at pos {}", pos));
+        }
+        if self.store.get_erasure(pos)?.is_some() {
+            return Err(anyhow::anyhow!("Evidence at pos {} is already erased", pos));
+        }
+        self.legal_holds.place(pos, principal, reason, chrono::Utc::now().timestamp())
+    }
+
+    /// 解除诉讼保全
+    pub fn release_hold(&mut self, pos: u64, principal: &str, reason: &str) -> anyhow::Result<HoldEvent> {
+        self.legal_holds.release(pos, principal, reason, chrono::Utc::now().timestamp())
+    }
+
+    /// 替换证据的访问控制列表：只能针对已入库的叶子
+    pub fn set_acl(&mut self, pos: u64, auditors: Vec<String>, principal: &str) -> anyhow::Result<EvidenceAcl> {
+        if pos >= self.mmr_size || pos_height_in_tree(pos) != 0 {
+            return Err(anyhow::anyhow!("No evidence at pos {}", pos));
+        }
+        self.acls.set(pos, auditors, principal, chrono::Utc::now().timestamp())
+    }
+
+    /// 记下刚签名入库的证据的来源说明 (空串不记)
+    pub fn record_source(&self, pos: u64, source: &str) -> anyhow::Result<()> {
+        if source.is_empty() {
+            return Ok(());
+        }
+        self.store.set_source(pos, source)?;
+        self.store.sync_append()
+    }
+
+    /// 替换证据的标签：只能针对已签名入库的证据 (标签须已规范化)
+    pub fn set_tags(&mut self, pos: u64, tags: Vec<String>, principal: &str) -> anyhow::Result<EvidenceTagSet> {
+        if pos >= self.mmr_size || pos_height_in_tree(pos) != 0 || self.store.get_attestation(pos)?.is_none() {
+            return Err(anyhow::anyhow!("No evidence at pos {}", pos));
+        }
+        self.tags.set(pos, tags, principal, chrono::Utc::now().timestamp())
+    }
+
+    /// 替换模型版本的 Prompt 说明对照表：只能针对已注册的模型
+    pub fn set_prompts(&mut self, prompt_pool_hash: &str, prompts: Vec<PromptInfo>, principal: &str) -> anyhow::Result<PromptSet> {
+        if !self.store.is_model_authorized(prompt_pool_hash) {
+            return Err(anyhow::anyhow!("Unknown model '{}'", prompt_pool_hash));
+        }
+        self.prompts.set(prompt_pool_hash, prompts, principal, chrono::Utc::now().timestamp())
+    }
+
+    /// 接受模型注册表的根轮换
+    pub fn rotate_registry_root(&mut self, next: &SignedRootMetadata) -> anyhow::Result<()> {
+        self.registry.rotate_root(next, chrono::Utc::now().timestamp())
+    }
+
+    /// 接受新的模型注册表目标元数据，白名单随之整体替换
+    pub fn update_registry_targets(&mut self, next: &SignedTargetsMetadata) -> anyhow::Result<AcceptedTargets> {
+        self.registry.update_targets(next, chrono::Utc::now().timestamp())
+    }
+
+    /// 写入一轮异常检测的结果 (见 `anomaly.rs`)，返回新增的标记数
+    pub fn record_anomalies(&mut self, flags: &[AnomalyFlag], scanned_leaves: u64) -> anyhow::Result<usize> {
+        self.anomalies.record(flags, scanned_leaves)
+    }
+
+    /// 给出异常标记的复核结论
+    pub fn resolve_anomaly(&mut self, pos: u64, status: AnomalyStatus, principal: &str, note: &str) -> anyhow::Result<AnomalyFlag> {
+        self.anomalies.resolve(pos, status, principal, note, chrono::Utc::now().timestamp())
+    }
+
+    /// 追加一条保管事件 (见 `custody.rs`)：只能针对已签名入库的证据
+    ///
+    /// 事件由租户根密钥签名，链到该证据上一条事件的叶子哈希后作为一片叶子追加进 MMR。
+    pub fn append_custody(&mut self, pos: u64, details: CustodyDetails, principal: &str, signer: &EvidenceSigner) -> anyhow::Result<(u64, CustodyRecord)> {
+        if pos >= self.mmr_size || pos_height_in_tree(pos) != 0 || self.store.get_attestation(pos)?.is_none() {
+            return Err(anyhow::anyhow!("No evidence at pos {}", pos));
+        }
+        let prev_hash = match self.custody.last(pos)? {
+            Some(last) => (&self.store).get_elem(last)
+                .map_err(|e| anyhow::anyhow!("MMR get_elem error: {}", e))?
+                .ok_or_else(|| anyhow::anyhow!("Missing custody leaf at pos {}", last))?,
+            None => [0u8; 32],
+        };
+        let event = CustodyEvent {
+            tenant_id: self.store.tenant_id().to_string(),
+            pos,
+            index: self.custody.leaves(pos)?.len() as u64,
+            action: details.action,
+            actor: details.actor,
+            recipient: details.recipient,
+            note: details.note,
+            principal: principal.to_string(),
+            timestamp: chrono::Utc::now().timestamp(),
+            prev_hash: hex::encode(prev_hash),
+        };
+        let record = CustodyRecord::sign(event, signer)?;
+        let bytes = record.canonical_bytes()?;
+        let leaf_hash = *blake3::hash(&bytes).as_bytes();
+        let outcome = self.append_leaf(bytes, leaf_hash, None, self.next_seq, None)?;
+        self.custody.record(&record.event, outcome.pos)?;
+        Ok((outcome.pos, record))
+    }
+
+    /// 收录一条鉴定人复核 (见 `review.rs`)：签名由调用方核对，这里核对它指向的是该位置上已签名入库的证据
+    ///
+    /// 同一份签名意见重复提交时报错。返回记录叶子的位置。
+    pub fn append_review(&mut self, record: ReviewRecord) -> anyhow::Result<u64> {
+        let pos = record.assessment.pos;
+        if pos >= self.mmr_size || pos_height_in_tree(pos) != 0 || self.store.get_attestation(pos)?.is_none() {
+            return Err(anyhow::anyhow!("No evidence at pos {}", pos));
+        }
+        if record.assessment.tenant_id != self.store.tenant_id() {
+            return Err(anyhow::anyhow!("Review is signed for tenant '{}'", record.assessment.tenant_id));
+        }
+        let leaf = (&self.store).get_elem(pos)
+            .map_err(|e| anyhow::anyhow!("MMR get_elem error: {}", e))?
+            .ok_or_else(|| anyhow::anyhow!("No evidence at pos {}", pos))?;
+        if record.assessment.evidence_hash != hex::encode(leaf) {
+            return Err(anyhow::anyhow!("Review does not match the evidence at pos {}: leaf hash differs", pos));
+        }
+        for leaf_pos in self.reviews.leaves(pos)? {
+            let existing = self.store.get_evidence_bytes(leaf_pos)?.map(|bytes| ReviewRecord::from_bytes(&bytes)).transpose()?.flatten();
+            if existing.is_some_and(|existing| existing.signature == record.signature) {
+                return Err(anyhow::anyhow!("Review is already recorded at pos {}", leaf_pos));
+            }
+        }
+        let bytes = record.canonical_bytes()?;
+        let leaf_hash = *blake3::hash(&bytes).as_bytes();
+        let outcome = self.append_leaf(bytes, leaf_hash, None, self.next_seq, None)?;
+        self.reviews.record(pos, outcome.pos)?;
+        Ok(outcome.pos)
+    }
+
+    /// 签名日志检查点 (Signing Log Checkpoint)
+    ///
+    /// 自上次检查点以来的签名次数达到 `signing_checkpoint_interval` 时，
+    /// 把日志链头作为一片叶子追加进 MMR，返回其位置；未到期或已关闭时返回 None。
+    pub fn checkpoint_signing_log(&mut self) -> anyhow::Result<Option<u64>> {
+        if self.signing_checkpoint_interval == 0 || self.signing_log.pending() < self.signing_checkpoint_interval {
+            return Ok(None);
         }
+        let Some(checkpoint) = self.signing_log.checkpoint(self.store.tenant_id(), chrono::Utc::now().timestamp()) else {
+            return Ok(None);
+        };
+        let bytes = checkpoint.canonical_bytes()?;
+        let leaf_hash = *blake3::hash(&bytes).as_bytes();
+        let outcome = self.append_leaf(bytes, leaf_hash, None, self.next_seq, None)?;
+        self.signing_log.record_checkpoint(&checkpoint, outcome.pos)?;
+        Ok(Some(outcome.pos))
     }
 
-    /// 核心功能：证据上链入库
-    pub fn append(&mut self, evidence: &Evidence) -> anyhow::Result<([u8; 32], u64)> {
-        // Step 0: 白名单校验 (Model Governance)
-        // 防止未授权的模型版本写入区块链
+    /// 运维日志检查点：把运维日志链头作为一片叶子追加进 MMR，返回其位置 (见 `ops_log.rs`)
+    pub fn append_ops_checkpoint(&mut self, checkpoint: &OpsCheckpoint) -> anyhow::Result<u64> {
+        let bytes = checkpoint.canonical_bytes()?;
+        let leaf_hash = *blake3::hash(&bytes).as_bytes();
+        Ok(self.append_leaf(bytes, leaf_hash, None, self.next_seq, None)?.pos)
+    }
+
+    /// 把已规范化的叶子原文追加进 MMR (不签名)
+    fn append_leaf(&mut self, evidence_bytes: Vec<u8>, leaf_hash: [u8; 32], salt: Option<[u8; 32]>, seq: u64, nonce: Option<&str>) -> anyhow::Result<AppendOutcome> {
+        self.check_peaks(seq)?;
+        self.fence.check()?;
+
+        // 在内存中计算追加结果 (此时尚未写盘)
+        let StagedAppend { mmr, pos, new_size, root, peaks } = self.stage(leaf_hash)?;
+
+        let record = WalRecord {
+            seq,
+            pos,
+            leaf_hash,
+            prev_size: self.mmr_size,
+            new_size,
+            new_root: root,
+            nonce: nonce.map(str::to_string),
+            evidence_bytes,
+            attestation: None,
+            salt,
+            blinded: false,
+        };
+        Self::persist(&self.store, mmr, std::slice::from_ref(&record))?;
+        self.mmr_size = new_size;
+        self.next_seq = seq + 1;
+        self.peaks = peaks;
+        self.publish();
+
+        // 按持久化策略落盘 (durable 模式下返回前 fsync)
+        self.store.sync_append()?;
+        
+        Ok(AppendOutcome { leaf_hash, pos, root, mmr_size: new_size, seq, salt })
+    }
+
+    /// 批量入库 (不签名)
+    ///
+    /// 所有叶子在同一个 MMR 句柄中 push，只 commit 一次、只 flush 一次，
+    /// 索引与元数据也在同一个事务中提交：整批要么全部入库，要么全部不入库。
+    /// 批内出现重复证据同样视为重放。
+    pub fn append_batch(&mut self, evidences: &[Evidence]) -> anyhow::Result<BatchOutcome> {
+        if evidences.is_empty() {
+            return Err(anyhow::anyhow!("Empty batch"));
+        }
+        self.fence.check()?;
+
+        let first_seq = self.next_seq;
+        let mut prepared = Vec::with_capacity(evidences.len());
+        let mut seen = std::collections::HashSet::new();
+        for (i, evidence) in evidences.iter().enumerate() {
+            let (evidence_bytes, content_hash, _) = self.precheck(evidence, None)?;
+            if !seen.insert(content_hash) {
+                return Err(anyhow::anyhow!("Replay detected: identical evidence repeated within batch at index {}", i));
+            }
+            let (leaf_hash, salt) = self.salt_leaf(&evidence_bytes, content_hash, false);
+            prepared.push((evidence_bytes, leaf_hash, salt));
+        }
+        self.check_peaks(first_seq)?;
+
+        let mut mmr = MMR::<[u8; 32], MergeBlake3, _>::new(self.mmr_size, &self.store);
+        let mut peaks = self.peaks.clone();
+        let mut records = Vec::with_capacity(prepared.len());
+        for (seq, (evidence_bytes, leaf_hash, salt)) in (first_seq..).zip(prepared) {
+            let prev_size = mmr.mmr_size();
+            let pos = mmr.push(leaf_hash).map_err(|e| anyhow::anyhow!("MMR append error: {}", e))?;
+            let new_root = peaks.push(leaf_hash)?;
+            records.push(WalRecord {
+                seq,
+                pos,
+                leaf_hash,
+                prev_size,
+                new_size: mmr.mmr_size(),
+                new_root,
+                nonce: None,
+                evidence_bytes,
+                attestation: None,
+                salt,
+                blinded: false,
+            });
+        }
+        let new_size = mmr.mmr_size();
+        let root = peaks.root().expect("peaks are non-empty after push");
+
+        Self::persist(&self.store, mmr, &records)?;
+        self.mmr_size = new_size;
+        self.next_seq = first_seq + records.len() as u64;
+        self.peaks = peaks;
+        self.publish();
+
+        // 按持久化策略落盘 (durable 模式下返回前 fsync)
+        self.store.sync_append()?;
+
+        Ok(BatchOutcome {
+            leaf_hashes: records.iter().map(|r| r.leaf_hash).collect(),
+            positions: records.iter().map(|r| r.pos).collect(),
+            root,
+            mmr_size: new_size,
+            first_seq,
+            salts: records.iter().map(|r| r.salt).collect(),
+        })
+    }
+
+    /// 入库前检查：模型白名单 + 重放检测
+    ///
+    /// 返回 (规范化字节, 内容哈希, 待分配的序列号)。内容哈希即证据 ID，也是无盐叶子的叶子哈希，
+    /// 加盐模式下同样用它做重放检测 (擦除时随之删除)。
+    fn precheck(&self, evidence: &Evidence, nonce: Option<&str>) -> anyhow::Result<(Vec<u8>, [u8; 32], u64)> {
+        // 白名单校验 (Model Governance)
+        // 防止未授权的模型版本写入区块链；签名模式下注册表元数据过期即停止接收
+        self.registry.check_current(chrono::Utc::now().timestamp())?;
         if !self.store.is_model_authorized(&evidence.prompt_pool_hash) {
              return Err(anyhow::anyhow!("Unauthorized Model Version: '{}'. Please register first.", evidence.prompt_pool_hash));
         }
+        // 多模型共识中的每个模型同样须已登记
+        for model in evidence.consensus.iter().flat_map(|consensus| &consensus.models) {
+            if !self.store.is_model_authorized(&model.prompt_pool_hash) {
+                return Err(anyhow::anyhow!("Unauthorized Model Version: '{}'. Please register first.", model.prompt_pool_hash));
+            }
+        }
 
-        let payload = bcs::to_bytes(evidence)?;
-        let leaf_hash = *blake3::hash(&payload).as_bytes();
+        let evidence_bytes = evidence.canonical_bytes_as(self.canonical_encoding)?;
+        let content_hash = evidence_id_of(&evidence_bytes);
 
+        // 重放检测 (Replay Protection)
+        if let Some(seq) = self.store.seq_of_leaf(&content_hash)? {
+            return Err(anyhow::anyhow!(
+                "Replay detected: identical evidence {} already archived at seq {}",
+                hex::encode(content_hash),
+                seq
+            ));
+        }
+        if let Some(seq) = nonce.map(|n| self.store.seq_of_nonce(n)).transpose()?.flatten() {
+            return Err(anyhow::anyhow!("Replay detected: nonce already used by seq {}", seq));
+        }
+        Ok((evidence_bytes, content_hash, self.next_seq))
+    }
+
+    /// 在内存中追加叶子 (尚未写盘)，新根由山峰缓存增量得到
+    fn stage(&self, leaf_hash: [u8; 32]) -> anyhow::Result<StagedAppend<'_>> {
         let mut mmr = MMR::<[u8; 32], MergeBlake3, _>::new(self.mmr_size, &self.store);
-        
         let pos = mmr.push(leaf_hash).map_err(|e| anyhow::anyhow!("MMR append error: {}", e))?;
-        
         let new_size = mmr.mmr_size();
-        let root = mmr.get_root().map_err(|e| anyhow::anyhow!("MMR get_root error: {}", e))?;
 
-        mmr.commit().map_err(|e| anyhow::anyhow!("MMR commit error: {}", e))?;
+        let mut peaks = self.peaks.clone();
+        let root = peaks.push(leaf_hash)?;
+        Ok(StagedAppend { mmr, pos, new_size, root, peaks })
+    }
 
-        // 持久化新的 Size
-        self.store.set_meta_size(new_size)?;
-        
-        // 显式 flush 确保数据落盘
-        self.store.flush()?;
+    /// 山峰缓存一致性自检
+    ///
+    /// 每隔 `peak_check_interval` 条，用库函数 `get_root` 重算当前根并与缓存比对。
+    /// 不一致时从存储重新加载山峰，并拒绝本次追加。
+    fn check_peaks(&mut self, seq: u64) -> anyhow::Result<()> {
+        if self.peak_check_interval == 0 || self.mmr_size == 0 || !seq.is_multiple_of(self.peak_check_interval) {
+            return Ok(());
+        }
+        let mmr = MMR::<[u8; 32], MergeBlake3, _>::new(self.mmr_size, &self.store);
+        let expected = mmr.get_root().map_err(|e| anyhow::anyhow!("MMR get_root error: {}", e))?;
+        if self.peaks.root() == Some(expected) {
+            return Ok(());
+        }
 
-        self.mmr_size = new_size;
-        
-        Ok((root, pos))
+        error!("🚨 山峰缓存与 MMR 根不一致 (Seq={})，从存储重新加载", seq);
+        let cached = self.peaks.root().map(hex::encode).unwrap_or_default();
+        self.peaks = PeakCache::load(&self.store, self.mmr_size)?;
+        Err(anyhow::anyhow!(
+            "Peak cache diverged at seq {}: cached {}, expected {}",
+            seq,
+            cached,
+            hex::encode(expected)
+        ))
+    }
+
+    /// 应用从主库复制来的叶子 (只读副本，见 `replication.rs`)
+    ///
+    /// 与崩溃恢复的重做相同：记录的 `prev_size` 必须等于本地大小，重新 push 后位置、大小与根必须与主库一致，
+    /// 否则说明副本与主库分叉，拒绝继续。带原文的叶子须能重算出叶子哈希。本地已有的叶子只核对叶子哈希后跳过。
+    /// `pruned_at` 为主库清理原文的时间。随后补上本地缺少的擦除 (擦除可能发生在叶子复制之后)。
+    /// 返回新增的叶子数。
+    pub fn apply_replicated(&mut self, records: &[(WalRecord, Option<i64>)], erasures: &[ErasureRecord]) -> anyhow::Result<u64> {
+        // 持有写租约的节点自己就是写者，再接收复制会与本地追加交错
+        if self.fence.is_open() {
+            return Err(anyhow::anyhow!("This node holds the writer lease; refusing replicated leaves"));
+        }
+        let mut applied = 0;
+        for (record, pruned_at) in records {
+            // 另一路复制 (接管前从其他副本追赶) 已先写入的叶子只核对叶子哈希
+            if record.new_size <= self.mmr_size {
+                let local = (&self.store).get_elem(record.pos).map_err(|e| anyhow::anyhow!("MMR get_elem error: {}", e))?;
+                if local != Some(record.leaf_hash) {
+                    return Err(anyhow::anyhow!("Replica diverges from the primary at pos {}: leaf differs", record.pos));
+                }
+                continue;
+            }
+            if record.prev_size != self.mmr_size {
+                return Err(anyhow::anyhow!(
+                    "Replicated leaf at pos {} does not follow the local tree (size {})",
+                    record.pos,
+                    self.mmr_size
+                ));
+            }
+            if !record.evidence_bytes.is_empty() && leaf_hash_of(&record.evidence_bytes, record.salt.as_ref()) != record.leaf_hash {
+                return Err(anyhow::anyhow!("Replicated evidence at pos {} does not match its leaf hash", record.pos));
+            }
+            let StagedAppend { mmr, pos, new_size, root, peaks } = self.stage(record.leaf_hash)?;
+            if pos != record.pos || new_size != record.new_size || root != record.new_root {
+                return Err(anyhow::anyhow!(
+                    "Replica diverges from the primary at pos {}: primary root {}, local root {}",
+                    record.pos,
+                    hex::encode(record.new_root),
+                    hex::encode(root)
+                ));
+            }
+            Self::persist(&self.store, mmr, std::slice::from_ref(record))?;
+            if let Some(pruned_at) = pruned_at {
+                self.store.prune_evidence(&[pos], *pruned_at)?;
+            }
+            if let Some(custody) = CustodyRecord::from_bytes(&record.evidence_bytes)? {
+                self.custody.record(&custody.event, pos)?;
+            } else if let Some(review) = ReviewRecord::from_bytes(&record.evidence_bytes)? {
+                self.reviews.record(review.assessment.pos, pos)?;
+            }
+            self.mmr_size = new_size;
+            self.next_seq = record.seq + 1;
+            self.peaks = peaks;
+            self.publish();
+            applied += 1;
+        }
+
+        for erasure in erasures {
+            if erasure.pos >= self.mmr_size || self.store.get_erasure(erasure.pos)?.is_some() {
+                continue;
+            }
+            let content_key = self.store.content_key_of_seq(erasure.seq)?;
+            self.store.erase_evidence(erasure, content_key.as_deref())?;
+            self.read_cache.invalidate(erasure.pos);
+        }
+        self.store.sync_append()?;
+        Ok(applied)
     }
 
-    /// 注册新模型
+    /// 当前根 (O(1)，空树为 None)
+    pub fn root(&self) -> Option<[u8; 32]> {
+        self.peaks.root()
+    }
+
+    /// 提交一次追加 (WAL → MMR 节点 → 元数据事务)
+    ///
+    /// **崩溃安全**: 任何一步中断，重启时 `recover` 都能把状态补齐或丢弃。
+    /// **失败回滚**: 运行时出错则丢弃 WAL；已写入的 MMR 节点位于已提交 size 之外，
+    /// 对外不可见，下次追加时会被覆盖。
+    fn persist(store: &SledStore, mmr: MMR<[u8; 32], MergeBlake3, &SledStore>, records: &[WalRecord]) -> anyhow::Result<()> {
+        store.wal_put(records)?;
+
+        let committed = mmr
+            .commit()
+            .map_err(|e| anyhow::anyhow!("MMR commit error: {}", e))
+            .and_then(|_| store.commit_append(records));
+        if let Err(e) = committed {
+            for record in records {
+                if let Err(discard_err) = store.wal_discard(record.seq) {
+                    warn!("⚠️  WAL 回滚失败 (重启时将自动处理): {}", discard_err);
+                }
+            }
+            return Err(e);
+        }
+        Ok(())
+    }
+
+    /// 当前 MMR 大小 (节点总数)
+    pub fn mmr_size(&self) -> u64 {
+        self.mmr_size
+    }
+
+    /// 注册新模型 (签名模式下拒绝，模型只能经签名的目标元数据获准)
     pub fn register_model(&self, hash: &str, description: &str) -> anyhow::Result<()> {
+        if self.registry.is_enabled() {
+            return Err(anyhow::anyhow!("The model registry is in signed mode; publish signed targets metadata instead"));
+        }
         self.store.register_model(hash, description)
     }
 
+    /// 获取只读句柄 (可跨线程克隆，供审计等读路径使用)
+    pub fn reader(&self) -> EvidenceReader {
+        EvidenceReader {
+            store: self.store.clone(),
+            committed_size: self.committed_size.clone(),
+            proof_cache: self.proof_cache.clone(),
+            read_cache: self.read_cache.clone(),
+            canonical_encoding: self.canonical_encoding,
+        }
+    }
+
+    /// 证据读缓存 (供删除 / 改写已入库数据的功能调用失效钩子)
+    pub fn read_cache(&self) -> &Arc<EvidenceReadCache> {
+        &self.read_cache
+    }
+
+    /// 当前已提交状态的快照
+    pub fn snapshot(&self) -> StoreSnapshot {
+        self.reader().snapshot()
+    }
+
+    /// 发布新的已提交大小，读端从此刻起可见
+    fn publish(&self) {
+        self.committed_size.store(self.mmr_size, Ordering::Release);
+        self.proof_cache.clear();
+    }
+}
+
+/// 只读句柄 (Evidence Reader)
+///
+/// **无锁读**: sled 本身支持并发读，MMR 又是只追加的结构，
+/// 读端只需要知道“已提交的大小”即可得到一致视图。
+/// 写端在事务提交后才发布新的大小，因此读端永远看不到写了一半的节点，
+/// 审计 / 证明生成也不会被写入阻塞。
+#[derive(Clone)]
+pub struct EvidenceReader {
+    store: SledStore,
+    committed_size: Arc<AtomicU64>,
+    proof_cache: Arc<ProofCache>,
+    read_cache: Arc<EvidenceReadCache>,
+    canonical_encoding: CanonicalEncoding,
+}
+
+impl EvidenceReader {
+    /// 当前已提交的 MMR 大小
+    pub fn mmr_size(&self) -> u64 {
+        self.committed_size.load(Ordering::Acquire)
+    }
+
+    /// 本租户的规范编码
+    pub fn canonical_encoding(&self) -> CanonicalEncoding {
+        self.canonical_encoding
+    }
+
+    /// 固定当前已提交大小，得到一个一致的只读快照
+    pub fn snapshot(&self) -> StoreSnapshot {
+        StoreSnapshot {
+            store: self.store.clone(),
+            mmr_size: self.mmr_size(),
+            proof_cache: self.proof_cache.clone(),
+            read_cache: self.read_cache.clone(),
+            canonical_encoding: self.canonical_encoding,
+        }
+    }
+
+    /// 证明缓存命中统计
+    pub fn proof_cache_stats(&self) -> CacheStats {
+        self.proof_cache.stats()
+    }
+
+    /// 证据读缓存 (命中统计与失效钩子)
+    pub fn read_cache(&self) -> &Arc<EvidenceReadCache> {
+        &self.read_cache
+    }
+}
+
+/// 只读快照 (Store Snapshot)
+///
+/// 同一个快照上的多次读取都基于同一棵树 (同一个 `mmr_size`)。
+pub struct StoreSnapshot {
+    store: SledStore,
+    mmr_size: u64,
+    proof_cache: Arc<ProofCache>,
+    read_cache: Arc<EvidenceReadCache>,
+    canonical_encoding: CanonicalEncoding,
+}
+
+impl StoreSnapshot {
+    /// 不经写端、直接按落盘状态打开的快照 (离线工具用，不做崩溃恢复与格式迁移，不走缓存)
+    ///
+    /// 存储格式不是当前版本时报错：旧格式的记录须先由服务启动时迁移。
+    pub fn open_detached(store: SledStore) -> anyhow::Result<Self> {
+        if let Some(format) = store.storage_format()? {
+            if format != StorageFormat::CURRENT {
+                return Err(anyhow::anyhow!(
+                    "Store '{}' uses storage format {}; start the server once to migrate it to {}",
+                    store.tenant_id(),
+                    format.tag(),
+                    StorageFormat::CURRENT.tag()
+                ));
+            }
+        }
+        store.check_evidence_key()?;
+        Ok(Self {
+            mmr_size: store.get_meta_size(),
+            proof_cache: Arc::new(ProofCache::new(0)),
+            read_cache: Arc::new(EvidenceReadCache::new(0)),
+            canonical_encoding: store.canonical_encoding()?,
+            store,
+        })
+    }
+
+    /// 叶子原文的落盘字节 (证据的规范字节或检查点字节)；已清理 / 擦除时为 None
+    pub fn evidence_bytes(&self, pos: u64) -> anyhow::Result<Option<Vec<u8>>> {
+        self.store.get_evidence_bytes(pos)
+    }
+
+    /// 最近一次已签名的根
+    pub fn last_signed_root(&self) -> anyhow::Result<Option<SignedRoot>> {
+        self.store.get_last_signed_root()
+    }
+
+    /// 读取指定位置的证据原文 (经过读缓存)
+    ///
+    /// 签名日志与运维日志的检查点叶子、保管与复核记录叶子不是证据，返回 None。
+    pub fn get_evidence(&self, pos: u64) -> anyhow::Result<Option<Evidence>> {
+        if let Some(evidence) = self.read_cache.evidence.get(&pos) {
+            return Ok(Some((*evidence).clone()));
+        }
+        let Some(bytes) = self.store.get_evidence_bytes(pos)? else {
+            return Ok(None);
+        };
+        if is_log_leaf(&bytes)? {
+            return Ok(None);
+        }
+        let evidence = Evidence::from_canonical_bytes(self.canonical_encoding, &bytes)?;
+        self.read_cache.evidence.put(pos, Arc::new(evidence.clone()));
+        Ok(Some(evidence))
+    }
+
+    /// 按证据 ID 查找叶子位置 (只看本快照范围内)
+    ///
+    /// 已擦除的证据不再能按 ID 找到 (内容索引随擦除删除)；副本上复制来时已清理的加盐叶子同样没有索引。
+    pub fn pos_of_evidence_id(&self, evidence_id: &[u8; 32]) -> anyhow::Result<Option<u64>> {
+        let Some(seq) = self.store.seq_of_leaf(evidence_id)? else {
+            return Ok(None);
+        };
+        Ok(self.store.pos_of_seq(seq)?.filter(|pos| *pos < self.mmr_size))
+    }
+
+    /// 指定位置的证据 ID
+    ///
+    /// 原文在时由原文算出；原文已清理的无盐叶子即叶子哈希；已擦除、已清理的加盐叶子与日志叶子返回 None。
+    pub fn evidence_id(&self, pos: u64) -> anyhow::Result<Option<[u8; 32]>> {
+        if let Some(bytes) = self.store.get_evidence_bytes(pos)? {
+            if is_log_leaf(&bytes)? {
+                return Ok(None);
+            }
+            return Ok(Some(evidence_id_of(&bytes)));
+        }
+        if self.pruned_at(pos)?.is_some() && self.get_salt(pos)?.is_none() {
+            return self.get_leaf(pos);
+        }
+        Ok(None)
+    }
+
+    /// 证据原文被保留期清理的时间 (Unix 秒)；未清理返回 None
+    pub fn pruned_at(&self, pos: u64) -> anyhow::Result<Option<i64>> {
+        self.store.get_pruned_at(pos)
+    }
+
+    /// 叶子盐值；无盐叶子、盲化叶子或已擦除时返回 None
+    pub fn get_salt(&self, pos: u64) -> anyhow::Result<Option<[u8; 32]>> {
+        self.store.get_salt(pos)
+    }
+
+    /// 是否为盲化叶子 (服务端只有叶子哈希与回执)
+    pub fn is_blinded(&self, pos: u64) -> anyhow::Result<bool> {
+        self.store.is_blinded(pos)
+    }
+
+    /// 指定位置的擦除记录
+    pub fn erasure(&self, pos: u64) -> anyhow::Result<Option<ErasureRecord>> {
+        self.store.get_erasure(pos)
+    }
+
+    /// 本租户的全部擦除记录
+    pub fn erasures(&self) -> anyhow::Result<Vec<ErasureRecord>> {
+        self.store.erasures()
+    }
+
+    /// 诉讼保全的只读视图
+    pub fn legal_holds(&self) -> anyhow::Result<LegalHolds> {
+        LegalHolds::open(&self.store)
+    }
+
+    /// 证据访问控制列表的只读视图
+    pub fn acls(&self) -> anyhow::Result<EvidenceAcls> {
+        EvidenceAcls::open(&self.store)
+    }
+
+    /// 证据标签的只读视图
+    pub fn tags(&self) -> anyhow::Result<EvidenceTags> {
+        EvidenceTags::open(&self.store)
+    }
+
+    /// 证据二级索引的只读视图 (索引树随写入即时变化，调用方按本快照的大小过滤)
+    pub fn evidence_index(&self) -> anyhow::Result<EvidenceIndex> {
+        EvidenceIndex::open(&self.store)
+    }
+
+    /// 提交时的来源说明 (未填写或已擦除为 None)
+    pub fn source(&self, pos: u64) -> anyhow::Result<Option<String>> {
+        self.store.get_source(pos)
+    }
+
+    /// 注册模型时登记的说明
+    pub fn model_description(&self, prompt_pool_hash: &str) -> anyhow::Result<Option<String>> {
+        self.store.model_description(prompt_pool_hash)
+    }
+
+    /// 模型是否已注册
+    pub fn is_model_registered(&self, prompt_pool_hash: &str) -> bool {
+        self.store.is_model_authorized(prompt_pool_hash)
+    }
+
+    /// Prompt 说明对照表的只读视图
+    pub fn prompt_registry(&self) -> anyhow::Result<PromptRegistry> {
+        PromptRegistry::open(&self.store)
+    }
+
+    /// 签名的模型注册表的只读视图
+    pub fn model_registry(&self) -> anyhow::Result<ModelRegistry> {
+        ModelRegistry::open(&self.store)
+    }
+
+    /// 激活路径异常标记的只读视图
+    pub fn anomalies(&self) -> anyhow::Result<AnomalyLog> {
+        AnomalyLog::open(&self.store)
+    }
+
+    /// 指定证据的保管事件 (按事件先后，只含本快照范围内的)：(叶子位置, 保管记录)
+    pub fn custody(&self, pos: u64) -> anyhow::Result<Vec<(u64, CustodyRecord)>> {
+        let mut records = Vec::new();
+        for leaf_pos in CustodyLog::open(&self.store)?.leaves(pos)? {
+            if leaf_pos >= self.mmr_size {
+                break;
+            }
+            let bytes = self.store.get_evidence_bytes(leaf_pos)?
+                .ok_or_else(|| anyhow::anyhow!("Missing custody record at pos {}", leaf_pos))?;
+            let record = CustodyRecord::from_bytes(&bytes)?
+                .ok_or_else(|| anyhow::anyhow!("Leaf at pos {} is not a custody record", leaf_pos))?;
+            records.push((leaf_pos, record));
+        }
+        Ok(records)
+    }
+
+    /// 指定证据的复核记录 (按收录先后，只含本快照范围内的)：(叶子位置, 复核记录)
+    pub fn reviews(&self, pos: u64) -> anyhow::Result<Vec<(u64, ReviewRecord)>> {
+        let mut records = Vec::new();
+        for leaf_pos in ReviewLog::open(&self.store)?.leaves(pos)? {
+            if leaf_pos >= self.mmr_size {
+                break;
+            }
+            let bytes = self.store.get_evidence_bytes(leaf_pos)?
+                .ok_or_else(|| anyhow::anyhow!("Missing review record at pos {}", leaf_pos))?;
+            let record = ReviewRecord::from_bytes(&bytes)?
+                .ok_or_else(|| anyhow::anyhow!("Leaf at pos {} is not a review record", leaf_pos))?;
+            records.push((leaf_pos, record));
+        }
+        Ok(records)
+    }
+
+    /// 历史树头存档 (根发布写入)
+    pub fn tree_heads(&self) -> anyhow::Result<TreeHeadArchive> {
+        TreeHeadArchive::open(&self.store)
+    }
+
+    /// 公证回执存档 (交叉公证写入)
+    pub fn notary_receipts(&self) -> anyhow::Result<NotaryArchive> {
+        NotaryArchive::open(&self.store)
+    }
+
+    /// RFC 3161 时间戳回执存档 (交叉公证写入)
+    pub fn timestamp_tokens(&self) -> anyhow::Result<TimestampArchive> {
+        TimestampArchive::open(&self.store)
+    }
+
+    /// Rekor 收录记录存档 (Rekor 交叉发布写入)
+    pub fn rekor_entries(&self) -> anyhow::Result<RekorArchive> {
+        RekorArchive::open(&self.store)
+    }
+
+    /// 取代指定位置的更正叶子 (只看本快照范围内)
+    pub fn superseded_by(&self, pos: u64) -> anyhow::Result<Option<u64>> {
+        Ok(self.store.superseded_by(pos)?.filter(|newer| *newer < self.mmr_size))
+    }
+
+    /// 指定位置所在的更正链 (按先后顺序，最后一个为当前有效的条目)
+    ///
+    /// 先沿回执中的 `supersedes` 回溯到链首，再沿 `superseded_by` 走到链尾；
+    /// 每一环都有各自的回执与包含性证明，更正历史因此可逐条举证。
+    pub fn supersession_chain(&self, pos: u64) -> anyhow::Result<Vec<u64>> {
+        let mut first = pos;
+        while let Some(previous) = self.get_attestation(first)?.and_then(|a| a.receipt.supersedes) {
+            first = previous;
+        }
+        let mut chain = vec![first];
+        let mut current = first;
+        while let Some(next) = self.superseded_by(current)? {
+            chain.push(next);
+            current = next;
+        }
+        Ok(chain)
+    }
+
+    /// 读取指定位置的签名日志检查点
+    pub fn get_signing_checkpoint(&self, pos: u64) -> anyhow::Result<Option<SigningCheckpoint>> {
+        match self.store.get_evidence_bytes(pos)? {
+            Some(bytes) => SigningCheckpoint::from_bytes(&bytes),
+            None => Ok(None),
+        }
+    }
+
+    /// 签名审计日志的只读视图
+    pub fn signing_log(&self) -> anyhow::Result<SigningLogView> {
+        SigningLogView::open(&self.store)
+    }
+
+    /// 读取指定位置的签名材料 (证据签名 + 回执 + 回执签名，经过读缓存)
+    pub fn get_attestation(&self, pos: u64) -> anyhow::Result<Option<StoredAttestation>> {
+        if let Some(attestation) = self.read_cache.attestations.get(&pos) {
+            return Ok(Some((*attestation).clone()));
+        }
+        let attestation = self.store.get_attestation(pos)?;
+        if let Some(attestation) = &attestation {
+            self.read_cache.attestations.put(pos, Arc::new(attestation.clone()));
+        }
+        Ok(attestation)
+    }
+
+    /// 快照对应的 MMR 大小 (节点总数)
+    pub fn mmr_size(&self) -> u64 {
+        self.mmr_size
+    }
+
+    /// MMR 节点 (`nodes`) 与指定各 tree 的条目数、键值字节数 (逐条遍历，统计用)
+    ///
+    /// 读的是当前落盘状态，不受快照的 `mmr_size` 限制。
+    pub fn tree_usage(&self, names: &[&str]) -> anyhow::Result<Vec<(String, u64, u64)>> {
+        let usage = |tree: &sled::Tree| -> anyhow::Result<(u64, u64)> {
+            let (mut entries, mut bytes) = (0, 0);
+            for entry in tree.iter() {
+                let (key, value) = entry?;
+                entries += 1;
+                bytes += (key.len() + value.len()) as u64;
+            }
+            Ok((entries, bytes))
+        };
+        let (entries, bytes) = usage(self.store.nodes())?;
+        let mut trees = vec![("nodes".to_string(), entries, bytes)];
+        for name in names {
+            let (entries, bytes) = usage(&self.store.tree(name)?)?;
+            trees.push((name.to_string(), entries, bytes));
+        }
+        Ok(trees)
+    }
+
+    /// 整个数据库在磁盘上的大小 (字节，所有租户共用)
+    pub fn size_on_disk(&self) -> anyhow::Result<u64> {
+        Ok(self.store.db.size_on_disk()?)
+    }
+
     /// 核心功能：开具证明
+    ///
+    /// 单个位置的证明会经过 LRU 缓存 (键为 `(tree_size, pos)`)。
     pub fn get_proof(&self, pos_list: Vec<u64>) -> anyhow::Result<ckb_merkle_mountain_range::MerkleProof<[u8; 32], MergeBlake3>> {
-        let mmr = MMR::<[u8; 32], MergeBlake3, _>::new(self.mmr_size, &self.store);
-        mmr.gen_proof(pos_list).map_err(|e| anyhow::anyhow!("MMR gen_proof error: {}", e))
+        self.get_proof_at(self.mmr_size, pos_list)
+    }
+
+    /// 在历史某一时刻 (tree_size) 的树上开具证明，可对照回执中签名的根离线校验
+    ///
+    /// `tree_size` 必须是某个叶子数对应的 MMR 大小 (回执、树头中的都是)，否则那一刻的树并不存在。
+    pub fn get_proof_at(&self, tree_size: u64, pos_list: Vec<u64>) -> anyhow::Result<ckb_merkle_mountain_range::MerkleProof<[u8; 32], MergeBlake3>> {
+        self.check_tree_size(tree_size)?;
+        if let Some(pos) = pos_list.iter().find(|pos| **pos >= tree_size) {
+            return Err(anyhow::anyhow!("Position {} is beyond tree size {}", pos, tree_size));
+        }
+        if let [pos] = pos_list.as_slice() {
+            if let Some(items) = self.proof_cache.get(&(tree_size, *pos)) {
+                return Ok(ckb_merkle_mountain_range::MerkleProof::new(tree_size, items.to_vec()));
+            }
+        }
+
+        let mmr = MMR::<[u8; 32], MergeBlake3, _>::new(tree_size, &self.store);
+        let proof = mmr.gen_proof(pos_list.clone()).map_err(|e| anyhow::anyhow!("MMR gen_proof error: {}", e))?;
+        if let [pos] = pos_list.as_slice() {
+            self.proof_cache.put((tree_size, *pos), Arc::new(proof.proof_items().to_vec()));
+        }
+        Ok(proof)
+    }
+
+    /// 批量证明：一次为多个叶子开具合并的证明路径，返回证明与各叶子哈希 (按位置升序、去重)
+    ///
+    /// 相邻叶子共享兄弟节点与山峰，抽查数百条时路径远短于逐条证明之和。
+    pub fn get_batch_proof(&self, tree_size: u64, positions: &[u64]) -> anyhow::Result<BatchProof> {
+        let mut positions = positions.to_vec();
+        positions.sort_unstable();
+        positions.dedup();
+        if positions.is_empty() {
+            return Err(anyhow::anyhow!("At least one position is required"));
+        }
+        if let Some(pos) = positions.iter().find(|pos| pos_height_in_tree(**pos) != 0) {
+            return Err(anyhow::anyhow!("Position {} is not a leaf", pos));
+        }
+        let proof = self.get_proof_at(tree_size, positions.clone())?;
+        let leaves = positions
+            .into_iter()
+            .map(|pos| {
+                let leaf = self.get_leaf(pos)?.ok_or_else(|| anyhow::anyhow!("No leaf at pos {}", pos))?;
+                Ok((pos, leaf))
+            })
+            .collect::<anyhow::Result<Vec<_>>>()?;
+        Ok(BatchProof { proof, leaves })
+    }
+
+    /// 读取指定位置的节点哈希
+    pub fn get_leaf(&self, pos: u64) -> anyhow::Result<Option<[u8; 32]>> {
+        (&self.store).get_elem(pos).map_err(|e| anyhow::anyhow!("MMR get_elem error: {}", e))
+    }
+
+    /// 计算历史某一时刻 (tree_size) 的根
+    ///
+    /// MMR 只追加不修改，`tree_size` 之前的节点永远不变，
+    /// 因此用旧的 size 打开同一个 store 就能还原当时的根。
+    pub fn root_at(&self, tree_size: u64) -> anyhow::Result<[u8; 32]> {
+        self.check_tree_size(tree_size)?;
+        let mmr = MMR::<[u8; 32], MergeBlake3, _>::new(tree_size, &self.store);
+        mmr.get_root().map_err(|e| anyhow::anyhow!("MMR get_root error: {}", e))
+    }
+
+    /// 历史树大小须非空、不超过当前树，且是某个叶子数对应的 MMR 大小
+    fn check_tree_size(&self, tree_size: u64) -> anyhow::Result<()> {
+        if tree_size == 0 || tree_size > self.mmr_size {
+            return Err(anyhow::anyhow!("Invalid tree size {} (current size {})", tree_size, self.mmr_size));
+        }
+        if !crate::position::is_valid_mmr_size(tree_size) {
+            return Err(anyhow::anyhow!("Invalid tree size {}: not the size of any tree in this log", tree_size));
+        }
+        Ok(())
+    }
+
+    /// 校验叶子 `leaf` 是否位于 `tree_size` 大小的树中的 `pos` 位置，且该树的根为 `root`
+    pub fn verify_inclusion(&self, pos: u64, leaf: [u8; 32], tree_size: u64, root: [u8; 32]) -> anyhow::Result<bool> {
+        if self.check_tree_size(tree_size).is_err() || pos >= tree_size {
+            return Ok(false);
+        }
+        let mmr = MMR::<[u8; 32], MergeBlake3, _>::new(tree_size, &self.store);
+        let proof = mmr.gen_proof(vec![pos]).map_err(|e| anyhow::anyhow!("MMR gen_proof error: {}", e))?;
+        Ok(proof.verify(root, vec![(pos, leaf)]).unwrap_or(false))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::position::leaf_index_to_pos;
+
+    /// 测试用的临时库目录，丢弃时删除
+    struct TempDb(std::path::PathBuf);
+
+    impl TempDb {
+        fn new(name: &str) -> Self {
+            let path = std::env::temp_dir().join(format!("yuanjing-mmr-store-{}-{}", name, std::process::id()));
+            let _ = std::fs::remove_dir_all(&path);
+            Self(path)
+        }
+
+        fn open(&self) -> EvidenceStore {
+            EvidenceStore::open(SledStore::new(self.0.to_str().unwrap()).unwrap(), &StoreOptions::default())
+        }
+    }
+
+    impl Drop for TempDb {
+        fn drop(&mut self) {
+            let _ = std::fs::remove_dir_all(&self.0);
+        }
+    }
+
+    fn append(store: &mut EvidenceStore, payload: &[u8]) -> AppendOutcome {
+        let seq = store.next_seq;
+        store.append_leaf(payload.to_vec(), *blake3::hash(payload).as_bytes(), None, seq, None).unwrap()
+    }
+
+    /// 模拟崩溃：WAL 已落盘，`with_nodes` 时 MMR 节点也已写入，但提交事务 (元数据、原文、删除 WAL) 没有执行
+    fn crash_after_wal(store: &EvidenceStore, payload: &[u8], with_nodes: bool) -> WalRecord {
+        let leaf_hash = *blake3::hash(payload).as_bytes();
+        let StagedAppend { mmr, pos, new_size, root, .. } = store.stage(leaf_hash).unwrap();
+        let record = WalRecord {
+            seq: store.next_seq,
+            pos,
+            leaf_hash,
+            prev_size: store.mmr_size,
+            new_size,
+            new_root: root,
+            nonce: Some("crash-nonce".to_string()),
+            evidence_bytes: payload.to_vec(),
+            attestation: None,
+            salt: None,
+            blinded: false,
+        };
+        store.store.wal_put(std::slice::from_ref(&record)).unwrap();
+        if with_nodes {
+            mmr.commit().unwrap();
+        }
+        record
+    }
+
+    #[test]
+    fn committed_wal_record_is_rolled_forward_on_reopen() {
+        for with_nodes in [false, true] {
+            let db = TempDb::new(if with_nodes { "roll-forward-nodes" } else { "roll-forward" });
+            let record = {
+                let mut store = db.open();
+                append(&mut store, b"first");
+                append(&mut store, b"second");
+                crash_after_wal(&store, b"third", with_nodes)
+            };
+
+            let mut store = db.open();
+            assert_eq!(store.mmr_size(), record.new_size);
+            assert_eq!(store.next_seq, record.seq + 1);
+            assert_eq!(store.root(), Some(record.new_root));
+            assert_eq!(store.store.get_evidence_bytes(record.pos).unwrap().as_deref(), Some(&b"third"[..]));
+            assert!(store.store.tree("nonces").unwrap().contains_key(b"crash-nonce").unwrap());
+            assert!(store.store.wal_pending().unwrap().is_empty());
+
+            // 重做之后照常追加，新根与从节点重算的一致
+            let outcome = append(&mut store, b"fourth");
+            let mmr = MMR::<[u8; 32], MergeBlake3, _>::new(store.mmr_size(), &store.store);
+            assert_eq!(Some(outcome.root), mmr.get_root().ok());
+        }
+    }
+
+    #[test]
+    fn torn_or_stale_wal_records_are_discarded_on_reopen() {
+        let db = TempDb::new("torn-wal");
+        let (size, root) = {
+            let mut store = db.open();
+            append(&mut store, b"first");
+            let outcome = append(&mut store, b"second");
+            let wal = store.store.tree("wal").unwrap();
+
+            // 写到一半的记录：合法编码被截断
+            let record = crash_after_wal(&store, b"torn", false);
+            let encoded = StorageCodec::encode(&record).unwrap();
+            wal.insert(record.seq.to_be_bytes(), &encoded[..encoded.len() / 2]).unwrap();
+
+            // 能解码但预期的根对不上的记录，以及追加前大小已过期的记录
+            let mismatched = WalRecord { seq: record.seq + 1, new_root: [0xAB; 32], ..record.clone() };
+            let stale = WalRecord { seq: record.seq + 2, prev_size: record.prev_size + 1, ..record };
+            store.store.wal_put(&[mismatched, stale]).unwrap();
+            (outcome.mmr_size, outcome.root)
+        };
+
+        let store = db.open();
+        assert_eq!(store.mmr_size(), size);
+        assert_eq!(store.root(), Some(root));
+        assert!(store.store.tree("wal").unwrap().is_empty());
+        assert!(store.store.get_evidence_bytes(leaf_index_to_pos(2)).unwrap().is_none());
     }
 }
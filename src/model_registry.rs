@@ -0,0 +1,386 @@
+use ed25519_dalek::{Signature, VerifyingKey};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use sled::transaction::TransactionError;
+use sled::Transactional;
+use std::collections::{BTreeMap, BTreeSet};
+
+use crate::codec::StorageCodec;
+use crate::mmr_store::SledStore;
+use crate::signer::EvidenceSigner;
+
+/// 根元数据签名的域分隔前缀
+const ROOT_DOMAIN: &[u8] = b"yuanjing-core model registry root v1";
+
+/// 目标元数据签名的域分隔前缀
+const TARGETS_DOMAIN: &[u8] = b"yuanjing-core model registry targets v1";
+
+/// 角色：一组公钥与签名门限
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct RoleKeys {
+    /// Ed25519 公钥 (Hex)
+    pub public_keys: Vec<String>,
+    /// 至少需要多少把不同公钥的有效签名
+    pub threshold: u32,
+}
+
+impl RoleKeys {
+    fn validate(&self, role: &str) -> anyhow::Result<()> {
+        for key in &self.public_keys {
+            parse_key(key).map_err(|e| anyhow::anyhow!("{} role: {}", role, e))?;
+        }
+        if self.threshold == 0 || self.threshold as usize > self.public_keys.iter().collect::<BTreeSet<_>>().len() {
+            return Err(anyhow::anyhow!(
+                "{} role threshold must be between 1 and the number of distinct keys, got {}",
+                role,
+                self.threshold
+            ));
+        }
+        Ok(())
+    }
+
+    /// 达到门限时返回 true；同一公钥的多个签名只算一次，不在角色内的签名忽略
+    fn is_satisfied_by(&self, message: &[u8], signatures: &[MetadataSignature]) -> bool {
+        let signers: BTreeSet<&str> = signatures
+            .iter()
+            .filter(|signature| self.public_keys.contains(&signature.public_key))
+            .filter(|signature| signature.verify(message))
+            .map(|signature| signature.public_key.as_str())
+            .collect();
+        signers.len() >= self.threshold as usize
+    }
+}
+
+/// 根元数据：规定哪些公钥可以签发根与目标元数据
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct RootMetadata {
+    /// 版本号，轮换时逐一递增
+    pub version: u64,
+    /// 过期时间 (Unix 秒)
+    pub expires: i64,
+    pub root: RoleKeys,
+    pub targets: RoleKeys,
+}
+
+impl RootMetadata {
+    pub fn canonical_bytes(&self) -> anyhow::Result<Vec<u8>> {
+        let mut bytes = ROOT_DOMAIN.to_vec();
+        bytes.extend_from_slice(&bcs::to_bytes(self)?);
+        Ok(bytes)
+    }
+}
+
+/// 目标元数据：当前获准的模型版本 (Prompt 池哈希 → 说明)
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct TargetsMetadata {
+    /// 版本号，只增不减
+    pub version: u64,
+    /// 过期时间 (Unix 秒)
+    pub expires: i64,
+    pub models: BTreeMap<String, String>,
+}
+
+impl TargetsMetadata {
+    pub fn canonical_bytes(&self) -> anyhow::Result<Vec<u8>> {
+        let mut bytes = TARGETS_DOMAIN.to_vec();
+        bytes.extend_from_slice(&bcs::to_bytes(self)?);
+        Ok(bytes)
+    }
+}
+
+/// 元数据上的一个签名
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct MetadataSignature {
+    /// 签名公钥 (Hex)
+    pub public_key: String,
+    /// Ed25519 签名 (Hex)
+    pub signature: String,
+}
+
+impl MetadataSignature {
+    fn verify(&self, message: &[u8]) -> bool {
+        let Ok(key) = parse_key(&self.public_key) else {
+            return false;
+        };
+        hex::decode(&self.signature)
+            .ok()
+            .and_then(|bytes| Signature::from_slice(&bytes).ok())
+            .is_some_and(|signature| EvidenceSigner::verify_bytes(&key, message, &signature))
+    }
+}
+
+/// 签名的根元数据
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct SignedRootMetadata {
+    pub signed: RootMetadata,
+    pub signatures: Vec<MetadataSignature>,
+}
+
+impl SignedRootMetadata {
+    /// 读取签名的根元数据 (JSON 文件)
+    pub fn load(path: &str) -> anyhow::Result<Self> {
+        let text = std::fs::read_to_string(path).map_err(|e| anyhow::anyhow!("Cannot read registry root '{}': {}", path, e))?;
+        serde_json::from_str(&text).map_err(|e| anyhow::anyhow!("Invalid registry root '{}': {}", path, e))
+    }
+
+    /// 追加一个签名 (离线签名工具使用)
+    pub fn sign(&mut self, signer: &EvidenceSigner) -> anyhow::Result<()> {
+        let signature = signer.sign_bytes(&self.signed.canonical_bytes()?)?;
+        add_signature(&mut self.signatures, signer, signature);
+        Ok(())
+    }
+
+    /// 满足自身根角色的门限 (首个受信根、以及轮换后的新根都须如此)
+    pub fn is_self_signed(&self) -> anyhow::Result<bool> {
+        Ok(self.signed.root.is_satisfied_by(&self.signed.canonical_bytes()?, &self.signatures))
+    }
+}
+
+/// 签名的目标元数据
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct SignedTargetsMetadata {
+    pub signed: TargetsMetadata,
+    pub signatures: Vec<MetadataSignature>,
+}
+
+impl SignedTargetsMetadata {
+    /// 追加一个签名 (离线签名工具使用)
+    pub fn sign(&mut self, signer: &EvidenceSigner) -> anyhow::Result<()> {
+        let signature = signer.sign_bytes(&self.signed.canonical_bytes()?)?;
+        add_signature(&mut self.signatures, signer, signature);
+        Ok(())
+    }
+
+    /// 满足 `root` 中目标角色的门限
+    pub fn is_signed_by(&self, root: &RootMetadata) -> anyhow::Result<bool> {
+        Ok(root.targets.is_satisfied_by(&self.signed.canonical_bytes()?, &self.signatures))
+    }
+}
+
+/// 已接受的目标元数据及其生效时间
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AcceptedTargets {
+    pub targets: SignedTargetsMetadata,
+    /// 本服务接受该版本的时间 (Unix 秒)，直到下一个版本被接受前一直生效
+    pub accepted_at: i64,
+}
+
+fn add_signature(signatures: &mut Vec<MetadataSignature>, signer: &EvidenceSigner, signature: Signature) {
+    let public_key = hex::encode(signer.public_key().to_bytes());
+    signatures.retain(|existing| existing.public_key != public_key);
+    signatures.push(MetadataSignature { public_key, signature: hex::encode(signature.to_bytes()) });
+}
+
+fn parse_key(hex_key: &str) -> anyhow::Result<VerifyingKey> {
+    let bytes: [u8; 32] = hex::decode(hex_key)
+        .map_err(|_| anyhow::anyhow!("Invalid public key '{}'", hex_key))?
+        .try_into()
+        .map_err(|_| anyhow::anyhow!("Public key '{}' must be 32 bytes", hex_key))?;
+    VerifyingKey::from_bytes(&bytes).map_err(|_| anyhow::anyhow!("Invalid public key '{}'", hex_key))
+}
+
+/// 校验根轮换：版本号加一，同时满足旧根与新根的根角色门限，且尚未过期
+pub fn check_root_rotation(current: &RootMetadata, next: &SignedRootMetadata, now: i64) -> anyhow::Result<()> {
+    next.signed.root.validate("root")?;
+    next.signed.targets.validate("targets")?;
+    if next.signed.version != current.version + 1 {
+        return Err(anyhow::anyhow!("Root version must be {}, got {}", current.version + 1, next.signed.version));
+    }
+    if next.signed.expires <= now {
+        return Err(anyhow::anyhow!("Root metadata v{} has already expired", next.signed.version));
+    }
+    if !current.root.is_satisfied_by(&next.signed.canonical_bytes()?, &next.signatures) {
+        return Err(anyhow::anyhow!("Root metadata v{} lacks a threshold of signatures from the current root keys", next.signed.version));
+    }
+    if !next.is_self_signed()? {
+        return Err(anyhow::anyhow!("Root metadata v{} lacks a threshold of signatures from its own root keys", next.signed.version));
+    }
+    Ok(())
+}
+
+/// 校验新的目标元数据：由当前根的目标角色达到门限签名，版本号大于已接受的版本 (防回滚)，且尚未过期
+pub fn check_targets(root: &RootMetadata, current: Option<&TargetsMetadata>, next: &SignedTargetsMetadata, now: i64) -> anyhow::Result<()> {
+    if root.expires <= now {
+        return Err(anyhow::anyhow!("Root metadata v{} has expired; rotate the root first", root.version));
+    }
+    if let Some(current) = current {
+        if next.signed.version <= current.version {
+            return Err(anyhow::anyhow!("Targets version must be greater than {}, got {}", current.version, next.signed.version));
+        }
+    }
+    if next.signed.expires <= now {
+        return Err(anyhow::anyhow!("Targets metadata v{} has already expired", next.signed.version));
+    }
+    if !next.is_signed_by(root)? {
+        return Err(anyhow::anyhow!("Targets metadata v{} lacks a threshold of signatures from the targets keys", next.signed.version));
+    }
+    Ok(())
+}
+
+/// 离线校验：某模型版本在 `at` 时刻是否由签名的注册表批准
+///
+/// 从事先信任的根 `trusted_root` 出发沿 `roots` (按版本升序) 逐级校验轮换，
+/// 再在 `targets` (已接受的目标元数据) 中找出 `at` 时刻生效的那一版，要求它由当时生效的根签名、未过期且列出该模型。
+/// 通过时返回该目标元数据的版本号。
+pub fn verify_model_at(
+    trusted_root: &SignedRootMetadata,
+    roots: &[SignedRootMetadata],
+    targets: &[AcceptedTargets],
+    prompt_pool_hash: &str,
+    at: i64,
+) -> anyhow::Result<u64> {
+    if !trusted_root.is_self_signed()? {
+        return Err(anyhow::anyhow!("Trusted root is not signed by its own root keys"));
+    }
+    let mut chain = vec![trusted_root.signed.clone()];
+    for next in roots.iter().filter(|root| root.signed.version > trusted_root.signed.version) {
+        let current = chain.last().expect("chain starts with the trusted root");
+        // 过期只在接受新根时检查；这里逐级核对签名与版本
+        check_root_rotation(current, next, i64::MIN)?;
+        chain.push(next.signed.clone());
+    }
+    let accepted = targets
+        .iter()
+        .filter(|accepted| accepted.accepted_at <= at)
+        .max_by_key(|accepted| accepted.targets.signed.version)
+        .ok_or_else(|| anyhow::anyhow!("No signed targets metadata was in force at {}", at))?;
+    let signed = &accepted.targets;
+    let in_force = chain
+        .iter()
+        .rev()
+        .find(|root| signed.is_signed_by(root).unwrap_or(false))
+        .ok_or_else(|| anyhow::anyhow!("Targets metadata v{} is not signed by any root in the chain", signed.signed.version))?;
+    if signed.signed.expires <= at || in_force.expires <= at {
+        return Err(anyhow::anyhow!("Registry metadata in force at {} had expired", at));
+    }
+    if !signed.signed.models.contains_key(prompt_pool_hash) {
+        return Err(anyhow::anyhow!("Model '{}' is not listed in targets metadata v{}", prompt_pool_hash, signed.signed.version));
+    }
+    Ok(signed.signed.version)
+}
+
+/// 模块：签名的模型注册表 (Signed Model Registry)
+///
+/// **为什么需要**: 模型白名单原先由 API 节点直接写入 (`POST /model/register`)，拿下一台 API 节点就能悄悄批准一个
+/// 被投毒的 Prompt 池，此后它出具的证据照样签名入库。这里仿照 TUF 把白名单本身变成签名元数据：
+/// 离线保管的根密钥签发根元数据 (规定根角色与目标角色的公钥和门限)，目标密钥签发目标元数据 (获准的模型列表)，
+/// 两者都带版本号与过期时间。服务端只持有公钥，只能接受、不能伪造；白名单随被接受的目标元数据整体替换。
+///
+/// - 根轮换须同时满足旧根与新根的门限，版本号逐一递增
+/// - 目标元数据版本号只增不减 (防回滚)，过期后拒绝新的存证，直到上传新版本
+/// - 根与目标元数据的每个被接受的版本都存档 (`registry_roots` / `registry_targets`)，
+///   验证方用 [`verify_model_at`] 离线核对某条证据签发时其模型是否获准
+///
+/// 一旦以受信根引导，本租户便处于签名模式，直接注册模型的接口随之关闭。
+#[derive(Clone)]
+pub struct ModelRegistry {
+    roots: sled::Tree,
+    targets: sled::Tree,
+    allowlist: sled::Tree,
+}
+
+impl ModelRegistry {
+    pub fn open(store: &SledStore) -> anyhow::Result<Self> {
+        Ok(Self {
+            roots: store.tree("registry_roots")?,
+            targets: store.tree("registry_targets")?,
+            allowlist: store.tree("models_allowlist")?,
+        })
+    }
+
+    /// 是否处于签名模式 (已引导受信根)
+    pub fn is_enabled(&self) -> bool {
+        !self.roots.is_empty()
+    }
+
+    /// 当前 (版本最高的) 根元数据
+    pub fn root(&self) -> anyhow::Result<Option<SignedRootMetadata>> {
+        self.roots.last()?.map(|(_, v)| StorageCodec::decode(&v)).transpose()
+    }
+
+    /// 全部根元数据 (按版本升序)
+    pub fn roots(&self) -> anyhow::Result<Vec<SignedRootMetadata>> {
+        self.roots.iter().map(|item| StorageCodec::decode(&item?.1)).collect()
+    }
+
+    /// 当前生效的目标元数据
+    pub fn targets(&self) -> anyhow::Result<Option<AcceptedTargets>> {
+        self.targets.last()?.map(|(_, v)| StorageCodec::decode(&v)).transpose()
+    }
+
+    /// 全部被接受过的目标元数据 (按版本升序)
+    pub fn targets_history(&self) -> anyhow::Result<Vec<AcceptedTargets>> {
+        self.targets.iter().map(|item| StorageCodec::decode(&item?.1)).collect()
+    }
+
+    /// 以受信根引导：尚未引导时存入，已引导时须与存档中同一版本的根一致；返回是否新存入
+    pub fn bootstrap(&self, trusted: &SignedRootMetadata) -> anyhow::Result<bool> {
+        trusted.signed.root.validate("root")?;
+        trusted.signed.targets.validate("targets")?;
+        if !trusted.is_self_signed()? {
+            return Err(anyhow::anyhow!("Trusted root v{} is not signed by its own root keys", trusted.signed.version));
+        }
+        let key = trusted.signed.version.to_be_bytes();
+        if self.is_enabled() {
+            let stored: Option<SignedRootMetadata> = self.roots.get(key)?.map(|v| StorageCodec::decode(&v)).transpose()?;
+            return match stored {
+                Some(stored) if stored.signed == trusted.signed => Ok(false),
+                _ => Err(anyhow::anyhow!("Trusted root v{} does not match the stored root chain", trusted.signed.version)),
+            };
+        }
+        self.roots.insert(key, StorageCodec::encode(trusted)?)?;
+        self.roots.flush()?;
+        Ok(true)
+    }
+
+    /// 接受根轮换
+    pub fn rotate_root(&self, next: &SignedRootMetadata, now: i64) -> anyhow::Result<()> {
+        let current = self.root()?.ok_or_else(|| anyhow::anyhow!("The signed model registry is not enabled"))?;
+        check_root_rotation(&current.signed, next, now)?;
+        self.roots.insert(next.signed.version.to_be_bytes(), StorageCodec::encode(next)?)?;
+        self.roots.flush()?;
+        Ok(())
+    }
+
+    /// 接受新的目标元数据：存档，并在同一个事务中把模型白名单整体替换为其中的模型列表
+    pub fn update_targets(&self, next: &SignedTargetsMetadata, now: i64) -> anyhow::Result<AcceptedTargets> {
+        let root = self.root()?.ok_or_else(|| anyhow::anyhow!("The signed model registry is not enabled"))?;
+        let current = self.targets()?;
+        check_targets(&root.signed, current.as_ref().map(|accepted| &accepted.targets.signed), next, now)?;
+        let accepted = AcceptedTargets { targets: next.clone(), accepted_at: now };
+        let encoded = StorageCodec::encode(&accepted)?;
+        let stale: Vec<sled::IVec> = self.allowlist.iter().keys().collect::<Result<_, _>>()?;
+        (&self.targets, &self.allowlist)
+            .transaction(|(targets, allowlist)| {
+                for key in &stale {
+                    allowlist.remove(key)?;
+                }
+                for (hash, description) in &next.signed.models {
+                    allowlist.insert(hash.as_bytes(), description.as_bytes())?;
+                }
+                targets.insert(&next.signed.version.to_be_bytes(), encoded.as_slice())?;
+                Ok(())
+            })
+            .map_err(|e: TransactionError<()>| anyhow::anyhow!("Registry transaction failed: {:?}", e))?;
+        self.targets.flush()?;
+        self.allowlist.flush()?;
+        Ok(accepted)
+    }
+
+    /// 签名模式下，注册表元数据在 `now` 是否仍然有效 (根与目标元数据都未过期，且已有目标元数据)
+    pub fn check_current(&self, now: i64) -> anyhow::Result<()> {
+        let Some(root) = self.root()? else {
+            return Ok(());
+        };
+        if root.signed.expires <= now {
+            return Err(anyhow::anyhow!("Model registry root metadata v{} has expired", root.signed.version));
+        }
+        match self.targets()? {
+            None => Err(anyhow::anyhow!("Model registry has no signed targets metadata yet")),
+            Some(accepted) if accepted.targets.signed.expires <= now => {
+                Err(anyhow::anyhow!("Model registry targets metadata v{} has expired", accepted.targets.signed.version))
+            }
+            Some(_) => Ok(()),
+        }
+    }
+}
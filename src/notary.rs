@@ -0,0 +1,428 @@
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use ed25519_dalek::{Signature, VerifyingKey};
+use log::{info, warn};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use std::time::Duration;
+
+use crate::codec::StorageCodec;
+use crate::mmr_store::SledStore;
+use crate::publication::SignedTreeHead;
+use crate::signer::EvidenceSigner;
+use crate::tenant::TenantContext;
+use crate::tsp::{TimeStampRequest, TimeStampToken};
+use crate::x509::TrustStore;
+
+/// 公证方副署的域分隔前缀
+const COUNTERSIGNATURE_DOMAIN: &[u8] = b"yuanjing-core notary countersignature v1";
+
+/// 提交 TSA 的原文的域分隔前缀 (TSA 只对原文的摘要盖时间戳)
+const TIMESTAMP_DOMAIN: &[u8] = b"yuanjing-core timestamped tree head v1";
+
+/// 公证方副署的内容：我方签名树头 + 公证时间
+///
+/// 公证方对 `"yuanjing-core notary countersignature v1" || BCS(NotaryStatement)` 签名。
+/// 签名树头已包含我方的签名与公钥，副署即证明“公证方在 `notarized_at` 见过这个由该公钥签出的树头”。
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct NotaryStatement {
+    pub signed_tree_head: SignedTreeHead,
+    /// 公证方记录的时间 (Unix 秒)
+    pub notarized_at: i64,
+}
+
+impl NotaryStatement {
+    pub fn canonical_bytes(&self) -> anyhow::Result<Vec<u8>> {
+        let mut bytes = COUNTERSIGNATURE_DOMAIN.to_vec();
+        bytes.extend_from_slice(&bcs::to_bytes(self)?);
+        Ok(bytes)
+    }
+}
+
+/// 公证回执：公证方对签名树头的副署
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct NotaryReceipt {
+    /// 公证方名称 (本地配置)
+    pub notary: String,
+    /// 公证方公钥 (Hex)
+    pub notary_public_key: String,
+    pub statement: NotaryStatement,
+    /// 公证方签名 (Hex)
+    pub signature: String,
+    /// 公证方自己的回执编号 (若有)，便于向公证方查询
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub receipt_id: Option<String>,
+}
+
+impl NotaryReceipt {
+    /// 校验副署；`trusted_key` 为事先固定的公证方公钥，与回执自带的公钥不符时直接判为无效
+    pub fn verify(&self, trusted_key: &VerifyingKey) -> anyhow::Result<bool> {
+        if hex::encode(trusted_key.to_bytes()) != self.notary_public_key {
+            return Ok(false);
+        }
+        let signature = Signature::from_slice(&hex::decode(&self.signature)?)?;
+        Ok(EvidenceSigner::verify_bytes(trusted_key, &self.statement.canonical_bytes()?, &signature))
+    }
+}
+
+/// RFC 3161 时间戳回执：TSA 对签名树头签发的时间戳令牌
+///
+/// 令牌盖的是 `"yuanjing-core timestamped tree head v1" || BCS(SignedTreeHead)` 的 SHA-256 摘要。
+/// 除 `TimestampReceipt::verify` 外，也可以把这段原文与 `token` 交给 `openssl ts -verify` 独立校验。
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct TimestampReceipt {
+    /// TSA 名称 (本地配置)
+    pub tsa: String,
+    pub signed_tree_head: SignedTreeHead,
+    /// 令牌的签发时间 genTime (Unix 秒)
+    pub gen_time: i64,
+    /// TSA 的时间戳策略 (OID)
+    pub policy: String,
+    /// 令牌序列号 (Hex)
+    pub serial_number: String,
+    /// DER 编码的 TimeStampToken (Base64)
+    pub token: String,
+}
+
+impl TimestampReceipt {
+    /// 提交 TSA 的原文
+    pub fn timestamped_bytes(head: &SignedTreeHead) -> anyhow::Result<Vec<u8>> {
+        let mut bytes = TIMESTAMP_DOMAIN.to_vec();
+        bytes.extend_from_slice(&bcs::to_bytes(head)?);
+        Ok(bytes)
+    }
+
+    /// 校验令牌：盖的是本树头，字段与令牌一致，且 TSA 证书在签发时刻可追溯到 `trust` 中的受信根
+    pub fn verify(&self, trust: &TrustStore) -> anyhow::Result<()> {
+        let token = TimeStampToken::from_der(BASE64.decode(&self.token)?)?;
+        token.verify(&Self::timestamped_bytes(&self.signed_tree_head)?, trust)?;
+        let info = token.info();
+        if info.gen_time != self.gen_time || info.policy != self.policy || hex::encode(&info.serial_number) != self.serial_number {
+            return Err(anyhow::anyhow!("Timestamp receipt does not match its token"));
+        }
+        Ok(())
+    }
+}
+
+/// 提交给公证方的请求体
+#[derive(Serialize)]
+struct NotarizeRequest<'a> {
+    signed_tree_head: &'a SignedTreeHead,
+}
+
+/// 公证方的响应
+#[derive(Deserialize)]
+struct NotarizeResponse {
+    notarized_at: i64,
+    /// 副署签名 (Hex)
+    signature: String,
+    #[serde(default)]
+    receipt_id: Option<String>,
+}
+
+/// 公证方的协议 (`[[anchoring.notaries]]` 的 `protocol`)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum NotaryKind {
+    /// RFC 3161 时间戳机构 (默认)
+    #[default]
+    Rfc3161,
+    /// 本服务自定的副署协议
+    Countersign,
+}
+
+impl NotaryKind {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Rfc3161 => "rfc3161",
+            Self::Countersign => "countersign",
+        }
+    }
+}
+
+/// 与公证方交互的协议及其信任配置
+#[derive(Debug, Clone)]
+pub enum NotaryProtocol {
+    /// RFC 3161 时间戳机构：TSA 证书须在签发时刻可追溯到 `trust` 中的受信根，且带有时间戳用途
+    Rfc3161 {
+        trust: TrustStore,
+        /// 要求的时间戳策略 (OID)，为空时由 TSA 选择
+        policy: Option<String>,
+    },
+    /// 本服务自定的副署协议：公证方用事先固定的公钥副署，副署不符的响应一律丢弃
+    Countersign { public_key: VerifyingKey },
+}
+
+/// 外部公证方
+#[derive(Debug, Clone)]
+pub struct NotaryTarget {
+    pub name: String,
+    /// 公证接口地址 (`POST`)
+    pub url: String,
+    pub protocol: NotaryProtocol,
+    /// 以 `Authorization: Bearer` 发送，为空时不发送
+    pub api_key: String,
+    /// 允许公证时间早于树头时间的最大偏差 (秒)
+    pub max_skew_secs: i64,
+}
+
+impl NotaryTarget {
+    /// 提交签名树头并校验副署 (副署协议)
+    pub async fn countersign(&self, head: &SignedTreeHead, public_key: &VerifyingKey) -> anyhow::Result<NotaryReceipt> {
+        let body = serde_json::to_vec(&NotarizeRequest { signed_tree_head: head })?;
+        let response = self.post("application/json", body).await?;
+        let response: NotarizeResponse = serde_json::from_slice(&response)
+            .map_err(|e| anyhow::anyhow!("Unexpected notary response from '{}': {}", self.url, e))?;
+
+        self.check_skew(head, response.notarized_at)?;
+        let receipt = NotaryReceipt {
+            notary: self.name.clone(),
+            notary_public_key: hex::encode(public_key.to_bytes()),
+            statement: NotaryStatement { signed_tree_head: head.clone(), notarized_at: response.notarized_at },
+            signature: response.signature,
+            receipt_id: response.receipt_id,
+        };
+        if !receipt.verify(public_key)? {
+            return Err(anyhow::anyhow!("Notary '{}' returned an invalid countersignature", self.name));
+        }
+        Ok(receipt)
+    }
+
+    /// 向 TSA 申请签名树头的时间戳并校验令牌 (RFC 3161)
+    pub async fn timestamp(&self, head: &SignedTreeHead, trust: &TrustStore, policy: Option<&str>) -> anyhow::Result<TimestampReceipt> {
+        let message = TimestampReceipt::timestamped_bytes(head)?;
+        let request = TimeStampRequest::new(&message, rand::random(), policy);
+        let response = self.post("application/timestamp-query", request.to_der()?).await?;
+        let token = TimeStampToken::from_response(&response)
+            .map_err(|e| anyhow::anyhow!("Unexpected TSA response from '{}': {}", self.url, e))?;
+
+        let info = token.info();
+        if info.nonce != Some(request.nonce) {
+            return Err(anyhow::anyhow!("TSA '{}' did not echo the request nonce", self.name));
+        }
+        if policy.is_some_and(|policy| policy != info.policy) {
+            return Err(anyhow::anyhow!("TSA '{}' answered under policy {} instead of {}", self.name, info.policy, policy.unwrap_or_default()));
+        }
+        token
+            .verify(&message, trust)
+            .map_err(|e| anyhow::anyhow!("TSA '{}' returned an invalid time-stamp token: {}", self.name, e))?;
+        self.check_skew(head, info.gen_time)?;
+        Ok(TimestampReceipt {
+            tsa: self.name.clone(),
+            signed_tree_head: head.clone(),
+            gen_time: info.gen_time,
+            policy: info.policy.clone(),
+            serial_number: hex::encode(&info.serial_number),
+            token: BASE64.encode(token.as_der()),
+        })
+    }
+
+    fn check_skew(&self, head: &SignedTreeHead, notarized_at: i64) -> anyhow::Result<()> {
+        if notarized_at < head.tree_head.timestamp - self.max_skew_secs {
+            return Err(anyhow::anyhow!(
+                "Notary '{}' timestamp {} predates the tree head ({})",
+                self.name,
+                notarized_at,
+                head.tree_head.timestamp
+            ));
+        }
+        Ok(())
+    }
+
+    async fn post(&self, content_type: &str, body: Vec<u8>) -> anyhow::Result<Vec<u8>> {
+        let mut request = ureq::post(&self.url)
+            .timeout(Duration::from_secs(30))
+            .set("Content-Type", content_type);
+        if !self.api_key.is_empty() {
+            request = request.set("Authorization", &format!("Bearer {}", self.api_key));
+        }
+        let url = self.url.clone();
+        tokio::task::spawn_blocking(move || match request.send_bytes(&body) {
+            Ok(response) => {
+                let mut bytes = Vec::new();
+                std::io::Read::read_to_end(&mut response.into_reader(), &mut bytes)?;
+                Ok(bytes)
+            }
+            Err(ureq::Error::Status(code, response)) => Err(anyhow::anyhow!(
+                "Notary '{}' rejected the tree head ({}): {}",
+                url,
+                code,
+                response.into_string().unwrap_or_default()
+            )),
+            Err(e) => Err(anyhow::anyhow!("Notary request to '{}' failed: {}", url, e)),
+        })
+        .await?
+    }
+}
+
+/// 公证回执存档 (Notary Receipt Archive)
+///
+/// 按 `公证方名称 || 0x00 || 树大小` 存于 `notary_receipts` 树，只增不删。
+#[derive(Clone)]
+pub struct NotaryArchive {
+    receipts: sled::Tree,
+}
+
+impl NotaryArchive {
+    pub fn open(store: &SledStore) -> anyhow::Result<Self> {
+        Ok(Self { receipts: store.tree("notary_receipts")? })
+    }
+
+    fn prefix(notary: &str) -> Vec<u8> {
+        let mut prefix = notary.as_bytes().to_vec();
+        prefix.push(0);
+        prefix
+    }
+
+    /// 该公证方最近一次副署的回执
+    pub fn latest(&self, notary: &str) -> anyhow::Result<Option<NotaryReceipt>> {
+        self.receipts.scan_prefix(Self::prefix(notary)).next_back().transpose()?
+            .map(|(_, v)| StorageCodec::decode(&v))
+            .transpose()
+    }
+
+    pub fn append(&self, receipt: &NotaryReceipt) -> anyhow::Result<()> {
+        let mut key = Self::prefix(&receipt.notary);
+        key.extend_from_slice(&receipt.statement.signed_tree_head.tree_head.tree_size.to_be_bytes());
+        self.receipts.insert(key, StorageCodec::encode(receipt)?)?;
+        self.receipts.flush()?;
+        Ok(())
+    }
+
+    /// 最近的 `limit` 条 (树大小大的在前；同一树大小按公证方名称排列)
+    pub fn recent(&self, limit: usize) -> anyhow::Result<Vec<NotaryReceipt>> {
+        let mut receipts = self.receipts.iter()
+            .map(|item| StorageCodec::decode(&item?.1))
+            .collect::<anyhow::Result<Vec<NotaryReceipt>>>()?;
+        receipts.sort_by(|a, b| {
+            b.statement.signed_tree_head.tree_head.tree_size
+                .cmp(&a.statement.signed_tree_head.tree_head.tree_size)
+                .then_with(|| a.notary.cmp(&b.notary))
+        });
+        receipts.truncate(limit);
+        Ok(receipts)
+    }
+}
+
+/// 时间戳回执存档
+///
+/// 与 `NotaryArchive` 同样按 `TSA 名称 || 0x00 || 树大小` 存于 `timestamp_tokens` 树，只增不删。
+#[derive(Clone)]
+pub struct TimestampArchive {
+    receipts: sled::Tree,
+}
+
+impl TimestampArchive {
+    pub fn open(store: &SledStore) -> anyhow::Result<Self> {
+        Ok(Self { receipts: store.tree("timestamp_tokens")? })
+    }
+
+    /// 该 TSA 最近一次签发的回执
+    pub fn latest(&self, tsa: &str) -> anyhow::Result<Option<TimestampReceipt>> {
+        self.receipts.scan_prefix(NotaryArchive::prefix(tsa)).next_back().transpose()?
+            .map(|(_, v)| StorageCodec::decode(&v))
+            .transpose()
+    }
+
+    pub fn append(&self, receipt: &TimestampReceipt) -> anyhow::Result<()> {
+        let mut key = NotaryArchive::prefix(&receipt.tsa);
+        key.extend_from_slice(&receipt.signed_tree_head.tree_head.tree_size.to_be_bytes());
+        self.receipts.insert(key, StorageCodec::encode(receipt)?)?;
+        self.receipts.flush()?;
+        Ok(())
+    }
+
+    /// 最近的 `limit` 条 (树大小大的在前；同一树大小按 TSA 名称排列)
+    pub fn recent(&self, limit: usize) -> anyhow::Result<Vec<TimestampReceipt>> {
+        let mut receipts = self.receipts.iter()
+            .map(|item| StorageCodec::decode(&item?.1))
+            .collect::<anyhow::Result<Vec<TimestampReceipt>>>()?;
+        receipts.sort_by(|a, b| {
+            b.signed_tree_head.tree_head.tree_size
+                .cmp(&a.signed_tree_head.tree_head.tree_size)
+                .then_with(|| a.tsa.cmp(&b.tsa))
+        });
+        receipts.truncate(limit);
+        Ok(receipts)
+    }
+}
+
+/// 模块：交叉公证 (Cross-Notarization)
+///
+/// **为什么需要**: 签名树头只由我们自己的密钥签发，若密钥泄露或运营方作恶，攻击者可以事后伪造一整段历史并重新签名。
+/// 把树头周期性地提交给独立的第三方公证方，由对方用自己的密钥签名并注明时间，
+/// 之后任何人都能证明“某个根在某时刻之前就已存在”，信任不再只系于我方一把密钥。
+///
+/// - 公证方默认是 RFC 3161 时间戳机构 (TSA)，令牌入库前校验随机数、摘要、签名与 TSA 证书链
+/// - 也支持本服务自定的副署协议，副署在入库前用事先固定的公证方公钥校验
+/// - 只提交根发布存档中的最新树头，每个公证方对同一树大小只提交一次；失败时下一轮重试
+/// - 回执经 `/roots/notarizations` 公开，验证方用 `TimestampReceipt::verify` / `NotaryReceipt::verify` 离线校验
+///
+/// 两种协议的细节见 `docs/API.md`。
+#[derive(Clone)]
+pub struct Notarizer {
+    notaries: Arc<Vec<NotaryTarget>>,
+    interval: Duration,
+}
+
+impl Notarizer {
+    pub fn new(interval: Duration, notaries: Vec<NotaryTarget>) -> Self {
+        Self { notaries: Arc::new(notaries), interval }
+    }
+
+    /// 对所有租户执行一次公证
+    pub async fn notarize_once(&self, tenants: &[Arc<TenantContext>]) {
+        for tenant in tenants {
+            for notary in self.notaries.iter() {
+                if let Err(e) = self.notarize_tenant(tenant, notary).await {
+                    warn!("⚠️  租户 '{}' 树头提交公证方 '{}' 失败 (下次重试): {}", tenant.id, notary.name, e);
+                }
+            }
+        }
+    }
+
+    async fn notarize_tenant(&self, tenant: &TenantContext, notary: &NotaryTarget) -> anyhow::Result<()> {
+        let snapshot = tenant.reader.snapshot();
+        let Some(head) = snapshot.tree_heads()?.latest()? else {
+            return Ok(());
+        };
+        let tree_size = head.tree_head.tree_size;
+        match &notary.protocol {
+            NotaryProtocol::Rfc3161 { trust, policy } => {
+                let archive = snapshot.timestamp_tokens()?;
+                if archive.latest(&notary.name)?.is_some_and(|latest| latest.signed_tree_head.tree_head.tree_size >= tree_size) {
+                    return Ok(());
+                }
+                let receipt = notary.timestamp(&head, trust, policy.as_deref()).await?;
+                archive.append(&receipt)?;
+                info!(
+                    "🤝 租户 '{}' 树头已获 TSA '{}' 时间戳 (size={}, 签发时间 {}, 序列号 {})",
+                    tenant.id, notary.name, tree_size, receipt.gen_time, receipt.serial_number
+                );
+            }
+            NotaryProtocol::Countersign { public_key } => {
+                let archive = snapshot.notary_receipts()?;
+                if archive.latest(&notary.name)?.is_some_and(|latest| latest.statement.signed_tree_head.tree_head.tree_size >= tree_size) {
+                    return Ok(());
+                }
+                let receipt = notary.countersign(&head, public_key).await?;
+                archive.append(&receipt)?;
+                info!(
+                    "🤝 租户 '{}' 树头已获公证方 '{}' 副署 (size={}, 公证时间 {})",
+                    tenant.id, notary.name, tree_size, receipt.statement.notarized_at
+                );
+            }
+        }
+        Ok(())
+    }
+
+    /// 启动后台周期公证任务 (启动时先执行一次)
+    pub fn spawn_periodic(self, tenants: Vec<Arc<TenantContext>>) {
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(self.interval);
+            loop {
+                ticker.tick().await;
+                self.notarize_once(&tenants).await;
+            }
+        });
+    }
+}
@@ -0,0 +1,302 @@
+use log::{info, warn};
+use std::fs::{File, OpenOptions};
+use std::io::{BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+use crate::mmr_store::StoreSnapshot;
+use crate::tenant::TenantContext;
+
+/// 日志条目哈希的域分隔前缀，随后是该行 JSON 的原始字节
+const ENTRY_DOMAIN: &[u8] = b"yuanjing-core ops log entry v1";
+
+/// 库目录中运维日志的默认文件名 (未配置 `ops_log.path` 时)
+pub const OPS_LOG_FILE: &str = "ops.log";
+
+/// 检查点叶子的域分隔前缀，使其原文不可能被当作证据解码
+const CHECKPOINT_DOMAIN: &[u8] = b"yuanjing-core ops log checkpoint v1";
+
+/// 运维事件
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum OpsEvent {
+    /// 服务启动
+    Startup { version: String },
+    /// 生效配置与上次启动时不同 (指纹为 Blake3(配置) 的前 16 字节)
+    ConfigChanged { previous: Option<String>, fingerprint: String },
+    /// 加载密钥 (`key_id` 为公钥或密钥 ID，不含任何秘密)
+    KeyLoaded { purpose: String, key_id: String, source: String },
+    /// 保留期清理删除了原文
+    Pruned { tenant_id: String, count: u64, cutoff: i64 },
+    /// 链头已作为检查点叶子写进该租户的 MMR (`covers` 为检查点覆盖到的最后一条)
+    Checkpoint { tenant_id: String, covers: u64, head: String, pos: u64 },
+    /// 提交方触发滥用规则被限流 (见 `abuse.rs`)
+    Throttled { tenant_id: String, principal: String, rule: String, until: i64 },
+}
+
+/// 运维日志条目：文件中的一行 JSON
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct OpsLogEntry {
+    /// 条目序号，从 0 开始连续递增
+    pub index: u64,
+    /// 记录时间 (Unix 秒)
+    pub at: i64,
+    #[serde(flatten)]
+    pub event: OpsEvent,
+    /// 上一条的哈希 (Hex)，首条为全零
+    pub prev_hash: String,
+}
+
+/// 运维日志检查点，作为一片普通叶子追加进 MMR
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct OpsCheckpoint {
+    /// 覆盖到的最后一条日志序号
+    pub index: u64,
+    /// 该条日志的哈希 (Hex)
+    pub head: String,
+    pub timestamp: i64,
+}
+
+impl OpsCheckpoint {
+    /// 检查点叶子原文 (域前缀 + BCS)，叶子哈希为其 Blake3
+    pub fn canonical_bytes(&self) -> anyhow::Result<Vec<u8>> {
+        let mut bytes = CHECKPOINT_DOMAIN.to_vec();
+        bytes.extend_from_slice(&bcs::to_bytes(self)?);
+        Ok(bytes)
+    }
+
+    /// 从叶子原文还原；不是检查点时返回 None
+    pub fn from_bytes(bytes: &[u8]) -> anyhow::Result<Option<Self>> {
+        bytes
+            .strip_prefix(CHECKPOINT_DOMAIN)
+            .map(|payload| Ok(bcs::from_bytes(payload)?))
+            .transpose()
+    }
+}
+
+/// 条目哈希：Blake3(域前缀 || 该行 JSON)
+fn line_hash(line: &str) -> [u8; 32] {
+    let mut hasher = blake3::Hasher::new();
+    hasher.update(ENTRY_DOMAIN);
+    hasher.update(line.as_bytes());
+    *hasher.finalize().as_bytes()
+}
+
+struct Inner {
+    file: File,
+    next_index: u64,
+    head: [u8; 32],
+    /// 最近一条非检查点条目的序号 (待写入检查点的范围)
+    last_event: Option<u64>,
+    /// 最近一次检查点覆盖到的序号
+    checkpointed: Option<u64>,
+    /// 最近一次配置变更记录的指纹
+    fingerprint: Option<String>,
+}
+
+impl Inner {
+    /// 记下刚追加 (或启动时读到) 的一条
+    fn track(&mut self, entry: &OpsLogEntry, hash: [u8; 32]) {
+        match &entry.event {
+            OpsEvent::Checkpoint { covers, .. } => self.checkpointed = Some(*covers),
+            OpsEvent::ConfigChanged { fingerprint, .. } => {
+                self.fingerprint = Some(fingerprint.clone());
+                self.last_event = Some(entry.index);
+            }
+            _ => self.last_event = Some(entry.index),
+        }
+        self.next_index = entry.index + 1;
+        self.head = hash;
+    }
+}
+
+/// 模块：运维审计日志 (Ops Audit Log)
+///
+/// **为什么需要**: 签名日志只覆盖“签过什么”，而换密钥、改配置、清理原文这类运维动作同样决定证据是否可信，
+/// 以前只留在可随意改写的标准输出里。运维日志是节点本地的只追加 JSON Lines 文件，
+/// 每行带上一行的哈希 (`prev_hash`)，并定期把链头作为检查点叶子写进各租户的 MMR：
+/// 检查点之前的任何一行被删改，重算出的链头就与 MMR 中签过名的检查点不符。
+///
+/// 启动时重算整条链并核对文件中记录的每个检查点，不一致则拒绝启动。
+#[derive(Clone)]
+pub struct OpsLog {
+    path: PathBuf,
+    inner: Arc<Mutex<Inner>>,
+}
+
+impl OpsLog {
+    /// 打开 (或创建) 日志文件并校验哈希链
+    pub fn open(path: &Path) -> anyhow::Result<Self> {
+        if let Some(parent) = path.parent().filter(|parent| !parent.as_os_str().is_empty()) {
+            std::fs::create_dir_all(parent)?;
+        }
+        let mut inner = Inner {
+            file: OpenOptions::new().create(true).append(true).open(path)?,
+            next_index: 0,
+            head: [0u8; 32],
+            last_event: None,
+            checkpointed: None,
+            fingerprint: None,
+        };
+        for entry in read_chain(path)? {
+            let (entry, hash) = entry?;
+            inner.track(&entry, hash);
+        }
+        Ok(Self { path: path.to_path_buf(), inner: Arc::new(Mutex::new(inner)) })
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// 追加一条事件并立即落盘
+    pub fn record(&self, event: OpsEvent) -> anyhow::Result<OpsLogEntry> {
+        let mut inner = self.inner.lock().map_err(|_| anyhow::anyhow!("Ops log lock poisoned"))?;
+        let entry = OpsLogEntry {
+            index: inner.next_index,
+            at: chrono::Utc::now().timestamp(),
+            event,
+            prev_hash: hex::encode(inner.head),
+        };
+        let line = serde_json::to_string(&entry)?;
+        inner.file.write_all(format!("{}\n", line).as_bytes())?;
+        inner.file.sync_data()?;
+        inner.track(&entry, line_hash(&line));
+        Ok(entry)
+    }
+
+    /// 记录一条事件；日志写入失败只告警，不影响调用方
+    pub fn note(&self, event: OpsEvent) {
+        if let Err(e) = self.record(event) {
+            warn!("⚠️  运维日志写入失败 ('{}'): {}", self.path.display(), e);
+        }
+    }
+
+    /// 配置指纹与上次记录的不同时追加 `config_changed`
+    pub fn note_config(&self, fingerprint: String) {
+        let previous = self.inner.lock().ok().and_then(|inner| inner.fingerprint.clone());
+        if previous.as_deref() != Some(fingerprint.as_str()) {
+            self.note(OpsEvent::ConfigChanged { previous, fingerprint });
+        }
+    }
+
+    /// 从 `from` 开始最多 `limit` 条，连同各条的哈希
+    pub fn entries(&self, from: u64, limit: usize) -> anyhow::Result<Vec<(OpsLogEntry, [u8; 32])>> {
+        let _guard = self.inner.lock().map_err(|_| anyhow::anyhow!("Ops log lock poisoned"))?;
+        read_chain(&self.path)?.skip(from as usize).take(limit).collect()
+    }
+
+    /// 条数与当前链头
+    pub fn head(&self) -> anyhow::Result<(u64, [u8; 32])> {
+        let inner = self.inner.lock().map_err(|_| anyhow::anyhow!("Ops log lock poisoned"))?;
+        Ok((inner.next_index, inner.head))
+    }
+
+    /// 为当前链头生成检查点；上次检查点之后没有新事件时返回 None
+    fn pending_checkpoint(&self) -> Option<OpsCheckpoint> {
+        let inner = self.inner.lock().ok()?;
+        let last = inner.last_event?;
+        (inner.checkpointed < Some(last)).then(|| OpsCheckpoint {
+            index: inner.next_index - 1,
+            head: hex::encode(inner.head),
+            timestamp: chrono::Utc::now().timestamp(),
+        })
+    }
+
+    /// 把链头写进每个租户的 MMR，并把写入位置记回日志
+    pub async fn checkpoint(&self, tenants: &[Arc<TenantContext>]) {
+        let Some(checkpoint) = self.pending_checkpoint() else {
+            return;
+        };
+        for tenant in tenants {
+            match tenant.writer.append_ops_checkpoint(checkpoint.clone()).await {
+                Ok(pos) => {
+                    info!("📒 [{}] 运维日志检查点已写入 MMR: 第 {} 条, Pos={}", tenant.id, checkpoint.index, pos);
+                    self.note(OpsEvent::Checkpoint { tenant_id: tenant.id.clone(), covers: checkpoint.index, head: checkpoint.head.clone(), pos });
+                }
+                Err(e) => warn!("⚠️  [{}] 运维日志检查点写入失败 (下个周期重试): {}", tenant.id, e),
+            }
+        }
+    }
+
+    /// 启动周期检查点任务 (启动时先写一次，覆盖本次启动的事件)；`interval` 为零时不写检查点
+    pub fn spawn_periodic(self, interval: Duration, tenants: Vec<Arc<TenantContext>>) {
+        if interval.is_zero() {
+            return;
+        }
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            loop {
+                ticker.tick().await;
+                self.checkpoint(&tenants).await;
+            }
+        });
+    }
+
+    /// 核对文件中记录的每个检查点：叶子仍在该租户的 MMR 中，且与重算的链头一致
+    ///
+    /// `snapshot_of` 对已从配置中移除的租户返回 None，跳过其检查点。返回核对过的检查点数。
+    pub fn verify_checkpoints(&self, snapshot_of: impl Fn(&str) -> anyhow::Result<Option<StoreSnapshot>>) -> anyhow::Result<usize> {
+        let mut heads = Vec::new();
+        let mut verified = 0;
+        for (entry, hash) in self.entries(0, usize::MAX)? {
+            if let OpsEvent::Checkpoint { tenant_id, covers, head, pos } = &entry.event {
+                let Some(snapshot) = snapshot_of(tenant_id)? else {
+                    continue;
+                };
+                let recomputed = heads
+                    .get(*covers as usize)
+                    .ok_or_else(|| anyhow::anyhow!("Ops log checkpoint at entry {} covers a later entry {}", entry.index, covers))?;
+                if *head != hex::encode(recomputed) {
+                    return Err(anyhow::anyhow!("Ops log diverges from the checkpoint recorded at entry {}", entry.index));
+                }
+                let bytes = snapshot
+                    .evidence_bytes(*pos)?
+                    .ok_or_else(|| anyhow::anyhow!("Ops log checkpoint leaf at pos {} (tenant '{}') is missing", pos, tenant_id))?;
+                let committed = OpsCheckpoint::from_bytes(&bytes)?
+                    .ok_or_else(|| anyhow::anyhow!("Leaf at pos {} (tenant '{}') is not an ops log checkpoint", pos, tenant_id))?;
+                if snapshot.get_leaf(*pos)? != Some(*blake3::hash(&bytes).as_bytes()) {
+                    return Err(anyhow::anyhow!("Ops log checkpoint leaf at pos {} (tenant '{}') does not match the MMR", pos, tenant_id));
+                }
+                if committed.index != *covers || committed.head != *head {
+                    return Err(anyhow::anyhow!("Ops log checkpoint at pos {} (tenant '{}') does not match entry {}", pos, tenant_id, entry.index));
+                }
+                verified += 1;
+            }
+            heads.push(hash);
+        }
+        Ok(verified)
+    }
+}
+
+/// 校验哈希链后按原样读出全部行 (快照归档用，停机时调用)
+pub fn read_lines(path: &Path) -> anyhow::Result<Vec<String>> {
+    for entry in read_chain(path)? {
+        entry?;
+    }
+    Ok(std::fs::read_to_string(path)?.lines().map(str::to_string).collect())
+}
+
+/// 逐行解析并校验哈希链 (序号连续、`prev_hash` 指向上一行)
+fn read_chain(path: &Path) -> anyhow::Result<impl Iterator<Item = anyhow::Result<(OpsLogEntry, [u8; 32])>>> {
+    let mut prev = [0u8; 32];
+    let mut expected = 0u64;
+    let lines = BufReader::new(File::open(path)?).lines();
+    Ok(lines.map(move |line| {
+        let line = line?;
+        let entry: OpsLogEntry = serde_json::from_str(&line)
+            .map_err(|e| anyhow::anyhow!("Ops log entry {} is malformed: {}", expected, e))?;
+        if entry.index != expected {
+            return Err(anyhow::anyhow!("Ops log gap: expected entry {}, found {}", expected, entry.index));
+        }
+        if entry.prev_hash != hex::encode(prev) {
+            return Err(anyhow::anyhow!("Ops log chain broken at entry {}", entry.index));
+        }
+        prev = line_hash(&line);
+        expected += 1;
+        Ok((entry, prev))
+    }))
+}
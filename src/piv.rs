@@ -0,0 +1,311 @@
+use ed25519_dalek::{Signature, VerifyingKey};
+use log::info;
+use rustls_pki_types::pem::PemObject;
+use rustls_pki_types::CertificateDer;
+use serde::Deserialize;
+use std::io::Write;
+use std::process::{Command, Stdio};
+use std::sync::Arc;
+use x509_parser::certificate::X509Certificate;
+use x509_parser::parse_x509_certificate;
+
+use crate::config::{PivConfig, SignerPivConfig};
+use crate::signer::EvidenceSigner;
+
+/// Ed25519 公钥算法 (RFC 8410)
+const OID_ED25519: &str = "1.3.101.112";
+/// Yubico 证明证书扩展：固件版本 (3 字节)
+const OID_YUBICO_FIRMWARE: &str = "1.3.6.1.4.1.41482.3.3";
+/// Yubico 证明证书扩展：序列号 (INTEGER)
+const OID_YUBICO_SERIAL: &str = "1.3.6.1.4.1.41482.3.7";
+/// Yubico 证明证书扩展：使用策略 (PIN 策略、触摸策略各 1 字节)
+const OID_YUBICO_POLICY: &str = "1.3.6.1.4.1.41482.3.8";
+
+/// 证明密钥所在的槽位 (出厂由 Yubico CA 签发证书)
+const ATTESTATION_SLOT: &str = "f9";
+
+/// 证明中的 PIN 策略：从不需要 PIN
+const PIN_POLICY_NEVER: u8 = 1;
+
+/// 槽位的触摸策略 (生成密钥时写入令牌，之后不可更改)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TouchPolicy {
+    /// 不需要触摸
+    Never,
+    /// 每次签名都要触摸 (默认)
+    #[default]
+    Always,
+    /// 触摸一次后 15 秒内的签名不再要求触摸
+    Cached,
+}
+
+impl TouchPolicy {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Never => "never",
+            Self::Always => "always",
+            Self::Cached => "cached",
+        }
+    }
+
+    /// 是否不弱于 `required` (always > cached > never)
+    pub fn satisfies(self, required: Self) -> bool {
+        self.rank() >= required.rank()
+    }
+
+    fn rank(self) -> u8 {
+        match self {
+            Self::Never => 0,
+            Self::Cached => 1,
+            Self::Always => 2,
+        }
+    }
+
+    fn from_attestation(byte: u8) -> Option<Self> {
+        match byte {
+            1 => Some(Self::Never),
+            2 => Some(Self::Always),
+            3 => Some(Self::Cached),
+            _ => None,
+        }
+    }
+}
+
+impl std::str::FromStr for TouchPolicy {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> anyhow::Result<Self> {
+        match s.to_ascii_lowercase().as_str() {
+            "never" => Ok(Self::Never),
+            "always" => Ok(Self::Always),
+            "cached" => Ok(Self::Cached),
+            other => Err(anyhow::anyhow!("unknown touch policy '{}' (expected never | always | cached)", other)),
+        }
+    }
+}
+
+/// 令牌对槽位密钥的证明：公钥与不可更改的使用策略
+#[derive(Debug, Clone)]
+pub struct SlotAttestation {
+    pub public_key: VerifyingKey,
+    pub touch_policy: TouchPolicy,
+    /// PIN 策略原值 (1 从不、2 每会话一次、3 每次)
+    pub pin_policy: Option<u8>,
+    pub serial: Option<u64>,
+    pub firmware: Option<String>,
+}
+
+/// 模块：PIV 硬件令牌签名 (YubiKey PIV)
+///
+/// **为什么需要**: 鉴定人的复核意见是以个人名义出具的结论，身份文件放在工作站上，谁拿到文件和口令都能替他签。
+/// 把鉴定人的 Ed25519 密钥生成在 YubiKey 的 PIV 槽位里 (固件 5.7 起支持)，私钥不出令牌，
+/// 槽位的触摸策略为 `always` 时每个签名都要本人按一下令牌，远程控制了工作站也签不出意见。
+///
+/// - 签名前先取槽位的证明证书 (由令牌出厂的 `f9` 证明密钥签发)，从中读出公钥与触摸策略，
+///   策略弱于 `[piv].touch_policy` 即拒绝签名；配置了 `attestation_ca` 时还要求 `f9` 证书由该 CA 签发，
+///   防止把一个软件模拟的“令牌”当成硬件；
+/// - 与令牌的交互交给 `yubico-piv-tool` (PIN 与触摸提示由它在终端完成)，这里只负责核对证明与签名结果；
+/// - 签名对象与文件密钥完全相同，服务端按 `[[api.examiners]]` 固定的公钥验签，不区分密钥在哪里。
+pub struct PivSigner {
+    config: PivConfig,
+    attestation: SlotAttestation,
+}
+
+impl PivSigner {
+    /// 读取并核对槽位证明；不是 Ed25519 密钥或触摸策略不满足要求时报错
+    pub fn open(config: &PivConfig) -> anyhow::Result<Self> {
+        let token = Self { config: config.clone(), attestation: attest(config)? };
+        let touch_policy = token.attestation.touch_policy;
+        if !touch_policy.satisfies(config.touch_policy) {
+            return Err(anyhow::anyhow!(
+                "PIV slot {} has touch policy '{}', but '{}' is required",
+                config.slot,
+                touch_policy.as_str(),
+                config.touch_policy.as_str()
+            ));
+        }
+        Ok(token)
+    }
+
+    pub fn public_key(&self) -> VerifyingKey {
+        self.attestation.public_key
+    }
+
+    pub fn attestation(&self) -> &SlotAttestation {
+        &self.attestation
+    }
+
+    /// 在令牌上签名；结果须能以证明过的公钥验签
+    ///
+    /// 槽位的 PIN 策略为“从不”时不再验证 PIN，无终端的服务进程只能使用这样的槽位。
+    pub fn sign_bytes(&self, payload: &[u8]) -> anyhow::Result<Signature> {
+        if self.attestation.touch_policy != TouchPolicy::Never {
+            info!("👆 请触摸 YubiKey 完成签名 (槽位 {}, 触摸策略 {})", self.config.slot, self.attestation.touch_policy.as_str());
+        }
+        let sign = ["-a", "sign", "-s", &self.config.slot, "-A", "ED25519", "-i", "-", "-o", "-"];
+        let args = if self.attestation.pin_policy == Some(PIN_POLICY_NEVER) {
+            sign.to_vec()
+        } else {
+            [&["-a", "verify-pin"][..], &sign].concat()
+        };
+        let output = run(&self.config, &args, Some(payload))?;
+        let signature = Signature::from_slice(&output)
+            .map_err(|_| anyhow::anyhow!("{} returned {} bytes, not an Ed25519 signature", self.config.command, output.len()))?;
+        if !EvidenceSigner::verify_bytes(&self.attestation.public_key, payload, &signature) {
+            return Err(anyhow::anyhow!("PIV slot {} returned a signature that does not verify under its attested key", self.config.slot));
+        }
+        Ok(signature)
+    }
+}
+
+/// 签名身份所在的令牌槽位 (`key_source = "piv"`)
+///
+/// 默认租户使用 `[signer.piv].slot`；令牌里的私钥派生不出子密钥，其他租户的根密钥
+/// 只能是 `tenant_slots` 中为它单独指定的槽位，同样经过证明核对。
+pub struct PivKey {
+    config: Arc<SignerPivConfig>,
+    signer: PivSigner,
+}
+
+impl PivKey {
+    /// 主身份 (默认租户) 的槽位
+    pub fn open(config: &SignerPivConfig) -> anyhow::Result<Self> {
+        Self::open_slot(Arc::new(config.clone()), &config.slot)
+    }
+
+    /// 租户根密钥所在的槽位；未配置时报错，而不是退回主身份的密钥
+    pub fn tenant(&self, tenant_id: &str) -> anyhow::Result<Self> {
+        let slot = self.config.tenant_slots.get(tenant_id).ok_or_else(|| {
+            anyhow::anyhow!("Tenant '{}' has no slot in signer.piv.tenant_slots; keys on a PIV token cannot derive tenant keys", tenant_id)
+        })?;
+        Self::open_slot(self.config.clone(), slot)
+    }
+
+    fn open_slot(config: Arc<SignerPivConfig>, slot: &str) -> anyhow::Result<Self> {
+        let signer = PivSigner::open(&config.slot(slot))?;
+        let attestation = signer.attestation();
+        info!(
+            "🔐 签名密钥在 YubiKey 槽位 {} (序列号 {}, 固件 {}, 触摸策略 {}): {}",
+            slot,
+            attestation.serial.map_or_else(|| "未知".to_string(), |serial| serial.to_string()),
+            attestation.firmware.as_deref().unwrap_or("未知"),
+            attestation.touch_policy.as_str(),
+            hex::encode(attestation.public_key.to_bytes())
+        );
+        Ok(Self { config, signer })
+    }
+
+    pub fn public_key(&self) -> VerifyingKey {
+        self.signer.public_key()
+    }
+
+    pub fn sign_bytes(&self, payload: &[u8]) -> anyhow::Result<Signature> {
+        self.signer.sign_bytes(payload)
+    }
+}
+
+/// 读取槽位证明证书与 `f9` 证明密钥证书，校验签发关系并解析
+pub fn attest(config: &PivConfig) -> anyhow::Result<SlotAttestation> {
+    let attestation = certificate(config, &["-a", "attest", "-s", &config.slot])?;
+    let intermediate = certificate(config, &["-a", "read-certificate", "-s", ATTESTATION_SLOT])?;
+    let cas = if config.attestation_ca.is_empty() {
+        Vec::new()
+    } else {
+        CertificateDer::pem_file_iter(&config.attestation_ca)
+            .map_err(|e| anyhow::anyhow!("Cannot read '{}': {}", config.attestation_ca, e))?
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| anyhow::anyhow!("Invalid PEM in '{}': {}", config.attestation_ca, e))?
+    };
+
+    let (_, attestation) = parse_x509_certificate(&attestation).map_err(|e| anyhow::anyhow!("Invalid attestation certificate: {}", e))?;
+    let (_, intermediate) = parse_x509_certificate(&intermediate).map_err(|e| anyhow::anyhow!("Invalid attestation key certificate: {}", e))?;
+    attestation
+        .verify_signature(Some(intermediate.public_key()))
+        .map_err(|e| anyhow::anyhow!("Attestation for slot {} is not signed by the token's attestation key: {}", config.slot, e))?;
+    if !cas.is_empty() {
+        let trusted = cas.iter().any(|ca| {
+            parse_x509_certificate(ca).is_ok_and(|(_, ca)| intermediate.verify_signature(Some(ca.public_key())).is_ok())
+        });
+        if !trusted {
+            return Err(anyhow::anyhow!("Token attestation key is not issued by any CA in '{}'", config.attestation_ca));
+        }
+    }
+    parse_attestation(&attestation, &config.slot)
+}
+
+fn parse_attestation(cert: &X509Certificate, slot: &str) -> anyhow::Result<SlotAttestation> {
+    let spki = cert.public_key();
+    if spki.algorithm.algorithm.to_id_string() != OID_ED25519 {
+        return Err(anyhow::anyhow!("PIV slot {} does not hold an Ed25519 key", slot));
+    }
+    let key: [u8; 32] = spki
+        .subject_public_key
+        .data
+        .as_ref()
+        .try_into()
+        .map_err(|_| anyhow::anyhow!("Malformed Ed25519 key in attestation for slot {}", slot))?;
+    let public_key = VerifyingKey::from_bytes(&key)?;
+
+    let (mut touch_policy, mut pin_policy, mut serial, mut firmware) = (None, None, None, None);
+    for extension in cert.extensions() {
+        let value = extension.value;
+        match extension.oid.to_id_string().as_str() {
+            OID_YUBICO_POLICY if value.len() == 2 => {
+                pin_policy = Some(value[0]);
+                touch_policy = TouchPolicy::from_attestation(value[1]);
+            }
+            OID_YUBICO_SERIAL => serial = der_unsigned(value),
+            OID_YUBICO_FIRMWARE if value.len() == 3 => firmware = Some(format!("{}.{}.{}", value[0], value[1], value[2])),
+            _ => {}
+        }
+    }
+    Ok(SlotAttestation {
+        public_key,
+        touch_policy: touch_policy.ok_or_else(|| anyhow::anyhow!("Attestation for slot {} carries no usage policy", slot))?,
+        pin_policy,
+        serial,
+        firmware,
+    })
+}
+
+/// DER 编码的非负 INTEGER (Yubico 序列号扩展)
+fn der_unsigned(value: &[u8]) -> Option<u64> {
+    let [0x02, len, digits @ ..] = value else {
+        return None;
+    };
+    if digits.len() != *len as usize || digits.len() > 9 || digits.len() == 9 && digits[0] != 0 {
+        return None;
+    }
+    Some(digits.iter().fold(0u64, |acc, byte| (acc << 8) | u64::from(*byte)))
+}
+
+/// 运行 `yubico-piv-tool` 并取回一张 PEM 证书
+fn certificate(config: &PivConfig, args: &[&str]) -> anyhow::Result<CertificateDer<'static>> {
+    let pem = run(config, args, None)?;
+    CertificateDer::from_pem_slice(&pem)
+        .map_err(|e| anyhow::anyhow!("{} {} did not return a PEM certificate: {}", config.command, args.join(" "), e))
+}
+
+/// 运行 `yubico-piv-tool`；标准错误直接交给终端，PIN 与触摸提示由它自己完成
+fn run(config: &PivConfig, args: &[&str], input: Option<&[u8]>) -> anyhow::Result<Vec<u8>> {
+    let mut command = Command::new(&config.command);
+    if !config.reader.is_empty() {
+        command.arg("-r").arg(&config.reader);
+    }
+    let mut child = command
+        .args(args)
+        .stdin(if input.is_some() { Stdio::piped() } else { Stdio::null() })
+        .stdout(Stdio::piped())
+        .stderr(Stdio::inherit())
+        .spawn()
+        .map_err(|e| anyhow::anyhow!("Cannot run '{}': {}", config.command, e))?;
+    if let (Some(mut stdin), Some(input)) = (child.stdin.take(), input) {
+        stdin.write_all(input)?;
+    }
+    let output = child.wait_with_output()?;
+    if !output.status.success() {
+        return Err(anyhow::anyhow!("{} {} failed ({})", config.command, args.join(" "), output.status));
+    }
+    Ok(output.stdout)
+}
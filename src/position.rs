@@ -0,0 +1,104 @@
+use ckb_merkle_mountain_range::helper::{get_peaks, parent_offset, pos_height_in_tree, sibling_offset};
+use std::collections::BTreeSet;
+
+use crate::proof::MAX_TREE_SIZE;
+
+/// 证明路径中每一项的字节数 (Blake3 哈希)
+pub const PROOF_ITEM_BYTES: usize = 32;
+
+/// 第 `index` 个叶子 (从 0 开始) 在 MMR 中的位置
+///
+/// 回执、`/audit/{pos}`、`/evidence/{pos}` 用的都是位置：位置把内部节点也算在内，
+/// 所以叶子的位置是 0, 1, 3, 4, 7, 8, 10, 11, 15…，并不连续。
+pub fn leaf_index_to_pos(index: u64) -> u64 {
+    ckb_merkle_mountain_range::leaf_index_to_pos(index)
+}
+
+/// 位置对应的叶子序号；内部节点的位置返回 None
+pub fn pos_to_leaf_index(pos: u64) -> Option<u64> {
+    // 叶子的位置恰好是追加它之前的 MMR 大小
+    is_leaf_pos(pos).then(|| leaf_count(pos))
+}
+
+/// 位置是否为叶子 (而不是内部节点)
+pub fn is_leaf_pos(pos: u64) -> bool {
+    pos_height_in_tree(pos) == 0
+}
+
+/// 给定 MMR 大小 (节点总数) 下的叶子数量
+///
+/// 每座山峰都是完美二叉树，高度为 h 的山峰下有 $2^h$ 个叶子。
+pub fn leaf_count(mmr_size: u64) -> u64 {
+    peaks(mmr_size)
+        .into_iter()
+        .map(|peak| 1u64 << pos_height_in_tree(peak))
+        .sum()
+}
+
+/// 含 `leaves` 个叶子的 MMR 大小 (节点总数)
+pub fn mmr_size_for_leaves(leaves: u64) -> u64 {
+    match leaves {
+        0 => 0,
+        leaves => ckb_merkle_mountain_range::leaf_index_to_mmr_size(leaves - 1),
+    }
+}
+
+/// 是否为某个叶子数对应的 MMR 大小 (回执的 `tree_size`、`?tree_size=` 都必须是)
+///
+/// 超过 [`MAX_TREE_SIZE`] 的一律不是 (推算山峰时会溢出)。
+pub fn is_valid_mmr_size(mmr_size: u64) -> bool {
+    mmr_size <= MAX_TREE_SIZE && mmr_size_for_leaves(leaf_count(mmr_size)) == mmr_size
+}
+
+/// 各山峰的位置，从左到右 (从高到低)；空树为空
+pub fn peaks(mmr_size: u64) -> Vec<u64> {
+    if mmr_size == 0 {
+        return Vec::new();
+    }
+    get_peaks(mmr_size)
+}
+
+/// 一组叶子在 `mmr_size` 大小的树中的证明路径项数 (合并证明，与 `/audit/batch` 一致)
+///
+/// 每座含有所求叶子的山里，凡是不能由所求叶子算出的兄弟节点各占一项；左侧不含所求叶子的山峰各占一项，
+/// 右侧的合并为一项。乘以 [`PROOF_ITEM_BYTES`] 即证明路径的字节数。
+/// 位置不是叶子或不在树内时返回 None。
+pub fn proof_len(mmr_size: u64, positions: &[u64]) -> Option<usize> {
+    let mut remaining: BTreeSet<u64> = positions.iter().copied().collect();
+    if remaining.is_empty() || remaining.iter().any(|pos| *pos >= mmr_size || !is_leaf_pos(*pos)) {
+        return None;
+    }
+    let mut items = 0;
+    let mut empty_peaks = 0;
+    for peak in peaks(mmr_size) {
+        let rest = remaining.split_off(&(peak + 1));
+        let mut level = std::mem::replace(&mut remaining, rest);
+        if level.is_empty() {
+            empty_peaks += 1;
+            continue;
+        }
+        // 左侧的空山峰各占一项
+        items += empty_peaks;
+        empty_peaks = 0;
+
+        let mut height = 0;
+        while !level.contains(&peak) {
+            let mut parents = BTreeSet::new();
+            for &pos in &level {
+                let (sibling, parent) = if pos_height_in_tree(pos + 1) > height {
+                    (pos - sibling_offset(height), pos + 1)
+                } else {
+                    (pos + sibling_offset(height), pos + parent_offset(height))
+                };
+                if !level.contains(&sibling) {
+                    items += 1;
+                }
+                parents.insert(parent);
+            }
+            level = parents;
+            height += 1;
+        }
+    }
+    // 右侧的空山峰合并为一项
+    Some(items + empty_peaks.min(1))
+}
@@ -0,0 +1,81 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+use crate::codec::StorageCodec;
+use crate::mmr_store::SledStore;
+
+/// 单个 Prompt 组件 (专家) 的说明
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct PromptInfo {
+    /// 在 Prompt 池中的索引，即证据 `activated_prompts` 中的数字
+    pub index: u32,
+    /// 专家名称 (如 `watermark-detector`)
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+}
+
+/// 某个模型版本 (Prompt 池哈希) 的全部 Prompt 说明
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PromptSet {
+    pub prompt_pool_hash: String,
+    /// 按索引升序
+    pub prompts: Vec<PromptInfo>,
+    /// 最近一次设置的时间 (Unix 秒)
+    pub updated_at: i64,
+    /// 设置者 (租户 API Key 指纹)
+    pub principal: String,
+}
+
+impl PromptSet {
+    /// 按索引查找
+    pub fn by_index(&self) -> BTreeMap<u32, &PromptInfo> {
+        self.prompts.iter().map(|prompt| (prompt.index, prompt)).collect()
+    }
+}
+
+/// 模块：Prompt 说明登记 (Prompt Registry)
+///
+/// **为什么需要**: 证据里的 `activated_prompts` 只是一串索引 (`[1, 2, 99]`)，离开模型源码就无从知道
+/// 触发的是哪几个专家。这里按模型版本 (Prompt 池哈希) 登记索引到专家名称与说明的对照表，
+/// 供 `GET /models/{hash}/prompts` 查询，审计报告据此把索引翻译成人能读懂的名称。
+///
+/// 对照表只是说明，不进入证据的签名内容：同一模型版本的说明可以事后补充或改正，已签发的证据不受影响。
+/// 存 `prompt_registry` 树 (键为 Prompt 池哈希)，每次整体替换。
+#[derive(Clone)]
+pub struct PromptRegistry {
+    sets: sled::Tree,
+}
+
+impl PromptRegistry {
+    pub fn open(store: &SledStore) -> anyhow::Result<Self> {
+        Ok(Self { sets: store.tree("prompt_registry")? })
+    }
+
+    /// 指定模型版本的对照表；从未登记过返回 None
+    pub fn get(&self, prompt_pool_hash: &str) -> anyhow::Result<Option<PromptSet>> {
+        self.sets.get(prompt_pool_hash)?.map(|v| StorageCodec::decode(&v)).transpose()
+    }
+
+    /// 整体替换对照表；索引重复时报错，空列表删除该模型的对照表
+    pub fn set(&self, prompt_pool_hash: &str, mut prompts: Vec<PromptInfo>, principal: &str, at: i64) -> anyhow::Result<PromptSet> {
+        prompts.sort_by_key(|prompt| prompt.index);
+        if let Some(pair) = prompts.windows(2).find(|pair| pair[0].index == pair[1].index) {
+            return Err(anyhow::anyhow!("Duplicate prompt index {}", pair[0].index));
+        }
+        let set = PromptSet {
+            prompt_pool_hash: prompt_pool_hash.to_string(),
+            prompts,
+            updated_at: at,
+            principal: principal.to_string(),
+        };
+        if set.prompts.is_empty() {
+            self.sets.remove(prompt_pool_hash)?;
+        } else {
+            self.sets.insert(prompt_pool_hash, StorageCodec::encode(&set)?)?;
+        }
+        self.sets.flush()?;
+        Ok(set)
+    }
+}
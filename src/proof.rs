@@ -0,0 +1,319 @@
+use ckb_merkle_mountain_range::{Merge, MerkleProof, Result as MMRResult};
+
+use crate::position::is_leaf_pos;
+
+/// 模块：证明校验原语 (Proof Primitives)
+///
+/// **为什么单独成模块**: 校验包含性证明只需要合并规则与证明路径，不需要 sled 或任何存储。
+/// 放在这里 (连同 `evidence.rs`、`receipt.rs`) 即可在关闭 `server` 特性时单独编译，
+/// 浏览器端的 WASM 校验包与服务端共用同一份实现。
+///
+/// 合并策略 (Merge Strategy)：父节点 = Blake3(左 || 右)
+pub struct MergeBlake3;
+
+impl Merge for MergeBlake3 {
+    type Item = [u8; 32];
+
+    fn merge(lhs: &Self::Item, rhs: &Self::Item) -> MMRResult<Self::Item> {
+        let mut hasher = blake3::Hasher::new();
+        hasher.update(lhs);
+        hasher.update(rhs);
+        Ok(*hasher.finalize().as_bytes())
+    }
+}
+
+/// 可校验的最大树大小 (MMR 节点总数)
+///
+/// 证明与陈述中的树大小来自不受信任的输入。`ckb_merkle_mountain_range` 按 `1 << (高度 + 1)` 推算山峰位置，
+/// 树大小接近 `u64::MAX` 时移位与加法溢出而 panic；2^62 个节点远超任何真实日志，超过的一律视为无效。
+pub const MAX_TREE_SIZE: u64 = 1 << 62;
+
+/// 离线校验包含性证明：叶子 `leaf` 位于 `tree_size` 大小、根为 `root` 的树中的 `pos` 位置
+///
+/// 只需证明路径，不需要访问存储；客户端与各语言绑定共用这一实现。
+/// `pos` 必须是叶子位置：山峰等内部节点的位置会被拒绝，否则拿内部节点的哈希冒充叶子也能对上根。
+pub fn verify_proof(tree_size: u64, proof_items: Vec<[u8; 32]>, pos: u64, leaf: [u8; 32], root: [u8; 32]) -> bool {
+    pos < tree_size
+        && is_leaf_pos(pos)
+        && tree_size <= MAX_TREE_SIZE
+        && MerkleProof::<[u8; 32], MergeBlake3>::new(tree_size, proof_items)
+            .verify(root, vec![(pos, leaf)])
+            .unwrap_or(false)
+}
+
+/// 离线校验批量证明：`leaves` 中的每个 (位置, 叶子哈希) 都位于 `tree_size` 大小、根为 `root` 的树中
+///
+/// `leaves` 须按位置升序且不重复，与 `/audit/batch` 的响应顺序一致。
+pub fn verify_batch_proof(tree_size: u64, proof_items: Vec<[u8; 32]>, leaves: Vec<(u64, [u8; 32])>, root: [u8; 32]) -> bool {
+    !leaves.is_empty()
+        && tree_size <= MAX_TREE_SIZE
+        && leaves.iter().all(|(pos, _)| *pos < tree_size && is_leaf_pos(*pos))
+        && leaves.windows(2).all(|pair| pair[0].0 < pair[1].0)
+        && MerkleProof::<[u8; 32], MergeBlake3>::new(tree_size, proof_items)
+            .verify(root, leaves)
+            .unwrap_or(false)
+}
+
+/// 单叶子包含性证明 (Inclusion Proof)
+///
+/// **为什么需要**: 下游 Rust 服务此前拿到 `/audit/{pos}` 的证明后，要自行依赖 `ckb_merkle_mountain_range`、
+/// 猜测合并规则与参数顺序，猜错了只会得到一个 `false`。这里把树大小、位置与证明路径收进一个类型，
+/// `verify` 固定使用 [`MergeBlake3`]，与服务端、客户端、WASM 包和链上合约同一套规则。
+///
+/// 叶子哈希用 [`crate::evidence::leaf_hash_of`] 由证据的规范编码算出；根必须取自回执或挑战应答中**签名**的根。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InclusionProof {
+    /// 证明所在树的大小 (MMR 节点总数)
+    pub tree_size: u64,
+    /// 叶子的 MMR 位置
+    pub pos: u64,
+    /// 证明路径
+    pub items: Vec<[u8; 32]>,
+}
+
+impl InclusionProof {
+    pub fn new(tree_size: u64, pos: u64, items: Vec<[u8; 32]>) -> Self {
+        Self { tree_size, pos, items }
+    }
+
+    /// 校验叶子 `leaf` 位于根为 `root` 的树中 (见 [`verify_proof`])
+    pub fn verify(&self, leaf: [u8; 32], root: [u8; 32]) -> bool {
+        verify_proof(self.tree_size, self.items.clone(), self.pos, leaf, root)
+    }
+}
+
+impl From<InclusionProof> for CompactProof {
+    fn from(proof: InclusionProof) -> Self {
+        Self { tree_size: proof.tree_size, positions: vec![proof.pos], items: proof.items }
+    }
+}
+
+impl TryFrom<CompactProof> for InclusionProof {
+    type Error = anyhow::Error;
+
+    /// 只接受单叶子的紧凑证明
+    fn try_from(proof: CompactProof) -> anyhow::Result<Self> {
+        match proof.positions.as_slice() {
+            [pos] => Ok(Self { tree_size: proof.tree_size, pos: *pos, items: proof.items }),
+            positions => Err(anyhow::anyhow!("Expected a single-leaf proof, got {} positions", positions.len())),
+        }
+    }
+}
+
+/// 紧凑证明的媒体类型 (`Accept` 协商)
+pub const COMPACT_PROOF_MEDIA_TYPE: &str = "application/vnd.yuanjing.proof";
+
+/// 紧凑证明编码版本
+pub const COMPACT_PROOF_VERSION: u8 = 1;
+
+const COMPACT_PROOF_MAGIC: &[u8; 3] = b"YJP";
+
+/// 紧凑二进制证明 (Compact Proof)
+///
+/// **为什么需要**: Hex 数组的 JSON 证明体积约为原始哈希的两倍多，且丢了 `mmr_size`，
+/// 脱离响应的其他字段就无法单独校验。紧凑编码把树大小、位置与证明路径装进一段自描述的字节：
+///
+/// ```text
+/// "YJP" | version: u8 | tree_size: u64 | n: u32 | positions: n × u64 | m: u32 | items: m × [u8; 32]
+/// ```
+///
+/// 整数均为大端序。位置按升序、不重复排列 (与 `gen_proof` 的要求一致)，单叶子证明即 n = 1。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CompactProof {
+    pub tree_size: u64,
+    pub positions: Vec<u64>,
+    pub items: Vec<[u8; 32]>,
+}
+
+impl CompactProof {
+    pub fn encode(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(4 + 8 + 4 + self.positions.len() * 8 + 4 + self.items.len() * 32);
+        bytes.extend_from_slice(COMPACT_PROOF_MAGIC);
+        bytes.push(COMPACT_PROOF_VERSION);
+        bytes.extend_from_slice(&self.tree_size.to_be_bytes());
+        bytes.extend_from_slice(&(self.positions.len() as u32).to_be_bytes());
+        for pos in &self.positions {
+            bytes.extend_from_slice(&pos.to_be_bytes());
+        }
+        bytes.extend_from_slice(&(self.items.len() as u32).to_be_bytes());
+        for item in &self.items {
+            bytes.extend_from_slice(item);
+        }
+        bytes
+    }
+
+    /// 解析紧凑证明；魔数、版本、长度、树大小、位置顺序不符或位置不是树内叶子时报错
+    pub fn decode(bytes: &[u8]) -> anyhow::Result<Self> {
+        let mut reader = ByteReader { bytes };
+        if reader.take(3)? != COMPACT_PROOF_MAGIC {
+            return Err(anyhow::anyhow!("Not a compact proof (bad magic)"));
+        }
+        let version = reader.take(1)?[0];
+        if version != COMPACT_PROOF_VERSION {
+            return Err(anyhow::anyhow!("Unsupported compact proof version {}", version));
+        }
+        let tree_size = reader.u64()?;
+        if tree_size > MAX_TREE_SIZE {
+            return Err(anyhow::anyhow!("Compact proof tree size {} exceeds {}", tree_size, MAX_TREE_SIZE));
+        }
+        let positions = (0..reader.count(8)?).map(|_| reader.u64()).collect::<anyhow::Result<Vec<_>>>()?;
+        let items = (0..reader.count(32)?)
+            .map(|_| Ok(reader.take(32)?.try_into()?))
+            .collect::<anyhow::Result<Vec<_>>>()?;
+        if !reader.bytes.is_empty() {
+            return Err(anyhow::anyhow!("Trailing bytes after compact proof"));
+        }
+        if positions.is_empty() || !positions.windows(2).all(|pair| pair[0] < pair[1]) {
+            return Err(anyhow::anyhow!("Compact proof positions must be non-empty, ascending and unique"));
+        }
+        if let Some(pos) = positions.iter().find(|pos| **pos >= tree_size || !is_leaf_pos(**pos)) {
+            return Err(anyhow::anyhow!("Compact proof position {} is not a leaf of a tree of size {}", pos, tree_size));
+        }
+        Ok(Self { tree_size, positions, items })
+    }
+
+    /// 校验 `leaves` (与 `positions` 一一对应) 都位于根为 `root` 的树中
+    pub fn verify(&self, leaves: &[[u8; 32]], root: [u8; 32]) -> bool {
+        leaves.len() == self.positions.len()
+            && verify_batch_proof(
+                self.tree_size,
+                self.items.clone(),
+                self.positions.iter().copied().zip(leaves.iter().copied()).collect(),
+                root,
+            )
+    }
+}
+
+struct ByteReader<'a> {
+    bytes: &'a [u8],
+}
+
+impl<'a> ByteReader<'a> {
+    fn take(&mut self, len: usize) -> anyhow::Result<&'a [u8]> {
+        if self.bytes.len() < len {
+            return Err(anyhow::anyhow!("Truncated compact proof"));
+        }
+        let (head, rest) = self.bytes.split_at(len);
+        self.bytes = rest;
+        Ok(head)
+    }
+
+    fn u64(&mut self) -> anyhow::Result<u64> {
+        Ok(u64::from_be_bytes(self.take(8)?.try_into()?))
+    }
+
+    /// 读取元素个数，并确认剩余字节至少够放下这么多个 `width` 字节的元素 (防止超大长度前缀)
+    fn count(&mut self, width: usize) -> anyhow::Result<usize> {
+        let count = u32::from_be_bytes(self.take(4)?.try_into()?) as usize;
+        if count.saturating_mul(width) > self.bytes.len() {
+            return Err(anyhow::anyhow!("Truncated compact proof"));
+        }
+        Ok(count)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ckb_merkle_mountain_range::util::MemMMR;
+
+    type TestMmr = MemMMR<[u8; 32], MergeBlake3>;
+
+    /// 含 `leaves` 个叶子的内存 MMR，叶子哈希为 Blake3(序号)
+    fn build(leaves: u64) -> (TestMmr, Vec<(u64, [u8; 32])>) {
+        let mut mmr = MemMMR::default();
+        let positions = (0..leaves)
+            .map(|index| {
+                let leaf = *blake3::hash(&index.to_be_bytes()).as_bytes();
+                (mmr.push(leaf).unwrap(), leaf)
+            })
+            .collect();
+        (mmr, positions)
+    }
+
+    fn compact(mmr: &TestMmr, positions: Vec<u64>) -> CompactProof {
+        let proof = mmr.gen_proof(positions.clone()).unwrap();
+        CompactProof { tree_size: mmr.mmr_size(), positions, items: proof.proof_items().to_vec() }
+    }
+
+    #[test]
+    fn round_trip_preserves_proof_and_verifies() {
+        let (mmr, leaves) = build(11);
+        let root = mmr.get_root().unwrap();
+        let picked = [leaves[0], leaves[4], leaves[10]];
+        let proof = compact(&mmr, picked.iter().map(|(pos, _)| *pos).collect());
+
+        let decoded = CompactProof::decode(&proof.encode()).unwrap();
+        assert_eq!(decoded, proof);
+        assert!(decoded.verify(&picked.map(|(_, leaf)| leaf), root));
+        assert!(!decoded.verify(&[picked[0].1, picked[2].1, picked[1].1], root));
+
+        let single = InclusionProof::try_from(compact(&mmr, vec![leaves[7].0])).unwrap();
+        let decoded = InclusionProof::try_from(CompactProof::decode(&CompactProof::from(single.clone()).encode()).unwrap()).unwrap();
+        assert_eq!(decoded, single);
+        assert!(decoded.verify(leaves[7].1, root));
+    }
+
+    #[test]
+    fn every_truncation_is_rejected() {
+        let (mmr, leaves) = build(6);
+        let bytes = compact(&mmr, vec![leaves[2].0, leaves[5].0]).encode();
+        for len in 0..bytes.len() {
+            assert!(CompactProof::decode(&bytes[..len]).is_err(), "prefix of {} bytes decoded", len);
+        }
+        let mut trailing = bytes.clone();
+        trailing.push(0);
+        assert!(CompactProof::decode(&trailing).is_err());
+    }
+
+    #[test]
+    fn oversized_counts_and_tree_sizes_are_rejected() {
+        let (mmr, leaves) = build(6);
+        let bytes = compact(&mmr, vec![leaves[3].0]).encode();
+        let items_at = 3 + 1 + 8 + 4 + 8;
+
+        let mut positions = bytes.clone();
+        positions[12..16].copy_from_slice(&u32::MAX.to_be_bytes());
+        assert!(CompactProof::decode(&positions).is_err());
+
+        let mut items = bytes.clone();
+        items[items_at..items_at + 4].copy_from_slice(&u32::MAX.to_be_bytes());
+        assert!(CompactProof::decode(&items).is_err());
+
+        let mut tree_size = bytes.clone();
+        tree_size[4..12].copy_from_slice(&(MAX_TREE_SIZE + 1).to_be_bytes());
+        assert!(CompactProof::decode(&tree_size).is_err());
+        assert!(!verify_proof(u64::MAX, Vec::new(), 0, leaves[0].1, [0u8; 32]));
+
+        let mut magic = bytes.clone();
+        magic[0] = b'X';
+        assert!(CompactProof::decode(&magic).is_err());
+        let mut version = bytes;
+        version[3] = COMPACT_PROOF_VERSION + 1;
+        assert!(CompactProof::decode(&version).is_err());
+    }
+
+    #[test]
+    fn positions_outside_the_tree_or_on_peaks_are_rejected() {
+        let (mmr, leaves) = build(3);
+        let root = mmr.get_root().unwrap();
+        let tree_size = mmr.mmr_size();
+
+        // 3 个叶子：位置 0、1、3 是叶子，2 是左侧山峰，4 不在树内
+        let peak = 2;
+        let peak_hash = MergeBlake3::merge(&leaves[0].1, &leaves[1].1).unwrap();
+        let proof = CompactProof { tree_size, positions: vec![peak], items: vec![leaves[2].1] };
+        assert!(CompactProof::decode(&proof.encode()).is_err());
+        assert!(!proof.verify(&[peak_hash], root));
+        assert!(!verify_proof(tree_size, vec![leaves[2].1], peak, peak_hash, root));
+
+        let beyond = CompactProof { tree_size, positions: vec![tree_size], items: Vec::new() };
+        assert!(CompactProof::decode(&beyond.encode()).is_err());
+        assert!(!beyond.verify(&[leaves[2].1], root));
+
+        let unordered = CompactProof { tree_size, positions: vec![leaves[1].0, leaves[0].0], items: Vec::new() };
+        assert!(CompactProof::decode(&unordered.encode()).is_err());
+        let empty = CompactProof { tree_size, positions: Vec::new(), items: Vec::new() };
+        assert!(CompactProof::decode(&empty.encode()).is_err());
+    }
+}
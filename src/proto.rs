@@ -0,0 +1,334 @@
+use crate::evidence::{self, CanonicalEncoding, ClientContext, Confidence, ConsensusRule, ProvenanceMode};
+use std::collections::BTreeMap;
+use crate::receipt;
+
+/// 模块：Protobuf 消息 (Protobuf Messages)
+///
+/// **职责**: `proto/yuanjing.proto` 的 Rust 对应类型 (prost)，以及与核心类型的互转。
+/// gRPC 客户端与非 Rust 验证方用同一份 `.proto` 生成代码，即可复现叶子哈希与签名原文。
+///
+/// **为什么手写而不是在 build.rs 中生成**: prost-build 依赖 `protoc`，会让 WASM / FFI 等
+/// 只需要校验逻辑的构建也依赖外部工具链。各消息都很小，字段与 `.proto` 逐一对应，
+/// 修改时两边须同步 (字段号只可追加)。
+///
+/// **确定性**: prost 按字段号升序编码、省略默认值、repeated 标量使用 packed 编码、
+/// 不保留未知字段，map 字段用 BTreeMap 按键的字节序输出，同一消息总是得到同一串字节。
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct Evidence {
+    #[prost(string, tag = "1")]
+    pub image_phash: String,
+    #[prost(string, tag = "2")]
+    pub image_sha256: String,
+    #[prost(bool, tag = "3")]
+    pub verdict: bool,
+    /// 历史证据的字符串置信度；新证据留空 (不输出)，改用 `confidence_bps`
+    #[prost(string, tag = "4")]
+    pub confidence: String,
+    #[prost(uint32, repeated, tag = "5")]
+    pub activated_prompts: Vec<u32>,
+    #[prost(string, tag = "6")]
+    pub prompt_pool_hash: String,
+    #[prost(string, tag = "7")]
+    pub external_knowledge_hash: String,
+    #[prost(int64, tag = "8")]
+    pub timestamp: i64,
+    #[prost(uint32, tag = "9")]
+    pub confidence_bps: u32,
+    #[prost(int64, optional, tag = "10")]
+    pub claimed_at: Option<i64>,
+    #[prost(message, optional, tag = "11")]
+    pub consensus: Option<Consensus>,
+    #[prost(message, optional, tag = "12")]
+    pub calibration: Option<Calibration>,
+    #[prost(message, optional, tag = "13")]
+    pub provenance: Option<Provenance>,
+    /// 按键排序输出；空表即未提供
+    #[prost(btree_map = "string, string", tag = "14")]
+    pub client_context: BTreeMap<String, String>,
+}
+
+/// `yuanjing.v1.ConsensusRule`
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, prost::Enumeration)]
+#[repr(i32)]
+pub enum Rule {
+    Majority = 0,
+    Unanimous = 1,
+    ConfidenceWeighted = 2,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct ModelVerdict {
+    #[prost(string, tag = "1")]
+    pub prompt_pool_hash: String,
+    #[prost(bool, tag = "2")]
+    pub verdict: bool,
+    #[prost(uint32, tag = "3")]
+    pub confidence_bps: u32,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct Calibration {
+    #[prost(string, tag = "1")]
+    pub version: String,
+    #[prost(uint32, tag = "2")]
+    pub temperature_milli: u32,
+    #[prost(uint32, tag = "3")]
+    pub threshold_bps: u32,
+}
+
+/// `yuanjing.v1.ProvenanceMode`
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, prost::Enumeration)]
+#[repr(i32)]
+pub enum Mode {
+    Plain = 0,
+    Hashed = 1,
+    Pseudonymized = 2,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct Provenance {
+    #[prost(enumeration = "Mode", tag = "1")]
+    pub mode: i32,
+    #[prost(string, optional, tag = "2")]
+    pub ip: Option<String>,
+    #[prost(string, optional, tag = "3")]
+    pub client_cert_subject: Option<String>,
+    #[prost(string, optional, tag = "4")]
+    pub user_agent: Option<String>,
+}
+
+impl From<&evidence::Provenance> for Provenance {
+    fn from(provenance: &evidence::Provenance) -> Self {
+        let mode = match provenance.mode {
+            ProvenanceMode::Plain => Mode::Plain,
+            ProvenanceMode::Hashed => Mode::Hashed,
+            ProvenanceMode::Pseudonymized => Mode::Pseudonymized,
+        };
+        Self {
+            mode: mode as i32,
+            ip: provenance.ip.clone(),
+            client_cert_subject: provenance.client_cert_subject.clone(),
+            user_agent: provenance.user_agent.clone(),
+        }
+    }
+}
+
+impl From<Provenance> for evidence::Provenance {
+    fn from(message: Provenance) -> Self {
+        let mode = match Mode::try_from(message.mode).unwrap_or(Mode::Plain) {
+            Mode::Plain => ProvenanceMode::Plain,
+            Mode::Hashed => ProvenanceMode::Hashed,
+            Mode::Pseudonymized => ProvenanceMode::Pseudonymized,
+        };
+        Self {
+            mode,
+            ip: message.ip,
+            client_cert_subject: message.client_cert_subject,
+            user_agent: message.user_agent,
+        }
+    }
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct Consensus {
+    #[prost(enumeration = "Rule", tag = "1")]
+    pub rule: i32,
+    #[prost(message, repeated, tag = "2")]
+    pub models: Vec<ModelVerdict>,
+}
+
+/// `yuanjing.v1.CanonicalEncoding`
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, prost::Enumeration)]
+#[repr(i32)]
+pub enum Encoding {
+    Bcs = 0,
+    Protobuf = 1,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct Receipt {
+    #[prost(string, tag = "1")]
+    pub evidence_hash: String,
+    #[prost(uint64, tag = "2")]
+    pub leaf_pos: u64,
+    #[prost(string, tag = "3")]
+    pub root: String,
+    #[prost(uint64, tag = "4")]
+    pub tree_size: u64,
+    #[prost(int64, tag = "5")]
+    pub timestamp: i64,
+    #[prost(uint64, tag = "6")]
+    pub seq: u64,
+    #[prost(bool, tag = "7")]
+    pub clock_uncertain: bool,
+    #[prost(string, tag = "8")]
+    pub tenant_id: String,
+    #[prost(string, tag = "9")]
+    pub key_path: String,
+    #[prost(enumeration = "Encoding", tag = "10")]
+    pub encoding: i32,
+    #[prost(uint64, optional, tag = "11")]
+    pub supersedes: Option<u64>,
+}
+
+impl From<&evidence::Evidence> for Evidence {
+    fn from(evidence: &evidence::Evidence) -> Self {
+        let (confidence, confidence_bps) = match &evidence.confidence {
+            Confidence::BasisPoints(bps) => (String::new(), u32::from(*bps)),
+            Confidence::Legacy(text) => (text.clone(), 0),
+        };
+        Self {
+            image_phash: evidence.image_phash.clone(),
+            image_sha256: evidence.image_sha256.clone(),
+            verdict: evidence.verdict,
+            confidence,
+            activated_prompts: evidence.activated_prompts.clone(),
+            prompt_pool_hash: evidence.prompt_pool_hash.clone(),
+            external_knowledge_hash: evidence.external_knowledge_hash.clone(),
+            timestamp: evidence.timestamp,
+            confidence_bps,
+            claimed_at: evidence.claimed_at,
+            consensus: evidence.consensus.as_ref().map(Consensus::from),
+            calibration: evidence.calibration.as_ref().map(|calibration| Calibration {
+                version: calibration.version.clone(),
+                temperature_milli: calibration.temperature_milli,
+                threshold_bps: u32::from(calibration.threshold_bps),
+            }),
+            provenance: evidence.provenance.as_ref().map(Provenance::from),
+            client_context: evidence.client_context.as_ref().map(|context| context.0.clone()).unwrap_or_default(),
+        }
+    }
+}
+
+impl From<Evidence> for evidence::Evidence {
+    fn from(message: Evidence) -> Self {
+        // 历史证据只有字符串置信度 (从不为空串)，新证据只有 `confidence_bps`
+        let confidence = if message.confidence.is_empty() {
+            Confidence::BasisPoints(message.confidence_bps.min(u32::from(evidence::CONFIDENCE_SCALE)) as u16)
+        } else {
+            Confidence::Legacy(message.confidence)
+        };
+        Self {
+            image_phash: message.image_phash,
+            image_sha256: message.image_sha256,
+            verdict: message.verdict,
+            confidence,
+            activated_prompts: message.activated_prompts,
+            prompt_pool_hash: message.prompt_pool_hash,
+            external_knowledge_hash: message.external_knowledge_hash,
+            timestamp: message.timestamp,
+            claimed_at: message.claimed_at,
+            consensus: message.consensus.map(Into::into),
+            calibration: message.calibration.map(|calibration| evidence::Calibration {
+                version: calibration.version,
+                temperature_milli: calibration.temperature_milli,
+                threshold_bps: calibration.threshold_bps.min(u32::from(evidence::CONFIDENCE_SCALE)) as u16,
+            }),
+            provenance: message.provenance.map(Into::into),
+            client_context: (!message.client_context.is_empty()).then_some(ClientContext(message.client_context)),
+        }
+    }
+}
+
+impl From<&evidence::Consensus> for Consensus {
+    fn from(consensus: &evidence::Consensus) -> Self {
+        let rule = match consensus.rule {
+            ConsensusRule::Majority => Rule::Majority,
+            ConsensusRule::Unanimous => Rule::Unanimous,
+            ConsensusRule::ConfidenceWeighted => Rule::ConfidenceWeighted,
+        };
+        Self {
+            rule: rule as i32,
+            models: consensus
+                .models
+                .iter()
+                .map(|model| ModelVerdict {
+                    prompt_pool_hash: model.prompt_pool_hash.clone(),
+                    verdict: model.verdict,
+                    // 共识中的置信度在入库前已校验为万分比
+                    confidence_bps: model.confidence.basis_points().map_or(0, u32::from),
+                })
+                .collect(),
+        }
+    }
+}
+
+impl From<Consensus> for evidence::Consensus {
+    fn from(message: Consensus) -> Self {
+        let rule = match Rule::try_from(message.rule).unwrap_or(Rule::Majority) {
+            Rule::Majority => ConsensusRule::Majority,
+            Rule::Unanimous => ConsensusRule::Unanimous,
+            Rule::ConfidenceWeighted => ConsensusRule::ConfidenceWeighted,
+        };
+        Self {
+            rule,
+            models: message
+                .models
+                .into_iter()
+                .map(|model| evidence::ModelVerdict {
+                    prompt_pool_hash: model.prompt_pool_hash,
+                    verdict: model.verdict,
+                    confidence: Confidence::BasisPoints(model.confidence_bps.min(u32::from(evidence::CONFIDENCE_SCALE)) as u16),
+                })
+                .collect(),
+        }
+    }
+}
+
+impl From<CanonicalEncoding> for Encoding {
+    fn from(encoding: CanonicalEncoding) -> Self {
+        match encoding {
+            CanonicalEncoding::Bcs => Self::Bcs,
+            CanonicalEncoding::Protobuf => Self::Protobuf,
+        }
+    }
+}
+
+impl From<Encoding> for CanonicalEncoding {
+    fn from(encoding: Encoding) -> Self {
+        match encoding {
+            Encoding::Bcs => Self::Bcs,
+            Encoding::Protobuf => Self::Protobuf,
+        }
+    }
+}
+
+impl From<&receipt::Receipt> for Receipt {
+    fn from(receipt: &receipt::Receipt) -> Self {
+        Self {
+            evidence_hash: receipt.evidence_hash.clone(),
+            leaf_pos: receipt.leaf_pos,
+            root: receipt.root.clone(),
+            tree_size: receipt.tree_size,
+            timestamp: receipt.timestamp,
+            seq: receipt.seq,
+            clock_uncertain: receipt.clock_uncertain,
+            tenant_id: receipt.tenant_id.clone(),
+            key_path: receipt.key_path.clone(),
+            encoding: Encoding::from(receipt.encoding) as i32,
+            supersedes: receipt.supersedes,
+        }
+    }
+}
+
+impl TryFrom<Receipt> for receipt::Receipt {
+    type Error = anyhow::Error;
+
+    fn try_from(message: Receipt) -> anyhow::Result<Self> {
+        let encoding = Encoding::try_from(message.encoding)
+            .map_err(|_| anyhow::anyhow!("Unknown canonical encoding {}", message.encoding))?;
+        Ok(Self {
+            evidence_hash: message.evidence_hash,
+            leaf_pos: message.leaf_pos,
+            root: message.root,
+            tree_size: message.tree_size,
+            timestamp: message.timestamp,
+            seq: message.seq,
+            clock_uncertain: message.clock_uncertain,
+            tenant_id: message.tenant_id,
+            key_path: message.key_path,
+            encoding: encoding.into(),
+            supersedes: message.supersedes,
+        })
+    }
+}
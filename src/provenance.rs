@@ -0,0 +1,126 @@
+use axum::http::header::USER_AGENT;
+use axum::http::{HeaderMap, HeaderName};
+use log::{info, warn};
+use rand::RngCore;
+use std::net::IpAddr;
+use std::path::Path;
+use zeroize::Zeroizing;
+
+use crate::config::ProvenanceConfig;
+use crate::evidence::{Provenance, ProvenanceMode};
+use crate::keystore;
+
+/// 原文记录时每个字段的最大字节数 (超出部分截断)
+pub const MAX_PLAIN_FIELD_BYTES: usize = 512;
+
+/// 模块：提交来源采集 (Submitter Provenance)
+///
+/// **为什么需要**: 一条证据从哪里提交上来 (哪个 IP、哪张客户端证书、什么客户端) 本身就可能是取证线索，
+/// 例如证明某批证据都来自同一台采集设备，或发现被盗用的 API Key 从陌生网络提交。
+/// 开启后，存证管线把这些来源写进证据 (`provenance`)，与判定一起签名。
+///
+/// 来源往往是个人数据，默认不采集；开启后默认用服务端密钥假名化 (同一来源得到同一假名，没有密钥无法穷举 IP)，
+/// 密钥文件不存在时生成一把，每个部署各不相同。不带密钥的哈希只适合取值空间大的字段：IPv4 几秒就能穷举，
+/// 证据签名后永久保存、随副本和证据包外传，所以只有明确配置时才记哈希或原文。
+///
+/// - IP 默认取 TCP 对端地址；部署在反向代理之后时开启 `trust_forwarded_for`，
+///   取 `X-Forwarded-For` 中最后一个地址 (即最近一跳代理看到的对端，客户端自己写的前缀不可信)
+/// - 本服务不终止 TLS，客户端证书主题由终止 TLS 的代理写入 `client_cert_header` 指定的请求头
+pub struct ProvenanceCapture {
+    mode: ProvenanceMode,
+    key: Option<Zeroizing<[u8; 32]>>,
+    trust_forwarded_for: bool,
+    client_cert_header: Option<HeaderName>,
+}
+
+impl ProvenanceCapture {
+    /// 按 `[provenance]` 加载；未开启时返回 None
+    pub fn load(config: &ProvenanceConfig) -> anyhow::Result<Option<Self>> {
+        if !config.enabled {
+            return Ok(None);
+        }
+        let key = match config.mode {
+            ProvenanceMode::Pseudonymized => Some(load_or_generate_key(Path::new(&config.key_file))?),
+            ProvenanceMode::Plain | ProvenanceMode::Hashed => {
+                warn!("⚠️  提交来源以 {} 方式记录：IP 可由证据穷举还原，等同于把原始 IP 写进签名证据", config.mode.as_str());
+                None
+            }
+        };
+        let client_cert_header = match config.client_cert_header.trim() {
+            "" => None,
+            name => Some(HeaderName::try_from(name).map_err(|_| anyhow::anyhow!("Invalid provenance client_cert_header '{}'", name))?),
+        };
+        Ok(Some(Self { mode: config.mode, key, trust_forwarded_for: config.trust_forwarded_for, client_cert_header }))
+    }
+
+    pub fn mode(&self) -> ProvenanceMode {
+        self.mode
+    }
+
+    /// 从请求头与 TCP 对端地址采集来源 (`peer` 在没有连接信息时为 None)
+    pub fn capture(&self, headers: &HeaderMap, peer: Option<IpAddr>) -> anyhow::Result<Provenance> {
+        let forwarded = self
+            .trust_forwarded_for
+            .then(|| header_text(headers, &HeaderName::from_static("x-forwarded-for")))
+            .flatten()
+            .and_then(|value| value.rsplit(',').map(str::trim).find(|addr| !addr.is_empty()).map(str::to_string));
+        let ip = forwarded.or_else(|| peer.map(|ip| ip.to_canonical().to_string()));
+        let client_cert_subject = self.client_cert_header.as_ref().and_then(|name| header_text(headers, name));
+        let user_agent = header_text(headers, &USER_AGENT);
+        Ok(Provenance {
+            mode: self.mode,
+            ip: ip.map(|value| self.record("ip", &value)).transpose()?,
+            client_cert_subject: client_cert_subject.map(|value| self.record("client_cert_subject", &value)).transpose()?,
+            user_agent: user_agent.map(|value| self.record("user_agent", &value)).transpose()?,
+        })
+    }
+
+    fn record(&self, field: &str, value: &str) -> anyhow::Result<String> {
+        let mut recorded = self.mode.apply(field, value, self.key.as_deref())?;
+        if recorded.len() > MAX_PLAIN_FIELD_BYTES {
+            let mut end = MAX_PLAIN_FIELD_BYTES;
+            while !recorded.is_char_boundary(end) {
+                end -= 1;
+            }
+            recorded.truncate(end);
+        }
+        Ok(recorded)
+    }
+}
+
+/// 请求头的文本值；不存在、不是可见 ASCII 或为空时为 None
+fn header_text(headers: &HeaderMap, name: &HeaderName) -> Option<String> {
+    headers
+        .get(name)
+        .and_then(|value| value.to_str().ok())
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+        .map(str::to_string)
+}
+
+/// 读取假名化密钥；文件不存在时生成一把随机密钥 (0600) 写入
+///
+/// 同一部署的假名须保持稳定，密钥文件要与身份文件一样备份；换了密钥，新旧证据中的同一来源就对不上了。
+fn load_or_generate_key(path: &Path) -> anyhow::Result<Zeroizing<[u8; 32]>> {
+    if !path.exists() {
+        let mut key = Zeroizing::new([0u8; 32]);
+        rand::rngs::OsRng.fill_bytes(key.as_mut_slice());
+        keystore::write_secret(path, Zeroizing::new(hex::encode(key.as_slice())).as_bytes())?;
+        info!("✨ 已生成提交来源假名化密钥: '{}' (请与身份文件一同备份)", path.display());
+        return Ok(key);
+    }
+    let value = Zeroizing::new(std::fs::read_to_string(path)
+        .map_err(|e| anyhow::anyhow!("Cannot read provenance key '{}': {}", path.display(), e))?);
+    parse_key(value.trim())
+}
+
+/// 假名化密钥为 64 位 Hex
+fn parse_key(value: &str) -> anyhow::Result<Zeroizing<[u8; 32]>> {
+    let bytes = Zeroizing::new(hex::decode(value).map_err(|_| anyhow::anyhow!("Provenance key must be hex"))?);
+    let mut key = Zeroizing::new([0u8; 32]);
+    if bytes.len() != key.len() {
+        return Err(anyhow::anyhow!("Provenance key must be 32 bytes, got {}", bytes.len()));
+    }
+    key.copy_from_slice(&bytes);
+    Ok(key)
+}
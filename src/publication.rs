@@ -0,0 +1,426 @@
+use ed25519_dalek::{Signature, VerifyingKey};
+use log::{info, warn};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::process::Stdio;
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+use tokio::io::AsyncWriteExt;
+
+use crate::codec::StorageCodec;
+use crate::mmr_store::SledStore;
+use crate::signer::EvidenceSigner;
+use crate::tenant::TenantContext;
+
+/// 树头签名的域分隔前缀，避免与证据 / 回执 / 挑战应答签名混用
+const TREE_HEAD_DOMAIN: &[u8] = b"yuanjing-core tree head v1";
+
+/// DNS TXT 记录的版本标记
+const TXT_VERSION: &str = "yjroot1";
+
+/// 单个 TXT 字符串的最大长度 (RFC 1035)，更长的记录拆成多段
+const TXT_CHUNK_LEN: usize = 255;
+
+/// 树头 (Tree Head)：某一时刻某租户 MMR 的大小与根
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct TreeHead {
+    pub tenant_id: String,
+    /// MMR 根 (Hex)
+    pub root: String,
+    /// MMR 大小 (节点总数)
+    pub tree_size: u64,
+    /// 签名时间 (Unix 秒)
+    pub timestamp: i64,
+}
+
+impl TreeHead {
+    /// 规范化字节 (域前缀 + BCS)，签名与验签均基于此
+    pub fn canonical_bytes(&self) -> anyhow::Result<Vec<u8>> {
+        let mut bytes = TREE_HEAD_DOMAIN.to_vec();
+        bytes.extend_from_slice(&bcs::to_bytes(self)?);
+        Ok(bytes)
+    }
+}
+
+/// 由租户根密钥签名的树头
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct SignedTreeHead {
+    pub tree_head: TreeHead,
+    /// 签名 (Hex)
+    pub signature: String,
+    /// 租户根公钥 (Hex)
+    pub public_key: String,
+}
+
+impl SignedTreeHead {
+    pub fn sign(tree_head: TreeHead, signer: &EvidenceSigner) -> anyhow::Result<Self> {
+        let signature = signer.sign_bytes(&tree_head.canonical_bytes()?)?;
+        Ok(Self {
+            tree_head,
+            signature: hex::encode(signature.to_bytes()),
+            public_key: hex::encode(signer.public_key().to_bytes()),
+        })
+    }
+
+    /// 校验签名；`trusted_key` 为事先固定的租户根公钥，与树头自带的公钥不符时直接判为无效
+    pub fn verify(&self, trusted_key: &VerifyingKey) -> anyhow::Result<bool> {
+        if hex::encode(trusted_key.to_bytes()) != self.public_key {
+            return Ok(false);
+        }
+        let signature = Signature::from_slice(&hex::decode(&self.signature)?)?;
+        Ok(EvidenceSigner::verify_bytes(trusted_key, &self.tree_head.canonical_bytes()?, &signature))
+    }
+
+    /// DNS TXT 记录内容：`v=yjroot1;tenant=..;size=..;root=..;ts=..;sig=..`
+    ///
+    /// 不含公钥：DNS 记录的意义在于让验证方把它与自己固定的公钥对照，而不是顺带分发公钥。
+    pub fn to_txt(&self) -> String {
+        format!(
+            "v={};tenant={};size={};root={};ts={};sig={}",
+            TXT_VERSION,
+            self.tree_head.tenant_id,
+            self.tree_head.tree_size,
+            self.tree_head.root,
+            self.tree_head.timestamp,
+            self.signature
+        )
+    }
+
+    /// 解析 TXT 记录 (多段字符串须先按顺序拼接)；公钥由验证方提供
+    pub fn from_txt(txt: &str, public_key: &VerifyingKey) -> anyhow::Result<Self> {
+        let mut fields = BTreeMap::new();
+        for part in txt.split(';') {
+            let (key, value) = part.split_once('=')
+                .ok_or_else(|| anyhow::anyhow!("Malformed tree head record field '{}'", part))?;
+            fields.insert(key.trim(), value.trim());
+        }
+        let field = |name: &str| fields.get(name).copied().ok_or_else(|| anyhow::anyhow!("Tree head record is missing '{}'", name));
+        if field("v")? != TXT_VERSION {
+            return Err(anyhow::anyhow!("Unsupported tree head record version '{}'", field("v")?));
+        }
+        Ok(Self {
+            tree_head: TreeHead {
+                tenant_id: field("tenant")?.to_string(),
+                root: field("root")?.to_string(),
+                tree_size: field("size")?.parse()?,
+                timestamp: field("ts")?.parse()?,
+            },
+            signature: field("sig")?.to_string(),
+            public_key: hex::encode(public_key.to_bytes()),
+        })
+    }
+}
+
+/// DNS 发布目标 (RFC 2136 动态更新，经 `nsupdate` 提交)
+#[derive(Debug, Clone)]
+pub struct DnsTarget {
+    /// 记录名后缀；租户 `t` 的记录为 `t.<name>`
+    pub name: String,
+    /// 权威服务器 (`nsupdate` 的 `server` 指令)，为空时由 `nsupdate` 自行查找主服务器
+    pub server: String,
+    /// TSIG 密钥文件 (`nsupdate -k`)，为空时不签名
+    pub key_file: String,
+    pub ttl: u32,
+    /// `nsupdate` 可执行文件
+    pub command: String,
+}
+
+impl DnsTarget {
+    pub fn record_name(&self, tenant_id: &str) -> String {
+        format!("{}.{}", tenant_id, self.name.trim_end_matches('.'))
+    }
+
+    /// 整条替换该租户的 TXT 记录
+    pub async fn push(&self, head: &SignedTreeHead) -> anyhow::Result<()> {
+        let txt = head.to_txt();
+        let chunks: Vec<String> = txt.as_bytes()
+            .chunks(TXT_CHUNK_LEN)
+            .map(|chunk| format!("\"{}\"", String::from_utf8_lossy(chunk)))
+            .collect();
+        let record = self.record_name(&head.tree_head.tenant_id);
+
+        let mut script = String::new();
+        if !self.server.is_empty() {
+            script.push_str(&format!("server {}\n", self.server));
+        }
+        script.push_str(&format!("update delete {} TXT\n", record));
+        script.push_str(&format!("update add {} {} TXT {}\n", record, self.ttl, chunks.join(" ")));
+        script.push_str("send\n");
+
+        let mut command = tokio::process::Command::new(&self.command);
+        if !self.key_file.is_empty() {
+            command.arg("-k").arg(&self.key_file);
+        }
+        let mut child = command
+            .stdin(Stdio::piped())
+            .stdout(Stdio::null())
+            .stderr(Stdio::piped())
+            .spawn()
+            .map_err(|e| anyhow::anyhow!("Cannot run '{}': {}", self.command, e))?;
+        if let Some(mut stdin) = child.stdin.take() {
+            stdin.write_all(script.as_bytes()).await?;
+        }
+        let output = child.wait_with_output().await?;
+        if !output.status.success() {
+            return Err(anyhow::anyhow!(
+                "{} failed ({}): {}",
+                self.command,
+                output.status,
+                String::from_utf8_lossy(&output.stderr).trim()
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// 历史树头存档 (Tree Head Archive)
+///
+/// 根发布每签出一个更大的树，就在 `tree_heads` 树中按树大小追加一条，只增不删。
+/// 订阅源 (`/roots/feed`) 据此输出，监控方可离线保存全部历史根，日后逐一做一致性检查。
+#[derive(Clone)]
+pub struct TreeHeadArchive {
+    heads: sled::Tree,
+}
+
+impl TreeHeadArchive {
+    pub fn open(store: &SledStore) -> anyhow::Result<Self> {
+        Ok(Self { heads: store.tree("tree_heads")? })
+    }
+
+    /// 最近存档的树头
+    pub fn latest(&self) -> anyhow::Result<Option<SignedTreeHead>> {
+        self.heads.last()?.map(|(_, v)| StorageCodec::decode(&v)).transpose()
+    }
+
+    /// 追加存档；树大小不超过最近一条时忽略 (同一棵树只存第一次签出的树头)，返回是否写入
+    pub fn append(&self, head: &SignedTreeHead) -> anyhow::Result<bool> {
+        if self.latest()?.is_some_and(|latest| latest.tree_head.tree_size >= head.tree_head.tree_size) {
+            return Ok(false);
+        }
+        self.heads.insert(head.tree_head.tree_size.to_be_bytes(), StorageCodec::encode(head)?)?;
+        self.heads.flush()?;
+        Ok(true)
+    }
+
+    /// 指定树大小的存档树头；该大小从未签出过返回 None
+    pub fn get(&self, tree_size: u64) -> anyhow::Result<Option<SignedTreeHead>> {
+        self.heads.get(tree_size.to_be_bytes())?.map(|v| StorageCodec::decode(&v)).transpose()
+    }
+
+    /// `ts` (Unix 秒) 时刻仍为最新的存档树头，以及它被下一条取代的时间 (之后再无存档为 None)
+    ///
+    /// 即签名时间不晚于 `ts` 的最后一条；从新到旧扫描。`ts` 早于第一条存档返回 None。
+    pub fn at(&self, ts: i64) -> anyhow::Result<Option<(SignedTreeHead, Option<i64>)>> {
+        let mut superseded_at = None;
+        for item in self.heads.iter().rev() {
+            let head: SignedTreeHead = StorageCodec::decode(&item?.1)?;
+            if head.tree_head.timestamp <= ts {
+                return Ok(Some((head, superseded_at)));
+            }
+            superseded_at = Some(head.tree_head.timestamp);
+        }
+        Ok(None)
+    }
+
+    /// 按根 (Hex) 查找存档树头，从新到旧逐条比对；没有签出过该根返回 None
+    pub fn find_root(&self, root: &str) -> anyhow::Result<Option<SignedTreeHead>> {
+        for item in self.heads.iter().rev() {
+            let head: SignedTreeHead = StorageCodec::decode(&item?.1)?;
+            if head.tree_head.root.eq_ignore_ascii_case(root) {
+                return Ok(Some(head));
+            }
+        }
+        Ok(None)
+    }
+
+    /// 最近的 `limit` 条 (新的在前)
+    pub fn recent(&self, limit: usize) -> anyhow::Result<Vec<SignedTreeHead>> {
+        self.heads.iter().rev().take(limit).map(|item| StorageCodec::decode(&item?.1)).collect()
+    }
+}
+
+/// 订阅源格式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FeedFormat {
+    #[default]
+    Atom,
+    Rss,
+}
+
+impl FeedFormat {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Atom => "atom",
+            Self::Rss => "rss",
+        }
+    }
+
+    pub fn content_type(self) -> &'static str {
+        match self {
+            Self::Atom => "application/atom+xml; charset=utf-8",
+            Self::Rss => "application/rss+xml; charset=utf-8",
+        }
+    }
+}
+
+/// 树头订阅源 (Atom 1.0 / RSS 2.0)
+///
+/// 每条目对应一个存档树头，正文是完整的签名树头 JSON，可直接用 `SignedTreeHead::verify` 校验；
+/// 条目 ID 由租户与树大小构成，重复抓取时阅读器不会重复收录。`heads` 须为新的在前。
+pub fn render_feed(format: FeedFormat, tenant_id: &str, self_link: &str, heads: &[SignedTreeHead]) -> anyhow::Result<String> {
+    let title = format!("Yuanjing signed tree heads: {}", tenant_id);
+    let updated = heads.first().map_or(0, |head| head.tree_head.timestamp);
+    let mut xml = String::from("<?xml version=\"1.0\" encoding=\"utf-8\"?>\n");
+    match format {
+        FeedFormat::Atom => {
+            xml.push_str("<feed xmlns=\"http://www.w3.org/2005/Atom\">\n");
+            xml.push_str(&format!("<id>urn:yuanjing:tree-heads:{}</id>\n", escape_xml(tenant_id)));
+            xml.push_str(&format!("<title>{}</title>\n", escape_xml(&title)));
+            xml.push_str(&format!("<updated>{}</updated>\n", rfc3339(updated)));
+            xml.push_str(&format!("<link rel=\"self\" href=\"{}\"/>\n", escape_xml(self_link)));
+            xml.push_str("<author><name>yuanjing-core</name></author>\n");
+            for head in heads {
+                let tree_head = &head.tree_head;
+                xml.push_str("<entry>\n");
+                xml.push_str(&format!("<id>{}</id>\n", entry_id(tree_head)));
+                xml.push_str(&format!("<title>tree_size {} root {}</title>\n", tree_head.tree_size, tree_head.root));
+                xml.push_str(&format!("<updated>{}</updated>\n", rfc3339(tree_head.timestamp)));
+                xml.push_str(&format!("<content type=\"application/json\">{}</content>\n", escape_xml(&serde_json::to_string(head)?)));
+                xml.push_str("</entry>\n");
+            }
+            xml.push_str("</feed>\n");
+        }
+        FeedFormat::Rss => {
+            xml.push_str("<rss version=\"2.0\">\n<channel>\n");
+            xml.push_str(&format!("<title>{}</title>\n", escape_xml(&title)));
+            xml.push_str(&format!("<link>{}</link>\n", escape_xml(self_link)));
+            xml.push_str("<description>Signed tree heads of a yuanjing-core transparency log</description>\n");
+            xml.push_str(&format!("<lastBuildDate>{}</lastBuildDate>\n", rfc2822(updated)));
+            for head in heads {
+                let tree_head = &head.tree_head;
+                xml.push_str("<item>\n");
+                xml.push_str(&format!("<guid isPermaLink=\"false\">{}</guid>\n", entry_id(tree_head)));
+                xml.push_str(&format!("<title>tree_size {} root {}</title>\n", tree_head.tree_size, tree_head.root));
+                xml.push_str(&format!("<pubDate>{}</pubDate>\n", rfc2822(tree_head.timestamp)));
+                xml.push_str(&format!("<description>{}</description>\n", escape_xml(&serde_json::to_string(head)?)));
+                xml.push_str("</item>\n");
+            }
+            xml.push_str("</channel>\n</rss>\n");
+        }
+    }
+    Ok(xml)
+}
+
+fn entry_id(tree_head: &TreeHead) -> String {
+    format!("urn:yuanjing:tree-head:{}:{}", escape_xml(&tree_head.tenant_id), tree_head.tree_size)
+}
+
+fn rfc3339(timestamp: i64) -> String {
+    chrono::DateTime::from_timestamp(timestamp, 0)
+        .unwrap_or_default()
+        .to_rfc3339_opts(chrono::SecondsFormat::Secs, true)
+}
+
+fn rfc2822(timestamp: i64) -> String {
+    chrono::DateTime::from_timestamp(timestamp, 0).unwrap_or_default().to_rfc2822()
+}
+
+fn escape_xml(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
+
+/// 模块：根发布 (Root Publication)
+///
+/// **为什么需要**: 回执里的根由日志运营方签发，验证方若只从运营方处取根，运营方就能对不同的人出示不同的树 (分叉攻击)。
+/// 周期性地用租户根密钥签名当前根，经 `/.well-known/yuanjing-root` 公开，并可写入运营方不完全掌控的渠道
+/// (DNS TXT 记录，经缓存解析器层层分发)。第三方固定 (pin) 这些树头后，日后任何回执都必须与之一致。
+///
+/// 每轮都重新签名 (时间戳刷新，供监控方判断日志是否停摆)；存档与 DNS 只在树大小变化时更新，避免无谓的写入与区域变更。
+/// 空树不发布。
+#[derive(Clone)]
+pub struct RootPublisher {
+    latest: Arc<RwLock<BTreeMap<String, SignedTreeHead>>>,
+    dns: Option<DnsTarget>,
+    /// 各租户已成功写入 DNS 的树大小；写入失败时不更新，下一轮重试
+    dns_sizes: Arc<RwLock<BTreeMap<String, u64>>>,
+    interval: Duration,
+}
+
+impl RootPublisher {
+    pub fn new(interval: Duration, dns: Option<DnsTarget>) -> Self {
+        Self {
+            latest: Arc::new(RwLock::new(BTreeMap::new())),
+            dns,
+            dns_sizes: Arc::new(RwLock::new(BTreeMap::new())),
+            interval,
+        }
+    }
+
+    /// 各租户最近一次发布的树头 (按租户 ID 排序)
+    pub fn latest(&self) -> Vec<SignedTreeHead> {
+        self.latest.read().expect("published roots poisoned").values().cloned().collect()
+    }
+
+    /// 某租户的 DNS 记录名与本进程已写入 DNS 的树大小；未配置 DNS 时返回 None
+    pub fn dns_status(&self, tenant_id: &str) -> Option<(String, Option<u64>)> {
+        let dns = self.dns.as_ref()?;
+        let published = self.dns_sizes.read().expect("published roots poisoned").get(tenant_id).copied();
+        Some((dns.record_name(tenant_id), published))
+    }
+
+    /// 对所有租户执行一次发布
+    pub async fn publish_once(&self, tenants: &[Arc<TenantContext>]) {
+        for tenant in tenants {
+            if let Err(e) = self.publish_tenant(tenant).await {
+                warn!("⚠️  租户 '{}' 根发布失败 (下次重试): {}", tenant.id, e);
+            }
+        }
+    }
+
+    async fn publish_tenant(&self, tenant: &TenantContext) -> anyhow::Result<()> {
+        let snapshot = tenant.reader.snapshot();
+        let tree_size = snapshot.mmr_size();
+        if tree_size == 0 {
+            return Ok(());
+        }
+        let tree_head = TreeHead {
+            tenant_id: tenant.id.clone(),
+            root: hex::encode(snapshot.root_at(tree_size)?),
+            tree_size,
+            timestamp: chrono::Utc::now().timestamp(),
+        };
+        let signed = SignedTreeHead::sign(tree_head, &tenant.signer)?;
+
+        self.latest.write().expect("published roots poisoned").insert(tenant.id.clone(), signed.clone());
+        if snapshot.tree_heads()?.append(&signed)? {
+            info!("🗂️  租户 '{}' 树头已存档 (size={})", tenant.id, tree_size);
+        }
+
+        let Some(dns) = &self.dns else {
+            return Ok(());
+        };
+        let published_size = self.dns_sizes.read().expect("published roots poisoned").get(&tenant.id).copied();
+        if published_size != Some(tree_size) {
+            dns.push(&signed).await?;
+            self.dns_sizes.write().expect("published roots poisoned").insert(tenant.id.clone(), tree_size);
+            info!("🌐 租户 '{}' 树头已写入 DNS: {} (size={})", tenant.id, dns.record_name(&tenant.id), tree_size);
+        }
+        Ok(())
+    }
+
+    /// 启动后台周期发布任务 (启动时先执行一次)
+    pub fn spawn_periodic(self, tenants: Vec<Arc<TenantContext>>) {
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(self.interval);
+            loop {
+                ticker.tick().await;
+                self.publish_once(&tenants).await;
+            }
+        });
+    }
+}
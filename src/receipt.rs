@@ -0,0 +1,212 @@
+use prost::Message;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::evidence::CanonicalEncoding;
+
+/// 回执签名的域分隔前缀
+///
+/// 证据与回执由同一把密钥签名；不加前缀时，两种规范字节一旦撞上，一份证据签名就能被当作回执签名重放。
+pub const RECEIPT_DOMAIN: &[u8] = b"yuanjing-core receipt v1";
+
+/// 模块：存证回执 (Receipt)
+///
+/// **职责**: 把“证据”和“它在账本里的位置”绑死在一起。
+/// 仅对 `Evidence` 签名是不够的：恶意服务端完全可以把别人的 `leaf_pos` / `root`
+/// 拼到一份合法签名的证据上返回给用户。
+/// 因此服务端在入库完成后，还需要对下面这个结构整体再签一次名。
+///
+/// **验证方**拿到回执后应当检查：
+/// 1. `evidence_hash` == Blake3(Evidence 按 `encoding` 编码的规范字节)
+/// 2. 回执签名有效 (签名覆盖 `RECEIPT_DOMAIN || 规范字节`，见 [`Receipt::signing_bytes`])
+/// 3. `evidence_hash` 在 `tree_size` 大小的 MMR 中位于 `leaf_pos`，且该树的根为 `root`
+/// 4. (导出日志时) `seq` 连续、递增、不重复
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, JsonSchema)]
+pub struct Receipt {
+    /// 叶子哈希 (Hex)，即 Blake3(Evidence 的规范字节)
+    pub evidence_hash: String,
+    /// 叶子在 MMR 中的位置 (注意：不是连续的叶子序号)
+    pub leaf_pos: u64,
+    /// 追加完成后的 MMR Root (Hex)
+    pub root: String,
+    /// 追加完成后的 MMR 大小 (节点总数)，用于重建当时的树
+    pub tree_size: u64,
+    /// 回执签发时间 (Unix 秒)
+    pub timestamp: i64,
+    /// 服务端分配的单调序列号，随回执一起签名。
+    /// 导出的日志中若出现缺号、乱序或重号，都可以据此举证。
+    pub seq: u64,
+    /// 签发时服务器时钟是否未通过 NTP 校验 (仅 `flag` 策略下可能为 true)
+    pub clock_uncertain: bool,
+    /// 所属租户；不同租户使用独立的 MMR 与签名子密钥
+    pub tenant_id: String,
+    /// 签名密钥的 SLIP-0010 派生路径 (如 `m/0'/3'`)；空串表示租户根密钥
+    #[serde(default)]
+    pub key_path: String,
+    /// 叶子哈希与两份签名所基于的规范编码；缺省为 BCS
+    #[serde(default)]
+    pub encoding: CanonicalEncoding,
+    /// 被本条更正 (取代) 的早先叶子位置；随回执一起签名，更正链因此可逐条举证
+    #[serde(default)]
+    pub supersedes: Option<u64>,
+}
+
+impl Receipt {
+    /// 回执的规范化字节 (BCS，全部字段)，签名与验签均基于此
+    ///
+    /// 以 Protobuf 签发的回执改用 `yuanjing.v1.Receipt` 的编码 (见 `proto.rs`)。
+    pub fn canonical_bytes(&self) -> anyhow::Result<Vec<u8>> {
+        if self.encoding == CanonicalEncoding::Protobuf {
+            return Ok(crate::proto::Receipt::from(self).encode_to_vec());
+        }
+        Ok(bcs::to_bytes(self)?)
+    }
+
+    /// 回执签名覆盖的字节 (域前缀 + 规范字节)
+    pub fn signing_bytes(&self) -> anyhow::Result<Vec<u8>> {
+        let mut bytes = RECEIPT_DOMAIN.to_vec();
+        bytes.extend_from_slice(&self.canonical_bytes()?);
+        Ok(bytes)
+    }
+}
+
+/// 回执指针 (Receipt Pointer)
+///
+/// **职责**: 纸质鉴定报告上二维码承载的内容：叶子哈希、位置、根、签名公钥与回执签名，
+/// 编码为一行 URI：
+///
+/// ```text
+/// yuanjing:receipt?v=1&tenant=default&pos=15&size=26&leaf=<hex>&root=<hex>&key=<hex>&sig=<hex>
+/// ```
+///
+/// 指针本身不足以验签 (回执签名覆盖时间、序列号等全部字段)。扫描方按 `tenant` / `pos` 回到日志
+/// 取完整回执，用 [`ReceiptPointer::matches`] 确认两者描述的是同一条记录，再用 `key` 对应的公钥验签。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReceiptPointer {
+    pub tenant_id: String,
+    pub leaf_pos: u64,
+    pub tree_size: u64,
+    /// 叶子哈希 (Hex)
+    pub leaf_hash: String,
+    /// 回执中签名的根 (Hex)
+    pub root: String,
+    /// 签发回执的公钥 (Hex)
+    pub key_id: String,
+    /// 回执签名 (Hex)
+    pub signature: String,
+}
+
+impl ReceiptPointer {
+    const PREFIX: &'static str = "yuanjing:receipt?";
+    const VERSION: &'static str = "1";
+
+    pub fn new(receipt: &Receipt, key_id: &str, signature: &str) -> Self {
+        Self {
+            tenant_id: receipt.tenant_id.clone(),
+            leaf_pos: receipt.leaf_pos,
+            tree_size: receipt.tree_size,
+            leaf_hash: receipt.evidence_hash.clone(),
+            root: receipt.root.clone(),
+            key_id: key_id.to_string(),
+            signature: signature.to_string(),
+        }
+    }
+
+    pub fn to_uri(&self) -> String {
+        format!(
+            "{}v={}&tenant={}&pos={}&size={}&leaf={}&root={}&key={}&sig={}",
+            Self::PREFIX,
+            Self::VERSION,
+            self.tenant_id,
+            self.leaf_pos,
+            self.tree_size,
+            self.leaf_hash,
+            self.root,
+            self.key_id,
+            self.signature
+        )
+    }
+
+    /// 解析扫描得到的 URI；参数顺序无关，缺少字段或版本不受支持时报错
+    pub fn parse(uri: &str) -> anyhow::Result<Self> {
+        let query = uri
+            .strip_prefix(Self::PREFIX)
+            .ok_or_else(|| anyhow::anyhow!("Not a receipt pointer: expected '{}'", Self::PREFIX))?;
+        let params: std::collections::HashMap<&str, &str> = query.split('&').filter_map(|pair| pair.split_once('=')).collect();
+        let field = |name: &str| {
+            params
+                .get(name)
+                .map(|value| value.to_string())
+                .ok_or_else(|| anyhow::anyhow!("Receipt pointer is missing '{}'", name))
+        };
+        if field("v")? != Self::VERSION {
+            return Err(anyhow::anyhow!("Unsupported receipt pointer version '{}'", field("v")?));
+        }
+        Ok(Self {
+            tenant_id: field("tenant")?,
+            leaf_pos: field("pos")?.parse()?,
+            tree_size: field("size")?.parse()?,
+            leaf_hash: field("leaf")?,
+            root: field("root")?,
+            key_id: field("key")?,
+            signature: field("sig")?,
+        })
+    }
+
+    /// 与从日志取回的完整回执及其签名核对 (Hex 不区分大小写)
+    pub fn matches(&self, receipt: &Receipt, receipt_signature: &str) -> bool {
+        self.tenant_id == receipt.tenant_id
+            && self.leaf_pos == receipt.leaf_pos
+            && self.tree_size == receipt.tree_size
+            && self.leaf_hash.eq_ignore_ascii_case(&receipt.evidence_hash)
+            && self.root.eq_ignore_ascii_case(&receipt.root)
+            && self.signature.eq_ignore_ascii_case(receipt_signature)
+    }
+}
+
+/// 序列号异常 (Sequence Violation)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SequenceViolation {
+    /// 缺号：期望 `expected`，实际出现 `found`
+    Gap { expected: u64, found: u64 },
+    /// 乱序：`found` 出现在 `previous` 之后
+    Reordered { previous: u64, found: u64 },
+    /// 重号：同一序列号出现多次 (重放)
+    Duplicate { seq: u64 },
+}
+
+impl std::fmt::Display for SequenceViolation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Gap { expected, found } => write!(f, "sequence gap: expected {}, found {}", expected, found),
+            Self::Reordered { previous, found } => write!(f, "sequence reordered: {} after {}", found, previous),
+            Self::Duplicate { seq } => write!(f, "duplicate sequence number {}", seq),
+        }
+    }
+}
+
+/// 检查一段导出日志中的回执序列是否连续
+///
+/// 回执应按导出顺序传入；返回遇到的第一个异常。
+/// 注意：这里只检查序列号本身，回执签名需另行验证。
+pub fn check_sequence(receipts: &[Receipt]) -> Result<(), SequenceViolation> {
+    let mut iter = receipts.iter();
+    let Some(first) = iter.next() else {
+        return Ok(());
+    };
+    let mut previous = first.seq;
+    for receipt in iter {
+        let found = receipt.seq;
+        if found == previous {
+            return Err(SequenceViolation::Duplicate { seq: found });
+        }
+        if found < previous {
+            return Err(SequenceViolation::Reordered { previous, found });
+        }
+        if found != previous + 1 {
+            return Err(SequenceViolation::Gap { expected: previous + 1, found });
+        }
+        previous = found;
+    }
+    Ok(())
+}
@@ -0,0 +1,322 @@
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use ed25519_dalek::VerifyingKey;
+use log::{info, warn};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use std::time::Duration;
+
+use crate::codec::StorageCodec;
+use crate::mmr_store::SledStore;
+use crate::publication::SignedTreeHead;
+use crate::tenant::TenantContext;
+
+/// Ed25519 公钥的 SubjectPublicKeyInfo DER 前缀 (RFC 8410)，其后紧跟 32 字节公钥
+const ED25519_SPKI_PREFIX: [u8; 12] = [0x30, 0x2a, 0x30, 0x05, 0x06, 0x03, 0x2b, 0x65, 0x70, 0x03, 0x21, 0x00];
+
+/// Rekor 条目提交接口 (相对于日志地址)
+const ENTRIES_PATH: &str = "/api/v1/log/entries";
+
+/// 租户根公钥的 PEM (`PUBLIC KEY`)，Rekor 按 PKIX 解析
+pub fn public_key_pem(key: &VerifyingKey) -> String {
+    let mut der = ED25519_SPKI_PREFIX.to_vec();
+    der.extend_from_slice(key.as_bytes());
+    format!("-----BEGIN PUBLIC KEY-----\n{}\n-----END PUBLIC KEY-----\n", BASE64.encode(der))
+}
+
+/// Rekor 对一个签名树头的收录记录
+///
+/// 提交的是 `rekord` 条目：原文为树头的规范化字节 (`TreeHead::canonical_bytes`)，签名与公钥即我方签名树头中的那两项。
+/// 凭 `log_index` 或 `uuid` 可在 Rekor 上查到该条目及其包含证明；`signed_entry_timestamp` 由 Rekor 的密钥签发，
+/// 用 Rekor 公开的公钥即可离线校验“该条目在 `integrated_time` 已被收录”。
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct RekorEntry {
+    /// Rekor 日志地址 (本地配置)
+    pub log_url: String,
+    pub signed_tree_head: SignedTreeHead,
+    /// 条目 UUID
+    pub uuid: String,
+    pub log_index: u64,
+    /// Rekor 收录时间 (Unix 秒)
+    pub integrated_time: i64,
+    /// Rekor 日志 ID (Hex，即日志公钥的 SHA-256)
+    pub log_id: String,
+    /// Rekor 对条目的签名时间戳 (Base64)，旧版本 Rekor 可能不返回
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub signed_entry_timestamp: Option<String>,
+    /// Rekor 规范化后的条目正文 (Base64)，连同上一项即可离线复核
+    pub body: String,
+}
+
+/// 提交给 Rekor 的 `rekord` 条目
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ProposedEntry {
+    api_version: &'static str,
+    kind: &'static str,
+    spec: ProposedSpec,
+}
+
+#[derive(Serialize)]
+struct ProposedSpec {
+    signature: ProposedSignature,
+    data: ProposedData,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ProposedSignature {
+    format: &'static str,
+    /// 签名 (Base64)
+    content: String,
+    public_key: ProposedContent,
+}
+
+#[derive(Serialize)]
+struct ProposedContent {
+    /// Base64
+    content: String,
+}
+
+#[derive(Serialize)]
+struct ProposedData {
+    /// 被签名的原文 (Base64)
+    content: String,
+}
+
+/// Rekor 返回的条目 (以 UUID 为键的对象中的值)
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct LogEntryResponse {
+    body: String,
+    integrated_time: i64,
+    #[serde(rename = "logID")]
+    log_id: String,
+    log_index: u64,
+    #[serde(default)]
+    verification: Option<LogEntryVerification>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct LogEntryVerification {
+    #[serde(default)]
+    signed_entry_timestamp: Option<String>,
+}
+
+/// Rekor 规范化后的 `rekord` 正文 (只取复核所需的字段)
+#[derive(Deserialize)]
+struct EntryBody {
+    kind: String,
+    spec: EntryBodySpec,
+}
+
+#[derive(Deserialize)]
+struct EntryBodySpec {
+    data: EntryBodyData,
+    signature: EntryBodySignature,
+}
+
+#[derive(Deserialize)]
+struct EntryBodyData {
+    hash: EntryBodyHash,
+}
+
+#[derive(Deserialize)]
+struct EntryBodyHash {
+    algorithm: String,
+    value: String,
+}
+
+#[derive(Deserialize)]
+struct EntryBodySignature {
+    content: String,
+}
+
+/// 外部透明日志 (Rekor 实例)
+#[derive(Debug, Clone)]
+pub struct RekorLog {
+    /// 日志地址，如 `https://rekor.sigstore.dev`
+    pub url: String,
+}
+
+impl RekorLog {
+    /// 把签名树头作为 `rekord` 条目提交，返回 Rekor 的收录记录
+    ///
+    /// 同一条目已被收录时 (上一轮提交成功但未来得及存档) Rekor 返回 `409`，此时按 `Location` 取回已有条目。
+    pub async fn submit(&self, head: &SignedTreeHead) -> anyhow::Result<RekorEntry> {
+        let data = head.tree_head.canonical_bytes()?;
+        let signature = hex::decode(&head.signature)?;
+        let public_key = VerifyingKey::from_bytes(
+            &hex::decode(&head.public_key)?
+                .try_into()
+                .map_err(|_| anyhow::anyhow!("Tree head public key must be 32 bytes"))?,
+        )?;
+        let proposed = ProposedEntry {
+            api_version: "0.0.1",
+            kind: "rekord",
+            spec: ProposedSpec {
+                signature: ProposedSignature {
+                    format: "x509",
+                    content: BASE64.encode(&signature),
+                    public_key: ProposedContent { content: BASE64.encode(public_key_pem(&public_key)) },
+                },
+                data: ProposedData { content: BASE64.encode(&data) },
+            },
+        };
+        let body = serde_json::to_vec(&proposed)?;
+        let base = self.url.trim_end_matches('/').to_string();
+        let entries: BTreeMap<String, LogEntryResponse> = tokio::task::spawn_blocking(move || {
+            let url = format!("{}{}", base, ENTRIES_PATH);
+            let response = match ureq::post(&url)
+                .timeout(Duration::from_secs(30))
+                .set("Content-Type", "application/json")
+                .send_bytes(&body)
+            {
+                Ok(response) => response,
+                Err(ureq::Error::Status(409, response)) => {
+                    let location = response
+                        .header("Location")
+                        .ok_or_else(|| anyhow::anyhow!("Rekor '{}' reported a duplicate entry without a Location", base))?;
+                    let existing = if location.starts_with("http") { location.to_string() } else { format!("{}{}", base, location) };
+                    ureq::get(&existing)
+                        .timeout(Duration::from_secs(30))
+                        .call()
+                        .map_err(|e| anyhow::anyhow!("Fetching the existing Rekor entry '{}' failed: {}", existing, e))?
+                }
+                Err(ureq::Error::Status(code, response)) => {
+                    return Err(anyhow::anyhow!(
+                        "Rekor '{}' rejected the tree head ({}): {}",
+                        base,
+                        code,
+                        response.into_string().unwrap_or_default()
+                    ))
+                }
+                Err(e) => return Err(anyhow::anyhow!("Rekor request to '{}' failed: {}", base, e)),
+            };
+            serde_json::from_reader(response.into_reader()).map_err(|e| anyhow::anyhow!("Unexpected Rekor response from '{}': {}", base, e))
+        })
+        .await??;
+
+        let (uuid, entry) = entries
+            .into_iter()
+            .next()
+            .ok_or_else(|| anyhow::anyhow!("Rekor '{}' returned no entry", self.url))?;
+        // 只认正文与所提交内容一致的条目：原文哈希与签名都须对上
+        let decoded: EntryBody = serde_json::from_slice(&BASE64.decode(&entry.body)?)
+            .map_err(|e| anyhow::anyhow!("Unexpected Rekor entry body: {}", e))?;
+        if decoded.kind != "rekord"
+            || decoded.spec.data.hash.algorithm != "sha256"
+            || decoded.spec.data.hash.value != hex::encode(Sha256::digest(&data))
+            || BASE64.decode(&decoded.spec.signature.content)? != signature
+        {
+            return Err(anyhow::anyhow!("Rekor '{}' returned an entry that does not match the submitted tree head", self.url));
+        }
+        Ok(RekorEntry {
+            log_url: self.url.clone(),
+            signed_tree_head: head.clone(),
+            uuid,
+            log_index: entry.log_index,
+            integrated_time: entry.integrated_time,
+            log_id: entry.log_id,
+            signed_entry_timestamp: entry.verification.and_then(|verification| verification.signed_entry_timestamp),
+            body: entry.body,
+        })
+    }
+}
+
+/// Rekor 收录记录存档
+///
+/// 按树大小存于 `rekor_entries` 树，与根发布存档 (`tree_heads`) 一一对应，只增不删。
+#[derive(Clone)]
+pub struct RekorArchive {
+    entries: sled::Tree,
+}
+
+impl RekorArchive {
+    pub fn open(store: &SledStore) -> anyhow::Result<Self> {
+        Ok(Self { entries: store.tree("rekor_entries")? })
+    }
+
+    pub fn latest(&self) -> anyhow::Result<Option<RekorEntry>> {
+        self.entries.last()?.map(|(_, v)| StorageCodec::decode(&v)).transpose()
+    }
+
+    /// 某个存档树头的收录记录
+    pub fn get(&self, tree_size: u64) -> anyhow::Result<Option<RekorEntry>> {
+        self.entries.get(tree_size.to_be_bytes())?.map(|v| StorageCodec::decode(&v)).transpose()
+    }
+
+    pub fn append(&self, entry: &RekorEntry) -> anyhow::Result<()> {
+        self.entries.insert(entry.signed_tree_head.tree_head.tree_size.to_be_bytes(), StorageCodec::encode(entry)?)?;
+        self.entries.flush()?;
+        Ok(())
+    }
+
+    /// 最近的 `limit` 条 (树大小大的在前)
+    pub fn recent(&self, limit: usize) -> anyhow::Result<Vec<RekorEntry>> {
+        self.entries.iter().rev().take(limit).map(|item| StorageCodec::decode(&item?.1)).collect()
+    }
+}
+
+/// 模块：Rekor 透明日志交叉发布 (Rekor Cross-Publication)
+///
+/// **为什么需要**: 交叉公证依赖我们自己挑选的公证方；Sigstore 的 Rekor 是任何人都能查询、由独立方运营的公开透明日志，
+/// 自身也是一棵对外公布根的 Merkle 树。把签名树头写进 Rekor，等于让一本我们无法改写的公共账本为我们的根做见证，
+/// 第三方不必信任我方或我方选的公证方，直接在 Rekor 上按 `log_index` 查到条目与收录时间即可。
+///
+/// - 只提交根发布存档中的最新树头，同一树大小只提交一次；失败时下一轮重试，只读副本不提交
+/// - Rekor 返回的条目正文须与所提交的原文哈希与签名一致才入库
+/// - 收录记录按树大小存档，经 `/roots/rekor` 公开
+#[derive(Clone)]
+pub struct RekorPublisher {
+    log: Arc<RekorLog>,
+    interval: Duration,
+}
+
+impl RekorPublisher {
+    pub fn new(interval: Duration, log: RekorLog) -> Self {
+        Self { log: Arc::new(log), interval }
+    }
+
+    /// 对所有租户执行一次提交
+    pub async fn publish_once(&self, tenants: &[Arc<TenantContext>]) {
+        for tenant in tenants {
+            if let Err(e) = self.publish_tenant(tenant).await {
+                warn!("⚠️  租户 '{}' 树头写入 Rekor '{}' 失败 (下次重试): {}", tenant.id, self.log.url, e);
+            }
+        }
+    }
+
+    async fn publish_tenant(&self, tenant: &TenantContext) -> anyhow::Result<()> {
+        let snapshot = tenant.reader.snapshot();
+        let Some(head) = snapshot.tree_heads()?.latest()? else {
+            return Ok(());
+        };
+        let archive = snapshot.rekor_entries()?;
+        if archive.latest()?
+            .is_some_and(|latest| latest.signed_tree_head.tree_head.tree_size >= head.tree_head.tree_size)
+        {
+            return Ok(());
+        }
+        let entry = self.log.submit(&head).await?;
+        archive.append(&entry)?;
+        info!(
+            "📜 租户 '{}' 树头已写入 Rekor (size={}, log_index={})",
+            tenant.id, head.tree_head.tree_size, entry.log_index
+        );
+        Ok(())
+    }
+
+    /// 启动后台周期提交任务 (启动时先执行一次)
+    pub fn spawn_periodic(self, tenants: Vec<Arc<TenantContext>>) {
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(self.interval);
+            loop {
+                ticker.tick().await;
+                self.publish_once(&tenants).await;
+            }
+        });
+    }
+}
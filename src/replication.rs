@@ -0,0 +1,501 @@
+use ckb_merkle_mountain_range::{leaf_index_to_mmr_size, leaf_index_to_pos};
+use log::{error, info, warn};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::sync::Notify;
+
+use crate::codec::{StorageCodec, StorageFormat};
+use crate::config::ReplicaConfig;
+use crate::failover::WriteFence;
+use crate::mmr_store::{leaf_count, ErasureRecord, StoreSnapshot, WalRecord};
+use crate::tenant::{hash_api_key, TenantContext};
+
+/// 单次复制拉取的叶子数上限
+pub const MAX_REPLICATION_BATCH: usize = 1000;
+
+/// 复制长轮询的等待上限 (毫秒)，须小于副本请求的超时
+pub const MAX_LONG_POLL_MS: u64 = 10_000;
+
+/// 复制的一片叶子
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReplicatedLeaf {
+    pub seq: u64,
+    pub pos: u64,
+    pub leaf_hash: String,
+    /// 追加该叶子后的树大小与根，副本重放后必须一致
+    pub tree_size: u64,
+    pub root: String,
+    /// 叶子原文 (Hex)；已清理或擦除时为 null
+    pub evidence: Option<String>,
+    pub salt: Option<String>,
+    /// 盲化叶子：主库只有叶子哈希，原文与盐值都为 null
+    #[serde(default)]
+    pub blinded: bool,
+    /// 签名材料的存储编码 (Hex)，主库与副本须使用同一存储格式
+    pub attestation: Option<String>,
+    /// 主库清理原文的时间 (Unix 秒)
+    pub pruned_at: Option<i64>,
+}
+
+/// 复制批次 (`GET /replication/leaves` 的响应)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReplicationBatch {
+    pub storage_format: u8,
+    /// 主库当前的树大小
+    pub primary_size: u64,
+    pub leaves: Vec<ReplicatedLeaf>,
+    /// 主库的全部擦除记录 (擦除可能发生在叶子复制之后，数量很少，每批都带上)
+    pub erasures: Vec<ErasureRecord>,
+}
+
+impl ReplicationBatch {
+    /// 还原为可由 [`EvidenceStore::apply_replicated`](crate::mmr_store::EvidenceStore::apply_replicated) 重做的记录
+    pub fn records(&self) -> anyhow::Result<Vec<(WalRecord, Option<i64>)>> {
+        if self.storage_format != StorageFormat::CURRENT.tag() {
+            return Err(anyhow::anyhow!(
+                "Primary uses storage format {}, this replica uses {}",
+                self.storage_format,
+                StorageFormat::CURRENT.tag()
+            ));
+        }
+        self.leaves
+            .iter()
+            .map(|leaf| {
+                let record = WalRecord {
+                    seq: leaf.seq,
+                    pos: leaf.pos,
+                    leaf_hash: decode_hash(&leaf.leaf_hash)?,
+                    // 新叶子总是落在追加前的树尾
+                    prev_size: leaf.pos,
+                    new_size: leaf.tree_size,
+                    new_root: decode_hash(&leaf.root)?,
+                    nonce: None,
+                    evidence_bytes: leaf.evidence.as_deref().map(hex::decode).transpose()?.unwrap_or_default(),
+                    attestation: leaf
+                        .attestation
+                        .as_deref()
+                        .map(|value| StorageCodec::decode(&hex::decode(value)?))
+                        .transpose()?,
+                    salt: leaf.salt.as_deref().map(decode_hash).transpose()?,
+                    blinded: leaf.blinded,
+                };
+                Ok((record, leaf.pruned_at))
+            })
+            .collect()
+    }
+}
+
+/// 模块：只读副本 (Read-Only Replica)
+///
+/// **为什么需要**: 审计、取证、验证流量远大于存证流量，但 MMR 只能有一个写者，
+/// 多个进程同时追加同一棵树就会分叉。副本从主库逐叶拉取已提交的叶子，在本地重放并核对每一步的根，
+/// 对外只提供读接口，`/prove` 等写接口一律拒绝。
+///
+/// 主库侧由 [`export`] 按叶子顺序导出 (管理员接口 `GET /replication/leaves`)，
+/// 副本侧由 [`ReplicaSync`] 周期拉取，经写线程 `apply_replicated` 落盘。
+/// 重放出的根与主库不一致时停止同步该租户，已复制的数据照常对外提供。
+///
+/// 不复制的状态：nonce 索引 (只用于 `/prove` 的重放检测)、签名审计日志、诉讼保全。
+/// 保留期清理按副本自身的配置执行。
+pub fn export(snapshot: &StoreSnapshot, from: u64, limit: usize) -> anyhow::Result<ReplicationBatch> {
+    let primary_size = snapshot.mmr_size();
+    let first = leaf_count(from);
+    let last = leaf_count(primary_size).min(first + limit as u64);
+
+    let mut leaves = Vec::new();
+    for leaf_index in first..last {
+        let pos = leaf_index_to_pos(leaf_index);
+        let tree_size = leaf_index_to_mmr_size(leaf_index);
+        let leaf = snapshot
+            .get_leaf(pos)?
+            .ok_or_else(|| anyhow::anyhow!("Leaf at pos {} is missing", pos))?;
+        leaves.push(ReplicatedLeaf {
+            // 序列号与叶子序号一致 (见 `EvidenceStore::open`)
+            seq: leaf_index,
+            pos,
+            leaf_hash: hex::encode(leaf),
+            tree_size,
+            root: hex::encode(snapshot.root_at(tree_size)?),
+            evidence: snapshot.evidence_bytes(pos)?.map(hex::encode),
+            salt: snapshot.get_salt(pos)?.map(hex::encode),
+            blinded: snapshot.is_blinded(pos)?,
+            attestation: snapshot
+                .get_attestation(pos)?
+                .map(|attestation| StorageCodec::encode(&attestation))
+                .transpose()?
+                .map(hex::encode),
+            pruned_at: snapshot.pruned_at(pos)?,
+        });
+    }
+    Ok(ReplicationBatch {
+        storage_format: StorageFormat::CURRENT.tag(),
+        primary_size,
+        leaves,
+        erasures: snapshot.erasures()?,
+    })
+}
+
+/// 副本同步失败的原因
+#[derive(Debug)]
+pub enum SyncError {
+    /// 拉取失败 (网络、鉴权、主库错误)，下次重试
+    Fetch(anyhow::Error),
+    /// 本地重放失败 (分叉、数据不符、存储格式不同)，重试不会好转
+    Apply(anyhow::Error),
+}
+
+impl std::fmt::Display for SyncError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Fetch(e) | Self::Apply(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+/// 副本同步任务
+#[derive(Clone)]
+pub struct ReplicaSync {
+    /// 主库地址，如 `http://primary:3000`
+    pub primary: String,
+    /// 主库的管理员 API Key
+    pub admin_key: String,
+    pub interval: Duration,
+    pub batch_size: usize,
+    /// 确认密钥 (`X-Replica-Key`)，主库据此认出副本并统计多数派确认；None 表示不参与确认
+    pub ack_key: Option<String>,
+    /// 追上主库后的长轮询时长，0 表示按 `interval` 轮询
+    pub long_poll: Duration,
+    /// 热备接管写入 (围栏打开) 后停止复制
+    pub fence: WriteFence,
+}
+
+impl ReplicaSync {
+    /// 同步一个租户直到追上主库，返回新增的叶子数
+    ///
+    /// `api_key` 为该租户在主库上的 API Key (未启用多租户时为 None)。
+    /// 启用长轮询时，追上后的最后一次请求在主库等待新叶子，直到等满 `long_poll` 仍没有新叶子才返回。
+    pub async fn sync_tenant(&self, tenant: &TenantContext, api_key: Option<&str>) -> Result<u64, SyncError> {
+        let mut applied = 0;
+        loop {
+            let url = format!(
+                "{}/v1/replication/leaves?from={}&limit={}&wait_ms={}",
+                self.primary.trim_end_matches('/'),
+                tenant.reader.mmr_size(),
+                self.batch_size,
+                self.long_poll.as_millis()
+            );
+            let mut request = ureq::get(&url).timeout(Duration::from_secs(30)).set("X-Admin-Key", &self.admin_key);
+            if let Some(api_key) = api_key {
+                request = request.set("X-Api-Key", api_key);
+            }
+            if let Some(ack_key) = &self.ack_key {
+                request = request.set("X-Replica-Key", ack_key);
+            }
+            let batch: ReplicationBatch = tokio::task::spawn_blocking(move || match request.call() {
+                Ok(response) => serde_json::from_reader(response.into_reader())
+                    .map_err(|e| anyhow::anyhow!("Unexpected replication response: {}", e)),
+                Err(ureq::Error::Status(code, response)) => Err(anyhow::anyhow!(
+                    "Primary rejected replication request ({}): {}",
+                    code,
+                    response.into_string().unwrap_or_default()
+                )),
+                Err(e) => Err(anyhow::anyhow!("Replication request to '{}' failed: {}", url, e)),
+            })
+            .await
+            .map_err(|e| SyncError::Fetch(e.into()))?
+            .map_err(SyncError::Fetch)?;
+
+            // 长轮询时拉到新叶子后立即再请求一次，下一次请求的 `from` 就是对这批叶子的确认
+            let caught_up = if self.long_poll.is_zero() {
+                batch.leaves.len() < self.batch_size
+            } else {
+                batch.leaves.is_empty()
+            };
+            let records = batch.records().map_err(SyncError::Apply)?;
+            applied += tenant.writer.replicate(records, batch.erasures).await.map_err(SyncError::Apply)?;
+            if caught_up {
+                return Ok(applied);
+            }
+        }
+    }
+
+    /// 启动后台同步任务 (每个租户一个，启动时先同步一次)；某个租户重放失败后停止同步它，本节点接管写入后全部停止
+    pub fn spawn(self, tenants: Vec<(Arc<TenantContext>, Option<String>)>) {
+        let sync = Arc::new(self);
+        for (tenant, api_key) in tenants {
+            let sync = sync.clone();
+            tokio::spawn(async move {
+                loop {
+                    if sync.fence.is_open() {
+                        info!("🛑 [{}] 本节点已持有写租约，停止从主库复制", tenant.id);
+                        return;
+                    }
+                    let result = sync.sync_tenant(&tenant, api_key.as_deref()).await;
+                    match &result {
+                        Ok(0) => {}
+                        Ok(applied) => info!("🔄 [{}] 已从主库复制 {} 条叶子, 当前大小 {}", tenant.id, applied, tenant.reader.mmr_size()),
+                        // 同步途中接管了写入，写线程拒绝复制，下一轮循环开头退出
+                        Err(SyncError::Apply(_)) if sync.fence.is_open() => continue,
+                        Err(SyncError::Apply(e)) => {
+                            error!("🚨 [{}] 副本重放失败，停止同步该租户: {}", tenant.id, e);
+                            return;
+                        }
+                        Err(SyncError::Fetch(e)) => warn!("⚠️  [{}] 复制拉取失败 (下次重试): {}", tenant.id, e),
+                    }
+                    // 长轮询已在主库等过，只有出错时才退避
+                    if sync.long_poll.is_zero() || result.is_err() {
+                        tokio::time::sleep(sync.interval).await;
+                    }
+                }
+            });
+        }
+    }
+}
+
+/// 接管前的追赶 (Peer Catch-Up)
+///
+/// 多数派确认只保证回执已写到 *某些* 副本上。热备抢到写租约后、放开写入前，
+/// 先从其他副本拉取自己缺少的叶子，使新主库包含所有已确认的回执。
+/// 其他副本同样提供 `GET /replication/leaves`，复制时照常逐条核对根，历史不一致时拒绝。
+///
+/// 已确认的回执至少在 `quorum` 个副本上，它们可能全在其他副本中；热备连同自己共 `peers + 1` 个副本，
+/// 只有追赶成功的副本不少于 `peers + 1 - quorum` 个时，才能保证其中至少有一个持有每一张已确认的回执。
+pub struct PeerCatchUp {
+    /// 其他副本的地址
+    pub peers: Vec<String>,
+    /// 拉取参数 (地址换成各个副本，不长轮询)
+    pub sync: ReplicaSync,
+    pub tenants: Vec<(Arc<TenantContext>, Option<String>)>,
+    /// 主库的多数派确认数 (`replication.ack_quorum`)，0 表示异步复制，追赶只尽力而为
+    pub quorum: usize,
+}
+
+impl PeerCatchUp {
+    /// 依次从每个副本追赶每个租户，返回新增的叶子数；连不上或比本节点短的副本跳过
+    ///
+    /// 所有租户都追赶成功的副本才算追上；追上的副本不足以覆盖多数派时报错，调用方不得接管写入。
+    pub async fn run(&self) -> anyhow::Result<u64> {
+        let mut applied = 0;
+        let mut reached = 0;
+        for peer in &self.peers {
+            // 追赶拉取不是对主库的确认，不带确认密钥
+            let sync = ReplicaSync {
+                primary: peer.clone(),
+                long_poll: Duration::ZERO,
+                ack_key: None,
+                ..self.sync.clone()
+            };
+            let mut caught_up = true;
+            for (tenant, api_key) in &self.tenants {
+                match sync.sync_tenant(tenant, api_key.as_deref()).await {
+                    Ok(0) => {}
+                    Ok(count) => {
+                        info!("🔄 [{}] 接管前从副本 {} 补齐 {} 条叶子", tenant.id, peer, count);
+                        applied += count;
+                    }
+                    Err(e) => {
+                        warn!("⚠️  [{}] 接管前从副本 {} 追赶失败 (跳过): {}", tenant.id, peer, e);
+                        caught_up = false;
+                    }
+                }
+            }
+            reached += usize::from(caught_up);
+        }
+        let required = self.required_peers();
+        if reached < required {
+            return Err(anyhow::anyhow!(
+                "Caught up with {} of {} peers, but {} are needed to cover ack_quorum {}; acknowledged receipts may be missing",
+                reached,
+                self.peers.len(),
+                required,
+                self.quorum
+            ));
+        }
+        Ok(applied)
+    }
+
+    /// 须追上的副本数
+    fn required_peers(&self) -> usize {
+        if self.quorum == 0 {
+            0
+        } else {
+            (self.peers.len() + 1).saturating_sub(self.quorum)
+        }
+    }
+}
+
+/// 多数派确认 (Replica Acknowledgements)
+///
+/// **为什么需要**: 异步复制下，主库在回执返回后、副本拉取前宕机，已签发的回执就只存在于坏掉的那台机器上。
+/// 副本每次拉取时的 `from` 即它已落盘的树大小，主库按 `X-Replica-Key` 认出副本 (只认 `replication.replicas` 中的密钥)，
+/// 记下各副本确认到的大小；持有管理员密钥但没有确认密钥的请求只能拉取，不能冒充副本凑数。
+/// 配置了 `replication.ack_quorum` 时，`/prove` 在返回回执前等待足够多的副本确认包含该叶子的树大小。
+/// 三节点部署 (一主两副本) 取 1，即连同主库在内的多数派。
+pub struct AckTracker {
+    quorum: usize,
+    timeout: Duration,
+    /// 确认密钥的哈希 -> 副本 ID
+    replicas: HashMap<[u8; 32], String>,
+    /// (租户, 副本 ID) -> 已确认的树大小
+    acks: Mutex<HashMap<(String, String), u64>>,
+    notify: Notify,
+}
+
+impl AckTracker {
+    pub fn new(quorum: usize, timeout: Duration, replicas: &[ReplicaConfig]) -> Self {
+        Self {
+            quorum,
+            timeout,
+            replicas: replicas.iter().map(|replica| (hash_api_key(&replica.ack_key), replica.id.clone())).collect(),
+            acks: Mutex::new(HashMap::new()),
+            notify: Notify::new(),
+        }
+    }
+
+    pub fn quorum(&self) -> usize {
+        self.quorum
+    }
+
+    pub fn timeout(&self) -> Duration {
+        self.timeout
+    }
+
+    /// 按确认密钥解析副本 ID
+    pub fn resolve(&self, ack_key: &str) -> Option<&str> {
+        self.replicas.get(&hash_api_key(ack_key)).map(String::as_str)
+    }
+
+    /// 记录副本的确认 (只增不减)；`replica` 须是 [`AckTracker::resolve`] 认出的副本 ID
+    pub fn record(&self, tenant: &str, replica: &str, size: u64) {
+        let mut acks = self.acks.lock().unwrap();
+        let acked = acks.entry((tenant.to_string(), replica.to_string())).or_default();
+        if size > *acked {
+            *acked = size;
+            self.notify.notify_waiters();
+        }
+    }
+
+    /// 已确认至少 `size` 的副本数
+    pub fn confirmed(&self, tenant: &str, size: u64) -> usize {
+        let acks = self.acks.lock().unwrap();
+        acks.iter().filter(|((t, _), acked)| t == tenant && **acked >= size).count()
+    }
+
+    /// 等待多数派确认 `size`；超时返回已确认的副本数
+    pub async fn wait_for(&self, tenant: &str, size: u64) -> Result<(), usize> {
+        let deadline = tokio::time::Instant::now() + self.timeout;
+        loop {
+            let notified = self.notify.notified();
+            tokio::pin!(notified);
+            notified.as_mut().enable();
+            if self.confirmed(tenant, size) >= self.quorum {
+                return Ok(());
+            }
+            if tokio::time::timeout_at(deadline, notified).await.is_err() {
+                return Err(self.confirmed(tenant, size));
+            }
+        }
+    }
+}
+
+fn decode_hash(value: &str) -> anyhow::Result<[u8; 32]> {
+    hex::decode(value)?
+        .try_into()
+        .map_err(|_| anyhow::anyhow!("Expected 32 bytes, got '{}'", value))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn replicas() -> Vec<ReplicaConfig> {
+        ["r1", "r2"]
+            .iter()
+            .map(|id| ReplicaConfig { id: id.to_string(), ack_key: format!("{}-ack-key", id) })
+            .collect()
+    }
+
+    fn catch_up(peers: usize, quorum: usize) -> PeerCatchUp {
+        PeerCatchUp {
+            peers: (0..peers).map(|i| format!("http://127.0.0.1:{}", 1 + i)).collect(),
+            sync: ReplicaSync {
+                primary: String::new(),
+                admin_key: String::new(),
+                interval: Duration::from_millis(10),
+                batch_size: 10,
+                ack_key: None,
+                long_poll: Duration::ZERO,
+                fence: WriteFence::closed(),
+            },
+            tenants: Vec::new(),
+            quorum,
+        }
+    }
+
+    #[test]
+    fn only_configured_ack_keys_identify_replicas() {
+        let acks = AckTracker::new(1, Duration::from_millis(50), &replicas());
+        assert_eq!(acks.resolve("r1-ack-key"), Some("r1"));
+        assert_eq!(acks.resolve("r2-ack-key"), Some("r2"));
+        assert_eq!(acks.resolve("admin-key"), None);
+    }
+
+    #[test]
+    fn acknowledgements_only_grow_and_stay_per_tenant() {
+        let acks = AckTracker::new(2, Duration::from_millis(50), &replicas());
+        acks.record("default", "r1", 10);
+        acks.record("default", "r1", 4);
+        acks.record("default", "r2", 7);
+        acks.record("other", "r2", 100);
+
+        assert_eq!(acks.confirmed("default", 7), 2);
+        assert_eq!(acks.confirmed("default", 10), 1);
+        assert_eq!(acks.confirmed("default", 11), 0);
+        assert_eq!(acks.confirmed("other", 100), 1);
+    }
+
+    #[tokio::test]
+    async fn wait_returns_once_a_quorum_confirms_the_size() {
+        let acks = Arc::new(AckTracker::new(2, Duration::from_secs(5), &replicas()));
+        acks.record("default", "r1", 3);
+
+        let waiter = tokio::spawn({
+            let acks = acks.clone();
+            async move { acks.wait_for("default", 3).await }
+        });
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        // 其他租户的确认不算数
+        acks.record("other", "r2", 3);
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        assert!(!waiter.is_finished());
+
+        acks.record("default", "r2", 4);
+        assert_eq!(waiter.await.unwrap(), Ok(()));
+    }
+
+    #[tokio::test]
+    async fn wait_times_out_with_the_confirmed_count() {
+        let acks = AckTracker::new(2, Duration::from_millis(50), &replicas());
+        acks.record("default", "r1", 3);
+        acks.record("default", "r2", 2);
+        assert_eq!(acks.wait_for("default", 3).await, Err(1));
+    }
+
+    #[test]
+    fn takeover_must_reach_enough_peers_to_cover_the_quorum() {
+        // 一主两副本、确认 1 个：热备连同自己共 3 个副本，须追上另外 2 个才能覆盖每张已确认回执
+        assert_eq!(catch_up(2, 1).required_peers(), 2);
+        assert_eq!(catch_up(2, 2).required_peers(), 1);
+        assert_eq!(catch_up(4, 2).required_peers(), 3);
+        // 异步复制只尽力而为
+        assert_eq!(catch_up(2, 0).required_peers(), 0);
+    }
+
+    #[test]
+    fn batches_from_another_storage_format_are_rejected() {
+        let batch = ReplicationBatch { storage_format: StorageFormat::LegacyBcs.tag(), primary_size: 0, leaves: Vec::new(), erasures: Vec::new() };
+        assert!(batch.records().is_err());
+    }
+}
@@ -0,0 +1,347 @@
+use serde::Deserialize;
+
+use crate::qr::{self, QrFormat};
+
+/// 报告输出格式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ReportFormat {
+    #[default]
+    Html,
+    Pdf,
+}
+
+impl ReportFormat {
+    pub fn content_type(self) -> &'static str {
+        match self {
+            Self::Html => "text/html; charset=utf-8",
+            Self::Pdf => "application/pdf",
+        }
+    }
+}
+
+/// 报告中的一节：若干 “名称 - 值” 行
+#[derive(Debug, Clone)]
+pub struct ReportSection {
+    pub title: String,
+    pub rows: Vec<(String, String)>,
+}
+
+impl ReportSection {
+    pub fn new(title: &str) -> Self {
+        Self { title: title.to_string(), rows: Vec::new() }
+    }
+
+    pub fn row(mut self, label: &str, value: impl Into<String>) -> Self {
+        self.rows.push((label.to_string(), value.into()));
+        self
+    }
+}
+
+/// 模块：鉴定报告 (Forensic Audit Report)
+///
+/// **职责**: 把一条证据的指纹、结论、签名与包含性证明整理成可打印的文档，
+/// 输出 HTML 或 PDF，末尾附验证步骤与回执二维码。内容由调用方 (`GET /report/{pos}`) 组装，本模块只负责排版。
+///
+/// **为什么需要**: 鉴定结论最终要交给法官、编辑等不读 JSON 的人，且常以纸质形式归档。
+///
+/// 报告正文使用英文：PDF 只用内置的 Helvetica 字体 (无需嵌入字体文件)，它仅覆盖 Latin-1，
+/// 超出范围的字符在 PDF 中以 `?` 代替；HTML 无此限制。
+#[derive(Debug, Clone)]
+pub struct Report {
+    pub title: String,
+    pub subtitle: String,
+    pub sections: Vec<ReportSection>,
+    /// 验证步骤 (按顺序编号输出)
+    pub instructions: Vec<String>,
+    /// 二维码内容 (回执指针 URI)
+    pub qr_payload: Option<String>,
+}
+
+impl Report {
+    pub fn render(&self, format: ReportFormat) -> anyhow::Result<Vec<u8>> {
+        match format {
+            ReportFormat::Html => Ok(self.to_html()?.into_bytes()),
+            ReportFormat::Pdf => self.to_pdf(),
+        }
+    }
+
+    /// 单文件 HTML (样式内联、二维码为内嵌 SVG)，可直接用浏览器打印
+    pub fn to_html(&self) -> anyhow::Result<String> {
+        let mut html = String::new();
+        html.push_str("<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n");
+        html.push_str(&format!("<title>{}</title>\n", escape_html(&self.title)));
+        html.push_str(HTML_STYLE);
+        html.push_str("</head>\n<body>\n");
+        html.push_str(&format!("<h1>{}</h1>\n<p class=\"subtitle\">{}</p>\n", escape_html(&self.title), escape_html(&self.subtitle)));
+
+        for section in &self.sections {
+            html.push_str(&format!("<h2>{}</h2>\n<table>\n", escape_html(&section.title)));
+            for (label, value) in &section.rows {
+                html.push_str(&format!("<tr><th>{}</th><td>{}</td></tr>\n", escape_html(label), escape_html(value)));
+            }
+            html.push_str("</table>\n");
+        }
+
+        if !self.instructions.is_empty() {
+            html.push_str("<h2>How to verify this report</h2>\n<ol>\n");
+            for step in &self.instructions {
+                html.push_str(&format!("<li>{}</li>\n", escape_html(step)));
+            }
+            html.push_str("</ol>\n");
+        }
+
+        if let Some(payload) = &self.qr_payload {
+            let svg = String::from_utf8(qr::render(payload, QrFormat::Svg)?)?;
+            // 去掉 XML 声明，直接内嵌 <svg>
+            let svg = svg.find("<svg").map_or(svg.as_str(), |start| &svg[start..]);
+            html.push_str(&format!(
+                "<h2>Receipt pointer</h2>\n<div class=\"qr\">{}</div>\n<p class=\"pointer\">{}</p>\n",
+                svg,
+                escape_html(payload)
+            ));
+        }
+
+        html.push_str("</body>\n</html>\n");
+        Ok(html)
+    }
+
+    /// 最小 PDF 1.4 文档：A4 纵向，内置 Helvetica 字体，二维码以矩形填充绘制
+    pub fn to_pdf(&self) -> anyhow::Result<Vec<u8>> {
+        let mut pdf = PdfPages::default();
+        pdf.text(PDF_MARGIN, "F2", 16.0, &self.title);
+        pdf.advance(8.0);
+        pdf.text(PDF_MARGIN, "F1", 10.0, &self.subtitle);
+        pdf.advance(10.0);
+
+        for section in &self.sections {
+            pdf.ensure_space(PDF_LINE * 3.0);
+            pdf.advance(6.0);
+            pdf.text(PDF_MARGIN, "F2", 11.0, &section.title);
+            pdf.advance(4.0);
+            for (label, value) in &section.rows {
+                let lines = wrap(value, PDF_VALUE_CHARS);
+                pdf.ensure_space(PDF_LINE * lines.len().min(4) as f32);
+                pdf.text_no_advance(PDF_MARGIN, "F2", 9.0, label);
+                for line in &lines {
+                    pdf.text(PDF_VALUE_X, "F1", 9.0, line);
+                }
+            }
+        }
+
+        if !self.instructions.is_empty() {
+            pdf.ensure_space(PDF_LINE * 4.0);
+            pdf.advance(6.0);
+            pdf.text(PDF_MARGIN, "F2", 11.0, "How to verify this report");
+            pdf.advance(4.0);
+            for (index, step) in self.instructions.iter().enumerate() {
+                let numbered = format!("{}. {}", index + 1, step);
+                for (line_no, line) in wrap(&numbered, PDF_FULL_CHARS).iter().enumerate() {
+                    let x = if line_no == 0 { PDF_MARGIN } else { PDF_MARGIN + 12.0 };
+                    pdf.text(x, "F1", 9.0, line);
+                }
+            }
+        }
+
+        if let Some(payload) = &self.qr_payload {
+            let (width, dark) = qr::modules(payload)?;
+            let side = width as f32 * PDF_QR_MODULE;
+            pdf.ensure_space(side + PDF_LINE * 3.0);
+            pdf.advance(6.0);
+            pdf.text(PDF_MARGIN, "F2", 11.0, "Receipt pointer");
+            pdf.advance(6.0);
+            pdf.qr(width, &dark);
+            pdf.advance(6.0);
+            for line in wrap(payload, PDF_FULL_CHARS) {
+                pdf.text(PDF_MARGIN, "F1", 7.0, &line);
+            }
+        }
+
+        Ok(pdf.finish())
+    }
+}
+
+const HTML_STYLE: &str = "<style>\n\
+body { font-family: Helvetica, Arial, sans-serif; max-width: 900px; margin: 2em auto; color: #222; }\n\
+h1 { margin-bottom: 0; }\n\
+.subtitle { color: #666; margin-top: 0.3em; }\n\
+h2 { border-bottom: 1px solid #ccc; padding-bottom: 0.2em; margin-top: 1.6em; font-size: 1.1em; }\n\
+table { border-collapse: collapse; width: 100%; }\n\
+th { text-align: left; vertical-align: top; width: 14em; padding: 0.25em 0.5em 0.25em 0; }\n\
+td { font-family: Menlo, Consolas, monospace; font-size: 0.9em; word-break: break-all; padding: 0.25em 0; }\n\
+.qr svg { width: 200px; height: 200px; }\n\
+.pointer { font-family: Menlo, Consolas, monospace; font-size: 0.75em; word-break: break-all; color: #555; }\n\
+</style>\n";
+
+fn escape_html(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// 按词折行；超过一行宽度的长词 (如 Hex 哈希) 硬切
+fn wrap(text: &str, width: usize) -> Vec<String> {
+    let mut lines = Vec::new();
+    let mut current = String::new();
+    for word in text.split_whitespace() {
+        let mut word: Vec<char> = word.chars().collect();
+        while word.len() > width {
+            if !current.is_empty() {
+                lines.push(std::mem::take(&mut current));
+            }
+            lines.push(word.drain(..width).collect());
+        }
+        let word: String = word.into_iter().collect();
+        if word.is_empty() {
+            continue;
+        }
+        if !current.is_empty() && current.chars().count() + 1 + word.chars().count() > width {
+            lines.push(std::mem::take(&mut current));
+        }
+        if !current.is_empty() {
+            current.push(' ');
+        }
+        current.push_str(&word);
+    }
+    if !current.is_empty() || lines.is_empty() {
+        lines.push(current);
+    }
+    lines
+}
+
+// ==========================================
+// PDF 输出
+// ==========================================
+
+const PDF_PAGE_WIDTH: f32 = 595.0;
+const PDF_PAGE_HEIGHT: f32 = 842.0;
+const PDF_MARGIN: f32 = 50.0;
+const PDF_LINE: f32 = 12.0;
+const PDF_VALUE_X: f32 = 200.0;
+/// 值列每行字符数 (9pt Helvetica 下约 345pt 宽)
+const PDF_VALUE_CHARS: usize = 64;
+/// 通栏每行字符数
+const PDF_FULL_CHARS: usize = 95;
+/// 二维码每个模块的边长 (pt)
+const PDF_QR_MODULE: f32 = 2.5;
+
+/// 逐页累积内容流；每页自上而下排版
+struct PdfPages {
+    pages: Vec<String>,
+    y: f32,
+}
+
+impl Default for PdfPages {
+    fn default() -> Self {
+        Self { pages: vec![String::new()], y: PDF_PAGE_HEIGHT - PDF_MARGIN }
+    }
+}
+
+impl PdfPages {
+    fn current(&mut self) -> &mut String {
+        self.pages.last_mut().expect("at least one page")
+    }
+
+    fn advance(&mut self, dy: f32) {
+        self.y -= dy;
+    }
+
+    /// 剩余高度不足时换页
+    fn ensure_space(&mut self, height: f32) {
+        if self.y - height < PDF_MARGIN {
+            self.pages.push(String::new());
+            self.y = PDF_PAGE_HEIGHT - PDF_MARGIN;
+        }
+    }
+
+    fn text(&mut self, x: f32, font: &str, size: f32, text: &str) {
+        self.ensure_space(PDF_LINE);
+        self.text_no_advance(x, font, size, text);
+        self.y -= PDF_LINE.max(size + 3.0);
+    }
+
+    fn text_no_advance(&mut self, x: f32, font: &str, size: f32, text: &str) {
+        let y = self.y - size;
+        let line = format!("BT /{} {} Tf {:.2} {:.2} Td ({}) Tj ET\n", font, size, x, y, escape_pdf(text));
+        self.current().push_str(&line);
+    }
+
+    fn qr(&mut self, width: usize, dark: &[bool]) {
+        let top = self.y;
+        let mut ops = String::from("0 g\n");
+        for (index, _) in dark.iter().enumerate().filter(|(_, is_dark)| **is_dark) {
+            let (row, col) = (index / width, index % width);
+            let x = PDF_MARGIN + col as f32 * PDF_QR_MODULE;
+            let y = top - (row + 1) as f32 * PDF_QR_MODULE;
+            ops.push_str(&format!("{:.2} {:.2} {:.2} {:.2} re\n", x, y, PDF_QR_MODULE, PDF_QR_MODULE));
+        }
+        ops.push_str("f\n");
+        self.current().push_str(&ops);
+        self.y -= width as f32 * PDF_QR_MODULE;
+    }
+
+    /// 写出完整文件：目录、页树、两个内置字体、每页一个页面对象与内容流，最后是交叉引用表
+    fn finish(self) -> Vec<u8> {
+        let page_count = self.pages.len();
+        let page_id = |index: usize| 5 + index * 2;
+        let kids: Vec<String> = (0..page_count).map(|index| format!("{} 0 R", page_id(index))).collect();
+
+        let mut objects = vec![
+            "<< /Type /Catalog /Pages 2 0 R >>".to_string(),
+            format!("<< /Type /Pages /Kids [{}] /Count {} >>", kids.join(" "), page_count),
+            "<< /Type /Font /Subtype /Type1 /BaseFont /Helvetica /Encoding /WinAnsiEncoding >>".to_string(),
+            "<< /Type /Font /Subtype /Type1 /BaseFont /Helvetica-Bold /Encoding /WinAnsiEncoding >>".to_string(),
+        ];
+        for (index, content) in self.pages.iter().enumerate() {
+            objects.push(format!(
+                "<< /Type /Page /Parent 2 0 R /MediaBox [0 0 {} {}] /Resources << /Font << /F1 3 0 R /F2 4 0 R >> >> /Contents {} 0 R >>",
+                PDF_PAGE_WIDTH,
+                PDF_PAGE_HEIGHT,
+                page_id(index) + 1
+            ));
+            objects.push(format!("<< /Length {} >>\nstream\n{}endstream", content.len(), content));
+        }
+
+        let mut out = b"%PDF-1.4\n%\xE2\xE3\xCF\xD3\n".to_vec();
+        let mut offsets = Vec::with_capacity(objects.len());
+        for (index, object) in objects.iter().enumerate() {
+            offsets.push(out.len());
+            out.extend_from_slice(format!("{} 0 obj\n{}\nendobj\n", index + 1, object).as_bytes());
+        }
+        let xref = out.len();
+        out.extend_from_slice(format!("xref\n0 {}\n0000000000 65535 f \n", objects.len() + 1).as_bytes());
+        for offset in offsets {
+            out.extend_from_slice(format!("{:010} 00000 n \n", offset).as_bytes());
+        }
+        out.extend_from_slice(
+            format!("trailer\n<< /Size {} /Root 1 0 R >>\nstartxref\n{}\n%%EOF\n", objects.len() + 1, xref).as_bytes(),
+        );
+        out
+    }
+}
+
+/// PDF 字符串转义；内容流按 WinAnsi 单字节写出，Latin-1 以外的字符替换为 `?`
+fn escape_pdf(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '(' | ')' | '\\' => {
+                escaped.push('\\');
+                escaped.push(c);
+            }
+            ' '..='~' => escaped.push(c),
+            '\u{a0}'..='\u{ff}' => escaped.push_str(&format!("\\{:03o}", c as u32)),
+            _ => escaped.push('?'),
+        }
+    }
+    escaped
+}
@@ -0,0 +1,67 @@
+use chrono::{Months, Utc};
+use log::{info, warn};
+use std::sync::Arc;
+use std::time::Duration;
+
+use crate::ops_log::{OpsEvent, OpsLog};
+use crate::tenant::TenantContext;
+
+/// 模块：保留策略 (Retention Policy)
+///
+/// **为什么需要**: 证据原文 (含图片指纹、推理参数) 不能无限期保存，但已签发的回执必须永远可验证。
+/// MMR 的包含性证明只依赖节点哈希，与叶子原文无关，因此到期后只删除原文，
+/// 叶子哈希、MMR 节点、回执与签名根全部保留 (见 `EvidenceStore::prune_expired`)。
+///
+/// `years` 为 0 时关闭清理。
+#[derive(Clone)]
+pub struct RetentionPolicy {
+    pub years: u32,
+    pub check_interval: Duration,
+    /// 清理结果记入运维日志 (可选)
+    pub ops_log: Option<OpsLog>,
+}
+
+impl RetentionPolicy {
+    /// 当前时刻对应的清理截止时间 (Unix 秒)：签发早于它的证据原文可以删除
+    pub fn cutoff(&self) -> Option<i64> {
+        if self.years == 0 {
+            return None;
+        }
+        Utc::now()
+            .checked_sub_months(Months::new(self.years.saturating_mul(12)))
+            .map(|cutoff| cutoff.timestamp())
+    }
+
+    /// 对所有租户执行一次清理
+    pub async fn prune_once(&self, tenants: &[Arc<TenantContext>]) {
+        let Some(cutoff) = self.cutoff() else {
+            return;
+        };
+        for tenant in tenants {
+            match tenant.writer.prune(cutoff).await {
+                Ok(0) => {}
+                Ok(pruned) => {
+                    info!("🗑️  租户 '{}' 清理过期证据原文 {} 条 (叶子哈希与证明保留)", tenant.id, pruned);
+                    if let Some(ops_log) = &self.ops_log {
+                        ops_log.note(OpsEvent::Pruned { tenant_id: tenant.id.clone(), count: pruned, cutoff });
+                    }
+                }
+                Err(e) => warn!("⚠️  租户 '{}' 保留期清理失败 (下次重试): {}", tenant.id, e),
+            }
+        }
+    }
+
+    /// 启动后台周期清理任务 (启动时先执行一次)
+    pub fn spawn_periodic(self, tenants: Vec<Arc<TenantContext>>) {
+        if self.years == 0 {
+            return;
+        }
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(self.check_interval);
+            loop {
+                ticker.tick().await;
+                self.prune_once(&tenants).await;
+            }
+        });
+    }
+}
@@ -0,0 +1,160 @@
+use ed25519_dalek::{Signature, VerifyingKey};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+use crate::config::{parse_public_key, ExaminerConfig};
+use crate::mmr_store::SledStore;
+use crate::signer::EvidenceSigner;
+
+/// 复核意见签名的域分隔前缀，避免与证据 / 回执 / 树头签名混用
+const ASSESSMENT_DOMAIN: &[u8] = b"yuanjing-core examiner review v1";
+
+/// 复核记录叶子的域分隔前缀，使其原文不可能被当作证据解码
+const LEAF_DOMAIN: &[u8] = b"yuanjing-core examiner review record v1";
+
+/// 复核结论
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ReviewOpinion {
+    /// 同意模型的判定
+    Agree,
+    /// 不同意模型的判定
+    Disagree,
+}
+
+/// 鉴定人签署的复核意见
+///
+/// 鉴定人用自己的密钥对 `"yuanjing-core examiner review v1" || BCS(ReviewAssessment)` 签名。
+/// `evidence_hash` 即回执中的叶子哈希，把意见绑定到那一条证据的确切内容上。
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ReviewAssessment {
+    pub tenant_id: String,
+    /// 被复核证据的位置
+    pub pos: u64,
+    /// 被复核证据的叶子哈希 (Hex)
+    pub evidence_hash: String,
+    pub opinion: ReviewOpinion,
+    pub notes: String,
+    /// 鉴定人名称 (见 `[[api.examiners]]`)
+    pub examiner: String,
+    /// 鉴定人签署的时间 (Unix 秒)
+    pub assessed_at: i64,
+}
+
+impl ReviewAssessment {
+    /// 规范化字节 (域前缀 + BCS)，签名与验签均基于此
+    pub fn canonical_bytes(&self) -> anyhow::Result<Vec<u8>> {
+        let mut bytes = ASSESSMENT_DOMAIN.to_vec();
+        bytes.extend_from_slice(&bcs::to_bytes(self)?);
+        Ok(bytes)
+    }
+
+    /// 鉴定人一侧签名，返回 Hex 签名
+    pub fn sign(&self, examiner: &EvidenceSigner) -> anyhow::Result<String> {
+        Ok(hex::encode(examiner.sign_bytes(&self.canonical_bytes()?)?.to_bytes()))
+    }
+}
+
+/// 复核记录：鉴定人签署的意见与服务端收录信息，整体作为一片叶子追加进 MMR
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ReviewRecord {
+    pub assessment: ReviewAssessment,
+    /// 鉴定人公钥 (Hex)
+    pub examiner_public_key: String,
+    /// 鉴定人签名 (Hex)
+    pub signature: String,
+    /// 提交者 (租户 API Key 指纹)
+    pub principal: String,
+    /// 服务端收录时间 (Unix 秒)
+    pub recorded_at: i64,
+}
+
+impl ReviewRecord {
+    /// 校验鉴定人签名；`trusted_key` 为事先固定的鉴定人公钥，与记录自带的公钥不符时直接判为无效
+    pub fn verify(&self, trusted_key: &VerifyingKey) -> anyhow::Result<bool> {
+        if hex::encode(trusted_key.to_bytes()) != self.examiner_public_key {
+            return Ok(false);
+        }
+        let signature = Signature::from_slice(&hex::decode(&self.signature)?)?;
+        Ok(EvidenceSigner::verify_bytes(trusted_key, &self.assessment.canonical_bytes()?, &signature))
+    }
+
+    /// 叶子原文 (域前缀 + BCS)，叶子哈希为其 Blake3
+    pub fn canonical_bytes(&self) -> anyhow::Result<Vec<u8>> {
+        let mut bytes = LEAF_DOMAIN.to_vec();
+        bytes.extend_from_slice(&bcs::to_bytes(self)?);
+        Ok(bytes)
+    }
+
+    /// 叶子哈希
+    pub fn leaf_hash(&self) -> anyhow::Result<[u8; 32]> {
+        Ok(*blake3::hash(&self.canonical_bytes()?).as_bytes())
+    }
+
+    /// 从叶子原文还原；不是复核记录时返回 None
+    pub fn from_bytes(bytes: &[u8]) -> anyhow::Result<Option<Self>> {
+        bytes
+            .strip_prefix(LEAF_DOMAIN)
+            .map(|payload| Ok(bcs::from_bytes(payload)?))
+            .transpose()
+    }
+}
+
+/// 已登记的鉴定人公钥 (按名称)
+#[derive(Default)]
+pub struct Examiners {
+    keys: HashMap<String, VerifyingKey>,
+}
+
+impl Examiners {
+    /// 按 `[[api.examiners]]` 加载；公钥无效时报错
+    pub fn new(configs: &[ExaminerConfig]) -> anyhow::Result<Self> {
+        let keys = configs
+            .iter()
+            .map(|examiner| Ok((examiner.name.clone(), parse_public_key(&examiner.public_key)?)))
+            .collect::<anyhow::Result<_>>()?;
+        Ok(Self { keys })
+    }
+
+    pub fn key_of(&self, name: &str) -> Option<&VerifyingKey> {
+        self.keys.get(name)
+    }
+}
+
+/// 模块：鉴定人复核 (Examiner Review)
+///
+/// **为什么需要**: 模型的判定只是第一意见。进入司法程序的证据通常还要由持证鉴定人复核，
+/// 复核结论 (同意或不同意、附说明) 必须出自鉴定人本人、不能由平台代签，并且与被复核的证据一起留痕。
+///
+/// 鉴定人在自己的设备上签署意见，经租户提交；服务端只收录 `[[api.examiners]]` 中登记过公钥的鉴定人，
+/// 核对签名与证据的叶子哈希后，把复核记录作为一片叶子追加进同一棵 MMR (与保管记录一样不是证据)。
+/// `reviews` 树是按证据查找复核记录的索引 (键为 证据位置 + 序号，值为记录叶子的位置)。
+#[derive(Clone)]
+pub struct ReviewLog {
+    index: sled::Tree,
+}
+
+impl ReviewLog {
+    pub fn open(store: &SledStore) -> anyhow::Result<Self> {
+        Ok(Self { index: store.tree("reviews")? })
+    }
+
+    /// 指定证据的复核记录叶子位置 (按收录先后)
+    pub fn leaves(&self, pos: u64) -> anyhow::Result<Vec<u64>> {
+        self.index
+            .scan_prefix(pos.to_be_bytes())
+            .values()
+            .map(|v| Ok(u64::from_be_bytes(v?.as_ref().try_into()?)))
+            .collect()
+    }
+
+    /// 记下复核记录叶子的位置
+    pub fn record(&self, pos: u64, leaf_pos: u64) -> anyhow::Result<()> {
+        let index = self.index.scan_prefix(pos.to_be_bytes()).count() as u64;
+        let key = [pos.to_be_bytes(), index.to_be_bytes()].concat();
+        self.index.insert(key, &leaf_pos.to_be_bytes())?;
+        self.index.flush()?;
+        Ok(())
+    }
+}
@@ -0,0 +1,193 @@
+use ed25519_dalek::{Signature, Verifier, VerifyingKey};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+#[cfg(feature = "server")]
+use crate::signer::EvidenceSigner;
+
+/// 吊销列表签名的域分隔前缀，避免与证据 / 回执 / 背书签名混用
+const REVOCATION_DOMAIN: &[u8] = b"yuanjing-core revocation list v1";
+
+/// 吊销条目 (Revocation)
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct Revocation {
+    /// 被吊销的公钥 (Hex)，可以是租户根公钥或派生公钥
+    pub public_key: String,
+    /// 生效时间 (Unix 秒)：此刻及之后签发的回执不再可信，之前的仍然有效
+    pub effective_from: i64,
+    pub reason: String,
+}
+
+/// 模块：密钥吊销列表 (Revocation List)
+///
+/// **职责**: 密钥泄露后，声明“从某一时刻起，此密钥的签名不再可信”。
+/// - 列表由主身份签名发布，验证方只需信任主公钥；
+/// - 吊销有生效时间：泄露之前签发的证据依然有效，不必全部作废；
+/// - `version` 单调递增，验证方可拒绝回滚到旧版本的列表。
+///
+/// **为什么按回执时间判定**: 回执时间由被吊销的密钥自己签名，单看签名无法排除回填时间。
+/// 但回执同时绑定了 MMR 位置与根，`/verify` 会核对叶子确实在本服务的日志中，
+/// 伪造的“早期”回执无法通过包含性校验。
+///
+/// 不依赖 `server` 特性：离线验证方 (证据包、C FFI、WASM、客户端) 与 `/verify` 用同一份判定。
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct RevocationList {
+    pub version: u64,
+    /// 发布时间 (Unix 秒)
+    pub issued_at: i64,
+    pub entries: Vec<Revocation>,
+}
+
+impl RevocationList {
+    /// 列表的规范化字节 (域前缀 + BCS)，签名与验签均基于此
+    pub fn canonical_bytes(&self) -> anyhow::Result<Vec<u8>> {
+        let mut bytes = REVOCATION_DOMAIN.to_vec();
+        bytes.extend_from_slice(&bcs::to_bytes(self)?);
+        Ok(bytes)
+    }
+
+    /// 查找某把公钥的吊销条目；同一公钥出现多次时以最早生效的为准
+    pub fn find(&self, public_key: &VerifyingKey) -> Option<&Revocation> {
+        let key_hex = hex::encode(public_key.to_bytes());
+        self.entries
+            .iter()
+            .filter(|entry| entry.public_key.eq_ignore_ascii_case(&key_hex))
+            .min_by_key(|entry| entry.effective_from)
+    }
+
+    /// 该公钥在 `timestamp` 时刻的签名是否已被吊销
+    pub fn is_revoked(&self, public_key: &VerifyingKey, timestamp: i64) -> bool {
+        self.find(public_key).is_some_and(|entry| timestamp >= entry.effective_from)
+    }
+}
+
+/// 带主身份签名的吊销列表，即落盘与对外发布的形式 (JSON)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SignedRevocationList {
+    pub list: RevocationList,
+    /// 签发者公钥 (Hex)，必须等于验证方信任的主公钥
+    pub signer_public_key: String,
+    /// 对 `list` 规范字节的签名 (Hex)
+    pub signature: String,
+}
+
+impl SignedRevocationList {
+    #[cfg(feature = "server")]
+    pub fn sign(list: RevocationList, signer: &EvidenceSigner) -> anyhow::Result<Self> {
+        let signature = signer.sign_bytes(&list.canonical_bytes()?)?;
+        Ok(Self {
+            list,
+            signer_public_key: hex::encode(signer.public_key().to_bytes()),
+            signature: hex::encode(signature.to_bytes()),
+        })
+    }
+
+    /// 校验列表确实由 `trusted` 签发
+    pub fn verify(&self, trusted: &VerifyingKey) -> anyhow::Result<()> {
+        if !self.signer_public_key.eq_ignore_ascii_case(&hex::encode(trusted.to_bytes())) {
+            return Err(anyhow::anyhow!("Revocation list was signed by an untrusted key {}", self.signer_public_key));
+        }
+        let signature = Signature::from_slice(&hex::decode(&self.signature)?)
+            .map_err(|_| anyhow::anyhow!("Invalid revocation list signature encoding"))?;
+        if trusted.verify(&self.list.canonical_bytes()?, &signature).is_ok() {
+            Ok(())
+        } else {
+            Err(anyhow::anyhow!("Revocation list signature is invalid"))
+        }
+    }
+
+    /// 解析 JSON (`GET /revocations` 的响应) 并校验确实由 `trusted` 签发
+    pub fn from_json(bytes: &[u8], trusted: &VerifyingKey) -> anyhow::Result<Self> {
+        let signed: Self = serde_json::from_slice(bytes)?;
+        signed.verify(trusted)?;
+        Ok(signed)
+    }
+
+    pub fn read(path: &Path) -> anyhow::Result<Self> {
+        Ok(serde_json::from_slice(&std::fs::read(path)?)?)
+    }
+
+    pub fn write(&self, path: &Path) -> anyhow::Result<()> {
+        std::fs::write(path, serde_json::to_vec_pretty(self)?)?;
+        Ok(())
+    }
+
+    /// 加载并校验吊销列表；文件不存在时视为空列表
+    pub fn load(path: &Path, trusted: &VerifyingKey) -> anyhow::Result<Option<Self>> {
+        if !path.exists() {
+            return Ok(None);
+        }
+        let signed = Self::read(path)
+            .map_err(|e| anyhow::anyhow!("Cannot read revocation list '{}': {}", path.display(), e))?;
+        signed.verify(trusted)?;
+        Ok(Some(signed))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ed25519_dalek::{Signer, SigningKey};
+
+    fn entry(key: &VerifyingKey, effective_from: i64) -> Revocation {
+        Revocation { public_key: hex::encode(key.to_bytes()), effective_from, reason: "key compromise".to_string() }
+    }
+
+    fn signed(list: RevocationList, master: &SigningKey) -> SignedRevocationList {
+        let signature = master.sign(&list.canonical_bytes().unwrap());
+        SignedRevocationList {
+            list,
+            signer_public_key: hex::encode(master.verifying_key().to_bytes()),
+            signature: hex::encode(signature.to_bytes()),
+        }
+    }
+
+    #[test]
+    fn revocation_applies_from_its_effective_time() {
+        let key = SigningKey::from_bytes(&[3; 32]).verifying_key();
+        let list = RevocationList { version: 1, issued_at: 2_000, entries: vec![entry(&key, 1_000)] };
+
+        assert!(!list.is_revoked(&key, 999));
+        assert!(list.is_revoked(&key, 1_000));
+        assert!(list.is_revoked(&key, 5_000));
+        assert!(!list.is_revoked(&SigningKey::from_bytes(&[4; 32]).verifying_key(), 5_000));
+    }
+
+    #[test]
+    fn the_earliest_of_duplicate_entries_wins() {
+        let key = SigningKey::from_bytes(&[3; 32]).verifying_key();
+        let mut early = entry(&key, 1_000);
+        early.public_key = early.public_key.to_uppercase();
+        let list = RevocationList { version: 2, issued_at: 3_000, entries: vec![entry(&key, 2_000), early] };
+
+        assert_eq!(list.find(&key).map(|entry| entry.effective_from), Some(1_000));
+        assert!(list.is_revoked(&key, 1_500));
+    }
+
+    #[test]
+    fn only_the_master_key_can_publish_a_list() {
+        let master = SigningKey::from_bytes(&[1; 32]);
+        let key = SigningKey::from_bytes(&[3; 32]).verifying_key();
+        let list = signed(RevocationList { version: 1, issued_at: 2_000, entries: vec![entry(&key, 1_000)] }, &master);
+        let json = serde_json::to_vec(&list).unwrap();
+
+        assert!(list.verify(&master.verifying_key()).is_ok());
+        assert!(SignedRevocationList::from_json(&json, &master.verifying_key()).is_ok());
+
+        let impostor = SigningKey::from_bytes(&[2; 32]);
+        assert!(SignedRevocationList::from_json(&json, &impostor.verifying_key()).is_err());
+        let forged = signed(list.list.clone(), &impostor);
+        assert!(forged.verify(&master.verifying_key()).is_err());
+    }
+
+    #[test]
+    fn an_edited_list_no_longer_verifies() {
+        let master = SigningKey::from_bytes(&[1; 32]);
+        let key = SigningKey::from_bytes(&[3; 32]).verifying_key();
+        let mut list = signed(RevocationList { version: 1, issued_at: 2_000, entries: vec![entry(&key, 1_000)] }, &master);
+
+        // 把生效时间往后推，企图让泄露期间签发的回执重新生效
+        list.list.entries[0].effective_from = 9_000;
+        assert!(list.verify(&master.verifying_key()).is_err());
+    }
+}
@@ -0,0 +1,233 @@
+use schemars::JsonSchema;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::{Arc, LazyLock, RwLock};
+
+/// 已生成的 Schema，按类型名缓存 (生成一次即可，之后每个请求只做校验)
+static CACHE: LazyLock<RwLock<HashMap<String, Arc<Value>>>> = LazyLock::new(Default::default);
+
+/// 类型 `T` 的 JSON Schema (Draft 2020-12)
+pub fn schema_for<T: JsonSchema>() -> Arc<Value> {
+    let name = T::schema_name().into_owned();
+    if let Some(schema) = CACHE.read().expect("schema cache poisoned").get(&name) {
+        return schema.clone();
+    }
+    let schema = Arc::new(schemars::schema_for!(T).to_value());
+    CACHE.write().expect("schema cache poisoned").insert(name, schema.clone());
+    schema
+}
+
+/// 一处校验错误
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SchemaError {
+    /// 出错位置的 JSON Pointer，请求体本身为 `/`
+    pub pointer: String,
+    pub message: String,
+}
+
+impl SchemaError {
+    fn new(path: &str, message: impl Into<String>) -> Self {
+        Self { pointer: display(path).to_string(), message: message.into() }
+    }
+}
+
+impl fmt::Display for SchemaError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.pointer, self.message)
+    }
+}
+
+/// 模块：JSON Schema 校验 (Schema Validation)
+///
+/// **为什么需要**: serde 的报错只有第一处错误，且措辞面向 Rust 开发者
+/// (如 `invalid type: string "0.9", expected f64 at line 1 column 42`)。
+/// 接入方更需要的是“哪个字段、期望什么、实际是什么”的完整列表。
+/// 请求体先按公开的 Schema (`GET /schemas/{name}`) 校验，全部错误一次返回，通过后再交给 serde。
+///
+/// 只实现 schemars 生成的 Schema 会用到的关键字：
+/// `$ref` (`#/$defs/...`)、`type`、`properties`、`required`、`additionalProperties`、`items`、
+/// `enum`、`const`、`anyOf`、`oneOf`、`allOf`、`propertyNames`、数值、长度与属性个数上下限。
+///
+/// 返回全部错误 (显示为 `/confidence: expected number, got string`)；空列表表示通过。
+pub fn validate(schema: &Value, instance: &Value) -> Vec<SchemaError> {
+    let mut errors = Vec::new();
+    check(schema, schema, instance, "", &mut errors);
+    errors
+}
+
+fn check(root: &Value, schema: &Value, instance: &Value, path: &str, errors: &mut Vec<SchemaError>) {
+    let Some(schema) = schema.as_object() else {
+        // `true` 接受一切，`false` 拒绝一切
+        if schema == &Value::Bool(false) {
+            errors.push(SchemaError::new(path, "no value is allowed here"));
+        }
+        return;
+    };
+
+    if let Some(reference) = schema.get("$ref").and_then(Value::as_str) {
+        match resolve(root, reference) {
+            Some(target) => check(root, target, instance, path, errors),
+            None => errors.push(SchemaError::new(path, format!("unresolvable schema reference {}", reference))),
+        }
+    }
+
+    if let Some(expected) = schema.get("type") {
+        let allowed: Vec<&str> = match expected {
+            Value::String(kind) => vec![kind.as_str()],
+            Value::Array(kinds) => kinds.iter().filter_map(Value::as_str).collect(),
+            _ => Vec::new(),
+        };
+        if !allowed.is_empty() && !allowed.iter().any(|kind| matches_type(kind, instance)) {
+            errors.push(SchemaError::new(path, format!("expected {}, got {}", allowed.join(" or "), type_name(instance))));
+            return;
+        }
+    }
+
+    if let Some(options) = schema.get("enum").and_then(Value::as_array) {
+        if !options.contains(instance) {
+            errors.push(SchemaError::new(path, format!("must be one of {}", Value::Array(options.clone()))));
+        }
+    }
+    if let Some(expected) = schema.get("const") {
+        if expected != instance {
+            errors.push(SchemaError::new(path, format!("must be {}", expected)));
+        }
+    }
+
+    for sub in schema.get("allOf").and_then(Value::as_array).into_iter().flatten() {
+        check(root, sub, instance, path, errors);
+    }
+    for (keyword, exactly_one) in [("anyOf", false), ("oneOf", true)] {
+        let Some(schemas) = schema.get(keyword).and_then(Value::as_array) else {
+            continue;
+        };
+        let results: Vec<Vec<SchemaError>> = schemas
+            .iter()
+            .map(|sub| {
+                let mut sub_errors = Vec::new();
+                check(root, sub, instance, path, &mut sub_errors);
+                sub_errors
+            })
+            .collect();
+        let matched = results.iter().filter(|sub_errors| sub_errors.is_empty()).count();
+        if matched == 0 {
+            // 只有一个分支不是 null 时 (即 Option<T>)，直接报告该分支的错误，更易读
+            match results.iter().filter(|sub_errors| !is_null_mismatch(sub_errors)).collect::<Vec<_>>().as_slice() {
+                [only] => errors.extend(only.iter().cloned()),
+                _ => errors.push(SchemaError::new(path, "does not match any allowed shape")),
+            }
+        } else if exactly_one && matched > 1 {
+            errors.push(SchemaError::new(path, "matches more than one allowed shape"));
+        }
+    }
+
+    match instance {
+        Value::Object(object) => {
+            let properties = schema.get("properties").and_then(Value::as_object);
+            for required in schema.get("required").and_then(Value::as_array).into_iter().flatten().filter_map(Value::as_str) {
+                if !object.contains_key(required) {
+                    errors.push(SchemaError::new(&join(path, required), "missing required field"));
+                }
+            }
+            for (key, value) in object {
+                let child = join(path, key);
+                match properties.and_then(|properties| properties.get(key)) {
+                    Some(sub) => check(root, sub, value, &child, errors),
+                    None => match schema.get("additionalProperties") {
+                        Some(Value::Bool(false)) => errors.push(SchemaError::new(&child, "unknown field")),
+                        Some(sub @ Value::Object(_)) => check(root, sub, value, &child, errors),
+                        _ => {}
+                    },
+                }
+                if let Some(sub) = schema.get("propertyNames") {
+                    check(root, sub, &Value::String(key.clone()), &child, errors);
+                }
+            }
+            bounds(schema, "minProperties", "maxProperties", object.len() as f64, "properties", path, errors);
+        }
+        Value::Array(items) => {
+            if let Some(sub) = schema.get("items") {
+                for (index, item) in items.iter().enumerate() {
+                    check(root, sub, item, &join(path, &index.to_string()), errors);
+                }
+            }
+            bounds(schema, "minItems", "maxItems", items.len() as f64, "items", path, errors);
+        }
+        Value::String(text) => bounds(schema, "minLength", "maxLength", text.chars().count() as f64, "characters", path, errors),
+        Value::Number(number) => {
+            let value = number.as_f64().unwrap_or(f64::NAN);
+            if let Some(minimum) = schema.get("minimum").and_then(Value::as_f64) {
+                if value < minimum {
+                    errors.push(SchemaError::new(path, format!("must be >= {}", minimum)));
+                }
+            }
+            if let Some(maximum) = schema.get("maximum").and_then(Value::as_f64) {
+                if value > maximum {
+                    errors.push(SchemaError::new(path, format!("must be <= {}", maximum)));
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
+fn bounds(schema: &serde_json::Map<String, Value>, min: &str, max: &str, actual: f64, unit: &str, path: &str, errors: &mut Vec<SchemaError>) {
+    if let Some(limit) = schema.get(min).and_then(Value::as_f64) {
+        if actual < limit {
+            errors.push(SchemaError::new(path, format!("must have at least {} {}", limit, unit)));
+        }
+    }
+    if let Some(limit) = schema.get(max).and_then(Value::as_f64) {
+        if actual > limit {
+            errors.push(SchemaError::new(path, format!("must have at most {} {}", limit, unit)));
+        }
+    }
+}
+
+/// 只支持本文档内的引用 (`#/$defs/Name`，兼容旧式 `#/definitions/Name`)
+fn resolve<'a>(root: &'a Value, reference: &str) -> Option<&'a Value> {
+    root.pointer(reference.strip_prefix('#')?)
+}
+
+fn matches_type(kind: &str, instance: &Value) -> bool {
+    match kind {
+        "null" => instance.is_null(),
+        "boolean" => instance.is_boolean(),
+        "object" => instance.is_object(),
+        "array" => instance.is_array(),
+        "string" => instance.is_string(),
+        "number" => instance.is_number(),
+        "integer" => instance.is_i64() || instance.is_u64(),
+        _ => true,
+    }
+}
+
+fn type_name(instance: &Value) -> &'static str {
+    match instance {
+        Value::Null => "null",
+        Value::Bool(_) => "boolean",
+        Value::Number(number) if number.is_f64() => "number",
+        Value::Number(_) => "integer",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+    }
+}
+
+fn is_null_mismatch(errors: &[SchemaError]) -> bool {
+    matches!(errors, [only] if only.message.starts_with("expected null, got "))
+}
+
+/// JSON Pointer 路径 (RFC 6901 转义)
+fn join(path: &str, key: &str) -> String {
+    format!("{}/{}", path, key.replace('~', "~0").replace('/', "~1"))
+}
+
+fn display(path: &str) -> &str {
+    if path.is_empty() {
+        "/"
+    } else {
+        path
+    }
+}
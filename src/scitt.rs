@@ -0,0 +1,204 @@
+use ckb_merkle_mountain_range::MerkleProof;
+use coset::cbor::Value as CborValue;
+use coset::cwt::{ClaimsSetBuilder, Timestamp};
+use coset::{iana, AsCborValue, CborSerializable, CoseSign1, CoseSign1Builder, HeaderBuilder, Label};
+use ed25519_dalek::{Signature, VerifyingKey};
+use serde_json::Value;
+
+use crate::dsse;
+use crate::proof::{InclusionProof, MergeBlake3};
+use crate::signer::EvidenceSigner;
+
+/// COSE_Sign1 的媒体类型 (RFC 9052)
+pub const MEDIA_TYPE: &str = "application/cose; cose-type=\"cose-sign1\"";
+
+/// 头部参数：CWT 声明 (RFC 9597)
+const HEADER_CWT_CLAIMS: i64 = 15;
+/// 头部参数：透明声明所附的收据 (SCITT)
+const HEADER_RECEIPTS: i64 = 394;
+/// 头部参数：可验证数据结构 (COSE Receipts)
+const HEADER_VDS: i64 = 395;
+/// 头部参数：可验证数据结构的证明 (COSE Receipts)
+const HEADER_VDP: i64 = 396;
+/// 证明类型：包含性证明
+const VDP_INCLUSION: i64 = -1;
+
+/// 可验证数据结构：Blake3 MMR (私用值；IANA 登记的只有 RFC 9162 的二叉 Merkle 树)
+pub const VDS_MMR_BLAKE3: i64 = -65537;
+
+/// 校验通过的透明声明
+#[derive(Debug, Clone)]
+pub struct VerifiedStatement {
+    /// 签名声明中的 in-toto 声明 (见 `dsse.rs`)
+    pub statement: Value,
+    /// 收据所证明的叶子位置、树大小与根
+    pub pos: u64,
+    pub tree_size: u64,
+    pub root: [u8; 32],
+}
+
+/// 签名声明 (Signed Statement)：签发方对 in-toto 声明的 COSE_Sign1 签名
+///
+/// 受保护头部含算法 (EdDSA)、内容类型、`kid` (DID 验证方法 ID) 与 CWT 声明 (`iss` 为 DID，`sub` 为图片摘要)。
+pub fn signed_statement(statement: &Value, issuer: &str, keyid: &str, signer: &EvidenceSigner) -> anyhow::Result<CoseSign1> {
+    let subject = statement["subject"][0]["name"]
+        .as_str()
+        .ok_or_else(|| anyhow::anyhow!("Statement has no subject"))?;
+    let claims = ClaimsSetBuilder::new()
+        .issuer(issuer.to_string())
+        .subject(subject.to_string())
+        .build()
+        .to_cbor_value()
+        .map_err(cose_error)?;
+    let protected = HeaderBuilder::new()
+        .algorithm(iana::Algorithm::EdDSA)
+        .content_type(dsse::PAYLOAD_TYPE.to_string())
+        .key_id(keyid.as_bytes().to_vec())
+        .value(HEADER_CWT_CLAIMS, claims)
+        .build();
+    Ok(CoseSign1Builder::new()
+        .protected(protected)
+        .payload(serde_jcs::to_vec(statement)?)
+        .try_create_signature(b"", |data| signer.sign_bytes(data).map(|signature| signature.to_bytes().to_vec()))?
+        .build())
+}
+
+/// 收据 (Receipt)：日志对“该叶子在此根之下”的 COSE_Sign1 签名
+///
+/// 载荷分离 (detached)，即证明算出的根；包含性证明放在不受保护头部 `396` 的 `-1` 中，
+/// 为 `bstr .cbor [tree_size, pos, [items...]]`，与 `/audit/{pos}` 同一条证明。
+pub fn receipt(
+    proof: &InclusionProof,
+    root: [u8; 32],
+    issuer: &str,
+    keyid: &str,
+    issued_at: i64,
+    signer: &EvidenceSigner,
+) -> anyhow::Result<CoseSign1> {
+    let claims = ClaimsSetBuilder::new()
+        .issuer(issuer.to_string())
+        .issued_at(Timestamp::WholeSeconds(issued_at))
+        .build()
+        .to_cbor_value()
+        .map_err(cose_error)?;
+    let protected = HeaderBuilder::new()
+        .algorithm(iana::Algorithm::EdDSA)
+        .key_id(keyid.as_bytes().to_vec())
+        .value(HEADER_VDS, CborValue::from(VDS_MMR_BLAKE3))
+        .value(HEADER_CWT_CLAIMS, claims)
+        .build();
+    let encoded_proof = CborValue::Array(vec![
+        CborValue::from(proof.tree_size),
+        CborValue::from(proof.pos),
+        CborValue::Array(proof.items.iter().map(|item| CborValue::Bytes(item.to_vec())).collect()),
+    ]);
+    let mut proof_bytes = Vec::new();
+    coset::cbor::ser::into_writer(&encoded_proof, &mut proof_bytes)?;
+    let unprotected = HeaderBuilder::new()
+        .value(HEADER_VDP, CborValue::Map(vec![(CborValue::from(VDP_INCLUSION), CborValue::Array(vec![CborValue::Bytes(proof_bytes)]))]))
+        .build();
+    Ok(CoseSign1Builder::new()
+        .protected(protected)
+        .unprotected(unprotected)
+        .try_create_detached_signature(&root, b"", |data| signer.sign_bytes(data).map(|signature| signature.to_bytes().to_vec()))?
+        .build())
+}
+
+/// 模块：SCITT 透明声明 (SCITT Transparent Statement)
+///
+/// **为什么需要**: IETF SCITT 架构把“签发方签名的声明 + 透明服务的收据”定为透明服务之间互认的交换格式，
+/// 其他透明服务与审计工具只需会解析 COSE 即可核验。我们的回执与证明是自有的 JSON/BCS 结构，
+/// 这里把同一条证据换成 SCITT 的形态：签名声明即 DSSE 导出的那份 in-toto 声明，收据用 COSE Receipts 的头部布局
+/// 装载 MMR 包含性证明，并以租户根密钥对根签名；收据附在签名声明的不受保护头部 `394` 中。
+///
+/// 日志的叶子仍是证据原文的哈希，不是签名声明本身的哈希：验证方从声明的 `predicate.proof.evidenceHash` 取叶子哈希。
+/// 包含性证明是 MMR 而非 RFC 9162 的二叉树，数据结构标识因此用私用值 [`VDS_MMR_BLAKE3`]。
+pub fn transparent_statement(signed: CoseSign1, receipt: CoseSign1) -> anyhow::Result<Vec<u8>> {
+    let receipt_bytes = receipt.to_vec().map_err(cose_error)?;
+    let mut signed = signed;
+    signed.unprotected.rest.push((Label::Int(HEADER_RECEIPTS), CborValue::Array(vec![CborValue::Bytes(receipt_bytes)])));
+    signed.to_vec().map_err(cose_error)
+}
+
+/// 离线校验透明声明：签名声明由 `issuer_key` 签出，其中任一收据由 `service_key` 签出且证明叶子在根之下
+pub fn verify(bytes: &[u8], issuer_key: &VerifyingKey, service_key: &VerifyingKey) -> anyhow::Result<VerifiedStatement> {
+    let signed = CoseSign1::from_slice(bytes).map_err(cose_error)?;
+    signed.verify_signature(b"", |signature, data| verify_ed25519(issuer_key, signature, data))?;
+    let statement: Value = serde_json::from_slice(
+        signed.payload.as_deref().ok_or_else(|| anyhow::anyhow!("Signed statement has no payload"))?,
+    )?;
+    let leaf: [u8; 32] = statement["predicate"]["proof"]["evidenceHash"]
+        .as_str()
+        .and_then(|hash| hex::decode(hash).ok())
+        .and_then(|hash| hash.try_into().ok())
+        .ok_or_else(|| anyhow::anyhow!("Statement has no evidence hash"))?;
+
+    let receipts = header_value(&signed.unprotected.rest, HEADER_RECEIPTS)
+        .and_then(CborValue::as_array)
+        .ok_or_else(|| anyhow::anyhow!("Transparent statement carries no receipts"))?;
+    let mut last_error = anyhow::anyhow!("No receipt verified");
+    for receipt in receipts {
+        let result = receipt
+            .as_bytes()
+            .ok_or_else(|| anyhow::anyhow!("Receipt is not a byte string"))
+            .and_then(|receipt| verify_receipt(receipt, leaf, service_key));
+        match result {
+            Ok((proof, root)) => {
+                return Ok(VerifiedStatement { statement, pos: proof.pos, tree_size: proof.tree_size, root });
+            }
+            Err(e) => last_error = e,
+        }
+    }
+    Err(last_error)
+}
+
+/// 校验一份收据，返回其中的包含性证明与由证明算出的根
+fn verify_receipt(bytes: &[u8], leaf: [u8; 32], service_key: &VerifyingKey) -> anyhow::Result<(InclusionProof, [u8; 32])> {
+    let receipt = CoseSign1::from_slice(bytes).map_err(cose_error)?;
+    if header_value(&receipt.protected.header.rest, HEADER_VDS).and_then(|vds| vds.as_integer()) != Some(VDS_MMR_BLAKE3.into()) {
+        return Err(anyhow::anyhow!("Unsupported verifiable data structure"));
+    }
+    let inclusion = header_value(&receipt.unprotected.rest, HEADER_VDP)
+        .and_then(CborValue::as_map)
+        .and_then(|proofs| proofs.iter().find(|(label, _)| label.as_integer() == Some(VDP_INCLUSION.into())))
+        .and_then(|(_, proofs)| proofs.as_array()?.first()?.as_bytes())
+        .ok_or_else(|| anyhow::anyhow!("Receipt has no inclusion proof"))?;
+    let proof = decode_inclusion_proof(inclusion)?;
+    let root = MerkleProof::<[u8; 32], MergeBlake3>::new(proof.tree_size, proof.items.clone())
+        .calculate_root(vec![(proof.pos, leaf)])
+        .map_err(|e| anyhow::anyhow!("Invalid inclusion proof: {}", e))?;
+    receipt.verify_detached_signature(&root, b"", |signature, data| verify_ed25519(service_key, signature, data))?;
+    Ok((proof, root))
+}
+
+fn decode_inclusion_proof(bytes: &[u8]) -> anyhow::Result<InclusionProof> {
+    let value: CborValue = coset::cbor::de::from_reader(bytes)?;
+    let malformed = || anyhow::anyhow!("Malformed inclusion proof");
+    let [tree_size, pos, items] = value.as_array().ok_or_else(malformed)?.as_slice() else {
+        return Err(malformed());
+    };
+    let integer = |value: &CborValue| value.as_integer().and_then(|value| u64::try_from(value).ok()).ok_or_else(malformed);
+    let items = items
+        .as_array()
+        .ok_or_else(malformed)?
+        .iter()
+        .map(|item| item.as_bytes().and_then(|item| <[u8; 32]>::try_from(item.as_slice()).ok()).ok_or_else(malformed))
+        .collect::<anyhow::Result<Vec<_>>>()?;
+    Ok(InclusionProof::new(integer(tree_size)?, integer(pos)?, items))
+}
+
+fn header_value(rest: &[(Label, CborValue)], label: i64) -> Option<&CborValue> {
+    rest.iter().find(|(key, _)| *key == Label::Int(label)).map(|(_, value)| value)
+}
+
+fn verify_ed25519(key: &VerifyingKey, signature: &[u8], data: &[u8]) -> anyhow::Result<()> {
+    let signature = Signature::from_slice(signature).map_err(|_| anyhow::anyhow!("Not an Ed25519 signature"))?;
+    if !EvidenceSigner::verify_bytes(key, data, &signature) {
+        return Err(anyhow::anyhow!("Invalid COSE signature"));
+    }
+    Ok(())
+}
+
+fn cose_error(e: coset::CoseError) -> anyhow::Error {
+    anyhow::anyhow!("COSE error: {:?}", e)
+}
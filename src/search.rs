@@ -0,0 +1,314 @@
+use log::warn;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeSet, HashMap};
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use tantivy::collector::{Count, TopDocs};
+use tantivy::directory::MmapDirectory;
+use tantivy::query::{Query, QueryParser};
+use tantivy::schema::{Field, IndexRecordOption, Schema, TextFieldIndexing, TextOptions, Value, FAST, INDEXED, STORED};
+use tantivy::tokenizer::{LowerCaser, PreTokenizedStream, PreTokenizedString, TextAnalyzer, Token, Tokenizer};
+use tantivy::{Index, IndexReader, IndexWriter, ReloadPolicy, TantivyDocument, Term};
+
+use crate::evidence::Evidence;
+use crate::mmr_store::StoreSnapshot;
+use crate::position::{leaf_count, leaf_index_to_pos, pos_to_leaf_index};
+use crate::tenant::TenantContext;
+
+/// 来源说明的最大长度 (字节)
+pub const MAX_SOURCE_BYTES: usize = 1024;
+
+/// 检索默认返回的条数
+pub const DEFAULT_SEARCH_LIMIT: usize = 20;
+
+/// 检索最多返回的条数
+pub const MAX_SEARCH_LIMIT: usize = 200;
+
+/// 分词器在索引中的名称
+const TOKENIZER: &str = "yuanjing";
+
+/// 索引写入的内存预算 (tantivy 单线程写入的下限)
+const WRITER_MEMORY_BYTES: usize = 15_000_000;
+
+/// 分词：连续的 ASCII 字母数字为一个词，其余文字 (中日韩等) 逐字成词，标点与空白只作分隔
+///
+/// 中文没有空格分词，逐字索引后多字的查询按短语匹配，即子串检索。
+#[derive(Clone, Default)]
+struct EvidenceTokenizer;
+
+impl Tokenizer for EvidenceTokenizer {
+    type TokenStream<'a> = PreTokenizedStream;
+
+    fn token_stream<'a>(&'a mut self, text: &'a str) -> PreTokenizedStream {
+        let mut tokens: Vec<Token> = Vec::new();
+        let mut word: Option<usize> = None;
+        let push = |tokens: &mut Vec<Token>, from: usize, to: usize| {
+            let position = tokens.len();
+            tokens.push(Token { offset_from: from, offset_to: to, position, text: text[from..to].to_string(), position_length: 1 });
+        };
+        for (i, c) in text.char_indices() {
+            if c.is_ascii_alphanumeric() {
+                word.get_or_insert(i);
+                continue;
+            }
+            if let Some(from) = word.take() {
+                push(&mut tokens, from, i);
+            }
+            if c.is_alphanumeric() {
+                push(&mut tokens, i, i + c.len_utf8());
+            }
+        }
+        if let Some(from) = word {
+            push(&mut tokens, from, text.len());
+        }
+        PreTokenizedString { text: text.to_string(), tokens }.into()
+    }
+}
+
+/// 一条命中
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SearchHit {
+    pub pos: u64,
+    /// 相关度 (BM25)，只在同一次检索的结果之间可比
+    pub score: f32,
+    /// 回执时间 (Unix 秒)
+    pub timestamp: i64,
+    /// 提交时的来源说明
+    pub source: Option<String>,
+    pub tags: Vec<String>,
+    /// 模型版本 (Prompt 池哈希)；原文已清理或擦除时为 null
+    pub model: Option<String>,
+}
+
+/// 检索结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SearchResults {
+    /// 命中总数
+    pub total: usize,
+    /// 按相关度降序
+    pub hits: Vec<SearchHit>,
+}
+
+struct Fields {
+    pos: Field,
+    timestamp: Field,
+    source: Field,
+    tags: Field,
+    model: Field,
+    model_description: Field,
+}
+
+/// 写端与已索引的叶子数 (随提交一起记在索引的 payload 里)
+struct WriterState {
+    writer: IndexWriter,
+    indexed_leaves: u64,
+}
+
+/// 单个租户的全文索引
+pub struct SearchIndex {
+    index: Index,
+    reader: IndexReader,
+    fields: Fields,
+    state: Mutex<WriterState>,
+    /// 已索引、但元数据有改动 (标签、擦除) 的位置，下一轮刷新时重新索引
+    pending: Mutex<BTreeSet<u64>>,
+}
+
+impl SearchIndex {
+    /// 打开或新建索引目录
+    pub fn open(dir: &Path) -> anyhow::Result<Self> {
+        let text = TextOptions::default().set_indexing_options(
+            TextFieldIndexing::default().set_tokenizer(TOKENIZER).set_index_option(IndexRecordOption::WithFreqsAndPositions),
+        );
+        let mut builder = Schema::builder();
+        let fields = Fields {
+            pos: builder.add_u64_field("pos", INDEXED | STORED | FAST),
+            timestamp: builder.add_i64_field("timestamp", STORED),
+            source: builder.add_text_field("source", text.clone() | STORED),
+            tags: builder.add_text_field("tags", text.clone() | STORED),
+            model: builder.add_text_field("model", text.clone() | STORED),
+            model_description: builder.add_text_field("model_description", text),
+        };
+        std::fs::create_dir_all(dir)?;
+        let index = Index::open_or_create(MmapDirectory::open(dir)?, builder.build())?;
+        index
+            .tokenizers()
+            .register(TOKENIZER, TextAnalyzer::builder(EvidenceTokenizer).filter(LowerCaser).build());
+        let indexed_leaves = index.load_metas()?.payload.and_then(|payload| payload.parse().ok()).unwrap_or(0);
+        let writer = index.writer_with_num_threads(1, WRITER_MEMORY_BYTES)?;
+        let reader = index.reader_builder().reload_policy(ReloadPolicy::Manual).try_into()?;
+        Ok(Self {
+            index,
+            reader,
+            fields,
+            state: Mutex::new(WriterState { writer, indexed_leaves }),
+            pending: Mutex::new(BTreeSet::new()),
+        })
+    }
+
+    /// 记下元数据有改动的位置
+    pub fn touch(&self, pos: u64) {
+        self.pending.lock().unwrap().insert(pos);
+    }
+
+    /// 把新叶子与有改动的位置写进索引并提交，返回写入的证据数
+    pub fn refresh(&self, snapshot: &StoreSnapshot) -> anyhow::Result<usize> {
+        let mut state = self.state.lock().unwrap();
+        let leaves = leaf_count(snapshot.mmr_size());
+        let pending = std::mem::take(&mut *self.pending.lock().unwrap());
+        if leaves <= state.indexed_leaves && pending.is_empty() {
+            return Ok(0);
+        }
+        match self.apply(&mut state, snapshot, &pending, leaves) {
+            Ok(indexed) => {
+                state.indexed_leaves = leaves;
+                self.reader.reload()?;
+                Ok(indexed)
+            }
+            Err(e) => {
+                state.writer.rollback()?;
+                self.pending.lock().unwrap().extend(pending);
+                Err(e)
+            }
+        }
+    }
+
+    fn apply(&self, state: &mut WriterState, snapshot: &StoreSnapshot, pending: &BTreeSet<u64>, leaves: u64) -> anyhow::Result<usize> {
+        let tags = snapshot.tags()?;
+        let mut indexed = 0;
+        // 尚未索引的位置随下面的新叶子一起处理
+        let changed = pending
+            .iter()
+            .copied()
+            .filter(|pos| pos_to_leaf_index(*pos).is_some_and(|leaf| leaf < state.indexed_leaves));
+        for pos in changed.chain((state.indexed_leaves..leaves).map(leaf_index_to_pos)) {
+            state.writer.delete_term(Term::from_field_u64(self.fields.pos, pos));
+            // 检查点、保管与复核记录叶子没有回执，不索引
+            let Some(attestation) = snapshot.get_attestation(pos)? else {
+                continue;
+            };
+            let mut document = TantivyDocument::default();
+            document.add_u64(self.fields.pos, pos);
+            document.add_i64(self.fields.timestamp, attestation.receipt.timestamp);
+            if let Some(source) = snapshot.source(pos)? {
+                document.add_text(self.fields.source, source);
+            }
+            for tag in tags.get(pos)?.map(|set| set.tags).unwrap_or_default() {
+                document.add_text(self.fields.tags, tag);
+            }
+            if let Some(bytes) = snapshot.evidence_bytes(pos)? {
+                let evidence = Evidence::from_canonical_bytes(attestation.receipt.encoding, &bytes)?;
+                if let Some(description) = snapshot.model_description(&evidence.prompt_pool_hash)? {
+                    document.add_text(self.fields.model_description, description);
+                }
+                document.add_text(self.fields.model, evidence.prompt_pool_hash);
+            }
+            state.writer.add_document(document)?;
+            indexed += 1;
+        }
+        let mut commit = state.writer.prepare_commit()?;
+        commit.set_payload(&leaves.to_string());
+        commit.commit()?;
+        Ok(indexed)
+    }
+
+    /// 解析查询 (tantivy 查询语法；不指定字段时在来源说明、标签、模型与模型说明中查找，多个词须同时出现)
+    pub fn parse_query(&self, text: &str) -> anyhow::Result<Box<dyn Query>> {
+        let fields = &self.fields;
+        let mut parser = QueryParser::for_index(&self.index, vec![fields.source, fields.tags, fields.model, fields.model_description]);
+        parser.set_conjunction_by_default();
+        parser.parse_query(text).map_err(|e| anyhow::anyhow!("{}", e))
+    }
+
+    /// 按相关度检索，跳过前 `offset` 条
+    pub fn search(&self, query: &dyn Query, limit: usize, offset: usize) -> anyhow::Result<SearchResults> {
+        let searcher = self.reader.searcher();
+        let (top, total) = searcher.search(query, &(TopDocs::with_limit(limit).and_offset(offset), Count))?;
+        let mut hits = Vec::with_capacity(top.len());
+        for (score, address) in top {
+            let document: TantivyDocument = searcher.doc(address)?;
+            let text = |field: Field| document.get_first(field).and_then(|value| value.as_str()).map(str::to_string);
+            hits.push(SearchHit {
+                pos: document.get_first(self.fields.pos).and_then(|value| value.as_u64()).unwrap_or_default(),
+                score,
+                timestamp: document.get_first(self.fields.timestamp).and_then(|value| value.as_i64()).unwrap_or_default(),
+                source: text(self.fields.source),
+                tags: document.get_all(self.fields.tags).filter_map(|value| value.as_str()).map(str::to_string).collect(),
+                model: text(self.fields.model),
+            });
+        }
+        Ok(SearchResults { total, hits })
+    }
+}
+
+/// 模块：证据元数据全文检索 (Evidence Search)
+///
+/// **为什么需要**: 调查人员手里往往只有一段描述 (“微博转发的截图”、某个案件编号、某个模型名)，
+/// 而此前只能按位置、哈希或完整的标签精确查找。这里为每个租户维护一份 tantivy 索引，
+/// 覆盖提交时的来源说明、标签、模型版本及其注册说明，支持自由文本检索 (`GET /search`)。
+///
+/// 索引是存储的派生数据：后台按 `refresh_interval_ms` 把新叶子与有改动的证据 (标签、擦除) 写进索引，
+/// 已索引的叶子数随每次提交记在索引里；索引目录丢失或损坏时删掉重启，即从存储完整重建。
+/// 检索结果最多滞后一个刷新间隔；进程崩溃前尚未刷新的标签改动不会自动补上，可删除索引重建。
+/// 案件编号等没有单独的字段，按约定以标签 (`case:2026-017`) 记录，随标签检索。
+pub struct SearchService {
+    indexes: HashMap<String, SearchIndex>,
+    refresh_interval: Duration,
+}
+
+impl SearchService {
+    /// 为每个租户打开 `<dir>/<租户>` 下的索引
+    pub fn open<'a>(dir: &str, tenant_ids: impl IntoIterator<Item = &'a str>, refresh_interval: Duration) -> anyhow::Result<Self> {
+        let mut indexes = HashMap::new();
+        for tenant_id in tenant_ids {
+            let path = Path::new(dir).join(tenant_id);
+            let index = SearchIndex::open(&path)
+                .map_err(|e| anyhow::anyhow!("Cannot open search index '{}': {}", path.display(), e))?;
+            indexes.insert(tenant_id.to_string(), index);
+        }
+        Ok(Self { indexes, refresh_interval })
+    }
+
+    pub fn index(&self, tenant_id: &str) -> Option<&SearchIndex> {
+        self.indexes.get(tenant_id)
+    }
+
+    /// 记下某租户某条证据的元数据有改动
+    pub fn touch(&self, tenant_id: &str, pos: u64) {
+        if let Some(index) = self.indexes.get(tenant_id) {
+            index.touch(pos);
+        }
+    }
+
+    /// 对所有租户执行一轮刷新
+    pub async fn refresh_once(self: &Arc<Self>, tenants: &[Arc<TenantContext>]) {
+        for tenant in tenants {
+            let snapshot = tenant.reader.snapshot();
+            let service = self.clone();
+            let id = tenant.id.clone();
+            let refreshed = tokio::task::spawn_blocking(move || match service.index(&id) {
+                Some(index) => index.refresh(&snapshot),
+                None => Ok(0),
+            })
+            .await
+            .map_err(anyhow::Error::from)
+            .and_then(|refreshed| refreshed);
+            if let Err(e) = refreshed {
+                warn!("⚠️  租户 '{}' 检索索引刷新失败 (下次重试): {}", tenant.id, e);
+            }
+        }
+    }
+
+    /// 启动后台周期刷新任务 (启动时先执行一次，补上索引落后的部分)
+    pub fn spawn_periodic(self: Arc<Self>, tenants: Vec<Arc<TenantContext>>) {
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(self.refresh_interval);
+            loop {
+                ticker.tick().await;
+                self.refresh_once(&tenants).await;
+            }
+        });
+    }
+}
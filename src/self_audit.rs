@@ -0,0 +1,170 @@
+use ckb_merkle_mountain_range::{leaf_index_to_mmr_size, leaf_index_to_pos};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+use crate::evidence::leaf_hash_of;
+use crate::mmr_store::{leaf_count, PeakCache, StoreSnapshot};
+
+/// 分歧类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DivergenceKind {
+    /// MMR 中缺少叶子节点
+    MissingLeaf,
+    /// 由原文重算的叶子哈希与 MMR 中的叶子节点不符
+    LeafHash,
+    /// 叶子节点与回执中的 `evidence_hash` 不符
+    ReceiptLeaf,
+    /// 重放得到的根与落盘的历史根不符
+    Root,
+}
+
+/// 第一处分歧
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Divergence {
+    pub kind: DivergenceKind,
+    /// 出现分歧的叶子位置
+    pub pos: u64,
+    /// 追加该叶子后的树大小
+    pub tree_size: u64,
+    /// 期望值的来源：`mmr_node` | `receipt` | `last_signed_root` | `tree_head` | `current_root`
+    pub source: String,
+    /// 期望值 (Hex)，即落盘的记录
+    pub expected: String,
+    /// 重放得到的值 (Hex)
+    pub actual: String,
+}
+
+/// 自审结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SelfAuditReport {
+    pub tenant_id: String,
+    pub tree_size: u64,
+    /// 重放的叶子数
+    pub leaves: u64,
+    /// 由原文重算叶子哈希的叶子数
+    pub rehashed: u64,
+    /// 原文已清理或擦除、只能沿用叶子节点的叶子数
+    pub unverifiable: u64,
+    /// 与之比对过的历史根数 (回执、最近签名根、树头存档与当前根)
+    pub roots_checked: u64,
+    /// 重放得到的最终根 (Hex)，空树为 null
+    pub root: Option<String>,
+    /// 第一处分歧；为 null 表示全部一致
+    pub first_divergence: Option<Divergence>,
+}
+
+impl SelfAuditReport {
+    pub fn is_consistent(&self) -> bool {
+        self.first_divergence.is_none()
+    }
+}
+
+/// 模块：自审重放 (Self-Audit Replay)
+///
+/// **为什么需要**: 启动时的崩溃恢复只核对最近一次签名根，山峰自检只核对当前根。
+/// 磁盘静默损坏、误操作或篡改可能改动更早的叶子原文或节点，而日常读写路径不会重新计算它们。
+///
+/// 按叶子顺序逐条重放：由落盘的原文 (加盐叶子前置盐值) 重算叶子哈希，与 MMR 叶子节点和回执比对，
+/// 再把叶子推入内存中的山峰缓存逐步重建根；每当树大小与某条落盘的历史根 (回执、最近签名根、树头存档) 相同，
+/// 就比对二者，最后与由落盘节点计算的当前根比对。遇到第一处分歧即停止并报告。
+///
+/// 原文已被清理或擦除的叶子无法重算，沿用叶子节点继续重放 (计入 `unverifiable`)。
+pub fn replay(snapshot: &StoreSnapshot, tenant_id: &str) -> anyhow::Result<SelfAuditReport> {
+    let tree_size = snapshot.mmr_size();
+    let mut report = SelfAuditReport {
+        tenant_id: tenant_id.to_string(),
+        tree_size,
+        leaves: 0,
+        rehashed: 0,
+        unverifiable: 0,
+        roots_checked: 0,
+        root: None,
+        first_divergence: None,
+    };
+
+    // 回执之外的历史根：树大小 -> (来源, 根)
+    let mut recorded: BTreeMap<u64, Vec<(&str, [u8; 32])>> = BTreeMap::new();
+    if let Some(signed) = snapshot.last_signed_root()? {
+        recorded.entry(signed.tree_size).or_default().push(("last_signed_root", signed.root));
+    }
+    for head in snapshot.tree_heads()?.recent(usize::MAX)? {
+        let root = decode_hash(&head.tree_head.root)?;
+        recorded.entry(head.tree_head.tree_size).or_default().push(("tree_head", root));
+    }
+
+    let mut peaks = PeakCache::default();
+    for leaf_index in 0..leaf_count(tree_size) {
+        let pos = leaf_index_to_pos(leaf_index);
+        let size = leaf_index_to_mmr_size(leaf_index);
+        report.leaves += 1;
+
+        let Some(stored) = snapshot.get_leaf(pos)? else {
+            report.first_divergence = Some(divergence(DivergenceKind::MissingLeaf, pos, size, "mmr_node", [0; 32], [0; 32]));
+            return Ok(report);
+        };
+        let leaf = match snapshot.evidence_bytes(pos)? {
+            Some(bytes) => {
+                report.rehashed += 1;
+                leaf_hash_of(&bytes, snapshot.get_salt(pos)?.as_ref())
+            }
+            None => {
+                report.unverifiable += 1;
+                stored
+            }
+        };
+        if leaf != stored {
+            report.first_divergence = Some(divergence(DivergenceKind::LeafHash, pos, size, "mmr_node", stored, leaf));
+            return Ok(report);
+        }
+
+        let root = peaks.push(leaf)?;
+        let mut expected = recorded.remove(&size).unwrap_or_default();
+        if let Some(attestation) = snapshot.get_attestation(pos)? {
+            let receipt = &attestation.receipt;
+            let receipt_leaf = decode_hash(&receipt.evidence_hash)?;
+            if receipt_leaf != leaf {
+                report.first_divergence = Some(divergence(DivergenceKind::ReceiptLeaf, pos, size, "receipt", receipt_leaf, leaf));
+                return Ok(report);
+            }
+            if receipt.tree_size == size {
+                expected.push(("receipt", decode_hash(&receipt.root)?));
+            }
+        }
+        for (source, recorded_root) in expected {
+            report.roots_checked += 1;
+            if recorded_root != root {
+                report.first_divergence = Some(divergence(DivergenceKind::Root, pos, size, source, recorded_root, root));
+                return Ok(report);
+            }
+        }
+    }
+
+    report.root = peaks.root().map(hex::encode);
+    if let Some(root) = peaks.root() {
+        report.roots_checked += 1;
+        let current = snapshot.root_at(tree_size)?;
+        if current != root {
+            let pos = leaf_index_to_pos(leaf_count(tree_size) - 1);
+            report.first_divergence = Some(divergence(DivergenceKind::Root, pos, tree_size, "current_root", current, root));
+        }
+    }
+    Ok(report)
+}
+
+fn divergence(kind: DivergenceKind, pos: u64, tree_size: u64, source: &str, expected: [u8; 32], actual: [u8; 32]) -> Divergence {
+    Divergence {
+        kind,
+        pos,
+        tree_size,
+        source: source.to_string(),
+        expected: hex::encode(expected),
+        actual: hex::encode(actual),
+    }
+}
+
+fn decode_hash(value: &str) -> anyhow::Result<[u8; 32]> {
+    hex::decode(value)?
+        .try_into()
+        .map_err(|_| anyhow::anyhow!("Expected a 32-byte hash, got '{}'", value))
+}
@@ -1,8 +1,17 @@
+use log::{info, warn};
 use std::fs;
+use std::ops::Deref;
 use std::path::Path;
+use std::sync::Once;
 use ed25519_dalek::{Signer, Verifier, SigningKey, VerifyingKey, Signature};
 use rand::rngs::OsRng; 
+use zeroize::Zeroizing;
+use crate::enclave::{DerivationStep, EnclaveKey};
 use crate::evidence::Evidence;
+use crate::hdkey::{self, DerivationPath, KeyEndorsement};
+use crate::keystore::{self, KeyFile, KeyFilePolicy};
+use crate::piv::PivKey;
+use crate::receipt::Receipt;
 
 /// 模块：签名器 (Signer)
 /// 
@@ -23,7 +32,24 @@ pub struct EvidenceSigner {
     /// **[⚠️ 风险预警]**: 
     /// 本项目目前将密钥存储在内存结构体中。
     /// 一旦服务器被攻破并 Dump 内存，私钥即泄露。
-    keypair: SigningKey,
+    ///
+    /// **[✅ 已缓解 - 内存保护]**: 密钥放在独立的堆页上并用 `mlock` 锁定，不会被换出到交换分区；
+    /// 销毁时先擦除再解锁，见 [`LockedKey`]。
+    ///
+    /// **[✅ 可选 - 飞地签名]**: `key_source = "enclave"` 时私钥只在飞地签名进程里，本进程只持有公钥，见 `enclave.rs`。
+    ///
+    /// **[✅ 可选 - 硬件令牌]**: `key_source = "piv"` 时私钥在 YubiKey 的 PIV 槽位里，每个签名按槽位的触摸策略由人按令牌，见 `piv.rs`。
+    key: SignerKey,
+}
+
+/// 私钥所在之处
+enum SignerKey {
+    /// 本进程内存
+    Local(LockedKey),
+    /// 飞地签名进程：规范化字节送进去，只有签名出来
+    Enclave(Box<EnclaveKey>),
+    /// PIV 硬件令牌的槽位
+    Piv(Box<PivKey>),
 }
 
 impl EvidenceSigner {
@@ -34,12 +60,24 @@ impl EvidenceSigner {
     /// 系统启动时会检查指定路径是否存在私钥文件：
     /// - **存在**: 读取文件恢复身份（模拟从 KeyStore 加载）。
     /// - **不存在**: 生成新密钥并保存到磁盘（模拟系统首次初始化）。
-    pub fn load_or_generate<P: AsRef<Path>>(path: P) -> anyhow::Result<Self> {
+    ///
+    /// **[✅ 已修复 - 文件权限]**: 新文件以 0600 创建；其他用户可访问的身份文件默认拒绝加载。
+    /// 只读模式 (`policy.read_only`) 下不会自动生成，缺失即报错，避免生产环境悄悄换了一个身份。
+    pub fn load_or_generate<P: AsRef<Path>>(path: P, policy: &KeyFilePolicy) -> anyhow::Result<Self> {
         let path = path.as_ref();
 
         if path.exists() {
-            println!("🔑 检测到现有身份文件，正在加载: '{}'", path.display());
-            let bytes = fs::read(path)?;
+            info!("🔑 检测到现有身份文件，正在加载: '{}'", path.display());
+            keystore::check_permissions(path, policy)?;
+            let bytes = Zeroizing::new(fs::read(path)?);
+
+            // 加密身份文件 (由 `yuanjing keygen` 生成)：需要口令解密
+            if keystore::is_encrypted_key_file(&bytes) {
+                let key_file: KeyFile = serde_json::from_slice(&bytes)?;
+                let passphrase = keystore::read_passphrase("🔐 请输入身份文件口令: ")?;
+                let seed = key_file.open(&passphrase)?;
+                return Ok(Self::from_seed(&seed));
+            }
             
             // 校验密钥长度 (Ed25519 Seed 为 32 字节)
             if bytes.len() != 32 {
@@ -47,29 +85,99 @@ impl EvidenceSigner {
             }
 
             // 转换 slice 到 array
-            let mut arr = [0u8; 32];
+            let mut arr = Zeroizing::new([0u8; 32]);
             arr.copy_from_slice(&bytes);
             
-            let keypair = SigningKey::from_bytes(&arr);
-            Ok(Self { keypair })
+            Ok(Self::from_seed(&arr))
+        } else if policy.read_only {
+            Err(anyhow::anyhow!("身份文件 '{}' 不存在，只读密钥模式下不会自动生成 (请先用 `yuanjing keygen` 生成)", path.display()))
         } else {
-            println!("✨ 未检测到身份文件，正在初始化新身份: '{}'", path.display());
-            let keypair = SigningKey::generate(&mut OsRng);
+            info!("✨ 未检测到身份文件，正在初始化新身份: '{}'", path.display());
+            warn!("⚠️  自动生成的身份文件为明文 Seed，生产环境请使用 `yuanjing keygen` 生成加密身份文件");
+            let seed = Self::generate_seed();
             
-            // 将私钥 Seed (32 bytes) 写入磁盘
-            // 注意：生产环境中，这个文件权限应设为 600 (只有拥有者可读)
-            fs::write(path, keypair.to_bytes())?;
+            // 将私钥 Seed (32 bytes) 写入磁盘，权限 600 (只有拥有者可读写)
+            keystore::write_secret(path, seed.as_slice())?;
             
-            Ok(Self { keypair })
+            Ok(Self::from_seed(&seed))
+        }
+    }
+
+    /// 生成一个新的随机 Seed (32 字节)，离开作用域时自动擦除
+    pub fn generate_seed() -> Zeroizing<[u8; 32]> {
+        Zeroizing::new(SigningKey::generate(&mut OsRng).to_bytes())
+    }
+
+    /// 从 32 字节 Seed 恢复签名器
+    pub fn from_seed(seed: &[u8; 32]) -> Self {
+        Self { key: SignerKey::Local(LockedKey::new(SigningKey::from_bytes(seed))) }
+    }
+
+    /// 由飞地中的密钥构造签名器
+    pub fn from_enclave(key: EnclaveKey) -> Self {
+        Self { key: SignerKey::Enclave(Box::new(key)) }
+    }
+
+    /// 由 PIV 令牌槽位中的密钥构造签名器
+    pub fn from_piv(key: PivKey) -> Self {
+        Self { key: SignerKey::Piv(Box::new(key)) }
+    }
+
+    /// 派生租户子密钥 (Tenant Subkey)
+    ///
+    /// $$ seed_{tenant} = \text{Blake3-KDF}(ctx, seed_{master} \,||\, tenant\_id) $$
+    /// 每个租户拥有独立的签名身份，彼此的签名不能互相冒充；
+    /// 而运维只需备份一份主密钥即可恢复全部租户身份。
+    ///
+    /// PIV 令牌上的密钥无法派生，租户根密钥改为 `signer.piv.tenant_slots` 中指定的槽位，未指定时报错。
+    pub fn derive_tenant(&self, tenant_id: &str) -> anyhow::Result<Self> {
+        match &self.key {
+            SignerKey::Local(keypair) => {
+                let mut material = Zeroizing::new(keypair.to_bytes().to_vec());
+                material.extend_from_slice(tenant_id.as_bytes());
+                let seed = Zeroizing::new(blake3::derive_key("yuanjing-core tenant signing key v1", &material));
+                Ok(Self::from_seed(&seed))
+            }
+            SignerKey::Enclave(key) => Ok(Self::from_enclave(key.derive(DerivationStep::Tenant(tenant_id.to_string()))?)),
+            SignerKey::Piv(key) => Ok(Self::from_piv(key.tenant(tenant_id)?)),
+        }
+    }
+
+    /// 按 SLIP-0010 路径派生子密钥 (部门 / 设备签名密钥)
+    ///
+    /// 以当前密钥的 Seed 作为 SLIP-0010 的输入种子。
+    pub fn derive_path(&self, path: &DerivationPath) -> anyhow::Result<Self> {
+        match &self.key {
+            SignerKey::Local(keypair) => {
+                let root = Zeroizing::new(keypair.to_bytes());
+                Ok(Self::from_seed(&hdkey::derive_seed(root.as_slice(), path)))
+            }
+            SignerKey::Enclave(key) => Ok(Self::from_enclave(key.derive(DerivationStep::Path(path.to_string()))?)),
+            SignerKey::Piv(_) => Err(anyhow::anyhow!("Keys on a PIV token cannot derive '{}'; department keys need another key_source", path)),
         }
     }
 
+    /// 为子公钥签发背书，供验证方在只知道根公钥的情况下信任子密钥
+    pub fn endorse(&self, endorsement: &KeyEndorsement) -> anyhow::Result<Signature> {
+        self.sign_bytes(&endorsement.canonical_bytes()?)
+    }
+
+    /// 静态验证子密钥背书
+    pub fn verify_endorsement(root_key: &VerifyingKey, endorsement: &KeyEndorsement, signature: &Signature) -> anyhow::Result<bool> {
+        let payload = endorsement.canonical_bytes()?;
+        Ok(root_key.verify(&payload, signature).is_ok())
+    }
+
     /// 导出公钥 (Public Key)
     ///
     /// **作用**: 自证清白。可以将此公钥公开在区块链上或 API 文档中。
     /// 任何人拿到这个公钥，就能验证“这确实是原镜系统签发的证据”。
     pub fn public_key(&self) -> VerifyingKey {
-        self.keypair.verifying_key()
+        match &self.key {
+            SignerKey::Local(keypair) => keypair.verifying_key(),
+            SignerKey::Enclave(key) => key.public_key(),
+            SignerKey::Piv(key) => key.public_key(),
+        }
     }
 
     /// 核心功能：证据签名 (Digital Signature)
@@ -89,7 +197,7 @@ impl EvidenceSigner {
     /// 此处已切换为 **BCS (Binary Canonical Serialization)**。
     /// BCS 保证同一数据结构永远生成相同的字节流，非常适合哈希和签名。
     pub fn sign(&self, evidence: &Evidence) -> anyhow::Result<Signature> {
-        let payload = bcs::to_bytes(evidence)?;
+        let payload = evidence.canonical_bytes()?;
 
         // Ed25519 签名算法 (EdDSA) 本质流程:
         // 1. Hash = SHA512(payload)  -> (压缩信息)
@@ -97,9 +205,7 @@ impl EvidenceSigner {
         // 3. R = r * G               -> (临时公钥点)
         // 4. S = r + Hash(R, Public, msg) * PrivateKey -> (标量混淆)
         // 5. Signature = (R, S)
-        let signature = self.keypair.sign(&payload);
-        
-        Ok(signature)
+        self.sign_bytes(&payload)
     }
 
     /// 静态验证函数 (Verify Signature)
@@ -113,7 +219,7 @@ impl EvidenceSigner {
     /// $$ \text{Right} = R + h \times P = (r \times G) + h \times (k \times G) = (r + h \times k) \times G = S \times G $$
     /// 只要等式成立，就能证明 $S$ 确实是由持有私钥 $k$ 的人计算出的。
     pub fn verify(verification_key: &VerifyingKey, evidence: &Evidence, signature: &Signature) -> anyhow::Result<bool> {
-        let payload = bcs::to_bytes(evidence)?;
+        let payload = evidence.canonical_bytes()?;
         
         // 椭圆曲线验证公式:
         // 验证点 $S \times G$ 是否等于 $R + Hash(...) \times Pub$
@@ -123,4 +229,147 @@ impl EvidenceSigner {
             Err(_) => Ok(false),
         }
     }
+
+    /// 对已规范化的字节直接签名
+    ///
+    /// 入库事务中证据只序列化一次，签名与叶子哈希共用同一份字节。
+    /// 本地密钥不会失败；飞地签名进程不可达、令牌拒签 (未触摸、拔出) 或返回的签名验不过时报错。
+    pub fn sign_bytes(&self, payload: &[u8]) -> anyhow::Result<Signature> {
+        match &self.key {
+            SignerKey::Local(keypair) => Ok(keypair.sign(payload)),
+            SignerKey::Enclave(key) => key.sign(payload),
+            SignerKey::Piv(key) => key.sign_bytes(payload),
+        }
+    }
+
+    /// 静态验证对原始字节的签名
+    pub fn verify_bytes(verification_key: &VerifyingKey, payload: &[u8], signature: &Signature) -> bool {
+        verification_key.verify(payload, signature).is_ok()
+    }
+
+    /// 批量验证 (payload, 签名, 公钥)，返回逐条结果
+    ///
+    /// 先用 Ed25519 批量验证 (随机线性组合，一次多标量乘法) 整体校验，全部通过时比逐条验证快数倍；
+    /// 批量验证只能回答“是否全部有效”，失败时再逐条验证找出无效的那几条。
+    pub fn verify_batch(items: &[(Vec<u8>, Signature, VerifyingKey)]) -> Vec<bool> {
+        let messages: Vec<&[u8]> = items.iter().map(|(payload, _, _)| payload.as_slice()).collect();
+        let signatures: Vec<Signature> = items.iter().map(|(_, signature, _)| *signature).collect();
+        let keys: Vec<VerifyingKey> = items.iter().map(|(_, _, key)| *key).collect();
+        if ed25519_dalek::verify_batch(&messages, &signatures, &keys).is_ok() {
+            return vec![true; items.len()];
+        }
+        items.iter().map(|(payload, signature, key)| Self::verify_bytes(key, payload, signature)).collect()
+    }
+
+    /// 回执签名 (Receipt Signature)
+    ///
+    /// 对 `{evidence_hash, leaf_pos, root, tree_size, timestamp}` 整体签名，
+    /// 防止服务端把合法证据与伪造的位置 / 根拼接在一起返回。
+    /// 签名字节带回执域前缀 (见 [`Receipt::signing_bytes`])，与证据签名互不可替换。
+    pub fn sign_receipt(&self, receipt: &Receipt) -> anyhow::Result<Signature> {
+        let payload = receipt.signing_bytes()?;
+        self.sign_bytes(&payload)
+    }
+
+    /// 静态验证回执签名
+    pub fn verify_receipt(verification_key: &VerifyingKey, receipt: &Receipt, signature: &Signature) -> anyhow::Result<bool> {
+        let payload = receipt.signing_bytes()?;
+        Ok(verification_key.verify(&payload, signature).is_ok())
+    }
+}
+
+/// 派生签名密钥 (Derived Signer)
+///
+/// 一把按部门 / 设备划分的子密钥，连同租户根密钥为它签发的背书。
+pub struct DerivedSigner {
+    /// 配置中的名称 (部门或设备)
+    pub name: String,
+    pub signer: EvidenceSigner,
+    pub endorsement: KeyEndorsement,
+    pub endorsement_signature: Signature,
+}
+
+impl DerivedSigner {
+    /// 从租户根密钥派生子密钥并签发背书
+    pub fn derive(root: &EvidenceSigner, tenant_id: &str, name: &str, path: &DerivationPath) -> anyhow::Result<Self> {
+        let signer = root.derive_path(path)?;
+        let endorsement = KeyEndorsement {
+            tenant_id: tenant_id.to_string(),
+            key_path: path.to_string(),
+            public_key: hex::encode(signer.public_key().to_bytes()),
+        };
+        let endorsement_signature = root.endorse(&endorsement)?;
+        Ok(Self { name: name.to_string(), signer, endorsement, endorsement_signature })
+    }
+
+    /// 派生路径 (规范写法)，与回执中的 `key_path` 一致
+    pub fn key_path(&self) -> &str {
+        &self.endorsement.key_path
+    }
+}
+
+/// 锁定在内存中的签名私钥
+///
+/// - 私钥单独占用一个按页对齐的堆块，地址固定，`mlock` 锁定该页，避免被换出到交换分区或写入休眠镜像；
+///   独占整页是因为 `munlock` 不计数，与其他数据共页时解锁会连带解锁别人；
+/// - 销毁时先用全零 Seed 覆盖 (旧的 `SigningKey` 随之按 `zeroize` 擦除)，再解锁内存页；
+/// - 锁定失败 (如 `RLIMIT_MEMLOCK` 过小、非 Unix 平台) 只打印一次警告，不影响启动。
+struct LockedKey {
+    key: Box<PageAligned>,
+    locked: bool,
+}
+
+/// 按 4 KiB 对齐，大小随之补齐为整页
+#[repr(align(4096))]
+struct PageAligned(SigningKey);
+
+impl LockedKey {
+    fn new(key: SigningKey) -> Self {
+        let key = Box::new(PageAligned(key));
+        let locked = memlock::lock(key.as_ref());
+        if !locked {
+            static WARN: Once = Once::new();
+            WARN.call_once(|| warn!("⚠️  无法锁定密钥内存页 (mlock)，私钥可能被换出到磁盘；可调大 RLIMIT_MEMLOCK"));
+        }
+        Self { key, locked }
+    }
+}
+
+impl Deref for LockedKey {
+    type Target = SigningKey;
+
+    fn deref(&self) -> &SigningKey {
+        &self.key.0
+    }
+}
+
+impl Drop for LockedKey {
+    fn drop(&mut self) {
+        self.key.0 = SigningKey::from_bytes(&[0u8; 32]);
+        if self.locked {
+            memlock::unlock(self.key.as_ref());
+        }
+    }
+}
+
+#[cfg(unix)]
+mod memlock {
+    pub fn lock<T>(value: &T) -> bool {
+        unsafe { libc::mlock(value as *const T as *const libc::c_void, std::mem::size_of::<T>()) == 0 }
+    }
+
+    pub fn unlock<T>(value: &T) {
+        unsafe {
+            libc::munlock(value as *const T as *const libc::c_void, std::mem::size_of::<T>());
+        }
+    }
+}
+
+#[cfg(not(unix))]
+mod memlock {
+    pub fn lock<T>(_value: &T) -> bool {
+        false
+    }
+
+    pub fn unlock<T>(_value: &T) {}
 }
@@ -0,0 +1,262 @@
+use ckb_merkle_mountain_range::MMRStore;
+use serde::{Deserialize, Serialize};
+
+use crate::mmr_store::SledStore;
+
+/// 日志条目哈希的域分隔前缀
+const ENTRY_DOMAIN: &[u8] = b"yuanjing-core signing log entry v1";
+
+/// 检查点叶子的域分隔前缀，使其原文不可能被当作证据 (BCS) 解码
+const CHECKPOINT_DOMAIN: &[u8] = b"yuanjing-core signing log checkpoint v1";
+
+/// 签名日志条目 (Signing Log Entry)
+///
+/// 每次签名存证一条，`prev_hash` 指向上一条的哈希，删改任意一条都会打断后续的链。
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct SigningLogEntry {
+    /// 条目序号，从 0 开始连续递增
+    pub index: u64,
+    /// 被签名证据的叶子哈希 (Hex)
+    pub evidence_hash: String,
+    /// 签名公钥 (Hex)
+    pub key_id: String,
+    /// 派生路径，空串表示租户根密钥
+    pub key_path: String,
+    /// 签名时间 (Unix 秒)，与回执时间一致
+    pub timestamp: i64,
+    /// 发起请求的主体 (API Key 指纹，未启用鉴权时为 `anonymous`)
+    pub principal: String,
+    /// 上一条的哈希 (Hex)，首条为全零
+    pub prev_hash: String,
+}
+
+impl SigningLogEntry {
+    /// 条目哈希：Blake3(域前缀 || BCS(条目))
+    pub fn hash(&self) -> anyhow::Result<[u8; 32]> {
+        let mut hasher = blake3::Hasher::new();
+        hasher.update(ENTRY_DOMAIN);
+        hasher.update(&bcs::to_bytes(self)?);
+        Ok(*hasher.finalize().as_bytes())
+    }
+}
+
+/// 签名日志检查点 (Checkpoint)
+///
+/// 作为一片普通叶子追加进 MMR：此后任何人持有包含它的签名根，
+/// 就能证明“截至第 `index` 条，日志链头是 `head`”。
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct SigningCheckpoint {
+    pub tenant_id: String,
+    /// 覆盖到的最后一条日志序号
+    pub index: u64,
+    /// 该条日志的哈希 (Hex)
+    pub head: String,
+    pub timestamp: i64,
+}
+
+impl SigningCheckpoint {
+    /// 检查点叶子原文 (域前缀 + BCS)，叶子哈希为其 Blake3
+    pub fn canonical_bytes(&self) -> anyhow::Result<Vec<u8>> {
+        let mut bytes = CHECKPOINT_DOMAIN.to_vec();
+        bytes.extend_from_slice(&bcs::to_bytes(self)?);
+        Ok(bytes)
+    }
+
+    /// 从叶子原文还原；不是检查点时返回 None
+    pub fn from_bytes(bytes: &[u8]) -> anyhow::Result<Option<Self>> {
+        bytes
+            .strip_prefix(CHECKPOINT_DOMAIN)
+            .map(|payload| Ok(bcs::from_bytes(payload)?))
+            .transpose()
+    }
+}
+
+/// 已写入 MMR 的检查点位置
+#[derive(Debug, Clone, Copy, Serialize)]
+pub struct CheckpointLocation {
+    /// 覆盖到的最后一条日志序号
+    pub index: u64,
+    /// 检查点叶子在 MMR 中的位置
+    pub pos: u64,
+}
+
+/// 签名日志的只读视图，读端 (API) 通过快照获取
+#[derive(Clone)]
+pub struct SigningLogView {
+    entries: sled::Tree,
+    checkpoints: sled::Tree,
+}
+
+impl SigningLogView {
+    pub fn open(store: &SledStore) -> anyhow::Result<Self> {
+        Ok(Self { entries: store.tree("signing_log")?, checkpoints: store.tree("signing_log_checkpoints")? })
+    }
+
+    /// 日志条数
+    pub fn len(&self) -> anyhow::Result<u64> {
+        Ok(self.last()?.map(|entry| entry.index + 1).unwrap_or(0))
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// 从 `from` 开始最多 `limit` 条
+    pub fn entries(&self, from: u64, limit: usize) -> anyhow::Result<Vec<SigningLogEntry>> {
+        self.entries
+            .range(from.to_be_bytes()..)
+            .values()
+            .take(limit)
+            .map(|value| Ok(bcs::from_bytes(&value?)?))
+            .collect()
+    }
+
+    /// 全部检查点 (按日志序号升序)
+    pub fn checkpoints(&self) -> anyhow::Result<Vec<CheckpointLocation>> {
+        self.checkpoints
+            .iter()
+            .map(|item| {
+                let (key, value) = item?;
+                Ok(CheckpointLocation { index: decode_u64(&key)?, pos: decode_u64(&value)? })
+            })
+            .collect()
+    }
+
+    fn last(&self) -> anyhow::Result<Option<SigningLogEntry>> {
+        self.entries
+            .last()?
+            .map(|(_, value)| Ok(bcs::from_bytes(&value)?))
+            .transpose()
+    }
+}
+
+/// 模块：签名审计日志 (Signing Audit Log)
+///
+/// **为什么需要**: 回执证明“签过什么”，却无法证明“没签过别的”。
+/// 运维人员手握私钥，完全可以签出一份回执交给他人，再删掉记录。
+/// 签名日志把每一次签名 (证据哈希、签名公钥、时间、请求主体) 串成哈希链，
+/// 并定期把链头作为检查点叶子写进 MMR 本身：
+/// - 检查点之前的日志一旦删改，重算出的链头就与 MMR 中的检查点不符；
+/// - MMR 是只追加的，检查点叶子也无法在不改变已签名根的前提下抹去。
+///
+/// 日志条目在签名**之前**落盘：凡是产生过的签名都有记录。
+/// 反过来，签名后入库失败时会留下一条没有对应叶子的记录，如实反映“签过但未入库”。
+pub struct SigningLog {
+    view: SigningLogView,
+    next_index: u64,
+    head: [u8; 32],
+    /// 最近一次检查点覆盖到的日志条数
+    checkpointed: u64,
+}
+
+impl SigningLog {
+    pub fn open(store: &SledStore) -> anyhow::Result<Self> {
+        let view = SigningLogView::open(store)?;
+        let (next_index, head) = match view.last()? {
+            Some(entry) => (entry.index + 1, entry.hash()?),
+            None => (0, [0u8; 32]),
+        };
+        let checkpointed = view.checkpoints.last()?.map(|(key, _)| decode_u64(&key)).transpose()?.map(|index| index + 1).unwrap_or(0);
+        Ok(Self { view, next_index, head, checkpointed })
+    }
+
+    /// 追加一条签名记录；`durable` 为 true 时立即落盘
+    pub fn append(
+        &mut self,
+        evidence_hash: [u8; 32],
+        key_id: &ed25519_dalek::VerifyingKey,
+        key_path: &str,
+        timestamp: i64,
+        principal: &str,
+        durable: bool,
+    ) -> anyhow::Result<SigningLogEntry> {
+        let entry = SigningLogEntry {
+            index: self.next_index,
+            evidence_hash: hex::encode(evidence_hash),
+            key_id: hex::encode(key_id.to_bytes()),
+            key_path: key_path.to_string(),
+            timestamp,
+            principal: principal.to_string(),
+            prev_hash: hex::encode(self.head),
+        };
+        let hash = entry.hash()?;
+        self.view.entries.insert(entry.index.to_be_bytes(), bcs::to_bytes(&entry)?)?;
+        if durable {
+            self.view.entries.flush()?;
+        }
+        self.next_index += 1;
+        self.head = hash;
+        Ok(entry)
+    }
+
+    /// 自上次检查点以来的新条目数
+    pub fn pending(&self) -> u64 {
+        self.next_index - self.checkpointed
+    }
+
+    /// 为当前链头生成检查点；没有新条目时返回 None
+    pub fn checkpoint(&self, tenant_id: &str, timestamp: i64) -> Option<SigningCheckpoint> {
+        (self.pending() > 0).then(|| SigningCheckpoint {
+            tenant_id: tenant_id.to_string(),
+            index: self.next_index - 1,
+            head: hex::encode(self.head),
+            timestamp,
+        })
+    }
+
+    /// 记录检查点叶子已写入 MMR 的位置
+    pub fn record_checkpoint(&mut self, checkpoint: &SigningCheckpoint, pos: u64) -> anyhow::Result<()> {
+        self.view.checkpoints.insert(checkpoint.index.to_be_bytes(), &pos.to_be_bytes())?;
+        self.view.checkpoints.flush()?;
+        self.checkpointed = checkpoint.index + 1;
+        Ok(())
+    }
+
+    /// 完整校验：逐条核对序号与哈希链，并核对每个检查点叶子仍在 MMR 中、且与重算的链头一致
+    ///
+    /// 返回 (日志条数, 检查点数)。
+    pub fn verify(&self, store: &SledStore, mmr_size: u64) -> anyhow::Result<(u64, usize)> {
+        let mut heads = Vec::new();
+        let mut prev = [0u8; 32];
+        for (expected, value) in (0u64..).zip(self.view.entries.iter().values()) {
+            let entry: SigningLogEntry = bcs::from_bytes(&value?)?;
+            if entry.index != expected {
+                return Err(anyhow::anyhow!("Signing log gap: expected entry {}, found {}", expected, entry.index));
+            }
+            if entry.prev_hash != hex::encode(prev) {
+                return Err(anyhow::anyhow!("Signing log chain broken at entry {}", entry.index));
+            }
+            prev = entry.hash()?;
+            heads.push(prev);
+        }
+
+        let checkpoints = self.view.checkpoints()?;
+        for location in &checkpoints {
+            if location.pos >= mmr_size {
+                return Err(anyhow::anyhow!("Signing log checkpoint for entry {} points beyond the MMR (pos {})", location.index, location.pos));
+            }
+            let bytes = store
+                .get_evidence_bytes(location.pos)?
+                .ok_or_else(|| anyhow::anyhow!("Signing log checkpoint leaf at pos {} is missing", location.pos))?;
+            let leaf = store
+                .get_elem(location.pos)
+                .map_err(|e| anyhow::anyhow!("MMR get_elem error: {}", e))?;
+            if leaf != Some(*blake3::hash(&bytes).as_bytes()) {
+                return Err(anyhow::anyhow!("Signing log checkpoint leaf at pos {} does not match the MMR", location.pos));
+            }
+            let checkpoint = SigningCheckpoint::from_bytes(&bytes)?
+                .ok_or_else(|| anyhow::anyhow!("Leaf at pos {} is not a signing log checkpoint", location.pos))?;
+            let head = heads
+                .get(checkpoint.index as usize)
+                .ok_or_else(|| anyhow::anyhow!("Signing log was truncated before checkpointed entry {}", checkpoint.index))?;
+            if checkpoint.index != location.index || checkpoint.head != hex::encode(head) {
+                return Err(anyhow::anyhow!("Signing log diverges from the checkpoint committed at pos {}", location.pos));
+            }
+        }
+        Ok((heads.len() as u64, checkpoints.len()))
+    }
+}
+
+fn decode_u64(bytes: &[u8]) -> anyhow::Result<u64> {
+    Ok(u64::from_be_bytes(bytes.try_into().map_err(|_| anyhow::anyhow!("Invalid u64 key"))?))
+}
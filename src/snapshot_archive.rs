@@ -0,0 +1,567 @@
+use ckb_merkle_mountain_range::MMR;
+use ed25519_dalek::{Signature, VerifyingKey};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeSet, HashMap};
+use std::fs::{File, OpenOptions};
+use std::io::{BufReader, BufWriter, Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+
+use crate::mmr_store::{MergeBlake3, SledStore, StoreSnapshot, DEFAULT_TENANT};
+use crate::ops_log::{self, OpsLog, OPS_LOG_FILE};
+use crate::position::leaf_count;
+use crate::signer::EvidenceSigner;
+use crate::stats::TENANT_TREES;
+
+/// 归档格式的当前版本
+pub const SNAPSHOT_VERSION: u32 = 1;
+
+/// 归档文件的扩展名
+pub const SNAPSHOT_EXTENSION: &str = "yjsnap";
+
+/// 文件头与文件尾的魔数
+const MAGIC: &[u8; 8] = b"YJSNAP\0\0";
+
+/// 清单签名的域分隔前缀，避免与证据 / 回执 / 树头签名混用
+const MANIFEST_DOMAIN: &[u8] = b"yuanjing-core snapshot manifest v1";
+
+/// sled 默认 tree 的名字 (默认租户的 MMR 节点所在)
+const DEFAULT_TREE: &str = "__sled__default";
+
+/// 存放根历史的 tree：签名树头归档、公证回执与透明日志条目
+const ROOT_HISTORY_TREES: &[&str] = &["tree_heads", "notary_receipts", "timestamp_tokens", "rekor_entries"];
+
+/// 可由原文重建的二级索引
+const INDEX_TREES: &[&str] = &["seq_index", "leaf_index", "nonces", "tag_index", "evidence_attrs", "evidence_order"];
+
+/// 段的类别 (各类段的编码相同；运维日志段不写进 sled，恢复为单独的文件)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SegmentKind {
+    /// MMR 节点、元数据、原文、回执等主数据
+    Store,
+    /// 根历史
+    RootHistory,
+    /// 二级索引
+    Index,
+    /// 运维日志 (记录为 `序号 (u64 BE) -> 该行原文`)：检查点叶子在 MMR 中，日志须与库一起恢复
+    OpsLog,
+}
+
+/// 清单中的一个段：一棵 sled tree 的全部键值
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Segment {
+    /// sled 中的 tree 名 (含租户前缀；默认租户的节点在 `__sled__default`)；运维日志段为 `ops.log`
+    pub tree: String,
+    /// 所属租户；不属于任何租户的全局 tree 为 None
+    pub tenant_id: Option<String>,
+    pub kind: SegmentKind,
+    /// 段在归档中的起始偏移与字节数
+    pub offset: u64,
+    pub length: u64,
+    pub entries: u64,
+    /// 段字节的 blake3 (Hex)
+    pub blake3: String,
+}
+
+/// 归档时各租户的树头，恢复后据此核对重建出的根
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TenantHead {
+    pub tenant_id: String,
+    pub mmr_size: u64,
+    pub leaves: u64,
+    /// 树根 (Hex)；空树为 None
+    pub root: Option<String>,
+}
+
+/// 归档清单
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SnapshotManifest {
+    pub version: u32,
+    /// 归档时间 (Unix 秒)
+    pub created_at: i64,
+    pub tenants: Vec<TenantHead>,
+    /// 按偏移排列，首尾相接地覆盖文件头与清单之间的全部字节
+    pub segments: Vec<Segment>,
+}
+
+impl SnapshotManifest {
+    /// 清单的规范化字节 (域前缀 + BCS)，签名与验签均基于此
+    pub fn canonical_bytes(&self) -> anyhow::Result<Vec<u8>> {
+        let mut bytes = MANIFEST_DOMAIN.to_vec();
+        bytes.extend_from_slice(&bcs::to_bytes(self)?);
+        Ok(bytes)
+    }
+
+    pub fn entries(&self) -> u64 {
+        self.segments.iter().map(|segment| segment.entries).sum()
+    }
+}
+
+/// 带归档者签名的清单，即写入归档尾部的形式 (JSON)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SignedManifest {
+    pub manifest: SnapshotManifest,
+    /// 归档者 (服务主身份) 公钥 (Hex)
+    pub creator_public_key: String,
+    /// 对 `manifest` 规范字节的签名 (Hex)
+    pub signature: String,
+}
+
+impl SignedManifest {
+    /// 校验签名；`trusted` 给出时还要求归档者就是该公钥，缺省时只能证明归档自洽
+    pub fn verify(&self, trusted: Option<&VerifyingKey>) -> anyhow::Result<()> {
+        let key_bytes: [u8; 32] = hex::decode(&self.creator_public_key)?
+            .try_into()
+            .map_err(|_| anyhow::anyhow!("Invalid creator public key length"))?;
+        let creator = VerifyingKey::from_bytes(&key_bytes)?;
+        if let Some(trusted) = trusted {
+            if creator != *trusted {
+                return Err(anyhow::anyhow!("Snapshot was signed by an untrusted key {}", self.creator_public_key));
+            }
+        }
+        let signature = Signature::from_slice(&hex::decode(&self.signature)?)
+            .map_err(|_| anyhow::anyhow!("Invalid snapshot signature encoding"))?;
+        if !EvidenceSigner::verify_bytes(&creator, &self.manifest.canonical_bytes()?, &signature) {
+            return Err(anyhow::anyhow!("Snapshot manifest signature is invalid"));
+        }
+        Ok(())
+    }
+}
+
+/// 模块：快照归档 (`.yjsnap`)
+///
+/// **为什么需要**: 备份本身也可能被篡改：拿到备份盘的人改掉一条原文或一个 MMR 节点，
+/// 恢复后服务照常启动，直到有人做自审才会发现，而那时已分不清是线上库还是备份出了问题。
+/// 归档把数据库的每棵 tree 写成一个段，清单记下每段的 blake3 与归档时各租户的树根，并由主身份签名：
+/// 任何一个字节被改、段被删除或夹带、清单被替换，`verify` 都会拒绝；`restore` 在写库之前先完整校验，
+/// 写完再重建各租户的根与清单比对。
+///
+/// 文件布局：`魔数 | 版本 (u32 BE) | 段... | 清单 JSON | 清单长度 (u64 BE) | 魔数`；
+/// 段内每条记录为 `键长 (u32 BE) | 键 | 值长 (u32 BE) | 值`。清单在文件尾，归档时只需顺序写一遍。
+/// 数据按落盘字节原样归档：静态加密的原文仍是密文，恢复后须配同一把主密钥。
+///
+/// `ops_log` 为运维日志文件：它的检查点叶子在库里，只恢复库而沿用别处的日志，启动时检查点就对不上。
+/// 给出且存在时先核对日志中的检查点与库一致，再作为最后一段归档。
+pub fn create(store: &SledStore, signer: &EvidenceSigner, ops_log: Option<&Path>, out: &Path) -> anyhow::Result<SignedManifest> {
+    let db = store.db();
+    let mut trees: Vec<(String, Option<String>, SegmentKind)> = db
+        .tree_names()
+        .iter()
+        .map(|name| {
+            let name = String::from_utf8_lossy(name).into_owned();
+            let (tenant_id, kind) = classify(&name);
+            (name, tenant_id, kind)
+        })
+        .collect();
+    trees.sort_by(|a, b| a.0.cmp(&b.0));
+
+    let mut tenants = Vec::new();
+    let tenant_ids: BTreeSet<&str> = trees.iter().filter_map(|(_, tenant_id, _)| tenant_id.as_deref()).collect();
+    for tenant_id in tenant_ids {
+        tenants.push(tenant_head(&store.for_tenant(tenant_id)?)?);
+    }
+    let ops_lines = match ops_log.filter(|path| path.exists()) {
+        Some(path) => {
+            verify_ops_log(store, path, &tenants)?;
+            Some(ops_log::read_lines(path)?)
+        }
+        None => None,
+    };
+
+    // 先写到临时文件，完整落盘后再改名，中途失败不会留下一个看似完整的归档
+    let partial = partial_path(out);
+    let mut writer = BufWriter::new(File::create(&partial)?);
+    writer.write_all(MAGIC)?;
+    writer.write_all(&SNAPSHOT_VERSION.to_be_bytes())?;
+    let mut offset = (MAGIC.len() + 4) as u64;
+    let mut segments = Vec::with_capacity(trees.len() + 1);
+    for (name, tenant_id, kind) in trees {
+        let records = db.open_tree(&name)?.iter().map(|entry| entry.map_err(anyhow::Error::from));
+        let segment = write_segment(&mut writer, Segment::at(name, tenant_id, kind, offset), records)?;
+        offset += segment.length;
+        segments.push(segment);
+    }
+    if let Some(lines) = ops_lines {
+        let records = lines.iter().enumerate().map(|(index, line)| Ok(((index as u64).to_be_bytes(), line.as_bytes())));
+        let segment = write_segment(&mut writer, Segment::at(OPS_LOG_FILE.to_string(), None, SegmentKind::OpsLog, offset), records)?;
+        segments.push(segment);
+    }
+
+    let manifest = SnapshotManifest { version: SNAPSHOT_VERSION, created_at: chrono::Utc::now().timestamp(), tenants, segments };
+    let signature = signer.sign_bytes(&manifest.canonical_bytes()?)?;
+    let signed = SignedManifest {
+        manifest,
+        creator_public_key: hex::encode(signer.public_key().to_bytes()),
+        signature: hex::encode(signature.to_bytes()),
+    };
+    let manifest_json = serde_json::to_vec(&signed)?;
+    writer.write_all(&manifest_json)?;
+    writer.write_all(&(manifest_json.len() as u64).to_be_bytes())?;
+    writer.write_all(MAGIC)?;
+    writer.into_inner().map_err(|e| e.into_error())?.sync_all()?;
+    std::fs::rename(&partial, out)?;
+    Ok(signed)
+}
+
+/// 把一组记录写成一个段，返回补全了长度、条目数与哈希的段
+fn write_segment<K: AsRef<[u8]>, V: AsRef<[u8]>>(
+    writer: &mut impl Write,
+    mut segment: Segment,
+    records: impl Iterator<Item = anyhow::Result<(K, V)>>,
+) -> anyhow::Result<Segment> {
+    let mut hasher = blake3::Hasher::new();
+    for record in records {
+        let (key, value) = record?;
+        for part in [key.as_ref(), value.as_ref()] {
+            let len = u32::try_from(part.len()).map_err(|_| anyhow::anyhow!("Record in tree '{}' is too large", segment.tree))?;
+            for bytes in [&len.to_be_bytes()[..], part] {
+                writer.write_all(bytes)?;
+                hasher.update(bytes);
+            }
+            segment.length += 4 + part.len() as u64;
+        }
+        segment.entries += 1;
+    }
+    segment.blake3 = hasher.finalize().to_hex().to_string();
+    Ok(segment)
+}
+
+/// 完整校验归档：文件头尾、清单签名、段的排布，以及逐段重算 blake3 与记录分帧
+///
+/// 返回通过校验的清单；任一项不符即报错。
+pub fn verify(path: &Path, trusted: Option<&VerifyingKey>) -> anyhow::Result<SignedManifest> {
+    let mut archive = Archive::open(path, trusted)?;
+    for segment in &archive.manifest.manifest.segments.clone() {
+        archive.read_segment(segment, |_, _| Ok(()))?;
+    }
+    Ok(archive.manifest)
+}
+
+/// 把归档恢复到一个新的数据库目录
+///
+/// 目标目录须不存在或为空；先完整校验归档，再逐段写入 (写入时再次核对段哈希，防止校验后文件被替换)，
+/// 最后由恢复出的 MMR 节点重建各租户的根，与清单记录的树头比对。
+/// 归档带运维日志且给出了 `ops_log` 时，日志恢复到该文件 (须不存在)，并核对其中的检查点与恢复出的库一致；
+/// `ops_log` 为 None (目标节点不记运维日志) 时不恢复日志。任一步失败都会删除写了一半的目录与日志。
+pub fn restore(path: &Path, db_path: &str, ops_log: Option<&Path>, trusted: Option<&VerifyingKey>) -> anyhow::Result<SignedManifest> {
+    let target = Path::new(db_path);
+    if target.exists() && std::fs::read_dir(target)?.next().is_some() {
+        return Err(anyhow::anyhow!("Restore target '{}' is not empty; restore into a fresh directory", db_path));
+    }
+    if let Some(ops_log) = ops_log.filter(|ops_log| ops_log.exists()) {
+        return Err(anyhow::anyhow!("Ops log '{}' already exists; restore next to a fresh ops log", ops_log.display()));
+    }
+    let manifest = verify(path, trusted)?;
+    let result = restore_into(path, db_path, ops_log, trusted, &manifest);
+    if result.is_err() {
+        let _ = std::fs::remove_dir_all(target);
+        if let Some(ops_log) = ops_log {
+            let _ = std::fs::remove_file(ops_log);
+        }
+    }
+    result.map(|_| manifest)
+}
+
+fn restore_into(path: &Path, db_path: &str, ops_log: Option<&Path>, trusted: Option<&VerifyingKey>, expected: &SignedManifest) -> anyhow::Result<()> {
+    let mut archive = Archive::open(path, trusted)?;
+    if archive.manifest.signature != expected.signature {
+        return Err(anyhow::anyhow!("Snapshot '{}' changed while it was being restored", path.display()));
+    }
+    let store = SledStore::new(db_path)?;
+    let db = store.db();
+    let mut ops_lines = None;
+    for segment in &archive.manifest.manifest.segments.clone() {
+        if segment.kind == SegmentKind::OpsLog {
+            let mut lines = Vec::new();
+            archive.read_segment(segment, |_, line| {
+                lines.push(line.to_vec());
+                Ok(())
+            })?;
+            ops_lines = Some(lines);
+            continue;
+        }
+        let tree = db.open_tree(&segment.tree)?;
+        archive.read_segment(segment, |key, value| {
+            tree.insert(key, value)?;
+            Ok(())
+        })?;
+    }
+    db.flush()?;
+
+    for head in &archive.manifest.manifest.tenants {
+        let restored = tenant_head(&store.for_tenant(&head.tenant_id)?)?;
+        if restored.mmr_size != head.mmr_size || restored.root != head.root {
+            return Err(anyhow::anyhow!(
+                "Tenant '{}' restored to size {} root {}, manifest records size {} root {}",
+                head.tenant_id,
+                restored.mmr_size,
+                restored.root.as_deref().unwrap_or("(empty)"),
+                head.mmr_size,
+                head.root.as_deref().unwrap_or("(empty)")
+            ));
+        }
+    }
+
+    if let (Some(ops_log), Some(lines)) = (ops_log, ops_lines) {
+        if let Some(parent) = ops_log.parent().filter(|parent| !parent.as_os_str().is_empty()) {
+            std::fs::create_dir_all(parent)?;
+        }
+        let mut file = OpenOptions::new().write(true).create_new(true).open(ops_log)?;
+        for line in lines {
+            file.write_all(&line)?;
+            file.write_all(b"\n")?;
+        }
+        file.sync_all()?;
+        verify_ops_log(&store, ops_log, &archive.manifest.manifest.tenants)?;
+    }
+    Ok(())
+}
+
+/// 核对运维日志中记录的检查点与库中的叶子一致 (只核对 `tenants` 中的租户)，返回核对过的检查点数
+fn verify_ops_log(store: &SledStore, ops_log: &Path, tenants: &[TenantHead]) -> anyhow::Result<usize> {
+    let mut stores = HashMap::new();
+    for head in tenants {
+        stores.insert(head.tenant_id.as_str(), store.for_tenant(&head.tenant_id)?);
+    }
+    OpsLog::open(ops_log)?
+        .verify_checkpoints(|tenant_id| stores.get(tenant_id).map(|store| StoreSnapshot::open_detached(store.clone())).transpose())
+        .map_err(|e| anyhow::anyhow!("Ops log '{}' does not match the store: {}", ops_log.display(), e))
+}
+
+/// 打开并校验过清单的归档文件
+struct Archive {
+    reader: BufReader<File>,
+    manifest: SignedManifest,
+}
+
+impl Archive {
+    fn open(path: &Path, trusted: Option<&VerifyingKey>) -> anyhow::Result<Self> {
+        let mut file = File::open(path).map_err(|e| anyhow::anyhow!("Cannot open snapshot '{}': {}", path.display(), e))?;
+        let file_len = file.metadata()?.len();
+        let header_len = (MAGIC.len() + 4) as u64;
+        let footer_len = (8 + MAGIC.len()) as u64;
+        if file_len < header_len + footer_len {
+            return Err(anyhow::anyhow!("'{}' is too short to be a snapshot", path.display()));
+        }
+
+        let mut header = [0u8; 12];
+        file.read_exact(&mut header)?;
+        if &header[..8] != MAGIC {
+            return Err(anyhow::anyhow!("'{}' is not a .{} snapshot", path.display(), SNAPSHOT_EXTENSION));
+        }
+        let version = u32::from_be_bytes(header[8..].try_into()?);
+        if version != SNAPSHOT_VERSION {
+            return Err(anyhow::anyhow!("Unsupported snapshot version {}", version));
+        }
+
+        let mut footer = [0u8; 16];
+        file.seek(SeekFrom::End(-(footer_len as i64)))?;
+        file.read_exact(&mut footer)?;
+        if &footer[8..] != MAGIC {
+            return Err(anyhow::anyhow!("Snapshot '{}' is truncated (missing trailer)", path.display()));
+        }
+        let manifest_len = u64::from_be_bytes(footer[..8].try_into()?);
+        let manifest_offset = (file_len - footer_len)
+            .checked_sub(manifest_len)
+            .filter(|offset| *offset >= header_len)
+            .ok_or_else(|| anyhow::anyhow!("Snapshot manifest length {} exceeds the file", manifest_len))?;
+        let mut manifest_json = vec![0u8; manifest_len as usize];
+        file.seek(SeekFrom::Start(manifest_offset))?;
+        file.read_exact(&mut manifest_json)?;
+        let manifest: SignedManifest =
+            serde_json::from_slice(&manifest_json).map_err(|e| anyhow::anyhow!("Snapshot manifest is malformed: {}", e))?;
+        if manifest.manifest.version != version {
+            return Err(anyhow::anyhow!("Snapshot manifest version {} does not match header version {}", manifest.manifest.version, version));
+        }
+        manifest.verify(trusted)?;
+
+        // 段须首尾相接地铺满文件头与清单之间，不允许空洞、重叠或夹带
+        let mut expected = header_len;
+        for segment in &manifest.manifest.segments {
+            if segment.offset != expected {
+                return Err(anyhow::anyhow!("Segment '{}' starts at {}, expected {}", segment.tree, segment.offset, expected));
+            }
+            expected = expected
+                .checked_add(segment.length)
+                .ok_or_else(|| anyhow::anyhow!("Segment '{}' length overflows", segment.tree))?;
+        }
+        if expected != manifest_offset {
+            return Err(anyhow::anyhow!("Segments end at {}, but the manifest starts at {}", expected, manifest_offset));
+        }
+        Ok(Self { reader: BufReader::new(file), manifest })
+    }
+
+    /// 读出一个段的全部记录交给 `visit`，读完后核对记录数与 blake3
+    fn read_segment(&mut self, segment: &Segment, mut visit: impl FnMut(&[u8], &[u8]) -> anyhow::Result<()>) -> anyhow::Result<()> {
+        self.reader.seek(SeekFrom::Start(segment.offset))?;
+        let mut hasher = blake3::Hasher::new();
+        let (mut remaining, mut entries) = (segment.length, 0u64);
+        let mut read_part = |reader: &mut BufReader<File>, remaining: &mut u64| -> anyhow::Result<Vec<u8>> {
+            if *remaining < 4 {
+                return Err(anyhow::anyhow!("Segment '{}' has a truncated record", segment.tree));
+            }
+            let mut len = [0u8; 4];
+            reader.read_exact(&mut len)?;
+            hasher.update(&len);
+            let len = u32::from_be_bytes(len) as u64;
+            if *remaining - 4 < len {
+                return Err(anyhow::anyhow!("Segment '{}' has a truncated record", segment.tree));
+            }
+            let mut part = vec![0u8; len as usize];
+            reader.read_exact(&mut part)?;
+            hasher.update(&part);
+            *remaining -= 4 + len;
+            Ok(part)
+        };
+        while remaining > 0 {
+            let key = read_part(&mut self.reader, &mut remaining)?;
+            let value = read_part(&mut self.reader, &mut remaining)?;
+            visit(&key, &value)?;
+            entries += 1;
+        }
+        if entries != segment.entries || hasher.finalize().to_hex().as_str() != segment.blake3 {
+            return Err(anyhow::anyhow!("Segment '{}' does not match the manifest (content was altered)", segment.tree));
+        }
+        Ok(())
+    }
+}
+
+impl Segment {
+    /// 从 `offset` 开始的空段，由 `write_segment` 补全
+    fn at(tree: String, tenant_id: Option<String>, kind: SegmentKind, offset: u64) -> Self {
+        Self { tree, tenant_id, kind, offset, length: 0, entries: 0, blake3: String::new() }
+    }
+}
+
+/// 按 tree 名判断所属租户与段类别
+fn classify(name: &str) -> (Option<String>, SegmentKind) {
+    let (tenant_id, tree) = match name.strip_prefix("tenant/").and_then(|rest| rest.split_once('/')) {
+        Some((tenant_id, tree)) => (Some(tenant_id.to_string()), tree),
+        None if name == DEFAULT_TREE || TENANT_TREES.contains(&name) => (Some(DEFAULT_TENANT.to_string()), name),
+        None => (None, name),
+    };
+    let kind = if ROOT_HISTORY_TREES.contains(&tree) {
+        SegmentKind::RootHistory
+    } else if INDEX_TREES.contains(&tree) {
+        SegmentKind::Index
+    } else {
+        SegmentKind::Store
+    };
+    (tenant_id, kind)
+}
+
+/// 由落盘的 MMR 节点算出租户当前的树头
+fn tenant_head(store: &SledStore) -> anyhow::Result<TenantHead> {
+    let mmr_size = store.get_meta_size();
+    let root = if mmr_size == 0 {
+        None
+    } else {
+        let mmr = MMR::<[u8; 32], MergeBlake3, _>::new(mmr_size, store);
+        Some(hex::encode(mmr.get_root().map_err(|e| anyhow::anyhow!("Tenant '{}': MMR get_root error: {}", store.tenant_id(), e))?))
+    };
+    Ok(TenantHead { tenant_id: store.tenant_id().to_string(), mmr_size, leaves: leaf_count(mmr_size), root })
+}
+
+fn partial_path(out: &Path) -> PathBuf {
+    let mut name = out.as_os_str().to_owned();
+    name.push(".partial");
+    PathBuf::from(name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mmr_store::{EvidenceStore, StoreOptions};
+    use crate::ops_log::{OpsCheckpoint, OpsEvent};
+
+    /// 测试用的临时目录，丢弃时删除
+    struct TempDir(PathBuf);
+
+    impl TempDir {
+        fn new(name: &str) -> Self {
+            let path = std::env::temp_dir().join(format!("yuanjing-snapshot-{}-{}", name, std::process::id()));
+            let _ = std::fs::remove_dir_all(&path);
+            std::fs::create_dir_all(&path).unwrap();
+            Self(path)
+        }
+
+        fn path(&self, name: &str) -> PathBuf {
+            self.0.join(name)
+        }
+    }
+
+    impl Drop for TempDir {
+        fn drop(&mut self) {
+            let _ = std::fs::remove_dir_all(&self.0);
+        }
+    }
+
+    /// 像服务启动时那样记一条事件，并把链头作为检查点写进 MMR
+    fn checkpoint(store: &mut EvidenceStore, ops_log: &OpsLog) -> u64 {
+        ops_log.record(OpsEvent::Startup { version: "test".to_string() }).unwrap();
+        let (entries, head) = ops_log.head().unwrap();
+        let checkpoint = OpsCheckpoint { index: entries - 1, head: hex::encode(head), timestamp: 0 };
+        let pos = store.append_ops_checkpoint(&checkpoint).unwrap();
+        ops_log
+            .record(OpsEvent::Checkpoint { tenant_id: DEFAULT_TENANT.to_string(), covers: checkpoint.index, head: checkpoint.head, pos })
+            .unwrap();
+        pos
+    }
+
+    /// 归档后原库继续写入检查点，再恢复到新目录：恢复出的库与归档时的运维日志成对，重新打开后检查点全部对得上
+    #[test]
+    fn restored_store_reopens_with_its_ops_log() {
+        let dir = TempDir::new("reopen");
+        let (db_path, archive) = (dir.path("db"), dir.path("backup.yjsnap"));
+        let signer = EvidenceSigner::from_seed(&[7u8; 32]);
+        let sled = SledStore::new(db_path.to_str().unwrap()).unwrap();
+        let mut store = EvidenceStore::open(sled.clone(), &StoreOptions::default());
+        let ops_path = db_path.join(OPS_LOG_FILE);
+        let ops_log = OpsLog::open(&ops_path).unwrap();
+        checkpoint(&mut store, &ops_log);
+
+        let signed = create(&sled, &signer, Some(&ops_path), &archive).unwrap();
+        assert!(signed.manifest.segments.iter().any(|segment| segment.kind == SegmentKind::OpsLog));
+        assert_eq!(checkpoint(&mut store, &ops_log), 1);
+
+        let restored = dir.path("restored");
+        let restored_ops = restored.join(OPS_LOG_FILE);
+        restore(&archive, restored.to_str().unwrap(), Some(&restored_ops), Some(&signer.public_key())).unwrap();
+
+        let reopened = SledStore::new(restored.to_str().unwrap()).unwrap();
+        let store = EvidenceStore::open(reopened.clone(), &StoreOptions::default());
+        assert_eq!(store.mmr_size(), 1);
+        let verified = OpsLog::open(&restored_ops)
+            .unwrap()
+            .verify_checkpoints(|tenant_id| Ok((tenant_id == DEFAULT_TENANT).then(|| store.snapshot())))
+            .unwrap();
+        assert_eq!(verified, 1);
+    }
+
+    /// 运维日志与库对不上时不归档；恢复不会覆盖已有的运维日志
+    #[test]
+    fn create_and_restore_refuse_a_mismatched_ops_log() {
+        let dir = TempDir::new("mismatch");
+        let (db_path, archive) = (dir.path("db"), dir.path("backup.yjsnap"));
+        let signer = EvidenceSigner::from_seed(&[7u8; 32]);
+        let sled = SledStore::new(db_path.to_str().unwrap()).unwrap();
+        let mut store = EvidenceStore::open(sled.clone(), &StoreOptions::default());
+        let ops_log = OpsLog::open(&db_path.join(OPS_LOG_FILE)).unwrap();
+        checkpoint(&mut store, &ops_log);
+
+        // 另一个库的日志：检查点指向本库中不存在的位置
+        let other = dir.path("other.log");
+        let other_log = OpsLog::open(&other).unwrap();
+        other_log
+            .record(OpsEvent::Checkpoint { tenant_id: DEFAULT_TENANT.to_string(), covers: 0, head: hex::encode([0u8; 32]), pos: 7 })
+            .unwrap();
+        assert!(create(&sled, &signer, Some(&other), &archive).is_err());
+        assert!(!archive.exists());
+
+        create(&sled, &signer, Some(&db_path.join(OPS_LOG_FILE)), &archive).unwrap();
+        let restored = dir.path("restored");
+        let error = restore(&archive, restored.to_str().unwrap(), Some(&other), None).unwrap_err();
+        assert!(error.to_string().contains("already exists"));
+        assert!(!restored.exists());
+    }
+}
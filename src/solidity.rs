@@ -0,0 +1,88 @@
+use crate::proof::verify_proof;
+
+/// 链上校验合约的源码 (`contracts/YuanjingMmrVerifier.sol`)，`/v1/contracts/YuanjingMmrVerifier.sol` 原样提供
+pub const MMR_VERIFIER_SOURCE: &str = include_str!("../contracts/YuanjingMmrVerifier.sol");
+
+/// `verifyInclusion(uint64,uint64,bytes32,bytes32[],bytes32)` 的函数选择器
+pub const VERIFY_INCLUSION_SELECTOR: [u8; 4] = [0x6e, 0x0f, 0xd1, 0x06];
+/// `verifyAnchored(uint64,uint64,bytes32,bytes32[])` 的函数选择器
+pub const VERIFY_ANCHORED_SELECTOR: [u8; 4] = [0x76, 0xd7, 0x35, 0x60];
+/// `anchorRoot(uint64,bytes32)` 的函数选择器
+pub const ANCHOR_ROOT_SELECTOR: [u8; 4] = [0xf7, 0x9f, 0x34, 0x21];
+
+/// 模块：链上校验 (Solidity Verifier)
+///
+/// **为什么需要**: 链上争议处理 (如托管赔付、保险理赔合约) 需要在合约里判断“这份证据确实在已锚定的根之下”，
+/// 不能依赖链下的验证方转述。`contracts/YuanjingMmrVerifier.sol` 用 Solidity 重现了 `proof::verify_proof`
+/// (BLAKE3 合并、峰从右向左合并)，这里把证明编码成该合约的 ABI 调用数据。
+///
+/// 只支持单叶子证明；EVM 没有 BLAKE3 预编译，每次合并约数万 gas，证明越深越贵，批量证明请在链下校验。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SolidityProof {
+    /// 树大小 (MMR 节点总数)
+    pub mmr_size: u64,
+    /// 叶子的 MMR 位置
+    pub pos: u64,
+    pub leaf: [u8; 32],
+    pub items: Vec<[u8; 32]>,
+    pub root: [u8; 32],
+}
+
+impl SolidityProof {
+    /// 与合约的判定一致：先在本地确认证明有效，再提交上链
+    pub fn verify(&self) -> bool {
+        verify_proof(self.mmr_size, self.items.clone(), self.pos, self.leaf, self.root)
+    }
+
+    /// `verifyInclusion(mmrSize, pos, leaf, proof, root)` 的调用数据 (对照调用方给出的根)
+    pub fn inclusion_calldata(&self) -> Vec<u8> {
+        let mut calldata = VERIFY_INCLUSION_SELECTOR.to_vec();
+        calldata.extend_from_slice(&abi_uint(self.mmr_size));
+        calldata.extend_from_slice(&abi_uint(self.pos));
+        calldata.extend_from_slice(&self.leaf);
+        calldata.extend_from_slice(&abi_uint(5 * 32));
+        calldata.extend_from_slice(&self.root);
+        calldata.extend_from_slice(&abi_bytes32_array(&self.items));
+        calldata
+    }
+
+    /// `verifyAnchored(mmrSize, pos, leaf, proof)` 的调用数据 (对照合约中已锚定的根)
+    pub fn anchored_calldata(&self) -> Vec<u8> {
+        let mut calldata = VERIFY_ANCHORED_SELECTOR.to_vec();
+        calldata.extend_from_slice(&abi_uint(self.mmr_size));
+        calldata.extend_from_slice(&abi_uint(self.pos));
+        calldata.extend_from_slice(&self.leaf);
+        calldata.extend_from_slice(&abi_uint(4 * 32));
+        calldata.extend_from_slice(&abi_bytes32_array(&self.items));
+        calldata
+    }
+
+    /// `anchorRoot(mmrSize, root)` 的调用数据，由合约 owner (日志运营方) 发送
+    pub fn anchor_calldata(&self) -> Vec<u8> {
+        let mut calldata = ANCHOR_ROOT_SELECTOR.to_vec();
+        calldata.extend_from_slice(&abi_uint(self.mmr_size));
+        calldata.extend_from_slice(&self.root);
+        calldata
+    }
+}
+
+/// EVM 惯用的 `0x` 前缀小写 Hex
+pub fn to_0x(bytes: impl AsRef<[u8]>) -> String {
+    format!("0x{}", hex::encode(bytes))
+}
+
+/// 左侧补零到 32 字节的大端整数
+fn abi_uint(value: u64) -> [u8; 32] {
+    let mut word = [0u8; 32];
+    word[24..].copy_from_slice(&value.to_be_bytes());
+    word
+}
+
+/// 动态数组 `bytes32[]` 的尾部：长度 + 各元素
+fn abi_bytes32_array(items: &[[u8; 32]]) -> Vec<u8> {
+    let mut bytes = abi_uint(items.len() as u64).to_vec();
+    for item in items {
+        bytes.extend_from_slice(item);
+    }
+    bytes
+}
@@ -0,0 +1,220 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+use crate::custody::CustodyRecord;
+use crate::review::ReviewRecord;
+use crate::evidence::Evidence;
+use crate::mmr_store::StoreSnapshot;
+use crate::position::{leaf_count, leaf_index_to_pos};
+use crate::publication::RootPublisher;
+
+/// 追加速率统计的时间窗 (小时)
+pub const APPEND_WINDOW_HOURS: i64 = 24;
+
+/// 本租户各 tree 的统计口径 (节点级的访问日志不计入)
+pub const TENANT_TREES: &[&str] = &[
+    "meta", "seq_index", "leaf_index", "nonces", "evidence", "salts", "blinded", "attestations", "supersessions",
+    "pruned", "erasures", "wal", "legal_holds", "legal_hold_history", "evidence_acl", "evidence_tags", "tag_index", "evidence_sources", "evidence_attrs", "evidence_order", "custody", "reviews", "tree_heads", "notary_receipts", "timestamp_tokens", "rekor_entries",
+    "signing_log", "signing_log_checkpoints", "models_allowlist", "registry_roots", "registry_targets", "prompt_registry",
+    "anomaly_flags", "anomaly_scan",
+];
+
+/// 按判定结果统计的证据数
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct EvidenceCounts {
+    /// 证据叶子总数 (不含检查点、保管与复核记录叶子)
+    pub total: u64,
+    /// `verdict = true` 的证据数
+    pub verdict_true: u64,
+    /// `verdict = false` 的证据数
+    pub verdict_false: u64,
+    /// 原文已清理或擦除、无法读出判定的证据数
+    pub unavailable: u64,
+    /// 检查点叶子数 (签名日志与运维日志)
+    pub checkpoints: u64,
+    /// 保管记录叶子数 (见 `custody.rs`)
+    pub custody_events: u64,
+    /// 鉴定人复核记录叶子数 (见 `review.rs`)
+    pub reviews: u64,
+}
+
+/// 某个小时内的追加数
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HourlyAppends {
+    /// 整点时间 (Unix 秒)
+    pub hour: i64,
+    pub count: u64,
+}
+
+/// 一棵 tree 的条目数与键值字节数
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TreeUsage {
+    pub entries: u64,
+    pub bytes: u64,
+}
+
+/// 存储占用
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StorageUsage {
+    /// 整个数据库在磁盘上的大小 (所有租户共用，含 sled 尚未回收的空间)
+    pub db_size_on_disk: u64,
+    /// 本租户 MMR 节点与各 tree 的键值字节数之和
+    pub tenant_bytes: u64,
+}
+
+/// 已签名树头的概况
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TreeHeadStatus {
+    pub tree_size: u64,
+    /// 签名时间 (Unix 秒)
+    pub timestamp: i64,
+}
+
+/// 某个公证方最近一次副署 (或 TSA 最近一次签发时间戳) 的树头
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NotaryStatus {
+    pub notary: String,
+    pub tree_size: u64,
+    /// 公证方记录的时间 (TSA 为令牌的签发时间，Unix 秒)
+    pub notarized_at: i64,
+}
+
+/// DNS TXT 记录的发布状态
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DnsStatus {
+    pub record: String,
+    /// 本进程启动后写入 DNS 的最新树大小；尚未写入为 null
+    pub tree_size: Option<u64>,
+}
+
+/// 锚定状态：根发布、DNS 与交叉公证
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AnchoringStatus {
+    /// 最近一轮根发布签名的树头 (内存中，重启后首轮发布前为 null)
+    pub published: Option<TreeHeadStatus>,
+    /// 存档中最新的树头
+    pub archived: Option<TreeHeadStatus>,
+    /// 尚未被任何存档树头覆盖的叶子数
+    pub unanchored_leaves: u64,
+    /// 未配置 DNS 发布时为 null
+    pub dns: Option<DnsStatus>,
+    /// 每个公证方最近一次副署 (按公证方名称排序)
+    pub notaries: Vec<NotaryStatus>,
+}
+
+/// 租户统计
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TenantStats {
+    pub tenant_id: String,
+    /// 统计时刻 (Unix 秒)
+    pub generated_at: i64,
+    /// MMR 大小 (节点总数)
+    pub tree_size: u64,
+    pub leaves: u64,
+    /// 当前根 (Hex)，空树为 null
+    pub root: Option<String>,
+    pub evidence: EvidenceCounts,
+    /// 最近 24 小时每小时的追加数 (按回执时间，旧的在前，含当前这个不满一小时的整点)
+    pub appends_per_hour: Vec<HourlyAppends>,
+    pub storage: StorageUsage,
+    /// 各 tree 的条目数与字节数 (`nodes` 为 MMR 节点)
+    pub indexes: BTreeMap<String, TreeUsage>,
+    pub anchoring: AnchoringStatus,
+}
+
+/// 模块：运行统计 (Admin Stats)
+///
+/// **为什么需要**: 审计看板要展示日志的规模与健康度 (证据量、判定分布、写入速率、存储占用、锚定是否跟上)，
+/// 此前只能拼凑 `/metrics`、`/roots` 与自审报告。这里在一个只读快照上一次性汇总。
+///
+/// 按叶子逐条读取回执与原文、逐棵 tree 统计字节数，耗时与数据量成正比；看板按分钟级轮询即可。
+/// 判定数来自原文，原文已清理或擦除的证据计入 `unavailable`；追加速率来自回执时间，检查点、保管与复核记录叶子没有回执、不计入。
+pub fn collect(snapshot: &StoreSnapshot, tenant_id: &str, roots: &RootPublisher, now: i64) -> anyhow::Result<TenantStats> {
+    let tree_size = snapshot.mmr_size();
+    let leaves = leaf_count(tree_size);
+
+    let current_hour = now - now.rem_euclid(3600);
+    let first_hour = current_hour - (APPEND_WINDOW_HOURS - 1) * 3600;
+    let mut hourly = vec![0u64; APPEND_WINDOW_HOURS as usize];
+    let mut evidence = EvidenceCounts::default();
+    for leaf in 0..leaves {
+        let pos = leaf_index_to_pos(leaf);
+        let Some(attestation) = snapshot.get_attestation(pos)? else {
+            match snapshot.evidence_bytes(pos)? {
+                Some(bytes) if CustodyRecord::from_bytes(&bytes)?.is_some() => evidence.custody_events += 1,
+                Some(bytes) if ReviewRecord::from_bytes(&bytes)?.is_some() => evidence.reviews += 1,
+                _ => evidence.checkpoints += 1,
+            }
+            continue;
+        };
+        evidence.total += 1;
+        let timestamp = attestation.receipt.timestamp;
+        if (first_hour..current_hour + 3600).contains(&timestamp) {
+            hourly[((timestamp - first_hour) / 3600) as usize] += 1;
+        }
+        // 擦除与清理都保留回执，只删原文
+        match snapshot.evidence_bytes(pos)? {
+            Some(bytes) if Evidence::from_canonical_bytes(attestation.receipt.encoding, &bytes)?.verdict => evidence.verdict_true += 1,
+            Some(_) => evidence.verdict_false += 1,
+            None => evidence.unavailable += 1,
+        }
+    }
+    let appends_per_hour = hourly
+        .into_iter()
+        .enumerate()
+        .map(|(i, count)| HourlyAppends { hour: first_hour + i as i64 * 3600, count })
+        .collect();
+
+    let indexes: BTreeMap<String, TreeUsage> = snapshot
+        .tree_usage(TENANT_TREES)?
+        .into_iter()
+        .map(|(name, entries, bytes)| (name, TreeUsage { entries, bytes }))
+        .collect();
+    let storage = StorageUsage {
+        db_size_on_disk: snapshot.size_on_disk()?,
+        tenant_bytes: indexes.values().map(|usage| usage.bytes).sum(),
+    };
+
+    let archived = snapshot.tree_heads()?.latest()?;
+    let unanchored_leaves = leaves - archived.as_ref().map_or(0, |head| leaf_count(head.tree_head.tree_size.min(tree_size)));
+    let mut notaries: BTreeMap<String, NotaryStatus> = BTreeMap::new();
+    for receipt in snapshot.notary_receipts()?.recent(usize::MAX)? {
+        // 按树大小从大到小排列，每个公证方取第一条
+        notaries.entry(receipt.notary.clone()).or_insert(NotaryStatus {
+            notary: receipt.notary,
+            tree_size: receipt.statement.signed_tree_head.tree_head.tree_size,
+            notarized_at: receipt.statement.notarized_at,
+        });
+    }
+    for receipt in snapshot.timestamp_tokens()?.recent(usize::MAX)? {
+        notaries.entry(receipt.tsa.clone()).or_insert(NotaryStatus {
+            notary: receipt.tsa,
+            tree_size: receipt.signed_tree_head.tree_head.tree_size,
+            notarized_at: receipt.gen_time,
+        });
+    }
+    let anchoring = AnchoringStatus {
+        published: roots
+            .latest()
+            .into_iter()
+            .find(|head| head.tree_head.tenant_id == tenant_id)
+            .map(|head| TreeHeadStatus { tree_size: head.tree_head.tree_size, timestamp: head.tree_head.timestamp }),
+        archived: archived.map(|head| TreeHeadStatus { tree_size: head.tree_head.tree_size, timestamp: head.tree_head.timestamp }),
+        unanchored_leaves,
+        dns: roots.dns_status(tenant_id).map(|(record, tree_size)| DnsStatus { record, tree_size }),
+        notaries: notaries.into_values().collect(),
+    };
+
+    Ok(TenantStats {
+        tenant_id: tenant_id.to_string(),
+        generated_at: now,
+        tree_size,
+        leaves,
+        root: (tree_size > 0).then(|| snapshot.root_at(tree_size).map(hex::encode)).transpose()?,
+        evidence,
+        appends_per_hour,
+        storage,
+        indexes,
+        anchoring,
+    })
+}
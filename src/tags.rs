@@ -0,0 +1,127 @@
+use serde::{Deserialize, Serialize};
+use sled::transaction::TransactionError;
+use sled::Transactional;
+
+use crate::codec::StorageCodec;
+use crate::mmr_store::SledStore;
+
+/// 每条证据最多的标签数
+pub const MAX_TAGS_PER_EVIDENCE: usize = 32;
+
+/// 标签的最大长度 (字节)
+pub const MAX_TAG_BYTES: usize = 64;
+
+/// 单条证据的标签
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EvidenceTagSet {
+    pub pos: u64,
+    /// 按字典序
+    pub tags: Vec<String>,
+    /// 最近一次设置的时间 (Unix 秒)
+    pub updated_at: i64,
+    /// 设置者 (租户 API Key 指纹)
+    pub principal: String,
+}
+
+/// 规范化标签：去掉首尾空白、转为小写、排序去重；字符集或数量不合规时报错
+pub fn normalize_tags(tags: Vec<String>) -> anyhow::Result<Vec<String>> {
+    let mut normalized = Vec::with_capacity(tags.len());
+    for tag in tags {
+        let tag = tag.trim().to_ascii_lowercase();
+        if tag.is_empty() || tag.len() > MAX_TAG_BYTES {
+            return Err(anyhow::anyhow!("Invalid tag '{}': must be 1 to {} bytes", tag, MAX_TAG_BYTES));
+        }
+        if !tag.bytes().all(|b| b.is_ascii_alphanumeric() || matches!(b, b'_' | b'-' | b'.' | b':' | b'/')) {
+            return Err(anyhow::anyhow!("Invalid tag '{}': only ASCII letters, digits, '_', '-', '.', ':' and '/' are allowed", tag));
+        }
+        normalized.push(tag);
+    }
+    normalized.sort();
+    normalized.dedup();
+    if normalized.len() > MAX_TAGS_PER_EVIDENCE {
+        return Err(anyhow::anyhow!("Invalid tags: at most {} per evidence, got {}", MAX_TAGS_PER_EVIDENCE, normalized.len()));
+    }
+    Ok(normalized)
+}
+
+/// 模块：证据标签 (Evidence Tags)
+///
+/// **为什么需要**: 证据一多，调查人员需要按案件、批次、线索把它们归拢 (`case:2026-017`、`batch/weibo`)，
+/// 而证据本身一旦签名就不可更改。标签存在签名内容之外，随时可改，不影响叶子哈希、回执与任何证明；
+/// 也因此不具备证明力，只用于整理与检索 (`GET /evidence?tag=`)。
+///
+/// 当前标签存 `evidence_tags` 树 (按位置)，反向索引存 `tag_index` 树 (键为 `标签 || 0x00 || 位置`，
+/// 标签字符集不含 0x00，按前缀扫描即得某个标签下按位置升序的全部证据)。两棵树在同一个事务中更新。
+#[derive(Clone)]
+pub struct EvidenceTags {
+    tags: sled::Tree,
+    index: sled::Tree,
+}
+
+impl EvidenceTags {
+    pub fn open(store: &SledStore) -> anyhow::Result<Self> {
+        Ok(Self { tags: store.tree("evidence_tags")?, index: store.tree("tag_index")? })
+    }
+
+    /// 指定位置的标签；从未设置过返回 None
+    pub fn get(&self, pos: u64) -> anyhow::Result<Option<EvidenceTagSet>> {
+        self.tags.get(pos.to_be_bytes())?.map(|v| StorageCodec::decode(&v)).transpose()
+    }
+
+    /// 整体替换标签 (须已规范化，见 [`normalize_tags`])；空列表删除该条的全部标签
+    pub fn set(&self, pos: u64, tags: Vec<String>, principal: &str, at: i64) -> anyhow::Result<EvidenceTagSet> {
+        let previous = self.get(pos)?.map(|set| set.tags).unwrap_or_default();
+        let set = EvidenceTagSet { pos, tags, updated_at: at, principal: principal.to_string() };
+        let encoded = StorageCodec::encode(&set)?;
+        (&self.tags, &self.index)
+            .transaction(|(tags, index)| {
+                for tag in &previous {
+                    index.remove(index_key(tag, pos))?;
+                }
+                for tag in &set.tags {
+                    index.insert(index_key(tag, pos), &[] as &[u8])?;
+                }
+                if set.tags.is_empty() {
+                    tags.remove(&pos.to_be_bytes())?;
+                } else {
+                    tags.insert(&pos.to_be_bytes(), encoded.as_slice())?;
+                }
+                Ok(())
+            })
+            .map_err(|e: TransactionError<()>| anyhow::anyhow!("Tag transaction failed: {:?}", e))?;
+        self.tags.flush()?;
+        self.index.flush()?;
+        Ok(set)
+    }
+
+    /// 带某个标签的证据位置，从 `from` (含) 开始按位置升序 (`descending` 时降序) 至多 `limit` 个
+    pub fn positions(&self, tag: &str, from: Option<u64>, limit: usize, descending: bool) -> anyhow::Result<Vec<u64>> {
+        let prefix = index_key(tag, 0);
+        let prefix = &prefix[..prefix.len() - 8];
+        let entries: Box<dyn Iterator<Item = sled::Result<(sled::IVec, sled::IVec)>>> = if descending {
+            Box::new(self.index.range(prefix.to_vec()..=index_key(tag, from.unwrap_or(u64::MAX))).rev())
+        } else {
+            Box::new(self.index.range(index_key(tag, from.unwrap_or(0))..))
+        };
+        let mut positions = Vec::new();
+        for entry in entries.take(limit) {
+            let (key, _) = entry?;
+            if !key.starts_with(prefix) {
+                break;
+            }
+            let mut pos = [0u8; 8];
+            pos.copy_from_slice(&key[prefix.len()..]);
+            positions.push(u64::from_be_bytes(pos));
+        }
+        Ok(positions)
+    }
+}
+
+/// 反向索引的键：标签 || 0x00 || 位置 (大端)
+fn index_key(tag: &str, pos: u64) -> Vec<u8> {
+    let mut key = Vec::with_capacity(tag.len() + 9);
+    key.extend_from_slice(tag.as_bytes());
+    key.push(0);
+    key.extend_from_slice(&pos.to_be_bytes());
+    key
+}
@@ -0,0 +1,140 @@
+use log::warn;
+use opentelemetry::context::FutureExt;
+use opentelemetry::trace::{Span, SpanKind, Status, TraceContextExt, Tracer};
+use opentelemetry::{global, Context, ContextGuard, KeyValue};
+use opentelemetry_otlp::{SpanExporter, WithExportConfig};
+use opentelemetry_sdk::trace::{Sampler, SdkTracerProvider};
+use opentelemetry_sdk::Resource;
+use std::future::Future;
+use std::time::SystemTime;
+
+/// 埋点使用的 tracer 名称
+const TRACER: &str = "yuanjing";
+
+/// 模块：链路追踪 (Tracing)
+///
+/// **为什么需要**: `/prove` 变慢时，单看总耗时分不清是卡在指纹计算的阻塞线程池排队、
+/// 写线程的命令队列 (单写者，相当于存储锁)，还是签名日志与 MMR 落盘的 fsync。
+/// 管线的每个阶段记为一个 OpenTelemetry span，经 OTLP (HTTP/protobuf) 导出到 Collector / Jaeger / Tempo。
+///
+/// 跨线程的阶段 (阻塞线程池、写线程) 用 [`Handoff`] 带上请求的上下文，并补记一段排队 span。
+/// 未配置导出端点时不安装 provider，埋点都是空操作。
+pub struct Telemetry {
+    provider: SdkTracerProvider,
+}
+
+impl Telemetry {
+    /// 按配置安装全局 tracer provider；`endpoint` 为空时返回 None
+    ///
+    /// `endpoint` 是 Collector 的 OTLP/HTTP 地址 (如 `http://collector:4318`)，自动补上 `/v1/traces`。
+    pub fn init(endpoint: &str, service_name: &str, sample_ratio: f64) -> anyhow::Result<Option<Self>> {
+        if endpoint.is_empty() {
+            return Ok(None);
+        }
+        let endpoint = match endpoint.trim_end_matches('/') {
+            base if base.ends_with("/v1/traces") => base.to_string(),
+            base => format!("{}/v1/traces", base),
+        };
+        let exporter = SpanExporter::builder()
+            .with_http()
+            .with_endpoint(endpoint)
+            .build()
+            .map_err(|e| anyhow::anyhow!("Failed to build OTLP exporter: {}", e))?;
+        let provider = SdkTracerProvider::builder()
+            .with_batch_exporter(exporter)
+            .with_sampler(Sampler::ParentBased(Box::new(Sampler::TraceIdRatioBased(sample_ratio))))
+            .with_resource(Resource::builder().with_service_name(service_name.to_string()).build())
+            .build();
+        global::set_tracer_provider(provider.clone());
+        Ok(Some(Self { provider }))
+    }
+
+    /// 停机前导出缓冲中的 span
+    pub fn shutdown(self) {
+        if let Err(e) = self.provider.shutdown() {
+            warn!("⚠️  追踪导出关闭失败: {}", e);
+        }
+    }
+}
+
+/// 一个进行中的阶段，drop 时结束
+pub struct Stage {
+    cx: Context,
+}
+
+impl Stage {
+    pub fn context(&self) -> &Context {
+        &self.cx
+    }
+
+    pub fn set_attribute(&self, key: &'static str, value: impl Into<opentelemetry::Value>) {
+        self.cx.span().set_attribute(KeyValue::new(key, value));
+    }
+
+    /// 所属 trace 的 ID (Hex)，错误响应据此关联；未开启追踪时为 None
+    pub fn trace_id(&self) -> Option<String> {
+        let span = self.cx.span();
+        let span_context = span.span_context();
+        span_context.is_valid().then(|| span_context.trace_id().to_string())
+    }
+
+    /// 标记阶段失败
+    pub fn fail(&self, message: &str) {
+        self.cx.span().set_status(Status::error(message.to_string()));
+    }
+}
+
+impl Drop for Stage {
+    fn drop(&mut self) {
+        self.cx.span().end();
+    }
+}
+
+/// 开启一次请求的根 span
+pub fn request(name: &'static str, tenant: &str) -> Stage {
+    let tracer = global::tracer(TRACER);
+    let span = tracer
+        .span_builder(name)
+        .with_kind(SpanKind::Server)
+        .with_attributes([KeyValue::new("yuanjing.tenant", tenant.to_string())])
+        .start_with_context(&tracer, &Context::new());
+    Stage { cx: Context::new().with_span(span) }
+}
+
+/// 以当前上下文为父，开启一个子阶段
+pub fn stage(name: &'static str) -> Stage {
+    let parent = Context::current();
+    let span = global::tracer(TRACER).start_with_context(name, &parent);
+    Stage { cx: parent.with_span(span) }
+}
+
+/// 把一个异步阶段记为子 span (轮询期间它就是当前上下文)
+pub async fn traced<F: Future>(name: &'static str, future: F) -> F::Output {
+    let stage = stage(name);
+    future.with_context(stage.cx.clone()).await
+}
+
+/// 跨线程交接的追踪上下文 (如写线程的命令、阻塞线程池的任务)
+pub struct Handoff {
+    cx: Context,
+    sent_at: SystemTime,
+}
+
+impl Handoff {
+    /// 在发送方捕获当前上下文与发出时刻
+    pub fn capture() -> Self {
+        Self { cx: Context::current(), sent_at: SystemTime::now() }
+    }
+
+    /// 在接收方线程恢复上下文，并补记一段从发出到接收的排队 span
+    ///
+    /// 返回的守卫存活期间，该线程上开启的阶段都挂在原请求之下。
+    pub fn resume(self, queue_stage: &'static str) -> ContextGuard {
+        if self.cx.has_active_span() {
+            let tracer = global::tracer(TRACER);
+            let mut queued = tracer.span_builder(queue_stage).with_start_time(self.sent_at).start_with_context(&tracer, &self.cx);
+            queued.end();
+        }
+        self.cx.attach()
+    }
+}
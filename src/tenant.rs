@@ -0,0 +1,246 @@
+use log::info;
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+
+use ed25519_dalek::VerifyingKey;
+
+use crate::config::{AuditorConfig, DepartmentKeyConfig, TenantConfig};
+use crate::hdkey::DerivationPath;
+use crate::mmr_store::{EvidenceReader, EvidenceStore, SledStore, StoreOptions, DEFAULT_TENANT};
+use crate::signer::{DerivedSigner, EvidenceSigner};
+use crate::writer::WriterHandle;
+
+/// 单个租户的运行时上下文
+///
+/// 每个租户独占：一棵 MMR (含索引与证据原文)、一个写线程、一把签名子密钥，
+/// 以及从该子密钥按部门 / 设备派生的签名密钥。
+pub struct TenantContext {
+    pub id: String,
+    pub signer: Arc<EvidenceSigner>,
+    pub writer: WriterHandle,
+    pub reader: EvidenceReader,
+    /// 派生签名密钥，按名称索引
+    pub derived_keys: HashMap<String, Arc<DerivedSigner>>,
+}
+
+impl TenantContext {
+    /// 按部门 / 设备名称查找派生密钥
+    pub fn derived_key(&self, name: &str) -> Option<Arc<DerivedSigner>> {
+        self.derived_keys.get(name).cloned()
+    }
+
+    /// 按回执中的 `key_path` 找到签名密钥；空串为租户根密钥
+    pub fn signing_key(&self, key_path: &str) -> Option<&EvidenceSigner> {
+        if key_path.is_empty() {
+            return Some(&self.signer);
+        }
+        self.derived_keys
+            .values()
+            .find(|key| key.key_path() == key_path)
+            .map(|key| &key.signer)
+    }
+
+    /// 按回执中的 `key_path` 解析验签公钥；空串为租户根公钥
+    pub fn verifying_key(&self, key_path: &str) -> Option<VerifyingKey> {
+        self.signing_key(key_path).map(EvidenceSigner::public_key)
+    }
+}
+
+/// 模块：租户注册表 (Tenant Registry)
+///
+/// **职责**: 把 API Key 映射到租户，并为每个租户准备好隔离的存储与签名身份。
+/// - 未配置租户时，只有一个 `default` 租户，且不要求 API Key (兼容单机部署)。
+/// - 配置了租户后，所有租户范围内的接口都必须携带有效的 API Key。
+///
+/// API Key 只以 Blake3 哈希形式保存在内存中。
+pub struct TenantRegistry {
+    tenants: HashMap<String, Arc<TenantContext>>,
+    api_keys: HashMap<[u8; 32], String>,
+    auth_required: bool,
+}
+
+impl TenantRegistry {
+    /// 根据配置构建所有租户
+    ///
+    /// `default` 租户使用主密钥与旧的无前缀 tree；其他租户使用派生子密钥与独立前缀。
+    pub fn build(
+        base: &SledStore,
+        master: &Arc<EvidenceSigner>,
+        configs: &[TenantConfig],
+        departments: &[DepartmentKeyConfig],
+        options: &StoreOptions,
+        writer_capacity: usize,
+    ) -> anyhow::Result<Self> {
+        let mut registry = Self {
+            tenants: HashMap::new(),
+            api_keys: HashMap::new(),
+            auth_required: !configs.is_empty(),
+        };
+
+        if configs.is_empty() {
+            registry.add_tenant(base, master, DEFAULT_TENANT, departments, options, writer_capacity)?;
+            return Ok(registry);
+        }
+
+        for config in configs {
+            validate_tenant_id(&config.id)?;
+            if config.api_key.is_empty() {
+                return Err(anyhow::anyhow!("Tenant '{}' has an empty API key", config.id));
+            }
+            if registry.tenants.contains_key(&config.id) {
+                return Err(anyhow::anyhow!("Duplicate tenant id '{}'", config.id));
+            }
+            registry.add_tenant(base, master, &config.id, departments, options, writer_capacity)?;
+            registry.api_keys.insert(hash_api_key(&config.api_key), config.id.clone());
+        }
+        Ok(registry)
+    }
+
+    fn add_tenant(
+        &mut self,
+        base: &SledStore,
+        master: &Arc<EvidenceSigner>,
+        id: &str,
+        departments: &[DepartmentKeyConfig],
+        options: &StoreOptions,
+        writer_capacity: usize,
+    ) -> anyhow::Result<()> {
+        let signer = tenant_signer(master, id)?;
+        let store = EvidenceStore::open(base.for_tenant(id)?, options);
+        let reader = store.reader();
+        let writer = WriterHandle::spawn(store, signer.clone(), writer_capacity);
+
+        info!("🏢 租户 '{}' 已加载, 公钥: {}", id, hex::encode(signer.public_key().to_bytes()));
+
+        let mut derived_keys = HashMap::new();
+        for department in departments {
+            let path = DerivationPath::parse(&department.path)?;
+            let key = DerivedSigner::derive(&signer, id, &department.name, &path)?;
+            info!("   🔑 派生密钥 '{}' ({}): {}", key.name, key.key_path(), key.endorsement.public_key);
+            derived_keys.insert(department.name.clone(), Arc::new(key));
+        }
+
+        self.tenants.insert(id.to_string(), Arc::new(TenantContext {
+            id: id.to_string(),
+            signer,
+            writer,
+            reader,
+            derived_keys,
+        }));
+        Ok(())
+    }
+
+    /// 是否需要 API Key
+    pub fn auth_required(&self) -> bool {
+        self.auth_required
+    }
+
+    /// 按 API Key 解析租户；未启用鉴权时总是返回 `default` 租户
+    pub fn resolve(&self, api_key: Option<&str>) -> Option<Arc<TenantContext>> {
+        if !self.auth_required {
+            return self.get(DEFAULT_TENANT);
+        }
+        let tenant_id = self.api_keys.get(&hash_api_key(api_key?))?;
+        self.get(tenant_id)
+    }
+
+    /// 按租户 ID 查找 (用于公开验证回执等无需鉴权的场景)
+    pub fn get(&self, tenant_id: &str) -> Option<Arc<TenantContext>> {
+        self.tenants.get(tenant_id).cloned()
+    }
+
+    /// 所有租户
+    pub fn all(&self) -> impl Iterator<Item = &Arc<TenantContext>> {
+        self.tenants.values()
+    }
+}
+
+/// 管理员 API Key (只保存哈希)
+///
+/// 管理员操作 (诉讼保全等) 仍按租户 API Key 确定作用的租户，另需在 `X-Admin-Key` 中携带管理员 Key。
+#[derive(Default)]
+pub struct AdminKeys {
+    keys: HashSet<[u8; 32]>,
+}
+
+impl AdminKeys {
+    pub fn new(keys: &[String]) -> Self {
+        Self { keys: keys.iter().map(|key| hash_api_key(key)).collect() }
+    }
+
+    /// 是否配置了管理员 Key
+    pub fn enabled(&self) -> bool {
+        !self.keys.is_empty()
+    }
+
+    pub fn contains(&self, key: &str) -> bool {
+        self.keys.contains(&hash_api_key(key))
+    }
+
+    /// 管理员主体标识，记入保全历史 (格式同 [`principal_of`])
+    pub fn principal_of(key: &str) -> String {
+        format!("admin-key:{}", hex::encode(&hash_api_key(key)[..8]))
+    }
+}
+
+/// 审计方 API Key (只保存哈希)
+///
+/// 审计方不属于任何租户，只能读取租户经访问控制列表授权给它的证据原文 (见 `acl.rs`)。
+#[derive(Default)]
+pub struct Auditors {
+    keys: HashMap<[u8; 32], String>,
+}
+
+impl Auditors {
+    pub fn new(configs: &[AuditorConfig]) -> Self {
+        Self { keys: configs.iter().map(|auditor| (hash_api_key(&auditor.api_key), auditor.name.clone())).collect() }
+    }
+
+    /// 按 Key 解析审计方名称
+    pub fn resolve(&self, key: &str) -> Option<&str> {
+        self.keys.get(&hash_api_key(key)).map(String::as_str)
+    }
+
+    /// 是否存在该名称的审计方
+    pub fn contains_name(&self, name: &str) -> bool {
+        self.keys.values().any(|auditor| auditor == name)
+    }
+
+    /// 审计方主体标识，记入访问日志
+    pub fn principal_of(name: &str) -> String {
+        format!("auditor:{}", name)
+    }
+}
+
+/// 租户根签名密钥：`default` 租户使用主密钥，其他租户使用派生子密钥
+pub fn tenant_signer(master: &Arc<EvidenceSigner>, id: &str) -> anyhow::Result<Arc<EvidenceSigner>> {
+    if id == DEFAULT_TENANT {
+        Ok(master.clone())
+    } else {
+        Ok(Arc::new(master.derive_tenant(id)?))
+    }
+}
+
+/// 请求主体标识：API Key 哈希的前 8 字节 (Hex)，不会泄露 Key 本身；未携带 Key 时为 `anonymous`
+pub fn principal_of(api_key: Option<&str>) -> String {
+    match api_key {
+        Some(api_key) => format!("api-key:{}", hex::encode(&hash_api_key(api_key)[..8])),
+        None => "anonymous".to_string(),
+    }
+}
+
+pub(crate) fn hash_api_key(api_key: &str) -> [u8; 32] {
+    *blake3::hash(api_key.as_bytes()).as_bytes()
+}
+
+/// 租户 ID 会成为 sled tree 名的一部分，只允许小写字母、数字、`-`、`_`
+pub(crate) fn validate_tenant_id(id: &str) -> anyhow::Result<()> {
+    let valid = !id.is_empty()
+        && id.len() <= 64
+        && id.chars().all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-' || c == '_');
+    if valid {
+        Ok(())
+    } else {
+        Err(anyhow::anyhow!("Invalid tenant id '{}': use 1-64 chars of [a-z0-9_-]", id))
+    }
+}
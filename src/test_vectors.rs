@@ -0,0 +1,350 @@
+use ckb_merkle_mountain_range::{util::MemStore, MMR};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+use crate::evidence::{
+    Calibration, CanonicalEncoding, ClientContext, Confidence, Consensus, ConsensusRule, Evidence, ModelVerdict, Provenance,
+    ProvenanceMode,
+};
+use crate::hdkey::{DerivationPath, KeyEndorsement};
+use crate::mmr_store::DEFAULT_TENANT;
+use crate::proof::{CompactProof, MergeBlake3};
+use crate::receipt::{Receipt, RECEIPT_DOMAIN};
+use crate::signer::EvidenceSigner;
+use crate::solidity::{to_0x, SolidityProof};
+use crate::tenant::tenant_signer;
+
+/// 测试向量格式的当前版本；规范编码、哈希或证明格式有任何变化都须另出新版本，旧版本文件保持不动
+///
+/// v2：回执签名改为覆盖 `RECEIPT_DOMAIN || 回执规范字节`。
+pub const TEST_VECTORS_VERSION: u32 = 2;
+
+/// 测试向量在仓库中的默认位置
+pub const TEST_VECTORS_PATH: &str = "test-vectors/v2/golden.json";
+
+/// 派生测试主 Seed 的 KDF 上下文 (Seed 随文件公开，只用于测试)
+const MASTER_SEED_CONTEXT: &str = "yuanjing-core golden test vectors v1";
+
+/// 证据与回执的固定时间基准 (Unix 秒)
+const BASE_TIMESTAMP: i64 = 1_760_000_000;
+
+/// 第二个日志的租户 (签名密钥由主 Seed 按租户派生)
+const SECOND_TENANT: &str = "vectors";
+
+/// 部门密钥的派生路径
+const DEPARTMENT_PATH: &str = "m/0'/1'";
+
+/// 跨语言测试向量 (Golden Test Vectors)
+///
+/// 一组固定证据在两个日志 (BCS 与 Protobuf 各一个) 中依次入库的完整材料：规范字节、证据 ID、叶子哈希、
+/// 证据签名、回执及其规范字节与签名、包含性证明 (JSON、紧凑编码与 Solidity 调用数据) 以及最终的树根。
+/// 全部字节由固定 Seed 与固定时间生成 (Ed25519 签名是确定性的)，重新生成的结果必须逐字节相同。
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TestVectors {
+    pub version: u32,
+    pub description: String,
+    /// 生成全部签名的主 Seed (Hex)，仅供测试，切勿用于生产
+    pub master_seed: String,
+    pub master_public_key: String,
+    /// 回执签名的域分隔前缀 (UTF-8)
+    pub receipt_domain: String,
+    pub logs: Vec<VectorLog>,
+}
+
+/// 一个租户日志 (一棵 MMR)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VectorLog {
+    pub tenant_id: String,
+    pub encoding: CanonicalEncoding,
+    /// 租户根公钥 (Hex)
+    pub public_key: String,
+    pub entries: Vec<VectorEntry>,
+    /// 全部条目入库后的树大小与根 (Hex)，`entries[].proof` 与 `batch_proof` 都相对于这棵树
+    pub tree_size: u64,
+    pub root: String,
+    /// 全部叶子的合并证明
+    pub batch_proof: BatchProofVector,
+}
+
+/// 一条证据的全部派生材料 (字节与哈希均为 Hex)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VectorEntry {
+    pub name: String,
+    pub description: String,
+    /// 证据 (即 `evidence_dump`)
+    pub evidence: Evidence,
+    /// 按日志的 `encoding` 编码的规范字节
+    pub canonical_bytes: String,
+    /// Blake3(规范字节)
+    pub evidence_id: String,
+    /// 叶子盐值，仅加盐叶子
+    pub salt: Option<String>,
+    /// 无盐时等于 `evidence_id`，加盐时为 Blake3(盐值 || 规范字节)
+    pub leaf_hash: String,
+    /// 签名公钥：租户根公钥，或 `endorsement` 中的派生公钥
+    pub signer_public_key: String,
+    /// 对规范字节的签名
+    pub evidence_signature: String,
+    /// 派生密钥签名时，租户根密钥对该子公钥的背书
+    pub endorsement: Option<EndorsementVector>,
+    pub receipt: Receipt,
+    pub receipt_canonical_bytes: String,
+    /// 对 `receipt_domain || receipt_canonical_bytes` 的签名
+    pub receipt_signature: String,
+    /// 相对于回执中 `tree_size` / `root` 的证明路径
+    pub receipt_proof: Vec<String>,
+    /// 相对于日志最终树的证明
+    pub proof: ProofVector,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EndorsementVector {
+    pub endorsement: KeyEndorsement,
+    pub canonical_bytes: String,
+    pub signature: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProofVector {
+    pub pos: u64,
+    pub items: Vec<String>,
+    /// 紧凑二进制证明 (`application/vnd.yuanjing.proof`)
+    pub compact: String,
+    /// `YuanjingMmrVerifier.verifyInclusion` 的调用数据
+    pub solidity_calldata: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BatchProofVector {
+    pub positions: Vec<u64>,
+    pub items: Vec<String>,
+    pub compact: String,
+}
+
+/// 一条固定证据及其入库方式
+struct Fixture {
+    name: &'static str,
+    description: &'static str,
+    evidence: Evidence,
+    salt: Option<[u8; 32]>,
+    /// 以部门密钥签名
+    derived: bool,
+    /// 更正的早先条目 (在 fixtures 中的下标)
+    supersedes: Option<usize>,
+}
+
+/// 生成测试向量；相同版本的代码每次生成的结果完全相同
+pub fn generate() -> anyhow::Result<TestVectors> {
+    let seed = blake3::derive_key(MASTER_SEED_CONTEXT, b"master seed");
+    let master = Arc::new(EvidenceSigner::from_seed(&seed));
+    let logs = [(DEFAULT_TENANT, CanonicalEncoding::Bcs), (SECOND_TENANT, CanonicalEncoding::Protobuf)]
+        .into_iter()
+        .map(|(tenant_id, encoding)| generate_log(tenant_id, encoding, &*tenant_signer(&master, tenant_id)?))
+        .collect::<anyhow::Result<Vec<_>>>()?;
+    Ok(TestVectors {
+        version: TEST_VECTORS_VERSION,
+        description: "yuanjing-core golden test vectors: canonical bytes, leaf hashes, signatures, receipts, MMR proofs and roots".to_string(),
+        master_seed: hex::encode(seed),
+        master_public_key: hex::encode(master.public_key().to_bytes()),
+        receipt_domain: String::from_utf8(RECEIPT_DOMAIN.to_vec())?,
+        logs,
+    })
+}
+
+/// 与已发布的向量逐项比对 (按 JSON 值)，返回不一致的条目 (空表示完全一致)
+pub fn compare(expected: &TestVectors, actual: &TestVectors) -> anyhow::Result<Vec<String>> {
+    let mut differences = Vec::new();
+    if expected.version != actual.version
+        || expected.master_seed != actual.master_seed
+        || expected.master_public_key != actual.master_public_key
+        || expected.receipt_domain != actual.receipt_domain
+    {
+        differences.push("header (version / master key / receipt domain)".to_string());
+    }
+    if expected.logs.len() != actual.logs.len() {
+        differences.push(format!("log count {} != {}", expected.logs.len(), actual.logs.len()));
+    }
+    for (expected, actual) in expected.logs.iter().zip(&actual.logs) {
+        if expected.entries.len() != actual.entries.len() {
+            differences.push(format!("{}: entry count {} != {}", expected.tenant_id, expected.entries.len(), actual.entries.len()));
+        }
+        for (expected_entry, actual_entry) in expected.entries.iter().zip(&actual.entries) {
+            if serde_json::to_value(expected_entry)? != serde_json::to_value(actual_entry)? {
+                differences.push(format!("{}/{}", expected.tenant_id, expected_entry.name));
+            }
+        }
+        if (expected.tree_size, &expected.root) != (actual.tree_size, &actual.root)
+            || serde_json::to_value(&expected.batch_proof)? != serde_json::to_value(&actual.batch_proof)?
+        {
+            differences.push(format!("{}: tree / batch proof", expected.tenant_id));
+        }
+    }
+    Ok(differences)
+}
+
+fn generate_log(tenant_id: &str, encoding: CanonicalEncoding, signer: &EvidenceSigner) -> anyhow::Result<VectorLog> {
+    let department_path = DerivationPath::parse(DEPARTMENT_PATH)?;
+    let department = signer.derive_path(&department_path)?;
+    let store = MemStore::default();
+    let mut mmr_size = 0;
+    let mut staged = Vec::new();
+    for (seq, fixture) in fixtures()?.into_iter().enumerate() {
+        let canonical_bytes = fixture.evidence.canonical_bytes_as(encoding)?;
+        let leaf_hash = crate::evidence::leaf_hash_of(&canonical_bytes, fixture.salt.as_ref());
+        let mut mmr = MMR::<[u8; 32], MergeBlake3, _>::new(mmr_size, &store);
+        let pos = mmr.push(leaf_hash).map_err(|e| anyhow::anyhow!("MMR append error: {}", e))?;
+        let root = mmr.get_root().map_err(|e| anyhow::anyhow!("MMR get_root error: {}", e))?;
+        mmr_size = mmr.mmr_size();
+        mmr.commit().map_err(|e| anyhow::anyhow!("MMR commit error: {}", e))?;
+
+        let key = if fixture.derived { &department } else { signer };
+        let receipt = Receipt {
+            evidence_hash: hex::encode(leaf_hash),
+            leaf_pos: pos,
+            root: hex::encode(root),
+            tree_size: mmr_size,
+            timestamp: fixture.evidence.timestamp,
+            seq: seq as u64,
+            clock_uncertain: false,
+            tenant_id: tenant_id.to_string(),
+            key_path: if fixture.derived { DEPARTMENT_PATH.to_string() } else { String::new() },
+            encoding,
+            supersedes: fixture.supersedes.and_then(|index| staged.get(index).map(|entry: &VectorEntry| entry.proof.pos)),
+        };
+        let endorsement = if fixture.derived {
+            let endorsement = KeyEndorsement {
+                tenant_id: tenant_id.to_string(),
+                key_path: DEPARTMENT_PATH.to_string(),
+                public_key: hex::encode(department.public_key().to_bytes()),
+            };
+            Some(EndorsementVector {
+                canonical_bytes: hex::encode(endorsement.canonical_bytes()?),
+                signature: hex::encode(signer.endorse(&endorsement)?.to_bytes()),
+                endorsement,
+            })
+        } else {
+            None
+        };
+        staged.push(VectorEntry {
+            name: fixture.name.to_string(),
+            description: fixture.description.to_string(),
+            canonical_bytes: hex::encode(&canonical_bytes),
+            evidence_id: hex::encode(crate::evidence::evidence_id_of(&canonical_bytes)),
+            salt: fixture.salt.map(hex::encode),
+            leaf_hash: hex::encode(leaf_hash),
+            signer_public_key: hex::encode(key.public_key().to_bytes()),
+            evidence_signature: hex::encode(key.sign_bytes(&canonical_bytes)?.to_bytes()),
+            endorsement,
+            receipt_canonical_bytes: hex::encode(receipt.canonical_bytes()?),
+            receipt_signature: hex::encode(key.sign_receipt(&receipt)?.to_bytes()),
+            receipt_proof: proof_items(&store, mmr_size, vec![pos])?.iter().map(hex::encode).collect(),
+            receipt,
+            evidence: fixture.evidence,
+            // 最终树的证明在全部条目入库后填写
+            proof: ProofVector { pos, items: Vec::new(), compact: String::new(), solidity_calldata: String::new() },
+        });
+    }
+
+    let root = MMR::<[u8; 32], MergeBlake3, _>::new(mmr_size, &store)
+        .get_root()
+        .map_err(|e| anyhow::anyhow!("MMR get_root error: {}", e))?;
+    for entry in &mut staged {
+        let pos = entry.proof.pos;
+        let items = proof_items(&store, mmr_size, vec![pos])?;
+        let leaf: [u8; 32] = hex::decode(&entry.leaf_hash)?.try_into().map_err(|_| anyhow::anyhow!("Invalid leaf hash"))?;
+        entry.proof = ProofVector {
+            pos,
+            items: items.iter().map(hex::encode).collect(),
+            compact: hex::encode(CompactProof { tree_size: mmr_size, positions: vec![pos], items: items.clone() }.encode()),
+            solidity_calldata: to_0x(SolidityProof { mmr_size, pos, leaf, items, root }.inclusion_calldata()),
+        };
+    }
+    let positions: Vec<u64> = staged.iter().map(|entry| entry.proof.pos).collect();
+    let batch_items = proof_items(&store, mmr_size, positions.clone())?;
+    Ok(VectorLog {
+        tenant_id: tenant_id.to_string(),
+        encoding,
+        public_key: hex::encode(signer.public_key().to_bytes()),
+        entries: staged,
+        tree_size: mmr_size,
+        root: hex::encode(root),
+        batch_proof: BatchProofVector {
+            items: batch_items.iter().map(hex::encode).collect(),
+            compact: hex::encode(CompactProof { tree_size: mmr_size, positions: positions.clone(), items: batch_items }.encode()),
+            positions,
+        },
+    })
+}
+
+fn proof_items(store: &MemStore<[u8; 32]>, mmr_size: u64, positions: Vec<u64>) -> anyhow::Result<Vec<[u8; 32]>> {
+    let proof = MMR::<[u8; 32], MergeBlake3, _>::new(mmr_size, store)
+        .gen_proof(positions)
+        .map_err(|e| anyhow::anyhow!("MMR gen_proof error: {}", e))?;
+    Ok(proof.proof_items().to_vec())
+}
+
+/// 固定证据：覆盖每一代规范布局 (可选字段逐个引入)、历史字符串置信度、加盐叶子、派生密钥与更正链
+fn fixtures() -> anyhow::Result<Vec<Fixture>> {
+    let base = |index: i64| Evidence {
+        image_phash: "jPDw8PDw8PA=".to_string(),
+        image_sha256: hex::encode(blake3::hash(format!("golden image {}", index).as_bytes()).as_bytes()),
+        verdict: index % 2 == 0,
+        confidence: Confidence::BasisPoints(9000 + index as u16),
+        activated_prompts: vec![3, 7, 12],
+        prompt_pool_hash: "golden-model-v1".to_string(),
+        external_knowledge_hash: hex::encode([0x11; 32]),
+        timestamp: BASE_TIMESTAMP + index,
+        claimed_at: None,
+        consensus: None,
+        calibration: None,
+        provenance: None,
+        client_context: None,
+    };
+    let fixture = |name, description, evidence| Fixture { name, description, evidence, salt: None, derived: false, supersedes: None };
+
+    Ok(vec![
+        fixture("minimal", "No optional fields (layout before claimed_at)", base(0)),
+        fixture("legacy_confidence", "Confidence stored as the original string (layout before fixed-point confidence)", Evidence {
+            confidence: Confidence::Legacy("0.93".to_string()),
+            ..base(1)
+        }),
+        fixture("claimed_at", "Submitter-claimed time", Evidence { claimed_at: Some(BASE_TIMESTAMP - 3600), ..base(2) }),
+        fixture("consensus", "Multi-model consensus", Evidence {
+            consensus: Some(Consensus {
+                rule: ConsensusRule::Majority,
+                models: vec![
+                    ModelVerdict { prompt_pool_hash: "golden-model-v1".to_string(), verdict: true, confidence: Confidence::BasisPoints(9100) },
+                    ModelVerdict { prompt_pool_hash: "golden-model-v2".to_string(), verdict: true, confidence: Confidence::BasisPoints(8700) },
+                    ModelVerdict { prompt_pool_hash: "golden-model-v3".to_string(), verdict: false, confidence: Confidence::BasisPoints(6000) },
+                ],
+            }),
+            ..base(3)
+        }),
+        fixture("calibration", "Model calibration (claimed_at and consensus absent)", Evidence {
+            calibration: Some(Calibration { version: "temp-2026.1".to_string(), temperature_milli: 1250, threshold_bps: 5000 }),
+            ..base(4)
+        }),
+        fixture("provenance", "Submitter provenance with a missing field", Evidence {
+            provenance: Some(Provenance {
+                mode: ProvenanceMode::Hashed,
+                ip: Some(hex::encode(blake3::hash(b"203.0.113.7").as_bytes())),
+                client_cert_subject: None,
+                user_agent: Some(hex::encode(blake3::hash(b"golden-client/1.0").as_bytes())),
+            }),
+            ..base(5)
+        }),
+        fixture("client_context", "Client context with keys whose BCS order differs from string order", Evidence {
+            claimed_at: Some(BASE_TIMESTAMP - 60),
+            client_context: Some(ClientContext(BTreeMap::from([
+                ("ticket".to_string(), "CASE-2026-0042".to_string()),
+                ("b".to_string(), "short key".to_string()),
+                ("pipeline_id".to_string(), "ingest-7".to_string()),
+            ]))),
+            ..base(6)
+        }),
+        Fixture { salt: Some(*blake3::hash(b"golden salt").as_bytes()), ..fixture("salted", "Salted leaf: leaf hash differs from evidence ID", base(7)) },
+        Fixture { derived: true, ..fixture("derived_key", "Signed by the department key m/0'/1' (receipt layout with key_path)", base(8)) },
+        Fixture { supersedes: Some(0), ..fixture("supersedes", "Correction of the first entry (full receipt layout)", base(9)) },
+    ])
+}
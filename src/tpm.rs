@@ -0,0 +1,191 @@
+use log::info;
+use serde::{Deserialize, Serialize};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use zeroize::Zeroizing;
+
+use crate::config::TpmConfig;
+use crate::keystore;
+use crate::signer::EvidenceSigner;
+
+/// 封存文件的当前版本
+const SEALED_SEED_VERSION: u32 = 1;
+
+/// 可用于封存策略的 PCR 库
+const PCR_BANKS: &[&str] = &["sha1", "sha256", "sha384", "sha512"];
+
+/// TPM 封存的签名 Seed (JSON)
+///
+/// `private` 由 TPM 的存储主密钥加密，只有同一颗 TPM 能载入；载入后还须当前 PCR 与封存时一致才能解封。
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SealedSeed {
+    pub version: u32,
+    /// 封存策略绑定的 PCR 选择 (tpm2-tools 写法，如 `sha256:0,2,4,7`)
+    pub pcrs: String,
+    /// 封存对象的 `TPM2B_PUBLIC` (hex)
+    pub public: String,
+    /// 封存对象的 `TPM2B_PRIVATE` (hex)
+    pub private: String,
+    /// 对应的公钥 (hex)，无需解封即可查看身份
+    pub public_key: String,
+    /// 封存时间 (Unix 秒)
+    pub created_at: i64,
+}
+
+impl SealedSeed {
+    pub fn load(path: &Path) -> anyhow::Result<Self> {
+        let bytes = std::fs::read(path).map_err(|e| anyhow::anyhow!("Cannot read sealed seed '{}': {}", path.display(), e))?;
+        let sealed: Self = serde_json::from_slice(&bytes)
+            .map_err(|e| anyhow::anyhow!("'{}' is not a TPM sealed seed: {}", path.display(), e))?;
+        if sealed.version != SEALED_SEED_VERSION {
+            return Err(anyhow::anyhow!("Unsupported sealed seed version {}", sealed.version));
+        }
+        Ok(sealed)
+    }
+
+    pub fn write(&self, path: &Path) -> anyhow::Result<()> {
+        keystore::write_secret(path, &serde_json::to_vec_pretty(self)?)
+    }
+}
+
+/// 模块：TPM 2.0 封存密钥 (TPM Sealed Seed)
+///
+/// **为什么需要**: 加密身份文件的口令总得交给服务进程 (环境变量或启动脚本)，拿到磁盘镜像的人往往也拿到了口令；
+/// 换了内核或引导链的机器照样能启动服务、照样能签名。这里把 Seed 封存进主机的 TPM，策略绑定一组 PCR：
+/// 只有这颗 TPM、且固件 / 引导程序 / 内核的度量与封存时完全一致，Seed 才能解封。
+/// 磁盘被拷走、引导链被篡改 (包括从另一块盘启动) 时服务起不来，而不是带着篡改过的系统继续出具证据。
+///
+/// - 与 TPM 的交互交给 `tpm2-tools` (`tpm2_createprimary` / `tpm2_create` / `tpm2_unseal`)，Seed 只经管道传递，不落临时文件；
+/// - 主密钥每次按默认模板在所有者层级重新生成 (同一 TPM 结果相同)，不占用持久句柄；
+/// - 合法的固件或内核升级同样会改变 PCR，升级后须以助记词 `keygen --recover` 重新封存。
+pub fn seal(config: &TpmConfig, seed: &[u8; 32], public_key: &[u8; 32]) -> anyhow::Result<SealedSeed> {
+    let work = WorkDir::create()?;
+    run(config, "tpm2_createprimary", &["-Q", "-C", "o", "-c", &work.file("primary.ctx")], None)?;
+    run(config, "tpm2_createpolicy", &["-Q", "--policy-pcr", "-l", &config.pcrs, "-L", &work.file("pcr.policy")], None)?;
+    run(
+        config,
+        "tpm2_create",
+        &[
+            "-Q",
+            "-C", &work.file("primary.ctx"),
+            "-L", &work.file("pcr.policy"),
+            "-i", "-",
+            "-u", &work.file("seed.pub"),
+            "-r", &work.file("seed.priv"),
+        ],
+        Some(seed),
+    )?;
+    Ok(SealedSeed {
+        version: SEALED_SEED_VERSION,
+        pcrs: config.pcrs.clone(),
+        public: hex::encode(std::fs::read(work.file("seed.pub"))?),
+        private: hex::encode(std::fs::read(work.file("seed.priv"))?),
+        public_key: hex::encode(public_key),
+        created_at: chrono::Utc::now().timestamp(),
+    })
+}
+
+/// 解封 `signer.tpm.sealed_path` 中的 Seed；PCR 与封存时不一致时 TPM 拒绝解封
+pub fn unseal(config: &TpmConfig) -> anyhow::Result<Zeroizing<[u8; 32]>> {
+    let sealed = SealedSeed::load(Path::new(&config.sealed_path))?;
+    info!("🔐 正在从 TPM 解封签名 Seed: '{}' (PCR {})", config.sealed_path, sealed.pcrs);
+    let work = WorkDir::create()?;
+    std::fs::write(work.file("seed.pub"), hex::decode(&sealed.public)?)?;
+    std::fs::write(work.file("seed.priv"), hex::decode(&sealed.private)?)?;
+    run(config, "tpm2_createprimary", &["-Q", "-C", "o", "-c", &work.file("primary.ctx")], None)?;
+    run(
+        config,
+        "tpm2_load",
+        &["-Q", "-C", &work.file("primary.ctx"), "-u", &work.file("seed.pub"), "-r", &work.file("seed.priv"), "-c", &work.file("seed.ctx")],
+        None,
+    )
+    .map_err(|e| anyhow::anyhow!("{} (sealed on a different TPM?)", e))?;
+    let output = Zeroizing::new(
+        run(config, "tpm2_unseal", &["-c", &work.file("seed.ctx"), "-p", &format!("pcr:{}", sealed.pcrs)], None)
+            .map_err(|e| anyhow::anyhow!("{} (PCR {} differ from when the seed was sealed: boot chain changed?)", e, sealed.pcrs))?,
+    );
+    let seed: [u8; 32] = output
+        .as_slice()
+        .try_into()
+        .map_err(|_| anyhow::anyhow!("TPM returned {} bytes, not a 32-byte seed", output.len()))?;
+    let seed = Zeroizing::new(seed);
+    if hex::encode(EvidenceSigner::from_seed(&seed).public_key().to_bytes()) != sealed.public_key {
+        return Err(anyhow::anyhow!("Unsealed seed does not match public key {}", sealed.public_key));
+    }
+    Ok(seed)
+}
+
+/// 封存时间；文件不可读时为 None
+pub fn created_at(config: &TpmConfig) -> Option<i64> {
+    SealedSeed::load(Path::new(&config.sealed_path)).ok().map(|sealed| sealed.created_at)
+}
+
+/// 校验 PCR 选择的写法：`<bank>:<n>[,<n>...]`，多个库以 `+` 连接，PCR 编号 0 ~ 23
+pub fn validate_pcrs(pcrs: &str) -> Result<(), String> {
+    for selection in pcrs.split('+') {
+        let (bank, indices) = selection
+            .split_once(':')
+            .ok_or_else(|| format!("'{}' is not '<bank>:<pcr>,...'", selection))?;
+        if !PCR_BANKS.contains(&bank) {
+            return Err(format!("unknown PCR bank '{}' (expected {})", bank, PCR_BANKS.join(" | ")));
+        }
+        for index in indices.split(',') {
+            if !index.parse::<u8>().is_ok_and(|index| index <= 23) {
+                return Err(format!("invalid PCR index '{}' (expected 0 ~ 23)", index));
+            }
+        }
+    }
+    Ok(())
+}
+
+/// 运行一个 tpm2-tools 命令；`tcti` 非空时经 `TPM2TOOLS_TCTI` 指定 TPM
+fn run(config: &TpmConfig, tool: &str, args: &[&str], input: Option<&[u8]>) -> anyhow::Result<Vec<u8>> {
+    let mut command = Command::new(tool);
+    if !config.tcti.is_empty() {
+        command.env("TPM2TOOLS_TCTI", &config.tcti);
+    }
+    let mut child = command
+        .args(args)
+        .stdin(if input.is_some() { Stdio::piped() } else { Stdio::null() })
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| anyhow::anyhow!("Cannot run '{}': {}", tool, e))?;
+    if let (Some(mut stdin), Some(input)) = (child.stdin.take(), input) {
+        stdin.write_all(input)?;
+    }
+    let output = child.wait_with_output()?;
+    if !output.status.success() {
+        return Err(anyhow::anyhow!("{} failed ({}): {}", tool, output.status, String::from_utf8_lossy(&output.stderr).trim()));
+    }
+    Ok(output.stdout)
+}
+
+/// 存放 TPM 上下文与封存对象的临时目录 (仅属主可访问)，用完即删
+struct WorkDir(PathBuf);
+
+impl WorkDir {
+    fn create() -> anyhow::Result<Self> {
+        let nanos = std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH)?.subsec_nanos();
+        let path = std::env::temp_dir().join(format!("yuanjing-tpm-{}-{}", std::process::id(), nanos));
+        let mut builder = std::fs::DirBuilder::new();
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::DirBuilderExt;
+            builder.mode(0o700);
+        }
+        builder.create(&path)?;
+        Ok(Self(path))
+    }
+
+    fn file(&self, name: &str) -> String {
+        self.0.join(name).to_string_lossy().into_owned()
+    }
+}
+
+impl Drop for WorkDir {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.0);
+    }
+}
@@ -0,0 +1,581 @@
+use rustls_pki_types::{CertificateDer, SignatureVerificationAlgorithm};
+use sha2::{Digest, Sha256, Sha384, Sha512};
+use std::borrow::Cow;
+use std::str::FromStr;
+use webpki::KeyUsage;
+use x509_parser::der_parser::oid::Oid;
+use x509_parser::extensions::ParsedExtension;
+use x509_parser::parse_x509_certificate;
+
+use crate::x509::{CertificateChain, TrustStore};
+
+/// CMS SignedData (RFC 5652)
+const OID_SIGNED_DATA: &str = "1.2.840.113549.1.7.2";
+/// 时间戳令牌的内容类型 id-ct-TSTInfo (RFC 3161)
+const OID_TST_INFO: &str = "1.2.840.113549.1.9.16.1.4";
+/// 签名属性：内容类型
+const OID_CONTENT_TYPE: &str = "1.2.840.113549.1.9.3";
+/// 签名属性：内容摘要
+const OID_MESSAGE_DIGEST: &str = "1.2.840.113549.1.9.4";
+const OID_SHA256: &str = "2.16.840.1.101.3.4.2.1";
+const OID_SHA384: &str = "2.16.840.1.101.3.4.2.2";
+const OID_SHA512: &str = "2.16.840.1.101.3.4.2.3";
+const OID_RSA_ENCRYPTION: &str = "1.2.840.113549.1.1.1";
+const OID_SHA256_WITH_RSA: &str = "1.2.840.113549.1.1.11";
+const OID_SHA384_WITH_RSA: &str = "1.2.840.113549.1.1.12";
+const OID_SHA512_WITH_RSA: &str = "1.2.840.113549.1.1.13";
+const OID_ECDSA_WITH_SHA256: &str = "1.2.840.10045.4.3.2";
+const OID_ECDSA_WITH_SHA384: &str = "1.2.840.10045.4.3.3";
+const OID_ED25519: &str = "1.3.101.112";
+
+/// 扩展密钥用途 id-kp-timeStamping (1.3.6.1.5.5.7.3.8) 的 DER 编码，TSA 证书必须带有
+const EKU_TIME_STAMPING: &[u8] = &[0x2b, 0x06, 0x01, 0x05, 0x05, 0x07, 0x03, 0x08];
+
+const TAG_BOOLEAN: u8 = 0x01;
+const TAG_INTEGER: u8 = 0x02;
+const TAG_OCTET_STRING: u8 = 0x04;
+const TAG_NULL: u8 = 0x05;
+const TAG_OID: u8 = 0x06;
+const TAG_UTF8_STRING: u8 = 0x0c;
+const TAG_GENERALIZED_TIME: u8 = 0x18;
+const TAG_SEQUENCE: u8 = 0x30;
+const TAG_SET: u8 = 0x31;
+/// `[0]` 隐式标签 (原始类型)，SignerIdentifier 中的 subjectKeyIdentifier
+const TAG_CONTEXT_0: u8 = 0x80;
+/// `[0]` / `[1]` 标签 (构造类型)
+const TAG_CONSTRUCTED_0: u8 = 0xa0;
+const TAG_CONSTRUCTED_1: u8 = 0xa1;
+
+/// 时间戳请求 (TimeStampReq)：只提交消息摘要，原文不离开本机
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TimeStampRequest {
+    /// 消息的 SHA-256 摘要
+    pub digest: [u8; 32],
+    /// 随机数，TSA 须在令牌中原样返回，防止重放旧令牌
+    pub nonce: u64,
+    /// 要求的时间戳策略 (OID)，为空时由 TSA 选择
+    pub policy: Option<String>,
+}
+
+impl TimeStampRequest {
+    pub fn new(message: &[u8], nonce: u64, policy: Option<&str>) -> Self {
+        Self { digest: Sha256::digest(message).into(), nonce, policy: policy.map(str::to_string) }
+    }
+
+    /// DER 编码，以 `application/timestamp-query` 发送；要求 TSA 在令牌中附带签名证书 (`certReq`)
+    pub fn to_der(&self) -> anyhow::Result<Vec<u8>> {
+        let mut fields = vec![
+            tlv(TAG_INTEGER, &[1]),
+            tlv(TAG_SEQUENCE, &[tlv(TAG_SEQUENCE, &[oid(OID_SHA256)?, tlv(TAG_NULL, &[])].concat()), tlv(TAG_OCTET_STRING, &self.digest)].concat()),
+        ];
+        if let Some(policy) = &self.policy {
+            fields.push(oid(policy)?);
+        }
+        fields.push(tlv(TAG_INTEGER, &unsigned(self.nonce)));
+        fields.push(tlv(TAG_BOOLEAN, &[0xff]));
+        Ok(tlv(TAG_SEQUENCE, &fields.concat()))
+    }
+}
+
+/// 令牌中经 TSA 签名的内容 (TSTInfo)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TstInfo {
+    /// 时间戳策略 (OID)
+    pub policy: String,
+    /// 消息摘要算法 (OID)
+    pub hash_algorithm: String,
+    pub hashed_message: Vec<u8>,
+    /// 令牌序列号 (INTEGER 的内容字节)
+    pub serial_number: Vec<u8>,
+    /// 签发时间 (Unix 秒，小数部分舍去)
+    pub gen_time: i64,
+    pub nonce: Option<u64>,
+}
+
+/// 签名者标识 (SignerIdentifier)
+#[derive(Debug, Clone)]
+enum SignerId {
+    IssuerAndSerial { issuer: Vec<u8>, serial: Vec<u8> },
+    SubjectKeyId(Vec<u8>),
+}
+
+/// 模块：RFC 3161 时间戳 (Time-Stamp Protocol)
+///
+/// **为什么需要**: 交叉公证要能直接对接通用的时间戳机构 (TSA)，而不是只认本服务自定的副署协议。
+/// 时间戳令牌 (TimeStampToken) 是内容为 TSTInfo 的 CMS SignedData，验证方用 `openssl ts -verify` 等现成工具即可独立校验。
+///
+/// 只实现客户端需要的部分：编码请求、解析响应与令牌、校验签名与证书链。
+/// 令牌只接受 DER 编码，且恰有一个签名者 (RFC 3161 §2.4.2)。
+#[derive(Debug, Clone)]
+pub struct TimeStampToken {
+    der: Vec<u8>,
+    info: TstInfo,
+    /// DER 编码的 TSTInfo (签名属性中的内容摘要即对它计算)
+    content: Vec<u8>,
+    certificates: Vec<CertificateDer<'static>>,
+    signer: SignerId,
+    digest_algorithm: String,
+    /// 签名属性，标签已按 RFC 5652 §5.4 换回 SET
+    signed_attributes: Vec<u8>,
+    content_type: String,
+    message_digest: Vec<u8>,
+    signature_algorithm: String,
+    signature: Vec<u8>,
+}
+
+impl TimeStampToken {
+    /// 解析 TSA 的响应 (TimeStampResp)；状态不是 granted / grantedWithMods 时返回 TSA 给出的原因
+    pub fn from_response(response: &[u8]) -> anyhow::Result<Self> {
+        let mut outer = Der::new(response);
+        let mut resp = Der::new(outer.expect(TAG_SEQUENCE)?);
+        outer.finish()?;
+        let mut status_info = Der::new(resp.expect(TAG_SEQUENCE)?);
+        let status = integer(status_info.expect(TAG_INTEGER)?)?;
+        if status > 1 {
+            let mut reason = Vec::new();
+            if let Some(text) = status_info.optional(TAG_SEQUENCE)? {
+                let mut text = Der::new(text);
+                while !text.is_empty() {
+                    reason.push(String::from_utf8_lossy(text.expect(TAG_UTF8_STRING)?).into_owned());
+                }
+            }
+            return Err(anyhow::anyhow!("TSA rejected the request (status {}): {}", status, reason.join("; ")));
+        }
+        let (tag, _, token) = resp.next()?;
+        if tag != TAG_SEQUENCE {
+            return Err(anyhow::anyhow!("TSA response carries no time-stamp token"));
+        }
+        resp.finish()?;
+        Self::from_der(token.to_vec())
+    }
+
+    pub fn from_der(der: Vec<u8>) -> anyhow::Result<Self> {
+        let mut outer = Der::new(&der);
+        let mut content_info = Der::new(outer.expect(TAG_SEQUENCE)?);
+        outer.finish()?;
+        if oid_string(content_info.expect(TAG_OID)?) != OID_SIGNED_DATA {
+            return Err(anyhow::anyhow!("Time-stamp token is not CMS SignedData"));
+        }
+        let mut explicit = Der::new(content_info.expect(TAG_CONSTRUCTED_0)?);
+        content_info.finish()?;
+        let mut signed_data = Der::new(explicit.expect(TAG_SEQUENCE)?);
+        explicit.finish()?;
+
+        signed_data.expect(TAG_INTEGER)?;
+        signed_data.expect(TAG_SET)?;
+        let mut encapsulated = Der::new(signed_data.expect(TAG_SEQUENCE)?);
+        if oid_string(encapsulated.expect(TAG_OID)?) != OID_TST_INFO {
+            return Err(anyhow::anyhow!("Time-stamp token does not encapsulate TSTInfo"));
+        }
+        let mut explicit = Der::new(encapsulated.expect(TAG_CONSTRUCTED_0)?);
+        encapsulated.finish()?;
+        let content = explicit.expect(TAG_OCTET_STRING)?.to_vec();
+        explicit.finish()?;
+
+        let mut certificates = Vec::new();
+        if let Some(bag) = signed_data.optional(TAG_CONSTRUCTED_0)? {
+            let mut bag = Der::new(bag);
+            while !bag.is_empty() {
+                let (tag, _, cert) = bag.next()?;
+                // 只收 X.509 证书，跳过属性证书等其他选项
+                if tag == TAG_SEQUENCE {
+                    certificates.push(CertificateDer::from(cert.to_vec()));
+                }
+            }
+        }
+        signed_data.optional(TAG_CONSTRUCTED_1)?;
+        let mut signer_infos = Der::new(signed_data.expect(TAG_SET)?);
+        signed_data.finish()?;
+        let mut signer_info = Der::new(signer_infos.expect(TAG_SEQUENCE)?);
+        if !signer_infos.is_empty() {
+            return Err(anyhow::anyhow!("Time-stamp token must have exactly one signer"));
+        }
+
+        signer_info.expect(TAG_INTEGER)?;
+        let (tag, contents, _) = signer_info.next()?;
+        let signer = match tag {
+            TAG_SEQUENCE => {
+                let mut sid = Der::new(contents);
+                let (_, _, issuer) = sid.next()?;
+                let serial = sid.expect(TAG_INTEGER)?.to_vec();
+                sid.finish()?;
+                SignerId::IssuerAndSerial { issuer: issuer.to_vec(), serial }
+            }
+            TAG_CONTEXT_0 => SignerId::SubjectKeyId(contents.to_vec()),
+            _ => return Err(anyhow::anyhow!("Unsupported signer identifier in time-stamp token")),
+        };
+        let digest_algorithm = algorithm(signer_info.expect(TAG_SEQUENCE)?)?;
+        if signer_info.peek() != Some(TAG_CONSTRUCTED_0) {
+            return Err(anyhow::anyhow!("Time-stamp token has no signed attributes"));
+        }
+        let (_, attributes, raw) = signer_info.next()?;
+        let mut signed_attributes = raw.to_vec();
+        signed_attributes[0] = TAG_SET;
+        let signature_algorithm = algorithm(signer_info.expect(TAG_SEQUENCE)?)?;
+        let signature = signer_info.expect(TAG_OCTET_STRING)?.to_vec();
+        signer_info.optional(TAG_CONSTRUCTED_1)?;
+        signer_info.finish()?;
+
+        let (mut content_type, mut message_digest) = (None, None);
+        let mut attributes = Der::new(attributes);
+        while !attributes.is_empty() {
+            let mut attribute = Der::new(attributes.expect(TAG_SEQUENCE)?);
+            let kind = oid_string(attribute.expect(TAG_OID)?);
+            let mut values = Der::new(attribute.expect(TAG_SET)?);
+            attribute.finish()?;
+            let slot = match kind.as_str() {
+                OID_CONTENT_TYPE => (&mut content_type, oid_string(values.expect(TAG_OID)?).into_bytes()),
+                OID_MESSAGE_DIGEST => (&mut message_digest, values.expect(TAG_OCTET_STRING)?.to_vec()),
+                _ => continue,
+            };
+            values.finish()?;
+            if slot.0.replace(slot.1).is_some() {
+                return Err(anyhow::anyhow!("Duplicate signed attribute {} in time-stamp token", kind));
+            }
+        }
+        let content_type = String::from_utf8(content_type.ok_or_else(|| anyhow::anyhow!("Time-stamp token has no content-type attribute"))?)?;
+        let message_digest = message_digest.ok_or_else(|| anyhow::anyhow!("Time-stamp token has no message-digest attribute"))?;
+
+        let info = TstInfo::from_der(&content)?;
+        Ok(Self {
+            der,
+            info,
+            content,
+            certificates,
+            signer,
+            digest_algorithm,
+            signed_attributes,
+            content_type,
+            message_digest,
+            signature_algorithm,
+            signature,
+        })
+    }
+
+    pub fn as_der(&self) -> &[u8] {
+        &self.der
+    }
+
+    pub fn info(&self) -> &TstInfo {
+        &self.info
+    }
+
+    /// 完整校验：令牌盖的是 `message` 的摘要，TSA 签名有效，且签名证书在签发时刻可追溯到 `trust` 中的受信根并带有时间戳用途
+    pub fn verify(&self, message: &[u8], trust: &TrustStore) -> anyhow::Result<()> {
+        if digest(&self.info.hash_algorithm, message)? != self.info.hashed_message {
+            return Err(anyhow::anyhow!("Time-stamp token covers a different message"));
+        }
+        if self.content_type != OID_TST_INFO {
+            return Err(anyhow::anyhow!("Time-stamp token content-type attribute is not TSTInfo"));
+        }
+        if digest(&self.digest_algorithm, &self.content)? != self.message_digest {
+            return Err(anyhow::anyhow!("Time-stamp token message-digest attribute does not match TSTInfo"));
+        }
+        let position = self
+            .certificates
+            .iter()
+            .position(|cert| self.issued_to_signer(cert))
+            .ok_or_else(|| anyhow::anyhow!("Time-stamp token does not include the TSA certificate"))?;
+        // 签名者证书在前，其余证书作为中间 CA 候选
+        let mut certs = self.certificates.clone();
+        let signer = certs.remove(position);
+        certs.insert(0, signer);
+        let chain = CertificateChain::from_der(certs)?;
+        chain.verify_signature(
+            &signature_algorithms(&self.signature_algorithm, &self.digest_algorithm)?,
+            &self.signed_attributes,
+            &self.signature,
+        )?;
+        chain.verify_chain(trust, self.info.gen_time, KeyUsage::required(EKU_TIME_STAMPING))
+    }
+
+    fn issued_to_signer(&self, cert: &CertificateDer) -> bool {
+        let Ok((_, cert)) = parse_x509_certificate(cert) else {
+            return false;
+        };
+        match &self.signer {
+            SignerId::IssuerAndSerial { issuer, serial } => {
+                cert.tbs_certificate.issuer.as_raw() == issuer.as_slice() && cert.tbs_certificate.raw_serial() == serial.as_slice()
+            }
+            SignerId::SubjectKeyId(key_id) => cert.extensions().iter().any(|extension| {
+                matches!(extension.parsed_extension(), ParsedExtension::SubjectKeyIdentifier(id) if id.0 == key_id.as_slice())
+            }),
+        }
+    }
+}
+
+impl TstInfo {
+    fn from_der(der: &[u8]) -> anyhow::Result<Self> {
+        let mut outer = Der::new(der);
+        let mut info = Der::new(outer.expect(TAG_SEQUENCE)?);
+        outer.finish()?;
+        if integer(info.expect(TAG_INTEGER)?)? != 1 {
+            return Err(anyhow::anyhow!("Unsupported TSTInfo version"));
+        }
+        let policy = oid_string(info.expect(TAG_OID)?);
+        let mut imprint = Der::new(info.expect(TAG_SEQUENCE)?);
+        let hash_algorithm = algorithm(imprint.expect(TAG_SEQUENCE)?)?;
+        let hashed_message = imprint.expect(TAG_OCTET_STRING)?.to_vec();
+        imprint.finish()?;
+        let serial_number = info.expect(TAG_INTEGER)?.to_vec();
+        let gen_time = generalized_time(info.expect(TAG_GENERALIZED_TIME)?)?;
+        info.optional(TAG_SEQUENCE)?;
+        info.optional(TAG_BOOLEAN)?;
+        let nonce = info.optional(TAG_INTEGER)?.map(integer).transpose()?;
+        info.optional(TAG_CONSTRUCTED_0)?;
+        info.optional(TAG_CONSTRUCTED_1)?;
+        info.finish()?;
+        Ok(Self { policy, hash_algorithm, hashed_message, serial_number, gen_time, nonce })
+    }
+}
+
+/// 签名算法与签名属性摘要算法对应的候选校验算法 (ECDSA 的曲线由证书公钥决定，两种都试)
+fn signature_algorithms(signature: &str, digest: &str) -> anyhow::Result<Vec<&'static dyn SignatureVerificationAlgorithm>> {
+    use webpki::ring::*;
+
+    Ok(match (signature, digest) {
+        (OID_RSA_ENCRYPTION, OID_SHA256) | (OID_SHA256_WITH_RSA, _) => vec![RSA_PKCS1_2048_8192_SHA256],
+        (OID_RSA_ENCRYPTION, OID_SHA384) | (OID_SHA384_WITH_RSA, _) => vec![RSA_PKCS1_2048_8192_SHA384],
+        (OID_RSA_ENCRYPTION, OID_SHA512) | (OID_SHA512_WITH_RSA, _) => vec![RSA_PKCS1_2048_8192_SHA512],
+        (OID_ECDSA_WITH_SHA256, _) => vec![ECDSA_P256_SHA256, ECDSA_P384_SHA256],
+        (OID_ECDSA_WITH_SHA384, _) => vec![ECDSA_P384_SHA384, ECDSA_P256_SHA384],
+        (OID_ED25519, _) => vec![ED25519],
+        _ => return Err(anyhow::anyhow!("Unsupported time-stamp signature algorithm {} with digest {}", signature, digest)),
+    })
+}
+
+fn digest(algorithm: &str, data: &[u8]) -> anyhow::Result<Vec<u8>> {
+    Ok(match algorithm {
+        OID_SHA256 => Sha256::digest(data).to_vec(),
+        OID_SHA384 => Sha384::digest(data).to_vec(),
+        OID_SHA512 => Sha512::digest(data).to_vec(),
+        other => return Err(anyhow::anyhow!("Unsupported digest algorithm {}", other)),
+    })
+}
+
+/// AlgorithmIdentifier 中的算法 OID (参数忽略)
+fn algorithm(contents: &[u8]) -> anyhow::Result<String> {
+    Ok(oid_string(Der::new(contents).expect(TAG_OID)?))
+}
+
+fn oid_string(contents: &[u8]) -> String {
+    Oid::new(Cow::Borrowed(contents)).to_id_string()
+}
+
+/// 点分 OID 的 DER 编码 (含标签与长度)
+fn oid(dotted: &str) -> anyhow::Result<Vec<u8>> {
+    let oid = Oid::from_str(dotted).map_err(|e| anyhow::anyhow!("Invalid OID '{}': {:?}", dotted, e))?;
+    Ok(tlv(TAG_OID, oid.as_bytes()))
+}
+
+/// 非负 INTEGER 的内容字节 (最短编码，最高位为 1 时补零)
+fn unsigned(value: u64) -> Vec<u8> {
+    let bytes = value.to_be_bytes();
+    let start = bytes.iter().position(|byte| *byte != 0).unwrap_or(7);
+    let mut out = Vec::with_capacity(9);
+    if bytes[start] & 0x80 != 0 {
+        out.push(0);
+    }
+    out.extend_from_slice(&bytes[start..]);
+    out
+}
+
+/// 不超过 64 位的非负 INTEGER
+fn integer(contents: &[u8]) -> anyhow::Result<u64> {
+    let digits = match contents {
+        [] => return Err(anyhow::anyhow!("Empty INTEGER")),
+        [first, ..] if first & 0x80 != 0 => return Err(anyhow::anyhow!("Negative INTEGER")),
+        [0, rest @ ..] if !rest.is_empty() => rest,
+        digits => digits,
+    };
+    if digits.len() > 8 {
+        return Err(anyhow::anyhow!("INTEGER does not fit in 64 bits"));
+    }
+    Ok(digits.iter().fold(0u64, |acc, byte| (acc << 8) | u64::from(*byte)))
+}
+
+/// GeneralizedTime (`YYYYMMDDHHMMSS[.f…]Z`，RFC 3161 要求 UTC)
+fn generalized_time(contents: &[u8]) -> anyhow::Result<i64> {
+    let text = std::str::from_utf8(contents)?;
+    let Some(fraction) = text.strip_suffix('Z').and_then(|text| text.get(14..)) else {
+        return Err(anyhow::anyhow!("Malformed GeneralizedTime '{}'", text));
+    };
+    if !(fraction.is_empty() || fraction.len() > 1 && fraction.starts_with('.') && fraction[1..].bytes().all(|b| b.is_ascii_digit())) {
+        return Err(anyhow::anyhow!("Malformed GeneralizedTime '{}'", text));
+    }
+    let time = chrono::NaiveDateTime::parse_from_str(&text[..14], "%Y%m%d%H%M%S")
+        .map_err(|e| anyhow::anyhow!("Malformed GeneralizedTime '{}': {}", text, e))?;
+    Ok(time.and_utc().timestamp())
+}
+
+fn tlv(tag: u8, contents: &[u8]) -> Vec<u8> {
+    let mut out = vec![tag];
+    match contents.len() {
+        len @ 0..=0x7f => out.push(len as u8),
+        len => {
+            let bytes = (len as u64).to_be_bytes();
+            let start = bytes.iter().position(|byte| *byte != 0).unwrap_or(7);
+            out.push(0x80 | (8 - start) as u8);
+            out.extend_from_slice(&bytes[start..]);
+        }
+    }
+    out.extend_from_slice(contents);
+    out
+}
+
+/// DER 读取器：按顺序取出 TLV，不支持不定长编码与多字节标签
+struct Der<'a> {
+    data: &'a [u8],
+}
+
+impl<'a> Der<'a> {
+    fn new(data: &'a [u8]) -> Self {
+        Self { data }
+    }
+
+    fn is_empty(&self) -> bool {
+        self.data.is_empty()
+    }
+
+    fn peek(&self) -> Option<u8> {
+        self.data.first().copied()
+    }
+
+    /// (标签, 内容, 含标签与长度的完整编码)
+    fn next(&mut self) -> anyhow::Result<(u8, &'a [u8], &'a [u8])> {
+        let truncated = || anyhow::anyhow!("Truncated DER value");
+        let [tag, first, rest @ ..] = self.data else {
+            return Err(truncated());
+        };
+        if tag & 0x1f == 0x1f {
+            return Err(anyhow::anyhow!("Unsupported multi-byte DER tag"));
+        }
+        let (len, header) = match *first {
+            len @ 0..=0x7f => (len as usize, 2),
+            0x81..=0x84 => {
+                let count = (first & 0x7f) as usize;
+                let bytes = rest.get(..count).ok_or_else(truncated)?;
+                let len = bytes.iter().fold(0usize, |acc, byte| (acc << 8) | usize::from(*byte));
+                if len < 0x80 || bytes[0] == 0 {
+                    return Err(anyhow::anyhow!("Non-minimal DER length"));
+                }
+                (len, 2 + count)
+            }
+            _ => return Err(anyhow::anyhow!("Unsupported DER length encoding")),
+        };
+        let end = header.checked_add(len).filter(|end| *end <= self.data.len()).ok_or_else(truncated)?;
+        let (raw, rest) = self.data.split_at(end);
+        self.data = rest;
+        Ok((*tag, &raw[header..], raw))
+    }
+
+    fn expect(&mut self, tag: u8) -> anyhow::Result<&'a [u8]> {
+        let (found, contents, _) = self.next()?;
+        if found != tag {
+            return Err(anyhow::anyhow!("Expected DER tag 0x{:02x}, found 0x{:02x}", tag, found));
+        }
+        Ok(contents)
+    }
+
+    fn optional(&mut self, tag: u8) -> anyhow::Result<Option<&'a [u8]>> {
+        if self.peek() == Some(tag) {
+            self.expect(tag).map(Some)
+        } else {
+            Ok(None)
+        }
+    }
+
+    fn finish(self) -> anyhow::Result<()> {
+        if !self.data.is_empty() {
+            return Err(anyhow::anyhow!("Trailing bytes after DER value"));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 测试 TSA 的响应 (`openssl ts -reply`)，请求由 `TimeStampRequest::to_der` 生成
+    const RESPONSE_ECDSA: &[u8] = include_bytes!("../data/mock/tsa/response_ecdsa.der");
+    const RESPONSE_RSA: &[u8] = include_bytes!("../data/mock/tsa/response_rsa.der");
+    /// 请求 SHA-1 摘要被拒绝的响应
+    const RESPONSE_REJECTED: &[u8] = include_bytes!("../data/mock/tsa/response_rejected.der");
+    const MESSAGE: &[u8] = b"yuanjing tsp fixture";
+    const NONCE: u64 = 0x8000_0000_0000_0001;
+    const POLICY: &str = "1.3.6.1.4.1.99999.2";
+
+    fn trust(name: &str) -> TrustStore {
+        TrustStore::load_pem(&[format!("{}/data/mock/tsa/{}", env!("CARGO_MANIFEST_DIR"), name)]).unwrap()
+    }
+
+    #[test]
+    fn request_encodes_imprint_policy_nonce_and_cert_req() {
+        let request = TimeStampRequest::new(MESSAGE, NONCE, Some(POLICY));
+        assert_eq!(
+            hex::encode(request.to_der().unwrap()),
+            "304f0201013031300d060960864801650304020105000420917d16c5942d693d1bddee580a4ba906a68fc9dd2c760b52bb05e473f56d5a68\
+             06092b06010401868d1f0202090080000000000000010101ff"
+        );
+        assert!(TimeStampRequest::new(MESSAGE, 1, Some("not-an-oid")).to_der().is_err());
+    }
+
+    #[test]
+    fn ecdsa_and_rsa_tokens_verify_against_the_tsa_root() {
+        for response in [RESPONSE_ECDSA, RESPONSE_RSA] {
+            let token = TimeStampToken::from_response(response).unwrap();
+            let info = token.info();
+            assert_eq!(info.policy, POLICY);
+            assert_eq!(info.nonce, Some(NONCE));
+            assert_eq!(info.hash_algorithm, OID_SHA256);
+            assert_eq!(info.hashed_message, Sha256::digest(MESSAGE).to_vec());
+            token.verify(MESSAGE, &trust("root.pem")).unwrap();
+
+            let reparsed = TimeStampToken::from_der(token.as_der().to_vec()).unwrap();
+            assert_eq!(reparsed.info(), info);
+        }
+    }
+
+    #[test]
+    fn tokens_are_rejected_for_other_messages_roots_or_tampered_signatures() {
+        let token = TimeStampToken::from_response(RESPONSE_ECDSA).unwrap();
+        assert!(token.verify(b"another message", &trust("root.pem")).is_err());
+        assert!(token.verify(MESSAGE, &trust("other_root.pem")).is_err());
+        assert!(token.verify(MESSAGE, &TrustStore::default()).is_err());
+
+        // SignerInfo 没有未签名属性，令牌的最后一个字节属于签名
+        let mut der = token.as_der().to_vec();
+        *der.last_mut().unwrap() ^= 0x01;
+        let tampered = TimeStampToken::from_der(der).unwrap();
+        assert!(tampered.verify(MESSAGE, &trust("root.pem")).is_err());
+
+        // 改动 TSTInfo 后签名属性中的内容摘要对不上
+        let mut tampered = token.clone();
+        let last = tampered.content.len() - 1;
+        tampered.content[last] ^= 0x01;
+        assert!(tampered.verify(MESSAGE, &trust("root.pem")).is_err());
+    }
+
+    #[test]
+    fn rejected_and_malformed_responses_fail_to_parse() {
+        let error = TimeStampToken::from_response(RESPONSE_REJECTED).unwrap_err().to_string();
+        assert!(error.contains("status 2") && error.contains("not supported"), "{}", error);
+
+        for len in [0, 1, 10, RESPONSE_ECDSA.len() / 2, RESPONSE_ECDSA.len() - 1] {
+            assert!(TimeStampToken::from_response(&RESPONSE_ECDSA[..len]).is_err());
+        }
+        let mut trailing = RESPONSE_ECDSA.to_vec();
+        trailing.push(0);
+        assert!(TimeStampToken::from_response(&trailing).is_err());
+    }
+
+    #[test]
+    fn generalized_time_requires_utc() {
+        assert_eq!(generalized_time(b"20261016174810Z").unwrap(), 1_792_172_890);
+        assert_eq!(generalized_time(b"20261016174810.25Z").unwrap(), 1_792_172_890);
+        for malformed in [&b"20261016174810"[..], b"20261016174810+0800", b"20261016174810.Z", b"202610161748Z"] {
+            assert!(generalized_time(malformed).is_err());
+        }
+    }
+}
@@ -0,0 +1,103 @@
+use log::info;
+use rustls_pki_types::pem::PemObject;
+use rustls_pki_types::CertificateDer;
+use serde::Deserialize;
+use std::sync::Arc;
+use std::time::Duration;
+use zeroize::Zeroizing;
+
+use crate::config::VaultConfig;
+use crate::keystore;
+
+/// 模块：Vault 密钥来源 (HashiCorp Vault KV v2)
+///
+/// **为什么需要**: 部分机构的合规要求禁止私钥以任何形式 (包括加密文件) 落盘。
+/// 此时签名 Seed 保存在 Vault 的 KV v2 引擎中，服务启动时读取一次，只存在于锁定的内存页里。
+///
+/// - 秘密字段可以是 64 位 Hex Seed，也可以是 `yuanjing keygen` 打印的 24 词助记词；
+/// - Token 从 `VAULT_TOKEN` 或 `token_file` (如 Vault Agent 的 sink 文件) 读取，不写进配置文件。
+///
+/// **为什么不用 Transit 远程签名**: 租户密钥 (Blake3-KDF) 与部门密钥 (SLIP-0010) 都从主 Seed 本地派生，
+/// Transit 中的密钥无法导出 Seed，也无法按我们的路径派生子密钥；
+/// 改为远程签名意味着每个租户 / 部门都要在 Vault 中单独建钥，且写入路径上每条证据多一次网络往返。
+pub fn fetch_seed(config: &VaultConfig) -> anyhow::Result<Zeroizing<[u8; 32]>> {
+    fetch_key(config, "签名 Seed")
+}
+
+/// 读取一把 32 字节密钥 (格式同 Seed)；`purpose` 只用于日志，如证据主密钥 (见 `at_rest.rs`)
+pub fn fetch_key(config: &VaultConfig, purpose: &str) -> anyhow::Result<Zeroizing<[u8; 32]>> {
+    let token = read_token(config)?;
+    let url = format!(
+        "{}/v1/{}/data/{}",
+        config.addr.trim_end_matches('/'),
+        config.mount.trim_matches('/'),
+        config.path.trim_matches('/')
+    );
+    info!("🔐 正在从 Vault 读取{}: {} (字段 '{}')", purpose, url, config.field);
+
+    let mut request = agent(config)?.get(&url).set("X-Vault-Token", &token);
+    if !config.namespace.is_empty() {
+        request = request.set("X-Vault-Namespace", &config.namespace);
+    }
+    let response = match request.call() {
+        Ok(response) => response,
+        Err(ureq::Error::Status(403, _)) => {
+            return Err(anyhow::anyhow!("Vault denied access to '{}' (check the token's policy)", url));
+        }
+        Err(ureq::Error::Status(404, _)) => return Err(anyhow::anyhow!("Vault secret '{}' not found", url)),
+        Err(e) => return Err(anyhow::anyhow!("Vault request to '{}' failed: {}", url, e)),
+    };
+    let body: KvResponse = serde_json::from_reader(response.into_reader())
+        .map_err(|e| anyhow::anyhow!("Unexpected Vault response from '{}': {}", url, e))?;
+    let mut data = body.data.data;
+    let value = Zeroizing::new(
+        data.remove(&config.field)
+            .ok_or_else(|| anyhow::anyhow!("Vault secret '{}' has no field '{}'", url, config.field))?,
+    );
+    keystore::parse_seed(value.trim())
+}
+
+/// KV v2 读取响应：`{"data": {"data": {...}, "metadata": {...}}}`
+#[derive(Deserialize)]
+struct KvResponse {
+    data: KvData,
+}
+
+#[derive(Deserialize)]
+struct KvData {
+    data: std::collections::HashMap<String, String>,
+}
+
+fn read_token(config: &VaultConfig) -> anyhow::Result<Zeroizing<String>> {
+    if let Ok(token) = std::env::var("VAULT_TOKEN") {
+        return Ok(Zeroizing::new(token));
+    }
+    if config.token_file.is_empty() {
+        return Err(anyhow::anyhow!("No Vault token: set VAULT_TOKEN or signer.vault.token_file"));
+    }
+    let token = Zeroizing::new(
+        std::fs::read_to_string(&config.token_file)
+            .map_err(|e| anyhow::anyhow!("Cannot read Vault token file '{}': {}", config.token_file, e))?,
+    );
+    Ok(Zeroizing::new(token.trim().to_string()))
+}
+
+/// 配置了 `ca_cert` 时只信任该 CA (与 Vault CLI 的 `VAULT_CACERT` 语义一致)，否则使用内置的公共根证书
+fn agent(config: &VaultConfig) -> anyhow::Result<ureq::Agent> {
+    let mut builder = ureq::AgentBuilder::new().timeout(Duration::from_secs(10));
+    if !config.ca_cert.is_empty() {
+        let mut roots = rustls::RootCertStore::empty();
+        for cert in CertificateDer::pem_file_iter(&config.ca_cert)
+            .map_err(|e| anyhow::anyhow!("Cannot read Vault CA certificate '{}': {:?}", config.ca_cert, e))?
+        {
+            let cert = cert.map_err(|e| anyhow::anyhow!("Invalid Vault CA certificate '{}': {:?}", config.ca_cert, e))?;
+            roots.add(cert)?;
+        }
+        let tls = rustls::ClientConfig::builder_with_provider(Arc::new(rustls::crypto::ring::default_provider()))
+            .with_safe_default_protocol_versions()?
+            .with_root_certificates(roots)
+            .with_no_client_auth();
+        builder = builder.tls_config(Arc::new(tls));
+    }
+    Ok(builder.build())
+}
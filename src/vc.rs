@@ -0,0 +1,110 @@
+use chrono::{DateTime, SecondsFormat};
+use ed25519_dalek::{Signature, VerifyingKey};
+use serde_json::{json, Value};
+use sha2::{Digest, Sha256};
+
+use crate::evidence::Evidence;
+use crate::receipt::Receipt;
+use crate::signer::EvidenceSigner;
+
+/// 数据完整性证明的密码套件：Ed25519 + JSON 规范化 (RFC 8785)
+pub const CRYPTOSUITE: &str = "eddsa-jcs-2022";
+
+/// 模块：可验证凭证 (W3C Verifiable Credential)
+///
+/// **职责**: 把一条已入库的证据包装成 VC 2.0 凭证，直接放进现有的 VC 钱包与验证器。
+/// - `issuer`: 鉴定中心的 DID (见 `did.rs`)；
+/// - `credentialSubject`: 图片指纹、AI 判定及其在 MMR 中的位置 (叶子哈希、位置、根、序列号)；
+/// - `proof`: Data Integrity 证明，套件为 `eddsa-jcs-2022`。
+///
+/// **为什么不用 Ed25519Signature2020**: 该套件要求先做 JSON-LD 展开与 RDF 数据集规范化 (URDNA2015)，
+/// 需要在线或内置全部上下文文档。`eddsa-jcs-2022` 是同一把 Ed25519 密钥、同一种 multibase 签名编码的
+/// W3C 标准套件，只把规范化换成 JCS，主流 Data Integrity 验证器均支持。
+pub fn issue(
+    evidence: &Evidence,
+    receipt: &Receipt,
+    issuer: &str,
+    verification_method: &str,
+    signer: &EvidenceSigner,
+    created: i64,
+) -> anyhow::Result<Value> {
+    let credential = json!({
+        "@context": ["https://www.w3.org/ns/credentials/v2"],
+        "id": format!("urn:yuanjing:{}:{}", receipt.tenant_id, receipt.evidence_hash),
+        "type": ["VerifiableCredential", "ForensicEvidenceCredential"],
+        "issuer": issuer,
+        "validFrom": rfc3339(receipt.timestamp)?,
+        "credentialSubject": {
+            "id": format!("urn:sha256:{}", evidence.image_sha256),
+            "imageSha256": evidence.image_sha256,
+            "imagePhash": evidence.image_phash,
+            "verdict": evidence.verdict,
+            "confidence": evidence.confidence,
+            "promptPoolHash": evidence.prompt_pool_hash,
+            "evidenceTimestamp": rfc3339(evidence.timestamp)?,
+            "evidenceHash": receipt.evidence_hash,
+            "leafPos": receipt.leaf_pos,
+            "root": receipt.root,
+            "treeSize": receipt.tree_size,
+            "seq": receipt.seq,
+            "tenantId": receipt.tenant_id,
+        },
+    });
+
+    let mut proof = json!({
+        "type": "DataIntegrityProof",
+        "cryptosuite": CRYPTOSUITE,
+        "created": rfc3339(created)?,
+        "verificationMethod": verification_method,
+        "proofPurpose": "assertionMethod",
+    });
+    let signature = signer.sign_bytes(&hash_data(&credential, &proof)?)?;
+    proof["proofValue"] = Value::String(format!("z{}", bs58::encode(signature.to_bytes()).into_string()));
+
+    let mut credential = credential;
+    credential["proof"] = proof;
+    Ok(credential)
+}
+
+/// 校验凭证上的 `eddsa-jcs-2022` 证明 (不解析 DID，公钥由调用方提供)
+pub fn verify(credential: &Value, public_key: &VerifyingKey) -> anyhow::Result<bool> {
+    let mut unsecured = credential.clone();
+    let mut proof = unsecured
+        .as_object_mut()
+        .and_then(|object| object.remove("proof"))
+        .ok_or_else(|| anyhow::anyhow!("Credential has no proof"))?;
+    if proof.get("cryptosuite").and_then(Value::as_str) != Some(CRYPTOSUITE) {
+        return Err(anyhow::anyhow!("Unsupported cryptosuite"));
+    }
+    let proof_value = proof
+        .as_object_mut()
+        .and_then(|object| object.remove("proofValue"))
+        .and_then(|value| value.as_str().map(str::to_string))
+        .ok_or_else(|| anyhow::anyhow!("Proof has no proofValue"))?;
+    let signature_bytes = proof_value
+        .strip_prefix('z')
+        .and_then(|encoded| bs58::decode(encoded).into_vec().ok())
+        .ok_or_else(|| anyhow::anyhow!("proofValue is not base58btc multibase"))?;
+    let signature = Signature::from_slice(&signature_bytes)
+        .map_err(|_| anyhow::anyhow!("proofValue is not an Ed25519 signature"))?;
+    Ok(EvidenceSigner::verify_bytes(public_key, &hash_data(&unsecured, &proof)?, &signature))
+}
+
+/// 待签名数据：SHA-256(JCS(证明配置)) || SHA-256(JCS(凭证))
+///
+/// 证明配置沿用凭证的 `@context`，与套件规范一致。
+fn hash_data(credential: &Value, proof: &Value) -> anyhow::Result<Vec<u8>> {
+    let mut proof_config = proof.clone();
+    if let Some(context) = credential.get("@context") {
+        proof_config["@context"] = context.clone();
+    }
+    let mut data = Sha256::digest(serde_jcs::to_vec(&proof_config)?).to_vec();
+    data.extend_from_slice(&Sha256::digest(serde_jcs::to_vec(credential)?));
+    Ok(data)
+}
+
+fn rfc3339(timestamp: i64) -> anyhow::Result<String> {
+    DateTime::from_timestamp(timestamp, 0)
+        .map(|time| time.to_rfc3339_opts(SecondsFormat::Secs, true))
+        .ok_or_else(|| anyhow::anyhow!("Timestamp {} out of range", timestamp))
+}
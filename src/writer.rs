@@ -0,0 +1,461 @@
+use log::{info, warn};
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use tokio::sync::{mpsc, oneshot};
+
+use crate::acl::EvidenceAcl;
+use crate::anomaly::{AnomalyFlag, AnomalyStatus};
+use crate::custody::{CustodyDetails, CustodyRecord};
+use crate::evidence::Evidence;
+use crate::ingest::Priority;
+use crate::integrity::IntegrityReport;
+use crate::legal_hold::{HoldEvent, LegalHold};
+use crate::model_registry::{AcceptedTargets, SignedRootMetadata, SignedTargetsMetadata};
+use crate::mmr_store::{AttestedEntry, CommitmentMode, ErasureRecord, EvidenceStore, Issuer, WalRecord};
+use crate::ops_log::OpsCheckpoint;
+use crate::prompts::{PromptInfo, PromptSet};
+use crate::tags::EvidenceTagSet;
+use crate::review::ReviewRecord;
+use crate::signer::{DerivedSigner, EvidenceSigner};
+use crate::telemetry::Handoff;
+
+/// 一次“签名并入库”请求
+pub struct AttestRequest {
+    pub evidence: Evidence,
+    pub nonce: Option<String>,
+    /// 被本条更正的早先位置
+    pub supersedes: Option<u64>,
+    /// 叶子承诺方式 (盲化叶子不保存原文与盐值)
+    pub commitment: CommitmentMode,
+    pub clock_uncertain: bool,
+    /// 使用的派生密钥；None 表示租户根密钥
+    pub key: Option<Arc<DerivedSigner>>,
+    /// 发起请求的主体，记入签名日志
+    pub principal: String,
+    /// 请求的优先级，写线程先处理高优先级的排队请求
+    pub priority: Priority,
+    /// 提交方的来源说明 (不签名，入库后另存，供检索)
+    pub source: String,
+}
+
+/// 写请求 (Write Command)
+pub enum WriteCommand {
+    /// 签名并入库
+    Attest {
+        request: Box<AttestRequest>,
+        /// 请求的追踪上下文 (写线程据此补记排队时长)
+        trace: Handoff,
+        reply: oneshot::Sender<anyhow::Result<AttestedEntry>>,
+    },
+    /// 注册模型白名单
+    RegisterModel {
+        hash: String,
+        description: String,
+        reply: oneshot::Sender<anyhow::Result<()>>,
+    },
+    /// 轮换模型注册表的根元数据
+    RotateRegistryRoot {
+        root: SignedRootMetadata,
+        reply: oneshot::Sender<anyhow::Result<()>>,
+    },
+    /// 接受模型注册表的目标元数据 (白名单随之整体替换)
+    UpdateRegistryTargets {
+        targets: SignedTargetsMetadata,
+        reply: oneshot::Sender<anyhow::Result<AcceptedTargets>>,
+    },
+    /// 保留期清理：删除签发时间早于 `cutoff` 的证据原文
+    Prune {
+        cutoff: i64,
+        reply: oneshot::Sender<anyhow::Result<u64>>,
+    },
+    /// 密码学擦除：销毁指定位置的叶子盐值
+    Erase {
+        pos: u64,
+        principal: String,
+        reason: String,
+        reply: oneshot::Sender<anyhow::Result<ErasureRecord>>,
+    },
+    /// 设置诉讼保全
+    PlaceHold {
+        pos: u64,
+        principal: String,
+        reason: String,
+        reply: oneshot::Sender<anyhow::Result<LegalHold>>,
+    },
+    /// 解除诉讼保全
+    ReleaseHold {
+        pos: u64,
+        principal: String,
+        reason: String,
+        reply: oneshot::Sender<anyhow::Result<HoldEvent>>,
+    },
+    /// 替换证据的访问控制列表
+    SetAcl {
+        pos: u64,
+        auditors: Vec<String>,
+        principal: String,
+        reply: oneshot::Sender<anyhow::Result<EvidenceAcl>>,
+    },
+    /// 替换证据的标签
+    SetTags {
+        pos: u64,
+        tags: Vec<String>,
+        principal: String,
+        reply: oneshot::Sender<anyhow::Result<EvidenceTagSet>>,
+    },
+    /// 替换模型版本的 Prompt 说明对照表
+    SetPrompts {
+        prompt_pool_hash: String,
+        prompts: Vec<PromptInfo>,
+        principal: String,
+        reply: oneshot::Sender<anyhow::Result<PromptSet>>,
+    },
+    /// 写入一轮激活路径异常检测的结果
+    RecordAnomalies {
+        flags: Vec<AnomalyFlag>,
+        scanned_leaves: u64,
+        reply: oneshot::Sender<anyhow::Result<usize>>,
+    },
+    /// 给出异常标记的复核结论
+    ResolveAnomaly {
+        pos: u64,
+        status: AnomalyStatus,
+        principal: String,
+        note: String,
+        reply: oneshot::Sender<anyhow::Result<AnomalyFlag>>,
+    },
+    /// 追加一条签名的保管事件
+    AppendCustody {
+        pos: u64,
+        details: CustodyDetails,
+        principal: String,
+        reply: oneshot::Sender<anyhow::Result<(u64, CustodyRecord)>>,
+    },
+    /// 收录一条已核对签名的鉴定人复核
+    AppendReview {
+        record: Box<ReviewRecord>,
+        reply: oneshot::Sender<anyhow::Result<u64>>,
+    },
+    /// 把运维日志链头写进 MMR
+    AppendOpsCheckpoint {
+        checkpoint: OpsCheckpoint,
+        reply: oneshot::Sender<anyhow::Result<u64>>,
+    },
+    /// 应用从主库复制来的叶子与擦除 (只读副本)
+    Replicate {
+        records: Vec<(WalRecord, Option<i64>)>,
+        erasures: Vec<ErasureRecord>,
+        reply: oneshot::Sender<anyhow::Result<u64>>,
+    },
+    /// 完整性检查 (只读，与追加串行以免读到半个事务)
+    CheckIntegrity {
+        reply: oneshot::Sender<anyhow::Result<IntegrityReport>>,
+    },
+}
+
+impl WriteCommand {
+    /// 排队优先级：存证按请求的优先级，其余命令 (管理、复制等) 按普通优先级
+    fn priority(&self) -> Priority {
+        match self {
+            Self::Attest { request, .. } => request.priority,
+            _ => Priority::Normal,
+        }
+    }
+}
+
+/// 写线程的本地优先级队列：先把通道里已到达的命令按优先级收下，再取最高优先级的一条
+///
+/// 收取数量以通道容量为限，本地队列与通道合计仍然有界。
+struct WriteQueue {
+    lanes: [VecDeque<WriteCommand>; 3],
+    capacity: usize,
+    queued: Arc<[AtomicUsize; 3]>,
+}
+
+impl WriteQueue {
+    fn next(&mut self, rx: &mut mpsc::Receiver<WriteCommand>) -> Option<WriteCommand> {
+        while self.lanes.iter().map(VecDeque::len).sum::<usize>() < self.capacity {
+            match rx.try_recv() {
+                Ok(command) => self.lanes[command.priority().lane()].push_back(command),
+                Err(_) => break,
+            }
+        }
+        let command = match self.lanes.iter_mut().find_map(VecDeque::pop_front) {
+            Some(command) => command,
+            None => rx.blocking_recv()?,
+        };
+        self.queued[command.priority().lane()].fetch_sub(1, Ordering::Relaxed);
+        Some(command)
+    }
+}
+
+/// 模块：单写者 (Single Writer Actor)
+///
+/// **职责**: 全系统唯一能修改 MMR 的地方。
+/// MMR 是只追加结构，天然要求写入串行；但读 (审计、证明生成) 完全不需要排队。
+/// 因此把 `EvidenceStore` 交给一个专属线程独占，所有写请求经 mpsc 通道排队送达，
+/// 读端则通过 `EvidenceReader` 无锁读取已提交的快照。
+///
+/// 写线程是普通 OS 线程而非 tokio 任务：sled 的写入与 fsync 都是阻塞调用，
+/// 放在异步运行时里会拖慢其他请求。
+#[derive(Clone)]
+pub struct WriterHandle {
+    tx: mpsc::Sender<WriteCommand>,
+    /// 各优先级排队中的写请求数 (按 `Priority::ALL` 顺序)
+    queued: Arc<[AtomicUsize; 3]>,
+}
+
+impl WriterHandle {
+    /// 启动写线程，`capacity` 为排队中的写请求上限
+    pub fn spawn(mut store: EvidenceStore, signer: Arc<EvidenceSigner>, capacity: usize) -> Self {
+        let (tx, mut rx) = mpsc::channel::<WriteCommand>(capacity);
+        let queued: Arc<[AtomicUsize; 3]> = Arc::default();
+        let mut queue = WriteQueue { lanes: Default::default(), capacity, queued: queued.clone() };
+
+        std::thread::Builder::new()
+            .name("yuanjing-writer".to_string())
+            .spawn(move || {
+                while let Some(command) = queue.next(&mut rx) {
+                    match command {
+                        WriteCommand::Attest { request, trace, reply } => {
+                            let AttestRequest { evidence, nonce, supersedes, commitment, clock_uncertain, key, principal, source, .. } = *request;
+                            let trace = trace.resume("writer.queue");
+                            let issuer = match &key {
+                                Some(key) => Issuer { signer: &key.signer, key_path: key.key_path(), clock_uncertain, principal: &principal },
+                                None => Issuer { signer: &signer, key_path: "", clock_uncertain, principal: &principal },
+                            };
+                            let result = store.attest(&evidence, nonce.as_deref(), supersedes, commitment, &issuer);
+                            if let Ok(entry) = &result {
+                                if let Err(e) = store.record_source(entry.outcome.pos, &source) {
+                                    warn!("⚠️  来源说明写入失败 (Pos={}): {}", entry.outcome.pos, e);
+                                }
+                            }
+                            let attested = result.is_ok();
+                            let _ = reply.send(result);
+                            drop(trace);
+
+                            // 回执已返回，再视情况把签名日志链头写进 MMR
+                            if attested {
+                                match store.checkpoint_signing_log() {
+                                    Ok(Some(pos)) => info!("🧾 签名日志检查点已写入 MMR: Pos={}", pos),
+                                    Ok(None) => {}
+                                    Err(e) => warn!("⚠️  签名日志检查点写入失败 (下次签名后重试): {}", e),
+                                }
+                            }
+                        }
+                        WriteCommand::RegisterModel { hash, description, reply } => {
+                            let _ = reply.send(store.register_model(&hash, &description));
+                        }
+                        WriteCommand::RotateRegistryRoot { root, reply } => {
+                            let _ = reply.send(store.rotate_registry_root(&root));
+                        }
+                        WriteCommand::UpdateRegistryTargets { targets, reply } => {
+                            let _ = reply.send(store.update_registry_targets(&targets));
+                        }
+                        WriteCommand::Prune { cutoff, reply } => {
+                            let _ = reply.send(store.prune_expired(cutoff));
+                        }
+                        WriteCommand::Erase { pos, principal, reason, reply } => {
+                            let _ = reply.send(store.erase(pos, &principal, &reason));
+                        }
+                        WriteCommand::PlaceHold { pos, principal, reason, reply } => {
+                            let _ = reply.send(store.place_hold(pos, &principal, &reason));
+                        }
+                        WriteCommand::ReleaseHold { pos, principal, reason, reply } => {
+                            let _ = reply.send(store.release_hold(pos, &principal, &reason));
+                        }
+                        WriteCommand::SetAcl { pos, auditors, principal, reply } => {
+                            let _ = reply.send(store.set_acl(pos, auditors, &principal));
+                        }
+                        WriteCommand::SetTags { pos, tags, principal, reply } => {
+                            let _ = reply.send(store.set_tags(pos, tags, &principal));
+                        }
+                        WriteCommand::SetPrompts { prompt_pool_hash, prompts, principal, reply } => {
+                            let _ = reply.send(store.set_prompts(&prompt_pool_hash, prompts, &principal));
+                        }
+                        WriteCommand::RecordAnomalies { flags, scanned_leaves, reply } => {
+                            let _ = reply.send(store.record_anomalies(&flags, scanned_leaves));
+                        }
+                        WriteCommand::ResolveAnomaly { pos, status, principal, note, reply } => {
+                            let _ = reply.send(store.resolve_anomaly(pos, status, &principal, &note));
+                        }
+                        WriteCommand::AppendCustody { pos, details, principal, reply } => {
+                            let _ = reply.send(store.append_custody(pos, details, &principal, &signer));
+                        }
+                        WriteCommand::AppendReview { record, reply } => {
+                            let _ = reply.send(store.append_review(*record));
+                        }
+                        WriteCommand::AppendOpsCheckpoint { checkpoint, reply } => {
+                            let _ = reply.send(store.append_ops_checkpoint(&checkpoint));
+                        }
+                        WriteCommand::Replicate { records, erasures, reply } => {
+                            let _ = reply.send(store.apply_replicated(&records, &erasures));
+                        }
+                        WriteCommand::CheckIntegrity { reply } => {
+                            let _ = reply.send(store.check_integrity());
+                        }
+                    }
+                }
+                info!("🛑 写线程退出: 所有写句柄已关闭");
+            })
+            .expect("spawn writer thread");
+
+        Self { tx, queued }
+    }
+
+    /// 该优先级排队中的写请求数
+    pub fn queued(&self, priority: Priority) -> usize {
+        self.queued[priority.lane()].load(Ordering::Relaxed)
+    }
+
+    /// 提交一次“签名并入库”请求，等待写线程处理完成
+    ///
+    /// 写队列已满时立即返回 `Writer queue full` 错误而不是排队等待，由上层转为 503。
+    /// 在写线程排队的时间记为追踪阶段 `writer.queue`；排队中的请求按 `priority` 先后处理。
+    /// `key` 指定签名用的派生密钥，None 则使用租户根密钥；`principal` 为请求主体，记入签名日志。
+    /// `supersedes` 为本条所更正的早先位置。
+    pub async fn attest(&self, request: AttestRequest) -> anyhow::Result<AttestedEntry> {
+        let (reply, rx) = oneshot::channel();
+        let priority = request.priority;
+        self.queued[priority.lane()].fetch_add(1, Ordering::Relaxed);
+        self.tx
+            .try_send(WriteCommand::Attest { request: Box::new(request), trace: Handoff::capture(), reply })
+            .map_err(|e| {
+                self.queued[priority.lane()].fetch_sub(1, Ordering::Relaxed);
+                match e {
+                    mpsc::error::TrySendError::Full(_) => anyhow::anyhow!("Writer queue full"),
+                    mpsc::error::TrySendError::Closed(_) => anyhow::anyhow!("Writer thread is not running"),
+                }
+            })?;
+        rx.await.map_err(|_| anyhow::anyhow!("Writer dropped the request"))?
+    }
+
+    /// 注册模型白名单
+    pub async fn register_model(&self, hash: String, description: String) -> anyhow::Result<()> {
+        let (reply, rx) = oneshot::channel();
+        self.send(WriteCommand::RegisterModel { hash, description, reply }).await?;
+        rx.await.map_err(|_| anyhow::anyhow!("Writer dropped the request"))?
+    }
+
+    /// 轮换模型注册表的根元数据
+    pub async fn rotate_registry_root(&self, root: SignedRootMetadata) -> anyhow::Result<()> {
+        let (reply, rx) = oneshot::channel();
+        self.send(WriteCommand::RotateRegistryRoot { root, reply }).await?;
+        rx.await.map_err(|_| anyhow::anyhow!("Writer dropped the request"))?
+    }
+
+    /// 接受模型注册表的目标元数据
+    pub async fn update_registry_targets(&self, targets: SignedTargetsMetadata) -> anyhow::Result<AcceptedTargets> {
+        let (reply, rx) = oneshot::channel();
+        self.send(WriteCommand::UpdateRegistryTargets { targets, reply }).await?;
+        rx.await.map_err(|_| anyhow::anyhow!("Writer dropped the request"))?
+    }
+
+    /// 保留期清理，返回清理的条数
+    pub async fn prune(&self, cutoff: i64) -> anyhow::Result<u64> {
+        let (reply, rx) = oneshot::channel();
+        self.send(WriteCommand::Prune { cutoff, reply }).await?;
+        rx.await.map_err(|_| anyhow::anyhow!("Writer dropped the request"))?
+    }
+
+    /// 密码学擦除，返回写入的擦除记录
+    pub async fn erase(&self, pos: u64, principal: String, reason: String) -> anyhow::Result<ErasureRecord> {
+        let (reply, rx) = oneshot::channel();
+        self.send(WriteCommand::Erase { pos, principal, reason, reply }).await?;
+        rx.await.map_err(|_| anyhow::anyhow!("Writer dropped the request"))?
+    }
+
+    /// 设置诉讼保全
+    pub async fn place_hold(&self, pos: u64, principal: String, reason: String) -> anyhow::Result<LegalHold> {
+        let (reply, rx) = oneshot::channel();
+        self.send(WriteCommand::PlaceHold { pos, principal, reason, reply }).await?;
+        rx.await.map_err(|_| anyhow::anyhow!("Writer dropped the request"))?
+    }
+
+    /// 解除诉讼保全
+    pub async fn release_hold(&self, pos: u64, principal: String, reason: String) -> anyhow::Result<HoldEvent> {
+        let (reply, rx) = oneshot::channel();
+        self.send(WriteCommand::ReleaseHold { pos, principal, reason, reply }).await?;
+        rx.await.map_err(|_| anyhow::anyhow!("Writer dropped the request"))?
+    }
+
+    /// 把运维日志链头写进 MMR，返回检查点叶子的位置
+    pub async fn append_ops_checkpoint(&self, checkpoint: OpsCheckpoint) -> anyhow::Result<u64> {
+        let (reply, rx) = oneshot::channel();
+        self.send(WriteCommand::AppendOpsCheckpoint { checkpoint, reply }).await?;
+        rx.await.map_err(|_| anyhow::anyhow!("Writer dropped the request"))?
+    }
+
+    /// 替换证据的访问控制列表
+    pub async fn set_acl(&self, pos: u64, auditors: Vec<String>, principal: String) -> anyhow::Result<EvidenceAcl> {
+        let (reply, rx) = oneshot::channel();
+        self.send(WriteCommand::SetAcl { pos, auditors, principal, reply }).await?;
+        rx.await.map_err(|_| anyhow::anyhow!("Writer dropped the request"))?
+    }
+
+    /// 替换证据的标签
+    pub async fn set_tags(&self, pos: u64, tags: Vec<String>, principal: String) -> anyhow::Result<EvidenceTagSet> {
+        let (reply, rx) = oneshot::channel();
+        self.send(WriteCommand::SetTags { pos, tags, principal, reply }).await?;
+        rx.await.map_err(|_| anyhow::anyhow!("Writer dropped the request"))?
+    }
+
+    /// 替换模型版本的 Prompt 说明对照表
+    pub async fn set_prompts(&self, prompt_pool_hash: String, prompts: Vec<PromptInfo>, principal: String) -> anyhow::Result<PromptSet> {
+        let (reply, rx) = oneshot::channel();
+        self.send(WriteCommand::SetPrompts { prompt_pool_hash, prompts, principal, reply }).await?;
+        rx.await.map_err(|_| anyhow::anyhow!("Writer dropped the request"))?
+    }
+
+    /// 写入一轮激活路径异常检测的结果，返回新增的标记数
+    pub async fn record_anomalies(&self, flags: Vec<AnomalyFlag>, scanned_leaves: u64) -> anyhow::Result<usize> {
+        let (reply, rx) = oneshot::channel();
+        self.send(WriteCommand::RecordAnomalies { flags, scanned_leaves, reply }).await?;
+        rx.await.map_err(|_| anyhow::anyhow!("Writer dropped the request"))?
+    }
+
+    /// 给出异常标记的复核结论
+    pub async fn resolve_anomaly(&self, pos: u64, status: AnomalyStatus, principal: String, note: String) -> anyhow::Result<AnomalyFlag> {
+        let (reply, rx) = oneshot::channel();
+        self.send(WriteCommand::ResolveAnomaly { pos, status, principal, note, reply }).await?;
+        rx.await.map_err(|_| anyhow::anyhow!("Writer dropped the request"))?
+    }
+
+    /// 追加一条保管事件，返回其叶子位置与签名后的记录
+    pub async fn append_custody(&self, pos: u64, details: CustodyDetails, principal: String) -> anyhow::Result<(u64, CustodyRecord)> {
+        let (reply, rx) = oneshot::channel();
+        self.send(WriteCommand::AppendCustody { pos, details, principal, reply }).await?;
+        rx.await.map_err(|_| anyhow::anyhow!("Writer dropped the request"))?
+    }
+
+    /// 收录一条鉴定人复核，返回其叶子位置
+    pub async fn append_review(&self, record: ReviewRecord) -> anyhow::Result<u64> {
+        let (reply, rx) = oneshot::channel();
+        self.send(WriteCommand::AppendReview { record: Box::new(record), reply }).await?;
+        rx.await.map_err(|_| anyhow::anyhow!("Writer dropped the request"))?
+    }
+
+    /// 应用复制来的叶子，返回新增的叶子数
+    pub async fn replicate(&self, records: Vec<(WalRecord, Option<i64>)>, erasures: Vec<ErasureRecord>) -> anyhow::Result<u64> {
+        let (reply, rx) = oneshot::channel();
+        self.send(WriteCommand::Replicate { records, erasures, reply }).await?;
+        rx.await.map_err(|_| anyhow::anyhow!("Writer dropped the request"))?
+    }
+
+    /// 完整性检查
+    pub async fn check_integrity(&self) -> anyhow::Result<IntegrityReport> {
+        let (reply, rx) = oneshot::channel();
+        self.send(WriteCommand::CheckIntegrity { reply }).await?;
+        rx.await.map_err(|_| anyhow::anyhow!("Writer dropped the request"))?
+    }
+
+    async fn send(&self, command: WriteCommand) -> anyhow::Result<()> {
+        let lane = command.priority().lane();
+        self.queued[lane].fetch_add(1, Ordering::Relaxed);
+        self.tx.send(command).await.map_err(|_| {
+            self.queued[lane].fetch_sub(1, Ordering::Relaxed);
+            anyhow::anyhow!("Writer thread is not running")
+        })
+    }
+}
@@ -0,0 +1,161 @@
+use ed25519_dalek::{Signature, VerifyingKey};
+use rustls_pki_types::pem::PemObject;
+use rustls_pki_types::{CertificateDer, SignatureVerificationAlgorithm, TrustAnchor, UnixTime};
+use std::time::Duration;
+use webpki::{EndEntityCert, KeyUsage};
+
+use crate::signer::EvidenceSigner;
+
+/// 扩展密钥用途 id-kp-documentSigning (1.3.6.1.5.5.7.3.36, RFC 9336) 的 DER 编码
+///
+/// 证书带有 EKU 扩展时必须包含此用途；不带 EKU 的证书视为不限用途。
+const EKU_DOCUMENT_SIGNING: &[u8] = &[0x2b, 0x06, 0x01, 0x05, 0x05, 0x07, 0x03, 0x24];
+
+/// 证书链 (Certificate Chain)：第一张为签名者证书，其后为中间 CA (可选)
+#[derive(Debug, Clone)]
+pub struct CertificateChain {
+    certs: Vec<CertificateDer<'static>>,
+}
+
+impl CertificateChain {
+    /// 从 PEM 文件读取证书链 (签名者证书在前)
+    pub fn load_pem(path: &str) -> anyhow::Result<Self> {
+        let certs = CertificateDer::pem_file_iter(path)
+            .and_then(|iter| iter.collect::<Result<Vec<_>, _>>())
+            .map_err(|e| anyhow::anyhow!("Cannot read certificate chain '{}': {:?}", path, e))?;
+        if certs.is_empty() {
+            return Err(anyhow::anyhow!("Certificate chain '{}' contains no certificates", path));
+        }
+        Ok(Self { certs })
+    }
+
+    /// 从 Hex 编码的 DER 列表还原 (验证方随回执提交的证书链)
+    pub fn from_hex(certs: &[String]) -> anyhow::Result<Self> {
+        if certs.is_empty() {
+            return Err(anyhow::anyhow!("Certificate chain is empty"));
+        }
+        let certs = certs
+            .iter()
+            .map(|cert| Ok(CertificateDer::from(hex::decode(cert)?)))
+            .collect::<anyhow::Result<Vec<_>>>()?;
+        Ok(Self { certs })
+    }
+
+    /// Hex 编码的 DER 列表，随回执一起返回
+    pub fn to_hex(&self) -> Vec<String> {
+        self.certs.iter().map(hex::encode).collect()
+    }
+
+    /// 由 DER 证书组成 (签名者证书在前)
+    pub fn from_der(certs: Vec<CertificateDer<'static>>) -> anyhow::Result<Self> {
+        if certs.is_empty() {
+            return Err(anyhow::anyhow!("Certificate chain is empty"));
+        }
+        Ok(Self { certs })
+    }
+
+    /// 证书中的公钥确实产生了 `signature` (即证书与签名密钥绑定)
+    pub fn verify_signed_by(&self, message: &[u8], signature: &Signature) -> anyhow::Result<()> {
+        EndEntityCert::try_from(&self.certs[0])
+            .map_err(|e| anyhow::anyhow!("Invalid signer certificate: {:?}", e))?
+            .verify_signature(webpki::ring::ED25519, message, &signature.to_bytes())
+            .map_err(|_| anyhow::anyhow!("Signer certificate does not match the signing key"))
+    }
+
+    /// 签名者证书的公钥按 `algorithms` 之一产生了 `signature` (算法与公钥类型不符的跳过)
+    pub fn verify_signature(
+        &self,
+        algorithms: &[&dyn SignatureVerificationAlgorithm],
+        message: &[u8],
+        signature: &[u8],
+    ) -> anyhow::Result<()> {
+        let end_entity = EndEntityCert::try_from(&self.certs[0])
+            .map_err(|e| anyhow::anyhow!("Invalid signer certificate: {:?}", e))?;
+        if algorithms.iter().any(|algorithm| end_entity.verify_signature(*algorithm, message, signature).is_ok()) {
+            Ok(())
+        } else {
+            Err(anyhow::anyhow!("Signature does not verify against the signer certificate"))
+        }
+    }
+
+    /// 证书链在 `at` 时刻 (Unix 秒) 可追溯到受信根，且签名者证书满足扩展密钥用途 `usage`
+    pub(crate) fn verify_chain(&self, trust: &TrustStore, at: i64, usage: KeyUsage) -> anyhow::Result<()> {
+        if trust.anchors.is_empty() {
+            return Err(anyhow::anyhow!("No trust anchors configured"));
+        }
+        let end_entity = EndEntityCert::try_from(&self.certs[0])
+            .map_err(|e| anyhow::anyhow!("Invalid signer certificate: {:?}", e))?;
+        let time = UnixTime::since_unix_epoch(Duration::from_secs(at.max(0) as u64));
+        end_entity
+            .verify_for_usage(
+                webpki::ALL_VERIFICATION_ALGS,
+                &trust.anchors,
+                &self.certs[1..],
+                time,
+                usage,
+                None,
+                None,
+            )
+            .map_err(|e| anyhow::anyhow!("Certificate chain verification failed: {:?}", e))?;
+        Ok(())
+    }
+
+    /// 完整校验：证书链在 `at` 时刻 (Unix 秒) 可追溯到受信根，且签名者证书的公钥产生了 `signature`
+    pub fn verify(&self, trust: &TrustStore, at: i64, message: &[u8], signature: &Signature) -> anyhow::Result<()> {
+        self.verify_chain(trust, at, KeyUsage::required_if_present(EKU_DOCUMENT_SIGNING))?;
+        self.verify_signed_by(message, signature)
+    }
+}
+
+/// 受信根证书集合 (Trust Anchors)
+#[derive(Debug, Clone, Default)]
+pub struct TrustStore {
+    anchors: Vec<TrustAnchor<'static>>,
+}
+
+impl TrustStore {
+    /// 从若干 PEM 文件加载根证书
+    pub fn load_pem(paths: &[String]) -> anyhow::Result<Self> {
+        let mut anchors = Vec::new();
+        for path in paths {
+            for cert in CertificateChain::load_pem(path)?.certs {
+                let anchor = webpki::anchor_from_trusted_cert(&cert)
+                    .map_err(|e| anyhow::anyhow!("Invalid trust anchor in '{}': {:?}", path, e))?;
+                anchors.push(anchor.to_owned());
+            }
+        }
+        Ok(Self { anchors })
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.anchors.is_empty()
+    }
+}
+
+/// 模块：签名身份证书 (Signer Certificate)
+///
+/// **为什么需要**: 法庭采信的是“某机构的签名”，而不是一把裸公钥。
+/// 由 CA 为主身份的 Ed25519 公钥签发证书，回执随附证书链，
+/// 验证方即可沿证书链追溯到自己信任的根证书 (trust anchor)。
+///
+/// 启动时用主身份对一段探测消息签名，确认证书中的公钥与私钥匹配；
+/// 配置了受信根时同时校验证书链，失败则拒绝启动。
+#[derive(Debug, Clone)]
+pub struct SignerCertificate {
+    pub public_key: VerifyingKey,
+    pub chain: CertificateChain,
+}
+
+impl SignerCertificate {
+    pub fn load(path: &str, signer: &EvidenceSigner, trust: &TrustStore) -> anyhow::Result<Self> {
+        let chain = CertificateChain::load_pem(path)?;
+        let probe = b"yuanjing-core certificate binding probe";
+        let signature = signer.sign_bytes(probe)?;
+        if trust.is_empty() {
+            chain.verify_signed_by(probe, &signature)?;
+        } else {
+            chain.verify(trust, chrono::Utc::now().timestamp(), probe, &signature)?;
+        }
+        Ok(Self { public_key: signer.public_key(), chain })
+    }
+}
@@ -0,0 +1,247 @@
+use std::ops::Range;
+
+/// 电路的求值后端：同一份电路描述既用于明文求值 (检查见证)，也用于证明方与验证方的三方模拟
+///
+/// 电路只由异或与与门组成；异或 (及与公开常量的运算) 是线性的，各方本地计算；与门需要各方交互，计入视图。
+pub(crate) trait Backend {
+    type Bit: Copy;
+    fn zero(&self) -> Self::Bit;
+    fn one(&self) -> Self::Bit;
+    fn xor(&mut self, a: Self::Bit, b: Self::Bit) -> Self::Bit;
+    fn and(&mut self, a: Self::Bit, b: Self::Bit) -> Self::Bit;
+}
+
+/// 明文求值，顺带统计与门数量
+#[derive(Default)]
+pub(crate) struct Plain {
+    pub and_gates: usize,
+}
+
+impl Backend for Plain {
+    type Bit = bool;
+
+    fn zero(&self) -> bool {
+        false
+    }
+
+    fn one(&self) -> bool {
+        true
+    }
+
+    fn xor(&mut self, a: bool, b: bool) -> bool {
+        a ^ b
+    }
+
+    fn and(&mut self, a: bool, b: bool) -> bool {
+        self.and_gates += 1;
+        a & b
+    }
+}
+
+const IV: [u32; 8] = [0x6A09E667, 0xBB67AE85, 0x3C6EF372, 0xA54FF53A, 0x510E527F, 0x9B05688C, 0x1F83D9AB, 0x5BE0CD19];
+const MSG_PERMUTATION: [usize; 16] = [2, 6, 3, 10, 7, 0, 4, 13, 1, 11, 12, 5, 9, 14, 15, 8];
+const CHUNK_START: u32 = 1;
+const CHUNK_END: u32 = 2;
+const ROOT: u32 = 8;
+
+/// 单个 BLAKE3 chunk 的上限：叶子原文不超过 1024 字节时，整段哈希只是 chunk 内的逐块压缩
+pub(crate) const MAX_MESSAGE_LEN: usize = 1024;
+
+/// 32 位字，第 i 位为 2^i
+type Word<T> = [T; 32];
+
+/// 零知识披露电路 (公开部分)
+///
+/// 见证 (按输入顺序)：
+/// - 叶子原文 `message_len` 字节，每字节从低位到高位 8 个比特
+/// - `height` 层合并路径，每层 `active` (是否合并)、`right` (当前节点是否为右孩子)、兄弟节点 256 位
+/// - 每座山峰一个选择位 (恰有一位为 1)
+///
+/// 输出：`disclosed` 各区间的原文比特，随后是“路径终点 ⊕ 选中的山峰” 256 位 (应全为 0)。
+pub(crate) struct Circuit {
+    pub message_len: usize,
+    /// 最高山峰的高度：路径最多合并这么多次
+    pub height: usize,
+    pub peaks: Vec<[u8; 32]>,
+    pub disclosed: Vec<Range<usize>>,
+}
+
+impl Circuit {
+    pub fn input_len(&self) -> usize {
+        8 * self.message_len + self.height * (2 + 256) + self.peaks.len()
+    }
+
+    pub fn output_len(&self) -> usize {
+        self.disclosed.iter().map(|range| 8 * range.len()).sum::<usize>() + 256
+    }
+
+    pub fn eval<B: Backend>(&self, b: &mut B, input: &[B::Bit]) -> Vec<B::Bit> {
+        let message = &input[..8 * self.message_len];
+        let mut item = hash_message(b, message);
+        let mut offset = 8 * self.message_len;
+        for _ in 0..self.height {
+            let active = input[offset];
+            let right = input[offset + 1];
+            let sibling = &input[offset + 2..offset + 258];
+            offset += 258;
+
+            // right = 1 时 (兄弟, 当前)，否则 (当前, 兄弟)：每位一个与门
+            let mut left = [b.zero(); 256];
+            let mut rhs = [b.zero(); 256];
+            for i in 0..256 {
+                let diff = b.xor(item[i], sibling[i]);
+                let pick = b.and(right, diff);
+                left[i] = b.xor(item[i], pick);
+                rhs[i] = b.xor(sibling[i], pick);
+            }
+            let merged = merge(b, &left, &rhs);
+            for i in 0..256 {
+                let diff = b.xor(item[i], merged[i]);
+                let pick = b.and(active, diff);
+                item[i] = b.xor(item[i], pick);
+            }
+        }
+
+        // 选择位与公开常量相与是线性的，不需要与门
+        let mut selected = [b.zero(); 256];
+        for (k, peak) in self.peaks.iter().enumerate() {
+            let select = input[offset + k];
+            for (i, bit) in selected.iter_mut().enumerate() {
+                if peak[i / 8] >> (i % 8) & 1 == 1 {
+                    *bit = b.xor(*bit, select);
+                }
+            }
+        }
+
+        let mut output = Vec::with_capacity(self.output_len());
+        for range in &self.disclosed {
+            output.extend_from_slice(&message[8 * range.start..8 * range.end]);
+        }
+        for i in 0..256 {
+            output.push(b.xor(item[i], selected[i]));
+        }
+        output
+    }
+}
+
+/// 明文侧的比特排布：字节从低位到高位
+pub(crate) fn bytes_to_bits(bytes: &[u8]) -> Vec<bool> {
+    bytes.iter().flat_map(|byte| (0..8).map(move |i| byte >> i & 1 == 1)).collect()
+}
+
+/// BLAKE3(message)，message 不超过一个 chunk
+fn hash_message<B: Backend>(b: &mut B, message: &[B::Bit]) -> [B::Bit; 256] {
+    let len = message.len() / 8;
+    let blocks = len.div_ceil(64).max(1);
+    let mut cv = IV.map(|word| const_word(b, word));
+    for block in 0..blocks {
+        let block_len = (len - 64 * block).min(64) as u32;
+        let mut flags = 0;
+        if block == 0 {
+            flags |= CHUNK_START;
+        }
+        if block == blocks - 1 {
+            flags |= CHUNK_END | ROOT;
+        }
+        let words: [Word<B::Bit>; 16] = std::array::from_fn(|w| {
+            std::array::from_fn(|i| {
+                let bit = 8 * 64 * block + 32 * w + i;
+                if bit < message.len() { message[bit] } else { b.zero() }
+            })
+        });
+        cv = compress(b, &cv, words, block_len, flags);
+    }
+    words_to_hash(b, &cv)
+}
+
+/// 合并规则 Blake3(左 || 右)：单块 64 字节
+fn merge<B: Backend>(b: &mut B, left: &[B::Bit; 256], right: &[B::Bit; 256]) -> [B::Bit; 256] {
+    let words: [Word<B::Bit>; 16] = std::array::from_fn(|w| {
+        let half = if w < 8 { left } else { right };
+        std::array::from_fn(|i| half[32 * (w % 8) + i])
+    });
+    let cv = IV.map(|word| const_word(b, word));
+    let cv = compress(b, &cv, words, 64, CHUNK_START | CHUNK_END | ROOT);
+    words_to_hash(b, &cv)
+}
+
+fn words_to_hash<B: Backend>(b: &B, cv: &[Word<B::Bit>; 8]) -> [B::Bit; 256] {
+    let mut hash = [b.zero(); 256];
+    for (w, word) in cv.iter().enumerate() {
+        hash[32 * w..32 * w + 32].copy_from_slice(word);
+    }
+    hash
+}
+
+/// BLAKE3 压缩函数 (块计数恒为 0)，只取前 8 个输出字
+fn compress<B: Backend>(
+    b: &mut B,
+    cv: &[Word<B::Bit>; 8],
+    mut m: [Word<B::Bit>; 16],
+    block_len: u32,
+    flags: u32,
+) -> [Word<B::Bit>; 8] {
+    let mut v = [const_word(b, 0); 16];
+    v[..8].copy_from_slice(cv);
+    for i in 0..4 {
+        v[8 + i] = const_word(b, IV[i]);
+    }
+    v[14] = const_word(b, block_len);
+    v[15] = const_word(b, flags);
+    for round in 0..7 {
+        g(b, &mut v, [0, 4, 8, 12], m[0], m[1]);
+        g(b, &mut v, [1, 5, 9, 13], m[2], m[3]);
+        g(b, &mut v, [2, 6, 10, 14], m[4], m[5]);
+        g(b, &mut v, [3, 7, 11, 15], m[6], m[7]);
+        g(b, &mut v, [0, 5, 10, 15], m[8], m[9]);
+        g(b, &mut v, [1, 6, 11, 12], m[10], m[11]);
+        g(b, &mut v, [2, 7, 8, 13], m[12], m[13]);
+        g(b, &mut v, [3, 4, 9, 14], m[14], m[15]);
+        if round < 6 {
+            m = std::array::from_fn(|i| m[MSG_PERMUTATION[i]]);
+        }
+    }
+    std::array::from_fn(|i| xor_word(b, &v[i], &v[i + 8]))
+}
+
+fn g<B: Backend>(b: &mut B, v: &mut [Word<B::Bit>; 16], [a, bb, c, d]: [usize; 4], mx: Word<B::Bit>, my: Word<B::Bit>) {
+    v[a] = add_word(b, &v[a], &v[bb]);
+    v[a] = add_word(b, &v[a], &mx);
+    v[d] = rotr(&xor_word(b, &v[d], &v[a]), 16);
+    v[c] = add_word(b, &v[c], &v[d]);
+    v[bb] = rotr(&xor_word(b, &v[bb], &v[c]), 12);
+    v[a] = add_word(b, &v[a], &v[bb]);
+    v[a] = add_word(b, &v[a], &my);
+    v[d] = rotr(&xor_word(b, &v[d], &v[a]), 8);
+    v[c] = add_word(b, &v[c], &v[d]);
+    v[bb] = rotr(&xor_word(b, &v[bb], &v[c]), 7);
+}
+
+fn const_word<B: Backend>(b: &B, value: u32) -> Word<B::Bit> {
+    std::array::from_fn(|i| if value >> i & 1 == 1 { b.one() } else { b.zero() })
+}
+
+fn xor_word<B: Backend>(b: &mut B, x: &Word<B::Bit>, y: &Word<B::Bit>) -> Word<B::Bit> {
+    std::array::from_fn(|i| b.xor(x[i], y[i]))
+}
+
+fn rotr<T: Copy>(word: &Word<T>, n: usize) -> Word<T> {
+    std::array::from_fn(|i| word[(i + n) % 32])
+}
+
+/// 模 2^32 加法 (行波进位)：进位 c' = ((x ⊕ c) ∧ (y ⊕ c)) ⊕ c，每位一个与门，最高位不算进位
+fn add_word<B: Backend>(b: &mut B, x: &Word<B::Bit>, y: &Word<B::Bit>) -> Word<B::Bit> {
+    let mut sum = [b.zero(); 32];
+    sum[0] = b.xor(x[0], y[0]);
+    let mut carry = b.and(x[0], y[0]);
+    for i in 1..32 {
+        let xc = b.xor(x[i], carry);
+        let yc = b.xor(y[i], carry);
+        sum[i] = b.xor(xc, y[i]);
+        if i < 31 {
+            let both = b.and(xc, yc);
+            carry = b.xor(both, carry);
+        }
+    }
+    sum
+}
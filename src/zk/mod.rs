@@ -0,0 +1,712 @@
+mod circuit;
+mod mpc;
+
+use ckb_merkle_mountain_range::helper::{get_peaks, parent_offset, pos_height_in_tree, sibling_offset};
+use ckb_merkle_mountain_range::Merge;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::evidence::{CanonicalEncoding, Evidence};
+use crate::proof::{MergeBlake3, MAX_TREE_SIZE};
+use circuit::{bytes_to_bits, Circuit, Plain, MAX_MESSAGE_LEN};
+use mpc::{Lanes, REPETITIONS};
+
+/// 零知识披露证明的媒体类型
+pub const ZK_PROOF_MEDIA_TYPE: &str = "application/vnd.yuanjing.zkproof";
+
+/// 零知识披露证明编码版本
+pub const ZK_PROOF_VERSION: u8 = 1;
+
+/// 编码后的证明体积上限 (叶子原文 1024 字节、树高 63 时约 24 MiB)
+pub const MAX_ZK_PROOF_BYTES: usize = 32 << 20;
+
+const ZK_PROOF_MAGIC: &[u8; 3] = b"YJZ";
+
+/// 证明中 `image_sha256` 的长度 (64 个 Hex 字符)
+const SHA256_HEX_LEN: usize = 64;
+
+/// 零知识披露的公开陈述
+///
+/// “树大小为 `tree_size`、根为 `root` 的树中，存在一个叶子，其原文的 `image_sha256` 为该值、`verdict` 为该值”。
+/// 叶子位置与证据的其余字段 (pHash 内容、置信度、激活提示词、时间戳……) 都不公开。
+///
+/// 另外公开的只有形状信息：叶子原文长度、是否加盐、pHash 的长度，以及各山峰 (可由根与树大小核对)。
+/// 只支持 BCS 规范编码的租户，原文 (含盐值) 不超过 1024 字节。
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct ZkStatement {
+    /// 树大小 (MMR 节点总数)
+    pub tree_size: u64,
+    /// 该树的根 (Hex)
+    pub root: String,
+    /// 从左到右的山峰 (Hex)，按 `proof::MergeBlake3` 从右向左合并得到 `root`
+    pub peaks: Vec<String>,
+    /// 叶子原文 (盐值 || BCS 规范字节) 的长度
+    pub message_len: u32,
+    /// 是否为加盐叶子 (盐值占原文前 32 字节)
+    pub salted: bool,
+    /// `image_phash` 的字节长度 (决定 `image_sha256` 在原文中的偏移)
+    pub phash_len: u32,
+    pub image_sha256: String,
+    /// 判决结果 (false = 伪造)
+    pub verdict: bool,
+}
+
+impl ZkStatement {
+    /// 电路与电路应有的输出 (披露的原文字节，随后是 256 个 0)
+    fn circuit(&self) -> anyhow::Result<(Circuit, Vec<bool>)> {
+        if self.tree_size == 0 || self.tree_size > MAX_TREE_SIZE {
+            return Err(anyhow::anyhow!("Statement tree size must be in [1, {}]", MAX_TREE_SIZE));
+        }
+        let positions = get_peaks(self.tree_size);
+        if positions.len() != self.peaks.len() {
+            return Err(anyhow::anyhow!(
+                "Tree size {} has {} peaks, statement lists {}",
+                self.tree_size,
+                positions.len(),
+                self.peaks.len()
+            ));
+        }
+        let peaks = self.peaks.iter().map(|peak| decode_hash(peak)).collect::<anyhow::Result<Vec<_>>>()?;
+        if bag_peaks(&peaks)? != decode_hash(&self.root)? {
+            return Err(anyhow::anyhow!("Statement peaks do not bag to the statement root"));
+        }
+        if self.image_sha256.len() != SHA256_HEX_LEN || !self.image_sha256.bytes().all(|byte| byte.is_ascii_hexdigit()) {
+            return Err(anyhow::anyhow!("Statement image_sha256 must be {} hex characters", SHA256_HEX_LEN));
+        }
+
+        // BCS 布局：[盐值] | uleb128(pHash 长度) | pHash | 0x40 | sha256 (64 字节) | verdict (1 字节) | ...
+        let message_len = self.message_len as usize;
+        let base = if self.salted { 32 } else { 0 };
+        let prefix = uleb128(self.phash_len);
+        let sha_offset = base + prefix.len() + self.phash_len as usize + 1;
+        if message_len > MAX_MESSAGE_LEN || sha_offset + SHA256_HEX_LEN + 1 > message_len {
+            return Err(anyhow::anyhow!("Statement message length {} does not fit the disclosed fields", message_len));
+        }
+        let mut expected = prefix.clone();
+        expected.push(SHA256_HEX_LEN as u8);
+        expected.extend_from_slice(self.image_sha256.as_bytes());
+        expected.push(self.verdict as u8);
+
+        let circuit = Circuit {
+            message_len,
+            height: pos_height_in_tree(positions[0]) as usize,
+            peaks,
+            disclosed: vec![base..base + prefix.len(), sha_offset - 1..sha_offset + SHA256_HEX_LEN + 1],
+        };
+        let mut output = bytes_to_bits(&expected);
+        output.resize(circuit.output_len(), false);
+        Ok((circuit, output))
+    }
+}
+
+/// 证明方的秘密见证：叶子原文、到山峰的合并路径、山峰序号
+pub struct ZkWitness {
+    /// 盐值 || 规范字节
+    message: Vec<u8>,
+    /// 从叶子往上每层 (当前节点是否为右孩子, 兄弟节点)
+    path: Vec<(bool, [u8; 32])>,
+    peak_index: usize,
+}
+
+impl ZkWitness {
+    fn input_bits(&self, circuit: &Circuit) -> anyhow::Result<Vec<bool>> {
+        if self.message.len() != circuit.message_len || self.path.len() > circuit.height || self.peak_index >= circuit.peaks.len() {
+            return Err(anyhow::anyhow!("Witness does not match the statement shape"));
+        }
+        let mut bits = bytes_to_bits(&self.message);
+        for level in 0..circuit.height {
+            match self.path.get(level) {
+                Some((right, sibling)) => {
+                    bits.push(true);
+                    bits.push(*right);
+                    bits.extend(bytes_to_bits(sibling));
+                }
+                None => bits.extend(std::iter::repeat_n(false, 258)),
+            }
+        }
+        bits.extend((0..circuit.peaks.len()).map(|k| k == self.peak_index));
+        Ok(bits)
+    }
+}
+
+/// 为 `tree_size` 大小的树中 `pos` 位置的叶子准备公开陈述与见证
+///
+/// `canonical_bytes` 为该叶子的 BCS 规范字节，`salt` 为加盐叶子的盐值；`node` 按 MMR 位置读取节点哈希。
+pub fn prepare(
+    tree_size: u64,
+    pos: u64,
+    salt: Option<[u8; 32]>,
+    canonical_bytes: &[u8],
+    node: impl Fn(u64) -> anyhow::Result<[u8; 32]>,
+) -> anyhow::Result<(ZkStatement, ZkWitness)> {
+    if pos >= tree_size || pos_height_in_tree(pos) != 0 {
+        return Err(anyhow::anyhow!("Position {} is not a leaf of tree size {}", pos, tree_size));
+    }
+    let evidence = Evidence::from_canonical_bytes(CanonicalEncoding::Bcs, canonical_bytes)?;
+    let mut message = salt.map(|salt| salt.to_vec()).unwrap_or_default();
+    message.extend_from_slice(canonical_bytes);
+    if message.len() > MAX_MESSAGE_LEN {
+        return Err(anyhow::anyhow!(
+            "Leaf preimage is {} bytes; zero-knowledge disclosure supports at most {}",
+            message.len(),
+            MAX_MESSAGE_LEN
+        ));
+    }
+
+    let positions = get_peaks(tree_size);
+    let (mut current, mut height, mut path) = (pos, 0u32, Vec::new());
+    while !positions.contains(&current) {
+        if pos_height_in_tree(current + 1) > height {
+            path.push((true, node(current - sibling_offset(height))?));
+            current += 1;
+        } else {
+            path.push((false, node(current + sibling_offset(height))?));
+            current += parent_offset(height);
+        }
+        height += 1;
+    }
+    let peak_index = positions.iter().position(|peak| *peak == current).unwrap_or_default();
+    let peaks = positions.into_iter().map(&node).collect::<anyhow::Result<Vec<_>>>()?;
+
+    let statement = ZkStatement {
+        tree_size,
+        root: hex::encode(bag_peaks(&peaks)?),
+        peaks: peaks.iter().map(hex::encode).collect(),
+        message_len: message.len() as u32,
+        salted: salt.is_some(),
+        phash_len: evidence.image_phash.len() as u32,
+        image_sha256: evidence.image_sha256,
+        verdict: evidence.verdict,
+    };
+    Ok((statement, ZkWitness { message, path, peak_index }))
+}
+
+/// 单次重复的证明：打开参与方 e 与 e+1
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Repetition {
+    /// 挑战 e ∈ {0, 1, 2}
+    challenge: u8,
+    /// 参与方 e、e+1 的种子
+    seeds: [[u8; 16]; 2],
+    /// 未打开的参与方 e+2 的承诺
+    hidden_commitment: [u8; 32],
+    /// 参与方 e+1 在各与门上的输出
+    opened_view: Vec<u8>,
+    /// 参与方 2 的输入份额 (参与方 2 被打开，即 e ≠ 0 时)
+    input_share: Option<Vec<u8>>,
+}
+
+/// 模块：零知识披露 (Zero-Knowledge Disclosure，实验性)
+///
+/// **为什么需要**: 向第三方 (如平台、媒体) 说明“这张图 (sha256=X) 在我们的日志里被判为伪造”时，
+/// 给出原文与审计路径会暴露叶子位置 (进而暴露提交时间与业务量) 以及置信度、激活的提示词等内部信息。
+/// 零知识披露只证明“根为 R 的树中存在一个叶子，其 `image_sha256` = X 且 `verdict` = 给定值”，其余一概不透露。
+///
+/// 方案为 MPC-in-the-head (ZKB++)：在电路中重算 BLAKE3(叶子原文) 与到山峰的合并路径，
+/// 证明方在脑中把见证拆成三份模拟三方计算，承诺各方视图后按 Fiat-Shamir 挑战打开其中两方。
+/// 只依赖 BLAKE3，不需要可信设置；代价是证明体积较大 (数 MB，随树高与原文长度线性增长)，
+/// 生成与校验都在秒级。219 次重复，可靠性误差约 2^-128。
+///
+/// 编码 (整数大端序)：
+///
+/// ```text
+/// "YJZ" | version: u8 | len: u32 | BCS(ZkStatement) | 219 × repetition
+/// repetition = e: u8 | seed_e: [u8; 16] | seed_{e+1}: [u8; 16] | commitment_{e+2}: [u8; 32]
+///              | view_{e+1}: ⌈与门数 / 8⌉ 字节 | (e ≠ 0 时) 参与方 2 的输入份额: ⌈输入位数 / 8⌉ 字节
+/// ```
+///
+/// 验证方仍需自行确认 `root` 确为日志在 `tree_size` 时的根 (签名树头、公证回执或链上锚定)。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ZkProof {
+    pub statement: ZkStatement,
+    repetitions: Vec<Repetition>,
+}
+
+impl ZkProof {
+    /// 生成证明；`master_seed` 须为新鲜的随机数，各参与方的种子由它派生，不得复用
+    pub fn prove(statement: &ZkStatement, witness: &ZkWitness, master_seed: [u8; 32]) -> anyhow::Result<Self> {
+        let (circuit, expected) = statement.circuit()?;
+        let input = witness.input_bits(&circuit)?;
+        let mut plain = Plain::default();
+        if circuit.eval(&mut plain, &input) != expected {
+            return Err(anyhow::anyhow!("Witness does not satisfy the statement"));
+        }
+        let (n_in, n_and) = (input.len(), plain.and_gates);
+
+        let seeds: Vec<[[u8; 16]; 3]> = (0..REPETITIONS)
+            .map(|rep| std::array::from_fn(|player| derive_seed(&master_seed, rep, player)))
+            .collect();
+        let tapes: [Vec<Vec<u8>>; 3] =
+            std::array::from_fn(|player| seeds.iter().map(|seeds| mpc::tape(&seeds[player], n_in + n_and)).collect());
+        let streams = |player: usize| tapes[player].iter().map(Vec::as_slice).collect::<Vec<_>>();
+
+        // 参与方 0、1 的输入份额取自随机带，参与方 2 补齐：x_2 = x ⊕ x_0 ⊕ x_1
+        let shares = [mpc::transpose(&streams(0), 0, n_in), mpc::transpose(&streams(1), 0, n_in)];
+        let inputs: Vec<[Lanes; 3]> = input
+            .iter()
+            .enumerate()
+            .map(|(i, bit)| {
+                let x = if *bit { Lanes::ONES } else { Lanes::default() };
+                [shares[0][i], shares[1][i], x ^ shares[0][i] ^ shares[1][i]]
+            })
+            .collect();
+        let mut prover = mpc::Prover::new(std::array::from_fn(|player| mpc::transpose(&streams(player), n_in, n_and)));
+        let outputs = circuit.eval(&mut prover, &inputs);
+        let third_share: Vec<Lanes> = inputs.iter().map(|shares| shares[2]).collect();
+        let output_shares: [Vec<Lanes>; 3] = std::array::from_fn(|player| outputs.iter().map(|shares| shares[player]).collect());
+
+        let mut commitments = Vec::with_capacity(REPETITIONS);
+        let mut output_bytes = Vec::with_capacity(REPETITIONS);
+        let mut views = Vec::with_capacity(REPETITIONS);
+        let mut input_shares = Vec::with_capacity(REPETITIONS);
+        for (rep, seeds) in seeds.iter().enumerate() {
+            let input_share = mpc::extract(&third_share, rep);
+            let view: [Vec<u8>; 3] = std::array::from_fn(|player| mpc::extract(&prover.views[player], rep));
+            commitments.push(std::array::from_fn(|player| {
+                commit(&seeds[player], (player == 2).then_some(input_share.as_slice()), &view[player])
+            }));
+            output_bytes.push(std::array::from_fn(|player| mpc::extract(&output_shares[player], rep)));
+            views.push(view);
+            input_shares.push(input_share);
+        }
+
+        let challenges = challenges(&bcs::to_bytes(statement)?, &commitments, &output_bytes);
+        let repetitions = challenges
+            .into_iter()
+            .zip(seeds.iter().zip(commitments.iter()))
+            .zip(views.iter_mut().zip(input_shares))
+            .map(|((e, (seeds, commitments)), (view, input_share))| {
+                let (opened, next, hidden) = (e as usize, (e as usize + 1) % 3, (e as usize + 2) % 3);
+                Repetition {
+                    challenge: e,
+                    seeds: [seeds[opened], seeds[next]],
+                    hidden_commitment: commitments[hidden],
+                    opened_view: std::mem::take(&mut view[next]),
+                    input_share: (e != 0).then_some(input_share),
+                }
+            })
+            .collect();
+        Ok(Self { statement: statement.clone(), repetitions })
+    }
+
+    /// 校验证明；陈述本身不自洽 (如山峰合并不出根) 时返回错误，证明无效时返回 false
+    pub fn verify(&self) -> anyhow::Result<bool> {
+        let (circuit, expected) = self.statement.circuit()?;
+        let (n_in, n_and) = (circuit.input_len(), and_gates(&circuit));
+        if self.repetitions.len() != REPETITIONS
+            || self.repetitions.iter().any(|rep| {
+                rep.challenge > 2
+                    || rep.opened_view.len() != n_and.div_ceil(8)
+                    || rep.input_share.as_ref().map(Vec::len) != (rep.challenge != 0).then_some(n_in.div_ceil(8))
+            })
+        {
+            return Ok(false);
+        }
+
+        let tapes: [Vec<Vec<u8>>; 2] =
+            std::array::from_fn(|slot| self.repetitions.iter().map(|rep| mpc::tape(&rep.seeds[slot], n_in + n_and)).collect());
+        // 槽位 0 为参与方 e，槽位 1 为参与方 e+1；参与方 2 的输入份额取自证明
+        let input_streams = |slot: usize| {
+            self.repetitions
+                .iter()
+                .zip(&tapes[slot])
+                .map(|(rep, tape)| match &rep.input_share {
+                    Some(share) if (rep.challenge as usize + slot) % 3 == 2 => share.as_slice(),
+                    _ => tape.as_slice(),
+                })
+                .collect::<Vec<_>>()
+        };
+        let tape_streams = |slot: usize| tapes[slot].iter().map(Vec::as_slice).collect::<Vec<_>>();
+        let shares = [mpc::transpose(&input_streams(0), 0, n_in), mpc::transpose(&input_streams(1), 0, n_in)];
+        let inputs: Vec<[Lanes; 2]> = (0..n_in).map(|i| [shares[0][i], shares[1][i]]).collect();
+        let mut ones = [Lanes::default(); 2];
+        for (rep, repetition) in self.repetitions.iter().enumerate() {
+            match repetition.challenge {
+                0 => ones[0].set(rep),
+                2 => ones[1].set(rep),
+                _ => {}
+            }
+        }
+        let opened_views = self.repetitions.iter().map(|rep| rep.opened_view.as_slice()).collect::<Vec<_>>();
+        let mut verifier = mpc::Verifier::new(
+            [mpc::transpose(&tape_streams(0), n_in, n_and), mpc::transpose(&tape_streams(1), n_in, n_and)],
+            ones,
+            mpc::transpose(&opened_views, 0, n_and),
+        );
+        let outputs = circuit.eval(&mut verifier, &inputs);
+        let output_shares: [Vec<Lanes>; 2] = std::array::from_fn(|slot| outputs.iter().map(|shares| shares[slot]).collect());
+
+        // 未打开一方的输出份额由公开输出补齐：y_{e+2} = y ⊕ y_e ⊕ y_{e+1}
+        let expected = pack_bits(&expected);
+        let mut commitments = Vec::with_capacity(REPETITIONS);
+        let mut output_bytes = Vec::with_capacity(REPETITIONS);
+        for (rep, repetition) in self.repetitions.iter().enumerate() {
+            let e = repetition.challenge as usize;
+            let (next, hidden) = ((e + 1) % 3, (e + 2) % 3);
+            let opened = [mpc::extract(&output_shares[0], rep), mpc::extract(&output_shares[1], rep)];
+            let third: Vec<u8> = expected.iter().zip(&opened[0]).zip(&opened[1]).map(|((y, a), b)| y ^ a ^ b).collect();
+            let input_share = repetition.input_share.as_deref();
+            let mut rep_commitments = [[0u8; 32]; 3];
+            rep_commitments[e] = commit(
+                &repetition.seeds[0],
+                input_share.filter(|_| e == 2),
+                &mpc::extract(&verifier.recomputed_view, rep),
+            );
+            rep_commitments[next] = commit(&repetition.seeds[1], input_share.filter(|_| next == 2), &repetition.opened_view);
+            rep_commitments[hidden] = repetition.hidden_commitment;
+            let [first, second] = opened;
+            let mut rep_outputs: [Vec<u8>; 3] = Default::default();
+            rep_outputs[e] = first;
+            rep_outputs[next] = second;
+            rep_outputs[hidden] = third;
+            commitments.push(rep_commitments);
+            output_bytes.push(rep_outputs);
+        }
+
+        let challenges = challenges(&bcs::to_bytes(&self.statement)?, &commitments, &output_bytes);
+        Ok(challenges.iter().zip(&self.repetitions).all(|(e, rep)| *e == rep.challenge))
+    }
+
+    pub fn encode(&self) -> anyhow::Result<Vec<u8>> {
+        let statement = bcs::to_bytes(&self.statement)?;
+        let mut bytes = ZK_PROOF_MAGIC.to_vec();
+        bytes.push(ZK_PROOF_VERSION);
+        bytes.extend_from_slice(&(statement.len() as u32).to_be_bytes());
+        bytes.extend_from_slice(&statement);
+        for rep in &self.repetitions {
+            bytes.push(rep.challenge);
+            bytes.extend_from_slice(&rep.seeds[0]);
+            bytes.extend_from_slice(&rep.seeds[1]);
+            bytes.extend_from_slice(&rep.hidden_commitment);
+            bytes.extend_from_slice(&rep.opened_view);
+            if let Some(share) = &rep.input_share {
+                bytes.extend_from_slice(share);
+            }
+        }
+        Ok(bytes)
+    }
+
+    pub fn decode(bytes: &[u8]) -> anyhow::Result<Self> {
+        if bytes.len() > MAX_ZK_PROOF_BYTES {
+            return Err(anyhow::anyhow!("Zero-knowledge proof exceeds {} bytes", MAX_ZK_PROOF_BYTES));
+        }
+        let mut reader = Reader { bytes };
+        if reader.take(3)? != ZK_PROOF_MAGIC {
+            return Err(anyhow::anyhow!("Not a zero-knowledge proof (bad magic)"));
+        }
+        let version = reader.take(1)?[0];
+        if version != ZK_PROOF_VERSION {
+            return Err(anyhow::anyhow!("Unsupported zero-knowledge proof version {}", version));
+        }
+        let len = u32::from_be_bytes(reader.take(4)?.try_into()?) as usize;
+        let statement: ZkStatement = bcs::from_bytes(reader.take(len)?)?;
+        let (circuit, _) = statement.circuit()?;
+        let (view_len, share_len) = (and_gates(&circuit).div_ceil(8), circuit.input_len().div_ceil(8));
+
+        let mut repetitions = Vec::with_capacity(REPETITIONS);
+        for _ in 0..REPETITIONS {
+            let challenge = reader.take(1)?[0];
+            if challenge > 2 {
+                return Err(anyhow::anyhow!("Invalid challenge {} in zero-knowledge proof", challenge));
+            }
+            repetitions.push(Repetition {
+                challenge,
+                seeds: [reader.take(16)?.try_into()?, reader.take(16)?.try_into()?],
+                hidden_commitment: reader.take(32)?.try_into()?,
+                opened_view: reader.take(view_len)?.to_vec(),
+                input_share: if challenge != 0 { Some(reader.take(share_len)?.to_vec()) } else { None },
+            });
+        }
+        if !reader.bytes.is_empty() {
+            return Err(anyhow::anyhow!("Trailing bytes after zero-knowledge proof"));
+        }
+        Ok(Self { statement, repetitions })
+    }
+}
+
+struct Reader<'a> {
+    bytes: &'a [u8],
+}
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, len: usize) -> anyhow::Result<&'a [u8]> {
+        if self.bytes.len() < len {
+            return Err(anyhow::anyhow!("Truncated zero-knowledge proof"));
+        }
+        let (head, tail) = self.bytes.split_at(len);
+        self.bytes = tail;
+        Ok(head)
+    }
+}
+
+/// 电路的与门数 (只取决于电路形状)
+fn and_gates(circuit: &Circuit) -> usize {
+    let mut plain = Plain::default();
+    circuit.eval(&mut plain, &vec![false; circuit.input_len()]);
+    plain.and_gates
+}
+
+fn derive_seed(master_seed: &[u8; 32], rep: usize, player: usize) -> [u8; 16] {
+    let mut hasher = blake3::Hasher::new_derive_key("yuanjing-core zk seed v1");
+    hasher.update(master_seed);
+    hasher.update(&(rep as u32).to_be_bytes());
+    hasher.update(&[player as u8]);
+    let mut seed = [0u8; 16];
+    seed.copy_from_slice(&hasher.finalize().as_bytes()[..16]);
+    seed
+}
+
+/// 参与方的承诺：种子 (参与方 2 另加输入份额) 与视图
+fn commit(seed: &[u8; 16], input_share: Option<&[u8]>, view: &[u8]) -> [u8; 32] {
+    let mut hasher = blake3::Hasher::new_derive_key("yuanjing-core zk commitment v1");
+    hasher.update(seed);
+    if let Some(share) = input_share {
+        hasher.update(share);
+    }
+    hasher.update(view);
+    *hasher.finalize().as_bytes()
+}
+
+/// Fiat-Shamir 挑战：对陈述、全部承诺与输出份额哈希，逐个 2 位取值，拒绝 3
+fn challenges(statement: &[u8], commitments: &[[[u8; 32]; 3]], outputs: &[[Vec<u8>; 3]]) -> Vec<u8> {
+    let mut hasher = blake3::Hasher::new_derive_key("yuanjing-core zk challenge v1");
+    hasher.update(statement);
+    for (commitments, outputs) in commitments.iter().zip(outputs) {
+        for (commitment, output) in commitments.iter().zip(outputs) {
+            hasher.update(commitment);
+            hasher.update(output);
+        }
+    }
+    let mut reader = hasher.finalize_xof();
+    let mut challenges = Vec::with_capacity(REPETITIONS);
+    while challenges.len() < REPETITIONS {
+        let mut byte = [0u8; 1];
+        reader.fill(&mut byte);
+        for shift in [0, 2, 4, 6] {
+            let value = byte[0] >> shift & 3;
+            if value < 3 && challenges.len() < REPETITIONS {
+                challenges.push(value);
+            }
+        }
+    }
+    challenges
+}
+
+/// 山峰从右向左合并出根 (与 MMR 的 `bag_rhs_peaks` 一致)
+fn bag_peaks(peaks: &[[u8; 32]]) -> anyhow::Result<[u8; 32]> {
+    let mut peaks = peaks.iter().rev();
+    let mut root = *peaks.next().ok_or_else(|| anyhow::anyhow!("No peaks"))?;
+    for peak in peaks {
+        root = MergeBlake3::merge(&root, peak).map_err(|e| anyhow::anyhow!("Merge error: {}", e))?;
+    }
+    Ok(root)
+}
+
+fn pack_bits(bits: &[bool]) -> Vec<u8> {
+    let mut bytes = vec![0u8; bits.len().div_ceil(8)];
+    for (i, bit) in bits.iter().enumerate() {
+        if *bit {
+            bytes[i / 8] |= 1 << (i % 8);
+        }
+    }
+    bytes
+}
+
+fn uleb128(mut value: u32) -> Vec<u8> {
+    let mut bytes = Vec::new();
+    loop {
+        let byte = (value & 0x7f) as u8;
+        value >>= 7;
+        if value == 0 {
+            bytes.push(byte);
+            return bytes;
+        }
+        bytes.push(byte | 0x80);
+    }
+}
+
+fn decode_hash(value: &str) -> anyhow::Result<[u8; 32]> {
+    hex::decode(value)?
+        .try_into()
+        .map_err(|_| anyhow::anyhow!("Expected a 32-byte hex hash, got '{}'", value))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::evidence::{leaf_hash_of, Confidence};
+    use ckb_merkle_mountain_range::util::MemMMR;
+    use ckb_merkle_mountain_range::MMRStore;
+    use std::sync::OnceLock;
+
+    struct Fixture {
+        statement: ZkStatement,
+        witness: ZkWitness,
+        proof: ZkProof,
+    }
+
+    /// 5 个叶子的树中第 4 个叶子 (加盐) 的陈述、见证与证明；生成一次，各用例共享
+    fn fixture() -> &'static Fixture {
+        static FIXTURE: OnceLock<Fixture> = OnceLock::new();
+        FIXTURE.get_or_init(|| {
+            let evidence = Evidence {
+                image_phash: "jPDw8PDw8PA=".to_string(),
+                image_sha256: hex::encode(blake3::hash(b"zk image").as_bytes()),
+                verdict: false,
+                confidence: Confidence::BasisPoints(9300),
+                activated_prompts: vec![2, 5],
+                prompt_pool_hash: "zk-model".to_string(),
+                external_knowledge_hash: hex::encode([0x22; 32]),
+                timestamp: 1_767_225_600,
+                claimed_at: None,
+                consensus: None,
+                calibration: None,
+                provenance: None,
+                client_context: None,
+            };
+            let bytes = evidence.canonical_bytes().unwrap();
+            let salt = *blake3::hash(b"zk salt").as_bytes();
+
+            let mut mmr = MemMMR::<[u8; 32], MergeBlake3>::default();
+            for index in 0..3u8 {
+                mmr.push(*blake3::hash(&[index]).as_bytes()).unwrap();
+            }
+            let pos = mmr.push(leaf_hash_of(&bytes, Some(&salt))).unwrap();
+            mmr.push(*blake3::hash(b"last").as_bytes()).unwrap();
+
+            let node = |pos: u64| mmr.store().get_elem(pos)?.ok_or_else(|| anyhow::anyhow!("Missing node {}", pos));
+            let (statement, witness) = prepare(mmr.mmr_size(), pos, Some(salt), &bytes, node).unwrap();
+            assert_eq!(statement.root, hex::encode(mmr.get_root().unwrap()));
+            let proof = ZkProof::prove(&statement, &witness, [7u8; 32]).unwrap();
+            Fixture { statement, witness, proof }
+        })
+    }
+
+    /// 改动证明后仍能校验通过即为失败；陈述不自洽时报错同样算拒绝
+    fn rejected(proof: &ZkProof) -> bool {
+        !matches!(proof.verify(), Ok(true))
+    }
+
+    #[test]
+    fn prove_verify_and_encoding_round_trip() {
+        let Fixture { statement, proof, .. } = fixture();
+        assert_eq!(&proof.statement, statement);
+        assert!(proof.verify().unwrap());
+
+        let bytes = proof.encode().unwrap();
+        let decoded = ZkProof::decode(&bytes).unwrap();
+        assert_eq!(&decoded, proof);
+        assert!(decoded.verify().unwrap());
+    }
+
+    #[test]
+    fn witness_must_satisfy_the_statement() {
+        let Fixture { statement, witness, .. } = fixture();
+        let claimed = ZkStatement { verdict: !statement.verdict, ..statement.clone() };
+        assert!(ZkProof::prove(&claimed, witness, [8u8; 32]).is_err());
+        let reshaped = ZkStatement { message_len: statement.message_len + 1, ..statement.clone() };
+        assert!(ZkProof::prove(&reshaped, witness, [8u8; 32]).is_err());
+    }
+
+    #[test]
+    fn tampered_views_are_rejected() {
+        let Fixture { proof, .. } = fixture();
+        let mut tampered = proof.clone();
+        tampered.repetitions[0].opened_view[0] ^= 1;
+        assert!(rejected(&tampered));
+
+        let mut tampered = proof.clone();
+        let share = tampered.repetitions.iter_mut().find_map(|rep| rep.input_share.as_mut()).unwrap();
+        share[0] ^= 1;
+        assert!(rejected(&tampered));
+
+        let mut tampered = proof.clone();
+        tampered.repetitions[REPETITIONS - 1].opened_view.pop();
+        assert!(rejected(&tampered));
+    }
+
+    #[test]
+    fn tampered_commitments_and_seeds_are_rejected() {
+        let Fixture { proof, .. } = fixture();
+        let mut tampered = proof.clone();
+        tampered.repetitions[1].hidden_commitment[31] ^= 0x80;
+        assert!(rejected(&tampered));
+
+        for slot in 0..2 {
+            let mut tampered = proof.clone();
+            tampered.repetitions[2].seeds[slot][0] ^= 1;
+            assert!(rejected(&tampered));
+        }
+    }
+
+    #[test]
+    fn tampered_challenges_are_rejected() {
+        let Fixture { proof, .. } = fixture();
+        // 挑战 1 与 2 都带参与方 2 的输入份额，互换后形状不变，只能靠 Fiat-Shamir 挑战拒绝
+        let rep = proof.repetitions.iter().position(|rep| rep.challenge == 1).unwrap();
+        let mut tampered = proof.clone();
+        tampered.repetitions[rep].challenge = 2;
+        assert!(rejected(&tampered));
+
+        let mut tampered = proof.clone();
+        tampered.repetitions[rep].challenge = 3;
+        assert!(rejected(&tampered));
+
+        let mut tampered = proof.clone();
+        tampered.repetitions.pop();
+        assert!(rejected(&tampered));
+    }
+
+    #[test]
+    fn tampered_statements_are_rejected() {
+        let Fixture { statement, proof, .. } = fixture();
+        let other_image = hex::encode(blake3::hash(b"another image").as_bytes());
+        let mut other_root = statement.clone();
+        other_root.peaks[0] = hex::encode([0u8; 32]);
+        other_root.root = hex::encode(bag_peaks(&other_root.peaks.iter().map(|peak| decode_hash(peak).unwrap()).collect::<Vec<_>>()).unwrap());
+        let mut unbagged = statement.clone();
+        unbagged.root = hex::encode([0u8; 32]);
+
+        for tampered in [
+            ZkStatement { verdict: !statement.verdict, ..statement.clone() },
+            ZkStatement { image_sha256: other_image, ..statement.clone() },
+            ZkStatement { salted: false, ..statement.clone() },
+            ZkStatement { message_len: statement.message_len - 1, ..statement.clone() },
+            ZkStatement { tree_size: statement.tree_size + 1, ..statement.clone() },
+            other_root,
+            unbagged,
+        ] {
+            assert!(rejected(&ZkProof { statement: tampered, ..proof.clone() }));
+        }
+    }
+
+    #[test]
+    fn truncated_or_oversized_encodings_are_rejected() {
+        let Fixture { proof, .. } = fixture();
+        let bytes = proof.encode().unwrap();
+        let statement_end = 8 + u32::from_be_bytes(bytes[4..8].try_into().unwrap()) as usize;
+        for len in [0, 3, 7, statement_end - 1, statement_end, statement_end + 40, bytes.len() - 1] {
+            assert!(ZkProof::decode(&bytes[..len]).is_err(), "prefix of {} bytes decoded", len);
+        }
+
+        let mut trailing = bytes.clone();
+        trailing.push(0);
+        assert!(ZkProof::decode(&trailing).is_err());
+
+        let mut oversized = bytes.clone();
+        oversized.resize(MAX_ZK_PROOF_BYTES + 1, 0);
+        assert!(ZkProof::decode(&oversized).is_err());
+
+        let mut statement_len = bytes.clone();
+        statement_len[4..8].copy_from_slice(&u32::MAX.to_be_bytes());
+        assert!(ZkProof::decode(&statement_len).is_err());
+
+        let mut challenge = bytes.clone();
+        challenge[statement_end] = 3;
+        assert!(ZkProof::decode(&challenge).is_err());
+
+        let mut magic = bytes;
+        magic[0] = b'X';
+        assert!(ZkProof::decode(&magic).is_err());
+    }
+}
@@ -0,0 +1,208 @@
+use std::ops::{BitAnd, BitXor};
+
+use super::circuit::Backend;
+
+/// 重复次数：每次重复作弊被抓住的概率为 1/3，219 次后作弊成功的概率约为 (2/3)^219 ≈ 2^-128
+pub(crate) const REPETITIONS: usize = 219;
+
+/// 每个比特按重复次数切片存放：第 r 次重复在第 r / 64 个字的第 r % 64 位
+const LANE_WORDS: usize = REPETITIONS.div_ceil(64);
+
+/// 所有重复中同一根线上的比特 (bitslice)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub(crate) struct Lanes([u64; LANE_WORDS]);
+
+impl Lanes {
+    pub const ONES: Self = Self([u64::MAX; LANE_WORDS]);
+
+    pub fn get(&self, rep: usize) -> bool {
+        self.0[rep / 64] >> (rep % 64) & 1 == 1
+    }
+
+    pub fn set(&mut self, rep: usize) {
+        self.0[rep / 64] |= 1 << (rep % 64);
+    }
+}
+
+impl BitXor for Lanes {
+    type Output = Self;
+
+    fn bitxor(self, rhs: Self) -> Self {
+        Self(std::array::from_fn(|i| self.0[i] ^ rhs.0[i]))
+    }
+}
+
+impl BitAnd for Lanes {
+    type Output = Self;
+
+    fn bitand(self, rhs: Self) -> Self {
+        Self(std::array::from_fn(|i| self.0[i] & rhs.0[i]))
+    }
+}
+
+/// 由种子展开的随机带：前 `n_in` 位为输入份额 (参与方 0、1)，其后每个与门 1 位
+pub(crate) fn tape(seed: &[u8; 16], bits: usize) -> Vec<u8> {
+    let mut hasher = blake3::Hasher::new_derive_key("yuanjing-core zk tape v1");
+    hasher.update(seed);
+    let mut bytes = vec![0u8; bits.div_ceil(8)];
+    hasher.finalize_xof().fill(&mut bytes);
+    bytes
+}
+
+/// 逐次重复的比特串 (从 `offset` 起 `bits` 位) 转为按线排列的 bitslice
+pub(crate) fn transpose(streams: &[&[u8]], offset: usize, bits: usize) -> Vec<Lanes> {
+    let mut lanes = vec![Lanes::default(); bits];
+    for (rep, stream) in streams.iter().enumerate() {
+        for (c, lane) in lanes.iter_mut().enumerate() {
+            let bit = offset + c;
+            if stream[bit / 8] >> (bit % 8) & 1 == 1 {
+                lane.set(rep);
+            }
+        }
+    }
+    lanes
+}
+
+/// bitslice 中第 `rep` 次重复的比特串 (字节从低位到高位)
+pub(crate) fn extract(lanes: &[Lanes], rep: usize) -> Vec<u8> {
+    let mut bytes = vec![0u8; lanes.len().div_ceil(8)];
+    for (c, lane) in lanes.iter().enumerate() {
+        if lane.get(rep) {
+            bytes[c / 8] |= 1 << (c % 8);
+        }
+    }
+    bytes
+}
+
+/// 证明方：三个参与方同时模拟，记录每个与门上各方的输出 (视图)
+pub(crate) struct Prover {
+    randomness: [Vec<Lanes>; 3],
+    pub views: [Vec<Lanes>; 3],
+}
+
+impl Prover {
+    pub fn new(randomness: [Vec<Lanes>; 3]) -> Self {
+        let gates = randomness[0].len();
+        Self { randomness, views: std::array::from_fn(|_| Vec::with_capacity(gates)) }
+    }
+}
+
+impl Backend for Prover {
+    type Bit = [Lanes; 3];
+
+    fn zero(&self) -> Self::Bit {
+        [Lanes::default(); 3]
+    }
+
+    /// 公开常量 1 只加在参与方 0 的份额上
+    fn one(&self) -> Self::Bit {
+        [Lanes::ONES, Lanes::default(), Lanes::default()]
+    }
+
+    fn xor(&mut self, a: Self::Bit, b: Self::Bit) -> Self::Bit {
+        std::array::from_fn(|j| a[j] ^ b[j])
+    }
+
+    /// ZKBoo 与门：z_j = x_j y_j ⊕ x_{j+1} y_j ⊕ x_j y_{j+1} ⊕ R_j ⊕ R_{j+1}
+    fn and(&mut self, a: Self::Bit, b: Self::Bit) -> Self::Bit {
+        let gate = self.views[0].len();
+        let z: [Lanes; 3] = std::array::from_fn(|j| {
+            let next = (j + 1) % 3;
+            (a[j] & b[j]) ^ (a[next] & b[j]) ^ (a[j] & b[next]) ^ self.randomness[j][gate] ^ self.randomness[next][gate]
+        });
+        for (view, share) in self.views.iter_mut().zip(z) {
+            view.push(share);
+        }
+        z
+    }
+}
+
+/// 验证方：只模拟被打开的两方 e、e+1；e 的与门输出自行重算，e+1 的取自证明
+pub(crate) struct Verifier {
+    randomness: [Vec<Lanes>; 2],
+    /// 参与方 0 落在哪个槽位 (按重复)，公开常量 1 加在该槽位上
+    ones: [Lanes; 2],
+    opened_view: Vec<Lanes>,
+    pub recomputed_view: Vec<Lanes>,
+}
+
+impl Verifier {
+    pub fn new(randomness: [Vec<Lanes>; 2], ones: [Lanes; 2], opened_view: Vec<Lanes>) -> Self {
+        let gates = opened_view.len();
+        Self { randomness, ones, opened_view, recomputed_view: Vec::with_capacity(gates) }
+    }
+}
+
+impl Backend for Verifier {
+    type Bit = [Lanes; 2];
+
+    fn zero(&self) -> Self::Bit {
+        [Lanes::default(); 2]
+    }
+
+    fn one(&self) -> Self::Bit {
+        self.ones
+    }
+
+    fn xor(&mut self, a: Self::Bit, b: Self::Bit) -> Self::Bit {
+        [a[0] ^ b[0], a[1] ^ b[1]]
+    }
+
+    fn and(&mut self, a: Self::Bit, b: Self::Bit) -> Self::Bit {
+        let gate = self.recomputed_view.len();
+        let z = (a[0] & b[0]) ^ (a[1] & b[0]) ^ (a[0] & b[1]) ^ self.randomness[0][gate] ^ self.randomness[1][gate];
+        self.recomputed_view.push(z);
+        [z, self.opened_view[gate]]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::RngCore;
+
+    fn random_lanes() -> Lanes {
+        let mut lanes = Lanes::default();
+        lanes.0.iter_mut().for_each(|word| *word = rand::thread_rng().next_u64());
+        lanes
+    }
+
+    #[test]
+    fn transpose_and_extract_round_trip() {
+        let bits = 21;
+        let streams: Vec<Vec<u8>> = (0..REPETITIONS).map(|rep| tape(&[rep as u8; 16], bits + 8)).collect();
+        let slices: Vec<&[u8]> = streams.iter().map(Vec::as_slice).collect();
+        let lanes = transpose(&slices, 8, bits);
+        for (rep, stream) in streams.iter().enumerate() {
+            let bytes = extract(&lanes, rep);
+            for bit in 0..bits {
+                assert_eq!(bytes[bit / 8] >> (bit % 8) & 1, stream[(bit + 8) / 8] >> ((bit + 8) % 8) & 1);
+            }
+        }
+        assert_ne!(tape(&[0u8; 16], 64), tape(&[1u8; 16], 64));
+    }
+
+    #[test]
+    fn and_gate_shares_reconstruct_and_match_the_verifier() {
+        let (a, b): ([Lanes; 3], [Lanes; 3]) = (std::array::from_fn(|_| random_lanes()), std::array::from_fn(|_| random_lanes()));
+        let randomness: [Vec<Lanes>; 3] = std::array::from_fn(|_| vec![random_lanes()]);
+        let mut prover = Prover::new(randomness.clone());
+        let z = prover.and(a, b);
+        assert_eq!(z[0] ^ z[1] ^ z[2], (a[0] ^ a[1] ^ a[2]) & (b[0] ^ b[1] ^ b[2]));
+
+        // 打开参与方 e、e+1 时，验证方重算出参与方 e 的视图，参与方 e+1 的取自证明
+        for e in 0..3 {
+            let next = (e + 1) % 3;
+            let opened = vec![prover.views[next][0]];
+            let mut verifier = Verifier::new([randomness[e].clone(), randomness[next].clone()], [Lanes::default(); 2], opened);
+            assert_eq!(verifier.and([a[e], a[next]], [b[e], b[next]]), [z[e], z[next]]);
+            assert_eq!(verifier.recomputed_view, vec![prover.views[e][0]]);
+        }
+
+        // 篡改被打开一方的输入份额，重算的视图偏离承诺过的视图 (偏差为 b_0 ⊕ b_1)
+        let mut verifier = Verifier::new([randomness[0].clone(), randomness[1].clone()], [Lanes::default(); 2], vec![z[1]]);
+        let recomputed = verifier.and([a[0] ^ Lanes::ONES, a[1]], [b[0], b[1]])[0];
+        assert_eq!(recomputed, z[0] ^ b[0] ^ b[1]);
+        assert_ne!(recomputed, prover.views[0][0]);
+    }
+}
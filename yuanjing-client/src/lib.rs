@@ -0,0 +1,551 @@
+use ed25519_dalek::VerifyingKey;
+use rand::RngCore;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::io::Read;
+use std::time::{Duration, Instant};
+
+use yuanjing_core::api::{
+    AsyncProveResponse, AuditResponse, BatchAuditRequest, BatchAuditResponse, ChallengeRequest, ChallengeResponse, CustodyEntry, CustodyRequest, CustodyResponse, EvidenceAclRequest, EvidenceAclResponse, EvidenceListResponse, EvidenceRecordResponse, EvidenceTagsRequest, EvidenceTagsResponse, ModelRegistryResponse, RegistryUpdateResponse, SearchResponse, IdentityResponse, JobResponse, JobStage, KeysResponse,
+    AnomaliesResponse, ModelRegisterRequest, ModelRegisterResponse, NotarizationsResponse, ProblemDetails, RekorEntriesResponse, PromptRegistryRequest, PromptRegistryResponse, ProveReceipt, ProveRequest, PublishedRootsResponse, ReviewEntry, ReviewRequest, ReviewsResponse, SolidityProofResponse, SthAtResponse,
+};
+use yuanjing_core::analytics::{ActivationAnalytics, TrendInterval};
+use yuanjing_core::anomaly::AnomalyStatus;
+use yuanjing_core::dsse::DsseEnvelope;
+use yuanjing_core::envelope::SealedBundle;
+use yuanjing_core::model_registry::{self, SignedRootMetadata, SignedTargetsMetadata};
+use yuanjing_core::evidence::Evidence;
+use yuanjing_core::prompts::PromptInfo;
+use yuanjing_core::receipt::Receipt;
+use yuanjing_core::revocation::SignedRevocationList;
+use yuanjing_core::signer::EvidenceSigner;
+use yuanjing_core::zk::{ZkProof, MAX_ZK_PROOF_BYTES};
+
+pub mod verify;
+
+pub use verify::VerificationReport;
+/// 与服务端共用的类型，调用方无需再依赖 `yuanjing-core`
+pub use yuanjing_core::{analytics, anomaly, api, envelope, evidence, position, proof, prompts, receipt, review, zk};
+
+/// 轮询回执的间隔
+const POLL_INTERVAL: Duration = Duration::from_millis(200);
+
+/// 模块：官方客户端 (yuanjing-client)
+///
+/// **职责**: 封装 HTTP API (提交证据、轮询回执、获取证明、挑战应答)，
+/// 并在本地完成全部验证，不依赖服务端的 `/verify`：
+/// 1. 证据哈希：Blake3(按回执 `encoding` 编码的 Evidence，加盐叶子前置盐值) == 回执中的 `evidence_hash`；
+/// 2. 签名：证据签名与回执签名有效，派生密钥的背书由租户根密钥签发；
+/// 3. 包含性：叶子位于回执签名的那棵树中 (`tree_size` / `root`)；
+/// 4. 根一致性：叶子仍位于服务端此刻签名的当前树中 (以随机 nonce 发起挑战，防止重放旧应答)；
+/// 5. 吊销：主身份签名的吊销列表中，回执签名密钥在回执时间未被吊销。
+///
+/// **为什么需要**: 各调用方此前各自拼 HTTP 请求、各自实现验证，容易漏掉其中一步
+/// (最常见的是只验签名、不验包含性)。DTO 直接复用服务端的定义，字段改动在编译期就能发现。
+///
+/// 未固定根公钥时，根公钥取自 `/keys` (首次信任)；生产环境应通过 `with_pinned_key` 固定。
+/// 吊销列表的签发公钥同理：未固定时取自 `/identity`，应通过 `with_master_key` 固定。
+pub struct Client {
+    base_url: String,
+    api_key: Option<String>,
+    pinned_key: Option<VerifyingKey>,
+    master_key: Option<VerifyingKey>,
+    registry_root: Option<SignedRootMetadata>,
+    agent: ureq::Agent,
+}
+
+impl Client {
+    pub fn new(base_url: &str) -> Self {
+        Self {
+            base_url: base_url.trim_end_matches('/').to_string(),
+            api_key: None,
+            pinned_key: None,
+            master_key: None,
+            registry_root: None,
+            agent: ureq::AgentBuilder::new().timeout(Duration::from_secs(30)).build(),
+        }
+    }
+
+    /// 以 `Authorization: Bearer` 携带 API Key (同时决定所属租户)
+    pub fn with_api_key(mut self, api_key: &str) -> Self {
+        self.api_key = Some(api_key.to_string());
+        self
+    }
+
+    /// 固定租户根公钥：`/keys` 返回的根公钥与之不符时验证直接失败
+    pub fn with_pinned_key(mut self, public_key: VerifyingKey) -> Self {
+        self.pinned_key = Some(public_key);
+        self
+    }
+
+    /// 固定主身份公钥：吊销列表必须由它签发，否则验证直接失败
+    pub fn with_master_key(mut self, public_key: VerifyingKey) -> Self {
+        self.master_key = Some(public_key);
+        self
+    }
+
+    /// 固定模型注册表的受信根：验证时据此离线核对注册表签名链，未固定时不检查 (`model_registry_valid` 为 None)
+    pub fn with_registry_root(mut self, root: SignedRootMetadata) -> Self {
+        self.registry_root = Some(root);
+        self
+    }
+
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.agent = ureq::AgentBuilder::new().timeout(timeout).build();
+        self
+    }
+
+    /// 注册模型 (Prompt Pool 哈希)，之后才能以它提交证据
+    pub fn register_model(&self, hash: &str, description: &str) -> anyhow::Result<ModelRegisterResponse> {
+        self.post(
+            "/v1/model/register",
+            &ModelRegisterRequest { hash: hash.to_string(), description: description.to_string() },
+        )
+    }
+
+    /// 签名的模型注册表：根元数据链与全部被接受过的目标元数据
+    pub fn model_registry(&self, tenant: Option<&str>) -> anyhow::Result<ModelRegistryResponse> {
+        match tenant {
+            Some(tenant) => self.get(&format!("/v1/model/registry?tenant={}", tenant)),
+            None => self.get("/v1/model/registry"),
+        }
+    }
+
+    /// 提交根轮换 (须由当前根与新根的根角色各自达到门限签名)
+    pub fn rotate_registry_root(&self, root: &SignedRootMetadata) -> anyhow::Result<RegistryUpdateResponse> {
+        self.post("/v1/model/registry/root", root)
+    }
+
+    /// 发布目标元数据 (须由当前根的目标角色达到门限签名)，模型白名单随之整体替换
+    pub fn publish_registry_targets(&self, targets: &SignedTargetsMetadata) -> anyhow::Result<RegistryUpdateResponse> {
+        self.post("/v1/model/registry/targets", targets)
+    }
+
+    /// 模型版本的 Prompt 说明对照表 (索引到专家名称)
+    pub fn prompts(&self, hash: &str) -> anyhow::Result<PromptRegistryResponse> {
+        self.get(&format!("/v1/models/{}/prompts", hash))
+    }
+
+    /// 整体替换模型版本的 Prompt 说明对照表；空列表删除
+    pub fn set_prompts(&self, hash: &str, prompts: Vec<PromptInfo>) -> anyhow::Result<PromptRegistryResponse> {
+        self.post(&format!("/v1/models/{}/prompts", hash), &PromptRegistryRequest { prompts })
+    }
+
+    /// 提交证据，返回签名回执
+    pub fn prove(&self, request: &ProveRequest) -> anyhow::Result<ProveReceipt> {
+        self.post("/v1/prove", request)
+    }
+
+    /// 读取已入库的证据及其回执
+    pub fn evidence(&self, pos: u64) -> anyhow::Result<EvidenceRecordResponse> {
+        self.get(&format!("/v1/evidence/{}", pos))
+    }
+
+    /// 按内容寻址的证据 ID (`/prove` 响应的 `evidence_id`) 读取证据及其回执
+    pub fn evidence_by_id(&self, evidence_id: &str) -> anyhow::Result<EvidenceRecordResponse> {
+        self.get(&format!("/v1/evidence/by-id/{}", evidence_id))
+    }
+
+    /// 证据的 DSSE 信封 (载荷为 in-toto 声明)；可用 [`yuanjing_core::dsse::verify`] 对照回执公钥校验
+    pub fn evidence_dsse(&self, pos: u64) -> anyhow::Result<DsseEnvelope> {
+        self.get(&format!("/v1/evidence/{}/dsse", pos))
+    }
+
+    /// 证据的 SCITT 透明声明 (COSE_Sign1 字节)；可用 [`yuanjing_core::scitt::verify`] 对照签发方与租户根公钥校验
+    pub fn evidence_scitt(&self, pos: u64) -> anyhow::Result<Vec<u8>> {
+        self.send_bytes(self.request("GET", &format!("/v1/evidence/{}/scitt", pos)).call())
+    }
+
+    /// 轮询直到 `pos` 处的证据带上回执 (尚未入库时服务端返回 404)，超时报错
+    pub fn poll_receipt(&self, pos: u64, timeout: Duration) -> anyhow::Result<EvidenceRecordResponse> {
+        let deadline = Instant::now() + timeout;
+        loop {
+            match self.evidence(pos) {
+                Ok(record) if record.receipt.is_some() => return Ok(record),
+                Ok(_) => {}
+                Err(e) if e.downcast_ref::<ApiError>().is_some_and(|e| e.status == 404) => {}
+                Err(e) => return Err(e),
+            }
+            if Instant::now() >= deadline {
+                return Err(anyhow::anyhow!("Timed out waiting for a receipt at pos {}", pos));
+            }
+            std::thread::sleep(POLL_INTERVAL);
+        }
+    }
+
+    /// 提交后台存证任务 (大文件)，用 [`Client::job`] 或 [`Client::wait_for_job`] 取结果
+    pub fn prove_async(&self, request: &ProveRequest) -> anyhow::Result<AsyncProveResponse> {
+        self.post("/v1/prove/async", request)
+    }
+
+    /// 查询后台存证任务的进度
+    pub fn job(&self, job_id: &str) -> anyhow::Result<JobResponse> {
+        self.get(&format!("/v1/jobs/{}", job_id))
+    }
+
+    /// 轮询直到后台任务结束，返回回执；任务失败时返回服务端的错误，超时报错
+    pub fn wait_for_job(&self, job_id: &str, timeout: Duration) -> anyhow::Result<ProveReceipt> {
+        let deadline = Instant::now() + timeout;
+        loop {
+            let job = self.job(job_id)?;
+            match (job.status, job.receipt, job.error) {
+                (JobStage::Completed, Some(receipt), _) => return Ok(receipt),
+                (JobStage::Failed, _, Some(problem)) => {
+                    return Err(ApiError { status: problem.status, message: problem.detail.clone(), problem: Some(problem) }.into())
+                }
+                (status, _, _) if status.is_finished() => {
+                    return Err(anyhow::anyhow!("Job {} finished as {:?} without a result", job_id, status))
+                }
+                _ => {}
+            }
+            if Instant::now() >= deadline {
+                return Err(anyhow::anyhow!("Timed out waiting for job {}", job_id));
+            }
+            std::thread::sleep(POLL_INTERVAL);
+        }
+    }
+
+    /// 获取包含性证明；`tree_size` 缺省为当前树
+    ///
+    /// `pos` 是 MMR 位置 (回执的 `leaf_pos`)；手里是叶子序号时先用 [`position::leaf_index_to_pos`] 换算。
+    pub fn audit(&self, pos: u64, tree_size: Option<u64>) -> anyhow::Result<AuditResponse> {
+        match tree_size {
+            Some(tree_size) => self.get(&format!("/v1/audit/{}?tree_size={}", pos, tree_size)),
+            None => self.get(&format!("/v1/audit/{}", pos)),
+        }
+    }
+
+    /// 链上校验用的证明与 ABI 调用数据；`tree_size` 缺省为当前树
+    pub fn solidity_proof(&self, pos: u64, tree_size: Option<u64>) -> anyhow::Result<SolidityProofResponse> {
+        match tree_size {
+            Some(tree_size) => self.get(&format!("/v1/audit/{}/solidity?tree_size={}", pos, tree_size)),
+            None => self.get(&format!("/v1/audit/{}/solidity", pos)),
+        }
+    }
+
+    /// 证据的访问控制列表 (获准读取原文的审计方)
+    pub fn evidence_acl(&self, pos: u64) -> anyhow::Result<EvidenceAclResponse> {
+        self.get(&format!("/v1/evidence/{}/acl", pos))
+    }
+
+    /// 整体替换证据的访问控制列表；空列表撤销全部授权
+    pub fn set_evidence_acl(&self, pos: u64, auditors: &[&str]) -> anyhow::Result<EvidenceAclResponse> {
+        let auditors = auditors.iter().map(|name| name.to_string()).collect();
+        self.post(&format!("/v1/evidence/{}/acl", pos), &EvidenceAclRequest { auditors })
+    }
+
+    /// 证据的标签 (签名内容之外，可随时修改)
+    pub fn evidence_tags(&self, pos: u64) -> anyhow::Result<EvidenceTagsResponse> {
+        self.get(&format!("/v1/evidence/{}/tags", pos))
+    }
+
+    /// 整体替换证据的标签；空列表删除全部标签
+    pub fn set_evidence_tags(&self, pos: u64, tags: &[&str]) -> anyhow::Result<EvidenceTagsResponse> {
+        let tags = tags.iter().map(|tag| tag.to_string()).collect();
+        self.post(&format!("/v1/evidence/{}/tags", pos), &EvidenceTagsRequest { tags })
+    }
+
+    /// 带某个标签的证据 (按位置升序)，从 `from` 开始一页；响应的 `next` 为下一页的 `from`
+    pub fn evidence_by_tag(&self, tag: &str, from: u64) -> anyhow::Result<EvidenceListResponse> {
+        self.get(&format!("/v1/evidence?tag={}&from={}", tag, from))
+    }
+
+    /// 按排序与筛选条件列出证据，`params` 为查询参数 (如 `[("sort", "-confidence"), ("verdict", "true")]`)；
+    /// 翻页时按位置排序传响应的 `next` 为 `from`，其他排序传 `next_cursor` 为 `cursor`
+    pub fn list_evidence(&self, params: &[(&str, &str)]) -> anyhow::Result<EvidenceListResponse> {
+        let request = params.iter().fold(self.request("GET", "/v1/evidence"), |request, (key, value)| request.query(key, value));
+        self.send(request.call())
+    }
+
+    /// 全文检索证据元数据 (来源说明、标签、模型)，按相关度返回前 `limit` 条
+    pub fn search(&self, query: &str, limit: usize) -> anyhow::Result<SearchResponse> {
+        self.send(self.request("GET", "/v1/search").query("q", query).query("limit", &limit.to_string()).call())
+    }
+
+    /// 证据的完整保管链 (按事件先后)；可用 [`verify::verify_custody_chain`] 离线校验
+    pub fn custody(&self, pos: u64) -> anyhow::Result<CustodyResponse> {
+        self.get(&format!("/v1/evidence/{}/custody", pos))
+    }
+
+    /// 为证据追加一条保管事件，返回签名后的记录及其叶子位置
+    pub fn record_custody(&self, pos: u64, request: &CustodyRequest) -> anyhow::Result<CustodyEntry> {
+        self.post(&format!("/v1/evidence/{}/custody", pos), request)
+    }
+
+    /// 证据的全部鉴定人复核 (按收录先后)
+    pub fn reviews(&self, pos: u64) -> anyhow::Result<ReviewsResponse> {
+        self.get(&format!("/v1/evidence/{}/reviews", pos))
+    }
+
+    /// 提交鉴定人签署的复核意见；签名用 [`review::ReviewAssessment::sign`] 在鉴定人一侧生成
+    pub fn submit_review(&self, pos: u64, request: &ReviewRequest) -> anyhow::Result<ReviewEntry> {
+        self.post(&format!("/v1/evidence/{}/reviews", pos), request)
+    }
+
+    /// 只有给定收件人 (X25519 公钥) 能解密的证据包
+    ///
+    /// 收件人用 [`SealedBundle::open`] 解密后再做完整校验；经手方只能用 [`SealedBundle::verify_public`] 核对回执。
+    pub fn sealed_bundle(&self, pos: u64, recipients: &[[u8; 32]]) -> anyhow::Result<SealedBundle> {
+        let recipients = recipients.iter().map(hex::encode).collect::<Vec<_>>().join(",");
+        self.get(&format!("/v1/evidence/{}/bundle/sealed?recipients={}", pos, recipients))
+    }
+
+    /// `pos` 处叶子的零知识披露证明 (实验性)；`tree_size` 缺省为当前树
+    ///
+    /// 用 [`ZkProof::verify`] 在本地校验，[`ZkProof::encode`] 后交给第三方；生成需要数秒。
+    pub fn zk_proof(&self, pos: u64, tree_size: Option<u64>) -> anyhow::Result<ZkProof> {
+        let path = match tree_size {
+            Some(tree_size) => format!("/v1/audit/{}/zk?tree_size={}", pos, tree_size),
+            None => format!("/v1/audit/{}/zk", pos),
+        };
+        ZkProof::decode(&self.send_bytes(self.request("GET", &path).call())?)
+    }
+
+    /// 一次获取多个叶子的合并证明；`tree_size` 缺省为当前树
+    ///
+    /// 用 [`verify::verify_batch_inclusion`] 校验，并自行核对各叶子哈希与手中回执的 `evidence_hash`。
+    pub fn audit_batch(&self, positions: &[u64], tree_size: Option<u64>) -> anyhow::Result<BatchAuditResponse> {
+        self.post("/v1/audit/batch", &BatchAuditRequest { positions: positions.to_vec(), leaf_indexes: Vec::new(), tree_size, root: None })
+    }
+
+    /// 公钥目录
+    pub fn keys(&self) -> anyhow::Result<KeysResponse> {
+        self.get("/v1/keys")
+    }
+
+    /// 服务身份 (主公钥、DID、证书链)；首次接入时取主公钥，经带外渠道核对后用 [`Client::with_pinned_key`] 固定
+    pub fn identity(&self) -> anyhow::Result<IdentityResponse> {
+        self.get("/v1/identity")
+    }
+
+    /// 密钥吊销列表，并核对由主身份签发 (固定了主公钥时对照它，否则对照 `/identity` 的主公钥)
+    pub fn revocations(&self) -> anyhow::Result<SignedRevocationList> {
+        let master_key = match self.master_key {
+            Some(key) => key,
+            None => verify::decode_key(&self.identity()?.public_key)?,
+        };
+        let signed: SignedRevocationList = self.get("/v1/revocations")?;
+        signed.verify(&master_key)?;
+        Ok(signed)
+    }
+
+    /// 最新签名树头 (`/.well-known/yuanjing-root`)，用 `SignedTreeHead::verify` 对照固定的根公钥校验
+    pub fn published_roots(&self) -> anyhow::Result<PublishedRootsResponse> {
+        self.get("/.well-known/yuanjing-root")
+    }
+
+    /// 外部公证方对该租户树头的副署 (新的在前)，用 `NotaryReceipt::verify` 对照固定的公证方公钥校验
+    pub fn notarizations(&self, tenant: Option<&str>) -> anyhow::Result<NotarizationsResponse> {
+        match tenant {
+            Some(tenant) => self.get(&format!("/v1/roots/notarizations?tenant={}", tenant)),
+            None => self.get("/v1/roots/notarizations"),
+        }
+    }
+
+    /// 该租户树头在 Rekor 透明日志中的条目 (新的在前)，凭 `log_index` 可在 Rekor 上核对
+    pub fn rekor_entries(&self, tenant: Option<&str>) -> anyhow::Result<RekorEntriesResponse> {
+        match tenant {
+            Some(tenant) => self.get(&format!("/v1/roots/rekor?tenant={}", tenant)),
+            None => self.get("/v1/roots/rekor"),
+        }
+    }
+
+    /// 激活模式统计 (各 Prompt 的激活次数、共同激活与趋势)；`model` 为 None 时统计全部模型
+    pub fn activation_analytics(&self, model: Option<&str>, interval: TrendInterval) -> anyhow::Result<ActivationAnalytics> {
+        match model {
+            Some(model) => self.get(&format!("/v1/analytics/activations?model={}&interval={}", model, interval.as_str())),
+            None => self.get(&format!("/v1/analytics/activations?interval={}", interval.as_str())),
+        }
+    }
+
+    /// 激活路径异常的复核队列 (按位置升序)；复核结论须由管理员提交
+    pub fn anomalies(&self, status: AnomalyStatus) -> anyhow::Result<AnomaliesResponse> {
+        self.get(&format!("/v1/anomalies?status={}", status.as_str()))
+    }
+
+    /// `ts` (Unix 秒) 时刻仍为最新的签名树头，用 `SignedTreeHead::verify` 对照固定的根公钥校验
+    pub fn sth_at(&self, ts: i64, tenant: Option<&str>) -> anyhow::Result<SthAtResponse> {
+        match tenant {
+            Some(tenant) => self.get(&format!("/v1/sth/at?ts={}&tenant={}", ts, tenant)),
+            None => self.get(&format!("/v1/sth/at?ts={}", ts)),
+        }
+    }
+
+    /// 挑战应答：服务端对 nonce + 当前根签名
+    pub fn challenge(&self, nonce: &str) -> anyhow::Result<ChallengeResponse> {
+        self.post("/v1/challenge", &ChallengeRequest { nonce: nonce.to_string() })
+    }
+
+    /// 读取 `pos` 处的证据与回执，并在本地完整验证
+    ///
+    /// 原文已被服务端清理时报错：此时须用自己留存的原文调用 [`Client::verify`]。
+    pub fn verify_pos(&self, pos: u64) -> anyhow::Result<VerificationReport> {
+        let record = self.evidence(pos)?;
+        let evidence = record
+            .evidence
+            .ok_or_else(|| anyhow::anyhow!("Evidence at pos {} was pruned or erased; verify it against a retained copy", pos))?;
+        let (receipt, receipt_signature) = record
+            .receipt
+            .zip(record.receipt_signature)
+            .ok_or_else(|| anyhow::anyhow!("Evidence at pos {} has no receipt", pos))?;
+        self.verify_salted(&evidence, &receipt, &receipt_signature, record.evidence_signature.as_deref(), record.salt.as_deref())
+    }
+
+    /// 本地完整验证：证据哈希 + 签名 + 包含性证明 + 根一致性
+    ///
+    /// 签名 (Hex) 通常取自 `prove` 或 `evidence` 的返回值；`evidence_signature` 可省略。
+    /// 加盐叶子请使用 [`Client::verify_salted`]。
+    pub fn verify(
+        &self,
+        evidence: &Evidence,
+        receipt: &Receipt,
+        receipt_signature: &str,
+        evidence_signature: Option<&str>,
+    ) -> anyhow::Result<VerificationReport> {
+        self.verify_salted(evidence, receipt, receipt_signature, evidence_signature, None)
+    }
+
+    /// 同 [`Client::verify`]，`salt` 为加盐叶子的盐值 (Hex，取自 `prove` 返回值)
+    pub fn verify_salted(
+        &self,
+        evidence: &Evidence,
+        receipt: &Receipt,
+        receipt_signature: &str,
+        evidence_signature: Option<&str>,
+        salt: Option<&str>,
+    ) -> anyhow::Result<VerificationReport> {
+        let salt = salt.map(verify::decode_hash).transpose()?;
+        let leaf = evidence.salted_leaf_hash_as(receipt.encoding, salt.as_ref())?;
+        let evidence_hash_match = hex::encode(leaf) == receipt.evidence_hash;
+
+        let (root_key, signing_key, endorsement_valid) =
+            verify::resolve_keys(&self.keys()?, &receipt.tenant_id, &receipt.key_path, self.pinned_key.as_ref())?;
+        let receipt_signature_valid =
+            EvidenceSigner::verify_receipt(&signing_key, receipt, &verify::decode_signature(receipt_signature)?)?;
+        let evidence_signature_valid = evidence_signature
+            .map(|signature| -> anyhow::Result<bool> {
+                let payload = evidence.canonical_bytes_as(receipt.encoding)?;
+                Ok(EvidenceSigner::verify_bytes(&signing_key, &payload, &verify::decode_signature(signature)?))
+            })
+            .transpose()?;
+        let key_revoked = self.revocations()?.list.is_revoked(&signing_key, receipt.timestamp);
+
+        // 回执签名的那棵树
+        let proof = self.audit(receipt.leaf_pos, Some(receipt.tree_size))?;
+        let included_in_receipt_root =
+            verify::verify_inclusion(&proof, receipt.leaf_pos, leaf, receipt.tree_size, verify::decode_hash(&receipt.root)?)?;
+
+        // 服务端此刻签名的当前树：MMR 只追加，当前树必须不小于回执时的树且仍包含该叶子
+        let mut nonce = [0u8; 32];
+        rand::thread_rng().fill_bytes(&mut nonce);
+        let nonce = hex::encode(nonce);
+        let answer = self.challenge(&nonce)?;
+        let statement = &answer.statement;
+        if statement.tenant_id != receipt.tenant_id {
+            return Err(anyhow::anyhow!(
+                "Challenge was answered for tenant '{}', receipt belongs to '{}' (check the API key)",
+                statement.tenant_id,
+                receipt.tenant_id
+            ));
+        }
+        if !statement.verify(&root_key, &verify::decode_signature(&answer.signature)?, &nonce)? {
+            return Err(anyhow::anyhow!("Challenge response is not signed by the tenant key"));
+        }
+        let included_in_current_root = statement.tree_size >= receipt.tree_size && {
+            let proof = self.audit(receipt.leaf_pos, Some(statement.tree_size))?;
+            verify::verify_inclusion(&proof, receipt.leaf_pos, leaf, statement.tree_size, verify::decode_hash(&statement.root)?)?
+        };
+
+        // 模型注册表：从固定的受信根出发核对签名链，回执签发时生效的目标元数据须列出证据用到的每个模型
+        let model_registry_valid = match &self.registry_root {
+            Some(trusted) => {
+                let registry = self.model_registry(Some(&receipt.tenant_id))?;
+                let models = std::iter::once(&evidence.prompt_pool_hash)
+                    .chain(evidence.consensus.iter().flat_map(|consensus| &consensus.models).map(|model| &model.prompt_pool_hash));
+                Some(models.into_iter().all(|model| {
+                    model_registry::verify_model_at(trusted, &registry.roots, &registry.targets, model, receipt.timestamp).is_ok()
+                }))
+            }
+            None => None,
+        };
+
+        Ok(VerificationReport {
+            evidence_hash_match,
+            evidence_signature_valid,
+            receipt_signature_valid,
+            endorsement_valid,
+            key_pinned: self.pinned_key.is_some(),
+            key_revoked,
+            included_in_receipt_root,
+            included_in_current_root,
+            current_tree_size: statement.tree_size,
+            model_registry_valid,
+        })
+    }
+
+    fn get<T: DeserializeOwned>(&self, path: &str) -> anyhow::Result<T> {
+        self.send(self.request("GET", path).call())
+    }
+
+    fn post<B: Serialize, T: DeserializeOwned>(&self, path: &str, body: &B) -> anyhow::Result<T> {
+        self.send(
+            self.request("POST", path)
+                .set("Content-Type", "application/json")
+                .send_string(&serde_json::to_string(body)?),
+        )
+    }
+
+    fn request(&self, method: &str, path: &str) -> ureq::Request {
+        let request = self.agent.request(method, &format!("{}{}", self.base_url, path));
+        match &self.api_key {
+            Some(api_key) => request.set("Authorization", &format!("Bearer {}", api_key)),
+            None => request,
+        }
+    }
+
+    fn send<T: DeserializeOwned>(&self, result: Result<ureq::Response, ureq::Error>) -> anyhow::Result<T> {
+        Ok(serde_json::from_slice(&self.send_bytes(result)?)?)
+    }
+
+    fn send_bytes(&self, result: Result<ureq::Response, ureq::Error>) -> anyhow::Result<Vec<u8>> {
+        match result {
+            Ok(response) => {
+                let mut body = Vec::new();
+                response.into_reader().take(MAX_ZK_PROOF_BYTES as u64).read_to_end(&mut body)?;
+                Ok(body)
+            }
+            Err(ureq::Error::Status(status, response)) => {
+                let body = response.into_string().unwrap_or_default();
+                let problem = serde_json::from_str::<ProblemDetails>(&body).ok();
+                let message = problem.as_ref().map_or(body, |problem| problem.detail.clone());
+                Err(ApiError { status, message, problem }.into())
+            }
+            Err(e) => Err(anyhow::anyhow!("Request failed: {}", e)),
+        }
+    }
+}
+
+/// 服务端返回的错误 (非 2xx 状态码及其响应正文)
+#[derive(Debug, Clone)]
+pub struct ApiError {
+    pub status: u16,
+    /// problem+json 的 `detail`；响应不是 problem+json 时为原始正文
+    pub message: String,
+    /// 服务端的错误详情，按 `problem_type` 区分错误类型
+    pub problem: Option<ProblemDetails>,
+}
+
+impl ApiError {
+    /// 错误类型 (`urn:yuanjing:problem:<slug>`)
+    pub fn problem_type(&self) -> Option<&str> {
+        self.problem.as_ref().map(|problem| problem.problem_type.as_str())
+    }
+}
+
+impl std::fmt::Display for ApiError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "HTTP {}: {}", self.status, self.message)
+    }
+}
+
+impl std::error::Error for ApiError {}
@@ -0,0 +1,151 @@
+use ed25519_dalek::{Signature, VerifyingKey};
+use serde::Serialize;
+
+use yuanjing_core::api::{AuditResponse, BatchAuditResponse, CustodyResponse, KeysResponse};
+use yuanjing_core::mmr_store;
+use yuanjing_core::signer::EvidenceSigner;
+use yuanjing_core::InclusionProof;
+
+/// 本地验证结果 (Verification Report)
+///
+/// 每一项都由客户端自己算出，不采信服务端的 `proof_valid` 之类的标记。
+#[derive(Debug, Clone, Serialize)]
+pub struct VerificationReport {
+    /// Blake3(BCS(Evidence)) 是否等于回执中的 `evidence_hash`
+    pub evidence_hash_match: bool,
+    /// 证据签名是否有效；未提供证据签名时为 None
+    pub evidence_signature_valid: Option<bool>,
+    pub receipt_signature_valid: bool,
+    /// 派生密钥的背书是否由租户根密钥签发；回执由根密钥签发时为 None
+    pub endorsement_valid: Option<bool>,
+    /// 租户根公钥是否来自本地固定 (pinned)，否则为首次从 `/keys` 获取 (TOFU)
+    pub key_pinned: bool,
+    /// 回执签发时签名密钥是否已被吊销 (按主身份签名的吊销列表)
+    pub key_revoked: bool,
+    /// 叶子位于回执签名的那棵树中 (`tree_size` / `root`)
+    pub included_in_receipt_root: bool,
+    /// 叶子仍位于服务端此刻签名的当前树中 (挑战应答中的根)
+    pub included_in_current_root: bool,
+    /// 当前树的大小 (挑战应答中签名的 `tree_size`)
+    pub current_tree_size: u64,
+    /// 回执签发时模型是否由签名的注册表批准；未固定注册表受信根时为 None
+    pub model_registry_valid: Option<bool>,
+}
+
+impl VerificationReport {
+    pub fn is_valid(&self) -> bool {
+        self.evidence_hash_match
+            && self.evidence_signature_valid.unwrap_or(true)
+            && self.receipt_signature_valid
+            && self.endorsement_valid.unwrap_or(true)
+            && !self.key_revoked
+            && self.included_in_receipt_root
+            && self.included_in_current_root
+            && self.model_registry_valid.unwrap_or(true)
+    }
+}
+
+/// 在 `/keys` 目录中找到租户根公钥与回执的签名公钥
+///
+/// 固定了根公钥时，目录中的根公钥必须与之一致；派生公钥须带有根公钥签发的有效背书。
+/// 返回 (根公钥, 签名公钥, 背书是否有效)。
+pub fn resolve_keys(
+    keys: &KeysResponse,
+    tenant_id: &str,
+    key_path: &str,
+    pinned: Option<&VerifyingKey>,
+) -> anyhow::Result<(VerifyingKey, VerifyingKey, Option<bool>)> {
+    let tenant = keys
+        .tenants
+        .iter()
+        .find(|tenant| tenant.tenant_id == tenant_id)
+        .ok_or_else(|| anyhow::anyhow!("Tenant '{}' is not listed in the key directory", tenant_id))?;
+    let root_key = decode_key(&tenant.public_key)?;
+    if let Some(pinned) = pinned {
+        if *pinned != root_key {
+            return Err(anyhow::anyhow!(
+                "Tenant '{}' key {} does not match the pinned key {}",
+                tenant_id,
+                tenant.public_key,
+                hex::encode(pinned.to_bytes())
+            ));
+        }
+    }
+    if key_path.is_empty() {
+        return Ok((root_key, root_key, None));
+    }
+
+    let derived = tenant
+        .derived_keys
+        .iter()
+        .find(|key| key.endorsement.key_path == key_path)
+        .ok_or_else(|| anyhow::anyhow!("Signing key '{}' is not listed for tenant '{}'", key_path, tenant_id))?;
+    let signature = decode_signature(&derived.endorsement_signature)?;
+    let endorsed = derived.endorsement.tenant_id == tenant_id
+        && EvidenceSigner::verify_endorsement(&root_key, &derived.endorsement, &signature)?;
+    Ok((root_key, decode_key(&derived.endorsement.public_key)?, Some(endorsed)))
+}
+
+/// 用 `/audit/{pos}` 返回的证明路径，离线校验叶子在 `tree_size` 大小、根为 `root` 的树中
+///
+/// 只使用证明路径本身；响应中的 `root` 与 `proof_valid` 一概不信。
+pub fn verify_inclusion(proof: &AuditResponse, pos: u64, leaf: [u8; 32], tree_size: u64, root: [u8; 32]) -> anyhow::Result<bool> {
+    if proof.leaf_pos != pos || proof.tree_size != tree_size {
+        return Ok(false);
+    }
+    let items = proof.proof_hex.iter().map(|item| decode_hash(item)).collect::<anyhow::Result<Vec<_>>>()?;
+    Ok(InclusionProof::new(tree_size, pos, items).verify(leaf, root))
+}
+
+/// 用 `/audit/batch` 返回的合并证明，离线校验其中全部叶子都在 `tree_size` 大小、根为 `root` 的树中
+///
+/// 叶子哈希取自响应本身，调用方须另行与各自回执中的 `evidence_hash` 比对。
+pub fn verify_batch_inclusion(proof: &BatchAuditResponse, tree_size: u64, root: [u8; 32]) -> anyhow::Result<bool> {
+    if proof.tree_size != tree_size {
+        return Ok(false);
+    }
+    let items = proof.proof_hex.iter().map(|item| decode_hash(item)).collect::<anyhow::Result<Vec<_>>>()?;
+    let leaves = proof
+        .leaves
+        .iter()
+        .map(|leaf| Ok((leaf.pos, decode_hash(&leaf.leaf_hash)?)))
+        .collect::<anyhow::Result<Vec<_>>>()?;
+    Ok(mmr_store::verify_batch_proof(tree_size, items, leaves, root))
+}
+
+/// 离线校验 `/evidence/{pos}/custody` 返回的保管链：每条记录都由 `trusted_key` 签名、指向同一证据、
+/// 序号连续，`prev_hash` 依次等于上一条记录重算出的叶子哈希，响应中的 `leaf_hash` 也与重算结果一致
+///
+/// 只说明链本身完整；各条记录确实在日志中，还须用其 `leaf_pos` 取 `/audit` 证明，交给 [`verify_inclusion`]。
+pub fn verify_custody_chain(chain: &CustodyResponse, trusted_key: &VerifyingKey) -> anyhow::Result<bool> {
+    let mut prev = [0u8; 32];
+    for (index, entry) in chain.events.iter().enumerate() {
+        let event = &entry.record.event;
+        if event.pos != chain.pos || event.index != index as u64 || decode_hash(&event.prev_hash)? != prev {
+            return Ok(false);
+        }
+        if !entry.record.verify(trusted_key)? {
+            return Ok(false);
+        }
+        let leaf = entry.record.leaf_hash()?;
+        if decode_hash(&entry.leaf_hash)? != leaf {
+            return Ok(false);
+        }
+        prev = leaf;
+    }
+    Ok(true)
+}
+
+pub(crate) fn decode_hash(value: &str) -> anyhow::Result<[u8; 32]> {
+    hex::decode(value)?
+        .try_into()
+        .map_err(|_| anyhow::anyhow!("Expected a 32-byte hash, got '{}'", value))
+}
+
+pub(crate) fn decode_key(value: &str) -> anyhow::Result<VerifyingKey> {
+    Ok(VerifyingKey::from_bytes(&decode_hash(value)?)?)
+}
+
+pub(crate) fn decode_signature(value: &str) -> anyhow::Result<Signature> {
+    Signature::from_slice(&hex::decode(value)?).map_err(|_| anyhow::anyhow!("Invalid signature '{}'", value))
+}
@@ -0,0 +1,70 @@
+/*
+ * yuanjing.h — C ABI for verifying yuanjing-core evidence bundles.
+ *
+ * Link against libyuanjing_ffi (.so / .a) built with:
+ *     cargo build --release -p yuanjing-ffi
+ *
+ * Return codes: 0 means valid / success, positive values are verification
+ * outcomes (YJ_*), negative values are caller errors (YJ_ERR_*).
+ * The library never retains caller pointers and never allocates memory
+ * that the caller must free.
+ */
+#ifndef YUANJING_H
+#define YUANJING_H
+
+#include <stddef.h>
+#include <stdint.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+#define YJ_VALID                      0
+#define YJ_MALFORMED                  1
+#define YJ_EVIDENCE_HASH_MISMATCH     2
+#define YJ_EVIDENCE_SIGNATURE_INVALID 3
+#define YJ_RECEIPT_SIGNATURE_INVALID  4
+#define YJ_ENDORSEMENT_INVALID        5
+#define YJ_INCLUSION_INVALID          6
+#define YJ_UNTRUSTED_KEY              7
+#define YJ_KEY_REVOKED                8
+
+#define YJ_ERR_INVALID_ARGUMENT      -1
+#define YJ_ERR_INTERNAL              -2
+#define YJ_ERR_REVOCATION_LIST       -3
+
+/* Verify a JSON evidence bundle (GET /evidence/{pos}/bundle), trusting the
+ * tenant key embedded in the bundle. Only proves the bundle is consistent. */
+int yj_verify_bundle(const uint8_t *bytes, size_t len);
+
+/* Verify a JSON evidence bundle and require its tenant key to equal
+ * trusted_key (32-byte Ed25519 public key). Use this on devices. */
+int yj_verify_bundle_with_key(const uint8_t *bytes, size_t len, const uint8_t trusted_key[32]);
+
+/* Like yj_verify_bundle_with_key, and additionally reject receipts signed
+ * after their signing key was revoked (YJ_KEY_REVOKED). revocations is the
+ * JSON of GET /revocations and must be signed by master_key (32-byte Ed25519
+ * public key); otherwise YJ_ERR_REVOCATION_LIST is returned. */
+int yj_verify_bundle_with_revocations(const uint8_t *bytes, size_t len, const uint8_t trusted_key[32],
+                                      const uint8_t *revocations, size_t revocations_len,
+                                      const uint8_t master_key[32]);
+
+/* Compute the leaf hash Blake3(BCS(evidence)) of a JSON evidence object. */
+int yj_leaf_hash(const uint8_t *evidence_json, size_t len, uint8_t out[32]);
+
+/* Verify that leaf sits at pos in the MMR of tree_size nodes with the given
+ * root. proof holds proof_len consecutive 32-byte hashes. */
+int yj_verify_inclusion(const uint8_t leaf[32], uint64_t pos, uint64_t tree_size,
+                        const uint8_t root[32], const uint8_t *proof, size_t proof_len);
+
+/* Static English description of a return code. Do not free. */
+const char *yj_strerror(int code);
+
+/* Library version. Do not free. */
+const char *yj_version(void);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif /* YUANJING_H */
@@ -0,0 +1,405 @@
+use ed25519_dalek::VerifyingKey;
+use std::ffi::{c_char, c_int};
+use std::panic::{catch_unwind, AssertUnwindSafe};
+
+use yuanjing_core::bundle::{BundleStatus, EvidenceBundle};
+use yuanjing_core::evidence::Evidence;
+use yuanjing_core::proof::verify_proof;
+use yuanjing_core::revocation::SignedRevocationList;
+
+/// 参数为空指针或长度非法
+pub const YJ_ERR_INVALID_ARGUMENT: c_int = -1;
+/// 内部错误 (Rust panic 被拦截在边界上，不会跨越 FFI)
+pub const YJ_ERR_INTERNAL: c_int = -2;
+/// 吊销列表无法解析，或不是由给定的主身份公钥签发
+pub const YJ_ERR_REVOCATION_LIST: c_int = -3;
+
+/// 模块：C FFI (嵌入式验证方)
+///
+/// **为什么需要**: 自助查验终端等嵌入式设备用 C/C++ 编写，无法引入 Rust 工具链。
+/// 这里以 C ABI 导出证据包校验 (`bundle.rs`) 与几个底层原语，设备只需链接预编译的
+/// `libyuanjing_ffi` 并包含 `include/yuanjing.h`。
+///
+/// 约定：返回 0 表示成功 / 有效；正数为 `BundleStatus` 中的校验结论；负数为调用错误。
+/// 所有输入均由调用方持有，本库不保留指针、不分配需要调用方释放的内存。
+///
+/// 校验 JSON 格式的证据包 (`GET /evidence/{pos}/bundle` 的响应)，信任包内自带的租户公钥
+///
+/// # Safety
+/// `bytes` 须指向至少 `len` 个可读字节。
+#[no_mangle]
+pub unsafe extern "C" fn yj_verify_bundle(bytes: *const u8, len: usize) -> c_int {
+    guard(|| {
+        let bytes = input(bytes, len)?;
+        Some(EvidenceBundle::verify_json(bytes, None, None) as c_int)
+    })
+}
+
+/// 校验证据包，并要求租户根公钥等于 `trusted_key` (32 字节 Ed25519 公钥)
+///
+/// 设备应在出厂时固化受信公钥并使用此函数；否则伪造者可以自签一份自洽的证据包。
+///
+/// # Safety
+/// `bytes` 须指向至少 `len` 个可读字节，`trusted_key` 须指向 32 个可读字节。
+#[no_mangle]
+pub unsafe extern "C" fn yj_verify_bundle_with_key(bytes: *const u8, len: usize, trusted_key: *const u8) -> c_int {
+    guard(|| {
+        let bytes = input(bytes, len)?;
+        let trusted_key = array(trusted_key)?;
+        let Ok(trusted_key) = VerifyingKey::from_bytes(&trusted_key) else {
+            return Some(BundleStatus::UntrustedKey as c_int);
+        };
+        Some(EvidenceBundle::verify_json(bytes, Some(&trusted_key), None) as c_int)
+    })
+}
+
+/// 同 [`yj_verify_bundle_with_key`]，并按吊销列表拒绝签发时密钥已被吊销的回执 (返回 8，`YJ_KEY_REVOKED`)
+///
+/// `revocations` 为 `GET /revocations` 响应的 JSON，须由 `master_key` (32 字节主身份公钥) 签发，
+/// 否则返回 `YJ_ERR_REVOCATION_LIST`：列表不可信时不会退化为不查吊销。
+///
+/// # Safety
+/// `bytes` 须指向至少 `len` 个可读字节，`revocations` 须指向至少 `revocations_len` 个可读字节，
+/// `trusted_key` 与 `master_key` 须各指向 32 个可读字节。
+#[no_mangle]
+pub unsafe extern "C" fn yj_verify_bundle_with_revocations(
+    bytes: *const u8,
+    len: usize,
+    trusted_key: *const u8,
+    revocations: *const u8,
+    revocations_len: usize,
+    master_key: *const u8,
+) -> c_int {
+    guard(|| {
+        let bytes = input(bytes, len)?;
+        let trusted_key = array(trusted_key)?;
+        let revocations = input(revocations, revocations_len)?;
+        let master_key = array(master_key)?;
+        let Some(signed) = VerifyingKey::from_bytes(&master_key)
+            .ok()
+            .and_then(|master_key| SignedRevocationList::from_json(revocations, &master_key).ok())
+        else {
+            return Some(YJ_ERR_REVOCATION_LIST);
+        };
+        let Ok(trusted_key) = VerifyingKey::from_bytes(&trusted_key) else {
+            return Some(BundleStatus::UntrustedKey as c_int);
+        };
+        Some(EvidenceBundle::verify_json(bytes, Some(&trusted_key), Some(&signed.list)) as c_int)
+    })
+}
+
+/// 计算证据 (JSON) 的叶子哈希 Blake3(BCS(Evidence))，写入 `out` (32 字节)
+///
+/// 证据无法解析时返回 1 (`YJ_MALFORMED`)。
+///
+/// # Safety
+/// `evidence_json` 须指向至少 `len` 个可读字节，`out` 须指向 32 个可写字节。
+#[no_mangle]
+pub unsafe extern "C" fn yj_leaf_hash(evidence_json: *const u8, len: usize, out: *mut u8) -> c_int {
+    guard(|| {
+        let bytes = input(evidence_json, len)?;
+        if out.is_null() {
+            return None;
+        }
+        let Some(leaf) = serde_json::from_slice::<Evidence>(bytes).ok().and_then(|evidence| evidence.leaf_hash().ok()) else {
+            return Some(BundleStatus::Malformed as c_int);
+        };
+        std::ptr::copy_nonoverlapping(leaf.as_ptr(), out, leaf.len());
+        Some(BundleStatus::Valid as c_int)
+    })
+}
+
+/// 校验包含性证明：`leaf` 位于 `tree_size` 大小、根为 `root` 的树中的 `pos` 位置
+///
+/// `proof` 为 `proof_len` 个依次排列的 32 字节哈希 (`proof_len` 可为 0)。
+/// 成立返回 0，不成立返回 6 (`YJ_INCLUSION_INVALID`)。
+///
+/// # Safety
+/// `leaf` 与 `root` 须各指向 32 个可读字节；`proof_len` 大于 0 时 `proof` 须指向 `proof_len * 32` 个可读字节。
+#[no_mangle]
+pub unsafe extern "C" fn yj_verify_inclusion(
+    leaf: *const u8,
+    pos: u64,
+    tree_size: u64,
+    root: *const u8,
+    proof: *const u8,
+    proof_len: usize,
+) -> c_int {
+    guard(|| {
+        let leaf = array(leaf)?;
+        let root = array(root)?;
+        let items = match proof_len {
+            0 => Vec::new(),
+            _ => input(proof, proof_len.checked_mul(32)?)?
+                .chunks_exact(32)
+                .map(|item| item.try_into().expect("chunks_exact yields 32-byte items"))
+                .collect(),
+        };
+        let status = match verify_proof(tree_size, items, pos, leaf, root) {
+            true => BundleStatus::Valid,
+            false => BundleStatus::InclusionInvalid,
+        };
+        Some(status as c_int)
+    })
+}
+
+/// 返回码的英文说明 (静态字符串，调用方不得释放)
+#[no_mangle]
+pub extern "C" fn yj_strerror(code: c_int) -> *const c_char {
+    let message: &'static [u8] = match code {
+        0 => b"bundle is valid\0",
+        1 => b"bundle is malformed or has an unsupported format\0",
+        2 => b"evidence does not match the receipt's evidence hash\0",
+        3 => b"evidence signature is invalid\0",
+        4 => b"receipt signature is invalid\0",
+        5 => b"derived signing key is not endorsed by the tenant key\0",
+        6 => b"inclusion proof does not match the receipt root\0",
+        7 => b"bundle is signed by an untrusted tenant key\0",
+        8 => b"receipt was signed after its signing key was revoked\0",
+        YJ_ERR_INVALID_ARGUMENT => b"invalid argument\0",
+        YJ_ERR_INTERNAL => b"internal error\0",
+        YJ_ERR_REVOCATION_LIST => b"revocation list is malformed or not signed by the master key\0",
+        _ => b"unknown error\0",
+    };
+    message.as_ptr().cast()
+}
+
+/// 库版本 (静态字符串)
+#[no_mangle]
+pub extern "C" fn yj_version() -> *const c_char {
+    concat!(env!("CARGO_PKG_VERSION"), "\0").as_ptr().cast()
+}
+
+/// 拦截 panic；闭包返回 None 表示参数非法
+fn guard(f: impl FnOnce() -> Option<c_int>) -> c_int {
+    match catch_unwind(AssertUnwindSafe(f)) {
+        Ok(Some(code)) => code,
+        Ok(None) => YJ_ERR_INVALID_ARGUMENT,
+        Err(_) => YJ_ERR_INTERNAL,
+    }
+}
+
+unsafe fn input<'a>(bytes: *const u8, len: usize) -> Option<&'a [u8]> {
+    (!bytes.is_null() && len <= isize::MAX as usize).then(|| std::slice::from_raw_parts(bytes, len))
+}
+
+unsafe fn array(bytes: *const u8) -> Option<[u8; 32]> {
+    input(bytes, 32)?.try_into().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ed25519_dalek::{Signer, SigningKey};
+    use std::ffi::CStr;
+    use yuanjing_core::bundle::BUNDLE_FORMAT;
+    use yuanjing_core::ckb_merkle_mountain_range::util::MemMMR;
+    use yuanjing_core::evidence::{CanonicalEncoding, Confidence};
+    use yuanjing_core::receipt::Receipt;
+    use yuanjing_core::revocation::{Revocation, RevocationList};
+    use yuanjing_core::{leaf_hash_of, MergeBlake3};
+
+    const ISSUED_AT: i64 = 1_767_225_700;
+
+    fn evidence() -> Evidence {
+        Evidence {
+            image_phash: "jPDw8PDw8PA=".to_string(),
+            image_sha256: hex::encode([0x5A; 32]),
+            verdict: true,
+            confidence: Confidence::BasisPoints(9100),
+            activated_prompts: vec![1, 4],
+            prompt_pool_hash: "model".to_string(),
+            external_knowledge_hash: hex::encode([0x11; 32]),
+            timestamp: 1_767_225_600,
+            claimed_at: None,
+            consensus: None,
+            calibration: None,
+            provenance: None,
+            client_context: None,
+        }
+    }
+
+    /// 由 `key` 签发的证据包：证据是三叶子树中的第二个叶子
+    fn bundle(key: &SigningKey) -> EvidenceBundle {
+        let evidence = evidence();
+        let evidence_bytes = evidence.canonical_bytes_as(CanonicalEncoding::Bcs).unwrap();
+        let leaf = leaf_hash_of(&evidence_bytes, None);
+
+        let mut mmr = MemMMR::<[u8; 32], MergeBlake3>::default();
+        mmr.push([0x01; 32]).unwrap();
+        let pos = mmr.push(leaf).unwrap();
+        mmr.push([0x03; 32]).unwrap();
+        let proof = mmr.gen_proof(vec![pos]).unwrap();
+
+        let receipt = Receipt {
+            evidence_hash: hex::encode(leaf),
+            leaf_pos: pos,
+            root: hex::encode(mmr.get_root().unwrap()),
+            tree_size: mmr.mmr_size(),
+            timestamp: ISSUED_AT,
+            seq: 1,
+            clock_uncertain: false,
+            tenant_id: "default".to_string(),
+            key_path: String::new(),
+            encoding: CanonicalEncoding::Bcs,
+            supersedes: None,
+        };
+        EvidenceBundle {
+            format: BUNDLE_FORMAT,
+            evidence_signature: hex::encode(key.sign(&evidence_bytes).to_bytes()),
+            receipt_signature: hex::encode(key.sign(&receipt.signing_bytes().unwrap()).to_bytes()),
+            receipt,
+            evidence,
+            public_key: hex::encode(key.verifying_key().to_bytes()),
+            endorsement: None,
+            proof: proof.proof_items().iter().map(hex::encode).collect(),
+            certificate_chain: None,
+            salt: None,
+        }
+    }
+
+    /// `master` 签发的吊销列表 (JSON)：`revoked` 自 `effective_from` 起吊销
+    fn revocations(master: &SigningKey, revoked: &SigningKey, effective_from: i64) -> Vec<u8> {
+        let list = RevocationList {
+            version: 1,
+            issued_at: effective_from,
+            entries: vec![Revocation {
+                public_key: hex::encode(revoked.verifying_key().to_bytes()),
+                effective_from,
+                reason: "key compromise".to_string(),
+            }],
+        };
+        let signed = SignedRevocationList {
+            signature: hex::encode(master.sign(&list.canonical_bytes().unwrap()).to_bytes()),
+            signer_public_key: hex::encode(master.verifying_key().to_bytes()),
+            list,
+        };
+        serde_json::to_vec(&signed).unwrap()
+    }
+
+    fn verify_with_revocations(bytes: &[u8], trusted: &SigningKey, list: &[u8], master: &SigningKey) -> c_int {
+        unsafe {
+            yj_verify_bundle_with_revocations(
+                bytes.as_ptr(),
+                bytes.len(),
+                trusted.verifying_key().as_bytes().as_ptr(),
+                list.as_ptr(),
+                list.len(),
+                master.verifying_key().as_bytes().as_ptr(),
+            )
+        }
+    }
+
+    fn message(code: c_int) -> String {
+        unsafe { CStr::from_ptr(yj_strerror(code)) }.to_str().unwrap().to_string()
+    }
+
+    #[test]
+    fn verifies_bundles_and_pins_the_tenant_key() {
+        let key = SigningKey::from_bytes(&[7; 32]);
+        let other = SigningKey::from_bytes(&[8; 32]);
+        let bytes = serde_json::to_vec(&bundle(&key)).unwrap();
+
+        unsafe {
+            assert_eq!(yj_verify_bundle(bytes.as_ptr(), bytes.len()), 0);
+            assert_eq!(yj_verify_bundle_with_key(bytes.as_ptr(), bytes.len(), key.verifying_key().as_bytes().as_ptr()), 0);
+            assert_eq!(
+                yj_verify_bundle_with_key(bytes.as_ptr(), bytes.len(), other.verifying_key().as_bytes().as_ptr()),
+                BundleStatus::UntrustedKey as c_int
+            );
+            assert_eq!(yj_verify_bundle(b"{}".as_ptr(), 2), BundleStatus::Malformed as c_int);
+        }
+    }
+
+    #[test]
+    fn rejects_tampered_bundles() {
+        let key = SigningKey::from_bytes(&[7; 32]);
+        let mut tampered = bundle(&key);
+        tampered.receipt.seq = 2;
+        let bytes = serde_json::to_vec(&tampered).unwrap();
+
+        assert_eq!(unsafe { yj_verify_bundle(bytes.as_ptr(), bytes.len()) }, BundleStatus::ReceiptSignatureInvalid as c_int);
+    }
+
+    #[test]
+    fn rejects_receipts_signed_after_revocation() {
+        let key = SigningKey::from_bytes(&[7; 32]);
+        let master = SigningKey::from_bytes(&[9; 32]);
+        let bytes = serde_json::to_vec(&bundle(&key)).unwrap();
+
+        let before = revocations(&master, &key, ISSUED_AT);
+        assert_eq!(verify_with_revocations(&bytes, &key, &before, &master), BundleStatus::KeyRevoked as c_int);
+        let after = revocations(&master, &key, ISSUED_AT + 1);
+        assert_eq!(verify_with_revocations(&bytes, &key, &after, &master), 0);
+    }
+
+    #[test]
+    fn refuses_revocation_lists_it_cannot_trust() {
+        let key = SigningKey::from_bytes(&[7; 32]);
+        let master = SigningKey::from_bytes(&[9; 32]);
+        let forger = SigningKey::from_bytes(&[10; 32]);
+        let bytes = serde_json::to_vec(&bundle(&key)).unwrap();
+
+        let forged = revocations(&forger, &key, ISSUED_AT + 1);
+        assert_eq!(verify_with_revocations(&bytes, &key, &forged, &master), YJ_ERR_REVOCATION_LIST);
+        assert_eq!(verify_with_revocations(&bytes, &key, b"not json", &master), YJ_ERR_REVOCATION_LIST);
+    }
+
+    #[test]
+    fn null_pointers_are_invalid_arguments() {
+        let key = SigningKey::from_bytes(&[7; 32]);
+        let bytes = serde_json::to_vec(&bundle(&key)).unwrap();
+        let list = revocations(&key, &key, ISSUED_AT + 1);
+        let trusted = key.verifying_key().to_bytes();
+        let null = std::ptr::null();
+
+        unsafe {
+            assert_eq!(yj_verify_bundle(null, 0), YJ_ERR_INVALID_ARGUMENT);
+            assert_eq!(yj_verify_bundle_with_key(bytes.as_ptr(), bytes.len(), null), YJ_ERR_INVALID_ARGUMENT);
+            assert_eq!(
+                yj_verify_bundle_with_revocations(bytes.as_ptr(), bytes.len(), trusted.as_ptr(), null, list.len(), trusted.as_ptr()),
+                YJ_ERR_INVALID_ARGUMENT
+            );
+            assert_eq!(
+                yj_verify_bundle_with_revocations(bytes.as_ptr(), bytes.len(), trusted.as_ptr(), list.as_ptr(), list.len(), null),
+                YJ_ERR_INVALID_ARGUMENT
+            );
+            assert_eq!(yj_leaf_hash(bytes.as_ptr(), bytes.len(), std::ptr::null_mut()), YJ_ERR_INVALID_ARGUMENT);
+            assert_eq!(yj_verify_inclusion(null, 0, 1, trusted.as_ptr(), null, 0), YJ_ERR_INVALID_ARGUMENT);
+            assert_eq!(yj_verify_inclusion(trusted.as_ptr(), 0, 1, trusted.as_ptr(), null, 1), YJ_ERR_INVALID_ARGUMENT);
+        }
+    }
+
+    #[test]
+    fn leaf_hash_and_inclusion_match_the_bundle() {
+        let key = SigningKey::from_bytes(&[7; 32]);
+        let bundle = bundle(&key);
+        let evidence = serde_json::to_vec(&bundle.evidence).unwrap();
+        let mut leaf = [0u8; 32];
+        assert_eq!(unsafe { yj_leaf_hash(evidence.as_ptr(), evidence.len(), leaf.as_mut_ptr()) }, 0);
+        assert_eq!(hex::encode(leaf), bundle.receipt.evidence_hash);
+        assert_eq!(unsafe { yj_leaf_hash(b"{}".as_ptr(), 2, leaf.as_mut_ptr()) }, BundleStatus::Malformed as c_int);
+
+        let proof: Vec<u8> = bundle.proof.iter().flat_map(|item| hex::decode(item).unwrap()).collect();
+        let mut root: [u8; 32] = hex::decode(&bundle.receipt.root).unwrap().try_into().unwrap();
+        let (pos, size) = (bundle.receipt.leaf_pos, bundle.receipt.tree_size);
+        let leaf: [u8; 32] = hex::decode(&bundle.receipt.evidence_hash).unwrap().try_into().unwrap();
+        unsafe {
+            assert_eq!(yj_verify_inclusion(leaf.as_ptr(), pos, size, root.as_ptr(), proof.as_ptr(), bundle.proof.len()), 0);
+            root[0] ^= 1;
+            assert_eq!(
+                yj_verify_inclusion(leaf.as_ptr(), pos, size, root.as_ptr(), proof.as_ptr(), bundle.proof.len()),
+                BundleStatus::InclusionInvalid as c_int
+            );
+        }
+    }
+
+    #[test]
+    fn every_return_code_has_a_message() {
+        for code in YJ_ERR_REVOCATION_LIST..=BundleStatus::KeyRevoked as c_int {
+            assert_ne!(message(code), "unknown error", "code {code}");
+        }
+        assert_eq!(message(9), "unknown error");
+        assert_eq!(unsafe { CStr::from_ptr(yj_version()) }.to_str().unwrap(), env!("CARGO_PKG_VERSION"));
+    }
+}
@@ -0,0 +1,141 @@
+use ed25519_dalek::{Signature, VerifyingKey};
+use pyo3::exceptions::{PyIOError, PyValueError};
+use pyo3::prelude::*;
+use pyo3::types::{PyBytes, PyString};
+use serde::de::DeserializeOwned;
+use std::path::Path;
+
+use yuanjing_core::evidence::{CanonicalEncoding, Evidence};
+use yuanjing_core::fingerprint;
+use yuanjing_core::mmr_store;
+use yuanjing_core::receipt::Receipt;
+use yuanjing_core::signer::EvidenceSigner;
+
+/// 模块：Python 绑定 (`import yuanjing`)
+///
+/// **为什么需要**: 推理服务用 Python 编写。此前 `client_sdk/evidence_schema.py` 手写了一份 BCS 编码，
+/// Evidence 每加一个字段都要两边同步，漏改一处叶子哈希就对不上。
+/// 这里直接导出服务端的实现：指纹、规范化字节、叶子哈希、签名与包含性证明校验，
+/// Python 侧算出的结果与服务端逐字节一致。
+///
+/// 证据与回执既可以传 `dict` (即 `/prove` 返回的 JSON 对象)，也可以传 JSON 字符串；
+/// 公钥、签名、哈希均为 Hex 字符串。
+#[pymodule]
+fn yuanjing(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_function(wrap_pyfunction!(fingerprints, m)?)?;
+    m.add_function(wrap_pyfunction!(canonical_bytes, m)?)?;
+    m.add_function(wrap_pyfunction!(leaf_hash, m)?)?;
+    m.add_function(wrap_pyfunction!(verify_evidence_signature, m)?)?;
+    m.add_function(wrap_pyfunction!(verify_receipt_signature, m)?)?;
+    m.add_function(wrap_pyfunction!(verify_inclusion, m)?)?;
+    m.add_function(wrap_pyfunction!(verify_receipt, m)?)?;
+    Ok(())
+}
+
+/// 图片指纹，返回 (SHA256, pHash)，与服务端入库时的算法相同
+#[pyfunction]
+fn fingerprints(path: &str) -> PyResult<(String, String)> {
+    fingerprint::generate_fingerprints(Path::new(path)).map_err(|e| PyIOError::new_err(e.to_string()))
+}
+
+/// 证据的规范化字节，即签名与叶子哈希的原文
+///
+/// `encoding` 取回执中的 `encoding` (`"bcs"` / `"protobuf"`)。
+#[pyfunction]
+#[pyo3(signature = (evidence, encoding = "bcs"))]
+fn canonical_bytes<'py>(evidence: &Bound<'py, PyAny>, encoding: &str) -> PyResult<Bound<'py, PyBytes>> {
+    let bytes = from_py::<Evidence>(evidence)?.canonical_bytes_as(parse_encoding(encoding)?).map_err(value_error)?;
+    Ok(PyBytes::new(evidence.py(), &bytes))
+}
+
+/// 叶子哈希 (Hex)：Blake3(规范字节)
+#[pyfunction]
+#[pyo3(signature = (evidence, encoding = "bcs"))]
+fn leaf_hash(evidence: &Bound<'_, PyAny>, encoding: &str) -> PyResult<String> {
+    Ok(hex::encode(from_py::<Evidence>(evidence)?.leaf_hash_as(parse_encoding(encoding)?).map_err(value_error)?))
+}
+
+/// 校验证据签名 (`/prove` 返回的 `signature`)
+#[pyfunction]
+#[pyo3(signature = (public_key, evidence, signature, encoding = "bcs"))]
+fn verify_evidence_signature(public_key: &str, evidence: &Bound<'_, PyAny>, signature: &str, encoding: &str) -> PyResult<bool> {
+    let payload = from_py::<Evidence>(evidence)?.canonical_bytes_as(parse_encoding(encoding)?).map_err(value_error)?;
+    Ok(EvidenceSigner::verify_bytes(&decode_key(public_key)?, &payload, &decode_signature(signature)?))
+}
+
+/// 校验回执签名 (`/prove` 返回的 `receipt_signature`)
+#[pyfunction]
+fn verify_receipt_signature(public_key: &str, receipt: &Bound<'_, PyAny>, signature: &str) -> PyResult<bool> {
+    EvidenceSigner::verify_receipt(&decode_key(public_key)?, &from_py(receipt)?, &decode_signature(signature)?).map_err(value_error)
+}
+
+/// 校验包含性证明：叶子位于 `tree_size` 大小、根为 `root` 的树中的 `pos` 位置
+///
+/// `proof` 为 `/audit/{pos}?tree_size=` 返回的 `proof_hex`。
+#[pyfunction]
+fn verify_inclusion(leaf_hash: &str, pos: u64, tree_size: u64, root: &str, proof: Vec<String>) -> PyResult<bool> {
+    let items = proof.iter().map(|item| decode_hash(item)).collect::<PyResult<Vec<_>>>()?;
+    Ok(mmr_store::verify_proof(tree_size, items, pos, decode_hash(leaf_hash)?, decode_hash(root)?))
+}
+
+/// 离线完整校验一份回执：证据哈希一致、回执签名有效、叶子位于回执签名的那棵树中
+///
+/// `public_key` 为回执的签名公钥 (根密钥或派生密钥，见 `/keys`)，
+/// `proof` 为 `/audit/{leaf_pos}?tree_size={tree_size}` 返回的 `proof_hex`。
+#[pyfunction]
+fn verify_receipt(
+    evidence: &Bound<'_, PyAny>,
+    receipt: &Bound<'_, PyAny>,
+    receipt_signature: &str,
+    public_key: &str,
+    proof: Vec<String>,
+) -> PyResult<bool> {
+    let evidence: Evidence = from_py(evidence)?;
+    let receipt: Receipt = from_py(receipt)?;
+    let leaf = evidence.leaf_hash_as(receipt.encoding).map_err(value_error)?;
+    if hex::encode(leaf) != receipt.evidence_hash {
+        return Ok(false);
+    }
+    if !EvidenceSigner::verify_receipt(&decode_key(public_key)?, &receipt, &decode_signature(receipt_signature)?)
+        .map_err(value_error)?
+    {
+        return Ok(false);
+    }
+    let items = proof.iter().map(|item| decode_hash(item)).collect::<PyResult<Vec<_>>>()?;
+    Ok(mmr_store::verify_proof(receipt.tree_size, items, receipt.leaf_pos, leaf, decode_hash(&receipt.root)?))
+}
+
+/// `dict` 先经 `json.dumps` 转成字符串，再按服务端的 serde 定义解析
+fn from_py<T: DeserializeOwned>(value: &Bound<'_, PyAny>) -> PyResult<T> {
+    let json = match value.downcast::<PyString>() {
+        Ok(text) => text.to_cow()?.into_owned(),
+        Err(_) => value.py().import("json")?.call_method1("dumps", (value,))?.extract()?,
+    };
+    serde_json::from_str(&json).map_err(value_error)
+}
+
+fn parse_encoding(encoding: &str) -> PyResult<CanonicalEncoding> {
+    encoding.parse().map_err(value_error)
+}
+
+fn decode_hash(value: &str) -> PyResult<[u8; 32]> {
+    hex::decode(value)
+        .ok()
+        .and_then(|bytes| bytes.try_into().ok())
+        .ok_or_else(|| PyValueError::new_err(format!("Expected a 32-byte hex hash, got '{}'", value)))
+}
+
+fn decode_key(value: &str) -> PyResult<VerifyingKey> {
+    VerifyingKey::from_bytes(&decode_hash(value)?).map_err(value_error)
+}
+
+fn decode_signature(value: &str) -> PyResult<Signature> {
+    hex::decode(value)
+        .ok()
+        .and_then(|bytes| Signature::from_slice(&bytes).ok())
+        .ok_or_else(|| PyValueError::new_err(format!("Invalid hex signature '{}'", value)))
+}
+
+fn value_error(e: impl std::fmt::Display) -> PyErr {
+    PyValueError::new_err(e.to_string())
+}
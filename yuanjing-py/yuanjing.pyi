@@ -0,0 +1,23 @@
+from typing import Any, List, Mapping, Tuple, Union
+
+# 证据 / 回执：`/prove` 返回的 JSON 对象或其字符串形式
+Json = Union[str, Mapping[str, Any]]
+
+def fingerprints(path: str) -> Tuple[str, str]:
+    """图片指纹 (SHA256, pHash)，与服务端算法相同"""
+
+# encoding: 回执中的 `encoding` ("bcs" / "protobuf")
+def canonical_bytes(evidence: Json, encoding: str = "bcs") -> bytes:
+    """证据的规范化字节"""
+
+def leaf_hash(evidence: Json, encoding: str = "bcs") -> str:
+    """叶子哈希 (Hex)：Blake3(规范字节)"""
+
+def verify_evidence_signature(public_key: str, evidence: Json, signature: str, encoding: str = "bcs") -> bool: ...
+def verify_receipt_signature(public_key: str, receipt: Json, signature: str) -> bool: ...
+
+def verify_inclusion(leaf_hash: str, pos: int, tree_size: int, root: str, proof: List[str]) -> bool:
+    """`proof` 为 `/audit/{pos}?tree_size=` 返回的 `proof_hex`"""
+
+def verify_receipt(evidence: Json, receipt: Json, receipt_signature: str, public_key: str, proof: List[str]) -> bool:
+    """离线完整校验：证据哈希 + 回执签名 + 回执所在树的包含性证明"""
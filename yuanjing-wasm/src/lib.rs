@@ -0,0 +1,168 @@
+use ed25519_dalek::{Signature, Verifier, VerifyingKey};
+use serde::Deserialize;
+use wasm_bindgen::prelude::*;
+
+use yuanjing_core::evidence::{CanonicalEncoding, Evidence};
+use yuanjing_core::proof::{self, CompactProof};
+use yuanjing_core::receipt::Receipt;
+use yuanjing_core::revocation::{RevocationList, SignedRevocationList};
+
+/// 模块：浏览器端校验包 (WASM)
+///
+/// **为什么需要**: 公开审计页若把回执提交给我们的 `/verify`，等于让被审计方自己给自己打分。
+/// 这里把校验核心 (规范化编码、Ed25519 验签、MMR 证明校验) 编译为 WebAssembly，
+/// 页面拿到回执与证明路径后完全在浏览器内完成校验。
+///
+/// 核心库以 `default-features = false` 引入，与服务端共用同一份 `Evidence` / `Receipt` 定义与合并规则。
+/// 所有 JSON 参数均为字符串 (`JSON.stringify(...)`)，公钥、签名、哈希均为 Hex。
+///
+/// 构建: `wasm-pack build yuanjing-wasm --target web --release`，产物 `pkg/` 可直接 `npm publish`。
+///
+/// `encoding` 为回执中的 `encoding` (`"bcs"` / `"protobuf"`)，省略时为 BCS。
+///
+/// `leafHash` 返回叶子哈希 (Hex)：Blake3(规范字节)，与回执中的 `evidence_hash` 对照。
+#[wasm_bindgen(js_name = leafHash)]
+pub fn leaf_hash(evidence_json: &str, encoding: Option<String>) -> Result<String, JsError> {
+    let encoding = parse_encoding(encoding)?;
+    Ok(hex::encode(parse::<Evidence>(evidence_json)?.leaf_hash_as(encoding).map_err(js_error)?))
+}
+
+/// 证据的规范化字节，即签名与叶子哈希的原文
+#[wasm_bindgen(js_name = canonicalBytes)]
+pub fn canonical_bytes(evidence_json: &str, encoding: Option<String>) -> Result<Vec<u8>, JsError> {
+    let encoding = parse_encoding(encoding)?;
+    parse::<Evidence>(evidence_json)?.canonical_bytes_as(encoding).map_err(js_error)
+}
+
+/// 校验证据签名 (`/prove` 返回的 `signature`)
+#[wasm_bindgen(js_name = verifyEvidenceSignature)]
+pub fn verify_evidence_signature(
+    public_key: &str,
+    evidence_json: &str,
+    signature: &str,
+    encoding: Option<String>,
+) -> Result<bool, JsError> {
+    let encoding = parse_encoding(encoding)?;
+    let payload = parse::<Evidence>(evidence_json)?.canonical_bytes_as(encoding).map_err(js_error)?;
+    Ok(decode_key(public_key)?.verify(&payload, &decode_signature(signature)?).is_ok())
+}
+
+/// 校验回执签名 (`/prove` 返回的 `receipt_signature`)
+#[wasm_bindgen(js_name = verifyReceiptSignature)]
+pub fn verify_receipt_signature(public_key: &str, receipt_json: &str, signature: &str) -> Result<bool, JsError> {
+    let payload = parse::<Receipt>(receipt_json)?.signing_bytes().map_err(js_error)?;
+    Ok(decode_key(public_key)?.verify(&payload, &decode_signature(signature)?).is_ok())
+}
+
+/// 校验包含性证明：`audit_json` 为 `/audit/{pos}` 的响应，`root` 取自回执或挑战应答中签名的根
+///
+/// 只使用响应中的位置、树大小与证明路径，不采信其中的 `root`。
+#[wasm_bindgen(js_name = verifyInclusion)]
+pub fn verify_inclusion(leaf_hash: &str, audit_json: &str, root: &str) -> Result<bool, JsError> {
+    let audit = parse::<AuditProof>(audit_json)?;
+    audit.verify(decode_hash(leaf_hash)?, decode_hash(root)?)
+}
+
+/// 校验紧凑二进制证明 (`Accept: application/vnd.yuanjing.proof` 时的 `/audit` 响应)
+///
+/// `leaf_hashes` 与证明中的位置一一对应 (按位置升序)，`root` 取自回执或挑战应答中签名的根。
+#[wasm_bindgen(js_name = verifyCompactProof)]
+pub fn verify_compact_proof(proof_bytes: &[u8], leaf_hashes: Vec<String>, root: &str) -> Result<bool, JsError> {
+    let proof = CompactProof::decode(proof_bytes).map_err(js_error)?;
+    let leaves = leaf_hashes.iter().map(|leaf| decode_hash(leaf)).collect::<Result<Vec<_>, _>>()?;
+    Ok(proof.verify(&leaves, decode_hash(root)?))
+}
+
+/// 离线完整校验一份回执：证据哈希一致、回执签名有效、叶子位于回执签名的那棵树中、签发时密钥未被吊销
+///
+/// `audit_json` 须为 `/audit/{leaf_pos}?tree_size={tree_size}` 的响应。
+/// `revocations_json` 为 `/revocations` 的响应，须与签发它的主身份公钥 `master_key` 一同给出；
+/// 列表签名不对时抛错，不会退化为不查吊销。两者都省略时不做吊销检查。
+#[wasm_bindgen(js_name = verifyReceipt)]
+pub fn verify_receipt(
+    evidence_json: &str,
+    receipt_json: &str,
+    receipt_signature: &str,
+    public_key: &str,
+    audit_json: &str,
+    revocations_json: Option<String>,
+    master_key: Option<String>,
+) -> Result<bool, JsError> {
+    let evidence = parse::<Evidence>(evidence_json)?;
+    let receipt = parse::<Receipt>(receipt_json)?;
+    let audit = parse::<AuditProof>(audit_json)?;
+    let revocations = revocation_list(revocations_json, master_key)?;
+
+    let leaf = evidence.leaf_hash_as(receipt.encoding).map_err(js_error)?;
+    if hex::encode(leaf) != receipt.evidence_hash {
+        return Ok(false);
+    }
+    let payload = receipt.signing_bytes().map_err(js_error)?;
+    let signing_key = decode_key(public_key)?;
+    if signing_key.verify(&payload, &decode_signature(receipt_signature)?).is_err() {
+        return Ok(false);
+    }
+    if revocations.is_some_and(|list| list.is_revoked(&signing_key, receipt.timestamp)) {
+        return Ok(false);
+    }
+    if audit.leaf_pos != receipt.leaf_pos || audit.tree_size != receipt.tree_size {
+        return Ok(false);
+    }
+    audit.verify(leaf, decode_hash(&receipt.root)?)
+}
+
+/// `/audit/{pos}` 响应中校验所需的字段
+#[derive(Deserialize)]
+struct AuditProof {
+    leaf_pos: u64,
+    tree_size: u64,
+    proof_hex: Vec<String>,
+}
+
+impl AuditProof {
+    fn verify(&self, leaf: [u8; 32], root: [u8; 32]) -> Result<bool, JsError> {
+        let items = self.proof_hex.iter().map(|item| decode_hash(item)).collect::<Result<Vec<_>, _>>()?;
+        Ok(proof::verify_proof(self.tree_size, items, self.leaf_pos, leaf, root))
+    }
+}
+
+/// 解析并核对吊销列表的主身份签名
+fn revocation_list(revocations_json: Option<String>, master_key: Option<String>) -> Result<Option<RevocationList>, JsError> {
+    match (revocations_json, master_key) {
+        (Some(json), Some(master_key)) => SignedRevocationList::from_json(json.as_bytes(), &decode_key(&master_key)?)
+            .map(|signed| Some(signed.list))
+            .map_err(js_error),
+        (None, None) => Ok(None),
+        _ => Err(JsError::new("revocationsJson and masterKey must be given together")),
+    }
+}
+
+fn parse<T: for<'de> Deserialize<'de>>(json: &str) -> Result<T, JsError> {
+    serde_json::from_str(json).map_err(js_error)
+}
+
+fn parse_encoding(encoding: Option<String>) -> Result<CanonicalEncoding, JsError> {
+    encoding.map_or(Ok(CanonicalEncoding::Bcs), |encoding| encoding.parse().map_err(js_error))
+}
+
+fn decode_hash(value: &str) -> Result<[u8; 32], JsError> {
+    hex::decode(value)
+        .ok()
+        .and_then(|bytes| bytes.try_into().ok())
+        .ok_or_else(|| JsError::new(&format!("Expected a 32-byte hex hash, got '{}'", value)))
+}
+
+fn decode_key(value: &str) -> Result<VerifyingKey, JsError> {
+    VerifyingKey::from_bytes(&decode_hash(value)?).map_err(js_error)
+}
+
+fn decode_signature(value: &str) -> Result<Signature, JsError> {
+    hex::decode(value)
+        .ok()
+        .and_then(|bytes| Signature::from_slice(&bytes).ok())
+        .ok_or_else(|| JsError::new(&format!("Invalid hex signature '{}'", value)))
+}
+
+fn js_error(e: impl std::fmt::Display) -> JsError {
+    JsError::new(&e.to_string())
+}